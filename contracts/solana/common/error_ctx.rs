@@ -0,0 +1,99 @@
+//! Structured error context shared by the Solana programs
+//!
+//! Anchor errors only carry a code and a static message. `require_ctx!` logs a
+//! single machine-parseable line with the values behind a failure before
+//! returning the error, e.g.
+//!
+//! `error_ctx: code=6005 error=InsufficientBalance requested=10 available=5`
+//!
+//! Keys are Rust identifiers and values never contain whitespace, so the line
+//! splits cleanly on spaces and `=`. Host builds get `ErrorContext` to parse it.
+//!
+//! Included into each program via `#[path]` so the format has one definition.
+
+/// Prefix of every structured error line
+pub const ERROR_CTX_PREFIX: &str = "error_ctx:";
+
+/// Like `require!`, but logs an `error_ctx` line with `key = value` context first
+#[macro_export]
+macro_rules! require_ctx {
+    ($cond:expr, $err:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        if !($cond) {
+            anchor_lang::prelude::msg!(
+                "{}",
+                $crate::error_ctx::format_error_ctx(
+                    u32::from($err),
+                    $crate::error_ctx::error_name(stringify!($err)),
+                    &[$((stringify!($key), ($value).to_string())),*],
+                )
+            );
+            return Err(anchor_lang::error!($err));
+        }
+    };
+}
+
+/// Build the `error_ctx` log line
+pub fn format_error_ctx(code: u32, error: &str, fields: &[(&str, String)]) -> String {
+    let mut line = format!("{} code={} error={}", ERROR_CTX_PREFIX, code, error);
+    for (key, value) in fields {
+        line.push(' ');
+        line.push_str(key);
+        line.push('=');
+        line.push_str(value);
+    }
+    line
+}
+
+/// `VestingError::StillLocked` -> `StillLocked`
+pub fn error_name(path: &'static str) -> &'static str {
+    path.rsplit("::").next().unwrap_or(path).trim()
+}
+
+/// Parsed `error_ctx` line, for SDKs and CLIs
+#[cfg(all(feature = "host", not(target_os = "solana")))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorContext {
+    pub code: u32,
+    pub error: String,
+    pub fields: Vec<(String, String)>,
+}
+
+#[cfg(all(feature = "host", not(target_os = "solana")))]
+impl ErrorContext {
+    /// Parse one log line; accepts the raw line or one prefixed with `Program log: `
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.strip_prefix("Program log: ").unwrap_or(line);
+        let mut parts = line.strip_prefix(ERROR_CTX_PREFIX)?.split_whitespace();
+
+        let code = parts.next()?.strip_prefix("code=")?.parse().ok()?;
+        let error = parts.next()?.strip_prefix("error=")?.to_string();
+        let fields = parts
+            .map(|part| {
+                let (key, value) = part.split_once('=')?;
+                Some((key.to_string(), value.to_string()))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(ErrorContext { code, error, fields })
+    }
+
+    /// Last `error_ctx` line in a transaction's logs (the one that failed it)
+    pub fn from_logs<S: AsRef<str>>(logs: &[S]) -> Option<Self> {
+        logs.iter().rev().find_map(|line| Self::parse(line.as_ref()))
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn get_u64(&self, key: &str) -> Option<u64> {
+        self.get(key)?.parse().ok()
+    }
+
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        self.get(key)?.parse().ok()
+    }
+}
@@ -0,0 +1,49 @@
+//! Keccak Merkle proofs shared by the Solana programs
+//!
+//! Sorted-pair proofs hash the running node with the next sibling, smaller one
+//! first, so they carry no left/right flags. Trinity folds consensus proofs
+//! with it; vesting checks allowlist membership against a committed root.
+//! Positional proofs carry one flag per level instead, for position-aware
+//! trees such as the Ethereum side's.
+//!
+//! Included into each program via `#[path]` so the tree layout has one definition.
+
+use anchor_lang::solana_program::keccak::hashv;
+
+/// Fold `proof` onto `leaf` and return the resulting root
+///
+/// Ties (a sibling equal to the running node) go to the running node first.
+/// Equal halves concatenate to the same bytes in either order, so the result
+/// is `keccak(node || node)` whichever way a verifier breaks the tie: it
+/// matches both TrinityConsensusVerifier's `<` and ProofValidation's `<=`.
+pub fn calculate_merkle_root(proof: &[[u8; 32]], leaf: &[u8; 32]) -> [u8; 32] {
+    let mut current_hash = *leaf;
+    
+    for proof_element in proof {
+        current_hash = if current_hash <= *proof_element {
+            hashv(&[&current_hash, proof_element]).0
+        } else {
+            hashv(&[proof_element, &current_hash]).0
+        };
+    }
+    
+    current_hash
+}
+
+/// Fold `proof` onto `leaf` in tree order: where `sibling_is_left[i]` is set
+/// the sibling is the left half, i.e. `keccak(sibling || node)`, otherwise
+/// `keccak(node || sibling)`, as `keccak256(abi.encodePacked(left, right))`
+/// builds the tree. Callers check that there is one flag per level.
+pub fn calculate_positional_merkle_root(proof: &[[u8; 32]], sibling_is_left: &[bool], leaf: &[u8; 32]) -> [u8; 32] {
+    let mut current_hash = *leaf;
+    
+    for (proof_element, &is_left) in proof.iter().zip(sibling_is_left) {
+        current_hash = if is_left {
+            hashv(&[proof_element, &current_hash]).0
+        } else {
+            hashv(&[&current_hash, proof_element]).0
+        };
+    }
+    
+    current_hash
+}
@@ -22,6 +22,7 @@ import {
   TOKEN_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID
 } from "@solana/spl-token";
+import { randomBytes } from "crypto";
 import * as anchor from "@coral-xyz/anchor";
 import fs from "fs";
 
@@ -111,6 +112,8 @@ async function deployRealVesting() {
   
   for (const schedule of CVT_CONFIG.vestingSchedules) {
     const scheduleId = new anchor.BN(schedule.id);
+    // Random nonce keeps the vesting PDA (and its ATA) unpredictable until creation
+    const nonce = new anchor.BN(randomBytes(8), "le");
     const unlockTimestamp = Math.floor(Date.now() / 1000) + 
       (schedule.years * 365 * 24 * 60 * 60);
     const amount = schedule.amount * Math.pow(10, CVT_CONFIG.decimals);
@@ -118,13 +121,14 @@ async function deployRealVesting() {
     console.log(`   ${schedule.period}: ${schedule.amount.toLocaleString()} CVT`);
     console.log(`   Unlock: ${new Date(unlockTimestamp * 1000).toLocaleDateString()}`);
     
-    // Derive vesting PDA (with schedule ID for uniqueness)
+    // Derive vesting PDA (schedule ID for uniqueness, nonce against squatting)
     const [vestingPDA] = PublicKey.findProgramAddressSync(
       [
         Buffer.from("vesting"),
         payer.publicKey.toBuffer(),
         mint.toBuffer(),
-        scheduleId.toArrayLike(Buffer, "le", 8)
+        scheduleId.toArrayLike(Buffer, "le", 8),
+        nonce.toArrayLike(Buffer, "le", 8)
       ],
       VESTING_PROGRAM_ID
     );
@@ -183,6 +187,7 @@ async function deployRealVesting() {
     
     vestingRecords.push({
       scheduleId: schedule.id,
+      nonce: nonce.toString(),
       period: schedule.period,
       amount: schedule.amount,
       unlockDate: new Date(unlockTimestamp * 1000).toISOString(),
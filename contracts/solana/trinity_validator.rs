@@ -16,16 +16,170 @@
 ///! ============================================================================
 
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
 use anchor_lang::solana_program::keccak::hashv;
+use anchor_lang::solana_program::sysvar::{instructions as sysvar_instructions, slot_hashes};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 
+/// Structured `error_ctx` log lines emitted before each failure (shared with vesting)
+#[macro_use]
+#[path = "common/error_ctx.rs"]
+pub mod error_ctx;
+
+/// Keccak Merkle roots, sorted-pair or positional (shared with vesting allowlists)
+#[path = "common/merkle.rs"]
+pub mod merkle;
+use merkle::{calculate_merkle_root, calculate_positional_merkle_root};
+use migrations::{AccountKind, Versioned};
+
+#[cfg(not(feature = "devtools"))]
 declare_id!("TrNtyV4L1D4T0RSoLAN4C0nsENSuS1111111111111");
 
+// `devtools` builds always deploy under a separate localnet program id, so a
+// bootstrap-capable binary can never be the mainnet program
+#[cfg(feature = "devtools")]
+declare_id!("TrNtyDevToo1sLoca1netBootstrap1111111111111");
+
+#[cfg(all(feature = "devtools", feature = "mainnet"))]
+compile_error!("the `devtools` feature must never be enabled in a mainnet build");
+
 /// High-frequency monitoring configuration constants
 pub const MIN_MONITORING_INTERVAL_MS: u64 = 400;       // Solana block time (~400ms)
 pub const DEFAULT_MONITORING_INTERVAL_MS: u64 = 1000;  // 1 second default
 pub const MAX_MONITORING_INTERVAL_MS: u64 = 60000;     // Max 1 minute
 pub const TARGET_PROOF_LATENCY_MS: u64 = 5000;         // Target <5 seconds
 
+/// Matches TrinityValidator.arbitrum_rpc_url max_len
+pub const MAX_RPC_URL_LEN: usize = 200;
+
+/// Daily observability shards
+pub const SECONDS_PER_DAY: u64 = 86_400;
+pub const STATS_RETENTION_DAYS: u64 = 90;              // DailyStats closable after 90 days
+
+/// End-to-end latency (Ethereum event to Solana proof), from the Ethereum
+/// timestamp the operator reports. Advisory: nothing on Solana can verify it.
+pub const MAX_PLAUSIBLE_LATENCY_SECS: u64 = 3_600;     // Longer reports are capped and flagged
+pub const LATENCY_BUCKETS: usize = 8;
+pub const LATENCY_BUCKET_BOUNDS_SECS: [u64; LATENCY_BUCKETS - 1] = [1, 2, 5, 10, 30, 60, 300]; // Inclusive upper bounds; the last bucket is open
+pub const LATENCY_REPORT_TOLERANCE_SECS: i64 = 15;     // Operator vs relayer timestamp drift before reconcile flags it
+
+/// Proof lifecycle constants (expiry of unconfirmed proofs and rent reclamation)
+pub const DEFAULT_PROOF_EXPIRY_SECS: u64 = 86_400;     // Unconfirmed proofs expire after 24h
+pub const DEFAULT_PROOF_RETENTION_SECS: u64 = 604_800; // Expired proofs kept 7 days before close
+pub const DEFAULT_SWEEP_BOUNTY_BPS: u16 = 500;         // Cranker earns 5% of reclaimed rent
+pub const MAX_SWEEP_BOUNTY_BPS: u16 = 2_000;           // Bounty can never exceed 20% of rent
+pub const MAX_SWEEP_BOUNTY_LAMPORTS: u64 = 1_000_000;  // Hard per-account bounty cap (0.001 SOL)
+
+/// Batch limits, checked before any work is done. Each proof level costs a
+/// keccak syscall and each swept account a deserialize + PDA derivation, so
+/// these stay well inside the default 200k CU budget. Oversized inputs are
+/// rejected up front instead of exhausting compute halfway through.
+pub const MAX_MERKLE_PROOF_LEN: usize = 10;            // Matches ProofRecord.merkle_proof max_len
+pub const MAX_DESTINATION_CONFIRMATIONS: usize = 4;    // Matches ProofRecord.confirmations max_len
+pub const MAX_SWEEP_BATCH: u8 = 20;                    // Max proof accounts per sweep
+pub const MAX_ROLLUP_BATCH: usize = 20;                // Max operator metrics accounts per rollup
+
+/// Proof budget: the estimated cost of a transaction's proof submissions, in
+/// units of one Merkle level. Each submit instruction in the transaction is
+/// charged a base (account creation, sysvar reads, argument hashing) plus its
+/// levels, and the total must stay within the validator's max_proof_budget.
+pub const PROOF_BUDGET_BASE_UNITS: u64 = 8;            // Per submit instruction
+pub const PROOF_BUDGET_LEVEL_UNITS: u64 = 1;           // Per Merkle proof level
+pub const DEFAULT_MAX_PROOF_BUDGET: u64 = 4 * (PROOF_BUDGET_BASE_UNITS + MAX_MERKLE_PROOF_LEN as u64); // Four full-length proofs
+
+/// Trinity chain IDs (match TrinityConsensusVerifier) and the 2-of-3 quorum
+pub const ARBITRUM_CHAIN_ID: u8 = 1;
+pub const SOLANA_CHAIN_ID: u8 = 2;
+pub const TON_CHAIN_ID: u8 = 3;
+pub const CONSENSUS_THRESHOLD: usize = 2;
+
+/// Shadow-mode consensus rules (CheckId): evaluated on every submission,
+/// only rejecting once flipped to Enforce through the timelocked queue
+pub const SHADOW_CHECK_COUNT: usize = 3;
+pub const MAX_PROOF_SLOT_AGE: u64 = 32;                // BlockFreshness: ~13s at 400ms slots
+#[cfg(not(feature = "devtools"))]
+pub const ENFORCEMENT_TIMELOCK_SECS: i64 = 2 * 86_400; // Delay before a rule or amount limit change applies
+#[cfg(feature = "devtools")]
+pub const ENFORCEMENT_TIMELOCK_SECS: i64 = 2;          // Localnet: short enough to test Enforce mode
+
+/// Public consensus mirrors: a stable ABI for third-party programs (see ConsensusMirror)
+pub const CONSENSUS_MIRROR_SCHEMA_VERSION: u8 = 1;
+#[cfg(not(feature = "devtools"))]
+pub const MIRROR_RETENTION_SECS: i64 = 365 * 86_400;   // Mirrors stay readable for a year
+#[cfg(feature = "devtools")]
+pub const MIRROR_RETENTION_SECS: i64 = 2;              // Localnet: short enough to test closing
+
+/// Stake-weighted consensus: operators attest to an operation with their
+/// bonded stake, which stays bonded for a while after each attestation
+pub const MAX_CONSENSUS_CONTRIBUTORS: usize = 10;      // Attestations recorded per operation; more stake displaces the least
+#[cfg(not(feature = "devtools"))]
+pub const STAKE_UNBONDING_SECS: i64 = 7 * 86_400;      // Outlasts disputes over the attested operation
+#[cfg(feature = "devtools")]
+pub const STAKE_UNBONDING_SECS: i64 = 2;               // Localnet: short enough to test unbonding
+
+/// OperationType codes accepted by CheckConfig; the four original types hold
+/// slots 0-3 from initialization and further codes are enabled one at a time
+/// through the timelocked queue
+pub const MAX_OPERATION_TYPES: usize = 8;
+pub const INITIAL_OPERATION_CODES: [u16; MAX_OPERATION_TYPES] = [0, 1, 2, 3, 0, 0, 0, 0];
+pub const INITIAL_OPERATION_TYPES: u8 = 4;
+
+/// Cheap replay filter: fingerprints of the most recent submissions
+pub const SEEN_OPERATIONS_WINDOW: usize = 256;         // Ring size (false-negative window)
+
+/// Reconciliation against Ethereum checkpoints: ProofSequence keeps the
+/// rolling hash after each of the most recent proofs
+pub const RECONCILE_WINDOW: usize = 64;                // Oldest checkpoint count still comparable
+
+/// Manual consensus override (catastrophic fallback, deliberately slow)
+pub const MAX_COUNCIL_MEMBERS: usize = 9;              // Dispute council size cap
+#[cfg(not(feature = "devtools"))]
+pub const OVERRIDE_DELAY_SECS: i64 = 7 * 86_400;       // 7-day delay before an override executes
+#[cfg(feature = "devtools")]
+pub const OVERRIDE_DELAY_SECS: i64 = 2;                // Localnet: short enough to test execution
+
+/// Operator scoring (see period_score): inputs accumulate on OperatorMetrics
+/// and the update_scores crank folds them into a decaying score
+pub const SCORE_BPS: u64 = 10_000;                     // Scores are basis points of a perfect period
+pub const SCORE_WEIGHT_TIMELINESS_BPS: u64 = 3_000;
+pub const SCORE_WEIGHT_ACCURACY_BPS: u64 = 5_000;
+pub const SCORE_WEIGHT_LIVENESS_BPS: u64 = 2_000;
+pub const DISPUTE_LOSS_FAULTS: u64 = 2;                // A lost dispute weighs as two divergences
+pub const HEARTBEAT_INTERVAL_SECS: i64 = 300;          // Each 5-minute window without a heartbeat is missed
+pub const SCORE_EPOCH_SECS: i64 = 86_400;              // An operator is scored at most once a day
+pub const SCORE_DECAY_BPS: u64 = 5_000;                // Weight the previous score keeps per epoch
+pub const MAX_DECAY_EPOCHS: u64 = 16;                  // Older history is negligible past this
+pub const MAX_SCORE_BATCH: usize = 16;                 // Max operator metrics accounts per scoring call
+
+/// Build attestation
+pub const DEFAULT_ATTESTATION_WINDOW_SLOTS: u64 = 9_000; // ~1 hour at 400ms slots to attest an upgrade
+pub const SOURCE_COMMIT_LEN: usize = 40;               // Hex SHA-1 git commit
+/// Commit this build was made from, embedded by trinity_validator_build.rs
+pub const GIT_COMMIT: &str = match option_env!("GIT_COMMIT") {
+    Some(commit) => commit,
+    None => "unknown",
+};
+
+/// Risk flags (see crossed_risk_flags): monitoring breadcrumbs, latched on
+/// RiskMetrics until the authority acknowledges them
+pub const RISK_FLAG_COUNT: usize = 3;
+pub const RISK_SHARE_WINDOW: usize = 16;               // Recent proofs an operator's share is measured over
+pub const RISK_SHARE_MIN_PROOFS: u64 = 4;              // Fewer recent proofs say nothing about concentration
+
+/// Chain-halt detection (see HaltMonitor)
+pub const ETHEREUM_BLOCK_SECS: u64 = 12;               // Post-merge slot time: one Ethereum confirmation
+pub const MAX_PROOF_REISSUES: u8 = 3;                 // Reorg rewrites per operation before it can only expire
+
+/// Disaster export/import (see state_export)
+pub const EXPORT_FORMAT_VERSION: u8 = 1;              // StateExported payload layout
+pub const EXPORT_KIND_COUNT: usize = 8;               // ExportKind variants
+pub const MAX_EXPORT_PAGE: usize = 6;                 // Accounts per export_state: a page of proofs stays under the 10 KB log limit
+
+const _: () = assert!(
+    SCORE_WEIGHT_TIMELINESS_BPS + SCORE_WEIGHT_ACCURACY_BPS + SCORE_WEIGHT_LIVENESS_BPS == SCORE_BPS
+);
+
 #[program]
 pub mod trinity_validator {
     use super::*;
@@ -38,6 +192,8 @@ pub mod trinity_validator {
         validator_ethereum_address: [u8; 20],   // Validator's Ethereum address
         arbitrum_rpc_url: String,               // Arbitrum Sepolia/Mainnet RPC
     ) -> Result<()> {
+        check_rpc_url(&arbitrum_rpc_url)?;
+        
         let validator = &mut ctx.accounts.validator;
         validator.authority = ctx.accounts.authority.key();
         validator.ethereum_bridge_address = ethereum_bridge_address;
@@ -46,7 +202,14 @@ pub mod trinity_validator {
         validator.total_proofs_submitted = 0;
         validator.last_processed_operation = 0;
         validator.is_active = true;
+        validator.proof_expiry_secs = DEFAULT_PROOF_EXPIRY_SECS;
+        validator.proof_retention_secs = DEFAULT_PROOF_RETENTION_SECS;
+        validator.sweep_bounty_bps = DEFAULT_SWEEP_BOUNTY_BPS;
+        validator.store_tx_signatures = true;
+        validator.operation_committer = ctx.accounts.authority.key();
+        validator.max_proof_budget = DEFAULT_MAX_PROOF_BUDGET;
         validator.bump = *ctx.bumps.get("validator").unwrap();
+        validator.layout_version = TrinityValidator::LAYOUT_VERSION;
         
         msg!("Trinity Validator initialized for Ethereum bridge: {:?}", ethereum_bridge_address);
         Ok(())
@@ -54,6 +217,9 @@ pub mod trinity_validator {
 
     /// Submit Trinity consensus proof to Ethereum
     /// Called by off-chain validator service after monitoring Ethereum events
+    /// HTLC swaps may attach the revealed preimage so the relayer can claim
+    /// the Ethereum side; other operation types must not
+    #[allow(clippy::too_many_arguments)]
     pub fn submit_consensus_proof(
         ctx: Context<SubmitProof>,
         operation_id: [u8; 32],                 // Ethereum operation ID
@@ -61,63 +227,334 @@ pub mod trinity_validator {
         solana_block_hash: [u8; 32],            // Solana block hash
         solana_tx_signature: [u8; 64],          // Solana transaction signature
         solana_block_number: u64,               // Solana slot number
+        operation_type: OperationType,          // Operation being proven
+        amount: u64,                            // Operation amount (checked against the type's limit)
+        htlc: Option<HtlcPreimage>,             // Revealed preimage (HTLC swaps only)
+        eth_event_timestamp: i64,               // Ethereum event timestamp, as observed by the operator
+        merkle_mode: MerkleMode,                // How merkle_proof folds onto the operation ID
+        sibling_is_left: Vec<bool>,             // One flag per level (Positional only)
     ) -> Result<()> {
-        let validator = &mut ctx.accounts.validator;
-        let proof_record = &mut ctx.accounts.proof_record;
-        
-        require!(validator.is_active, TrinityError::ValidatorNotActive);
+        require_operation_unpaused(&ctx.accounts.consensus_state)?;
+        observe_halt(&ctx.accounts.halt_monitor, true)?;
+        let record = build_proof_record(
+            &ctx.accounts.validator,
+            &load_check_config(&ctx.accounts.check_config)?,
+            &ctx.accounts.slot_hashes,
+            &ctx.accounts.instructions,
+            ctx.accounts.proof_record.key(),
+            ctx.accounts.authority.key(),
+            ctx.accounts.authority.key() == ctx.accounts.validator.authority,
+            ProofInputs {
+                operation_id,
+                merkle_proof,
+                solana_block_hash,
+                solana_tx_signature,
+                solana_block_number,
+                operation_type,
+                amount,
+                htlc,
+                eth_event_timestamp,
+                merkle_mode,
+                sibling_is_left,
+            },
+        )?;
         
-        // Generate Merkle root from proof
-        let merkle_root = calculate_merkle_root(&merkle_proof, &operation_id);
-        
-        // Store proof record on Solana
-        proof_record.operation_id = operation_id;
-        proof_record.merkle_root = merkle_root;
-        proof_record.merkle_proof = merkle_proof;
-        proof_record.solana_block_hash = solana_block_hash;
-        proof_record.solana_tx_signature = solana_tx_signature;
-        proof_record.solana_block_number = solana_block_number;
-        proof_record.timestamp = Clock::get()?.unix_timestamp as u64;
-        proof_record.submitted_to_ethereum = false;
-        proof_record.validator = validator.key();
+        let authority = ctx.accounts.authority.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let target = ctx.accounts.proof_record.to_account_info();
+        create_proof_account(
+            &target,
+            &system_program,
+            &record,
+            *ctx.bumps.get("proof_record").unwrap(),
+            |rent| {
+                anchor_lang::system_program::transfer(
+                    CpiContext::new(
+                        system_program.clone(),
+                        anchor_lang::system_program::Transfer {
+                            from: authority.clone(),
+                            to: target.clone(),
+                        },
+                    ),
+                    rent,
+                )
+            },
+        )?;
         
-        validator.total_proofs_submitted += 1;
+        // After the proof account, so a resubmission still fails with ProofAlreadyExists
+        consume_expected_operation(&ctx.accounts.expected_operation, &operation_id)?;
         
-        msg!("Solana proof generated for operation: {:?}", operation_id);
-        msg!("Merkle root: {:?}", merkle_root);
-        msg!("Block number: {}", solana_block_number);
+        finish_proof_submission(
+            &record,
+            ctx.accounts.seen_operations.as_deref_mut(),
+            &mut ctx.accounts.operator_metrics,
+            ctx.accounts.authority.key(),
+            *ctx.bumps.get("operator_metrics").unwrap(),
+            &mut ctx.accounts.proof_sequence,
+            *ctx.bumps.get("proof_sequence").unwrap(),
+            &mut ctx.accounts.daily_stats,
+            *ctx.bumps.get("daily_stats").unwrap(),
+        )?;
         
-        // Emit event for off-chain relayer to submit to Ethereum
-        emit!(ProofGenerated {
-            operation_id,
-            merkle_root,
-            solana_block_hash,
-            solana_block_number,
-            timestamp: proof_record.timestamp,
-        });
+        let operator = ctx.accounts.authority.key();
+        observe_risk(&ctx.accounts.risk_metrics, operation_id, operator, |metrics| RiskObservation {
+            amount: Some(record.amount),
+            operator_share_bps: metrics.record_submission(operator),
+            consensus_secs: None,
+        })?;
         
         Ok(())
     }
 
     /// Mark proof as submitted to Ethereum
-    /// Called after off-chain relayer confirms Ethereum transaction
+    /// Called after off-chain relayer confirms Ethereum transaction. Names
+    /// no destination chain; relayers serving several use confirm_submission.
     pub fn confirm_ethereum_submission(
         ctx: Context<ConfirmSubmission>,
         operation_id: [u8; 32],
         ethereum_tx_hash: [u8; 32],
     ) -> Result<()> {
+        require_ctx!(
+            !ctx.accounts.proof_record.submitted_to_ethereum,
+            TrinityError::AlreadySubmitted,
+            proof = ctx.accounts.proof_record.key(),
+        );
+        let stats_bump = *ctx.bumps.get("daily_stats").unwrap();
+        record_confirmation(ctx.accounts, stats_bump, operation_id, ethereum_tx_hash)?;
+        
+        msg!("Ethereum submission confirmed for operation: {:?}", operation_id);
+        msg!("Ethereum TX: {:?}", ethereum_tx_hash);
+        
+        Ok(())
+    }
+    
+    /// Record the proof's confirmation on one destination chain (EVM chain
+    /// ID), once per chain and up to MAX_DESTINATION_CONFIRMATIONS chains.
+    /// The first confirmation on any chain also confirms the proof itself, as
+    /// confirm_ethereum_submission does; later ones only add their record.
+    /// Either confirmation takes the validator authority or the operation
+    /// committer, and each record grows the proof account at its expense.
+    pub fn confirm_submission(
+        ctx: Context<ConfirmSubmission>,
+        operation_id: [u8; 32],
+        chain_id: u64,
+        tx_hash: [u8; 32],
+        block_number: u64,
+    ) -> Result<()> {
+        let proof_record = &ctx.accounts.proof_record;
+        require_ctx!(chain_id != 0, TrinityError::InvalidDestinationChain, chain_id = chain_id);
+        require_ctx!(
+            !proof_record.confirmations.iter().any(|confirmation| confirmation.chain_id == chain_id),
+            TrinityError::AlreadySubmitted,
+            proof = proof_record.key(),
+            chain_id = chain_id,
+        );
+        require_ctx!(
+            proof_record.confirmations.len() < MAX_DESTINATION_CONFIRMATIONS,
+            TrinityError::ConfirmationLimitReached,
+            proof = proof_record.key(),
+            max = MAX_DESTINATION_CONFIRMATIONS,
+        );
+        let stats_bump = *ctx.bumps.get("daily_stats").unwrap();
+        record_confirmation(ctx.accounts, stats_bump, operation_id, tx_hash)?;
+        
+        // Sized at its serialized length (see create_proof_account): make room
+        let account = ctx.accounts.proof_record.to_account_info();
+        let space = account.data_len() + ChainConfirmation::INIT_SPACE;
+        let rent = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+        if rent > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: account.clone(),
+                    },
+                ),
+                rent,
+            )?;
+        }
+        account.realloc(space, false)?;
+        
+        let confirmation = ChainConfirmation {
+            chain_id,
+            tx_hash,
+            block_number,
+            confirmed_at: Clock::get()?.unix_timestamp as u64,
+        };
         let proof_record = &mut ctx.accounts.proof_record;
+        proof_record.confirmations.push(confirmation);
+        
+        emit!(SubmissionConfirmed {
+            operation_id,
+            chain_id,
+            tx_hash,
+            block_number,
+            confirmations: proof_record.confirmations.len() as u8,
+        });
         
-        require!(!proof_record.submitted_to_ethereum, TrinityError::AlreadySubmitted);
+        msg!("Submission confirmed on chain {} for operation: {:?}", chain_id, operation_id);
+        msg!("TX: {:?} (block {})", tx_hash, block_number);
         
-        proof_record.submitted_to_ethereum = true;
-        proof_record.ethereum_tx_hash = ethereum_tx_hash;
+        Ok(())
+    }
+
+    /// Finalize organic 2-of-3 consensus for an operation whose Solana proof
+    /// has been confirmed on Ethereum. `chains` are the agreeing chain IDs and
+    /// must include Solana. Once a consensus weight threshold is configured,
+    /// the stake attested to the operation must also exceed it (see
+    /// attest_operation). Emits ConsensusFinalized, the relayer's single
+    /// trigger, exactly once per operation.
+    ///
+    /// With the reward pool passed, reward_per_operation is split among the
+    /// operation's contributors by attested stake, paid to one token account
+    /// of each passed in remaining_accounts; see distribute_rewards.
+    pub fn finalize_consensus<'info>(
+        ctx: Context<'_, '_, '_, 'info, FinalizeConsensus<'info>>,
+        operation_id: [u8; 32],
+        chains: Vec<u8>,
+    ) -> Result<()> {
+        let proof_record = &ctx.accounts.proof_record;
+        let consensus = &mut ctx.accounts.consensus_state;
         
-        msg!("Ethereum submission confirmed for operation: {:?}", operation_id);
-        msg!("Ethereum TX: {:?}", ethereum_tx_hash);
+        require_ctx!(
+            !consensus.finalized,
+            TrinityError::ConsensusAlreadyFinalized,
+            consensus = consensus.key(),
+            finalized_at = consensus.finalized_at,
+        );
+        require_ctx!(
+            consensus.failed_at == 0,
+            TrinityError::OperationFailed,
+            consensus = consensus.key(),
+            failed_at = consensus.failed_at,
+        );
+        require_ctx!(!consensus.operation_paused, TrinityError::OperationPaused, consensus = consensus.key());
+        require_ctx!(
+            proof_record.submitted_to_ethereum && !proof_record.expired,
+            TrinityError::ProofNotConfirmed,
+            proof = proof_record.key(),
+            expired = proof_record.expired,
+        );
+        // Against the limit snapshotted at the first attestation, so a later
+        // limit change neither strands nor legitimizes a pending operation
+        reject(check_amount_limit(&proof_record.operation_type, proof_record.amount, proof_record.amount_limit))?;
+        validate_consensus_chains(&chains)?;
+        let threshold = load_initialized::<StakeConfig>(&ctx.accounts.stake_config)?
+            .map_or(0, |config| config.consensus_weight_threshold);
+        require_stake_weight(consensus, threshold)?;
+        
+        let now = Clock::get()?.unix_timestamp;
+        consensus.operation_id = operation_id;
+        consensus.finalized_at = now;
+        consensus.overridden = false;
+        consensus.justification_hash = [0u8; 32];
+        consensus.merkle_root = proof_record.merkle_root;
+        consensus.chains = chains.clone();
+        consensus.failed_at = 0;
+        consensus.finalized = true;
+        consensus.bump = *ctx.bumps.get("consensus_state").unwrap();
+        consensus.layout_version = ConsensusState::LAYOUT_VERSION;
+        
+        let stats = &mut ctx.accounts.daily_stats;
+        touch_daily_stats(stats, *ctx.bumps.get("daily_stats").unwrap());
+        stats.consensus_reached = stats.consensus_reached.saturating_add(1);
+        
+        emit!(ConsensusFinalized {
+            operation_id,
+            merkle_root: proof_record.merkle_root,
+            chains,
+            finalized_at: now,
+        });
+        
+        let consensus_secs = (now as u64).saturating_sub(proof_record.timestamp);
+        observe_risk(&ctx.accounts.risk_metrics, operation_id, ctx.accounts.authority.key(), |_| RiskObservation {
+            consensus_secs: Some(consensus_secs),
+            ..RiskObservation::default()
+        })?;
+        
+        if let Some(pool) = &ctx.accounts.reward_pool {
+            let vault = ctx.accounts.reward_vault.as_ref();
+            require_ctx!(
+                vault.is_some_and(|vault| vault.key() == pool.vault) && ctx.accounts.token_program.is_some(),
+                TrinityError::InvalidRewardVault,
+                vault = vault.map_or(Pubkey::default(), |vault| vault.key()),
+                expected = pool.vault,
+            );
+            distribute_rewards(
+                pool,
+                vault.unwrap(),
+                ctx.accounts.token_program.as_ref().unwrap(),
+                consensus,
+                ctx.remaining_accounts,
+                operation_id,
+            )?;
+        }
+        
+        msg!("🔺 Consensus finalized for operation: {:?}", operation_id);
+        Ok(())
+    }
+    
+    /// Freeze (or release) a single disputed operation without pausing the
+    /// validator: while paused, proofs for it can't be submitted and it can't
+    /// be finalized. Creates the operation's ConsensusState if it has none yet.
+    pub fn set_operation_pause(ctx: Context<SetOperationPause>, operation_id: [u8; 32], paused: bool) -> Result<()> {
+        let consensus = &mut ctx.accounts.consensus_state;
+        consensus.operation_id = operation_id;
+        consensus.operation_paused = paused;
+        consensus.bump = *ctx.bumps.get("consensus_state").unwrap();
+        consensus.layout_version = ConsensusState::LAYOUT_VERSION;
+        
+        emit!(OperationPauseSet {
+            operation_id,
+            paused,
+            authority: ctx.accounts.authority.key(),
+        });
         
+        msg!("Operation {:?} paused: {}", operation_id, paused);
         Ok(())
     }
+    
+    /// Consensus status of an operation (return data), so relayers can poll
+    /// without scraping logs. Pending counts the confirmations seen on Solana
+    /// (its proof, then that proof's Ethereum confirmation); an open or
+    /// executed manual override is reported as a conflict.
+    pub fn consensus_status(ctx: Context<GetConsensusStatus>, _operation_id: [u8; 32]) -> Result<ConsensusOutcome> {
+        let consensus = load_initialized::<ConsensusState>(&ctx.accounts.consensus_state)?;
+        if let Some(failed_at) = consensus.as_ref().map(|consensus| consensus.failed_at).filter(|at| *at != 0) {
+            return Ok(ConsensusOutcome::Failed { failed_at });
+        }
+        let consensus = consensus.filter(|consensus| consensus.finalized);
+        let proposal = load_initialized::<OverrideProposal>(&ctx.accounts.override_proposal)?;
+        
+        if let Some(consensus) = &consensus {
+            if !consensus.overridden {
+                return Ok(ConsensusOutcome::Reached {
+                    chains: consensus.chains.len() as u8,
+                    finalized_at: consensus.finalized_at,
+                });
+            }
+        }
+        if consensus.is_some() || proposal.is_some() {
+            return Ok(ConsensusOutcome::Conflict {
+                override_approvals: proposal.map_or(0, |proposal| proposal.approvals),
+                overridden: consensus.is_some(),
+            });
+        }
+        
+        let confirmations = match load_initialized::<ProofRecord>(&ctx.accounts.proof_record)? {
+            Some(record) if !record.expired => 1 + record.submitted_to_ethereum as u8,
+            _ => 0,
+        };
+        Ok(ConsensusOutcome::Pending { confirmations, required: CONSENSUS_THRESHOLD as u8 })
+    }
+
+    /// Forensic snapshot of a live proof record (return data): what was proven
+    /// and the cluster environment it was proven in. Closed records leave the
+    /// same payload in their ProofArchived event.
+    pub fn get_proof_evidence(ctx: Context<GetProofEvidence>, _operation_id: [u8; 32]) -> Result<ProofEvidence> {
+        Ok(proof_evidence(&ctx.accounts.proof_record))
+    }
 
     /// Verify vault operation for Trinity consensus
     /// Checks vault state on Solana and generates Merkle proof for Ethereum
@@ -134,14 +571,54 @@ pub mod trinity_validator {
         let vault = &ctx.accounts.vault;
         
         // SECURITY: Verify vault exists and is owned by correct user
-        require!(*vault.owner != System::id(), TrinityError::VaultNotInitialized);
-        require!(vault.key() == vault_owner, TrinityError::VaultMismatch);
+        require_ctx!(*vault.owner != System::id(), TrinityError::VaultNotInitialized, vault = vault.key());
+        require_ctx!(
+            vault.key() == vault_owner,
+            TrinityError::VaultMismatch,
+            vault = vault.key(),
+            expected = vault_owner,
+        );
+        let rules = load_check_config(&ctx.accounts.check_config)?;
+        let slot = rules.type_slot(&operation_type)?;
+        reject(check_amount_limit(&operation_type, amount, rules.max_amount[slot]))?;
+        if operation_type == OperationType::VaultWithdrawal {
+            require_no_pending_emergency(
+                &ctx.accounts.vesting_link,
+                vault,
+                ctx.accounts.emergency_proposal.as_ref().map(|proposal| proposal.as_ref()),
+            )?;
+        }
         
-        // Generate verification proof that will be submitted to Ethereum
+        // Cross-chain transfers also count against the user's cumulative bridge cap
+        if operation_type == OperationType::CrossChainTransfer {
+            require_ctx!(ctx.accounts.bridged_total.is_some(), TrinityError::BridgedTotalRequired, user = user);
+            let ledger = ctx.accounts.bridged_total.as_mut().unwrap();
+            let bridged_total = ledger.bridged_total.saturating_add(amount);
+            require_ctx!(
+                bridged_total <= rules.max_bridged_per_user,
+                TrinityError::BridgeCapExceeded,
+                user = user,
+                bridged_total = ledger.bridged_total,
+                amount = amount,
+                max = rules.max_bridged_per_user,
+            );
+            ledger.user = user;
+            ledger.bridged_total = bridged_total;
+            ledger.bump = *ctx.bumps.get("bridged_total").unwrap();
+        }
+        
+        let stats = &mut ctx.accounts.daily_stats;
+        touch_daily_stats(stats, *ctx.bumps.get("daily_stats").unwrap());
+        let by_type = &mut stats.verifications_by_type[slot];
+        *by_type = by_type.saturating_add(1);
+        
+        // Generate verification proof that will be submitted to Ethereum.
+        // The original types encode as their one-byte tag, as before codes.
+        let type_bytes = operation_type.try_to_vec()?;
         let verification_hash = hashv(&[
             &vault_id.to_le_bytes(),
             vault_owner.as_ref(),
-            &[operation_type as u8],
+            &type_bytes,
             &amount.to_le_bytes(),
             user.as_ref(),
             &Clock::get()?.unix_timestamp.to_le_bytes(),
@@ -149,7 +626,7 @@ pub mod trinity_validator {
         
         verification.vault_id = vault_id;
         verification.vault_owner = vault_owner;
-        verification.operation_type = operation_type;
+        verification.operation_type = operation_type.clone();
         verification.amount = amount;
         verification.user = user;
         verification.verification_hash = verification_hash.0;
@@ -186,6 +663,7 @@ pub mod trinity_validator {
         let validator = &mut ctx.accounts.validator;
         
         if let Some(rpc) = new_arbitrum_rpc {
+            check_rpc_url(&rpc)?;
             validator.arbitrum_rpc_url = rpc;
         }
         
@@ -200,129 +678,706 @@ pub mod trinity_validator {
         msg!("Validator configuration updated");
         Ok(())
     }
-    
-    // ========================================================================
-    // HIGH-FREQUENCY MONITORING SYSTEM (Solana's Role in Trinity Protocol)
-    // ========================================================================
-    
-    /// Initialize high-frequency monitoring configuration
-    /// Configures the monitoring interval and thresholds
-    pub fn initialize_monitoring(
-        ctx: Context<InitializeMonitoring>,
-        monitoring_interval_ms: u64,
-        max_latency_ms: u64,
-    ) -> Result<()> {
-        let monitor_config = &mut ctx.accounts.monitor_config;
-        let validator = &ctx.accounts.validator;
+
+    /// Rotate the Arbitrum RPC endpoint only (for relayer tooling)
+    pub fn set_rpc_url(ctx: Context<UpdateValidator>, rpc_url: String) -> Result<()> {
+        check_rpc_url(&rpc_url)?;
         
-        require!(validator.is_active, TrinityError::ValidatorNotActive);
-        require!(
-            monitoring_interval_ms >= MIN_MONITORING_INTERVAL_MS,
-            TrinityError::MonitoringIntervalTooLow
+        let validator = &mut ctx.accounts.validator;
+        let previous_url = std::mem::replace(&mut validator.arbitrum_rpc_url, rpc_url);
+        
+        emit!(RpcUrlUpdated {
+            validator: validator.key(),
+            previous_url,
+            new_url: validator.arbitrum_rpc_url.clone(),
+            timestamp: Clock::get()?.unix_timestamp as u64,
+        });
+        
+        msg!("Arbitrum RPC updated: {}", validator.arbitrum_rpc_url);
+        Ok(())
+    }
+
+    /// Configure proof expiry, retention and the sweep bounty
+    pub fn configure_proof_lifecycle(
+        ctx: Context<UpdateValidator>,
+        proof_expiry_secs: u64,
+        proof_retention_secs: u64,
+        sweep_bounty_bps: u16,
+    ) -> Result<()> {
+        require_ctx!(
+            proof_expiry_secs > 0,
+            TrinityError::InvalidLifecycleConfig,
+            proof_expiry_secs = proof_expiry_secs,
         );
-        require!(
-            monitoring_interval_ms <= MAX_MONITORING_INTERVAL_MS,
-            TrinityError::MonitoringIntervalTooHigh
+        require_ctx!(
+            sweep_bounty_bps <= MAX_SWEEP_BOUNTY_BPS,
+            TrinityError::SweepBountyTooHigh,
+            sweep_bounty_bps = sweep_bounty_bps,
+            max = MAX_SWEEP_BOUNTY_BPS,
         );
         
-        monitor_config.validator = validator.key();
-        monitor_config.monitoring_interval_ms = monitoring_interval_ms;
-        monitor_config.max_latency_ms = max_latency_ms;
-        monitor_config.last_check_timestamp = Clock::get()?.unix_timestamp as u64;
-        monitor_config.last_check_slot = Clock::get()?.slot;
-        monitor_config.total_checks = 0;
-        monitor_config.successful_proofs = 0;
-        monitor_config.failed_proofs = 0;
-        monitor_config.average_latency_ms = 0;
-        monitor_config.is_active = true;
-        monitor_config.bump = *ctx.bumps.get("monitor_config").unwrap();
+        let validator = &mut ctx.accounts.validator;
+        validator.proof_expiry_secs = proof_expiry_secs;
+        validator.proof_retention_secs = proof_retention_secs;
+        validator.sweep_bounty_bps = sweep_bounty_bps;
         
-        msg!("⚡ High-frequency monitoring initialized");
-        msg!("   Interval: {}ms", monitoring_interval_ms);
-        msg!("   Max Latency: {}ms", max_latency_ms);
+        msg!("Proof lifecycle updated");
+        msg!("   Expiry: {}s", proof_expiry_secs);
+        msg!("   Retention: {}s", proof_retention_secs);
+        msg!("   Sweep bounty: {} bps", sweep_bounty_bps);
+        Ok(())
+    }
+
+    /// Cap the proof budget of a transaction's submissions (see
+    /// PROOF_BUDGET_BASE_UNITS); at least one single-level proof must fit
+    pub fn set_max_proof_budget(ctx: Context<UpdateValidator>, max_proof_budget: u64) -> Result<()> {
+        let minimum = proof_budget(&[1]);
+        require_ctx!(
+            max_proof_budget >= minimum,
+            TrinityError::InvalidProofBudget,
+            max_proof_budget = max_proof_budget,
+            minimum = minimum,
+        );
+        ctx.accounts.validator.max_proof_budget = max_proof_budget;
         
+        msg!("Max proof budget: {} units", max_proof_budget);
         Ok(())
     }
     
-    /// Record a high-frequency monitoring check
-    /// Called by off-chain monitoring service at configured intervals
-    /// ENFORCES <5 second SLA and triggers alerts on breaches
-    pub fn record_monitoring_check(
-        ctx: Context<RecordMonitoringCheck>,
-        check_type: MonitoringCheckType,
-        latency_ms: u64,
-        operation_count: u32,
-        proof_generated: bool,
-    ) -> Result<()> {
-        let monitor_config = &mut ctx.accounts.monitor_config;
-        let validator = &ctx.accounts.validator;
+    /// Stop (or resume) storing the 64-byte Solana transaction signature on new
+    /// proof records, e.g. once signatures are checked via instruction
+    /// introspection instead. Records already stored keep their size.
+    pub fn set_store_tx_signatures(ctx: Context<UpdateValidator>, store_tx_signatures: bool) -> Result<()> {
+        ctx.accounts.validator.store_tx_signatures = store_tx_signatures;
+        
+        msg!("Store transaction signatures: {}", store_tx_signatures);
+        Ok(())
+    }
+    
+    /// Hand operation commits to another key, e.g. the bridge program's PDA
+    /// so it can commit each operation by CPI as it emits it
+    pub fn set_operation_committer(ctx: Context<UpdateValidator>, operation_committer: Pubkey) -> Result<()> {
+        ctx.accounts.validator.operation_committer = operation_committer;
         
-        require!(validator.is_active, TrinityError::ValidatorNotActive);
-        require!(monitor_config.is_active, TrinityError::MonitoringNotActive);
+        msg!("Operation committer: {}", operation_committer);
+        Ok(())
+    }
+    
+    /// Name the vesting program whose schedules verify_vault_operation
+    /// cross-checks for a pending emergency recovery (default pubkey: none)
+    pub fn set_vesting_program(ctx: Context<SetVestingProgram>, vesting_program: Pubkey) -> Result<()> {
+        let link = &mut ctx.accounts.vesting_link;
+        link.vesting_program = vesting_program;
+        link.bump = *ctx.bumps.get("vesting_link").unwrap();
         
-        let current_timestamp = Clock::get()?.unix_timestamp as u64;
-        let current_slot = Clock::get()?.slot;
+        msg!("Vesting program: {}", vesting_program);
+        Ok(())
+    }
+    
+    /// Store the latest Ethereum checkpoint: how many Solana proofs the bridge
+    /// has processed and the rolling hash over their operation IDs, in
+    /// ProofGenerated sequence order. Posted by the operation committer,
+    /// which relays bridge state to Solana. `eth_event_timestamp` is the
+    /// relayer's own observation of the count-th operation's Ethereum event
+    /// (0 if not reported), checked against the operator's by reconcile.
+    pub fn submit_eth_checkpoint(
+        ctx: Context<SubmitEthCheckpoint>,
+        count: u64,
+        rolling_hash: [u8; 32],
+        eth_block: u64,
+        eth_event_timestamp: i64,
+    ) -> Result<()> {
+        let checkpoint = &mut ctx.accounts.eth_checkpoint;
+        require_ctx!(
+            eth_block > checkpoint.eth_block,
+            TrinityError::StaleCheckpoint,
+            eth_block = eth_block,
+            latest = checkpoint.eth_block,
+        );
         
-        // Calculate slot difference (Solana ~400ms per slot)
-        let slot_diff = current_slot.saturating_sub(monitor_config.last_check_slot);
+        checkpoint.validator = ctx.accounts.validator.key();
+        checkpoint.count = count;
+        checkpoint.rolling_hash = rolling_hash;
+        checkpoint.eth_block = eth_block;
+        checkpoint.eth_event_timestamp = eth_event_timestamp;
+        checkpoint.submitted_at = Clock::get()?.unix_timestamp;
+        checkpoint.bump = *ctx.bumps.get("eth_checkpoint").unwrap();
         
-        // Update monitoring stats with OVERFLOW PROTECTION
-        monitor_config.last_check_timestamp = current_timestamp;
-        monitor_config.last_check_slot = current_slot;
+        msg!("Ethereum checkpoint at block {}: {} operations", eth_block, count);
+        Ok(())
+    }
+    
+    /// Permissionless: compare the latest Ethereum checkpoint with the local
+    /// rolling hash at the same count. A mismatch is not an error: it is
+    /// recorded and puts the validator in monitor-only mode (proofs are still
+    /// generated, but none confirmed or finalized) until the authority
+    /// acknowledges it. On a match, the operator-reported Ethereum timestamp
+    /// of the count-th proof is cross-checked against the relayer's; a
+    /// disagreement only emits LatencyReportMismatch, since latency is
+    /// advisory.
+    pub fn reconcile(ctx: Context<Reconcile>) -> Result<()> {
+        let checkpoint = &ctx.accounts.eth_checkpoint;
+        let sequence = &ctx.accounts.proof_sequence;
         
-        // Saturating add to prevent overflow
-        monitor_config.total_checks = monitor_config.total_checks.saturating_add(1);
+        // Past the local count, Ethereum has processed proofs Solana never generated
+        let (actual_count, actual, operator_reported) = if checkpoint.count > sequence.global_proof_seq {
+            (sequence.global_proof_seq, sequence.rolling_hash, 0)
+        } else {
+            let entry = sequence.checkpoint_at(checkpoint.count);
+            require_ctx!(
+                entry.is_some(),
+                TrinityError::CheckpointOutsideWindow,
+                count = checkpoint.count,
+                latest = sequence.global_proof_seq,
+                window = RECONCILE_WINDOW,
+            );
+            let entry = entry.unwrap_or_default();
+            (checkpoint.count, entry.rolling_hash, entry.eth_event_timestamp)
+        };
         
-        if proof_generated {
-            monitor_config.successful_proofs = monitor_config.successful_proofs.saturating_add(1);
-        } else if operation_count > 0 {
-            monitor_config.failed_proofs = monitor_config.failed_proofs.saturating_add(1);
+        if actual_count == checkpoint.count && actual == checkpoint.rolling_hash {
+            let drift = operator_reported.saturating_sub(checkpoint.eth_event_timestamp).saturating_abs();
+            if checkpoint.count > 0 && checkpoint.eth_event_timestamp != 0 && drift > LATENCY_REPORT_TOLERANCE_SECS {
+                emit!(LatencyReportMismatch {
+                    eth_block: checkpoint.eth_block,
+                    count: checkpoint.count,
+                    operator_reported,
+                    relayer_reported: checkpoint.eth_event_timestamp,
+                });
+                msg!("⚠️ Operator-reported Ethereum timestamp off by {}s at count {}", drift, checkpoint.count);
+            }
+            emit!(ReconciliationOk {
+                eth_block: checkpoint.eth_block,
+                count: checkpoint.count,
+                rolling_hash: actual,
+            });
+            return Ok(());
         }
         
-        // ROLLING AVERAGE with overflow protection
-        // Use exponential moving average to prevent unbounded growth
-        // New average = (old_avg * 7 + new_value * 1) / 8 (12.5% weight for new values)
-        let old_avg = monitor_config.average_latency_ms;
-        let weighted_old = old_avg.saturating_mul(7);
-        let weighted_new = latency_ms;
-        monitor_config.average_latency_ms = weighted_old.saturating_add(weighted_new) / 8;
+        ctx.accounts.validator.monitor_only = true;
+        emit!(ReconciliationMismatch {
+            eth_block: checkpoint.eth_block,
+            expected_count: checkpoint.count,
+            actual_count,
+            expected: checkpoint.rolling_hash,
+            actual,
+        });
+        msg!("⚠️ Reconciliation mismatch at Ethereum block {}: monitor-only", checkpoint.eth_block);
+        Ok(())
+    }
+    
+    /// Leave monitor-only mode once a reconciliation mismatch has been investigated
+    pub fn acknowledge_reconciliation(ctx: Context<UpdateValidator>) -> Result<()> {
+        let validator = &mut ctx.accounts.validator;
+        require_ctx!(validator.monitor_only, TrinityError::NotMonitorOnly, validator = validator.key());
+        validator.monitor_only = false;
         
-        // ================================================================
-        // ENFORCE <5 SECOND SLA
-        // ================================================================
-        let sla_breached = latency_ms > TARGET_PROOF_LATENCY_MS;
-        let critical_breach = latency_ms > TARGET_PROOF_LATENCY_MS * 2; // >10 seconds is critical
+        emit!(ReconciliationAcknowledged {
+            acknowledged_by: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+    
+    /// Register an operation the bridge emitted. Proofs are only accepted for
+    /// committed operations that have not been proven yet.
+    pub fn commit_operation(ctx: Context<CommitOperation>, operation_id: [u8; 32]) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
         
-        // Emit standard monitoring event
-        emit!(MonitoringCheckRecorded {
-            validator: validator.key(),
-            check_type: check_type.clone(),
-            timestamp: current_timestamp,
-            slot: current_slot,
-            latency_ms,
-            operation_count,
-            proof_generated,
-            slots_since_last_check: slot_diff,
+        let expected = &mut ctx.accounts.expected_operation;
+        expected.operation_id = operation_id;
+        expected.committed_by = ctx.accounts.operation_committer.key();
+        expected.committed_at = now;
+        expected.pending = true;
+        expected.bump = *ctx.bumps.get("expected_operation").unwrap();
+        
+        emit!(OperationCommitted {
+            operation_id,
+            committed_by: expected.committed_by,
+            timestamp: now,
         });
+        Ok(())
+    }
+    
+    /// Permissionless crank: expire stale proofs and close those past retention
+    /// Proof PDAs are supplied via remaining_accounts; anything that doesn't
+    /// qualify (fresh, confirmed, foreign or malformed) is skipped, not rejected.
+    /// The cranker is paid a capped bounty out of each closed account's rent.
+    pub fn sweep_expired<'info>(
+        ctx: Context<'_, '_, '_, 'info, SweepExpired<'info>>,
+        max_count: u8,
+    ) -> Result<()> {
+        require_ctx!(
+            max_count > 0 && max_count <= MAX_SWEEP_BATCH,
+            TrinityError::InvalidSweepBatch,
+            max_count = max_count,
+            max = MAX_SWEEP_BATCH,
+        );
+        require_ctx!(
+            ctx.remaining_accounts.len() <= MAX_SWEEP_BATCH as usize,
+            TrinityError::InvalidSweepBatch,
+            accounts = ctx.remaining_accounts.len(),
+            max = MAX_SWEEP_BATCH,
+        );
         
-        // EMIT ALERT on SLA breach
-        if sla_breached {
-            emit!(SlaBreachAlert {
-                validator: validator.key(),
-                latency_ms,
-                target_latency_ms: TARGET_PROOF_LATENCY_MS,
-                breach_severity: if critical_breach { 2 } else { 1 },
-                timestamp: current_timestamp,
-                slot: current_slot,
-            });
+        let validator = &ctx.accounts.validator;
+        let cranker = ctx.accounts.cranker.to_account_info();
+        let rent_receiver = ctx.accounts.authority.to_account_info();
+        let now = Clock::get()?.unix_timestamp as u64;
+        
+        let mut examined: u8 = 0;
+        let mut expired: u8 = 0;
+        let mut closed: u8 = 0;
+        let mut skipped: u8 = 0;
+        let mut bounty_paid: u64 = 0;
+        
+        for info in ctx.remaining_accounts.iter().take(max_count as usize) {
+            examined += 1;
             
-            if critical_breach {
-                msg!("🚨 CRITICAL: Latency {}ms exceeds 2x target ({}ms)", latency_ms, TARGET_PROOF_LATENCY_MS * 2);
+            // Skip anything that isn't a writable ProofRecord at its canonical PDA
+            if !info.is_writable {
+                skipped += 1;
+                continue;
+            }
+            let mut record = match Account::<ProofRecord>::try_from(info) {
+                Ok(record) => record,
+                Err(_) => {
+                    skipped += 1;
+                    continue;
+                }
+            };
+            let (expected_pda, _) = Pubkey::find_program_address(
+                &[b"proof", record.operation_id.as_ref()],
+                ctx.program_id,
+            );
+            if expected_pda != info.key() {
+                skipped += 1;
+                continue;
+            }
+            
+            let mut touched = false;
+            
+            if proof_is_expirable(&record, validator, now) {
+                record.expired = true;
+                record.expired_at = now;
+                expired += 1;
+                touched = true;
+                
+                emit!(ProofExpired {
+                    operation_id: record.operation_id,
+                    validator: record.validator,
+                    expired_at: now,
+                });
+            }
+            
+            if proof_is_closable(&record, validator, now) {
+                let bounty = sweep_bounty(info.lamports(), validator.sweep_bounty_bps);
+                if bounty > 0 {
+                    **info.try_borrow_mut_lamports()? -= bounty;
+                    **cranker.try_borrow_mut_lamports()? += bounty;
+                    bounty_paid = bounty_paid.saturating_add(bounty);
+                }
+                emit!(ProofArchived { evidence: proof_evidence(&record), closed_at: now });
+                record.close(rent_receiver.clone())?;
+                closed += 1;
+            } else if touched {
+                record.exit(ctx.program_id)?;
             } else {
-                msg!("⚠️  SLA BREACH: Latency {}ms exceeds target {}ms", latency_ms, TARGET_PROOF_LATENCY_MS);
+                skipped += 1;
             }
-        } else {
-            msg!("✅ Monitoring check passed: {}ms latency (target: {}ms)", latency_ms, TARGET_PROOF_LATENCY_MS);
+        }
+        
+        let stats = &mut ctx.accounts.daily_stats;
+        touch_daily_stats(stats, *ctx.bumps.get("daily_stats").unwrap());
+        stats.proofs_expired = stats.proofs_expired.saturating_add(expired as u64);
+        
+        emit!(ProofsSwept {
+            cranker: cranker.key(),
+            examined,
+            expired,
+            closed,
+            skipped,
+            bounty_paid,
+            timestamp: now,
+        });
+        
+        msg!("🧹 Sweep complete: {} examined, {} expired, {} closed, {} skipped", examined, expired, closed, skipped);
+        msg!("   Bounty paid: {} lamports", bounty_paid);
+        
+        Ok(())
+    }
+    
+    /// Permissionless crank: fold per-operator submission counters into
+    /// `total_proofs_submitted`. Submissions only write their operator's
+    /// OperatorMetrics, so the validator account stays read-only on the hot
+    /// path and operators don't serialize on it. OperatorMetrics PDAs are
+    /// supplied via remaining_accounts; foreign or malformed ones are skipped.
+    pub fn rollup_metrics<'info>(ctx: Context<'_, '_, '_, 'info, RollupMetrics<'info>>) -> Result<()> {
+        require_ctx!(
+            ctx.remaining_accounts.len() <= MAX_ROLLUP_BATCH,
+            TrinityError::RollupBatchTooLarge,
+            accounts = ctx.remaining_accounts.len(),
+            max = MAX_ROLLUP_BATCH,
+        );
+        
+        let mut operators: u8 = 0;
+        let mut proofs_added: u64 = 0;
+        
+        for info in ctx.remaining_accounts.iter() {
+            if !info.is_writable {
+                continue;
+            }
+            let mut metrics = match load_operator_metrics(info, ctx.program_id) {
+                Some(metrics) => metrics,
+                None => continue,
+            };
+            if metrics.proofs_submitted == metrics.rolled_up {
+                continue;
+            }
+            
+            proofs_added = proofs_added.saturating_add(metrics.proofs_submitted - metrics.rolled_up);
+            metrics.rolled_up = metrics.proofs_submitted;
+            metrics.exit(ctx.program_id)?;
+            operators += 1;
+        }
+        
+        let validator = &mut ctx.accounts.validator;
+        validator.total_proofs_submitted = validator.total_proofs_submitted.saturating_add(proofs_added);
+        
+        emit!(MetricsRolledUp {
+            operators,
+            proofs_added,
+            total_proofs_submitted: validator.total_proofs_submitted,
+        });
+        
+        Ok(())
+    }
+    
+    /// Create the optional seen-operations ring used to reject recent replays
+    /// before any ProofRecord work (see SeenOperations for its limits)
+    pub fn initialize_seen_operations(ctx: Context<InitializeSeenOperations>) -> Result<()> {
+        let seen = &mut ctx.accounts.seen_operations;
+        seen.validator = ctx.accounts.validator.key();
+        seen.bump = *ctx.bumps.get("seen_operations").unwrap();
+        
+        msg!("Seen-operations filter initialized ({} entries)", SEEN_OPERATIONS_WINDOW);
+        Ok(())
+    }
+    
+    /// Create the shadow-mode settings with every rule in Monitor
+    pub fn initialize_check_config(ctx: Context<InitializeCheckConfig>) -> Result<()> {
+        let checks = &mut ctx.accounts.check_config;
+        checks.validator = ctx.accounts.validator.key();
+        checks.enforcement = [Enforcement::Monitor; SHADOW_CHECK_COUNT];
+        checks.queued = [QueuedEnforcement::NONE; SHADOW_CHECK_COUNT];
+        checks.operation_codes = INITIAL_OPERATION_CODES;
+        checks.operation_types = INITIAL_OPERATION_TYPES;
+        checks.max_amount = [u64::MAX; MAX_OPERATION_TYPES];
+        checks.queued_limits = [QueuedLimit::NONE; MAX_OPERATION_TYPES];
+        checks.max_bridged_per_user = u64::MAX;
+        checks.queued_bridge_cap = QueuedLimit::NONE;
+        checks.queued_operation_type = QueuedOperationType::NONE;
+        checks.consensus_window_secs = [0; MAX_OPERATION_TYPES];
+        checks.bump = *ctx.bumps.get("check_config").unwrap();
+        checks.layout_version = CheckConfig::LAYOUT_VERSION;
+        
+        msg!("🕶️ Shadow-mode checks initialized (all Monitor, no amount limits)");
+        Ok(())
+    }
+    
+    /// Queue an enforcement change for one rule; it applies after
+    /// ENFORCEMENT_TIMELOCK_SECS. Re-queueing replaces the pending change.
+    pub fn queue_enforcement(
+        ctx: Context<QueueRuleChange>,
+        check: CheckId,
+        enforcement: Enforcement,
+    ) -> Result<()> {
+        let executable_at = Clock::get()?.unix_timestamp + ENFORCEMENT_TIMELOCK_SECS;
+        ctx.accounts.check_config.queued[check as usize] = QueuedEnforcement { enforcement, executable_at };
+        
+        emit!(EnforcementQueued { check, enforcement, executable_at });
+        
+        msg!("🕶️ {:?} -> {:?} queued, executable at {}", check, enforcement, executable_at);
+        Ok(())
+    }
+    
+    /// Apply a queued enforcement change once its timelock has elapsed (permissionless)
+    pub fn apply_enforcement(ctx: Context<ApplyRuleChange>, check: CheckId) -> Result<()> {
+        let checks = &mut ctx.accounts.check_config;
+        let queued = checks.queued[check as usize];
+        require_queued_change_ready(queued.executable_at)?;
+        
+        checks.enforcement[check as usize] = queued.enforcement;
+        checks.queued[check as usize] = QueuedEnforcement::NONE;
+        
+        emit!(EnforcementApplied { check, enforcement: queued.enforcement });
+        
+        msg!("🕶️ {:?} is now {:?}", check, queued.enforcement);
+        Ok(())
+    }
+    
+    /// Queue a new per-type amount limit (u64::MAX for none); it applies
+    /// after ENFORCEMENT_TIMELOCK_SECS. Re-queueing replaces the pending change.
+    pub fn queue_amount_limit(
+        ctx: Context<QueueRuleChange>,
+        operation_type: OperationType,
+        max_amount: u64,
+    ) -> Result<()> {
+        let slot = ctx.accounts.check_config.type_slot(&operation_type)?;
+        let executable_at = Clock::get()?.unix_timestamp + ENFORCEMENT_TIMELOCK_SECS;
+        ctx.accounts.check_config.queued_limits[slot] = QueuedLimit { max_amount, executable_at };
+        
+        emit!(AmountLimitQueued { operation_type: operation_type.clone(), max_amount, executable_at });
+        
+        msg!("🧮 {:?} limit -> {} queued, executable at {}", operation_type, max_amount, executable_at);
+        Ok(())
+    }
+    
+    /// Apply a queued amount limit once its timelock has elapsed (permissionless)
+    pub fn apply_amount_limit(ctx: Context<ApplyRuleChange>, operation_type: OperationType) -> Result<()> {
+        let checks = &mut ctx.accounts.check_config;
+        let slot = checks.type_slot(&operation_type)?;
+        let queued = checks.queued_limits[slot];
+        require_queued_change_ready(queued.executable_at)?;
+        
+        checks.max_amount[slot] = queued.max_amount;
+        checks.queued_limits[slot] = QueuedLimit::NONE;
+        
+        emit!(AmountLimitApplied { operation_type: operation_type.clone(), max_amount: queued.max_amount });
+        
+        msg!("🧮 {:?} limit is now {}", operation_type, queued.max_amount);
+        Ok(())
+    }
+    
+    /// Set how long an operation of this type has to reach consensus: each
+    /// proof's deadline is fixed at submission from its type's window, so a
+    /// change takes effect immediately and only for later submissions.
+    /// Types without a window keep the validator's proof_expiry_secs.
+    pub fn set_consensus_window(
+        ctx: Context<QueueRuleChange>,
+        operation_type: OperationType,
+        consensus_window_secs: u64,
+    ) -> Result<()> {
+        require_ctx!(
+            consensus_window_secs > 0,
+            TrinityError::InvalidConsensusWindow,
+            operation_type = operation_type.code(),
+            consensus_window_secs = consensus_window_secs,
+        );
+        let checks = &mut ctx.accounts.check_config;
+        let slot = checks.type_slot(&operation_type)?;
+        checks.consensus_window_secs[slot] = consensus_window_secs;
+        
+        emit!(ConsensusWindowSet { operation_type: operation_type.clone(), consensus_window_secs });
+        
+        msg!("⏳ {:?} consensus window is now {}s", operation_type, consensus_window_secs);
+        Ok(())
+    }
+    
+    /// Queue a new cumulative CrossChainTransfer cap per user (u64::MAX for
+    /// none); it applies after ENFORCEMENT_TIMELOCK_SECS. Re-queueing replaces
+    /// the pending change.
+    pub fn queue_bridge_cap(ctx: Context<QueueRuleChange>, max_bridged_per_user: u64) -> Result<()> {
+        let executable_at = Clock::get()?.unix_timestamp + ENFORCEMENT_TIMELOCK_SECS;
+        ctx.accounts.check_config.queued_bridge_cap = QueuedLimit { max_amount: max_bridged_per_user, executable_at };
+        
+        emit!(BridgeCapQueued { max_bridged_per_user, executable_at });
+        
+        msg!("🌉 Bridge cap -> {} queued, executable at {}", max_bridged_per_user, executable_at);
+        Ok(())
+    }
+    
+    /// Apply a queued bridge cap once its timelock has elapsed (permissionless)
+    pub fn apply_bridge_cap(ctx: Context<ApplyRuleChange>) -> Result<()> {
+        let checks = &mut ctx.accounts.check_config;
+        let queued = checks.queued_bridge_cap;
+        require_queued_change_ready(queued.executable_at)?;
+        
+        checks.max_bridged_per_user = queued.max_amount;
+        checks.queued_bridge_cap = QueuedLimit::NONE;
+        
+        emit!(BridgeCapApplied { max_bridged_per_user: queued.max_amount });
+        
+        msg!("🌉 Bridge cap is now {}", queued.max_amount);
+        Ok(())
+    }
+    
+    /// Queue enabling an OperationType code in the next free registry slot; it
+    /// applies after ENFORCEMENT_TIMELOCK_SECS. Re-queueing replaces the
+    /// pending code. Enabled codes keep their slot for good.
+    pub fn queue_operation_type(ctx: Context<QueueRuleChange>, code: u16) -> Result<()> {
+        let checks = &mut ctx.accounts.check_config;
+        require_ctx!(
+            !checks.enabled_codes().contains(&code),
+            TrinityError::OperationTypeAlreadyEnabled,
+            code = code,
+        );
+        require_ctx!(
+            (checks.operation_types as usize) < MAX_OPERATION_TYPES,
+            TrinityError::OperationTypeRegistryFull,
+            max = MAX_OPERATION_TYPES,
+        );
+        let executable_at = Clock::get()?.unix_timestamp + ENFORCEMENT_TIMELOCK_SECS;
+        checks.queued_operation_type = QueuedOperationType { code, executable_at };
+        
+        emit!(OperationTypeQueued { code, executable_at });
+        
+        msg!("🏷️ {:?} queued, executable at {}", OperationType::from_code(code), executable_at);
+        Ok(())
+    }
+    
+    /// Enable a queued OperationType code once its timelock has elapsed
+    /// (permissionless). It starts with no amount limit.
+    pub fn apply_operation_type(ctx: Context<ApplyRuleChange>) -> Result<()> {
+        let checks = &mut ctx.accounts.check_config;
+        let queued = checks.queued_operation_type;
+        require_queued_change_ready(queued.executable_at)?;
+        
+        let slot = checks.operation_types;
+        checks.operation_codes[slot as usize] = queued.code;
+        checks.operation_types += 1;
+        checks.queued_operation_type = QueuedOperationType::NONE;
+        
+        emit!(OperationTypeEnabled { code: queued.code, slot });
+        
+        msg!("🏷️ {:?} enabled in slot {}", OperationType::from_code(queued.code), slot);
+        Ok(())
+    }
+    
+    // ========================================================================
+    // HIGH-FREQUENCY MONITORING SYSTEM (Solana's Role in Trinity Protocol)
+    // ========================================================================
+    
+    /// Initialize high-frequency monitoring configuration
+    /// Configures the monitoring interval and thresholds
+    pub fn initialize_monitoring(
+        ctx: Context<InitializeMonitoring>,
+        monitoring_interval_ms: u64,
+        max_latency_ms: u64,
+    ) -> Result<()> {
+        let monitor_config = &mut ctx.accounts.monitor_config;
+        let validator = &ctx.accounts.validator;
+        
+        require_ctx!(validator.is_active, TrinityError::ValidatorNotActive, validator = validator.key());
+        require_ctx!(
+            monitoring_interval_ms >= MIN_MONITORING_INTERVAL_MS,
+            TrinityError::MonitoringIntervalTooLow,
+            interval_ms = monitoring_interval_ms,
+            min = MIN_MONITORING_INTERVAL_MS,
+        );
+        require_ctx!(
+            monitoring_interval_ms <= MAX_MONITORING_INTERVAL_MS,
+            TrinityError::MonitoringIntervalTooHigh,
+            interval_ms = monitoring_interval_ms,
+            max = MAX_MONITORING_INTERVAL_MS,
+        );
+        
+        monitor_config.validator = validator.key();
+        monitor_config.monitoring_interval_ms = monitoring_interval_ms;
+        monitor_config.max_latency_ms = max_latency_ms;
+        monitor_config.last_check_timestamp = Clock::get()?.unix_timestamp as u64;
+        monitor_config.last_check_slot = Clock::get()?.slot;
+        monitor_config.total_checks = 0;
+        monitor_config.successful_proofs = 0;
+        monitor_config.failed_proofs = 0;
+        monitor_config.average_latency_ms = 0;
+        monitor_config.is_active = true;
+        monitor_config.bump = *ctx.bumps.get("monitor_config").unwrap();
+        
+        msg!("⚡ High-frequency monitoring initialized");
+        msg!("   Interval: {}ms", monitoring_interval_ms);
+        msg!("   Max Latency: {}ms", max_latency_ms);
+        
+        Ok(())
+    }
+    
+    /// Record a high-frequency monitoring check
+    /// Called by off-chain monitoring service at configured intervals
+    /// ENFORCES <5 second SLA and triggers alerts on breaches
+    pub fn record_monitoring_check(
+        ctx: Context<RecordMonitoringCheck>,
+        check_type: MonitoringCheckType,
+        latency_ms: u64,
+        operation_count: u32,
+        proof_generated: bool,
+    ) -> Result<()> {
+        let monitor_config = &mut ctx.accounts.monitor_config;
+        let validator = &ctx.accounts.validator;
+        
+        require_ctx!(validator.is_active, TrinityError::ValidatorNotActive, validator = validator.key());
+        require_ctx!(
+            monitor_config.is_active,
+            TrinityError::MonitoringNotActive,
+            monitor = monitor_config.key(),
+        );
+        
+        let current_timestamp = Clock::get()?.unix_timestamp as u64;
+        let current_slot = Clock::get()?.slot;
+        
+        // Calculate slot difference (Solana ~400ms per slot)
+        let slot_diff = current_slot.saturating_sub(monitor_config.last_check_slot);
+        
+        // Update monitoring stats with OVERFLOW PROTECTION
+        monitor_config.last_check_timestamp = current_timestamp;
+        monitor_config.last_check_slot = current_slot;
+        
+        // Saturating add to prevent overflow
+        monitor_config.total_checks = monitor_config.total_checks.saturating_add(1);
+        
+        if proof_generated {
+            monitor_config.successful_proofs = monitor_config.successful_proofs.saturating_add(1);
+        } else if operation_count > 0 {
+            monitor_config.failed_proofs = monitor_config.failed_proofs.saturating_add(1);
+            
+            let stats = &mut ctx.accounts.daily_stats;
+            touch_daily_stats(stats, *ctx.bumps.get("daily_stats").unwrap());
+            stats.proofs_failed = stats.proofs_failed.saturating_add(1);
+        }
+        
+        // ROLLING AVERAGE with overflow protection
+        // Use exponential moving average to prevent unbounded growth
+        // New average = (old_avg * 7 + new_value * 1) / 8 (12.5% weight for new values)
+        let old_avg = monitor_config.average_latency_ms;
+        let weighted_old = old_avg.saturating_mul(7);
+        let weighted_new = latency_ms;
+        monitor_config.average_latency_ms = weighted_old.saturating_add(weighted_new) / 8;
+        
+        // ================================================================
+        // ENFORCE <5 SECOND SLA
+        // ================================================================
+        let sla_breached = latency_ms > TARGET_PROOF_LATENCY_MS;
+        let critical_breach = latency_ms > TARGET_PROOF_LATENCY_MS * 2; // >10 seconds is critical
+        
+        // Emit standard monitoring event
+        emit!(MonitoringCheckRecorded {
+            validator: validator.key(),
+            check_type: check_type.clone(),
+            timestamp: current_timestamp,
+            slot: current_slot,
+            latency_ms,
+            operation_count,
+            proof_generated,
+            slots_since_last_check: slot_diff,
+        });
+        
+        // EMIT ALERT on SLA breach
+        if sla_breached {
+            emit!(SlaBreachAlert {
+                validator: validator.key(),
+                latency_ms,
+                target_latency_ms: TARGET_PROOF_LATENCY_MS,
+                breach_severity: if critical_breach { 2 } else { 1 },
+                timestamp: current_timestamp,
+                slot: current_slot,
+            });
+            
+            if critical_breach {
+                msg!("🚨 CRITICAL: Latency {}ms exceeds 2x target ({}ms)", latency_ms, TARGET_PROOF_LATENCY_MS * 2);
+            } else {
+                msg!("⚠️  SLA BREACH: Latency {}ms exceeds target {}ms", latency_ms, TARGET_PROOF_LATENCY_MS);
+            }
+        } else {
+            msg!("✅ Monitoring check passed: {}ms latency (target: {}ms)", latency_ms, TARGET_PROOF_LATENCY_MS);
         }
         
         Ok(())
@@ -339,8 +1394,12 @@ pub mod trinity_validator {
         let validator = &ctx.accounts.validator;
         let fast_proof = &mut ctx.accounts.fast_proof;
         
-        require!(validator.is_active, TrinityError::ValidatorNotActive);
-        require!(urgency_level > 0 && urgency_level <= 3, TrinityError::InvalidUrgencyLevel);
+        require_ctx!(validator.is_active, TrinityError::ValidatorNotActive, validator = validator.key());
+        require_ctx!(
+            urgency_level > 0 && urgency_level <= 3,
+            TrinityError::InvalidUrgencyLevel,
+            urgency_level = urgency_level,
+        );
         
         let current_timestamp = Clock::get()?.unix_timestamp as u64;
         let current_slot = Clock::get()?.slot;
@@ -392,240 +1451,3451 @@ pub mod trinity_validator {
             is_active: monitor_config.is_active,
         })
     }
-}
-
-// ============================================================================
-// Account Structures
-// ============================================================================
-
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + TrinityValidator::INIT_SPACE,
-        seeds = [b"trinity_validator"],
-        bump
-    )]
-    pub validator: Account<'info, TrinityValidator>,
     
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    // ========================================================================
+    // MANUAL CONSENSUS OVERRIDE (catastrophic fallback)
+    // ========================================================================
     
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-#[instruction(operation_id: [u8; 32])]
-pub struct SubmitProof<'info> {
-    #[account(mut, seeds = [b"trinity_validator"], bump = validator.bump)]
-    pub validator: Account<'info, TrinityValidator>,
+    /// Set up the dispute council whose M-of-N quorum gates manual overrides
+    pub fn initialize_dispute_council(
+        ctx: Context<InitializeDisputeCouncil>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        validate_council(&members, threshold)?;
+        
+        let council = &mut ctx.accounts.council;
+        council.validator = ctx.accounts.validator.key();
+        council.members = members;
+        council.threshold = threshold;
+        council.bump = *ctx.bumps.get("council").unwrap();
+        
+        msg!("⚖️ Dispute council initialized: {}-of-{}", threshold, council.members.len());
+        Ok(())
+    }
     
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + ProofRecord::INIT_SPACE,
-        seeds = [b"proof", operation_id.as_ref()],
-        bump
-    )]
-    pub proof_record: Account<'info, ProofRecord>,
+    /// Propose pushing an operation through without organic consensus
+    /// Only for when two chains are halted. Requires the full council quorum
+    /// (proposer + co-signers in remaining_accounts), is announced immediately
+    /// and can't execute for OVERRIDE_DELAY_SECS.
+    pub fn manual_override(
+        ctx: Context<ProposeOverride>,
+        operation_id: [u8; 32],
+        justification_hash: [u8; 32],
+    ) -> Result<()> {
+        let council = &ctx.accounts.council;
+        let now = Clock::get()?.unix_timestamp;
+        
+        require_ctx!(justification_hash != [0u8; 32], TrinityError::MissingJustification);
+        let approvals = count_council_approvals(
+            council,
+            &ctx.accounts.proposer.key(),
+            ctx.remaining_accounts,
+        );
+        require_ctx!(
+            approvals >= council.threshold,
+            TrinityError::InsufficientCouncilApprovals,
+            approvals = approvals,
+            threshold = council.threshold,
+        );
+        
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.operation_id = operation_id;
+        proposal.justification_hash = justification_hash;
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.proposed_at = now;
+        proposal.executable_at = now + OVERRIDE_DELAY_SECS;
+        proposal.approvals = approvals;
+        proposal.at_fault = ctx.accounts.at_fault.as_ref().map_or(Pubkey::default(), |metrics| metrics.operator);
+        proposal.bump = *ctx.bumps.get("proposal").unwrap();
+        
+        emit!(OverrideProposed {
+            operation_id,
+            justification_hash,
+            proposer: proposal.proposer,
+            approvals,
+            executable_at: proposal.executable_at,
+        });
+        
+        msg!("🚨 MANUAL OVERRIDE PROPOSED for operation: {:?}", operation_id);
+        msg!("   Approvals: {}/{}", approvals, council.members.len());
+        msg!("   Executable at: {}", proposal.executable_at);
+        
+        Ok(())
+    }
     
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// Execute an override once its delay has elapsed (permissionless)
+    /// The resulting ConsensusState is permanently marked `overridden`. It may
+    /// already exist (a pause or an attestation creates it), but must be
+    /// neither finalized, failed nor paused.
+    pub fn execute_manual_override(
+        ctx: Context<ExecuteOverride>,
+        operation_id: [u8; 32],
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        let consensus = &ctx.accounts.consensus_state;
+        let now = Clock::get()?.unix_timestamp;
+        
+        require_ctx!(
+            !consensus.finalized,
+            TrinityError::ConsensusAlreadyFinalized,
+            consensus = consensus.key(),
+            finalized_at = consensus.finalized_at,
+        );
+        require_ctx!(
+            consensus.failed_at == 0,
+            TrinityError::OperationFailed,
+            consensus = consensus.key(),
+            failed_at = consensus.failed_at,
+        );
+        require_ctx!(!consensus.operation_paused, TrinityError::OperationPaused, consensus = consensus.key());
+        require_ctx!(
+            now >= proposal.executable_at,
+            TrinityError::OverrideDelayActive,
+            executable_at = proposal.executable_at,
+            now = now,
+        );
+        if proposal.at_fault != Pubkey::default() {
+            let at_fault = ctx.accounts.at_fault.as_deref_mut();
+            require_ctx!(
+                at_fault.as_ref().map(|metrics| metrics.operator) == Some(proposal.at_fault),
+                TrinityError::AtFaultOperatorMismatch,
+                at_fault = proposal.at_fault,
+            );
+            if let Some(metrics) = at_fault {
+                metrics.score_inputs.disputes_lost = metrics.score_inputs.disputes_lost.saturating_add(1);
+            }
+        }
+        
+        let consensus = &mut ctx.accounts.consensus_state;
+        consensus.operation_id = operation_id;
+        consensus.finalized_at = now;
+        consensus.overridden = true;
+        consensus.justification_hash = proposal.justification_hash;
+        consensus.finalized = true;
+        consensus.bump = *ctx.bumps.get("consensus_state").unwrap();
+        consensus.layout_version = ConsensusState::LAYOUT_VERSION;
+        
+        let stats = &mut ctx.accounts.daily_stats;
+        touch_daily_stats(stats, *ctx.bumps.get("daily_stats").unwrap());
+        stats.consensus_reached = stats.consensus_reached.saturating_add(1);
+        stats.consensus_overridden = stats.consensus_overridden.saturating_add(1);
+        
+        emit!(OverrideExecuted {
+            operation_id,
+            justification_hash: proposal.justification_hash,
+            executed_at: now,
+        });
+        
+        msg!("🚨 MANUAL OVERRIDE EXECUTED for operation: {:?}", operation_id);
+        Ok(())
+    }
     
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-#[instruction(operation_id: [u8; 32])]
-pub struct ConfirmSubmission<'info> {
-    #[account(
-        mut,
-        seeds = [b"proof", operation_id.as_ref()],
-        bump
-    )]
-    pub proof_record: Account<'info, ProofRecord>,
+    /// Any single council member can abort a pending override during the delay
+    pub fn cancel_manual_override(
+        ctx: Context<CancelOverride>,
+        operation_id: [u8; 32],
+    ) -> Result<()> {
+        require_ctx!(
+            ctx.accounts.council.members.contains(&ctx.accounts.member.key()),
+            TrinityError::NotCouncilMember,
+            member = ctx.accounts.member.key(),
+        );
+        
+        emit!(OverrideCancelled {
+            operation_id,
+            cancelled_by: ctx.accounts.member.key(),
+        });
+        
+        msg!("⛔ Manual override cancelled for operation: {:?}", operation_id);
+        Ok(())
+    }
     
-    #[account(mut)]
-    pub authority: Signer<'info>,
-}
-
-#[derive(Accounts)]
-#[instruction(vault_id: u64, vault_owner: Pubkey)]
-pub struct VerifyOperation<'info> {
-    #[account(seeds = [b"trinity_validator"], bump = validator.bump)]
-    pub validator: Account<'info, TrinityValidator>,
+    /// Force a jammed operation (conflicting roots, an override nobody will
+    /// execute) to Failed so the bridge can move on. Needs the full council
+    /// quorum like a proposal, but takes effect immediately: it only ever
+    /// stops an operation, and a reached one can't be expired. A Failed
+    /// operation can never finalize, and an open override on it can no longer
+    /// execute.
+    pub fn force_expire_operation(
+        ctx: Context<ForceExpireOperation>,
+        operation_id: [u8; 32],
+    ) -> Result<()> {
+        let council = &ctx.accounts.council;
+        let consensus = &mut ctx.accounts.consensus_state;
+        
+        require_ctx!(
+            !consensus.finalized,
+            TrinityError::ConsensusAlreadyFinalized,
+            consensus = consensus.key(),
+            finalized_at = consensus.finalized_at,
+        );
+        require_ctx!(
+            consensus.failed_at == 0,
+            TrinityError::OperationFailed,
+            consensus = consensus.key(),
+            failed_at = consensus.failed_at,
+        );
+        let approvals = count_council_approvals(
+            council,
+            &ctx.accounts.forced_by.key(),
+            ctx.remaining_accounts,
+        );
+        require_ctx!(
+            approvals >= council.threshold,
+            TrinityError::InsufficientCouncilApprovals,
+            approvals = approvals,
+            threshold = council.threshold,
+        );
+        
+        let now = Clock::get()?.unix_timestamp;
+        consensus.operation_id = operation_id;
+        consensus.failed_at = now;
+        consensus.bump = *ctx.bumps.get("consensus_state").unwrap();
+        consensus.layout_version = ConsensusState::LAYOUT_VERSION;
+        if let Some(metrics) = ctx.accounts.at_fault.as_deref_mut() {
+            metrics.score_inputs.divergences = metrics.score_inputs.divergences.saturating_add(1);
+        }
+        
+        emit!(ConsensusFailed {
+            operation_id,
+            forced_by: ctx.accounts.forced_by.key(),
+            approvals,
+            failed_at: now,
+        });
+        
+        msg!("💀 Operation force-expired: {:?}", operation_id);
+        msg!("   Approvals: {}/{}", approvals, council.members.len());
+        Ok(())
+    }
     
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + VaultVerification::INIT_SPACE,
-        seeds = [b"verification", &vault_id.to_le_bytes(), vault_owner.as_ref()],
-        bump
-    )]
-    pub verification: Account<'info, VaultVerification>,
+    // ========================================================================
+    // DAILY OBSERVABILITY STATS
+    // ========================================================================
     
-    /// CHECK: Vault account - verified by checking it's not System-owned and matches vault_owner
-    pub vault: AccountInfo<'info>,
+    /// Get today's counters (return data); fails if nothing was recorded today
+    pub fn get_daily_stats(ctx: Context<GetDailyStats>) -> Result<DailyStats> {
+        Ok(DailyStats::clone(&ctx.accounts.daily_stats))
+    }
     
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// Today's end-to-end latency summary (return data). Advisory: computed
+    /// from Ethereum timestamps the operators report themselves.
+    pub fn get_latency_stats(ctx: Context<GetDailyStats>) -> Result<LatencyStats> {
+        let stats = &ctx.accounts.daily_stats;
+        Ok(LatencyStats {
+            samples: stats.latency_samples,
+            average_secs: stats.latency_sum_secs.checked_div(stats.latency_samples).unwrap_or(0),
+            p95_bound_secs: latency_percentile_bound(&stats.latency_buckets, 95),
+            buckets: stats.latency_buckets,
+            implausible: stats.latency_implausible,
+            advisory: true,
+        })
+    }
     
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-pub struct UpdateValidator<'info> {
-    #[account(
-        mut,
-        seeds = [b"trinity_validator"],
-        bump = validator.bump,
-        has_one = authority
-    )]
-    pub validator: Account<'info, TrinityValidator>,
+    /// One-call localnet setup: validator, monitoring and a 2-of-3 dispute
+    /// council of the deterministic devtools keys, all with default settings.
+    /// Anchor can't cfg-gate a #[program] instruction, so without the
+    /// `devtools` feature this only ever fails with DevtoolsDisabled.
+    pub fn bootstrap_localnet(ctx: Context<BootstrapLocalnet>) -> Result<()> {
+        #[cfg(feature = "devtools")]
+        return devtools::bootstrap_localnet(ctx);
+        
+        #[cfg(not(feature = "devtools"))]
+        {
+            let _ = ctx;
+            err!(TrinityError::DevtoolsDisabled)
+        }
+    }
     
-    pub authority: Signer<'info>,
-}
-
-// ============================================================================
-// HIGH-FREQUENCY MONITORING Account Structures
-// ============================================================================
-
-#[derive(Accounts)]
-pub struct InitializeMonitoring<'info> {
-    #[account(seeds = [b"trinity_validator"], bump = validator.bump)]
-    pub validator: Account<'info, TrinityValidator>,
+    /// Close a stats shard past retention (permissionless)
+    /// Its final counters are emitted so indexers keep the history.
+    pub fn close_daily_stats(ctx: Context<CloseDailyStats>, day: u64) -> Result<()> {
+        require_ctx!(
+            current_day() >= day.saturating_add(STATS_RETENTION_DAYS),
+            TrinityError::StatsRetentionActive,
+            day = day,
+            closable_from = day.saturating_add(STATS_RETENTION_DAYS),
+        );
+        
+        let stats = &ctx.accounts.daily_stats;
+        emit!(DailyStatsClosed {
+            day,
+            proofs_submitted: stats.proofs_submitted,
+            proofs_confirmed: stats.proofs_confirmed,
+            proofs_failed: stats.proofs_failed,
+            proofs_expired: stats.proofs_expired,
+            consensus_reached: stats.consensus_reached,
+            consensus_overridden: stats.consensus_overridden,
+            verifications_by_type: stats.verifications_by_type,
+            latency_samples: stats.latency_samples,
+            latency_sum_secs: stats.latency_sum_secs,
+            latency_buckets: stats.latency_buckets,
+            latency_implausible: stats.latency_implausible,
+        });
+        
+        msg!("📊 Daily stats for day {} closed", day);
+        Ok(())
+    }
     
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + MonitorConfig::INIT_SPACE,
-        seeds = [b"monitor_config", validator.key().as_ref()],
-        bump
-    )]
-    pub monitor_config: Account<'info, MonitorConfig>,
+    // ========================================================================
+    // PUBLIC CONSENSUS MIRRORS
+    // ========================================================================
     
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// Permissionless crank: publish an organically reached consensus as a
+    /// ConsensusMirror, for programs that only need "has this operation
+    /// reached consensus" without our getters or SDK. The caller pays rent.
+    pub fn create_consensus_mirror(ctx: Context<CreateConsensusMirror>, operation_id: [u8; 32]) -> Result<()> {
+        let consensus = &ctx.accounts.consensus_state;
+        require_ctx!(
+            consensus.finalized && !consensus.overridden,
+            TrinityError::ConsensusNotReached,
+            consensus = consensus.key(),
+            overridden = consensus.overridden,
+        );
+        
+        let mirror = &mut ctx.accounts.consensus_mirror;
+        mirror.schema_version = CONSENSUS_MIRROR_SCHEMA_VERSION;
+        mirror.operation_id = operation_id;
+        mirror.merkle_root = consensus.merkle_root;
+        mirror.consensus_at = consensus.finalized_at;
+        mirror.chain_bitmap = chain_bitmap(&consensus.chains);
+        
+        msg!("🪞 Consensus mirrored for operation: {:?}", operation_id);
+        Ok(())
+    }
     
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-pub struct RecordMonitoringCheck<'info> {
-    #[account(seeds = [b"trinity_validator"], bump = validator.bump)]
-    pub validator: Account<'info, TrinityValidator>,
+    /// Permissionless: close a mirror once MIRROR_RETENTION_SECS have passed
+    /// since consensus. The closer collects the rent, and the final state is
+    /// emitted so indexers keep it.
+    pub fn close_consensus_mirror(ctx: Context<CloseConsensusMirror>, operation_id: [u8; 32]) -> Result<()> {
+        let mirror = &ctx.accounts.consensus_mirror;
+        let now = Clock::get()?.unix_timestamp;
+        let closable_at = mirror.consensus_at.saturating_add(MIRROR_RETENTION_SECS);
+        require_ctx!(
+            now >= closable_at,
+            TrinityError::MirrorRetentionActive,
+            mirror = mirror.key(),
+            closable_at = closable_at,
+        );
+        
+        emit!(ConsensusMirrorClosed {
+            schema_version: mirror.schema_version,
+            operation_id,
+            merkle_root: mirror.merkle_root,
+            consensus_at: mirror.consensus_at,
+            chain_bitmap: mirror.chain_bitmap,
+            closed_by: ctx.accounts.closer.key(),
+        });
+        Ok(())
+    }
     
-    #[account(
-        mut,
-        seeds = [b"monitor_config", validator.key().as_ref()],
-        bump = monitor_config.bump
-    )]
-    pub monitor_config: Account<'info, MonitorConfig>,
+    // ========================================================================
+    // CONSENSUS REWARDS
+    // ========================================================================
     
-    #[account(mut)]
-    pub authority: Signer<'info>,
-}
-
-#[derive(Accounts)]
-#[instruction(vault_id: u64, operation_hash: [u8; 32])]
-pub struct FastVerifyOperation<'info> {
-    #[account(seeds = [b"trinity_validator"], bump = validator.bump)]
-    pub validator: Account<'info, TrinityValidator>,
+    /// Create or update the reward pool paid out on finalize_consensus. The
+    /// pool's mint is fixed by its first configuration; anyone may fund the
+    /// vault with a plain token transfer. A zero reward disables payouts.
+    pub fn configure_rewards(ctx: Context<ConfigureRewards>, reward_per_operation: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.mint = ctx.accounts.mint.key();
+        pool.vault = ctx.accounts.reward_vault.key();
+        pool.reward_per_operation = reward_per_operation;
+        pool.bump = *ctx.bumps.get("reward_pool").unwrap();
+        
+        msg!("🏅 Reward pool configured: {} per operation", reward_per_operation);
+        Ok(())
+    }
     
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + FastProof::INIT_SPACE,
-        seeds = [b"fast_proof", &vault_id.to_le_bytes(), operation_hash.as_ref()],
-        bump
-    )]
-    pub fast_proof: Account<'info, FastProof>,
+    /// Name the treasury that sweep_reward_surplus pays, and the reserve it
+    /// leaves in the reward vault
+    pub fn configure_reward_treasury(
+        ctx: Context<ConfigureRewardTreasury>,
+        treasury: Pubkey,
+        reserve: u64,
+    ) -> Result<()> {
+        require_ctx!(treasury != Pubkey::default(), TrinityError::InvalidRewardTreasury, treasury = treasury);
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.treasury = treasury;
+        pool.reserve = reserve;
+        
+        msg!("🏅 Reward surplus above {} goes to {}", reserve, treasury);
+        Ok(())
+    }
     
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// Move the reward vault's balance above its floor (see reward_surplus)
+    /// to a token account of the treasury, signed by the pool PDA
+    pub fn sweep_reward_surplus(ctx: Context<SweepRewardSurplus>) -> Result<()> {
+        let pool = &ctx.accounts.reward_pool;
+        let vault = &ctx.accounts.reward_vault;
+        let surplus = reward_surplus(vault.amount, pool.reserve, pool.reward_per_operation);
+        require_ctx!(
+            surplus > 0,
+            TrinityError::NoRewardSurplus,
+            balance = vault.amount,
+            reserve = pool.reserve,
+            reward_per_operation = pool.reward_per_operation,
+        );
+        
+        let signer: &[&[u8]] = &[b"reward_pool", &[pool.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: vault.to_account_info(),
+                    to: ctx.accounts.treasury_token_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                &[signer],
+            ),
+            surplus,
+        )?;
+        
+        emit!(RewardSurplusSwept {
+            treasury: pool.treasury,
+            amount: surplus,
+            remaining: vault.amount - surplus,
+        });
+        
+        msg!("🏅 Swept {} reward surplus to the treasury", surplus);
+        Ok(())
+    }
     
-    pub system_program: Program<'info, System>,
-}
-
-#[derive(Accounts)]
-pub struct GetMonitoringStats<'info> {
-    #[account(seeds = [b"trinity_validator"], bump)]
-    pub validator: Account<'info, TrinityValidator>,
+    // ========================================================================
+    // STAKE-WEIGHTED CONSENSUS
+    // ========================================================================
     
-    #[account(seeds = [b"monitor_config", validator.key().as_ref()], bump = monitor_config.bump)]
-    pub monitor_config: Account<'info, MonitorConfig>,
-}
-
-// ============================================================================
-// State Structures
-// ============================================================================
-
-#[account]
-#[derive(InitSpace)]
-pub struct TrinityValidator {
-    pub authority: Pubkey,                          // Validator authority
-    pub ethereum_bridge_address: [u8; 20],          // CrossChainBridgeOptimized address
-    pub validator_ethereum_address: [u8; 20],       // Validator's Ethereum address (for signing)
-    #[max_len(200)]
-    pub arbitrum_rpc_url: String,                   // Arbitrum RPC endpoint
-    pub total_proofs_submitted: u64,                // Total proofs generated
-    pub last_processed_operation: u64,              // Last operation ID processed
-    pub is_active: bool,                            // Validator active status
-    pub bump: u8,                                   // PDA bump
-}
-
-#[account]
-#[derive(InitSpace)]
-pub struct ProofRecord {
-    pub operation_id: [u8; 32],                     // Ethereum operation ID
-    pub merkle_root: [u8; 32],                      // Computed Merkle root
-    #[max_len(10)]
-    pub merkle_proof: Vec<[u8; 32]>,                // Merkle proof path
-    pub solana_block_hash: [u8; 32],                // Solana block hash
-    pub solana_tx_signature: [u8; 64],              // Solana transaction signature
-    pub solana_block_number: u64,                   // Solana slot number
-    pub timestamp: u64,                             // Proof generation timestamp
-    pub submitted_to_ethereum: bool,                // Ethereum submission status
-    pub ethereum_tx_hash: [u8; 32],                 // Ethereum transaction hash
-    pub validator: Pubkey,                          // Validator that generated proof
-}
-
-#[account]
-#[derive(InitSpace)]
-pub struct VaultVerification {
-    pub vault_id: u64,                              // Vault identifier from Ethereum
-    pub vault_owner: Pubkey,                        // Vault owner public key
-    pub operation_type: OperationType,              // Operation being verified
-    pub amount: u64,                                // Operation amount
-    pub user: Pubkey,                               // User initiating operation
-    pub verification_hash: [u8; 32],                // Verification hash (submitted to Ethereum)
-    pub timestamp: u64,                             // Verification timestamp
-    pub validator: Pubkey,                          // Validator that verified
-}
-
-// ============================================================================
-// HIGH-FREQUENCY MONITORING State Structures
-// ============================================================================
-
-#[account]
-#[derive(InitSpace)]
-pub struct MonitorConfig {
-    pub validator: Pubkey,                          // Associated validator
-    pub monitoring_interval_ms: u64,                // Monitoring check interval in ms
-    pub max_latency_ms: u64,                        // Maximum acceptable proof latency
-    pub last_check_timestamp: u64,                  // Unix timestamp of last check
-    pub last_check_slot: u64,                       // Solana slot of last check
-    pub total_checks: u64,                          // Total monitoring checks performed
-    pub successful_proofs: u64,                     // Number of successful proofs
-    pub failed_proofs: u64,                         // Number of failed proofs
-    pub average_latency_ms: u64,                    // Rolling average latency
-    pub is_active: bool,                            // Monitoring active status
-    pub bump: u8,                                   // PDA bump
-}
-
-#[account]
-#[derive(InitSpace)]
+    /// Set up operator staking in `mint` (fixed once configured) and the
+    /// stake finalize_consensus requires on top of the chain quorum: the
+    /// operators attesting to an operation must together have bonded more
+    /// than `consensus_weight_threshold`, so that any number of operators
+    /// with negligible stake can't finalize. 0 turns the requirement off.
+    pub fn configure_stake(ctx: Context<ConfigureStake>, consensus_weight_threshold: u64) -> Result<()> {
+        let config = &mut ctx.accounts.stake_config;
+        config.mint = ctx.accounts.mint.key();
+        config.vault = ctx.accounts.stake_vault.key();
+        config.consensus_weight_threshold = consensus_weight_threshold;
+        config.bump = *ctx.bumps.get("stake_config").unwrap();
+        
+        msg!("⚖️ Consensus needs attested stake above {}", consensus_weight_threshold);
+        Ok(())
+    }
+    
+    /// Bond `amount` of the operator's stake tokens into the stake vault
+    pub fn bond_stake(ctx: Context<BondStake>, amount: u64) -> Result<()> {
+        require_ctx!(amount > 0, TrinityError::InsufficientStake, amount = amount);
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.operator_token_account.to_account_info(),
+                    to: ctx.accounts.stake_vault.to_account_info(),
+                    authority: ctx.accounts.operator.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        
+        let stake = &mut ctx.accounts.operator_stake;
+        stake.operator = ctx.accounts.operator.key();
+        stake.amount = stake.amount.saturating_add(amount);
+        stake.bump = *ctx.bumps.get("operator_stake").unwrap();
+        
+        emit!(StakeBonded {
+            operator: stake.operator,
+            amount,
+            bonded: stake.amount,
+        });
+        
+        msg!("⚖️ {} bonded {} (total {})", stake.operator, amount, stake.amount);
+        Ok(())
+    }
+    
+    /// Withdraw `amount` of bonded stake to a token account of the operator,
+    /// once STAKE_UNBONDING_SECS have passed since its latest attestation
+    pub fn unbond_stake(ctx: Context<UnbondStake>, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let stake = &mut ctx.accounts.operator_stake;
+        require_ctx!(
+            now >= stake.locked_until,
+            TrinityError::StakeLocked,
+            locked_until = stake.locked_until,
+            now = now,
+        );
+        require_ctx!(
+            amount > 0 && amount <= stake.amount,
+            TrinityError::InsufficientStake,
+            amount = amount,
+            bonded = stake.amount,
+        );
+        stake.amount -= amount;
+        
+        let config = &ctx.accounts.stake_config;
+        let signer: &[&[u8]] = &[b"stake_config", &[config.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.stake_vault.to_account_info(),
+                    to: ctx.accounts.operator_token_account.to_account_info(),
+                    authority: config.to_account_info(),
+                },
+                &[signer],
+            ),
+            amount,
+        )?;
+        
+        emit!(StakeUnbonded {
+            operator: stake.operator,
+            amount,
+            bonded: stake.amount,
+        });
+        
+        msg!("⚖️ {} unbonded {} (total {})", stake.operator, amount, stake.amount);
+        Ok(())
+    }
+    
+    /// Attest to an operation with the operator's bonded stake, adding it to
+    /// the operation's stake weight. Each operator attests once per
+    /// operation, and its stake stays bonded for STAKE_UNBONDING_SECS. Once
+    /// the contributor list is full, a larger stake displaces the smallest.
+    pub fn attest_operation(ctx: Context<AttestOperation>, operation_id: [u8; 32]) -> Result<()> {
+        let consensus = &mut ctx.accounts.consensus_state;
+        require_ctx!(
+            !consensus.finalized,
+            TrinityError::ConsensusAlreadyFinalized,
+            consensus = consensus.key(),
+            finalized_at = consensus.finalized_at,
+        );
+        require_ctx!(
+            consensus.failed_at == 0,
+            TrinityError::OperationFailed,
+            consensus = consensus.key(),
+            failed_at = consensus.failed_at,
+        );
+        require_ctx!(!consensus.operation_paused, TrinityError::OperationPaused, consensus = consensus.key());
+        require_ctx!(
+            !ctx.accounts.proof_record.expired,
+            TrinityError::ProofExpired,
+            proof = ctx.accounts.proof_record.key(),
+        );
+        
+        let now = Clock::get()?.unix_timestamp;
+        let stake = &mut ctx.accounts.operator_stake;
+        let displaced = record_contribution(consensus, stake.operator, stake.amount)?;
+        stake.locked_until = stake.locked_until.max(now.saturating_add(STAKE_UNBONDING_SECS));
+        consensus.operation_id = operation_id;
+        consensus.bump = *ctx.bumps.get("consensus_state").unwrap();
+        consensus.layout_version = ConsensusState::LAYOUT_VERSION;
+        
+        emit!(OperationAttested {
+            operation_id,
+            operator: stake.operator,
+            stake: stake.amount,
+            stake_weight: consensus.stake_weight,
+            contributors: consensus.contributors.len() as u8,
+            displaced: displaced.map(|contribution| contribution.operator),
+        });
+        
+        msg!("⚖️ Operation {:?} attested: stake weight {}", operation_id, consensus.stake_weight);
+        Ok(())
+    }
+    
+    // ========================================================================
+    // ACCOUNT MIGRATIONS
+    // ========================================================================
+    
+    /// Permissionless: bring a versioned account (validator, proof, operator
+    /// metrics, consensus, check config) to the current layout through the
+    /// migrations registry, growing it as needed at the payer's expense. The
+    /// steps are pure functions of the stored data, so anyone may run them.
+    pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+        let account = ctx.accounts.account.to_account_info();
+        let upgrade = migrations::upgrade(&account.try_borrow_data()?)?;
+        
+        let rent = Rent::get()?.minimum_balance(upgrade.data.len()).saturating_sub(account.lamports());
+        if rent > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: account.clone(),
+                    },
+                ),
+                rent,
+            )?;
+        }
+        if upgrade.data.len() != account.data_len() {
+            account.realloc(upgrade.data.len(), false)?;
+        }
+        account.try_borrow_mut_data()?.copy_from_slice(&upgrade.data);
+        
+        for (from, to) in upgrade.steps {
+            emit!(AccountMigrated {
+                account: account.key(),
+                kind: upgrade.kind,
+                from,
+                to,
+            });
+        }
+        
+        msg!("🧬 {:?} account {} migrated to layout {}", upgrade.kind, account.key(), upgrade.kind.current_version());
+        Ok(())
+    }
+    
+    // ========================================================================
+    // OPERATOR FEE VAULTS (sponsored proof rent)
+    // ========================================================================
+    
+    /// Create or update an operator's fee vault and its daily sponsorship cap
+    pub fn configure_fee_vault(
+        ctx: Context<ConfigureFeeVault>,
+        operator: Pubkey,
+        daily_cap: u32,
+    ) -> Result<()> {
+        let vault = &mut ctx.accounts.fee_vault;
+        vault.operator = operator;
+        vault.daily_cap = daily_cap;
+        vault.bump = *ctx.bumps.get("fee_vault").unwrap();
+        
+        msg!("⛽ Fee vault configured for operator {}", operator);
+        msg!("   Daily cap: {} proofs", daily_cap);
+        Ok(())
+    }
+    
+    /// Top up an operator's fee vault (anyone may fund it)
+    pub fn operator_prefund(ctx: Context<OperatorPrefund>, amount: u64) -> Result<()> {
+        require_ctx!(amount > 0, TrinityError::InvalidPrefundAmount);
+        
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.funder.to_account_info(),
+                    to: ctx.accounts.fee_vault.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        
+        let vault = &ctx.accounts.fee_vault;
+        emit!(FeeVaultFunded {
+            operator: vault.operator,
+            funder: ctx.accounts.funder.key(),
+            amount,
+            vault_balance: fee_vault_spendable(&vault.to_account_info())?,
+        });
+        
+        msg!("⛽ Fee vault for {} funded with {} lamports", vault.operator, amount);
+        Ok(())
+    }
+    
+    /// Operator withdraws unused prefund; the vault's own rent reserve stays
+    pub fn reclaim_prefund(ctx: Context<ReclaimPrefund>, amount: u64) -> Result<()> {
+        let vault_info = ctx.accounts.fee_vault.to_account_info();
+        let spendable = fee_vault_spendable(&vault_info)?;
+        
+        require_ctx!(amount > 0, TrinityError::InvalidPrefundAmount);
+        require_ctx!(
+            amount <= spendable,
+            TrinityError::FeeVaultInsufficient,
+            requested = amount,
+            spendable = spendable,
+        );
+        
+        **vault_info.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.recipient.try_borrow_mut_lamports()? += amount;
+        
+        emit!(FeeVaultReclaimed {
+            operator: ctx.accounts.operator.key(),
+            recipient: ctx.accounts.recipient.key(),
+            amount,
+            vault_balance: spendable - amount,
+        });
+        
+        msg!("⛽ {} lamports reclaimed from fee vault", amount);
+        Ok(())
+    }
+    
+    /// submit_consensus_proof with the ProofRecord rent paid by the operator's
+    /// fee vault, so the operator's hot key never needs to hold SOL.
+    /// At most `daily_cap` proofs are sponsored per operator per day.
+    #[allow(clippy::too_many_arguments)]
+    pub fn submit_with_sponsored_rent(
+        ctx: Context<SubmitSponsoredProof>,
+        operation_id: [u8; 32],
+        merkle_proof: Vec<[u8; 32]>,
+        solana_block_hash: [u8; 32],
+        solana_tx_signature: [u8; 64],
+        solana_block_number: u64,
+        operation_type: OperationType,
+        amount: u64,
+        htlc: Option<HtlcPreimage>,
+        eth_event_timestamp: i64,
+        merkle_mode: MerkleMode,
+        sibling_is_left: Vec<bool>,
+    ) -> Result<()> {
+        require_operation_unpaused(&ctx.accounts.consensus_state)?;
+        observe_halt(&ctx.accounts.halt_monitor, true)?;
+        // The operator is authorized by the fee vault the validator authority set up
+        let record = build_proof_record(
+            &ctx.accounts.validator,
+            &load_check_config(&ctx.accounts.check_config)?,
+            &ctx.accounts.slot_hashes,
+            &ctx.accounts.instructions,
+            ctx.accounts.proof_record.key(),
+            ctx.accounts.operator.key(),
+            true,
+            ProofInputs {
+                operation_id,
+                merkle_proof,
+                solana_block_hash,
+                solana_tx_signature,
+                solana_block_number,
+                operation_type,
+                amount,
+                htlc,
+                eth_event_timestamp,
+                merkle_mode,
+                sibling_is_left,
+            },
+        )?;
+        
+        let vault = &mut ctx.accounts.fee_vault;
+        let today = current_day();
+        if vault.day != today {
+            vault.day = today;
+            vault.sponsored_today = 0;
+        }
+        require_ctx!(
+            vault.sponsored_today < vault.daily_cap,
+            TrinityError::SponsorCapReached,
+            operator = vault.operator,
+            daily_cap = vault.daily_cap,
+        );
+        
+        let vault_info = vault.to_account_info();
+        let target = ctx.accounts.proof_record.to_account_info();
+        let rent = create_proof_account(
+            &target,
+            &ctx.accounts.system_program,
+            &record,
+            *ctx.bumps.get("proof_record").unwrap(),
+            |rent| {
+                let spendable = fee_vault_spendable(&vault_info)?;
+                require_ctx!(
+                    rent <= spendable,
+                    TrinityError::FeeVaultInsufficient,
+                    rent = rent,
+                    spendable = spendable,
+                );
+                **vault_info.try_borrow_mut_lamports()? -= rent;
+                **target.try_borrow_mut_lamports()? += rent;
+                Ok(())
+            },
+        )?;
+        consume_expected_operation(&ctx.accounts.expected_operation, &operation_id)?;
+        
+        vault.sponsored_today += 1;
+        vault.total_sponsored = vault.total_sponsored.saturating_add(1);
+        vault.total_rent_sponsored = vault.total_rent_sponsored.saturating_add(rent);
+        
+        emit!(ProofRentSponsored {
+            operator: vault.operator,
+            operation_id,
+            rent,
+            sponsored_today: vault.sponsored_today,
+            vault_balance: fee_vault_spendable(&vault.to_account_info())?,
+        });
+        
+        finish_proof_submission(
+            &record,
+            ctx.accounts.seen_operations.as_deref_mut(),
+            &mut ctx.accounts.operator_metrics,
+            ctx.accounts.operator.key(),
+            *ctx.bumps.get("operator_metrics").unwrap(),
+            &mut ctx.accounts.proof_sequence,
+            *ctx.bumps.get("proof_sequence").unwrap(),
+            &mut ctx.accounts.daily_stats,
+            *ctx.bumps.get("daily_stats").unwrap(),
+        )?;
+        
+        let operator = ctx.accounts.operator.key();
+        observe_risk(&ctx.accounts.risk_metrics, operation_id, operator, |metrics| RiskObservation {
+            amount: Some(record.amount),
+            operator_share_bps: metrics.record_submission(operator),
+            consensus_secs: None,
+        })?;
+        
+        Ok(())
+    }
+    
+    /// Operator liveness (return data): how many more proofs the fee vault can
+    /// sponsor, so monitoring can warn before an operator runs dry
+    pub fn get_operator_liveness(ctx: Context<GetOperatorLiveness>) -> Result<OperatorLiveness> {
+        let vault = &ctx.accounts.fee_vault;
+        let vault_balance = fee_vault_spendable(&vault.to_account_info())?;
+        let proof_rent = Rent::get()?.minimum_balance(8 + ProofRecord::INIT_SPACE);
+        let sponsored_today = if vault.day == current_day() { vault.sponsored_today } else { 0 };
+        
+        Ok(OperatorLiveness {
+            operator: vault.operator,
+            vault_balance,
+            proof_rent,
+            sponsorable_proofs: vault_balance / proof_rent,
+            daily_cap: vault.daily_cap,
+            remaining_today: vault.daily_cap.saturating_sub(sponsored_today),
+            total_sponsored: vault.total_sponsored,
+        })
+    }
+    
+    // ========================================================================
+    // OPERATOR SCORING
+    // ========================================================================
+    
+    /// Show the operator is online between submissions (each submission
+    /// counts as a heartbeat too)
+    pub fn heartbeat(ctx: Context<Heartbeat>) -> Result<()> {
+        let metrics = &mut ctx.accounts.operator_metrics;
+        metrics.operator = ctx.accounts.operator.key();
+        metrics.bump = *ctx.bumps.get("operator_metrics").unwrap();
+        metrics.layout_version = OperatorMetrics::LAYOUT_VERSION;
+        record_heartbeat(metrics, Clock::get()?.unix_timestamp);
+        observe_halt(&ctx.accounts.halt_monitor, false)?;
+        
+        Ok(())
+    }
+    
+    /// Permissionless crank: fold each operator's score inputs since its last
+    /// update into its decaying score, at most once per SCORE_EPOCH_SECS.
+    /// OperatorMetrics PDAs are supplied via remaining_accounts; foreign,
+    /// malformed or not-yet-due ones are skipped.
+    pub fn update_scores<'info>(ctx: Context<'_, '_, '_, 'info, UpdateScores<'info>>) -> Result<()> {
+        require_ctx!(
+            ctx.remaining_accounts.len() <= MAX_SCORE_BATCH,
+            TrinityError::ScoreBatchTooLarge,
+            accounts = ctx.remaining_accounts.len(),
+            max = MAX_SCORE_BATCH,
+        );
+        
+        let now = Clock::get()?.unix_timestamp;
+        for info in ctx.remaining_accounts.iter() {
+            if !info.is_writable {
+                continue;
+            }
+            let mut metrics = match load_operator_metrics(info, ctx.program_id) {
+                Some(metrics) => metrics,
+                None => continue,
+            };
+            let first = metrics.scored_at == 0;
+            let epochs = if first { 1 } else { (now - metrics.scored_at) / SCORE_EPOCH_SECS };
+            if epochs <= 0 {
+                continue;
+            }
+            
+            accrue_missed_heartbeats(&mut metrics, now);
+            let period = period_score(
+                metrics.proofs_submitted - metrics.scored_proofs,
+                &metrics.score_inputs.since(&metrics.scored_inputs),
+            );
+            metrics.score = if first { period } else { decayed_score(metrics.score, period, epochs as u64) };
+            metrics.scored_proofs = metrics.proofs_submitted;
+            metrics.scored_inputs = metrics.score_inputs;
+            metrics.scored_at = now;
+            metrics.exit(ctx.program_id)?;
+            
+            emit!(OperatorScored {
+                operator: metrics.operator,
+                period_score: period,
+                score: metrics.score,
+                epochs: epochs as u64,
+            });
+        }
+        
+        Ok(())
+    }
+    
+    /// Operators ranked by score, best first (return data); ties go to the
+    /// lower key. OperatorMetrics PDAs are supplied via remaining_accounts;
+    /// foreign or malformed ones are left out.
+    pub fn get_operator_ranking<'info>(
+        ctx: Context<'_, '_, '_, 'info, GetOperatorRanking<'info>>,
+    ) -> Result<Vec<OperatorScore>> {
+        require_ctx!(
+            ctx.remaining_accounts.len() <= MAX_SCORE_BATCH,
+            TrinityError::ScoreBatchTooLarge,
+            accounts = ctx.remaining_accounts.len(),
+            max = MAX_SCORE_BATCH,
+        );
+        
+        let mut ranking: Vec<OperatorScore> = ctx.remaining_accounts.iter()
+            .filter_map(|info| load_operator_metrics(info, ctx.program_id))
+            .map(|metrics| OperatorScore {
+                operator: metrics.operator,
+                score: metrics.score,
+                scored_at: metrics.scored_at,
+            })
+            .collect();
+        ranking.sort_by(|a, b| b.score.cmp(&a.score).then(a.operator.cmp(&b.operator)));
+        ranking.dedup_by_key(|entry| entry.operator);
+        
+        Ok(ranking)
+    }
+    
+    // ========================================================================
+    // BUILD ATTESTATION
+    // ========================================================================
+    
+    /// Name the upgrade-guard program allowed to attest builds (through its
+    /// `build_attester` PDA) and how many slots an upgrade may go unattested
+    /// before check_build_attestation warns
+    pub fn configure_build_attestation(
+        ctx: Context<ConfigureBuildAttestation>,
+        upgrade_guard: Pubkey,
+        attestation_window_slots: u64,
+    ) -> Result<()> {
+        let attestation = &mut ctx.accounts.build_attestation;
+        attestation.upgrade_guard = upgrade_guard;
+        attestation.attestation_window_slots = attestation_window_slots;
+        attestation.bump = *ctx.bumps.get("build_attestation").unwrap();
+        
+        msg!("🔏 Build attestation by {} within {} slots", upgrade_guard, attestation_window_slots);
+        Ok(())
+    }
+    
+    /// Upgrade guard only (via CPI, signed by its `build_attester` PDA), after
+    /// each upgrade: record the solana-verify hash of the deployed build with
+    /// the source commit this build embeds
+    pub fn attest_build(ctx: Context<AttestBuild>, build_hash: [u8; 32]) -> Result<()> {
+        require_ctx!(build_hash != [0; 32], TrinityError::InvalidBuildHash);
+        let clock = Clock::get()?;
+        let attestation = &mut ctx.accounts.build_attestation;
+        attestation.build_hash = build_hash;
+        attestation.source_commit = source_commit(GIT_COMMIT);
+        attestation.upgrade_slot = ctx.accounts.program_data.slot;
+        attestation.attested_slot = clock.slot;
+        attestation.attested_at = clock.unix_timestamp;
+        attestation.attestations = attestation.attestations.saturating_add(1);
+        
+        emit!(BuildAttested {
+            build_hash,
+            source_commit: attestation.source_commit,
+            upgrade_slot: attestation.upgrade_slot,
+            slot: clock.slot,
+        });
+        
+        msg!("🔏 Build attested for the upgrade at slot {}", attestation.upgrade_slot);
+        Ok(())
+    }
+    
+    /// Permissionless crank: emit UnattestedBuild, once per upgrade, when the
+    /// deployed program has gone attestation_window_slots without attestation
+    pub fn check_build_attestation(ctx: Context<CheckBuildAttestation>) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let upgrade_slot = ctx.accounts.program_data.slot;
+        let attestation = &mut ctx.accounts.build_attestation;
+        let slots_unattested = match unattested_slots(attestation, upgrade_slot, slot) {
+            Some(slots) => slots,
+            None => return Ok(()),
+        };
+        attestation.warned_upgrade_slot = upgrade_slot;
+        
+        emit!(UnattestedBuild {
+            upgrade_slot,
+            attested_upgrade_slot: attestation.upgrade_slot,
+            slots_unattested,
+        });
+        
+        msg!("⚠️ Upgrade at slot {} unattested for {} slots", upgrade_slot, slots_unattested);
+        Ok(())
+    }
+    
+    /// The latest build attestation (return data)
+    pub fn get_build_attestation(ctx: Context<GetBuildAttestation>) -> Result<BuildAttestation> {
+        Ok(BuildAttestation::clone(&ctx.accounts.build_attestation))
+    }
+    
+    /// Version and source commit of the running build (return data)
+    pub fn get_program_info(_ctx: Context<GetProgramInfo>) -> Result<ProgramInfo> {
+        Ok(ProgramInfo {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_commit: GIT_COMMIT.to_string(),
+        })
+    }
+    
+    // ========================================================================
+    // RISK FLAGS
+    // ========================================================================
+    
+    /// Create the RiskMetrics with every threshold off
+    pub fn initialize_risk_metrics(ctx: Context<InitializeRiskMetrics>) -> Result<()> {
+        let metrics = &mut ctx.accounts.risk_metrics;
+        metrics.thresholds = RiskThresholds::NONE;
+        metrics.queued = QueuedRiskThresholds::NONE;
+        metrics.bump = *ctx.bumps.get("risk_metrics").unwrap();
+        
+        msg!("🚩 Risk metrics initialized (no thresholds)");
+        Ok(())
+    }
+    
+    /// Queue new risk thresholds; they apply after ENFORCEMENT_TIMELOCK_SECS.
+    /// Re-queueing replaces the pending change.
+    pub fn queue_risk_thresholds(ctx: Context<UpdateRiskMetrics>, thresholds: RiskThresholds) -> Result<()> {
+        let executable_at = Clock::get()?.unix_timestamp + ENFORCEMENT_TIMELOCK_SECS;
+        ctx.accounts.risk_metrics.queued = QueuedRiskThresholds { thresholds, executable_at };
+        
+        emit!(RiskThresholdsQueued { thresholds, executable_at });
+        
+        msg!("🚩 Risk thresholds {:?} queued, executable at {}", thresholds, executable_at);
+        Ok(())
+    }
+    
+    /// Apply queued risk thresholds once their timelock has elapsed (permissionless)
+    pub fn apply_risk_thresholds(ctx: Context<ApplyRiskThresholds>) -> Result<()> {
+        let metrics = &mut ctx.accounts.risk_metrics;
+        let queued = metrics.queued;
+        require_queued_change_ready(queued.executable_at)?;
+        
+        metrics.thresholds = queued.thresholds;
+        metrics.queued = QueuedRiskThresholds::NONE;
+        
+        emit!(RiskThresholdsApplied { thresholds: queued.thresholds });
+        
+        msg!("🚩 Risk thresholds are now {:?}", queued.thresholds);
+        Ok(())
+    }
+    
+    /// Clear a latched risk flag once it has been looked into, so the next
+    /// crossing of its threshold raises it again
+    pub fn acknowledge_risk_flag(ctx: Context<UpdateRiskMetrics>, flag: RiskFlag) -> Result<()> {
+        let metrics = &mut ctx.accounts.risk_metrics;
+        require_ctx!(metrics.is_latched(flag), TrinityError::RiskFlagNotRaised, flag = flag as u8);
+        let raised_at = metrics.raised_at[flag as usize];
+        metrics.latched &= !flag.bit();
+        metrics.raised_at[flag as usize] = 0;
+        
+        emit!(RiskFlagAcknowledged {
+            flag,
+            raised_at,
+            authority: ctx.accounts.authority.key(),
+        });
+        
+        msg!("🚩 {:?} acknowledged (raised at {})", flag, raised_at);
+        Ok(())
+    }
+    
+    // ========================================================================
+    // CHAIN HALT
+    // ========================================================================
+    
+    /// Set the halt policy, creating the HaltMonitor on first use; the first
+    /// configuration starts the heartbeat clock at the current slot
+    pub fn configure_halt_policy(ctx: Context<ConfigureHaltPolicy>, policy: HaltPolicy) -> Result<()> {
+        require_ctx!(
+            policy.cooldown_secs >= 0,
+            TrinityError::InvalidHaltPolicy,
+            cooldown_secs = policy.cooldown_secs,
+        );
+        let monitor = &mut ctx.accounts.halt_monitor;
+        monitor.policy = policy;
+        if monitor.last_heartbeat_slot == 0 {
+            monitor.last_heartbeat_slot = Clock::get()?.slot;
+        }
+        monitor.bump = *ctx.bumps.get("halt_monitor").unwrap();
+        
+        msg!("⛓️ Halt policy is now {:?}", policy);
+        Ok(())
+    }
+    
+    /// Once a halt is detected (a gap over max_slot_gap since the last
+    /// heartbeat, whether or not a heartbeat has reported it yet), record its
+    /// window in a HaltRecord and let submissions resume. Proofs generated in
+    /// the following cooldown_secs need extra_eth_confirmations Ethereum
+    /// blocks before their confirmation is accepted.
+    pub fn resume_after_halt(ctx: Context<ResumeAfterHalt>) -> Result<()> {
+        let clock = Clock::get()?;
+        let monitor = &mut ctx.accounts.halt_monitor;
+        monitor.observe_slot(clock.slot);
+        require_ctx!(
+            monitor.halted_since.is_some(),
+            TrinityError::NoHaltDetected,
+            last_heartbeat_slot = monitor.last_heartbeat_slot,
+            slot = clock.slot,
+        );
+        let start_slot = monitor.halted_since.unwrap_or_default();
+        let cooldown_until = clock.unix_timestamp.saturating_add(monitor.policy.cooldown_secs);
+        let extra_eth_confirmations = monitor.policy.extra_eth_confirmations;
+        
+        let record = &mut ctx.accounts.halt_record;
+        record.index = monitor.halts;
+        record.start_slot = start_slot;
+        record.end_slot = clock.slot;
+        record.resumed_at = clock.unix_timestamp;
+        record.cooldown_until = cooldown_until;
+        record.extra_eth_confirmations = extra_eth_confirmations;
+        record.resumed_by = ctx.accounts.authority.key();
+        record.bump = *ctx.bumps.get("halt_record").unwrap();
+        
+        monitor.halted_since = None;
+        monitor.last_heartbeat_slot = clock.slot;
+        monitor.resumed_at = clock.unix_timestamp;
+        monitor.cooldown_until = cooldown_until;
+        monitor.extra_eth_confirmations = extra_eth_confirmations;
+        monitor.halts = monitor.halts.saturating_add(1);
+        
+        emit!(HaltResumed {
+            index: record.index,
+            start_slot,
+            end_slot: clock.slot,
+            cooldown_until,
+            extra_eth_confirmations,
+            authority: record.resumed_by,
+        });
+        
+        msg!("⛓️ Resumed after a halt over slots {}..{}", start_slot, clock.slot);
+        Ok(())
+    }
+    
+    // ========================================================================
+    // PROOF REISSUE
+    // ========================================================================
+    
+    /// Rewrite an unconfirmed proof against a newer Solana block after a
+    /// reorg dropped the one it referenced. The proof keeps its generation
+    /// timestamp, so a reissue never postpones its expiry, and an operation
+    /// can be reissued at most MAX_PROOF_REISSUES times: past that it can
+    /// only be confirmed as is or expire. The new path folds in the proof's
+    /// original Merkle mode.
+    pub fn reissue_proof(
+        ctx: Context<ReissueProof>,
+        operation_id: [u8; 32],
+        merkle_proof: Vec<[u8; 32]>,
+        solana_block_hash: [u8; 32],
+        solana_block_number: u64,
+        sibling_is_left: Vec<bool>,
+    ) -> Result<()> {
+        let validator = &ctx.accounts.validator;
+        let proof_record = &mut ctx.accounts.proof_record;
+        let clock = Clock::get()?;
+        
+        require_ctx!(
+            !proof_record.submitted_to_ethereum,
+            TrinityError::AlreadySubmitted,
+            proof = proof_record.key(),
+        );
+        require_ctx!(
+            !proof_record.expired && !proof_is_expirable(proof_record, validator, clock.unix_timestamp as u64),
+            TrinityError::ProofExpired,
+            proof = proof_record.key(),
+            timestamp = proof_record.timestamp,
+        );
+        let reissues = &mut ctx.accounts.proof_reissues;
+        require_ctx!(
+            reissues.reissues < MAX_PROOF_REISSUES,
+            TrinityError::ReissueLimitReached,
+            proof = proof_record.key(),
+            reissues = reissues.reissues,
+            max = MAX_PROOF_REISSUES,
+        );
+        require_ctx!(
+            merkle_proof.len() <= MAX_MERKLE_PROOF_LEN,
+            TrinityError::MerkleProofTooLong,
+            len = merkle_proof.len(),
+            max = MAX_MERKLE_PROOF_LEN,
+        );
+        reject(check_merkle_path(proof_record.merkle_mode, &merkle_proof, &sibling_is_left))?;
+        // Same block checks as a submission: recent, and the cluster's own hash
+        let recorded_hash = recent_slot_hash(&ctx.accounts.slot_hashes, solana_block_number)?;
+        require_ctx!(
+            recorded_hash == Some(solana_block_hash),
+            TrinityError::InvalidMerkleProof,
+            slot = solana_block_number,
+            recent = recorded_hash.is_some(),
+        );
+        let age = clock.slot.saturating_sub(solana_block_number);
+        require_ctx!(
+            age <= MAX_PROOF_SLOT_AGE,
+            TrinityError::StaleBlock,
+            slot = solana_block_number,
+            age = age,
+            max = MAX_PROOF_SLOT_AGE,
+        );
+        
+        let merkle_root = proof_record.merkle_mode.root(&merkle_proof, &sibling_is_left, &operation_id);
+        let previous_proof_id = proof_record.proof_id;
+        proof_record.proof_id = derive_proof_id(&operation_id, &merkle_root, &validator.key());
+        proof_record.merkle_root = merkle_root;
+        proof_record.merkle_proof = merkle_proof;
+        proof_record.sibling_is_left = sibling_is_left;
+        proof_record.solana_block_hash = solana_block_hash;
+        proof_record.solana_block_number = solana_block_number;
+        proof_record.solana_epoch = clock.epoch;
+        proof_record.leader_schedule_epoch = clock.leader_schedule_epoch;
+        proof_record.environment_hash = environment_hash(&clock, &EpochSchedule::get()?, &ctx.accounts.slot_hashes)?;
+        
+        reissues.operation_id = operation_id;
+        reissues.reissues += 1;
+        reissues.bump = *ctx.bumps.get("proof_reissues").unwrap();
+        
+        emit!(ProofReissued {
+            operation_id,
+            previous_proof_id,
+            proof_id: proof_record.proof_id,
+            solana_block_number,
+            reissues: reissues.reissues,
+        });
+        
+        msg!("♻️ Proof reissued at slot {} ({}/{})", solana_block_number, reissues.reissues, MAX_PROOF_REISSUES);
+        Ok(())
+    }
+    
+    /// Permissionless audit check: fails with InvariantViolated if any
+    /// protocol invariant (see `invariants`) is broken by the ProofRecord,
+    /// ConsensusState and OperatorMetrics accounts passed, in any mix, via
+    /// remaining_accounts. Anything else is rejected rather than skipped, so a
+    /// pass always covers every account given. Writes nothing.
+    /// The proof count is only bounded here; `audit` builds check it exactly,
+    /// which needs every operator's OperatorMetrics passed.
+    pub fn assert_invariants<'info>(ctx: Context<'_, '_, '_, 'info, AssertInvariants<'info>>) -> Result<()> {
+        let global_proof_seq = load_initialized::<ProofSequence>(&ctx.accounts.proof_sequence)?
+            .map_or(0, |sequence| sequence.global_proof_seq);
+        invariants::check_proof_count_bound(&ctx.accounts.validator, global_proof_seq)?;
+        
+        let mut seen: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+        let mut proofs: u32 = 0;
+        let mut consensus_states: u32 = 0;
+        let mut metrics: Vec<OperatorMetrics> = Vec::new();
+        
+        for info in ctx.remaining_accounts.iter() {
+            let data = info.try_borrow_data()?;
+            let kind = AccountKind::of(&data);
+            require_ctx!(
+                info.owner == ctx.program_id
+                    && !seen.contains(info.key)
+                    && matches!(
+                        kind,
+                        Some(AccountKind::ProofRecord | AccountKind::ConsensusState | AccountKind::OperatorMetrics)
+                    ),
+                TrinityError::InvalidInvariantAccount,
+                account = info.key(),
+            );
+            seen.push(info.key());
+            
+            match kind {
+                Some(AccountKind::ProofRecord) => {
+                    let record = ProofRecord::try_deserialize(&mut &data[..])?;
+                    require_ctx!(record.is_current(), TrinityError::AccountNeedsMigration, account = info.key());
+                    invariants::check_proof_record(info.key, &record)?;
+                    proofs += 1;
+                }
+                Some(AccountKind::ConsensusState) => {
+                    let consensus = ConsensusState::try_deserialize(&mut &data[..])?;
+                    require_ctx!(consensus.is_current(), TrinityError::AccountNeedsMigration, account = info.key());
+                    invariants::check_consensus(info.key, &consensus)?;
+                    consensus_states += 1;
+                }
+                _ => {
+                    let operator = OperatorMetrics::try_deserialize(&mut &data[..])?;
+                    require_ctx!(operator.is_current(), TrinityError::AccountNeedsMigration, account = info.key());
+                    invariants::check_operator_metrics(info.key, &operator)?;
+                    metrics.push(operator);
+                }
+            }
+        }
+        
+        #[cfg(feature = "audit")]
+        invariants::check_proof_count(&ctx.accounts.validator, global_proof_seq, &metrics)?;
+        
+        msg!(
+            "✅ Invariants hold: {} proofs, {} consensus states, {} operators, {} proofs numbered",
+            proofs,
+            consensus_states,
+            metrics.len(),
+            global_proof_seq
+        );
+        Ok(())
+    }
+    
+    // ========================================================================
+    // DISASTER EXPORT / IMPORT
+    // ========================================================================
+    
+    /// Start the one-shot disaster export ahead of a redeployment: the
+    /// authority and a dispute council member (the guardian) together
+    /// deactivate the validator, so no proof lands mid-export, and open the
+    /// export ledger. There is one export per validator.
+    pub fn begin_state_export(ctx: Context<BeginStateExport>) -> Result<()> {
+        let guardian = ctx.accounts.guardian.key();
+        require_ctx!(
+            ctx.accounts.council.members.contains(&guardian),
+            TrinityError::NotCouncilMember,
+            member = guardian,
+        );
+        let now = Clock::get()?.unix_timestamp;
+        let validator = &mut ctx.accounts.validator;
+        validator.is_active = false;
+        
+        let export = &mut ctx.accounts.state_export;
+        export.validator = validator.key();
+        export.guardian = guardian;
+        export.started_at = now;
+        export.cursors = [Pubkey::default(); EXPORT_KIND_COUNT];
+        export.exported = [0; EXPORT_KIND_COUNT];
+        export.digests = [[0; 32]; EXPORT_KIND_COUNT];
+        export.bump = *ctx.bumps.get("state_export").unwrap();
+        
+        emit!(StateExportStarted {
+            validator: validator.key(),
+            authority: validator.authority,
+            guardian,
+            started_at: now,
+        });
+        
+        msg!("📦 State export started; validator deactivated");
+        Ok(())
+    }
+    
+    /// Export one page of `kind` accounts, passed in remaining_accounts in
+    /// ascending address order after `cursor`, as StateExported events that
+    /// import_state replays on the new deployment. The authority and a
+    /// guardian sign every page. The ledger cursor moves to the page's last
+    /// account, marking everything up to it exported, so each account is
+    /// exported exactly once and in the order its digest chains.
+    pub fn export_state<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExportState<'info>>,
+        kind: ExportKind,
+        cursor: Pubkey,
+    ) -> Result<()> {
+        let guardian = ctx.accounts.guardian.key();
+        require_ctx!(
+            ctx.accounts.council.members.contains(&guardian),
+            TrinityError::NotCouncilMember,
+            member = guardian,
+        );
+        require_ctx!(
+            !ctx.accounts.validator.is_active,
+            TrinityError::ValidatorNotFrozen,
+            validator = ctx.accounts.validator.key(),
+        );
+        
+        let export = &mut ctx.accounts.state_export;
+        let slot = kind as usize;
+        let page: Vec<Pubkey> = ctx.remaining_accounts.iter().map(|info| info.key()).collect();
+        let next_cursor = state_export::check_page(&export.cursors[slot], &cursor, &page)?;
+        
+        for info in ctx.remaining_accounts.iter() {
+            require_ctx!(info.owner == ctx.program_id, TrinityError::InvalidExportAccount, account = info.key());
+            let data = info.try_borrow_data()?;
+            state_export::check_exportable(kind, info.key, &data)?;
+            
+            let index = export.exported[slot];
+            export.digests[slot] = state_export::chain(&export.digests[slot], info.key, &data);
+            export.exported[slot] += 1;
+            emit!(StateExported {
+                format_version: EXPORT_FORMAT_VERSION,
+                kind,
+                index,
+                source: info.key(),
+                data: data.to_vec(),
+            });
+        }
+        export.cursors[slot] = next_cursor;
+        
+        msg!("📦 Exported {} {:?} accounts ({} so far)", page.len(), kind, export.exported[slot]);
+        Ok(())
+    }
+    
+    /// Append `chunk` to the signer's import staging account, which holds one
+    /// exported payload at a time until import_state consumes it. Payloads
+    /// larger than a transaction (the ProofSequence) arrive over several calls.
+    pub fn stage_import(ctx: Context<StageImport>, chunk: Vec<u8>) -> Result<()> {
+        let staging = ctx.accounts.staging.to_account_info();
+        let payer = ctx.accounts.authority.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let len = staging.data_len() + chunk.len();
+        
+        let rent = Rent::get()?.minimum_balance(len).saturating_sub(staging.lamports());
+        if rent > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    anchor_lang::system_program::Transfer { from: payer, to: staging.clone() },
+                ),
+                rent,
+            )?;
+        }
+        if staging.owner == &System::id() {
+            let bump = [*ctx.bumps.get("staging").unwrap()];
+            let seeds: &[&[u8]] = &[b"import_staging", ctx.accounts.authority.key.as_ref(), &bump];
+            anchor_lang::system_program::allocate(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    anchor_lang::system_program::Allocate { account_to_allocate: staging.clone() },
+                    &[seeds],
+                ),
+                len as u64,
+            )?;
+            anchor_lang::system_program::assign(
+                CpiContext::new_with_signer(
+                    system_program,
+                    anchor_lang::system_program::Assign { account_to_assign: staging.clone() },
+                    &[seeds],
+                ),
+                &crate::ID,
+            )?;
+        } else {
+            staging.realloc(len, false)?;
+        }
+        
+        staging.try_borrow_mut_data()?[len - chunk.len()..].copy_from_slice(&chunk);
+        Ok(())
+    }
+    
+    /// Import the staged StateExported payload on a fresh deployment. The
+    /// validator goes first, signed by the authority it carries: like
+    /// initialize, the first import claims the deployment, so run it right
+    /// after deploying. Every later import must be signed by that same
+    /// authority, the old deployment's, while the imported validator is still
+    /// inactive; reactivating it (update_validator) ends the import. Each
+    /// account lands once, at its derived address on this deployment, with
+    /// the exported bytes (see state_export::place), and chains into the
+    /// import ledger's digest exactly as it did into the export's.
+    pub fn import_state(ctx: Context<ImportState>, kind: ExportKind, format_version: u8, source: Pubkey) -> Result<()> {
+        require_ctx!(
+            format_version == EXPORT_FORMAT_VERSION,
+            TrinityError::UnsupportedExportFormat,
+            format_version = format_version,
+            supported = EXPORT_FORMAT_VERSION,
+        );
+        let authority = ctx.accounts.authority.key();
+        let staging = ctx.accounts.staging.to_account_info();
+        let data = staging.try_borrow_data()?.to_vec();
+        let import = &mut ctx.accounts.state_import;
+        let slot = kind as usize;
+        
+        if kind == ExportKind::Validator {
+            let validator = TrinityValidator::try_deserialize(&mut &data[..])?;
+            require_ctx!(
+                import.imported[slot] == 0 && validator.authority == authority && !validator.is_active,
+                TrinityError::InvalidStateImport,
+                kind = format!("{:?}", kind),
+                signer = authority,
+            );
+            import.authority = authority;
+            import.source_validator = source;
+            import.bump = *ctx.bumps.get("state_import").unwrap();
+        } else {
+            let validator = load_initialized::<TrinityValidator>(&ctx.accounts.validator)?;
+            require_ctx!(
+                import.imported[ExportKind::Validator as usize] == 1
+                    && import.authority == authority
+                    && validator.is_some_and(|validator| !validator.is_active),
+                TrinityError::InvalidStateImport,
+                kind = format!("{:?}", kind),
+                signer = authority,
+            );
+        }
+        
+        let placement = state_export::place(kind, &data, ctx.program_id)?;
+        let target = ctx.accounts.target.to_account_info();
+        require_ctx!(
+            target.key() == placement.address && target.owner == &System::id() && target.data_is_empty(),
+            TrinityError::InvalidStateImport,
+            target = target.key(),
+            expected = placement.address,
+        );
+        
+        let payer = ctx.accounts.authority.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let rent = Rent::get()?.minimum_balance(data.len()).saturating_sub(target.lamports());
+        if rent > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    anchor_lang::system_program::Transfer { from: payer.clone(), to: target.clone() },
+                ),
+                rent,
+            )?;
+        }
+        let bump = [placement.bump];
+        let mut seeds: Vec<&[u8]> = placement.seeds.iter().map(Vec::as_slice).collect();
+        seeds.push(&bump);
+        anchor_lang::system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                anchor_lang::system_program::Allocate { account_to_allocate: target.clone() },
+                &[&seeds],
+            ),
+            data.len() as u64,
+        )?;
+        anchor_lang::system_program::assign(
+            CpiContext::new_with_signer(
+                system_program,
+                anchor_lang::system_program::Assign { account_to_assign: target.clone() },
+                &[&seeds],
+            ),
+            &crate::ID,
+        )?;
+        target.try_borrow_mut_data()?.copy_from_slice(&placement.data);
+        
+        // Empty the staging account for the next payload, refunding its rent
+        staging.realloc(0, false)?;
+        let refund = staging.lamports().saturating_sub(Rent::get()?.minimum_balance(0));
+        **staging.try_borrow_mut_lamports()? -= refund;
+        **payer.try_borrow_mut_lamports()? += refund;
+        
+        let index = import.imported[slot];
+        import.digests[slot] = state_export::chain(&import.digests[slot], &source, &data);
+        import.imported[slot] += 1;
+        emit!(StateImported {
+            kind,
+            index,
+            source,
+            account: target.key(),
+            digest: import.digests[slot],
+        });
+        
+        msg!("📥 Imported {:?} {} as {}", kind, source, target.key());
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Account Structures
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TrinityValidator::INIT_SPACE,
+        seeds = [b"trinity_validator"],
+        bump
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct SubmitProof<'info> {
+    /// Read-only, so concurrent submissions don't contend on it (see rollup_metrics)
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    /// Optional replay filter, checked before the proof record is created
+    #[account(
+        mut,
+        seeds = [b"seen_operations", validator.key().as_ref()],
+        bump = seen_operations.bump,
+        constraint = !seen_operations.contains(&operation_id) @ TrinityError::OperationAlreadySeen
+    )]
+    pub seen_operations: Option<Account<'info, SeenOperations>>,
+    
+    /// CHECK: ExpectedOperation PDA, loaded by hand so that an operation the
+    /// bridge never committed fails with OperationNotFound
+    #[account(mut, seeds = [b"expected_operation", operation_id.as_ref()], bump)]
+    pub expected_operation: UncheckedAccount<'info>,
+    
+    /// CHECK: Uncreated ProofRecord PDA, created in the handler (after the seen-operations check)
+    #[account(mut, seeds = [b"proof", operation_id.as_ref()], bump)]
+    pub proof_record: UncheckedAccount<'info>,
+    
+    /// CHECK: ConsensusState PDA, read only if created, for set_operation_pause
+    #[account(seeds = [b"consensus", operation_id.as_ref()], bump)]
+    pub consensus_state: UncheckedAccount<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + DailyStats::INIT_SPACE,
+        seeds = [b"stats", current_day().to_le_bytes().as_ref()],
+        bump
+    )]
+    pub daily_stats: Account<'info, DailyStats>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + OperatorMetrics::INIT_SPACE,
+        seeds = [b"operator_metrics", authority.key().as_ref()],
+        bump
+    )]
+    pub operator_metrics: Account<'info, OperatorMetrics>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ProofSequence::INIT_SPACE,
+        seeds = [b"proof_sequence", validator.key().as_ref()],
+        bump
+    )]
+    pub proof_sequence: Account<'info, ProofSequence>,
+    
+    /// CHECK: SlotHashes sysvar, read manually (too large to deserialize on-chain)
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+    
+    /// CHECK: Instructions sysvar, read for the transaction's proof budget
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    
+    /// CHECK: CheckConfig PDA, always passed so shadow rules and amount limits
+    /// can't be skipped; every rule is in Monitor until it is initialized
+    #[account(seeds = [b"check_config", validator.key().as_ref()], bump)]
+    pub check_config: UncheckedAccount<'info>,
+    
+    /// CHECK: RiskMetrics PDA, always passed so risk flags can't be skipped;
+    /// nothing is evaluated until it is initialized
+    #[account(mut, seeds = [b"risk_metrics"], bump)]
+    pub risk_metrics: UncheckedAccount<'info>,
+    
+    /// CHECK: HaltMonitor PDA, always passed so a detected halt can't be
+    /// skipped; nothing is checked until it is initialized
+    #[account(mut, seeds = [b"halt_monitor"], bump)]
+    pub halt_monitor: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct ConfirmSubmission<'info> {
+    // Only the validator authority or the relayer committing operations may
+    // vouch for a destination-chain transaction
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        constraint = authority.key() == validator.authority
+            || authority.key() == validator.operation_committer @ TrinityError::UnauthorizedUser,
+        constraint = !validator.monitor_only @ TrinityError::MonitorOnly,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        mut,
+        seeds = [b"proof", operation_id.as_ref()],
+        bump,
+        constraint = proof_record.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub proof_record: Account<'info, ProofRecord>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + DailyStats::INIT_SPACE,
+        seeds = [b"stats", current_day().to_le_bytes().as_ref()],
+        bump
+    )]
+    pub daily_stats: Account<'info, DailyStats>,
+    
+    /// CHECK: RiskMetrics PDA, always passed so risk flags can't be skipped;
+    /// nothing is evaluated until it is initialized
+    #[account(mut, seeds = [b"risk_metrics"], bump)]
+    pub risk_metrics: UncheckedAccount<'info>,
+    
+    /// CHECK: HaltMonitor PDA, always passed so post-halt proofs can't skip
+    /// their extra confirmations; nothing is checked until it is initialized
+    #[account(seeds = [b"halt_monitor"], bump)]
+    pub halt_monitor: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Every account may be uninitialized; each is read only if program-owned
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct GetConsensusStatus<'info> {
+    /// CHECK: ConsensusState PDA
+    #[account(seeds = [b"consensus", operation_id.as_ref()], bump)]
+    pub consensus_state: UncheckedAccount<'info>,
+    
+    /// CHECK: ProofRecord PDA
+    #[account(seeds = [b"proof", operation_id.as_ref()], bump)]
+    pub proof_record: UncheckedAccount<'info>,
+    
+    /// CHECK: OverrideProposal PDA
+    #[account(seeds = [b"override", operation_id.as_ref()], bump)]
+    pub override_proposal: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct GetProofEvidence<'info> {
+    #[account(
+        seeds = [b"proof", operation_id.as_ref()],
+        bump,
+        constraint = proof_record.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub proof_record: Account<'info, ProofRecord>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct FinalizeConsensus<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = !validator.monitor_only @ TrinityError::MonitorOnly,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        seeds = [b"proof", operation_id.as_ref()],
+        bump,
+        constraint = proof_record.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub proof_record: Account<'info, ProofRecord>,
+    
+    // init_if_needed so a second call reaches the `finalized` check
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ConsensusState::INIT_SPACE,
+        seeds = [b"consensus", operation_id.as_ref()],
+        bump
+    )]
+    pub consensus_state: Account<'info, ConsensusState>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + DailyStats::INIT_SPACE,
+        seeds = [b"stats", current_day().to_le_bytes().as_ref()],
+        bump
+    )]
+    pub daily_stats: Account<'info, DailyStats>,
+    
+    /// CHECK: RiskMetrics PDA, always passed so risk flags can't be skipped;
+    /// nothing is evaluated until it is initialized
+    #[account(mut, seeds = [b"risk_metrics"], bump)]
+    pub risk_metrics: UncheckedAccount<'info>,
+    
+    /// CHECK: StakeConfig PDA, always passed so the stake requirement can't be
+    /// skipped; there is none until it is initialized
+    #[account(seeds = [b"stake_config"], bump)]
+    pub stake_config: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    
+    /// Only to pay contributor rewards; the vault is checked against the pool
+    #[account(seeds = [b"reward_pool"], bump = reward_pool.bump)]
+    pub reward_pool: Option<Account<'info, RewardPool>>,
+    
+    #[account(mut)]
+    pub reward_vault: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct SetOperationPause<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    // init_if_needed so an operation can be frozen before it is finalized
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ConsensusState::INIT_SPACE,
+        seeds = [b"consensus", operation_id.as_ref()],
+        bump
+    )]
+    pub consensus_state: Account<'info, ConsensusState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(vault_id: u64, vault_owner: Pubkey, operation_type: OperationType, amount: u64, user: Pubkey)]
+pub struct VerifyOperation<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VaultVerification::INIT_SPACE,
+        seeds = [b"verification", &vault_id.to_le_bytes(), vault_owner.as_ref()],
+        bump
+    )]
+    pub verification: Account<'info, VaultVerification>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + DailyStats::INIT_SPACE,
+        seeds = [b"stats", current_day().to_le_bytes().as_ref()],
+        bump
+    )]
+    pub daily_stats: Account<'info, DailyStats>,
+    
+    /// CHECK: Vault account - verified by checking it's not System-owned and matches vault_owner
+    pub vault: AccountInfo<'info>,
+    
+    /// CHECK: CheckConfig PDA, always passed so amount limits can't be skipped;
+    /// no limits until it is initialized
+    #[account(seeds = [b"check_config", validator.key().as_ref()], bump)]
+    pub check_config: UncheckedAccount<'info>,
+    
+    /// User's cumulative bridged amount; required for CrossChainTransfer
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + BridgedTotal::INIT_SPACE,
+        seeds = [b"bridged", user.as_ref()],
+        bump
+    )]
+    pub bridged_total: Option<Account<'info, BridgedTotal>>,
+    
+    /// CHECK: VestingLink PDA, always passed so the emergency cross-check
+    /// can't be skipped; nothing is checked until it is initialized
+    #[account(seeds = [b"vesting_link"], bump)]
+    pub vesting_link: UncheckedAccount<'info>,
+    
+    /// CHECK: The vesting program's RecoveryProposal PDA for `vault`, whether
+    /// or not it exists; required for withdrawals from vesting schedules
+    pub emergency_proposal: Option<UncheckedAccount<'info>>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SweepExpired<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    /// CHECK: Validator authority - receives reclaimed rent net of the bounty
+    #[account(mut)]
+    pub authority: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = 8 + DailyStats::INIT_SPACE,
+        seeds = [b"stats", current_day().to_le_bytes().as_ref()],
+        bump
+    )]
+    pub daily_stats: Account<'info, DailyStats>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RollupMetrics<'info> {
+    #[account(
+        mut,
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeSeenOperations<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SeenOperations::INIT_SPACE,
+        seeds = [b"seen_operations", validator.key().as_ref()],
+        bump
+    )]
+    pub seen_operations: Account<'info, SeenOperations>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct CommitOperation<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = operation_committer @ TrinityError::UnauthorizedUser,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ExpectedOperation::INIT_SPACE,
+        seeds = [b"expected_operation", operation_id.as_ref()],
+        bump
+    )]
+    pub expected_operation: Account<'info, ExpectedOperation>,
+    
+    /// Bridge program PDA (signing via CPI) or relayer key
+    pub operation_committer: Signer<'info>,
+    
+    /// Separate from the committer, since a PDA can't pay for account creation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitEthCheckpoint<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = operation_committer @ TrinityError::UnauthorizedUser,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + EthCheckpoint::INIT_SPACE,
+        seeds = [b"eth_checkpoint", validator.key().as_ref()],
+        bump
+    )]
+    pub eth_checkpoint: Account<'info, EthCheckpoint>,
+    
+    pub operation_committer: Signer<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Reconcile<'info> {
+    #[account(
+        mut,
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(seeds = [b"eth_checkpoint", validator.key().as_ref()], bump = eth_checkpoint.bump)]
+    pub eth_checkpoint: Account<'info, EthCheckpoint>,
+    
+    #[account(seeds = [b"proof_sequence", validator.key().as_ref()], bump = proof_sequence.bump)]
+    pub proof_sequence: Account<'info, ProofSequence>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeCheckConfig<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CheckConfig::INIT_SPACE,
+        seeds = [b"check_config", validator.key().as_ref()],
+        bump
+    )]
+    pub check_config: Account<'info, CheckConfig>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct QueueRuleChange<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        mut,
+        seeds = [b"check_config", validator.key().as_ref()],
+        bump = check_config.bump,
+        constraint = check_config.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub check_config: Account<'info, CheckConfig>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyRuleChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"check_config", check_config.validator.as_ref()],
+        bump = check_config.bump,
+        constraint = check_config.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub check_config: Account<'info, CheckConfig>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateValidator<'info> {
+    #[account(
+        mut,
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetVestingProgram<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + VestingLink::INIT_SPACE,
+        seeds = [b"vesting_link"],
+        bump
+    )]
+    pub vesting_link: Account<'info, VestingLink>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================================
+// HIGH-FREQUENCY MONITORING Account Structures
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct InitializeMonitoring<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MonitorConfig::INIT_SPACE,
+        seeds = [b"monitor_config", validator.key().as_ref()],
+        bump
+    )]
+    pub monitor_config: Account<'info, MonitorConfig>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecordMonitoringCheck<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        mut,
+        seeds = [b"monitor_config", validator.key().as_ref()],
+        bump = monitor_config.bump
+    )]
+    pub monitor_config: Account<'info, MonitorConfig>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + DailyStats::INIT_SPACE,
+        seeds = [b"stats", current_day().to_le_bytes().as_ref()],
+        bump
+    )]
+    pub daily_stats: Account<'info, DailyStats>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(vault_id: u64, operation_hash: [u8; 32])]
+pub struct FastVerifyOperation<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + FastProof::INIT_SPACE,
+        seeds = [b"fast_proof", &vault_id.to_le_bytes(), operation_hash.as_ref()],
+        bump
+    )]
+    pub fast_proof: Account<'info, FastProof>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetMonitoringStats<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(seeds = [b"monitor_config", validator.key().as_ref()], bump = monitor_config.bump)]
+    pub monitor_config: Account<'info, MonitorConfig>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeDisputeCouncil<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + DisputeCouncil::INIT_SPACE,
+        seeds = [b"dispute_council", validator.key().as_ref()],
+        bump
+    )]
+    pub council: Account<'info, DisputeCouncil>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct ProposeOverride<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(seeds = [b"dispute_council", validator.key().as_ref()], bump = council.bump)]
+    pub council: Account<'info, DisputeCouncil>,
+    
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + OverrideProposal::INIT_SPACE,
+        seeds = [b"override", operation_id.as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, OverrideProposal>,
+    
+    /// Operator whose attestation the override corrects; charged a lost
+    /// dispute when it executes
+    pub at_fault: Option<Account<'info, OperatorMetrics>>,
+    
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct ExecuteOverride<'info> {
+    #[account(
+        mut,
+        seeds = [b"override", operation_id.as_ref()],
+        bump = proposal.bump,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal: Account<'info, OverrideProposal>,
+    
+    // init_if_needed: a pause or an attestation may have created it already
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + ConsensusState::INIT_SPACE,
+        seeds = [b"consensus", operation_id.as_ref()],
+        bump
+    )]
+    pub consensus_state: Account<'info, ConsensusState>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + DailyStats::INIT_SPACE,
+        seeds = [b"stats", current_day().to_le_bytes().as_ref()],
+        bump
+    )]
+    pub daily_stats: Account<'info, DailyStats>,
+    
+    /// Required when the proposal names an operator at fault
+    #[account(mut)]
+    pub at_fault: Option<Account<'info, OperatorMetrics>>,
+    
+    /// CHECK: Receives the proposal rent; verified via has_one
+    #[account(mut)]
+    pub proposer: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct ForceExpireOperation<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(seeds = [b"dispute_council", validator.key().as_ref()], bump = council.bump)]
+    pub council: Account<'info, DisputeCouncil>,
+    
+    // init_if_needed so a reached or already-failed operation hits its check
+    #[account(
+        init_if_needed,
+        payer = forced_by,
+        space = 8 + ConsensusState::INIT_SPACE,
+        seeds = [b"consensus", operation_id.as_ref()],
+        bump
+    )]
+    pub consensus_state: Account<'info, ConsensusState>,
+    
+    /// Operator whose attestation jammed the operation; charged a divergence
+    #[account(mut)]
+    pub at_fault: Option<Account<'info, OperatorMetrics>>,
+    
+    #[account(mut)]
+    pub forced_by: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct CancelOverride<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(seeds = [b"dispute_council", validator.key().as_ref()], bump = council.bump)]
+    pub council: Account<'info, DisputeCouncil>,
+    
+    #[account(
+        mut,
+        seeds = [b"override", operation_id.as_ref()],
+        bump = proposal.bump,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal: Account<'info, OverrideProposal>,
+    
+    /// CHECK: Receives the proposal rent; verified via has_one
+    #[account(mut)]
+    pub proposer: AccountInfo<'info>,
+    
+    pub member: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetDailyStats<'info> {
+    #[account(seeds = [b"stats", current_day().to_le_bytes().as_ref()], bump = daily_stats.bump)]
+    pub daily_stats: Account<'info, DailyStats>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct CreateConsensusMirror<'info> {
+    #[account(
+        seeds = [b"consensus", operation_id.as_ref()],
+        bump = consensus_state.bump,
+        constraint = consensus_state.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub consensus_state: Account<'info, ConsensusState>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ConsensusMirror::INIT_SPACE,
+        seeds = [b"consensus_mirror", operation_id.as_ref()],
+        bump
+    )]
+    pub consensus_mirror: Account<'info, ConsensusMirror>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct CloseConsensusMirror<'info> {
+    // No stored bump: the mirror layout is limited to its public fields
+    #[account(
+        mut,
+        seeds = [b"consensus_mirror", operation_id.as_ref()],
+        bump,
+        close = closer
+    )]
+    pub consensus_mirror: Account<'info, ConsensusMirror>,
+    
+    #[account(mut)]
+    pub closer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// CHECK: Any account this program owns; dispatched on its discriminator
+    /// by migrations::upgrade, which rejects unknown kinds
+    #[account(mut, owner = crate::ID)]
+    pub account: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureRewards<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + RewardPool::INIT_SPACE,
+        seeds = [b"reward_pool"],
+        bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    // Created with the pool; a later call with another mint fails here
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"reward_vault"],
+        bump,
+        token::mint = mint,
+        token::authority = reward_pool
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureRewardTreasury<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(mut, seeds = [b"reward_pool"], bump = reward_pool.bump)]
+    pub reward_pool: Account<'info, RewardPool>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SweepRewardSurplus<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(seeds = [b"reward_pool"], bump = reward_pool.bump)]
+    pub reward_pool: Account<'info, RewardPool>,
+    
+    #[account(mut, address = reward_pool.vault @ TrinityError::InvalidRewardVault)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = reward_pool.treasury != Pubkey::default()
+            && treasury_token_account.owner == reward_pool.treasury
+            && treasury_token_account.mint == reward_pool.mint @ TrinityError::InvalidRewardTreasury
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureStake<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + StakeConfig::INIT_SPACE,
+        seeds = [b"stake_config"],
+        bump
+    )]
+    pub stake_config: Account<'info, StakeConfig>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    // Created with the config; a later call with another mint fails here
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"stake_vault"],
+        bump,
+        token::mint = mint,
+        token::authority = stake_config
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BondStake<'info> {
+    #[account(seeds = [b"stake_config"], bump = stake_config.bump)]
+    pub stake_config: Account<'info, StakeConfig>,
+    
+    #[account(mut, address = stake_config.vault @ TrinityError::InvalidStakeAccount)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = operator,
+        space = 8 + OperatorStake::INIT_SPACE,
+        seeds = [b"operator_stake", operator.key().as_ref()],
+        bump
+    )]
+    pub operator_stake: Account<'info, OperatorStake>,
+    
+    #[account(mut)]
+    pub operator_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub operator: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnbondStake<'info> {
+    #[account(seeds = [b"stake_config"], bump = stake_config.bump)]
+    pub stake_config: Account<'info, StakeConfig>,
+    
+    #[account(mut, address = stake_config.vault @ TrinityError::InvalidStakeAccount)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut, seeds = [b"operator_stake", operator.key().as_ref()], bump = operator_stake.bump)]
+    pub operator_stake: Account<'info, OperatorStake>,
+    
+    #[account(
+        mut,
+        constraint = operator_token_account.owner == operator.key()
+            && operator_token_account.mint == stake_config.mint @ TrinityError::InvalidStakeAccount
+    )]
+    pub operator_token_account: Account<'info, TokenAccount>,
+    
+    pub operator: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct AttestOperation<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        constraint = validator.is_active @ TrinityError::ValidatorNotActive,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        seeds = [b"proof", operation_id.as_ref()],
+        bump,
+        constraint = proof_record.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub proof_record: Account<'info, ProofRecord>,
+    
+    // init_if_needed: the first attestation may come before finalization
+    #[account(
+        init_if_needed,
+        payer = operator,
+        space = 8 + ConsensusState::INIT_SPACE,
+        seeds = [b"consensus", operation_id.as_ref()],
+        bump
+    )]
+    pub consensus_state: Account<'info, ConsensusState>,
+    
+    #[account(mut, seeds = [b"operator_stake", operator.key().as_ref()], bump = operator_stake.bump)]
+    pub operator_stake: Account<'info, OperatorStake>,
+    
+    #[account(mut)]
+    pub operator: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(day: u64)]
+pub struct CloseDailyStats<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        mut,
+        seeds = [b"stats", day.to_le_bytes().as_ref()],
+        bump = daily_stats.bump,
+        close = authority
+    )]
+    pub daily_stats: Account<'info, DailyStats>,
+    
+    /// CHECK: Validator authority - receives the reclaimed rent
+    #[account(mut)]
+    pub authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BootstrapLocalnet<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TrinityValidator::INIT_SPACE,
+        seeds = [b"trinity_validator"],
+        bump
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MonitorConfig::INIT_SPACE,
+        seeds = [b"monitor_config", validator.key().as_ref()],
+        bump
+    )]
+    pub monitor_config: Account<'info, MonitorConfig>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + DisputeCouncil::INIT_SPACE,
+        seeds = [b"dispute_council", validator.key().as_ref()],
+        bump
+    )]
+    pub council: Account<'info, DisputeCouncil>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(operator: Pubkey)]
+pub struct ConfigureFeeVault<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + FeeVault::INIT_SPACE,
+        seeds = [b"fee_vault", operator.as_ref()],
+        bump
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OperatorPrefund<'info> {
+    #[account(
+        mut,
+        seeds = [b"fee_vault", fee_vault.operator.as_ref()],
+        bump = fee_vault.bump
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+    
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimPrefund<'info> {
+    #[account(
+        mut,
+        seeds = [b"fee_vault", fee_vault.operator.as_ref()],
+        bump = fee_vault.bump,
+        has_one = operator
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+    
+    pub operator: Signer<'info>,
+    
+    /// CHECK: Any account chosen by the operator - only receives lamports
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct SubmitSponsoredProof<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    /// Optional replay filter, checked before the proof record is created
+    #[account(
+        mut,
+        seeds = [b"seen_operations", validator.key().as_ref()],
+        bump = seen_operations.bump,
+        constraint = !seen_operations.contains(&operation_id) @ TrinityError::OperationAlreadySeen
+    )]
+    pub seen_operations: Option<Account<'info, SeenOperations>>,
+    
+    #[account(
+        mut,
+        seeds = [b"fee_vault", fee_vault.operator.as_ref()],
+        bump = fee_vault.bump,
+        has_one = operator
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+    
+    /// CHECK: ExpectedOperation PDA, loaded by hand so that an operation the
+    /// bridge never committed fails with OperationNotFound
+    #[account(mut, seeds = [b"expected_operation", operation_id.as_ref()], bump)]
+    pub expected_operation: UncheckedAccount<'info>,
+    
+    /// CHECK: Uncreated ProofRecord PDA, created here with rent from the fee vault
+    #[account(mut, seeds = [b"proof", operation_id.as_ref()], bump)]
+    pub proof_record: UncheckedAccount<'info>,
+    
+    /// CHECK: ConsensusState PDA, read only if created, for set_operation_pause
+    #[account(seeds = [b"consensus", operation_id.as_ref()], bump)]
+    pub consensus_state: UncheckedAccount<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        space = 8 + DailyStats::INIT_SPACE,
+        seeds = [b"stats", current_day().to_le_bytes().as_ref()],
+        bump
+    )]
+    pub daily_stats: Account<'info, DailyStats>,
+    
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        space = 8 + OperatorMetrics::INIT_SPACE,
+        seeds = [b"operator_metrics", operator.key().as_ref()],
+        bump
+    )]
+    pub operator_metrics: Account<'info, OperatorMetrics>,
+    
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        space = 8 + ProofSequence::INIT_SPACE,
+        seeds = [b"proof_sequence", validator.key().as_ref()],
+        bump
+    )]
+    pub proof_sequence: Account<'info, ProofSequence>,
+    
+    /// CHECK: SlotHashes sysvar, read manually (too large to deserialize on-chain)
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+    
+    /// CHECK: Instructions sysvar, read for the transaction's proof budget
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    
+    /// CHECK: CheckConfig PDA, always passed so shadow rules and amount limits
+    /// can't be skipped; every rule is in Monitor until it is initialized
+    #[account(seeds = [b"check_config", validator.key().as_ref()], bump)]
+    pub check_config: UncheckedAccount<'info>,
+    
+    /// CHECK: RiskMetrics PDA, always passed so risk flags can't be skipped;
+    /// nothing is evaluated until it is initialized
+    #[account(mut, seeds = [b"risk_metrics"], bump)]
+    pub risk_metrics: UncheckedAccount<'info>,
+    
+    /// CHECK: HaltMonitor PDA, always passed so a detected halt can't be
+    /// skipped; nothing is checked until it is initialized
+    #[account(mut, seeds = [b"halt_monitor"], bump)]
+    pub halt_monitor: UncheckedAccount<'info>,
+    
+    /// Operator submission key - signs but holds no SOL
+    pub operator: Signer<'info>,
+    
+    /// Pays the transaction fee and any new DailyStats shard
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetOperatorLiveness<'info> {
+    #[account(seeds = [b"fee_vault", fee_vault.operator.as_ref()], bump = fee_vault.bump)]
+    pub fee_vault: Account<'info, FeeVault>,
+}
+
+#[derive(Accounts)]
+pub struct Heartbeat<'info> {
+    #[account(
+        init_if_needed,
+        payer = operator,
+        space = 8 + OperatorMetrics::INIT_SPACE,
+        seeds = [b"operator_metrics", operator.key().as_ref()],
+        bump
+    )]
+    pub operator_metrics: Account<'info, OperatorMetrics>,
+    
+    /// CHECK: HaltMonitor PDA, whose heartbeat slot this refreshes; nothing
+    /// is recorded until it is initialized
+    #[account(mut, seeds = [b"halt_monitor"], bump)]
+    pub halt_monitor: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub operator: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateScores<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+}
+
+#[derive(Accounts)]
+pub struct GetOperatorRanking<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureBuildAttestation<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + BuildAttestation::INIT_SPACE,
+        seeds = [b"build_attestation"],
+        bump
+    )]
+    pub build_attestation: Account<'info, BuildAttestation>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AttestBuild<'info> {
+    #[account(mut, seeds = [b"build_attestation"], bump = build_attestation.bump)]
+    pub build_attestation: Account<'info, BuildAttestation>,
+    
+    // Only the configured upgrade guard can sign for this PDA
+    #[account(seeds = [b"build_attester"], bump, seeds::program = build_attestation.upgrade_guard)]
+    pub build_attester: Signer<'info>,
+    
+    #[account(seeds = [crate::ID.as_ref()], bump, seeds::program = bpf_loader_upgradeable::ID)]
+    pub program_data: Account<'info, ProgramData>,
+}
+
+#[derive(Accounts)]
+pub struct CheckBuildAttestation<'info> {
+    #[account(mut, seeds = [b"build_attestation"], bump = build_attestation.bump)]
+    pub build_attestation: Account<'info, BuildAttestation>,
+    
+    #[account(seeds = [crate::ID.as_ref()], bump, seeds::program = bpf_loader_upgradeable::ID)]
+    pub program_data: Account<'info, ProgramData>,
+}
+
+#[derive(Accounts)]
+pub struct GetBuildAttestation<'info> {
+    #[account(seeds = [b"build_attestation"], bump = build_attestation.bump)]
+    pub build_attestation: Account<'info, BuildAttestation>,
+}
+
+#[derive(Accounts)]
+pub struct GetProgramInfo {}
+
+#[derive(Accounts)]
+pub struct InitializeRiskMetrics<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RiskMetrics::INIT_SPACE,
+        seeds = [b"risk_metrics"],
+        bump
+    )]
+    pub risk_metrics: Account<'info, RiskMetrics>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRiskMetrics<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(mut, seeds = [b"risk_metrics"], bump = risk_metrics.bump)]
+    pub risk_metrics: Account<'info, RiskMetrics>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyRiskThresholds<'info> {
+    #[account(mut, seeds = [b"risk_metrics"], bump = risk_metrics.bump)]
+    pub risk_metrics: Account<'info, RiskMetrics>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureHaltPolicy<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + HaltMonitor::INIT_SPACE,
+        seeds = [b"halt_monitor"],
+        bump
+    )]
+    pub halt_monitor: Account<'info, HaltMonitor>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResumeAfterHalt<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(mut, seeds = [b"halt_monitor"], bump = halt_monitor.bump)]
+    pub halt_monitor: Account<'info, HaltMonitor>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + HaltRecord::INIT_SPACE,
+        seeds = [b"halt_record", halt_monitor.halts.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub halt_record: Account<'info, HaltRecord>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct ReissueProof<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(
+        mut,
+        seeds = [b"proof", operation_id.as_ref()],
+        bump,
+        constraint = proof_record.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub proof_record: Account<'info, ProofRecord>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ProofReissues::INIT_SPACE,
+        seeds = [b"proof_reissues", operation_id.as_ref()],
+        bump
+    )]
+    pub proof_reissues: Account<'info, ProofReissues>,
+    
+    /// CHECK: SlotHashes sysvar, read manually (too large to deserialize on-chain)
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Read-only; the audited accounts come in remaining_accounts
+#[derive(Accounts)]
+pub struct AssertInvariants<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    /// CHECK: ProofSequence PDA; no proof has been numbered until it is initialized
+    #[account(seeds = [b"proof_sequence", validator.key().as_ref()], bump)]
+    pub proof_sequence: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BeginStateExport<'info> {
+    #[account(
+        mut,
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(seeds = [b"dispute_council", validator.key().as_ref()], bump = council.bump)]
+    pub council: Account<'info, DisputeCouncil>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + StateExport::INIT_SPACE,
+        seeds = [b"state_export", validator.key().as_ref()],
+        bump
+    )]
+    pub state_export: Account<'info, StateExport>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// Dispute council member co-signing the export
+    pub guardian: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// The exported accounts come in remaining_accounts
+#[derive(Accounts)]
+pub struct ExportState<'info> {
+    #[account(
+        seeds = [b"trinity_validator"],
+        bump = validator.bump,
+        has_one = authority,
+        constraint = validator.is_current() @ TrinityError::AccountNeedsMigration
+    )]
+    pub validator: Account<'info, TrinityValidator>,
+    
+    #[account(seeds = [b"dispute_council", validator.key().as_ref()], bump = council.bump)]
+    pub council: Account<'info, DisputeCouncil>,
+    
+    #[account(mut, seeds = [b"state_export", validator.key().as_ref()], bump = state_export.bump)]
+    pub state_export: Account<'info, StateExport>,
+    
+    pub authority: Signer<'info>,
+    
+    /// Dispute council member co-signing the page
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StageImport<'info> {
+    /// CHECK: raw payload bytes, written only by stage_import and consumed by import_state
+    #[account(mut, seeds = [b"import_staging", authority.key().as_ref()], bump)]
+    pub staging: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ImportState<'info> {
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + StateImport::INIT_SPACE,
+        seeds = [b"state_import"],
+        bump
+    )]
+    pub state_import: Account<'info, StateImport>,
+    
+    /// CHECK: this deployment's validator PDA; absent until the validator is imported
+    #[account(seeds = [b"trinity_validator"], bump)]
+    pub validator: UncheckedAccount<'info>,
+    
+    /// CHECK: staged payload (see stage_import)
+    #[account(mut, seeds = [b"import_staging", authority.key().as_ref()], bump, owner = crate::ID)]
+    pub staging: UncheckedAccount<'info>,
+    
+    /// CHECK: the imported account's derived address, created here (checked against the payload)
+    #[account(mut)]
+    pub target: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+// ============================================================================
+// State Structures
+// ============================================================================
+
+#[account]
+#[derive(InitSpace)]
+pub struct TrinityValidator {
+    pub authority: Pubkey,                          // Validator authority
+    pub ethereum_bridge_address: [u8; 20],          // CrossChainBridgeOptimized address
+    pub validator_ethereum_address: [u8; 20],       // Validator's Ethereum address (for signing)
+    #[max_len(200)]
+    pub arbitrum_rpc_url: String,                   // Arbitrum RPC endpoint
+    pub total_proofs_submitted: u64,                // Total proofs generated, as of the last rollup_metrics
+    pub last_processed_operation: u64,              // Last operation ID processed
+    pub is_active: bool,                            // Validator active status
+    pub proof_expiry_secs: u64,                     // Unconfirmed proof lifetime
+    pub proof_retention_secs: u64,                  // Expired proof retention before close
+    pub sweep_bounty_bps: u16,                      // Cranker share of reclaimed rent
+    pub store_tx_signatures: bool,                  // Keep solana_tx_signature on new proof records
+    pub operation_committer: Pubkey,                // Commits expected operations (bridge PDA or relayer)
+    pub monitor_only: bool,                         // Confirmations halted by a reconciliation mismatch
+    pub bump: u8,                                   // PDA bump
+    pub max_proof_budget: u64,                      // Max proof budget per transaction (see PROOF_BUDGET_BASE_UNITS)
+    pub layout_version: u8,                         // Account layout (see migrations); 0 before versioning
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ProofRecord {
+    pub operation_id: [u8; 32],                     // Ethereum operation ID
+    pub proof_id: [u8; 32],                         // keccak(operation_id, merkle_root, validator)
+    pub merkle_root: [u8; 32],                      // Computed Merkle root
+    #[max_len(10)]
+    pub merkle_proof: Vec<[u8; 32]>,                // Merkle proof path
+    pub solana_block_hash: [u8; 32],                // Solana block hash
+    pub solana_tx_signature: Option<[u8; 64]>,      // Solana transaction signature (None unless stored)
+    pub solana_block_number: u64,                   // Solana slot number
+    pub solana_epoch: u64,                          // Epoch at submission
+    pub leader_schedule_epoch: u64,                 // Leader schedule epoch at submission
+    pub environment_hash: [u8; 32],                 // keccak(EpochSchedule, leader schedule epoch, newest slot hash)
+    pub operation_type: OperationType,              // Operation being proven
+    pub amount: u64,                                // Operation amount
+    pub amount_limit: u64,                          // Type limit in force at submission
+    pub hashlock: [u8; 32],                         // HTLC hashlock (zero if none)
+    pub preimage: [u8; 32],                         // Revealed HTLC preimage (zero if none)
+    pub timestamp: u64,                             // Proof generation timestamp
+    pub submitted_to_ethereum: bool,                // Ethereum submission status
+    pub ethereum_tx_hash: [u8; 32],                 // Ethereum transaction hash
+    pub validator: Pubkey,                          // Validator that generated proof
+    pub expired: bool,                              // Expired without Ethereum confirmation
+    pub expired_at: u64,                            // Expiry timestamp (0 if live)
+    pub submission_hash: [u8; 32],                  // keccak of the Borsh-serialized instruction args
+    pub eth_event_timestamp: i64,                   // Ethereum event timestamp reported by the operator
+    pub observed_latency_seconds: u64,              // timestamp - eth_event_timestamp, clamped to [0, MAX_PLAUSIBLE_LATENCY_SECS]
+    pub latency_implausible: bool,                  // Reported latency exceeded MAX_PLAUSIBLE_LATENCY_SECS
+    pub layout_version: u8,                         // Account layout (see migrations); 0 before versioning
+    pub merkle_mode: MerkleMode,                    // How merkle_proof folds into merkle_root (layout 2)
+    #[max_len(10)]
+    pub sibling_is_left: Vec<bool>,                 // Per-level sibling side, Positional only (layout 2)
+    pub consensus_deadline: u64,                    // Expiry fixed at submission (0: none, see proof_deadline) (layout 3)
+    #[max_len(4)]
+    pub confirmations: Vec<ChainConfirmation>,      // Per destination chain, in confirmation order (layout 4)
+}
+
+/// A proof's confirmation on one destination chain (see confirm_submission)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct ChainConfirmation {
+    pub chain_id: u64,                              // EVM chain ID
+    pub tx_hash: [u8; 32],                          // Confirming transaction
+    pub block_number: u64,                          // Block including it
+    pub confirmed_at: u64,                          // When it was recorded here
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct VaultVerification {
+    pub vault_id: u64,                              // Vault identifier from Ethereum
+    pub vault_owner: Pubkey,                        // Vault owner public key
+    pub operation_type: OperationType,              // Operation being verified
+    pub amount: u64,                                // Operation amount
+    pub user: Pubkey,                               // User initiating operation
+    pub verification_hash: [u8; 32],                // Verification hash (submitted to Ethereum)
+    pub timestamp: u64,                             // Verification timestamp
+    pub validator: Pubkey,                          // Validator that verified
+}
+
+/// Ring of 64-bit fingerprints (first 8 bytes of the operation ID) of the
+/// last SEEN_OPERATIONS_WINDOW submissions. A hit rejects the submission
+/// before its ProofRecord is touched; a miss proves nothing, so the
+/// ProofRecord PDA stays the authoritative duplicate check.
+///
+/// False-negative window: an operation is forgotten once
+/// SEEN_OPERATIONS_WINDOW newer submissions have been recorded, after which a
+/// replay falls through to the (more expensive) record path. Submissions sent
+/// without this account are not recorded at all. False positives need a
+/// 64-bit fingerprint collision among keccak-derived IDs.
+#[account]
+#[derive(InitSpace)]
+pub struct SeenOperations {
+    pub validator: Pubkey,                          // Associated validator
+    pub fingerprints: [u64; 256],                   // Recent fingerprints (SEEN_OPERATIONS_WINDOW)
+    pub len: u16,                                   // Filled slots (saturates at the window)
+    pub next: u16,                                  // Next slot to overwrite
+    pub bump: u8,                                   // PDA bump
+}
+
+impl SeenOperations {
+    pub fn fingerprint(operation_id: &[u8; 32]) -> u64 {
+        u64::from_le_bytes(operation_id[..8].try_into().unwrap())
+    }
+    
+    pub fn contains(&self, operation_id: &[u8; 32]) -> bool {
+        let fingerprint = Self::fingerprint(operation_id);
+        self.fingerprints[..self.len as usize].contains(&fingerprint)
+    }
+    
+    pub fn record(&mut self, operation_id: &[u8; 32]) {
+        self.fingerprints[self.next as usize] = Self::fingerprint(operation_id);
+        self.next = ((self.next as usize + 1) % SEEN_OPERATIONS_WINDOW) as u16;
+        self.len = (self.len as usize + 1).min(SEEN_OPERATIONS_WINDOW) as u16;
+    }
+}
+
+/// An operation the bridge emitted, committed before any proof for it is
+/// accepted; it stops being pending once a proof is stored
+#[account]
+#[derive(InitSpace, Default)]
+pub struct ExpectedOperation {
+    pub operation_id: [u8; 32],                     // Ethereum operation ID
+    pub committed_by: Pubkey,                       // Operation committer at the time
+    pub committed_at: i64,                          // Commit timestamp
+    pub pending: bool,                              // No proof accepted yet
+    pub bump: u8,                                   // PDA bump
+}
+
+/// Per-rule shadow-mode settings (indexed by CheckId), the registry of
+/// enabled OperationType codes and their amount limits (indexed by registry
+/// slot), each with timelocked pending changes, and each type's consensus
+/// window
+#[account]
+#[derive(InitSpace)]
+pub struct CheckConfig {
+    pub validator: Pubkey,                          // Associated validator
+    pub enforcement: [Enforcement; 3],              // Current mode per rule (SHADOW_CHECK_COUNT)
+    pub queued: [QueuedEnforcement; 3],             // Pending change per rule
+    pub operation_codes: [u16; 8],                  // Enabled OperationType code per slot (MAX_OPERATION_TYPES)
+    pub operation_types: u8,                        // Slots in use
+    pub max_amount: [u64; 8],                       // Largest single operation per slot (u64::MAX: none)
+    pub queued_limits: [QueuedLimit; 8],            // Pending limit change per slot
+    pub max_bridged_per_user: u64,                  // Cumulative CrossChainTransfer cap per user (u64::MAX: none)
+    pub queued_bridge_cap: QueuedLimit,             // Pending bridge cap change
+    pub queued_operation_type: QueuedOperationType, // Pending code to enable
+    pub bump: u8,                                   // PDA bump
+    pub layout_version: u8,                         // Account layout (see migrations); 0 before versioning
+    pub consensus_window_secs: [u64; 8],            // Submission-to-deadline per slot (0: proof_expiry_secs) (layout 2)
+}
+
+impl CheckConfig {
+    /// Rules in force before the CheckConfig is initialized
+    fn unconfigured() -> Self {
+        Self {
+            validator: Pubkey::default(),
+            enforcement: [Enforcement::Monitor; SHADOW_CHECK_COUNT],
+            queued: [QueuedEnforcement::NONE; SHADOW_CHECK_COUNT],
+            operation_codes: INITIAL_OPERATION_CODES,
+            operation_types: INITIAL_OPERATION_TYPES,
+            max_amount: [u64::MAX; MAX_OPERATION_TYPES],
+            queued_limits: [QueuedLimit::NONE; MAX_OPERATION_TYPES],
+            max_bridged_per_user: u64::MAX,
+            queued_bridge_cap: QueuedLimit::NONE,
+            queued_operation_type: QueuedOperationType::NONE,
+            bump: 0,
+            layout_version: CheckConfig::LAYOUT_VERSION,
+            consensus_window_secs: [0; MAX_OPERATION_TYPES],
+        }
+    }
+    
+    fn enabled_codes(&self) -> &[u16] {
+        &self.operation_codes[..self.operation_types as usize]
+    }
+    
+    /// Registry slot of an enabled operation type: its index into max_amount,
+    /// queued_limits, consensus_window_secs and DailyStats.verifications_by_type
+    fn type_slot(&self, operation_type: &OperationType) -> Result<usize> {
+        let code = operation_type.code();
+        require_ctx!(operation_type.is_canonical(), TrinityError::NonCanonicalOperationType, code = code);
+        let slot = self.enabled_codes().iter().position(|enabled| *enabled == code);
+        require_ctx!(slot.is_some(), TrinityError::OperationTypeNotEnabled, code = code);
+        Ok(slot.unwrap())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct QueuedEnforcement {
+    pub enforcement: Enforcement,                   // Mode to switch to
+    pub executable_at: i64,                         // Earliest apply time (0 if nothing queued)
+}
+
+impl QueuedEnforcement {
+    pub const NONE: Self = Self { enforcement: Enforcement::Monitor, executable_at: 0 };
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct QueuedLimit {
+    pub max_amount: u64,                            // Limit to switch to
+    pub executable_at: i64,                         // Earliest apply time (0 if nothing queued)
+}
+
+impl QueuedLimit {
+    pub const NONE: Self = Self { max_amount: u64::MAX, executable_at: 0 };
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct QueuedOperationType {
+    pub code: u16,                                  // OperationType code to enable
+    pub executable_at: i64,                         // Earliest apply time (0 if nothing queued)
+}
+
+impl QueuedOperationType {
+    pub const NONE: Self = Self { code: 0, executable_at: 0 };
+}
+
+/// Cumulative CrossChainTransfer amount verified for one user, checked
+/// against CheckConfig.max_bridged_per_user
+#[account]
+#[derive(InitSpace)]
+pub struct BridgedTotal {
+    pub user: Pubkey,                               // User the transfers were verified for
+    pub bridged_total: u64,                         // Sum of verified transfer amounts
+    pub bump: u8,                                   // PDA bump
+}
+
+/// The vesting program cross-checked by verify_vault_operation, at PDA [b"vesting_link"]
+#[account]
+#[derive(InitSpace)]
+pub struct VestingLink {
+    pub vesting_program: Pubkey,                    // Default pubkey: no cross-check
+    pub bump: u8,                                   // PDA bump
+}
+
+// ============================================================================
+// HIGH-FREQUENCY MONITORING State Structures
+// ============================================================================
+
+#[account]
+#[derive(InitSpace)]
+pub struct MonitorConfig {
+    pub validator: Pubkey,                          // Associated validator
+    pub monitoring_interval_ms: u64,                // Monitoring check interval in ms
+    pub max_latency_ms: u64,                        // Maximum acceptable proof latency
+    pub last_check_timestamp: u64,                  // Unix timestamp of last check
+    pub last_check_slot: u64,                       // Solana slot of last check
+    pub total_checks: u64,                          // Total monitoring checks performed
+    pub successful_proofs: u64,                     // Number of successful proofs
+    pub failed_proofs: u64,                         // Number of failed proofs
+    pub average_latency_ms: u64,                    // Rolling average latency
+    pub is_active: bool,                            // Monitoring active status
+    pub bump: u8,                                   // PDA bump
+}
+
+#[account]
+#[derive(InitSpace)]
 pub struct FastProof {
     pub vault_id: u64,                              // Vault identifier
     pub operation_hash: [u8; 32],                   // Operation hash
@@ -637,151 +4907,4415 @@ pub struct FastProof {
     pub submitted_to_ethereum: bool,                // Submission status
 }
 
-/// Return type for get_monitoring_stats
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct MonitoringStats {
-    pub total_checks: u64,
-    pub successful_proofs: u64,
-    pub failed_proofs: u64,
-    pub average_latency_ms: u64,
-    pub last_check_timestamp: u64,
-    pub is_active: bool,
+/// Return type for get_latency_stats
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct LatencyStats {
+    pub samples: u64,
+    pub average_secs: u64,
+    pub p95_bound_secs: u64,                        // Upper bound of the bucket holding the 95th percentile
+    pub buckets: [u64; 8],
+    pub implausible: u64,
+    pub advisory: bool,                             // Always true: operator-reported Ethereum timestamps
+}
+
+/// Return type for get_monitoring_stats
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MonitoringStats {
+    pub total_checks: u64,
+    pub successful_proofs: u64,
+    pub failed_proofs: u64,
+    pub average_latency_ms: u64,
+    pub last_check_timestamp: u64,
+    pub is_active: bool,
+}
+
+// ============================================================================
+// MANUAL OVERRIDE State Structures
+// ============================================================================
+
+#[account]
+#[derive(InitSpace)]
+pub struct DisputeCouncil {
+    pub validator: Pubkey,                          // Associated validator
+    #[max_len(9)]
+    pub members: Vec<Pubkey>,                       // Council members
+    pub threshold: u8,                              // Approvals required (M of N)
+    pub bump: u8,                                   // PDA bump
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct OverrideProposal {
+    pub operation_id: [u8; 32],                     // Operation being forced through
+    pub justification_hash: [u8; 32],               // Hash of the off-chain justification
+    pub proposer: Pubkey,                           // Council member who proposed
+    pub proposed_at: i64,                           // Proposal timestamp
+    pub executable_at: i64,                         // Earliest execution time
+    pub approvals: u8,                              // Council approvals at proposal
+    pub at_fault: Pubkey,                           // Operator charged on execution (default if none)
+    pub bump: u8,                                   // PDA bump
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ConsensusState {
+    pub operation_id: [u8; 32],                     // Ethereum operation ID
+    pub finalized_at: i64,                          // Finalization timestamp
+    pub overridden: bool,                           // Forced by manual override, never organic
+    pub justification_hash: [u8; 32],               // Override justification (zero if organic)
+    pub merkle_root: [u8; 32],                      // Finalized Solana proof root (zero if overridden)
+    #[max_len(3)]
+    pub chains: Vec<u8>,                            // Agreeing chain IDs (empty if overridden)
+    pub finalized: bool,                            // Set once; guards double finalization
+    pub failed_at: i64,                             // Force-expiry timestamp (0 unless Failed)
+    pub bump: u8,                                   // PDA bump
+    pub layout_version: u8,                         // Account layout (see migrations); 0 before versioning
+    pub operation_paused: bool,                     // Frozen by set_operation_pause (layout 2)
+    #[max_len(10)]
+    pub contributors: Vec<Contribution>,            // Stake-weighted attestations (MAX_CONSENSUS_CONTRIBUTORS) (layout 3)
+    pub stake_weight: u64,                          // Sum of the contributors' stakes (layout 3)
+}
+
+/// An operator's attestation to an operation and the stake behind it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct Contribution {
+    pub operator: Pubkey,
+    pub stake: u64,                                 // Bonded when it attested
+}
+
+// ============================================================================
+// PUBLIC CONSENSUS MIRROR (stable ABI)
+// ============================================================================
+
+/// Public, read-only record that an operation reached organic Trinity
+/// consensus, at PDA [b"consensus_mirror", operation_id].
+///
+/// STABLE ABI: this layout never changes within a major version. Any change
+/// means a new schema_version, and consumers must reject versions they don't
+/// know. Byte offsets, after the 8-byte Anchor discriminator
+/// (sha256("account:ConsensusMirror")[..8]):
+///   8   schema_version  u8        CONSENSUS_MIRROR_SCHEMA_VERSION
+///   9   operation_id    [u8; 32]
+///   41  merkle_root     [u8; 32]  Finalized Solana proof root
+///   73  consensus_at    i64 LE    Unix timestamp of finalization
+///   81  chain_bitmap    u8        Bit (chain ID - 1) per attesting chain
+#[account]
+#[derive(InitSpace)]
+pub struct ConsensusMirror {
+    pub schema_version: u8,
+    pub operation_id: [u8; 32],
+    pub merkle_root: [u8; 32],
+    pub consensus_at: i64,
+    pub chain_bitmap: u8,
+}
+
+// ============================================================================
+// CONSENSUS REWARDS
+// ============================================================================
+
+/// Token rewards for finalized operations, paid from `vault` (a token
+/// account owned by this PDA) to the contributing operators
+#[account]
+#[derive(InitSpace)]
+pub struct RewardPool {
+    pub mint: Pubkey,                               // Reward token
+    pub vault: Pubkey,                              // Token account holding the rewards
+    pub reward_per_operation: u64,                  // Split among contributors per finalized operation
+    pub bump: u8,                                   // PDA bump
+    pub treasury: Pubkey,                           // Owner of the surplus destination (default: none)
+    pub reserve: u64,                               // Balance sweep_reward_surplus leaves in the vault
+}
+
+// ============================================================================
+// STAKE-WEIGHTED CONSENSUS
+// ============================================================================
+
+#[account]
+#[derive(InitSpace)]
+pub struct StakeConfig {
+    pub mint: Pubkey,                               // Stake token
+    pub vault: Pubkey,                              // Token account holding bonded stake
+    pub consensus_weight_threshold: u64,            // Attested stake finalize_consensus must exceed (0: none)
+    pub bump: u8,                                   // PDA bump
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct OperatorStake {
+    pub operator: Pubkey,                           // Bonding operator
+    pub amount: u64,                                // Bonded stake
+    pub locked_until: i64,                          // Latest attestation + STAKE_UNBONDING_SECS
+    pub bump: u8,                                   // PDA bump
+}
+
+// ============================================================================
+// BUILD ATTESTATION
+// ============================================================================
+
+/// Verified build of the deployed program, recorded by the upgrade guard
+/// after each upgrade so partners can match it against the audited source
+#[account]
+#[derive(InitSpace)]
+pub struct BuildAttestation {
+    pub upgrade_guard: Pubkey,                      // Program whose `build_attester` PDA attests
+    pub attestation_window_slots: u64,              // Slots an upgrade may go unattested
+    pub build_hash: [u8; 32],                       // solana-verify hash of the attested build
+    pub source_commit: [u8; SOURCE_COMMIT_LEN],     // Git commit embedded in it (hex, zero-padded)
+    pub upgrade_slot: u64,                          // ProgramData slot of the attested deployment
+    pub attested_slot: u64,
+    pub attested_at: i64,
+    pub attestations: u64,
+    pub warned_upgrade_slot: u64,                   // Deployment UnattestedBuild last reported
+    pub bump: u8,                                   // PDA bump
+}
+
+// ============================================================================
+// RISK FLAGS
+// ============================================================================
+
+/// Risk thresholds and the flags they have raised, at PDA [b"risk_metrics"].
+/// Submissions, Ethereum confirmations and finalizations evaluate the
+/// thresholds (see crossed_risk_flags); a crossed threshold latches its flag
+/// until acknowledge_risk_flag, so each breach is reported once.
+#[account]
+#[derive(InitSpace)]
+pub struct RiskMetrics {
+    pub thresholds: RiskThresholds,                 // In force
+    pub queued: QueuedRiskThresholds,               // Pending change
+    pub latched: u8,                                // Bit per RiskFlag raised and not yet acknowledged
+    pub raised_at: [i64; 3],                        // When each latched flag was raised, by RiskFlag (0 if clear)
+    pub recent_operators: [Pubkey; 16],             // Latest submitters (RISK_SHARE_WINDOW), by recent_proofs % window
+    pub recent_proofs: u64,                         // Proofs recorded in the window so far
+    pub bump: u8,                                   // PDA bump
+}
+
+impl RiskMetrics {
+    pub fn is_latched(&self, flag: RiskFlag) -> bool {
+        self.latched & flag.bit() != 0
+    }
+    
+    /// Record a proof by `operator` in the share window; returns its share of
+    /// the window, in basis points, once the window holds RISK_SHARE_MIN_PROOFS
+    pub fn record_submission(&mut self, operator: Pubkey) -> Option<u64> {
+        self.recent_operators[self.recent_proofs as usize % RISK_SHARE_WINDOW] = operator;
+        self.recent_proofs = self.recent_proofs.saturating_add(1);
+        let filled = self.recent_proofs.min(RISK_SHARE_WINDOW as u64);
+        if filled < RISK_SHARE_MIN_PROOFS {
+            return None;
+        }
+        let submitted = self.recent_operators[..filled as usize].iter().filter(|recent| **recent == operator).count();
+        Some(ratio_bps(submitted as u64, filled))
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct RiskThresholds {
+    pub max_operation_amount: u64,                  // LargeOperation above this amount (u64::MAX: none)
+    pub max_operator_share_bps: u64,                // OperatorConcentration above this share of the window (10,000: none)
+    pub max_consensus_secs: u64,                    // SlowConsensus once a proof is older than this (u64::MAX: none)
+}
+
+impl RiskThresholds {
+    pub const NONE: Self = Self {
+        max_operation_amount: u64::MAX,
+        max_operator_share_bps: SCORE_BPS,
+        max_consensus_secs: u64::MAX,
+    };
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct QueuedRiskThresholds {
+    pub thresholds: RiskThresholds,                 // Thresholds to switch to
+    pub executable_at: i64,                         // Earliest apply time (0 if nothing queued)
+}
+
+impl QueuedRiskThresholds {
+    pub const NONE: Self = Self { thresholds: RiskThresholds::NONE, executable_at: 0 };
+}
+
+/// What an instruction observed, for crossed_risk_flags; None where it has no reading
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RiskObservation {
+    pub amount: Option<u64>,                        // Single operation amount (submissions)
+    pub operator_share_bps: Option<u64>,            // Submitter's share of the recent window (submissions)
+    pub consensus_secs: Option<u64>,                // Age of the operation's proof (confirmation, finalization)
+}
+
+// ============================================================================
+// CHAIN HALT State Structures
+// ============================================================================
+
+/// Chain-halt detection, at PDA [b"halt_monitor"]. Heartbeats and submissions
+/// record the slot they land in; a gap over max_slot_gap since the previous
+/// one means Solana stalled and this validator may resume with a stale view,
+/// so submissions are refused until the authority calls resume_after_halt.
+#[account]
+#[derive(InitSpace)]
+pub struct HaltMonitor {
+    pub policy: HaltPolicy,
+    pub last_heartbeat_slot: u64,                   // Slot of the latest heartbeat or submission
+    pub halted_since: Option<u64>,                  // Last heartbeat slot before a detected gap, until resumed
+    pub resumed_at: i64,                            // Latest resume_after_halt (0 if none)
+    pub cooldown_until: i64,                        // Proofs generated from resumed_at until this need extra confirmations
+    pub extra_eth_confirmations: u64,               // How many: the policy's at the latest resume
+    pub halts: u64,                                 // HaltRecords written
+    pub bump: u8,                                   // PDA bump
+}
+
+impl HaltMonitor {
+    /// Record a heartbeat at `slot`, or latch a halt if the gap since the last
+    /// one is over max_slot_gap (0: detection off). Returns whether a halt is
+    /// latched; the heartbeat slot doesn't move while one is.
+    pub fn observe_slot(&mut self, slot: u64) -> bool {
+        if self.halted_since.is_none() {
+            let gap = slot.saturating_sub(self.last_heartbeat_slot);
+            if self.policy.max_slot_gap != 0 && gap > self.policy.max_slot_gap {
+                self.halted_since = Some(self.last_heartbeat_slot);
+            } else {
+                self.last_heartbeat_slot = self.last_heartbeat_slot.max(slot);
+            }
+        }
+        self.halted_since.is_some()
+    }
+    
+    /// Ethereum confirmations a proof generated at `timestamp` needs before
+    /// its confirmation is accepted: extra_eth_confirmations inside the latest
+    /// post-halt cooldown, none otherwise
+    pub fn required_confirmations(&self, timestamp: i64) -> u64 {
+        if self.resumed_at != 0 && (self.resumed_at..self.cooldown_until).contains(&timestamp) {
+            self.extra_eth_confirmations
+        } else {
+            0
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct HaltPolicy {
+    pub max_slot_gap: u64,                          // Slots without a heartbeat that count as a halt (0: detection off)
+    pub cooldown_secs: i64,                         // How long after a resume proofs need extra confirmations
+    pub extra_eth_confirmations: u64,               // Ethereum blocks those proofs wait for
+}
+
+/// One resumed halt, at PDA [b"halt_record", index]
+#[account]
+#[derive(InitSpace)]
+pub struct HaltRecord {
+    pub index: u64,                                 // HaltMonitor.halts when written
+    pub start_slot: u64,                            // Last heartbeat before the gap
+    pub end_slot: u64,                              // Slot of the resume
+    pub resumed_at: i64,
+    pub cooldown_until: i64,
+    pub extra_eth_confirmations: u64,
+    pub resumed_by: Pubkey,
+    pub bump: u8,                                   // PDA bump
+}
+
+/// Reissues of one operation's proof, at PDA [b"proof_reissues", operation_id].
+/// Kept beside the ProofRecord so its layout doesn't change.
+#[account]
+#[derive(InitSpace)]
+pub struct ProofReissues {
+    pub operation_id: [u8; 32],
+    pub reissues: u8,                               // Capped at MAX_PROOF_REISSUES
+    pub bump: u8,                                   // PDA bump
+}
+
+// ============================================================================
+// DAILY STATS State Structures
+// ============================================================================
+
+/// Per-day counters for dashboards, sharded by days since the Unix epoch
+#[account]
+#[derive(InitSpace)]
+pub struct DailyStats {
+    pub day: u64,                                   // Days since Unix epoch
+    pub proofs_submitted: u64,                      // submit_consensus_proof calls
+    pub proofs_confirmed: u64,                      // Ethereum submissions confirmed
+    pub proofs_failed: u64,                         // Monitoring checks with failed proofs
+    pub proofs_expired: u64,                        // Proofs expired by sweeps
+    pub consensus_reached: u64,                     // ConsensusState records created
+    pub consensus_overridden: u64,                  // ... of which by manual override
+    pub verifications_by_type: [u64; 8],            // Vault verifications, indexed by CheckConfig slot
+    pub bump: u8,                                   // PDA bump
+    pub latency_advisory: bool,                     // Set once latency is recorded: operator-reported, unverified
+    pub latency_samples: u64,                       // Plausible latency reports
+    pub latency_sum_secs: u64,                      // ... their sum (average = sum / samples)
+    pub latency_buckets: [u64; 8],                  // ... histogram by LATENCY_BUCKET_BOUNDS_SECS
+    pub latency_implausible: u64,                   // Capped reports, excluded from the above
+}
+
+// ============================================================================
+// OPERATOR FEE VAULT State Structures
+// ============================================================================
+
+/// Per-operator lamport pool that pays ProofRecord rent for sponsored submissions
+#[account]
+#[derive(InitSpace)]
+pub struct FeeVault {
+    pub operator: Pubkey,                           // Submission key this vault sponsors
+    pub daily_cap: u32,                             // Max sponsored proofs per day
+    pub day: u64,                                   // Day of sponsored_today (since Unix epoch)
+    pub sponsored_today: u32,                       // Proofs sponsored on `day`
+    pub total_sponsored: u64,                       // Lifetime sponsored proofs
+    pub total_rent_sponsored: u64,                  // Lifetime lamports paid as proof rent
+    pub bump: u8,                                   // PDA bump
+}
+
+/// Per-operator submission counters and score inputs. Only that operator's
+/// submissions and heartbeats, the council's fault rulings and the cranks
+/// write it; rollup_metrics folds the proof delta into TrinityValidator
+/// lazily, update_scores the score inputs into `score`.
+#[account]
+#[derive(InitSpace)]
+pub struct OperatorMetrics {
+    pub operator: Pubkey,                           // Submitting key
+    pub proofs_submitted: u64,                      // Lifetime proofs submitted
+    pub rolled_up: u64,                             // ... of which counted in total_proofs_submitted
+    pub last_submission: i64,                       // Unix timestamp of the latest proof
+    pub bump: u8,                                   // PDA bump
+    pub last_heartbeat: i64,                        // Latest heartbeat (or submission), 0 if none
+    pub score_inputs: ScoreComponents,              // Lifetime score inputs
+    pub scored_proofs: u64,                         // proofs_submitted at the last update_scores
+    pub scored_inputs: ScoreComponents,             // score_inputs at the last update_scores
+    pub score: u64,                                 // Decaying score, in SCORE_BPS
+    pub scored_at: i64,                             // Last update_scores (0 if never scored)
+    pub layout_version: u8,                         // Account layout (see migrations); 0 before versioning
+}
+
+/// Penalty inputs to an operator's score (see period_score)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct ScoreComponents {
+    pub latency_slots: u64,                         // Block age of each proof, capped at MAX_PROOF_SLOT_AGE
+    pub divergences: u64,                           // Force-expired operations charged to the operator
+    pub disputes_lost: u64,                         // Executed overrides charged to the operator
+    pub missed_heartbeats: u64,                     // HEARTBEAT_INTERVAL_SECS windows without a heartbeat
+}
+
+impl ScoreComponents {
+    /// Inputs accumulated since `earlier`
+    pub fn since(&self, earlier: &Self) -> Self {
+        Self {
+            latency_slots: self.latency_slots.saturating_sub(earlier.latency_slots),
+            divergences: self.divergences.saturating_sub(earlier.divergences),
+            disputes_lost: self.disputes_lost.saturating_sub(earlier.disputes_lost),
+            missed_heartbeats: self.missed_heartbeats.saturating_sub(earlier.missed_heartbeats),
+        }
+    }
+}
+
+/// Global ProofGenerated numbering, so indexers replaying logs can dedupe
+/// proofs and spot gaps. Every submission writes it.
+#[account]
+#[derive(InitSpace)]
+pub struct ProofSequence {
+    pub global_proof_seq: u64,                      // Number of the latest ProofGenerated (first is 1)
+    pub rolling_hash: [u8; 32],                     // keccak chain over operation IDs in sequence order
+    pub recent: [SequenceCheckpoint; 64],           // Rolling hash after each recent proof (RECONCILE_WINDOW), by seq % window
+    pub bump: u8,                                   // PDA bump
+}
+
+impl ProofSequence {
+    /// Number the next proof and fold its operation into the rolling hash
+    pub fn advance(&mut self, operation_id: &[u8; 32], eth_event_timestamp: i64) -> Result<()> {
+        self.global_proof_seq = self.global_proof_seq
+            .checked_add(1)
+            .ok_or(TrinityError::ProofSequenceOverflow)?;
+        self.rolling_hash = hashv(&[&self.rolling_hash, operation_id]).0;
+        self.recent[self.global_proof_seq as usize % RECONCILE_WINDOW] = SequenceCheckpoint {
+            seq: self.global_proof_seq,
+            rolling_hash: self.rolling_hash,
+            eth_event_timestamp,
+        };
+        Ok(())
+    }
+    
+    /// State after the first `count` proofs, if still within the window
+    pub fn checkpoint_at(&self, count: u64) -> Option<SequenceCheckpoint> {
+        if count == 0 {
+            return Some(SequenceCheckpoint::default());
+        }
+        let entry = self.recent[count as usize % RECONCILE_WINDOW];
+        (entry.seq == count).then_some(entry)
+    }
+    
+    /// Rolling hash after the first `count` proofs, if still within the window
+    pub fn rolling_hash_at(&self, count: u64) -> Option<[u8; 32]> {
+        self.checkpoint_at(count).map(|entry| entry.rolling_hash)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct SequenceCheckpoint {
+    pub seq: u64,
+    pub rolling_hash: [u8; 32],
+    pub eth_event_timestamp: i64,                   // Operator-reported, for the reconcile cross-check
+}
+
+/// Latest checkpoint posted back from the Ethereum bridge (see reconcile)
+#[account]
+#[derive(InitSpace)]
+pub struct EthCheckpoint {
+    pub validator: Pubkey,                          // Associated validator
+    pub count: u64,                                 // Solana proofs processed on Ethereum
+    pub rolling_hash: [u8; 32],                     // Ethereum's rolling hash over those operation IDs
+    pub eth_block: u64,                             // Ethereum block of the checkpoint
+    pub submitted_at: i64,                          // Solana timestamp of the post
+    pub bump: u8,                                   // PDA bump
+    pub eth_event_timestamp: i64,                   // Relayer-observed timestamp of the count-th event (0 if unreported)
+}
+
+/// Return type for get_proof_evidence, and the ProofArchived payload
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProofEvidence {
+    pub operation_id: [u8; 32],
+    pub proof_id: [u8; 32],
+    pub merkle_root: [u8; 32],
+    pub validator: Pubkey,
+    pub solana_block_hash: [u8; 32],
+    pub solana_block_number: u64,
+    pub solana_epoch: u64,
+    pub leader_schedule_epoch: u64,
+    pub environment_hash: [u8; 32],
+    pub timestamp: u64,
+    pub submitted_to_ethereum: bool,
+    pub expired_at: u64,
+    pub submission_hash: [u8; 32],
+}
+
+/// Entry of get_operator_ranking
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OperatorScore {
+    pub operator: Pubkey,
+    pub score: u64,                                 // Decaying score, in SCORE_BPS
+    pub scored_at: i64,                             // 0 if never scored
+}
+
+/// Return type for get_operator_liveness
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct OperatorLiveness {
+    pub operator: Pubkey,
+    pub vault_balance: u64,                         // Spendable lamports (above the vault's rent)
+    pub proof_rent: u64,                            // Rent of the largest possible ProofRecord
+    pub sponsorable_proofs: u64,                    // vault_balance / proof_rent
+    pub daily_cap: u32,
+    pub remaining_today: u32,
+    pub total_sponsored: u64,
+}
+
+/// Return type for get_program_info
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ProgramInfo {
+    pub version: String,                            // Crate version
+    pub git_commit: String,                         // GIT_COMMIT, "unknown" if not embedded
+}
+
+/// Return type for consensus_status
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ConsensusOutcome {
+    Pending {
+        confirmations: u8,                          // Solana proof + its Ethereum confirmation
+        required: u8,                               // CONSENSUS_THRESHOLD
+    },
+    Reached {
+        chains: u8,                                 // Agreeing chains at finalization
+        finalized_at: i64,
+    },
+    Conflict {
+        override_approvals: u8,                     // Approvals on the open proposal (0 once executed)
+        overridden: bool,                           // Consensus was forced by the council
+    },
+    Failed {
+        failed_at: i64,                             // Force-expired by the council
+    },
+}
+
+// ============================================================================
+// DISASTER EXPORT / IMPORT State Structures
+// ============================================================================
+
+/// Progress of the one disaster export of a validator's state, per
+/// ExportKind. Accounts are exported in ascending address order, so
+/// everything up to a kind's cursor has been exported.
+#[account]
+#[derive(InitSpace)]
+pub struct StateExport {
+    pub validator: Pubkey,                          // Validator being exported
+    pub guardian: Pubkey,                           // Council member who co-signed begin_state_export
+    pub started_at: i64,                            // Export start (validator deactivated)
+    pub cursors: [Pubkey; 8],                       // Last exported address per kind (EXPORT_KIND_COUNT)
+    pub exported: [u64; 8],                         // Accounts exported per kind
+    pub digests: [[u8; 32]; 8],                     // Hash chain over (address, data) per kind (state_export::chain)
+    pub bump: u8,                                   // PDA bump
+}
+
+/// Progress of an import on a new deployment; its digests match the source
+/// StateExport's once every page has been imported in order
+#[account]
+#[derive(InitSpace)]
+pub struct StateImport {
+    pub authority: Pubkey,                          // Old deployment's authority, signing every import
+    pub source_validator: Pubkey,                   // Validator address on the old deployment
+    pub imported: [u64; 8],                         // Accounts imported per kind (EXPORT_KIND_COUNT)
+    pub digests: [[u8; 32]; 8],                     // Hash chain over (source address, exported data) per kind
+    pub bump: u8,                                   // PDA bump
+}
+
+// ============================================================================
+// ACCOUNT SIZE Audit
+// ============================================================================
+
+// Worst-case INIT_SPACE (discriminator excluded) of every account created per
+// operation, pinned so that growth is a reviewed change rather than a silent
+// rent increase. ProofRecords are allocated at their serialized length (see
+// create_proof_account), so for them this is a ceiling, not the typical cost.
+const _: () = assert!(ProofRecord::INIT_SPACE == 1039);
+const _: () = assert!(VaultVerification::INIT_SPACE == 155);
+const _: () = assert!(ConsensusState::INIT_SPACE == 536);
+const _: () = assert!(FastProof::INIT_SPACE == 122);
+const _: () = assert!(OperatorMetrics::INIT_SPACE == 154);
+const _: () = assert!(BridgedTotal::INIT_SPACE == 41);
+const _: () = assert!(ExpectedOperation::INIT_SPACE == 74);
+const _: () = assert!(ConsensusMirror::INIT_SPACE == 74);
+
+// ============================================================================
+// Enums
+// ============================================================================
+
+/// Kind of operation a proof or verification covers, identified by a u16
+/// code. On the wire this is the Borsh enum tag: named types keep one-byte
+/// tags (so records written before codes existed decode unchanged) and any
+/// other code travels as Unknown(code). Named types added later go after
+/// Unknown. Which codes are accepted is up to CheckConfig's registry.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
+pub enum OperationType {
+    VaultWithdrawal,        // Code 0
+    HTLCSwap,               // Code 1
+    EmergencyRecovery,      // Code 2
+    CrossChainTransfer,     // Code 3
+    VaultRebalance,         // Code 4
+    FeeSettlement,          // Code 5
+    Unknown(u16),           // Any code without a named variant
+}
+
+impl OperationType {
+    pub fn code(&self) -> u16 {
+        match self {
+            OperationType::VaultWithdrawal => 0,
+            OperationType::HTLCSwap => 1,
+            OperationType::EmergencyRecovery => 2,
+            OperationType::CrossChainTransfer => 3,
+            OperationType::VaultRebalance => 4,
+            OperationType::FeeSettlement => 5,
+            OperationType::Unknown(code) => *code,
+        }
+    }
+    
+    pub fn from_code(code: u16) -> Self {
+        match code {
+            0 => OperationType::VaultWithdrawal,
+            1 => OperationType::HTLCSwap,
+            2 => OperationType::EmergencyRecovery,
+            3 => OperationType::CrossChainTransfer,
+            4 => OperationType::VaultRebalance,
+            5 => OperationType::FeeSettlement,
+            code => OperationType::Unknown(code),
+        }
+    }
+    
+    /// Unknown never stands in for a named type, so `==` against a named
+    /// variant (e.g. the CrossChainTransfer bridge cap) can't be sidestepped
+    pub fn is_canonical(&self) -> bool {
+        *self == Self::from_code(self.code())
+    }
+}
+
+/// Revealed HTLC secret attached to a proof; keccak256(preimage) == hashlock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct HtlcPreimage {
+    pub hashlock: [u8; 32],
+    pub preimage: [u8; 32],
+}
+
+/// How a Merkle path folds onto its leaf. Sorted pairs need no flags;
+/// position-aware trees (the Ethereum side's) need the sibling's side at
+/// every level. Records written before modes existed decode as Sorted.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum MerkleMode {
+    Sorted,                 // calculate_merkle_root; no flags
+    Positional,             // calculate_positional_merkle_root; one sibling_is_left flag per level
+}
+
+impl MerkleMode {
+    pub fn root(self, proof: &[[u8; 32]], sibling_is_left: &[bool], leaf: &[u8; 32]) -> [u8; 32] {
+        match self {
+            MerkleMode::Sorted => calculate_merkle_root(proof, leaf),
+            MerkleMode::Positional => calculate_positional_merkle_root(proof, sibling_is_left, leaf),
+        }
+    }
+}
+
+/// Account kinds carried by a disaster export, in import order: each kind's
+/// index slots into the StateExport and StateImport ledgers
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum ExportKind {
+    Validator,              // TrinityValidator; imported first, claims the new deployment
+    CheckConfig,
+    DisputeCouncil,
+    FeeVault,               // Operator registrations
+    OperatorMetrics,
+    ProofSequence,
+    ConsensusState,         // Trusted roots
+    ProofRecord,            // Unconfirmed, unexpired proofs only
+}
+
+/// Consensus rules that run in shadow mode before they are enforced
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum CheckId {
+    AuthoritySignature,     // Proof submitted by the validator authority (or a sponsored operator)
+    BlockFreshness,         // Referenced block at most MAX_PROOF_SLOT_AGE slots old
+    NonEmptyProof,          // Merkle path has at least one level
+}
+
+/// Monitor: a failing rule emits ShadowViolation and the instruction proceeds
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum Enforcement {
+    Monitor,
+    Enforce,
+}
+
+/// High-frequency monitoring check types
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum MonitoringCheckType {
+    PeriodicScan,           // Regular interval scan
+    EventTriggered,         // Triggered by cross-chain event
+    FastPath,               // Urgent fast-path verification
+    RecoveryCheck,          // Emergency recovery monitoring
+}
+
+/// Monitoring thresholds (see RiskThresholds), indexing RiskMetrics.raised_at
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum RiskFlag {
+    LargeOperation,         // A single operation above max_operation_amount
+    OperatorConcentration,  // One operator submitted over max_operator_share_bps of the window
+    SlowConsensus,          // Confirmed or finalized more than max_consensus_secs after its proof
+}
+
+impl RiskFlag {
+    /// The flag's bit in RiskMetrics.latched
+    pub fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// Where a risk threshold was crossed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RiskContext {
+    pub operation_id: [u8; 32],
+    pub operator: Pubkey,                           // Submitter, relayer or finalizer
+    pub observed: u64,
+    pub threshold: u64,
+    pub raised_at: i64,
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct ProofGenerated {
+    pub operation_id: [u8; 32],
+    pub proof_id: [u8; 32],
+    pub merkle_root: [u8; 32],
+    pub solana_block_hash: [u8; 32],
+    pub solana_block_number: u64,
+    pub operation_type: OperationType,
+    pub hashlock: [u8; 32],                         // Zero unless an HTLC preimage was attached
+    pub preimage: [u8; 32],
+    pub timestamp: u64,
+    pub global_proof_seq: u64,                      // +1 per proof across all operators; a gap is a missed event
+    pub submission_hash: [u8; 32],                  // keccak of the submitted instruction args
+    pub observed_latency_seconds: u64,              // Advisory: from the operator-reported Ethereum timestamp
+    pub latency_implausible: bool,                  // Latency was capped at MAX_PLAUSIBLE_LATENCY_SECS
+}
+
+#[event]
+pub struct OperationVerified {
+    pub vault_id: u64,
+    pub vault_owner: Pubkey,
+    pub operation_type: OperationType,
+    pub amount: u64,
+    pub user: Pubkey,
+    pub verification_hash: [u8; 32],
+}
+
+// High-frequency monitoring events
+#[event]
+pub struct MonitoringCheckRecorded {
+    pub validator: Pubkey,
+    pub check_type: MonitoringCheckType,
+    pub timestamp: u64,
+    pub slot: u64,
+    pub latency_ms: u64,
+    pub operation_count: u32,
+    pub proof_generated: bool,
+    pub slots_since_last_check: u64,
+}
+
+#[event]
+pub struct FastProofGenerated {
+    pub vault_id: u64,
+    pub operation_hash: [u8; 32],
+    pub verification_hash: [u8; 32],
+    pub urgency_level: u8,
+    pub timestamp: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ProofExpired {
+    pub operation_id: [u8; 32],
+    pub validator: Pubkey,
+    pub expired_at: u64,
+}
+
+/// A proof record's forensic evidence, emitted as sweep_expired closes it
+#[event]
+pub struct ProofArchived {
+    pub evidence: ProofEvidence,
+    pub closed_at: u64,
+}
+
+/// Summary of a sweep_expired crank run
+#[event]
+pub struct ProofsSwept {
+    pub cranker: Pubkey,
+    pub examined: u8,
+    pub expired: u8,
+    pub closed: u8,
+    pub skipped: u8,
+    pub bounty_paid: u64,
+    pub timestamp: u64,
+}
+
+/// Summary of a rollup_metrics crank run
+#[event]
+pub struct MetricsRolledUp {
+    pub operators: u8,
+    pub proofs_added: u64,
+    pub total_proofs_submitted: u64,
+}
+
+/// One operator's score update in an update_scores crank run
+#[event]
+pub struct OperatorScored {
+    pub operator: Pubkey,
+    pub period_score: u64,
+    pub score: u64,
+    pub epochs: u64,
+}
+
+/// SLA breach alert - emitted when latency exceeds target
+#[event]
+pub struct SlaBreachAlert {
+    pub validator: Pubkey,
+    pub latency_ms: u64,
+    pub target_latency_ms: u64,
+    pub breach_severity: u8,  // 1 = warning (>5s), 2 = critical (>10s)
+    pub timestamp: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct RpcUrlUpdated {
+    pub validator: Pubkey,
+    pub previous_url: String,
+    pub new_url: String,
+    pub timestamp: u64,
+}
+
+/// Final state of a ConsensusMirror being closed
+#[event]
+pub struct ConsensusMirrorClosed {
+    pub schema_version: u8,
+    pub operation_id: [u8; 32],
+    pub merkle_root: [u8; 32],
+    pub consensus_at: i64,
+    pub chain_bitmap: u8,
+    pub closed_by: Pubkey,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub kind: AccountKind,
+    pub from: u8,
+    pub to: u8,
+}
+
+#[event]
+pub struct RewardsDistributed {
+    pub operation_id: [u8; 32],
+    pub recipients: u8,
+    pub paid: u64,                                  // Sum of the stake-weighted shares
+    pub dust: u64,                                  // Rounding remainder, left in the vault
+}
+
+#[event]
+pub struct RewardSurplusSwept {
+    pub treasury: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,                             // Left in the vault
+}
+
+#[event]
+pub struct StakeBonded {
+    pub operator: Pubkey,
+    pub amount: u64,
+    pub bonded: u64,                                // Operator's total after the bond
+}
+
+#[event]
+pub struct StakeUnbonded {
+    pub operator: Pubkey,
+    pub amount: u64,
+    pub bonded: u64,                                // Operator's total after the withdrawal
+}
+
+#[event]
+pub struct OperationAttested {
+    pub operation_id: [u8; 32],
+    pub operator: Pubkey,
+    pub stake: u64,
+    pub stake_weight: u64,                          // Operation's attested stake including this one
+    pub contributors: u8,
+    pub displaced: Option<Pubkey>,                  // Smallest-stake attester this one replaced, if the list was full
+}
+
+#[event]
+pub struct OperationPauseSet {
+    pub operation_id: [u8; 32],
+    pub paused: bool,
+    pub authority: Pubkey,
+}
+
+/// Final counters of a DailyStats shard being closed
+#[event]
+pub struct DailyStatsClosed {
+    pub day: u64,
+    pub proofs_submitted: u64,
+    pub proofs_confirmed: u64,
+    pub proofs_failed: u64,
+    pub proofs_expired: u64,
+    pub consensus_reached: u64,
+    pub consensus_overridden: u64,
+    pub verifications_by_type: [u64; 8],
+    pub latency_samples: u64,
+    pub latency_sum_secs: u64,
+    pub latency_buckets: [u64; 8],
+    pub latency_implausible: u64,
+}
+
+/// Authoritative relayer trigger: organic consensus reached for an operation
+#[event]
+pub struct ConsensusFinalized {
+    pub operation_id: [u8; 32],
+    pub merkle_root: [u8; 32],
+    pub chains: Vec<u8>,                            // Agreeing chain IDs (ascending)
+    pub finalized_at: i64,
+}
+
+/// A monitored rule failed; the instruction went ahead anyway
+#[event]
+pub struct ShadowViolation {
+    pub check: CheckId,
+    pub operation_id: [u8; 32],
+    pub error_code: u32,                            // Error the rule maps to once enforced
+    pub context: String,                            // `key=value` pairs, as in error_ctx lines
+}
+
+/// An operation above its type's amount limit was rejected. Emitted just
+/// before the error, so it lives in the failed transaction's logs.
+#[event]
+pub struct AmountLimitExceeded {
+    pub operation_type: OperationType,
+    pub amount: u64,
+    pub max_amount: u64,
+}
+
+#[event]
+pub struct AmountLimitQueued {
+    pub operation_type: OperationType,
+    pub max_amount: u64,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct AmountLimitApplied {
+    pub operation_type: OperationType,
+    pub max_amount: u64,
+}
+
+#[event]
+pub struct ConsensusWindowSet {
+    pub operation_type: OperationType,
+    pub consensus_window_secs: u64,
+}
+
+#[event]
+pub struct SubmissionConfirmed {
+    pub operation_id: [u8; 32],
+    pub chain_id: u64,
+    pub tx_hash: [u8; 32],
+    pub block_number: u64,
+    pub confirmations: u8,                          // Chains confirmed so far, including this one
+}
+
+#[event]
+pub struct BridgeCapQueued {
+    pub max_bridged_per_user: u64,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct BridgeCapApplied {
+    pub max_bridged_per_user: u64,
+}
+
+#[event]
+pub struct OperationTypeQueued {
+    pub code: u16,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct OperationTypeEnabled {
+    pub code: u16,
+    pub slot: u8,                                   // CheckConfig registry slot
+}
+
+#[event]
+pub struct EnforcementQueued {
+    pub check: CheckId,
+    pub enforcement: Enforcement,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct EnforcementApplied {
+    pub check: CheckId,
+    pub enforcement: Enforcement,
+}
+
+/// Loud, public signal that the council wants to bypass organic consensus
+#[event]
+pub struct OverrideProposed {
+    pub operation_id: [u8; 32],
+    pub justification_hash: [u8; 32],
+    pub proposer: Pubkey,
+    pub approvals: u8,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct OverrideExecuted {
+    pub operation_id: [u8; 32],
+    pub justification_hash: [u8; 32],
+    pub executed_at: i64,
+}
+
+#[event]
+pub struct OverrideCancelled {
+    pub operation_id: [u8; 32],
+    pub cancelled_by: Pubkey,
+}
+
+#[event]
+pub struct ConsensusFailed {
+    pub operation_id: [u8; 32],
+    pub forced_by: Pubkey,
+    pub approvals: u8,
+    pub failed_at: i64,
+}
+
+#[event]
+pub struct FeeVaultFunded {
+    pub operator: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub vault_balance: u64,
+}
+
+#[event]
+pub struct FeeVaultReclaimed {
+    pub operator: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub vault_balance: u64,
+}
+
+#[event]
+pub struct ReconciliationOk {
+    pub eth_block: u64,
+    pub count: u64,
+    pub rolling_hash: [u8; 32],
+}
+
+#[event]
+pub struct ReconciliationMismatch {
+    pub eth_block: u64,
+    pub expected_count: u64,                        // Ethereum checkpoint
+    pub actual_count: u64,                          // Local count compared against
+    pub expected: [u8; 32],
+    pub actual: [u8; 32],
+}
+
+/// Advisory: the operator's and the relayer's timestamps for the same
+/// Ethereum event differ by more than LATENCY_REPORT_TOLERANCE_SECS
+#[event]
+pub struct LatencyReportMismatch {
+    pub eth_block: u64,
+    pub count: u64,                                 // Proof whose timestamps were compared
+    pub operator_reported: i64,
+    pub relayer_reported: i64,
+}
+
+#[event]
+pub struct ReconciliationAcknowledged {
+    pub acknowledged_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OperationCommitted {
+    pub operation_id: [u8; 32],
+    pub committed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProofRentSponsored {
+    pub operator: Pubkey,
+    pub operation_id: [u8; 32],
+    pub rent: u64,
+    pub sponsored_today: u32,
+    pub vault_balance: u64,
+}
+
+#[event]
+pub struct BuildAttested {
+    pub build_hash: [u8; 32],
+    pub source_commit: [u8; SOURCE_COMMIT_LEN],
+    pub upgrade_slot: u64,
+    pub slot: u64,
+}
+
+/// The deployed program has gone its attestation window without one
+#[event]
+pub struct UnattestedBuild {
+    pub upgrade_slot: u64,
+    pub attested_upgrade_slot: u64,                 // Deployment of the latest attestation
+    pub slots_unattested: u64,
+}
+
+/// A risk threshold newly crossed; its flag stays latched on RiskMetrics
+/// (and isn't raised again) until acknowledge_risk_flag
+#[event]
+pub struct RiskFlagRaised {
+    pub flag: RiskFlag,
+    pub context: RiskContext,
+}
+
+#[event]
+pub struct RiskFlagAcknowledged {
+    pub flag: RiskFlag,
+    pub raised_at: i64,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct RiskThresholdsQueued {
+    pub thresholds: RiskThresholds,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct RiskThresholdsApplied {
+    pub thresholds: RiskThresholds,
+}
+
+/// A heartbeat found the gap since the previous one over max_slot_gap;
+/// submissions are refused until resume_after_halt
+#[event]
+pub struct ChainHaltDetected {
+    pub halt_start_slot: u64,
+    pub detected_slot: u64,
+}
+
+#[event]
+pub struct HaltResumed {
+    pub index: u64,                                 // HaltRecord written
+    pub start_slot: u64,
+    pub end_slot: u64,
+    pub cooldown_until: i64,
+    pub extra_eth_confirmations: u64,
+    pub authority: Pubkey,
+}
+
+/// An unconfirmed proof was rewritten against a newer block (see reissue_proof)
+#[event]
+pub struct ProofReissued {
+    pub operation_id: [u8; 32],
+    pub previous_proof_id: [u8; 32],
+    pub proof_id: [u8; 32],
+    pub solana_block_number: u64,
+    pub reissues: u8,                               // Including this one
+}
+
+#[event]
+pub struct StateExportStarted {
+    pub validator: Pubkey,
+    pub authority: Pubkey,
+    pub guardian: Pubkey,
+    pub started_at: i64,
+}
+
+/// One exported account, as import_state takes it on the new deployment
+#[event]
+pub struct StateExported {
+    pub format_version: u8,                         // EXPORT_FORMAT_VERSION
+    pub kind: ExportKind,
+    pub index: u64,                                 // Position within the kind's export
+    pub source: Pubkey,                             // Address on the exporting deployment
+    pub data: Vec<u8>,                              // Full account data, discriminator included
+}
+
+#[event]
+pub struct StateImported {
+    pub kind: ExportKind,
+    pub index: u64,                                 // Position within the kind's import
+    pub source: Pubkey,
+    pub account: Pubkey,                            // Address on this deployment
+    pub digest: [u8; 32],                           // Kind's import digest including this account
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Stable cross-chain identifier for a logical proof, independent of its PDA
+fn derive_proof_id(operation_id: &[u8; 32], merkle_root: &[u8; 32], validator: &Pubkey) -> [u8; 32] {
+    hashv(&[operation_id, merkle_root, validator.as_ref()]).0
+}
+
+/// Arguments shared by submit_consensus_proof and submit_with_sponsored_rent.
+/// Field order matches both instructions, so its Borsh encoding is byte for
+/// byte the instruction data after the 8-byte discriminator.
+#[derive(AnchorSerialize)]
+struct ProofInputs {
+    operation_id: [u8; 32],
+    merkle_proof: Vec<[u8; 32]>,
+    solana_block_hash: [u8; 32],
+    solana_tx_signature: [u8; 64],
+    solana_block_number: u64,
+    operation_type: OperationType,
+    amount: u64,
+    htlc: Option<HtlcPreimage>,
+    eth_event_timestamp: i64,
+    merkle_mode: MerkleMode,
+    sibling_is_left: Vec<bool>,
+}
+
+/// A rule that failed: the error it maps to and its error_ctx fields
+pub struct Violation {
+    pub error: TrinityError,
+    pub fields: Vec<(&'static str, String)>,
+}
+
+/// Structured outcome of one proof validation
+pub type CheckResult = std::result::Result<(), Violation>;
+
+/// Like `require_ctx!`, but hands a CheckResult to `gate` instead of
+/// failing; the context is only formatted if the check fails
+macro_rules! check_ctx {
+    ($cond:expr, $err:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        if $cond {
+            Ok(())
+        } else {
+            Err(Violation { error: $err, fields: vec![$((stringify!($key), ($value).to_string())),*] })
+        }
+    };
+}
+
+/// Which rule a validation belongs to: core rules always reject
+#[derive(Clone, Copy)]
+enum Rule {
+    Core,
+    Shadow(CheckId),
+}
+
+/// The one enforcement gate every proof validation goes through. A failing
+/// core or enforced rule logs its error_ctx line and rejects; a failing
+/// monitored rule emits ShadowViolation and lets the instruction proceed.
+fn gate(
+    enforcement: &[Enforcement; SHADOW_CHECK_COUNT],
+    operation_id: &[u8; 32],
+    rule: Rule,
+    result: CheckResult,
+) -> Result<()> {
+    let Err(violation) = result else {
+        return Ok(());
+    };
+    match rule {
+        Rule::Shadow(check) if enforcement[check as usize] == Enforcement::Monitor => {
+            let context = violation
+                .fields
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join(" ");
+            msg!("🕶️ Shadow violation {:?}: {}", check, context);
+            emit!(ShadowViolation {
+                check,
+                operation_id: *operation_id,
+                error_code: u32::from(violation.error),
+                context,
+            });
+            Ok(())
+        }
+        _ => reject(Err(violation)),
+    }
+}
+
+/// Fail on a violation, logging its error_ctx line first
+fn reject(result: CheckResult) -> Result<()> {
+    let Err(violation) = result else {
+        return Ok(());
+    };
+    msg!(
+        "{}",
+        error_ctx::format_error_ctx(u32::from(violation.error), &violation.error.name(), &violation.fields)
+    );
+    Err(error!(violation.error))
+}
+
+/// An Arbitrum RPC URL the relayer can use: at most MAX_RPC_URL_LEN bytes,
+/// https, with a host, and free of control characters. Catches config typos
+/// at write time rather than when the relayer silently fails.
+fn check_rpc_url(url: &str) -> Result<()> {
+    require_ctx!(
+        url.len() <= MAX_RPC_URL_LEN,
+        TrinityError::RpcUrlTooLong,
+        len = url.len(),
+        max = MAX_RPC_URL_LEN,
+    );
+    require_ctx!(is_well_formed_rpc_url(url), TrinityError::InvalidRpcUrl, len = url.len());
+    Ok(())
+}
+
+fn is_well_formed_rpc_url(url: &str) -> bool {
+    url.strip_prefix("https://").is_some_and(|rest| !rest.is_empty()) && !url.chars().any(char::is_control)
+}
+
+/// Single-operation amount limit for a type; a violation also emits the
+/// AmountLimitExceeded alert
+fn check_amount_limit(operation_type: &OperationType, amount: u64, max_amount: u64) -> CheckResult {
+    if amount > max_amount {
+        emit!(AmountLimitExceeded { operation_type: operation_type.clone(), amount, max_amount });
+    }
+    check_ctx!(
+        amount <= max_amount,
+        TrinityError::AmountExceedsTypeLimit,
+        operation_type = operation_type.code(),
+        amount = amount,
+        max = max_amount,
+    )
+}
+
+/// Positional paths carry exactly one side flag per level, sorted ones none
+fn check_merkle_path(mode: MerkleMode, proof: &[[u8; 32]], sibling_is_left: &[bool]) -> CheckResult {
+    let flags = match mode {
+        MerkleMode::Sorted => 0,
+        MerkleMode::Positional => proof.len(),
+    };
+    check_ctx!(
+        sibling_is_left.len() == flags,
+        TrinityError::InvalidMerklePath,
+        mode = format!("{:?}", mode),
+        levels = proof.len(),
+        flags = sibling_is_left.len(),
+    )
+}
+
+/// A queued rule or limit change exists and its timelock has elapsed
+fn require_queued_change_ready(executable_at: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require_ctx!(executable_at != 0, TrinityError::NoQueuedEnforcement);
+    require_ctx!(
+        now >= executable_at,
+        TrinityError::EnforcementTimelockActive,
+        executable_at = executable_at,
+        now = now,
+    );
+    Ok(())
+}
+
+/// A program account at a seeds-pinned address, or None while it doesn't exist
+fn load_initialized<T: AccountDeserialize>(info: &AccountInfo) -> Result<Option<T>> {
+    if info.owner != &crate::ID {
+        return Ok(None);
+    }
+    Ok(Some(T::try_deserialize(&mut &info.try_borrow_data()?[..])?))
+}
+
+/// Rules in force: all Monitor and no amount limits until the CheckConfig is initialized
+fn load_check_config(info: &AccountInfo) -> Result<CheckConfig> {
+    let checks = load_initialized::<CheckConfig>(info)?;
+    require_ctx!(
+        checks.iter().all(|checks| checks.is_current()),
+        TrinityError::AccountNeedsMigration,
+        account = info.key(),
+    );
+    Ok(checks.unwrap_or_else(CheckConfig::unconfigured))
+}
+
+/// Reject a submission for an operation frozen by set_operation_pause. Most
+/// operations have no ConsensusState before they are finalized.
+fn require_operation_unpaused(info: &AccountInfo) -> Result<()> {
+    let consensus = load_initialized::<ConsensusState>(info)?;
+    require_ctx!(
+        consensus.iter().all(|consensus| consensus.is_current()),
+        TrinityError::AccountNeedsMigration,
+        account = info.key(),
+    );
+    require_ctx!(
+        !consensus.is_some_and(|consensus| consensus.operation_paused),
+        TrinityError::OperationPaused,
+        consensus = info.key(),
+    );
+    Ok(())
+}
+
+/// Refuse to attest a withdrawal from a vesting schedule while the vesting
+/// program has a beneficiary recovery (its emergency path) pending on it, so
+/// the two can't drain the schedule at once. The proposal is located by its
+/// PDA, [b"recovery", vault], and is pending while that account exists.
+fn require_no_pending_emergency(link: &AccountInfo, vault: &AccountInfo, proposal: Option<&AccountInfo>) -> Result<()> {
+    let Some(link) = load_initialized::<VestingLink>(link)? else {
+        return Ok(());
+    };
+    if link.vesting_program == Pubkey::default() || *vault.owner != link.vesting_program {
+        return Ok(());
+    }
+    let (expected, _) = Pubkey::find_program_address(&[b"recovery", vault.key.as_ref()], &link.vesting_program);
+    let proposal = proposal.filter(|proposal| proposal.key() == expected);
+    require_ctx!(
+        proposal.is_some(),
+        TrinityError::EmergencyProposalRequired,
+        vault = vault.key(),
+        expected = expected,
+    );
+    require_ctx!(
+        proposal.is_some_and(|proposal| *proposal.owner != link.vesting_program),
+        TrinityError::ConflictingEmergencyPending,
+        vault = vault.key(),
+        proposal = expected,
+    );
+    Ok(())
+}
+
+/// Mark a committed operation as proven. Uncommitted operations, and those
+/// that already have a proof, are rejected.
+fn consume_expected_operation(info: &AccountInfo, operation_id: &[u8; 32]) -> Result<()> {
+    let mut expected = load_initialized::<ExpectedOperation>(info)?.unwrap_or_default();
+    require_ctx!(
+        expected.pending && expected.operation_id == *operation_id,
+        TrinityError::OperationNotFound,
+        expected_operation = info.key(),
+    );
+    
+    expected.pending = false;
+    let mut data = info.try_borrow_mut_data()?;
+    expected.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+/// Thresholds `observation` crosses, as (flag, observed, threshold)
+pub fn crossed_risk_flags(thresholds: &RiskThresholds, observation: &RiskObservation) -> Vec<(RiskFlag, u64, u64)> {
+    [
+        (RiskFlag::LargeOperation, observation.amount, thresholds.max_operation_amount),
+        (RiskFlag::OperatorConcentration, observation.operator_share_bps, thresholds.max_operator_share_bps),
+        (RiskFlag::SlowConsensus, observation.consensus_secs, thresholds.max_consensus_secs),
+    ]
+    .into_iter()
+    .filter_map(|(flag, observed, threshold)| {
+        observed.filter(|observed| *observed > threshold).map(|observed| (flag, observed, threshold))
+    })
+    .collect()
+}
+
+/// Evaluate the risk thresholds against what `observe` reports for an
+/// operation, latching each newly crossed flag with a RiskFlagRaised. Nothing
+/// is evaluated until the RiskMetrics is initialized.
+fn observe_risk(
+    info: &AccountInfo,
+    operation_id: [u8; 32],
+    operator: Pubkey,
+    observe: impl FnOnce(&mut RiskMetrics) -> RiskObservation,
+) -> Result<()> {
+    let Some(mut metrics) = load_initialized::<RiskMetrics>(info)? else {
+        return Ok(());
+    };
+    let now = Clock::get()?.unix_timestamp;
+    let observation = observe(&mut metrics);
+    for (flag, observed, threshold) in crossed_risk_flags(&metrics.thresholds, &observation) {
+        if metrics.is_latched(flag) {
+            continue;
+        }
+        metrics.latched |= flag.bit();
+        metrics.raised_at[flag as usize] = now;
+        emit!(RiskFlagRaised {
+            flag,
+            context: RiskContext { operation_id, operator, observed, threshold, raised_at: now },
+        });
+        msg!("🚩 {:?}: {} over {} (operation {:?})", flag, observed, threshold, operation_id);
+    }
+    
+    let mut data = info.try_borrow_mut_data()?;
+    metrics.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+/// Record a heartbeat (or submission) slot on the HaltMonitor. A heartbeat
+/// that latches a halt emits ChainHaltDetected; a submission that finds one
+/// fails with ResumeRequired, leaving the monitor as it was so every later
+/// submission fails too. Nothing happens until the HaltMonitor is initialized.
+fn observe_halt(info: &AccountInfo, submission: bool) -> Result<()> {
+    let Some(mut monitor) = load_initialized::<HaltMonitor>(info)? else {
+        return Ok(());
+    };
+    let slot = Clock::get()?.slot;
+    let already_halted = monitor.halted_since.is_some();
+    let halted = monitor.observe_slot(slot);
+    let halt_start_slot = monitor.halted_since.unwrap_or_default();
+    require_ctx!(
+        !(submission && halted),
+        TrinityError::ResumeRequired,
+        halt_start_slot = halt_start_slot,
+        slot = slot,
+    );
+    if halted && !already_halted {
+        emit!(ChainHaltDetected { halt_start_slot, detected_slot: slot });
+        msg!("⛓️ Chain halt: no heartbeat between slots {} and {}", halt_start_slot, slot);
+    }
+    
+    let mut data = info.try_borrow_mut_data()?;
+    monitor.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+/// Refuse the Ethereum confirmation of a proof generated in a post-halt
+/// cooldown until its extra confirmations have had time to land, counting
+/// one per ETHEREUM_BLOCK_SECS since the proof
+fn require_halt_confirmations(info: &AccountInfo, proof_record: &Account<ProofRecord>) -> Result<()> {
+    let Some(monitor) = load_initialized::<HaltMonitor>(info)? else {
+        return Ok(());
+    };
+    let generated_at = proof_record.timestamp as i64;
+    let required = monitor.required_confirmations(generated_at);
+    let elapsed = Clock::get()?.unix_timestamp.saturating_sub(generated_at).max(0) as u64;
+    require_ctx!(
+        elapsed / ETHEREUM_BLOCK_SECS >= required,
+        TrinityError::HaltConfirmationsPending,
+        proof = proof_record.key(),
+        confirmations = elapsed / ETHEREUM_BLOCK_SECS,
+        required = required,
+    );
+    Ok(())
+}
+
+/// Checks and bookkeeping shared by every confirmation of a live proof. The
+/// first one, on whichever chain, confirms the proof: it sets
+/// submitted_to_ethereum and ethereum_tx_hash and is the one counted in
+/// DailyStats and the consensus latency.
+fn record_confirmation(
+    accounts: &mut ConfirmSubmission,
+    stats_bump: u8,
+    operation_id: [u8; 32],
+    tx_hash: [u8; 32],
+) -> Result<()> {
+    let proof_record = &mut accounts.proof_record;
+    require_ctx!(
+        !proof_record.expired,
+        TrinityError::ProofExpired,
+        proof = proof_record.key(),
+        expired_at = proof_record.expired_at,
+    );
+    // A confirmed proof always records its Ethereum transaction (see invariants)
+    require_ctx!(
+        tx_hash != [0u8; 32],
+        TrinityError::MissingEthereumTxHash,
+        proof = proof_record.key(),
+    );
+    require_halt_confirmations(&accounts.halt_monitor, proof_record)?;
+    
+    let stats = &mut accounts.daily_stats;
+    touch_daily_stats(stats, stats_bump);
+    if proof_record.submitted_to_ethereum {
+        return Ok(());
+    }
+    proof_record.submitted_to_ethereum = true;
+    proof_record.ethereum_tx_hash = tx_hash;
+    stats.proofs_confirmed = stats.proofs_confirmed.saturating_add(1);
+    
+    let consensus_secs = (Clock::get()?.unix_timestamp as u64).saturating_sub(proof_record.timestamp);
+    observe_risk(&accounts.risk_metrics, operation_id, accounts.authority.key(), |_| RiskObservation {
+        consensus_secs: Some(consensus_secs),
+        ..RiskObservation::default()
+    })
+}
+
+/// Validate a submission and build its ProofRecord (not yet stored)
+#[allow(clippy::too_many_arguments)]
+fn build_proof_record(
+    validator: &Account<TrinityValidator>,
+    rules: &CheckConfig,
+    slot_hashes: &AccountInfo,
+    instructions: &AccountInfo,
+    proof_key: Pubkey,
+    submitter: Pubkey,
+    submitter_authorized: bool,
+    inputs: ProofInputs,
+) -> Result<ProofRecord> {
+    let operation_id = inputs.operation_id;
+    let check = |rule, result| gate(&rules.enforcement, &operation_id, rule, result);
+    
+    check(Rule::Core, check_ctx!(validator.is_active, TrinityError::ValidatorNotActive, validator = validator.key()))?;
+    check(
+        Rule::Core,
+        check_ctx!(
+            inputs.merkle_proof.len() <= MAX_MERKLE_PROOF_LEN,
+            TrinityError::MerkleProofTooLong,
+            len = inputs.merkle_proof.len(),
+            max = MAX_MERKLE_PROOF_LEN,
+        ),
+    )?;
+    check(Rule::Core, check_merkle_path(inputs.merkle_mode, &inputs.merkle_proof, &inputs.sibling_is_left))?;
+    // Before any hashing or sysvar parsing, so an oversized batch fails cheaply
+    let batch = submission_batch(instructions)?;
+    let budget = proof_budget(&batch);
+    check(
+        Rule::Core,
+        check_ctx!(
+            budget <= validator.max_proof_budget,
+            TrinityError::ProofBudgetExceeded,
+            budget = budget,
+            max = validator.max_proof_budget,
+            submissions = batch.len(),
+            levels = inputs.merkle_proof.len(),
+        ),
+    )?;
+    let submission_hash = hashv(&[&inputs.try_to_vec()?]).0;
+    check(
+        Rule::Shadow(CheckId::NonEmptyProof),
+        check_ctx!(!inputs.merkle_proof.is_empty(), TrinityError::EmptyMerkleProof, len = 0),
+    )?;
+    check(
+        Rule::Shadow(CheckId::AuthoritySignature),
+        check_ctx!(
+            submitter_authorized,
+            TrinityError::UnauthorizedSubmitter,
+            submitter = submitter,
+            authority = validator.authority,
+        ),
+    )?;
+    
+    // SECURITY: The referenced block must be recent and its hash must match
+    // the cluster's own record for that slot (no stale or fabricated hashes)
+    let recorded_hash = recent_slot_hash(slot_hashes, inputs.solana_block_number)?;
+    check(
+        Rule::Core,
+        check_ctx!(
+            recorded_hash == Some(inputs.solana_block_hash),
+            TrinityError::InvalidMerkleProof,
+            slot = inputs.solana_block_number,
+            recent = recorded_hash.is_some(),
+        ),
+    )?;
+    let clock = Clock::get()?;
+    let age = clock.slot.saturating_sub(inputs.solana_block_number);
+    check(
+        Rule::Shadow(CheckId::BlockFreshness),
+        check_ctx!(
+            age <= MAX_PROOF_SLOT_AGE,
+            TrinityError::StaleBlock,
+            slot = inputs.solana_block_number,
+            age = age,
+            max = MAX_PROOF_SLOT_AGE,
+        ),
+    )?;
+    
+    let slot = rules.type_slot(&inputs.operation_type)?;
+    let amount_limit = rules.max_amount[slot];
+    check(Rule::Core, check_amount_limit(&inputs.operation_type, inputs.amount, amount_limit))?;
+    
+    let (hashlock, preimage) = match inputs.htlc {
+        Some(htlc) => {
+            check(
+                Rule::Core,
+                check_ctx!(
+                    inputs.operation_type == OperationType::HTLCSwap,
+                    TrinityError::PreimageNotAllowed,
+                    operation_type = inputs.operation_type.code(),
+                ),
+            )?;
+            // Same check as the Ethereum HTLC: keccak256(abi.encodePacked(secret))
+            check(
+                Rule::Core,
+                check_ctx!(
+                    hashv(&[&htlc.preimage]).0 == htlc.hashlock,
+                    TrinityError::HashlockMismatch,
+                    proof = proof_key,
+                ),
+            )?;
+            (htlc.hashlock, htlc.preimage)
+        }
+        None => ([0u8; 32], [0u8; 32]),
+    };
+    
+    // Generate Merkle root from proof
+    let merkle_root = inputs.merkle_mode.root(&inputs.merkle_proof, &inputs.sibling_is_left, &inputs.operation_id);
+    let proof_id = derive_proof_id(&inputs.operation_id, &merkle_root, &validator.key());
+    let environment_hash = environment_hash(&clock, &EpochSchedule::get()?, slot_hashes)?;
+    let (observed_latency_seconds, latency_implausible) =
+        observed_latency(clock.unix_timestamp, inputs.eth_event_timestamp);
+    
+    Ok(ProofRecord {
+        operation_id: inputs.operation_id,
+        proof_id,
+        merkle_root,
+        merkle_proof: inputs.merkle_proof,
+        solana_block_hash: inputs.solana_block_hash,
+        solana_tx_signature: validator.store_tx_signatures.then_some(inputs.solana_tx_signature),
+        solana_block_number: inputs.solana_block_number,
+        solana_epoch: clock.epoch,
+        leader_schedule_epoch: clock.leader_schedule_epoch,
+        environment_hash,
+        operation_type: inputs.operation_type,
+        amount: inputs.amount,
+        amount_limit,
+        hashlock,
+        preimage,
+        timestamp: clock.unix_timestamp as u64,
+        submitted_to_ethereum: false,
+        ethereum_tx_hash: [0u8; 32],
+        validator: validator.key(),
+        expired: false,
+        expired_at: 0,
+        submission_hash,
+        eth_event_timestamp: inputs.eth_event_timestamp,
+        observed_latency_seconds,
+        latency_implausible,
+        layout_version: ProofRecord::LAYOUT_VERSION,
+        merkle_mode: inputs.merkle_mode,
+        sibling_is_left: inputs.sibling_is_left,
+        consensus_deadline: consensus_deadline(rules.consensus_window_secs[slot], clock.unix_timestamp as u64),
+        confirmations: Vec::new(),
+    })
+}
+
+/// Proof budget of a batch of submissions, given each one's Merkle levels
+fn proof_budget(levels: &[u64]) -> u64 {
+    levels.iter().fold(0u64, |total, &len| {
+        total
+            .saturating_add(PROOF_BUDGET_BASE_UNITS)
+            .saturating_add(len.saturating_mul(PROOF_BUDGET_LEVEL_UNITS))
+    })
+}
+
+/// Merkle levels of every top-level submit instruction (plain or sponsored)
+/// in the current transaction. The proof length is read straight from the
+/// instruction data: operation_id is fixed-size, so the Vec's u32 length
+/// prefix always sits right after it.
+fn submission_batch(instructions: &AccountInfo) -> Result<Vec<u64>> {
+    const LEN_OFFSET: usize = 8 + 32;
+    let mut levels = Vec::new();
+    let mut index = 0;
+    while let Ok(ix) = sysvar_instructions::load_instruction_at_checked(index, instructions) {
+        index += 1;
+        let is_submit = ix.program_id == crate::ID
+            && (ix.data.starts_with(&instruction::SubmitConsensusProof::discriminator())
+                || ix.data.starts_with(&instruction::SubmitWithSponsoredRent::discriminator()));
+        if !is_submit {
+            continue;
+        }
+        let len = ix.data
+            .get(LEN_OFFSET..LEN_OFFSET + 4)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap_or_default()))
+            .unwrap_or(0);
+        levels.push(len as u64);
+    }
+    Ok(levels)
+}
+
+/// Count a stored proof against its operator, remember it in the
+/// seen-operations ring (if given) and emit ProofGenerated for the relayer
+#[allow(clippy::too_many_arguments)]
+fn finish_proof_submission(
+    record: &ProofRecord,
+    seen: Option<&mut SeenOperations>,
+    metrics: &mut OperatorMetrics,
+    operator: Pubkey,
+    metrics_bump: u8,
+    sequence: &mut ProofSequence,
+    sequence_bump: u8,
+    stats: &mut DailyStats,
+    stats_bump: u8,
+) -> Result<()> {
+    sequence.advance(&record.operation_id, record.eth_event_timestamp)?;
+    sequence.bump = sequence_bump;
+    
+    metrics.operator = operator;
+    metrics.bump = metrics_bump;
+    metrics.layout_version = OperatorMetrics::LAYOUT_VERSION;
+    metrics.proofs_submitted = metrics.proofs_submitted.saturating_add(1);
+    metrics.last_submission = record.timestamp as i64;
+    let latency = Clock::get()?.slot.saturating_sub(record.solana_block_number).min(MAX_PROOF_SLOT_AGE);
+    metrics.score_inputs.latency_slots = metrics.score_inputs.latency_slots.saturating_add(latency);
+    record_heartbeat(metrics, record.timestamp as i64);
+    if let Some(seen) = seen {
+        seen.record(&record.operation_id);
+    }
+    
+    touch_daily_stats(stats, stats_bump);
+    stats.proofs_submitted = stats.proofs_submitted.saturating_add(1);
+    record_latency(stats, record.observed_latency_seconds, record.latency_implausible);
+    
+    msg!("Solana proof generated for operation: {:?}", record.operation_id);
+    msg!("Merkle root: {:?}", record.merkle_root);
+    msg!("Block number: {}", record.solana_block_number);
+    
+    // Emit event for off-chain relayer to submit to Ethereum
+    emit!(ProofGenerated {
+        operation_id: record.operation_id,
+        proof_id: record.proof_id,
+        merkle_root: record.merkle_root,
+        solana_block_hash: record.solana_block_hash,
+        solana_block_number: record.solana_block_number,
+        operation_type: record.operation_type.clone(),
+        hashlock: record.hashlock,
+        preimage: record.preimage,
+        timestamp: record.timestamp,
+        global_proof_seq: sequence.global_proof_seq,
+        submission_hash: record.submission_hash,
+        observed_latency_seconds: record.observed_latency_seconds,
+        latency_implausible: record.latency_implausible,
+    });
+    
+    Ok(())
+}
+
+/// Lamports a fee vault can spend without dropping below its own rent reserve
+fn fee_vault_spendable(vault: &AccountInfo) -> Result<u64> {
+    let reserve = Rent::get()?.minimum_balance(vault.data_len());
+    Ok(vault.lamports().saturating_sub(reserve))
+}
+
+/// Create the ProofRecord PDA at `target` and store `record` in it. `fund`
+/// moves the rent shortfall into `target` (only the shortfall, since anyone
+/// may pre-send lamports to the PDA). Returns the lamports funded.
+/// Done by hand rather than with `init` so that account constraints (the
+/// seen-operations filter) run before any account is created.
+fn create_proof_account<'info>(
+    target: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    record: &ProofRecord,
+    bump: u8,
+    fund: impl FnOnce(u64) -> Result<()>,
+) -> Result<u64> {
+    require_ctx!(
+        target.owner == &System::id() && target.data_is_empty(),
+        TrinityError::ProofAlreadyExists,
+        proof = target.key(),
+    );
+    
+    // Sized to the record rather than INIT_SPACE: a short Merkle path and no
+    // stored signature cost far less rent than the worst case. Only
+    // confirm_submission changes the record's length after creation.
+    let space = 8 + record.try_to_vec()?.len();
+    let rent = Rent::get()?.minimum_balance(space).saturating_sub(target.lamports());
+    if rent > 0 {
+        fund(rent)?;
+    }
+    
+    let seeds: &[&[u8]] = &[b"proof", record.operation_id.as_ref(), &[bump]];
+    anchor_lang::system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::Allocate { account_to_allocate: target.clone() },
+            &[seeds],
+        ),
+        space as u64,
+    )?;
+    anchor_lang::system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::Assign { account_to_assign: target.clone() },
+            &[seeds],
+        ),
+        &crate::ID,
+    )?;
+    
+    let mut data = target.try_borrow_mut_data()?;
+    record.try_serialize(&mut &mut data[..])?;
+    
+    Ok(rent)
+}
+
+/// An unconfirmed proof expires once it has outlived the configured expiry window
+fn proof_is_expirable(record: &ProofRecord, validator: &TrinityValidator, now: u64) -> bool {
+    !record.expired && !record.submitted_to_ethereum && now >= proof_deadline(record, validator)
+}
+
+/// Deadline a proof submitted at `submitted_at` gets from its type's
+/// consensus window; 0 (none fixed) if the type has no window
+fn consensus_deadline(consensus_window_secs: u64, submitted_at: u64) -> u64 {
+    if consensus_window_secs == 0 {
+        return 0;
+    }
+    submitted_at.saturating_add(consensus_window_secs)
+}
+
+/// When an unconfirmed proof expires: its fixed deadline, or for proofs
+/// without one the validator's current proof_expiry_secs after generation
+fn proof_deadline(record: &ProofRecord, validator: &TrinityValidator) -> u64 {
+    match record.consensus_deadline {
+        0 => record.timestamp.saturating_add(validator.proof_expiry_secs),
+        deadline => deadline,
+    }
+}
+
+/// An expired proof can be closed once its retention period has elapsed
+fn proof_is_closable(record: &ProofRecord, validator: &TrinityValidator, now: u64) -> bool {
+    record.expired && now >= record.expired_at.saturating_add(validator.proof_retention_secs)
+}
+
+/// Cranker bounty for closing an account holding `rent` lamports
+fn sweep_bounty(rent: u64, bounty_bps: u16) -> u64 {
+    let bps = bounty_bps.min(MAX_SWEEP_BOUNTY_BPS) as u128;
+    let bounty = (rent as u128 * bps / 10_000) as u64;
+    bounty.min(MAX_SWEEP_BOUNTY_LAMPORTS)
+}
+
+/// Days since the Unix epoch: the DailyStats shard key
+fn current_day() -> u64 {
+    Clock::get()
+        .map(|clock| clock.unix_timestamp.max(0) as u64 / SECONDS_PER_DAY)
+        .unwrap_or(0)
+}
+
+/// Stamp a (possibly just created) DailyStats shard; idempotent for a given day
+fn touch_daily_stats(stats: &mut DailyStats, bump: u8) {
+    stats.day = current_day();
+    stats.bump = bump;
+}
+
+/// Latency from the operator-reported Ethereum timestamp to `now`: clamped
+/// at 0 (clock skew), and capped and flagged past MAX_PLAUSIBLE_LATENCY_SECS
+fn observed_latency(now: i64, eth_event_timestamp: i64) -> (u64, bool) {
+    let latency = now.saturating_sub(eth_event_timestamp).max(0) as u64;
+    if latency > MAX_PLAUSIBLE_LATENCY_SECS {
+        (MAX_PLAUSIBLE_LATENCY_SECS, true)
+    } else {
+        (latency, false)
+    }
+}
+
+/// Histogram bucket of a latency: the first bound it doesn't exceed
+fn latency_bucket(latency: u64) -> usize {
+    LATENCY_BUCKET_BOUNDS_SECS
+        .iter()
+        .position(|&bound| latency <= bound)
+        .unwrap_or(LATENCY_BUCKETS - 1)
+}
+
+/// Upper bound of the bucket holding the `percentile`th sample (0 if there
+/// are none). The open last bucket is bounded by MAX_PLAUSIBLE_LATENCY_SECS.
+fn latency_percentile_bound(buckets: &[u64; LATENCY_BUCKETS], percentile: u64) -> u64 {
+    let samples: u64 = buckets.iter().sum();
+    let rank = (samples * percentile).div_ceil(100);
+    let mut seen = 0u64;
+    for (bucket, count) in buckets.iter().enumerate() {
+        seen += count;
+        if samples > 0 && seen >= rank {
+            return LATENCY_BUCKET_BOUNDS_SECS.get(bucket).copied().unwrap_or(MAX_PLAUSIBLE_LATENCY_SECS);
+        }
+    }
+    0
+}
+
+/// Fold one proof's latency into today's shard; implausible reports are
+/// only counted, so a bad clock can't skew the average or the histogram
+fn record_latency(stats: &mut DailyStats, latency: u64, implausible: bool) {
+    stats.latency_advisory = true;
+    if implausible {
+        stats.latency_implausible = stats.latency_implausible.saturating_add(1);
+        return;
+    }
+    stats.latency_samples = stats.latency_samples.saturating_add(1);
+    stats.latency_sum_secs = stats.latency_sum_secs.saturating_add(latency);
+    let bucket = &mut stats.latency_buckets[latency_bucket(latency)];
+    *bucket = bucket.saturating_add(1);
+}
+
+/// Fingerprint of the cluster environment a proof was created in, for
+/// forensic replay: the epoch schedule, the leader schedule epoch and the
+/// newest SlotHashes entry. Recorded only; nothing validates against it.
+fn environment_hash(clock: &Clock, schedule: &EpochSchedule, slot_hashes: &AccountInfo) -> Result<[u8; 32]> {
+    let data = slot_hashes.try_borrow_data()?;
+    let newest = data.get(8..8 + 8 + 32).ok_or(TrinityError::SlotHashesUnavailable)?;
+    Ok(hashv(&[
+        &schedule.slots_per_epoch.to_le_bytes(),
+        &schedule.leader_schedule_slot_offset.to_le_bytes(),
+        &[schedule.warmup as u8],
+        &schedule.first_normal_epoch.to_le_bytes(),
+        &schedule.first_normal_slot.to_le_bytes(),
+        &clock.leader_schedule_epoch.to_le_bytes(),
+        newest,
+    ])
+    .0)
+}
+
+fn proof_evidence(record: &ProofRecord) -> ProofEvidence {
+    ProofEvidence {
+        operation_id: record.operation_id,
+        proof_id: record.proof_id,
+        merkle_root: record.merkle_root,
+        validator: record.validator,
+        solana_block_hash: record.solana_block_hash,
+        solana_block_number: record.solana_block_number,
+        solana_epoch: record.solana_epoch,
+        leader_schedule_epoch: record.leader_schedule_epoch,
+        environment_hash: record.environment_hash,
+        timestamp: record.timestamp,
+        submitted_to_ethereum: record.submitted_to_ethereum,
+        expired_at: record.expired_at,
+        submission_hash: record.submission_hash,
+    }
+}
+
+/// Hash the SlotHashes sysvar records for `slot`, or None if the slot is
+/// outside its recent window (~512 slots) or was skipped.
+/// Layout: u64 LE entry count, then (u64 LE slot, [u8; 32] hash) entries
+/// ordered by descending slot.
+fn recent_slot_hash(sysvar: &AccountInfo, slot: u64) -> Result<Option<[u8; 32]>> {
+    const ENTRY_LEN: usize = 8 + 32;
+    
+    let data = sysvar.try_borrow_data()?;
+    let count = data
+        .get(..8)
+        .map(|len| u64::from_le_bytes(len.try_into().unwrap()) as usize)
+        .ok_or(TrinityError::InvalidMerkleProof)?;
+    let entries = data
+        .get(8..8 + count.saturating_mul(ENTRY_LEN))
+        .ok_or(TrinityError::InvalidMerkleProof)?;
+    
+    let slot_at = |i: usize| {
+        u64::from_le_bytes(entries[i * ENTRY_LEN..i * ENTRY_LEN + 8].try_into().unwrap())
+    };
+    
+    // Binary search over descending slots
+    let (mut lo, mut hi) = (0usize, count);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let mid_slot = slot_at(mid);
+        if mid_slot == slot {
+            let start = mid * ENTRY_LEN + 8;
+            return Ok(Some(entries[start..start + 32].try_into().unwrap()));
+        } else if mid_slot > slot {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    Ok(None)
+}
+
+/// A quorum of distinct, known chain IDs in ascending order that includes Solana
+fn validate_consensus_chains(chains: &[u8]) -> Result<()> {
+    require_ctx!(
+        chains.len() >= CONSENSUS_THRESHOLD && chains.len() <= 3,
+        TrinityError::InvalidConsensusChains,
+        chains = chains.len(),
+        threshold = CONSENSUS_THRESHOLD,
+    );
+    require_ctx!(
+        chains.windows(2).all(|pair| pair[0] < pair[1])
+            && chains.iter().all(|chain| (ARBITRUM_CHAIN_ID..=TON_CHAIN_ID).contains(chain)),
+        TrinityError::InvalidConsensusChains,
+        first = chains[0],
+        last = chains[chains.len() - 1],
+    );
+    require_ctx!(
+        chains.contains(&SOLANA_CHAIN_ID),
+        TrinityError::InvalidConsensusChains,
+        missing = SOLANA_CHAIN_ID,
+    );
+    Ok(())
+}
+
+/// ConsensusMirror chain_bitmap: bit (chain ID - 1) for each agreeing chain
+fn chain_bitmap(chains: &[u8]) -> u8 {
+    chains
+        .iter()
+        .filter(|chain| (ARBITRUM_CHAIN_ID..=TON_CHAIN_ID).contains(*chain))
+        .fold(0, |bitmap, chain| bitmap | 1 << (chain - 1))
+}
+
+/// Add `operator`'s attestation with `stake` to the operation's weight: once
+/// per operator, with stake bonded. Once MAX_CONSENSUS_CONTRIBUTORS have
+/// attested, a larger stake takes the smallest one's place (returned), so
+/// dust-staked attestations can't fill the list ahead of real operators.
+fn record_contribution(consensus: &mut ConsensusState, operator: Pubkey, stake: u64) -> Result<Option<Contribution>> {
+    require_ctx!(stake > 0, TrinityError::InsufficientStake, operator = operator, stake = stake);
+    require_ctx!(
+        !consensus.contributors.iter().any(|contribution| contribution.operator == operator),
+        TrinityError::AlreadyAttested,
+        operator = operator,
+    );
+    let contribution = Contribution { operator, stake };
+    if consensus.contributors.len() < MAX_CONSENSUS_CONTRIBUTORS {
+        consensus.contributors.push(contribution);
+        consensus.stake_weight = consensus.stake_weight.saturating_add(stake);
+        return Ok(None);
+    }
+    
+    // Full: the first of the smallest stakes goes, if this one is larger
+    let (smallest, least) = consensus
+        .contributors
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, contribution)| contribution.stake)
+        .map(|(index, contribution)| (index, *contribution))
+        .unwrap();
+    require_ctx!(
+        stake > least.stake,
+        TrinityError::ContributorLimitReached,
+        contributors = consensus.contributors.len(),
+        max = MAX_CONSENSUS_CONTRIBUTORS,
+        smallest_stake = least.stake,
+    );
+    consensus.contributors[smallest] = contribution;
+    consensus.stake_weight = consensus.stake_weight.saturating_sub(least.stake).saturating_add(stake);
+    Ok(Some(least))
+}
+
+/// With a threshold configured, the attested stake must exceed it; with
+/// none (0), the chain quorum alone finalizes
+fn require_stake_weight(consensus: &ConsensusState, threshold: u64) -> Result<()> {
+    require_ctx!(
+        threshold == 0 || consensus.stake_weight > threshold,
+        TrinityError::StakeWeightNotReached,
+        stake_weight = consensus.stake_weight,
+        threshold = threshold,
+        contributors = consensus.contributors.len(),
+    );
+    Ok(())
+}
+
+/// A contributor's share of `total`, pro rata to its attested `stake` out of
+/// the operation's `stake_weight`, rounded down. The rounding dust stays in
+/// the reward vault and rolls into later operations.
+fn reward_share(total: u64, stake: u64, stake_weight: u64) -> u64 {
+    (total as u128 * stake as u128 / stake_weight as u128) as u64
+}
+
+/// Reward vault balance above its floor: the configured reserve, but never
+/// less than one operation's reward, so the next finalization still pays
+fn reward_surplus(balance: u64, reserve: u64, reward_per_operation: u64) -> u64 {
+    balance.saturating_sub(reserve.max(reward_per_operation))
+}
+
+/// Pay the operation's contributors their stake-weighted share of the pool's
+/// reward_per_operation. `recipients` are token accounts of the pool's mint,
+/// one owned by each of `consensus.contributors` (in any order), so the
+/// finalizing authority can neither add payees nor leave a contributor out.
+fn distribute_rewards<'info>(
+    pool: &Account<'info, RewardPool>,
+    vault: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    consensus: &ConsensusState,
+    recipients: &[AccountInfo<'info>],
+    operation_id: [u8; 32],
+) -> Result<()> {
+    if pool.reward_per_operation == 0 {
+        return Ok(());
+    }
+    
+    let contributors = &consensus.contributors;
+    require_ctx!(
+        !contributors.is_empty() && recipients.len() == contributors.len(),
+        TrinityError::InvalidRewardRecipients,
+        accounts = recipients.len(),
+        contributors = contributors.len(),
+    );
+    let mut shares: Vec<(Pubkey, u64)> = Vec::with_capacity(recipients.len());
+    for info in recipients {
+        let recipient = Account::<TokenAccount>::try_from(info)?;
+        let contribution = contributors.iter().find(|contribution| contribution.operator == recipient.owner);
+        require_ctx!(
+            contribution.is_some()
+                && recipient.mint == pool.mint
+                && !shares.iter().any(|(operator, _)| *operator == recipient.owner),
+            TrinityError::InvalidRewardRecipients,
+            recipient = recipient.key(),
+            owner = recipient.owner,
+            mint = recipient.mint,
+        );
+        let share = reward_share(pool.reward_per_operation, contribution.unwrap().stake, consensus.stake_weight);
+        shares.push((recipient.owner, share));
+    }
+    
+    let paid: u64 = shares.iter().map(|(_, share)| share).sum();
+    require_ctx!(
+        vault.amount >= paid,
+        TrinityError::RewardPoolUnderfunded,
+        balance = vault.amount,
+        payout = paid,
+    );
+    let signer: &[&[u8]] = &[b"reward_pool", &[pool.bump]];
+    for (info, (_, share)) in recipients.iter().zip(&shares) {
+        if *share == 0 {
+            continue;
+        }
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                Transfer {
+                    from: vault.to_account_info(),
+                    to: info.clone(),
+                    authority: pool.to_account_info(),
+                },
+                &[signer],
+            ),
+            *share,
+        )?;
+    }
+    
+    emit!(RewardsDistributed {
+        operation_id,
+        recipients: recipients.len() as u8,
+        paid,
+        dust: pool.reward_per_operation - paid,
+    });
+    Ok(())
+}
+
+/// Council must be non-empty, bounded, duplicate-free, with 1 <= threshold <= len
+fn validate_council(members: &[Pubkey], threshold: u8) -> Result<()> {
+    require_ctx!(
+        !members.is_empty() && members.len() <= MAX_COUNCIL_MEMBERS,
+        TrinityError::InvalidCouncil,
+        members = members.len(),
+        max = MAX_COUNCIL_MEMBERS,
+    );
+    require_ctx!(
+        threshold > 0 && threshold as usize <= members.len(),
+        TrinityError::InvalidCouncil,
+        threshold = threshold,
+        members = members.len(),
+    );
+    for (i, member) in members.iter().enumerate() {
+        require_ctx!(
+            !members[i + 1..].contains(member),
+            TrinityError::InvalidCouncil,
+            duplicate = member,
+        );
+    }
+    Ok(())
+}
+
+/// Count distinct council signers among the (signing) proposer and remaining accounts
+fn count_council_approvals(
+    council: &DisputeCouncil,
+    proposer: &Pubkey,
+    remaining: &[AccountInfo],
+) -> u8 {
+    let mut seen: Vec<Pubkey> = Vec::with_capacity(MAX_COUNCIL_MEMBERS);
+    if council.members.contains(proposer) {
+        seen.push(*proposer);
+    }
+    for info in remaining.iter().take(MAX_COUNCIL_MEMBERS).filter(|info| info.is_signer) {
+        if council.members.contains(info.key) && !seen.contains(info.key) {
+            seen.push(*info.key);
+        }
+    }
+    seen.len() as u8
+}
+
+/// An OperatorMetrics account at its canonical PDA, or None
+fn load_operator_metrics<'info>(
+    info: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Option<Account<'info, OperatorMetrics>> {
+    let metrics = Account::<OperatorMetrics>::try_from(info).ok()?;
+    let (expected_pda, _) = Pubkey::find_program_address(&[b"operator_metrics", metrics.operator.as_ref()], program_id);
+    (expected_pda == info.key()).then_some(metrics)
+}
+
+/// Count the HEARTBEAT_INTERVAL_SECS windows since the last heartbeat that
+/// passed without one, moving last_heartbeat past them so none is counted
+/// twice. Nothing is owed before the first heartbeat.
+fn accrue_missed_heartbeats(metrics: &mut OperatorMetrics, now: i64) {
+    if metrics.last_heartbeat == 0 {
+        return;
+    }
+    let missed = (now - metrics.last_heartbeat - 1).max(0) / HEARTBEAT_INTERVAL_SECS;
+    metrics.score_inputs.missed_heartbeats = metrics.score_inputs.missed_heartbeats.saturating_add(missed as u64);
+    metrics.last_heartbeat += missed * HEARTBEAT_INTERVAL_SECS;
+}
+
+fn record_heartbeat(metrics: &mut OperatorMetrics, now: i64) {
+    accrue_missed_heartbeats(metrics, now);
+    metrics.last_heartbeat = now;
+}
+
+// ============================================================================
+// BUILD ATTESTATION (pure)
+// ============================================================================
+
+/// Slots the deployment made at `upgrade_slot` has gone unattested, once
+/// that exceeds the attestation window; None while it is attested, within
+/// the window or already reported
+pub fn unattested_slots(attestation: &BuildAttestation, upgrade_slot: u64, slot: u64) -> Option<u64> {
+    if attestation.upgrade_slot == upgrade_slot || attestation.warned_upgrade_slot == upgrade_slot {
+        return None;
+    }
+    let elapsed = slot.saturating_sub(upgrade_slot);
+    (elapsed >= attestation.attestation_window_slots).then_some(elapsed)
+}
+
+/// `commit` as stored in a BuildAttestation: truncated or zero-padded
+pub fn source_commit(commit: &str) -> [u8; SOURCE_COMMIT_LEN] {
+    let mut stored = [0; SOURCE_COMMIT_LEN];
+    let len = commit.len().min(SOURCE_COMMIT_LEN);
+    stored[..len].copy_from_slice(&commit.as_bytes()[..len]);
+    stored
+}
+
+// ============================================================================
+// OPERATOR SCORING (pure)
+// ============================================================================
+
+/// Score of one period's inputs, in SCORE_BPS: the weighted sum of
+/// - timeliness: 1 - average proof block age / MAX_PROOF_SLOT_AGE,
+/// - accuracy: proofs / (proofs + faults), where a fault is a divergence or
+///   DISPUTE_LOSS_FAULTS per lost dispute,
+/// - liveness: 1 / (1 + missed heartbeats).
+///
+/// A period without proofs keeps full timeliness and, without faults, full
+/// accuracy. The score never rises with any penalty input.
+pub fn period_score(proofs: u64, inputs: &ScoreComponents) -> u64 {
+    let timeliness = SCORE_BPS - ratio_bps(inputs.latency_slots, proofs.saturating_mul(MAX_PROOF_SLOT_AGE));
+    let faults = inputs.divergences.saturating_add(inputs.disputes_lost.saturating_mul(DISPUTE_LOSS_FAULTS));
+    let accuracy = SCORE_BPS - ratio_bps(faults, proofs.saturating_add(faults));
+    let liveness = SCORE_BPS / inputs.missed_heartbeats.saturating_add(1);
+    
+    (timeliness * SCORE_WEIGHT_TIMELINESS_BPS
+        + accuracy * SCORE_WEIGHT_ACCURACY_BPS
+        + liveness * SCORE_WEIGHT_LIVENESS_BPS)
+        / SCORE_BPS
+}
+
+/// `previous` decayed by SCORE_DECAY_BPS per elapsed epoch (at most
+/// MAX_DECAY_EPOCHS), `period` taking up the weight it gives up. Never falls
+/// as either score rises.
+pub fn decayed_score(previous: u64, period: u64, epochs: u64) -> u64 {
+    (0..epochs.min(MAX_DECAY_EPOCHS)).fold(previous.min(SCORE_BPS), |score, _| {
+        (score * SCORE_DECAY_BPS + period.min(SCORE_BPS) * (SCORE_BPS - SCORE_DECAY_BPS)) / SCORE_BPS
+    })
+}
+
+/// `part / whole` in SCORE_BPS, capped at SCORE_BPS; 0 when `whole` is
+fn ratio_bps(part: u64, whole: u64) -> u64 {
+    if whole == 0 {
+        return 0;
+    }
+    (part as u128 * SCORE_BPS as u128 / whole as u128).min(SCORE_BPS as u128) as u64
+}
+
+// ============================================================================
+// PROTOCOL INVARIANTS
+// ============================================================================
+
+/// Machine-checkable protocol invariants, asserted on-chain by
+/// assert_invariants and after every step of the randomized invariant tests.
+/// Each check covers only the accounts it is given and fails with
+/// InvariantViolated, naming the invariant and the offending account.
+pub mod invariants {
+    use super::*;
+    
+    /// Rolled-up proofs never outnumber the proof records ever created. Every
+    /// record, live or since archived by sweep_expired, took one number in
+    /// ProofSequence when it was submitted.
+    pub fn check_proof_count_bound(validator: &TrinityValidator, global_proof_seq: u64) -> Result<()> {
+        require_ctx!(
+            validator.total_proofs_submitted <= global_proof_seq,
+            TrinityError::InvariantViolated,
+            invariant = "proof_count",
+            total_proofs_submitted = validator.total_proofs_submitted,
+            global_proof_seq = global_proof_seq,
+        );
+        Ok(())
+    }
+    
+    /// total_proofs_submitted plus what `metrics` have yet to roll up is
+    /// exactly the number of live and archived proof records. Only meaningful
+    /// with every operator's OperatorMetrics in `metrics`.
+    pub fn check_proof_count(validator: &TrinityValidator, global_proof_seq: u64, metrics: &[OperatorMetrics]) -> Result<()> {
+        let pending = metrics
+            .iter()
+            .fold(0u64, |pending, operator| pending.saturating_add(operator.proofs_submitted.saturating_sub(operator.rolled_up)));
+        require_ctx!(
+            validator.total_proofs_submitted.saturating_add(pending) == global_proof_seq,
+            TrinityError::InvariantViolated,
+            invariant = "proof_count",
+            total_proofs_submitted = validator.total_proofs_submitted,
+            pending_rollup = pending,
+            operators = metrics.len(),
+            global_proof_seq = global_proof_seq,
+        );
+        Ok(())
+    }
+    
+    /// An operator's rolled-up count never passes its submissions
+    pub fn check_operator_metrics(address: &Pubkey, metrics: &OperatorMetrics) -> Result<()> {
+        require_ctx!(
+            metrics.rolled_up <= metrics.proofs_submitted,
+            TrinityError::InvariantViolated,
+            invariant = "operator_rollup",
+            account = address,
+            rolled_up = metrics.rolled_up,
+            proofs_submitted = metrics.proofs_submitted,
+        );
+        Ok(())
+    }
+    
+    /// An operation's stake weight is the sum of its distinct contributors'
+    /// stakes. Organic consensus names at least CONSENSUS_THRESHOLD distinct
+    /// chains; overrides record none, and unfinalized states have reached
+    /// nothing.
+    pub fn check_consensus(address: &Pubkey, consensus: &ConsensusState) -> Result<()> {
+        let contributors = &consensus.contributors;
+        let stake = contributors.iter().fold(0u64, |stake, contribution| stake.saturating_add(contribution.stake));
+        let distinct = (0..contributors.len())
+            .all(|i| !contributors[..i].iter().any(|earlier| earlier.operator == contributors[i].operator));
+        require_ctx!(
+            distinct && stake == consensus.stake_weight,
+            TrinityError::InvariantViolated,
+            invariant = "consensus_stake_weight",
+            account = address,
+            stake_weight = consensus.stake_weight,
+            contributed = stake,
+        );
+        if !consensus.finalized || consensus.overridden {
+            return Ok(());
+        }
+        let chains = &consensus.chains;
+        let distinct = (0..chains.len()).filter(|&i| !chains[..i].contains(&chains[i])).count();
+        require_ctx!(
+            distinct >= CONSENSUS_THRESHOLD,
+            TrinityError::InvariantViolated,
+            invariant = "consensus_chains",
+            account = address,
+            distinct_chains = distinct,
+            threshold = CONSENSUS_THRESHOLD,
+        );
+        Ok(())
+    }
+    
+    /// A proof confirmed on Ethereum records the confirming transaction, and
+    /// a destination chain's confirmation implies it, once per chain
+    pub fn check_proof_record(address: &Pubkey, record: &ProofRecord) -> Result<()> {
+        require_ctx!(
+            !record.submitted_to_ethereum || record.ethereum_tx_hash != [0u8; 32],
+            TrinityError::InvariantViolated,
+            invariant = "confirmed_tx_hash",
+            account = address,
+        );
+        let confirmations = &record.confirmations;
+        let distinct = (0..confirmations.len())
+            .filter(|&i| confirmations[..i].iter().all(|earlier| earlier.chain_id != confirmations[i].chain_id))
+            .count();
+        require_ctx!(
+            (confirmations.is_empty() || record.submitted_to_ethereum) && distinct == confirmations.len(),
+            TrinityError::InvariantViolated,
+            invariant = "chain_confirmations",
+            account = address,
+            confirmations = confirmations.len(),
+            distinct_chains = distinct,
+        );
+        Ok(())
+    }
+}
+
+// ============================================================================
+// DISASTER EXPORT / IMPORT
+// ============================================================================
+
+/// Payload rules shared by export_state and import_state. An exported
+/// account is its full data. On import it keeps those bytes except for what
+/// depends on the program id: its PDA bump and the validator address it
+/// names, rebound to the new deployment. Placing the result back on the old
+/// program id reproduces the exported bytes exactly.
+pub mod state_export {
+    use super::*;
+    
+    /// Where an exported account lands on a deployment, and its data there
+    pub struct Placement {
+        pub address: Pubkey,
+        pub seeds: Vec<Vec<u8>>,
+        pub bump: u8,
+        pub data: Vec<u8>,
+    }
+    
+    /// Validate a page against the ledger cursor and return the new cursor:
+    /// 1..=MAX_EXPORT_PAGE addresses, strictly ascending past `cursor`
+    pub fn check_page(ledger_cursor: &Pubkey, cursor: &Pubkey, page: &[Pubkey]) -> Result<Pubkey> {
+        require_ctx!(
+            cursor == ledger_cursor,
+            TrinityError::ExportCursorMismatch,
+            cursor = cursor,
+            expected = ledger_cursor,
+        );
+        require_ctx!(
+            (1..=MAX_EXPORT_PAGE).contains(&page.len()),
+            TrinityError::ExportCursorMismatch,
+            page = page.len(),
+            max = MAX_EXPORT_PAGE,
+        );
+        let mut last = *cursor;
+        for address in page {
+            require_ctx!(
+                address.to_bytes() > last.to_bytes(),
+                TrinityError::ExportCursorMismatch,
+                account = address,
+                after = last,
+            );
+            last = *address;
+        }
+        Ok(last)
+    }
+    
+    /// Extend a kind's digest with one account
+    pub fn chain(digest: &[u8; 32], source: &Pubkey, data: &[u8]) -> [u8; 32] {
+        hashv(&[digest, source.as_ref(), data]).0
+    }
+    
+    fn decode<T: AccountDeserialize>(kind: ExportKind, address: &Pubkey, data: &[u8]) -> Result<T> {
+        T::try_deserialize(&mut &data[..]).map_err(|_| {
+            msg!("{:?} export: {} does not decode", kind, address);
+            error!(TrinityError::InvalidExportAccount)
+        })
+    }
+    
+    fn current<T: Versioned + AccountDeserialize>(kind: ExportKind, address: &Pubkey, data: &[u8]) -> Result<T> {
+        let account = decode::<T>(kind, address, data)?;
+        require_ctx!(
+            account.is_current(),
+            TrinityError::AccountNeedsMigration,
+            account = address,
+            version = account.layout_version(),
+        );
+        Ok(account)
+    }
+    
+    /// An account of `kind` at the address its data derives on this
+    /// deployment, in its current layout. Proofs settled on Ethereum or
+    /// expired are history, not state, and stay behind.
+    pub fn check_exportable(kind: ExportKind, address: &Pubkey, data: &[u8]) -> Result<()> {
+        match kind {
+            ExportKind::Validator => {
+                current::<TrinityValidator>(kind, address, data)?;
+            }
+            ExportKind::CheckConfig => {
+                current::<CheckConfig>(kind, address, data)?;
+            }
+            ExportKind::OperatorMetrics => {
+                current::<OperatorMetrics>(kind, address, data)?;
+            }
+            ExportKind::ConsensusState => {
+                current::<ConsensusState>(kind, address, data)?;
+            }
+            ExportKind::ProofRecord => {
+                let record = current::<ProofRecord>(kind, address, data)?;
+                require_ctx!(
+                    !record.submitted_to_ethereum && !record.expired,
+                    TrinityError::InvalidExportAccount,
+                    account = address,
+                    submitted = record.submitted_to_ethereum,
+                    expired = record.expired,
+                );
+            }
+            ExportKind::DisputeCouncil | ExportKind::FeeVault | ExportKind::ProofSequence => {}
+        }
+        let placement = place(kind, data, &crate::ID)?;
+        require_ctx!(
+            placement.address == *address && placement.data == data,
+            TrinityError::InvalidExportAccount,
+            account = address,
+            expected = placement.address,
+        );
+        Ok(())
+    }
+    
+    /// The account on `program_id` for exported `data` of `kind`
+    pub fn place(kind: ExportKind, data: &[u8], program_id: &Pubkey) -> Result<Placement> {
+        match kind {
+            ExportKind::Validator => rebound::<TrinityValidator>(kind, data, program_id),
+            ExportKind::CheckConfig => rebound::<CheckConfig>(kind, data, program_id),
+            ExportKind::DisputeCouncil => rebound::<DisputeCouncil>(kind, data, program_id),
+            ExportKind::FeeVault => rebound::<FeeVault>(kind, data, program_id),
+            ExportKind::OperatorMetrics => rebound::<OperatorMetrics>(kind, data, program_id),
+            ExportKind::ProofSequence => rebound::<ProofSequence>(kind, data, program_id),
+            ExportKind::ConsensusState => rebound::<ConsensusState>(kind, data, program_id),
+            ExportKind::ProofRecord => rebound::<ProofRecord>(kind, data, program_id),
+        }
+    }
+    
+    /// Decode `data`, derive the account's address on `program_id`, rebind it
+    /// and re-serialize over a copy of `data`, so that allocation padding past
+    /// the serialized fields survives unchanged
+    fn rebound<T: Exportable>(kind: ExportKind, data: &[u8], program_id: &Pubkey) -> Result<Placement> {
+        let mut account = T::try_deserialize(&mut &data[..]).map_err(|_| {
+            msg!("{:?} payload does not decode", kind);
+            error!(TrinityError::InvalidStateImport)
+        })?;
+        let (validator, _) = Pubkey::find_program_address(&[b"trinity_validator"], program_id);
+        let seeds = account.seeds(&validator);
+        let slices: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+        let (address, bump) = Pubkey::find_program_address(&slices, program_id);
+        account.rebind(&validator, bump);
+        
+        let mut placed = data.to_vec();
+        account.try_serialize(&mut &mut placed[..])?;
+        Ok(Placement { address, seeds, bump, data: placed })
+    }
+    
+    /// What ties an exported account to its deployment
+    trait Exportable: AccountSerialize + AccountDeserialize {
+        /// PDA seeds (bump excluded) on the deployment whose validator is `validator`
+        fn seeds(&self, validator: &Pubkey) -> Vec<Vec<u8>>;
+        /// Point the account at that deployment's validator and PDA bump
+        fn rebind(&mut self, validator: &Pubkey, bump: u8);
+    }
+    
+    impl Exportable for TrinityValidator {
+        fn seeds(&self, _: &Pubkey) -> Vec<Vec<u8>> {
+            vec![b"trinity_validator".to_vec()]
+        }
+        
+        fn rebind(&mut self, _: &Pubkey, bump: u8) {
+            self.bump = bump;
+        }
+    }
+    
+    impl Exportable for CheckConfig {
+        fn seeds(&self, validator: &Pubkey) -> Vec<Vec<u8>> {
+            vec![b"check_config".to_vec(), validator.to_bytes().to_vec()]
+        }
+        
+        fn rebind(&mut self, validator: &Pubkey, bump: u8) {
+            self.validator = *validator;
+            self.bump = bump;
+        }
+    }
+    
+    impl Exportable for DisputeCouncil {
+        fn seeds(&self, validator: &Pubkey) -> Vec<Vec<u8>> {
+            vec![b"dispute_council".to_vec(), validator.to_bytes().to_vec()]
+        }
+        
+        fn rebind(&mut self, validator: &Pubkey, bump: u8) {
+            self.validator = *validator;
+            self.bump = bump;
+        }
+    }
+    
+    impl Exportable for FeeVault {
+        fn seeds(&self, _: &Pubkey) -> Vec<Vec<u8>> {
+            vec![b"fee_vault".to_vec(), self.operator.to_bytes().to_vec()]
+        }
+        
+        fn rebind(&mut self, _: &Pubkey, bump: u8) {
+            self.bump = bump;
+        }
+    }
+    
+    impl Exportable for OperatorMetrics {
+        fn seeds(&self, _: &Pubkey) -> Vec<Vec<u8>> {
+            vec![b"operator_metrics".to_vec(), self.operator.to_bytes().to_vec()]
+        }
+        
+        fn rebind(&mut self, _: &Pubkey, bump: u8) {
+            self.bump = bump;
+        }
+    }
+    
+    impl Exportable for ProofSequence {
+        fn seeds(&self, validator: &Pubkey) -> Vec<Vec<u8>> {
+            vec![b"proof_sequence".to_vec(), validator.to_bytes().to_vec()]
+        }
+        
+        fn rebind(&mut self, _: &Pubkey, bump: u8) {
+            self.bump = bump;
+        }
+    }
+    
+    impl Exportable for ConsensusState {
+        fn seeds(&self, _: &Pubkey) -> Vec<Vec<u8>> {
+            vec![b"consensus".to_vec(), self.operation_id.to_vec()]
+        }
+        
+        fn rebind(&mut self, _: &Pubkey, bump: u8) {
+            self.bump = bump;
+        }
+    }
+    
+    impl Exportable for ProofRecord {
+        fn seeds(&self, _: &Pubkey) -> Vec<Vec<u8>> {
+            vec![b"proof".to_vec(), self.operation_id.to_vec()]
+        }
+        
+        /// Created with a derived bump it doesn't store (see create_proof_account)
+        fn rebind(&mut self, validator: &Pubkey, _: u8) {
+            self.validator = *validator;
+        }
+    }
 }
 
 // ============================================================================
-// Enums
+// ACCOUNT LAYOUT MIGRATIONS
 // ============================================================================
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Debug)]
-pub enum OperationType {
-    VaultWithdrawal,
-    HTLCSwap,
-    EmergencyRecovery,
-    CrossChainTransfer,
-}
-
-/// High-frequency monitoring check types
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
-pub enum MonitoringCheckType {
-    PeriodicScan,           // Regular interval scan
-    EventTriggered,         // Triggered by cross-chain event
-    FastPath,               // Urgent fast-path verification
-    RecoveryCheck,          // Emergency recovery monitoring
+/// Upgrade path for versioned account layouts. Each versioned account has a
+/// `layout_version` byte after its original fields; version 0 is the layout
+/// it had before that byte existed (padding reads as 0 there, or the account
+/// is one byte short). Later layouts only append fields after it, and zero
+/// bytes decode as each appended field's default, so a stored body always
+/// decodes in the current layout once zero-extended.
+/// Instructions only accept current layouts (`Versioned::is_current`); older
+/// accounts are brought forward one registry step at a time by migrate_account.
+pub mod migrations {
+    use super::*;
+    
+    /// Account types with a versioned layout, told apart by discriminator
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum AccountKind {
+        Validator,
+        ProofRecord,
+        OperatorMetrics,
+        ConsensusState,
+        CheckConfig,
+    }
+    
+    pub trait Versioned: AnchorSerialize + AnchorDeserialize + Discriminator {
+        const KIND: AccountKind;
+        /// Layout this build reads and writes
+        const LAYOUT_VERSION: u8;
+        /// Smallest allocation for the current layout; 0 if sized to the data
+        const MIN_SPACE: usize;
+        
+        fn layout_version(&self) -> u8;
+        fn set_layout_version(&mut self, version: u8);
+        
+        /// The version guard every instruction applies to the accounts it reads
+        fn is_current(&self) -> bool {
+            self.layout_version() == Self::LAYOUT_VERSION
+        }
+    }
+    
+    macro_rules! versioned {
+        ($account:ty, $kind:ident, $version:expr, $min_space:expr) => {
+            impl Versioned for $account {
+                const KIND: AccountKind = AccountKind::$kind;
+                const LAYOUT_VERSION: u8 = $version;
+                const MIN_SPACE: usize = $min_space;
+                
+                fn layout_version(&self) -> u8 {
+                    self.layout_version
+                }
+                
+                fn set_layout_version(&mut self, version: u8) {
+                    self.layout_version = version;
+                }
+            }
+        };
+    }
+    
+    versioned!(TrinityValidator, Validator, 1, 8 + TrinityValidator::INIT_SPACE);
+    // Allocated at its serialized length (see create_proof_account)
+    // 2: merkle_mode, sibling_is_left; 3: consensus_deadline; 4: confirmations
+    versioned!(ProofRecord, ProofRecord, 4, 0);
+    versioned!(OperatorMetrics, OperatorMetrics, 1, 8 + OperatorMetrics::INIT_SPACE);
+    // 2: operation_paused; 3: contributors, stake_weight
+    versioned!(ConsensusState, ConsensusState, 3, 8 + ConsensusState::INIT_SPACE);
+    // 2: consensus_window_secs
+    versioned!(CheckConfig, CheckConfig, 2, 8 + CheckConfig::INIT_SPACE);
+    
+    /// One layout step: `migrate` maps the account body (after the
+    /// discriminator) at `from` to the body at `to`
+    pub struct Migration {
+        pub kind: AccountKind,
+        pub from: u8,
+        pub to: u8,
+        pub migrate: fn(&[u8]) -> Result<Vec<u8>>,
+    }
+    
+    /// Every layout change, in order. A new layout adds its step here and
+    /// bumps the type's LAYOUT_VERSION.
+    pub const REGISTRY: &[Migration] = &[
+        Migration {
+            kind: AccountKind::Validator,
+            from: 0,
+            to: 1,
+            migrate: stamp_layout_version::<TrinityValidator, 1>,
+        },
+        Migration {
+            kind: AccountKind::ProofRecord,
+            from: 0,
+            to: 1,
+            migrate: stamp_layout_version::<ProofRecord, 1>,
+        },
+        Migration {
+            kind: AccountKind::ProofRecord,
+            from: 1,
+            to: 2,
+            migrate: stamp_layout_version::<ProofRecord, 2>,
+        },
+        Migration {
+            kind: AccountKind::ProofRecord,
+            from: 2,
+            to: 3,
+            migrate: stamp_layout_version::<ProofRecord, 3>,
+        },
+        Migration {
+            kind: AccountKind::ProofRecord,
+            from: 3,
+            to: 4,
+            migrate: stamp_layout_version::<ProofRecord, 4>,
+        },
+        Migration {
+            kind: AccountKind::OperatorMetrics,
+            from: 0,
+            to: 1,
+            migrate: stamp_layout_version::<OperatorMetrics, 1>,
+        },
+        Migration {
+            kind: AccountKind::ConsensusState,
+            from: 0,
+            to: 1,
+            migrate: stamp_layout_version::<ConsensusState, 1>,
+        },
+        Migration {
+            kind: AccountKind::ConsensusState,
+            from: 1,
+            to: 2,
+            migrate: stamp_layout_version::<ConsensusState, 2>,
+        },
+        Migration {
+            kind: AccountKind::ConsensusState,
+            from: 2,
+            to: 3,
+            migrate: stamp_layout_version::<ConsensusState, 3>,
+        },
+        Migration {
+            kind: AccountKind::CheckConfig,
+            from: 0,
+            to: 1,
+            migrate: stamp_layout_version::<CheckConfig, 1>,
+        },
+        Migration {
+            kind: AccountKind::CheckConfig,
+            from: 1,
+            to: 2,
+            migrate: stamp_layout_version::<CheckConfig, 2>,
+        },
+    ];
+    
+    /// Zero bytes read past the end of a stored body: room for every field
+    /// appended since the account was written
+    const ZERO_TAIL: usize = 64;
+    
+    fn zero_extended(body: &[u8]) -> Vec<u8> {
+        let mut extended = body.to_vec();
+        extended.resize(body.len() + ZERO_TAIL, 0);
+        extended
+    }
+    
+    /// Any append-only step: the body read with its missing fields at their
+    /// defaults, re-serialized at layout `TO`
+    fn stamp_layout_version<T: Versioned, const TO: u8>(body: &[u8]) -> Result<Vec<u8>> {
+        let mut account = T::deserialize(&mut zero_extended(body).as_slice())
+            .map_err(|_| error!(anchor_lang::error::ErrorCode::AccountDidNotDeserialize))?;
+        account.set_layout_version(TO);
+        account.try_to_vec().map_err(|_| error!(anchor_lang::error::ErrorCode::AccountDidNotSerialize))
+    }
+    
+    /// Version of a stored body, decoded zero-extended in the current layout:
+    /// an unversioned, exactly-sized account reads as 0
+    fn stored_version<T: Versioned>(body: &[u8]) -> u8 {
+        T::deserialize(&mut zero_extended(body).as_slice()).map_or(0, |account| account.layout_version())
+    }
+    
+    impl AccountKind {
+        pub fn of(data: &[u8]) -> Option<Self> {
+            let discriminator = data.get(..8)?;
+            [
+                (TrinityValidator::discriminator(), AccountKind::Validator),
+                (ProofRecord::discriminator(), AccountKind::ProofRecord),
+                (OperatorMetrics::discriminator(), AccountKind::OperatorMetrics),
+                (ConsensusState::discriminator(), AccountKind::ConsensusState),
+                (CheckConfig::discriminator(), AccountKind::CheckConfig),
+            ]
+            .into_iter()
+            .find_map(|(known, kind)| (known[..] == *discriminator).then_some(kind))
+        }
+        
+        pub fn current_version(self) -> u8 {
+            match self {
+                AccountKind::Validator => TrinityValidator::LAYOUT_VERSION,
+                AccountKind::ProofRecord => ProofRecord::LAYOUT_VERSION,
+                AccountKind::OperatorMetrics => OperatorMetrics::LAYOUT_VERSION,
+                AccountKind::ConsensusState => ConsensusState::LAYOUT_VERSION,
+                AccountKind::CheckConfig => CheckConfig::LAYOUT_VERSION,
+            }
+        }
+        
+        fn stored_version(self, body: &[u8]) -> u8 {
+            match self {
+                AccountKind::Validator => stored_version::<TrinityValidator>(body),
+                AccountKind::ProofRecord => stored_version::<ProofRecord>(body),
+                AccountKind::OperatorMetrics => stored_version::<OperatorMetrics>(body),
+                AccountKind::ConsensusState => stored_version::<ConsensusState>(body),
+                AccountKind::CheckConfig => stored_version::<CheckConfig>(body),
+            }
+        }
+        
+        fn min_space(self) -> usize {
+            match self {
+                AccountKind::Validator => TrinityValidator::MIN_SPACE,
+                AccountKind::ProofRecord => ProofRecord::MIN_SPACE,
+                AccountKind::OperatorMetrics => OperatorMetrics::MIN_SPACE,
+                AccountKind::ConsensusState => ConsensusState::MIN_SPACE,
+                AccountKind::CheckConfig => CheckConfig::MIN_SPACE,
+            }
+        }
+    }
+    
+    /// A stored account brought to its current layout
+    #[derive(Debug)]
+    pub struct Upgrade {
+        pub kind: AccountKind,
+        pub steps: Vec<(u8, u8)>,
+        pub data: Vec<u8>,
+    }
+    
+    /// Run the registry steps from the stored version of `data` (discriminator
+    /// included) to the current one. The result is at least the kind's
+    /// MIN_SPACE and never shorter than `data`, zero-padded.
+    pub fn upgrade(data: &[u8]) -> Result<Upgrade> {
+        let kind = AccountKind::of(data);
+        require_ctx!(kind.is_some(), TrinityError::UnknownAccountKind, len = data.len());
+        let kind = kind.unwrap();
+        let current = kind.current_version();
+        let mut version = kind.stored_version(&data[8..]);
+        require_ctx!(
+            version < current,
+            TrinityError::AccountAlreadyCurrent,
+            kind = format!("{:?}", kind),
+            version = version,
+        );
+        
+        let mut body = data[8..].to_vec();
+        let mut steps = Vec::new();
+        while version < current {
+            let step = REGISTRY.iter().find(|step| step.kind == kind && step.from == version);
+            require_ctx!(
+                step.is_some(),
+                TrinityError::MigrationMissing,
+                kind = format!("{:?}", kind),
+                from = version,
+            );
+            let step = step.unwrap();
+            body = (step.migrate)(&body)?;
+            steps.push((step.from, step.to));
+            version = step.to;
+        }
+        
+        let mut upgraded = data[..8].to_vec();
+        upgraded.extend_from_slice(&body);
+        upgraded.resize(upgraded.len().max(kind.min_space()).max(data.len()), 0);
+        Ok(Upgrade { kind, steps, data: upgraded })
+    }
 }
 
 // ============================================================================
-// Events
+// DEVTOOLS (localnet only, `devtools` feature)
 // ============================================================================
 
-#[event]
-pub struct ProofGenerated {
-    pub operation_id: [u8; 32],
-    pub merkle_root: [u8; 32],
-    pub solana_block_hash: [u8; 32],
-    pub solana_block_number: u64,
-    pub timestamp: u64,
-}
-
-#[event]
-pub struct OperationVerified {
-    pub vault_id: u64,
-    pub vault_owner: Pubkey,
-    pub operation_type: OperationType,
-    pub amount: u64,
-    pub user: Pubkey,
-    pub verification_hash: [u8; 32],
-}
-
-// High-frequency monitoring events
-#[event]
-pub struct MonitoringCheckRecorded {
-    pub validator: Pubkey,
-    pub check_type: MonitoringCheckType,
-    pub timestamp: u64,
-    pub slot: u64,
-    pub latency_ms: u64,
-    pub operation_count: u32,
-    pub proof_generated: bool,
-    pub slots_since_last_check: u64,
+#[cfg(feature = "devtools")]
+pub mod devtools {
+    use super::*;
+    
+    /// Localnet defaults: placeholder bridge/signer addresses and a local
+    /// Arbitrum node (nitro-testnode's default RPC port)
+    pub const DEVTOOLS_BRIDGE_ADDRESS: [u8; 20] = [0xaa; 20];
+    pub const DEVTOOLS_VALIDATOR_ETH_ADDRESS: [u8; 20] = [0xbb; 20];
+    pub const DEVTOOLS_ARBITRUM_RPC_URL: &str = "http://127.0.0.1:8547";
+    
+    /// Deterministic dispute council keys. Secret seeds are public on purpose:
+    /// `Keypair.fromSeed(DEVTOOLS_COUNCIL_SEEDS[i])` signs as member i.
+    pub const DEVTOOLS_COUNCIL_SEEDS: [[u8; 32]; 3] = [[0xd1; 32], [0xd2; 32], [0xd3; 32]];
+    pub const DEVTOOLS_COUNCIL: [Pubkey; 3] = [
+        // 8TpTZAippgEQ8qJFsmx8RYDbXMxqMrqHJEo7CrTehpVF
+        Pubkey::new_from_array([
+            0x6e, 0xe0, 0x91, 0xfd, 0x28, 0x0a, 0x9b, 0x68, 0x55, 0x4f, 0xa7, 0x3c, 0x58, 0x81, 0x25, 0xd4,
+            0x7d, 0x34, 0x25, 0xc6, 0x8a, 0x26, 0xba, 0x23, 0x6b, 0x4d, 0xad, 0x90, 0xf9, 0x0a, 0x8f, 0x92,
+        ]),
+        // 5SHZSriNACrYm32eXQFXythrkBM8sWxoFmsBS5hkZ76k
+        Pubkey::new_from_array([
+            0x41, 0xe8, 0xfd, 0xe1, 0x32, 0xad, 0x67, 0x0e, 0x53, 0x4c, 0xd8, 0xb2, 0x75, 0xd2, 0xcd, 0x7e,
+            0xec, 0x77, 0x73, 0x3c, 0x66, 0xf8, 0xdb, 0x48, 0xa1, 0xca, 0xda, 0x7f, 0xab, 0xfc, 0x45, 0x55,
+        ]),
+        // D9R1yZCctRaqnQyD6dTthaKxYX6s9TUUT8undKVMPNGc
+        Pubkey::new_from_array([
+            0xb4, 0x74, 0x0b, 0xa4, 0xe0, 0xf7, 0xe0, 0x57, 0x6f, 0x11, 0xe8, 0x14, 0x9d, 0xbb, 0xb2, 0x52,
+            0x9c, 0x21, 0x22, 0x13, 0xdb, 0x67, 0x98, 0x31, 0xfb, 0x5d, 0x1b, 0x22, 0x1e, 0x84, 0x55, 0x2d,
+        ]),
+    ];
+    pub const DEVTOOLS_COUNCIL_THRESHOLD: u8 = 2;
+    
+    pub fn bootstrap_localnet(ctx: Context<BootstrapLocalnet>) -> Result<()> {
+        let now = Clock::get()?;
+        
+        let validator = &mut ctx.accounts.validator;
+        validator.authority = ctx.accounts.authority.key();
+        validator.ethereum_bridge_address = DEVTOOLS_BRIDGE_ADDRESS;
+        validator.validator_ethereum_address = DEVTOOLS_VALIDATOR_ETH_ADDRESS;
+        validator.arbitrum_rpc_url = DEVTOOLS_ARBITRUM_RPC_URL.to_string();
+        validator.total_proofs_submitted = 0;
+        validator.last_processed_operation = 0;
+        validator.is_active = true;
+        validator.proof_expiry_secs = DEFAULT_PROOF_EXPIRY_SECS;
+        validator.proof_retention_secs = DEFAULT_PROOF_RETENTION_SECS;
+        validator.sweep_bounty_bps = DEFAULT_SWEEP_BOUNTY_BPS;
+        validator.store_tx_signatures = true;
+        validator.operation_committer = ctx.accounts.authority.key();
+        validator.max_proof_budget = DEFAULT_MAX_PROOF_BUDGET;
+        validator.bump = *ctx.bumps.get("validator").unwrap();
+        validator.layout_version = TrinityValidator::LAYOUT_VERSION;
+        
+        let monitor_config = &mut ctx.accounts.monitor_config;
+        monitor_config.validator = validator.key();
+        monitor_config.monitoring_interval_ms = DEFAULT_MONITORING_INTERVAL_MS;
+        monitor_config.max_latency_ms = TARGET_PROOF_LATENCY_MS;
+        monitor_config.last_check_timestamp = now.unix_timestamp as u64;
+        monitor_config.last_check_slot = now.slot;
+        monitor_config.total_checks = 0;
+        monitor_config.successful_proofs = 0;
+        monitor_config.failed_proofs = 0;
+        monitor_config.average_latency_ms = 0;
+        monitor_config.is_active = true;
+        monitor_config.bump = *ctx.bumps.get("monitor_config").unwrap();
+        
+        let council = &mut ctx.accounts.council;
+        council.validator = validator.key();
+        council.members = DEVTOOLS_COUNCIL.to_vec();
+        council.threshold = DEVTOOLS_COUNCIL_THRESHOLD;
+        council.bump = *ctx.bumps.get("council").unwrap();
+        
+        msg!("🧪 Localnet bootstrapped (devtools build)");
+        msg!("   Monitoring: {}ms", DEFAULT_MONITORING_INTERVAL_MS);
+        msg!("   Council: {}-of-{}", DEVTOOLS_COUNCIL_THRESHOLD, DEVTOOLS_COUNCIL.len());
+        Ok(())
+    }
 }
 
-#[event]
-pub struct FastProofGenerated {
-    pub vault_id: u64,
-    pub operation_hash: [u8; 32],
-    pub verification_hash: [u8; 32],
-    pub urgency_level: u8,
-    pub timestamp: u64,
-    pub slot: u64,
-}
+// ============================================================================
+// Errors
+// ============================================================================
 
-/// SLA breach alert - emitted when latency exceeds target
-#[event]
-pub struct SlaBreachAlert {
-    pub validator: Pubkey,
-    pub latency_ms: u64,
-    pub target_latency_ms: u64,
-    pub breach_severity: u8,  // 1 = warning (>5s), 2 = critical (>10s)
-    pub timestamp: u64,
-    pub slot: u64,
+#[error_code]
+pub enum TrinityError {
+    #[msg("Validator is not active")]
+    ValidatorNotActive,
+    
+    #[msg("Proof already submitted to Ethereum")]
+    AlreadySubmitted,
+    
+    #[msg("Vault ID mismatch")]
+    VaultMismatch,
+    
+    #[msg("Unauthorized user")]
+    UnauthorizedUser,
+    
+    #[msg("Invalid Merkle proof")]
+    InvalidMerkleProof,
+    
+    #[msg("Operation not found")]
+    OperationNotFound,
+    
+    #[msg("Vault not initialized (owned by System program)")]
+    VaultNotInitialized,
+    
+    // High-frequency monitoring errors
+    #[msg("Monitoring interval too low (minimum 400ms)")]
+    MonitoringIntervalTooLow,
+    
+    #[msg("Monitoring interval too high (maximum 60000ms)")]
+    MonitoringIntervalTooHigh,
+    
+    #[msg("Monitoring is not active")]
+    MonitoringNotActive,
+    
+    #[msg("Invalid urgency level (must be 1-3)")]
+    InvalidUrgencyLevel,
+    
+    // Proof lifecycle errors
+    #[msg("Proof has expired")]
+    ProofExpired,
+    
+    #[msg("Invalid proof lifecycle configuration")]
+    InvalidLifecycleConfig,
+    
+    #[msg("Sweep bounty exceeds maximum (2000 bps)")]
+    SweepBountyTooHigh,
+    
+    #[msg("Invalid sweep batch size (must be 1-20)")]
+    InvalidSweepBatch,
+    
+    #[msg("Merkle proof too long (maximum 10 levels)")]
+    MerkleProofTooLong,
+    
+    #[msg("RPC URL too long (maximum 200 bytes)")]
+    RpcUrlTooLong,
+    
+    #[msg("Daily stats are still within the retention period")]
+    StatsRetentionActive,
+    
+    // Manual override errors
+    #[msg("Invalid dispute council or threshold")]
+    InvalidCouncil,
+    
+    #[msg("Not enough dispute council approvals")]
+    InsufficientCouncilApprovals,
+    
+    #[msg("Signer is not a dispute council member")]
+    NotCouncilMember,
+    
+    #[msg("Override delay has not elapsed")]
+    OverrideDelayActive,
+    
+    #[msg("Override requires a justification hash")]
+    MissingJustification,
+    
+    #[msg("Localnet bootstrap is only available in devtools builds")]
+    DevtoolsDisabled,
+    
+    // HTLC attestation errors
+    #[msg("Preimage is only accepted for HTLC swaps")]
+    PreimageNotAllowed,
+    
+    #[msg("Preimage does not hash to the hashlock")]
+    HashlockMismatch,    
+    // Operator fee vault errors
+    #[msg("Proof record already exists")]
+    ProofAlreadyExists,
+    
+    #[msg("Fee vault balance too low")]
+    FeeVaultInsufficient,
+    
+    #[msg("Daily sponsored proof cap reached")]
+    SponsorCapReached,
+    
+    #[msg("Prefund amount must be non-zero")]
+    InvalidPrefundAmount,
+    
+    #[msg("Operation was recently submitted")]
+    OperationAlreadySeen,
+    
+    // Consensus finalization errors
+    #[msg("Consensus already finalized for this operation")]
+    ConsensusAlreadyFinalized,
+    
+    #[msg("Solana proof is not confirmed on Ethereum")]
+    ProofNotConfirmed,
+    
+    #[msg("Consensus needs 2-3 distinct chain IDs in ascending order, including Solana")]
+    InvalidConsensusChains,    
+    // Shadow-mode rule errors
+    #[msg("Proof submitter is not the validator authority")]
+    UnauthorizedSubmitter,
+    
+    #[msg("Referenced block is too old")]
+    StaleBlock,
+    
+    #[msg("Merkle proof is empty")]
+    EmptyMerkleProof,
+    
+    #[msg("Enforcement change timelock has not elapsed")]
+    EnforcementTimelockActive,
+    
+    #[msg("No enforcement change queued for this check")]
+    NoQueuedEnforcement,    
+    #[msg("Operation amount exceeds its type's limit")]
+    AmountExceedsTypeLimit,    
+    #[msg("Operation was force-expired")]
+    OperationFailed,
+    
+    #[msg("Too many operator metrics accounts for one rollup")]
+    RollupBatchTooLarge,
+    
+    #[msg("Cross-chain transfer verification needs the user's bridged total account")]
+    BridgedTotalRequired,
+    
+    #[msg("Cross-chain transfer would exceed the user's bridge cap")]
+    BridgeCapExceeded,
+    
+    #[msg("Operation type is not enabled in the check config")]
+    OperationTypeNotEnabled,
+    
+    #[msg("Unknown operation type code belongs to a named type")]
+    NonCanonicalOperationType,
+    
+    #[msg("Operation type is already enabled")]
+    OperationTypeAlreadyEnabled,
+    
+    #[msg("Operation type registry is full")]
+    OperationTypeRegistryFull,
+    
+    #[msg("Proof sequence number overflowed")]
+    ProofSequenceOverflow,
+    
+    #[msg("Too many operator metrics accounts for one scoring call")]
+    ScoreBatchTooLarge,
+    
+    #[msg("Operator at fault doesn't match the override proposal")]
+    AtFaultOperatorMismatch,
+    
+    #[msg("Validator is monitor-only until a reconciliation mismatch is acknowledged")]
+    MonitorOnly,
+    
+    #[msg("Validator is not in monitor-only mode")]
+    NotMonitorOnly,
+    
+    #[msg("Checkpoint is not newer than the stored one")]
+    StaleCheckpoint,
+    
+    #[msg("Checkpoint count is older than the reconciliation window")]
+    CheckpointOutsideWindow,
+    
+    #[msg("Transaction's proof submissions exceed the max proof budget")]
+    ProofBudgetExceeded,
+    
+    #[msg("Max proof budget must fit at least one single-level proof")]
+    InvalidProofBudget,
+    
+    #[msg("Operation has not reached organic consensus")]
+    ConsensusNotReached,
+    
+    #[msg("Consensus mirror is still within its retention period")]
+    MirrorRetentionActive,
+    
+    #[msg("Reward vault or token program missing, or not the pool's vault")]
+    InvalidRewardVault,
+    
+    #[msg("Reward recipients must be one reward-mint account of each contributor")]
+    InvalidRewardRecipients,
+    
+    #[msg("Reward vault balance doesn't cover the payout")]
+    RewardPoolUnderfunded,
+    
+    #[msg("Reward treasury is not configured, or the token account isn't the treasury's")]
+    InvalidRewardTreasury,
+    
+    #[msg("Reward vault holds nothing above its reserve")]
+    NoRewardSurplus,
+    
+    #[msg("Account layout is out of date; run migrate_account first")]
+    AccountNeedsMigration,
+    
+    #[msg("Account is not of a versioned kind")]
+    UnknownAccountKind,
+    
+    #[msg("Account is already at the current layout")]
+    AccountAlreadyCurrent,
+    
+    #[msg("No registered migration from the account's layout")]
+    MigrationMissing,
+    
+    #[msg("Operation is paused pending a dispute")]
+    OperationPaused,
+    
+    #[msg("Build hash must be nonzero")]
+    InvalidBuildHash,
+    
+    #[msg("Risk flag is not raised")]
+    RiskFlagNotRaised,
+    
+    #[msg("A chain halt was detected: resume_after_halt is required before new submissions")]
+    ResumeRequired,
+    
+    #[msg("No chain halt detected")]
+    NoHaltDetected,
+    
+    #[msg("Proof generated after a halt needs more Ethereum confirmations")]
+    HaltConfirmationsPending,
+    
+    #[msg("Invalid halt policy")]
+    InvalidHaltPolicy,
+    
+    #[msg("Proof reissue limit reached: the operation must be confirmed or expire")]
+    ReissueLimitReached,
+    
+    #[msg("Withdrawals from a vesting schedule need its emergency proposal account")]
+    EmergencyProposalRequired,
+    
+    #[msg("An emergency proposal is pending on this vesting schedule")]
+    ConflictingEmergencyPending,
+    
+    #[msg("RPC URL must start with https:// and contain no control characters")]
+    InvalidRpcUrl,
+    
+    #[msg("Protocol invariant violated")]
+    InvariantViolated,
+    
+    #[msg("Invariant checks take distinct ProofRecord, ConsensusState or OperatorMetrics accounts of this program")]
+    InvalidInvariantAccount,
+    
+    #[msg("A confirmed submission must name its Ethereum transaction")]
+    MissingEthereumTxHash,
+    
+    #[msg("Positional Merkle proofs take one left/right flag per level; sorted proofs take none")]
+    InvalidMerklePath,
+    
+    #[msg("Export pages continue from the ledger cursor, in ascending address order")]
+    ExportCursorMismatch,
+    
+    #[msg("Not an exportable account of this kind")]
+    InvalidExportAccount,
+    
+    #[msg("The validator must be deactivated for a state export or import")]
+    ValidatorNotFrozen,
+    
+    #[msg("State import rejected")]
+    InvalidStateImport,
+    
+    #[msg("Unsupported state export format")]
+    UnsupportedExportFormat,
+    
+    #[msg("Stake token accounts must be the stake vault or the operator's own, in the stake mint")]
+    InvalidStakeAccount,
+    
+    #[msg("Not enough bonded stake")]
+    InsufficientStake,
+    
+    #[msg("Stake stays bonded for STAKE_UNBONDING_SECS after the operator's latest attestation")]
+    StakeLocked,
+    
+    #[msg("This operator already attested to the operation")]
+    AlreadyAttested,
+    
+    #[msg("The operation has the maximum number of attestations")]
+    ContributorLimitReached,
+    
+    #[msg("Attested stake does not exceed the consensus weight threshold")]
+    StakeWeightNotReached,
+    
+    #[msg("Consensus window must be nonzero")]
+    InvalidConsensusWindow,
+    
+    #[msg("Destination chain ID must be nonzero")]
+    InvalidDestinationChain,
+    
+    #[msg("The proof is confirmed on the maximum number of destination chains")]
+    ConfirmationLimitReached,
+    
+    #[msg("SlotHashes sysvar has no entries to fingerprint the environment with")]
+    SlotHashesUnavailable,
 }
 
-// ============================================================================
-// Helper Functions
-// ============================================================================
-
-/// Calculate Merkle root from proof and leaf
-fn calculate_merkle_root(proof: &[[u8; 32]], leaf: &[u8; 32]) -> [u8; 32] {
-    let mut current_hash = *leaf;
+#[cfg(test)]
+mod tests {
+    use super::*;
     
-    for proof_element in proof {
-        current_hash = if current_hash < *proof_element {
-            hashv(&[&current_hash, proof_element]).0
-        } else {
-            hashv(&[proof_element, &current_hash]).0
+    /// Small deterministic generator, so the property checks cover a spread
+    /// of inputs without a proptest dependency
+    struct Lcg(u64);
+    
+    impl Lcg {
+        fn below(&mut self, bound: u64) -> u64 {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (self.0 >> 33) % bound
+        }
+        
+        fn inputs(&mut self) -> (u64, ScoreComponents) {
+            let proofs = self.below(200);
+            let inputs = ScoreComponents {
+                latency_slots: self.below(proofs * MAX_PROOF_SLOT_AGE + 1),
+                divergences: self.below(20),
+                disputes_lost: self.below(10),
+                missed_heartbeats: self.below(300),
+            };
+            (proofs, inputs)
+        }
+    }
+    
+    const SAMPLES: usize = 5_000;
+    
+    #[test]
+    fn period_score_never_rises_with_a_penalty() {
+        let mut rng = Lcg(7);
+        let bumps: [fn(&mut ScoreComponents, u64); 4] = [
+            |c, n| c.latency_slots += n,
+            |c, n| c.divergences += n,
+            |c, n| c.disputes_lost += n,
+            |c, n| c.missed_heartbeats += n,
+        ];
+        for _ in 0..SAMPLES {
+            let (proofs, inputs) = rng.inputs();
+            let score = period_score(proofs, &inputs);
+            assert!(score <= SCORE_BPS);
+            for bump in bumps {
+                let mut worse = inputs;
+                bump(&mut worse, 1 + rng.below(50));
+                assert!(period_score(proofs, &worse) <= score, "{} {:?} -> {:?}", proofs, inputs, worse);
+            }
+        }
+    }
+    
+    #[test]
+    fn period_score_extremes() {
+        assert_eq!(period_score(0, &ScoreComponents::default()), SCORE_BPS);
+        assert_eq!(period_score(10, &ScoreComponents::default()), SCORE_BPS);
+        
+        // Every proof at the freshness bound, one divergence per proof: only liveness is left
+        let inputs = ScoreComponents { latency_slots: 10 * MAX_PROOF_SLOT_AGE, divergences: 10, ..Default::default() };
+        let accuracy = SCORE_BPS / 2 * SCORE_WEIGHT_ACCURACY_BPS / SCORE_BPS;
+        assert_eq!(period_score(10, &inputs), accuracy + SCORE_WEIGHT_LIVENESS_BPS);
+        
+        // Latency past the bound scores as the bound
+        let capped = ScoreComponents { latency_slots: 10 * MAX_PROOF_SLOT_AGE, ..Default::default() };
+        let over = ScoreComponents { latency_slots: 100 * MAX_PROOF_SLOT_AGE, ..Default::default() };
+        assert_eq!(period_score(10, &capped), period_score(10, &over));
+    }
+    
+    #[test]
+    fn decayed_score_never_falls_as_a_score_rises() {
+        let mut rng = Lcg(11);
+        for _ in 0..SAMPLES {
+            let (previous, period, epochs) = (rng.below(SCORE_BPS + 1), rng.below(SCORE_BPS + 1), rng.below(20));
+            let score = decayed_score(previous, period, epochs);
+            assert!(score <= SCORE_BPS);
+            assert!(score >= previous.min(period) && score <= previous.max(period));
+            let more = 1 + rng.below(100);
+            assert!(decayed_score((previous + more).min(SCORE_BPS), period, epochs) >= score);
+            assert!(decayed_score(previous, (period + more).min(SCORE_BPS), epochs) >= score);
+        }
+    }
+    
+    #[test]
+    fn decay_halves_the_gap_each_epoch() {
+        assert_eq!(decayed_score(8_000, 0, 0), 8_000);
+        assert_eq!(decayed_score(8_000, 0, 1), 4_000);
+        assert_eq!(decayed_score(8_000, 0, 3), 1_000);
+        assert_eq!(decayed_score(0, 10_000, 2), 7_500);
+        assert_eq!(decayed_score(SCORE_BPS, 0, 1_000), decayed_score(SCORE_BPS, 0, MAX_DECAY_EPOCHS));
+    }
+    
+    #[test]
+    fn missed_heartbeats_are_counted_once() {
+        let mut metrics = OperatorMetrics {
+            operator: Pubkey::default(),
+            proofs_submitted: 0,
+            rolled_up: 0,
+            last_submission: 0,
+            bump: 0,
+            last_heartbeat: 0,
+            score_inputs: ScoreComponents::default(),
+            scored_proofs: 0,
+            scored_inputs: ScoreComponents::default(),
+            score: 0,
+            scored_at: 0,
+            layout_version: OperatorMetrics::LAYOUT_VERSION,
+        };
+        record_heartbeat(&mut metrics, 1_000);
+        record_heartbeat(&mut metrics, 1_000 + HEARTBEAT_INTERVAL_SECS);
+        assert_eq!(metrics.score_inputs.missed_heartbeats, 0);
+        
+        // A crank mid-gap and the next heartbeat split the gap between them
+        let last = metrics.last_heartbeat;
+        accrue_missed_heartbeats(&mut metrics, last + 2 * HEARTBEAT_INTERVAL_SECS + 10);
+        assert_eq!(metrics.score_inputs.missed_heartbeats, 2);
+        record_heartbeat(&mut metrics, last + 4 * HEARTBEAT_INTERVAL_SECS);
+        assert_eq!(metrics.score_inputs.missed_heartbeats, 3);
+    }
+    
+    #[test]
+    fn rolling_hash_is_kept_for_the_reconcile_window() {
+        let mut sequence = ProofSequence {
+            global_proof_seq: 0,
+            rolling_hash: [0u8; 32],
+            recent: [SequenceCheckpoint::default(); RECONCILE_WINDOW],
+            bump: 0,
         };
+        let mut hashes = vec![[0u8; 32]];
+        for i in 0..(RECONCILE_WINDOW as u8 + 10) {
+            sequence.advance(&[i; 32], 1_700_000_000 + i as i64).unwrap();
+            hashes.push(hashv(&[hashes.last().unwrap(), &[i; 32]]).0);
+        }
+        let latest = sequence.global_proof_seq;
+        
+        assert_eq!(sequence.rolling_hash, hashes[latest as usize]);
+        assert_eq!(sequence.rolling_hash_at(0), Some([0u8; 32]));
+        for count in (latest + 1 - RECONCILE_WINDOW as u64)..=latest {
+            assert_eq!(sequence.rolling_hash_at(count), Some(hashes[count as usize]));
+        }
+        assert_eq!(sequence.rolling_hash_at(latest - RECONCILE_WINDOW as u64), None);
+        assert_eq!(sequence.rolling_hash_at(latest + 1), None);
+        assert_eq!(sequence.checkpoint_at(latest).unwrap().eth_event_timestamp, 1_700_000_000 + latest as i64 - 1);
     }
     
-    current_hash
-}
-
-// ============================================================================
-// Errors
-// ============================================================================
-
-#[error_code]
-pub enum TrinityError {
-    #[msg("Validator is not active")]
-    ValidatorNotActive,
+    /// Layout snapshot of the public ConsensusMirror ABI: if this fails, the
+    /// change needs a new CONSENSUS_MIRROR_SCHEMA_VERSION, not a new snapshot
+    #[test]
+    fn consensus_mirror_layout_is_stable() {
+        let mirror = ConsensusMirror {
+            schema_version: CONSENSUS_MIRROR_SCHEMA_VERSION,
+            operation_id: [0x11; 32],
+            merkle_root: [0x22; 32],
+            consensus_at: 0x0102_0304_0506_0708,
+            chain_bitmap: 0b011,
+        };
+        let mut data = Vec::new();
+        mirror.try_serialize(&mut data).unwrap();
+        
+        // sha256("account:ConsensusMirror")[..8], then the fields at their documented offsets
+        let mut expected = vec![0x20, 0x73, 0x77, 0x3e, 0x00, 0x5b, 0xf9, 0xa7];
+        expected.push(1);
+        expected.extend_from_slice(&[0x11; 32]);
+        expected.extend_from_slice(&[0x22; 32]);
+        expected.extend_from_slice(&[0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01]);
+        expected.push(0b011);
+        assert_eq!(data, expected);
+        assert_eq!(data.len(), 8 + ConsensusMirror::INIT_SPACE);
+        assert_eq!(CONSENSUS_MIRROR_SCHEMA_VERSION, 1);
+    }
     
-    #[msg("Proof already submitted to Ethereum")]
-    AlreadySubmitted,
+    #[test]
+    fn chain_bitmap_sets_one_bit_per_chain() {
+        assert_eq!(chain_bitmap(&[ARBITRUM_CHAIN_ID, SOLANA_CHAIN_ID]), 0b011);
+        assert_eq!(chain_bitmap(&[SOLANA_CHAIN_ID, TON_CHAIN_ID]), 0b110);
+        assert_eq!(chain_bitmap(&[ARBITRUM_CHAIN_ID, SOLANA_CHAIN_ID, TON_CHAIN_ID]), 0b111);
+        assert_eq!(chain_bitmap(&[]), 0);
+    }
     
-    #[msg("Vault ID mismatch")]
-    VaultMismatch,
+    #[test]
+    fn reward_share_is_pro_rata_to_stake() {
+        assert_eq!(reward_share(100, 200, 1_000), 20);
+        assert_eq!(reward_share(100, 1, 3), 33);
+        assert_eq!(reward_share(2, 1, 3), 0);
+        assert_eq!(reward_share(100, 1_000, 1_000), 100);
+        // No overflow with large rewards and stakes
+        assert_eq!(reward_share(u64::MAX, u64::MAX - 1, u64::MAX), u64::MAX - 1);
+    }
     
-    #[msg("Unauthorized user")]
-    UnauthorizedUser,
+    #[test]
+    fn reward_surplus_keeps_the_reserve_and_the_next_payout() {
+        assert_eq!(reward_surplus(1_000, 300, 100), 700);
+        // A reserve below one operation's reward still leaves that reward
+        assert_eq!(reward_surplus(1_000, 0, 100), 900);
+        assert_eq!(reward_surplus(250, 300, 100), 0);
+        // Payouts disabled: everything above the reserve is surplus
+        assert_eq!(reward_surplus(1_000, 0, 0), 1_000);
+    }
     
-    #[msg("Invalid Merkle proof")]
-    InvalidMerkleProof,
+    #[test]
+    fn proof_budget_charges_each_submission_and_level() {
+        assert_eq!(proof_budget(&[]), 0);
+        assert_eq!(proof_budget(&[0]), PROOF_BUDGET_BASE_UNITS);
+        assert_eq!(proof_budget(&[3, 1]), 2 * PROOF_BUDGET_BASE_UNITS + 4 * PROOF_BUDGET_LEVEL_UNITS);
+        assert_eq!(proof_budget(&[u64::MAX]), u64::MAX);
+        // The default fits four full-length proofs and no more
+        let full = MAX_MERKLE_PROOF_LEN as u64;
+        assert_eq!(proof_budget(&[full; 4]), DEFAULT_MAX_PROOF_BUDGET);
+        assert!(proof_budget(&[full, full, full, full, 0]) > DEFAULT_MAX_PROOF_BUDGET);
+    }
     
-    #[msg("Operation not found")]
-    OperationNotFound,
+    #[test]
+    fn reported_latency_is_clamped_and_capped() {
+        assert_eq!(observed_latency(1_000, 997), (3, false));
+        assert_eq!(observed_latency(1_000, 1_010), (0, false));
+        assert_eq!(observed_latency(1_000, 1_000 - MAX_PLAUSIBLE_LATENCY_SECS as i64), (MAX_PLAUSIBLE_LATENCY_SECS, false));
+        assert_eq!(observed_latency(10_000, 0), (MAX_PLAUSIBLE_LATENCY_SECS, true));
+        assert_eq!(observed_latency(i64::MAX, i64::MIN), (MAX_PLAUSIBLE_LATENCY_SECS, true));
+    }
     
-    #[msg("Vault not initialized (owned by System program)")]
-    VaultNotInitialized,
+    #[test]
+    fn latency_histogram_reports_the_p95_bucket() {
+        assert_eq!(latency_bucket(0), 0);
+        assert_eq!(latency_bucket(2), 1);
+        assert_eq!(latency_bucket(3), 2);
+        assert_eq!(latency_bucket(300), LATENCY_BUCKETS - 2);
+        assert_eq!(latency_bucket(301), LATENCY_BUCKETS - 1);
+        
+        assert_eq!(latency_percentile_bound(&[0; LATENCY_BUCKETS], 95), 0);
+        // 95 of 100 samples within 5s: the 95th is still in the 5s bucket
+        assert_eq!(latency_percentile_bound(&[50, 40, 5, 5, 0, 0, 0, 0], 95), 5);
+        assert_eq!(latency_percentile_bound(&[50, 40, 4, 5, 0, 0, 0, 1], 95), 10);
+        assert_eq!(latency_percentile_bound(&[0, 0, 0, 0, 0, 0, 0, 1], 95), MAX_PLAUSIBLE_LATENCY_SECS);
+    }
     
-    // High-frequency monitoring errors
-    #[msg("Monitoring interval too low (minimum 400ms)")]
-    MonitoringIntervalTooLow,
+    fn validator_fixture() -> TrinityValidator {
+        TrinityValidator {
+            authority: Pubkey::new_from_array([0x11; 32]),
+            ethereum_bridge_address: [0xaa; 20],
+            validator_ethereum_address: [0xbb; 20],
+            arbitrum_rpc_url: "https://arb1.example.org".to_string(),
+            total_proofs_submitted: 42,
+            last_processed_operation: 7,
+            is_active: true,
+            proof_expiry_secs: DEFAULT_PROOF_EXPIRY_SECS,
+            proof_retention_secs: DEFAULT_PROOF_RETENTION_SECS,
+            sweep_bounty_bps: DEFAULT_SWEEP_BOUNTY_BPS,
+            store_tx_signatures: true,
+            operation_committer: Pubkey::new_from_array([0x22; 32]),
+            monitor_only: false,
+            bump: 254,
+            max_proof_budget: DEFAULT_MAX_PROOF_BUDGET,
+            layout_version: 0,
+        }
+    }
     
-    #[msg("Monitoring interval too high (maximum 60000ms)")]
-    MonitoringIntervalTooHigh,
+    fn proof_record_fixture() -> ProofRecord {
+        ProofRecord {
+            operation_id: [0x01; 32],
+            proof_id: [0x02; 32],
+            merkle_root: [0x03; 32],
+            merkle_proof: vec![[0x04; 32]; 3],
+            solana_block_hash: [0x05; 32],
+            solana_tx_signature: None,
+            solana_block_number: 1_234,
+            solana_epoch: 5,
+            leader_schedule_epoch: 6,
+            environment_hash: [0x06; 32],
+            operation_type: OperationType::VaultWithdrawal,
+            amount: 1_000,
+            amount_limit: u64::MAX,
+            hashlock: [0; 32],
+            preimage: [0; 32],
+            timestamp: 1_700_000_000,
+            submitted_to_ethereum: true,
+            ethereum_tx_hash: [0x07; 32],
+            validator: Pubkey::new_from_array([0x33; 32]),
+            expired: false,
+            expired_at: 0,
+            submission_hash: [0x08; 32],
+            eth_event_timestamp: 1_699_999_998,
+            observed_latency_seconds: 2,
+            latency_implausible: false,
+            layout_version: 0,
+            merkle_mode: MerkleMode::Sorted,
+            sibling_is_left: vec![],
+            consensus_deadline: 0,
+            confirmations: vec![],
+        }
+    }
     
-    #[msg("Monitoring is not active")]
-    MonitoringNotActive,
+    /// An account as stored before layout_version existed: the current
+    /// fields minus the trailing version byte, in an allocation of `space`
+    fn unversioned<T: Versioned>(account: &T, space: usize) -> Vec<u8> {
+        let mut data = T::discriminator().to_vec();
+        let body = account.try_to_vec().unwrap();
+        data.extend_from_slice(&body[..body.len() - 1]);
+        data.resize(space.max(data.len()), 0);
+        data
+    }
     
-    #[msg("Invalid urgency level (must be 1-3)")]
-    InvalidUrgencyLevel,
+    #[test]
+    fn migrates_an_unversioned_validator_in_place() {
+        // Allocated at the old INIT_SPACE, so the short RPC URL leaves padding
+        let fixture = validator_fixture();
+        let stored = unversioned(&fixture, 8 + TrinityValidator::INIT_SPACE - 1);
+        
+        let upgrade = migrations::upgrade(&stored).unwrap();
+        assert_eq!(upgrade.kind, AccountKind::Validator);
+        assert_eq!(upgrade.steps, vec![(0, 1)]);
+        assert_eq!(upgrade.data.len(), 8 + TrinityValidator::INIT_SPACE);
+        
+        let migrated = TrinityValidator::try_deserialize(&mut upgrade.data.as_slice()).unwrap();
+        assert!(migrated.is_current());
+        assert_eq!(migrated.try_to_vec().unwrap(), TrinityValidator { layout_version: 1, ..fixture }.try_to_vec().unwrap());
+        
+        // Padding decodes as version 0, which the guard refuses until migrated
+        let before = TrinityValidator::try_deserialize(&mut stored.as_slice()).unwrap();
+        assert!(!before.is_current());
+        assert_eq!(
+            migrations::upgrade(&upgrade.data).unwrap_err(),
+            error!(TrinityError::AccountAlreadyCurrent)
+        );
+    }
+    
+    #[test]
+    fn migrates_an_exactly_sized_proof_record() {
+        // ProofRecords are allocated at their serialized length: no padding.
+        // Layout 0 ended before layout_version, layout 1 at it, layout 2
+        // before consensus_deadline, layout 3 before confirmations.
+        let fixture = proof_record_fixture();
+        let body = fixture.try_to_vec().unwrap();
+        let layout_3_len = body.len() - 4;
+        let layout_2_len = layout_3_len - 8;
+        let layout_1_len = layout_2_len - 1 - 4;
+        let stored = [&ProofRecord::discriminator()[..], &body[..layout_1_len - 1]].concat();
+        assert!(ProofRecord::try_deserialize(&mut stored.as_slice()).is_err());
+        
+        let upgrade = migrations::upgrade(&stored).unwrap();
+        assert_eq!(upgrade.kind, AccountKind::ProofRecord);
+        assert_eq!(upgrade.steps, vec![(0, 1), (1, 2), (2, 3), (3, 4)]);
+        assert_eq!(upgrade.data.len(), 8 + body.len());
+        
+        let migrated = ProofRecord::try_deserialize(&mut upgrade.data.as_slice()).unwrap();
+        assert!(migrated.is_current());
+        assert_eq!(migrated.try_to_vec().unwrap(), ProofRecord { layout_version: 4, ..fixture.clone() }.try_to_vec().unwrap());
+        
+        // Layout 1 records fold as sorted pairs, as they were written
+        let layout_1 = ProofRecord { layout_version: 1, ..fixture.clone() };
+        let stored = [&ProofRecord::discriminator()[..], &layout_1.try_to_vec().unwrap()[..layout_1_len]].concat();
+        let upgrade = migrations::upgrade(&stored).unwrap();
+        assert_eq!(upgrade.steps, vec![(1, 2), (2, 3), (3, 4)]);
+        let migrated = ProofRecord::try_deserialize(&mut upgrade.data.as_slice()).unwrap();
+        assert_eq!((migrated.merkle_mode, migrated.sibling_is_left.len()), (MerkleMode::Sorted, 0));
+        
+        // Layout 2 records have no fixed deadline and keep the validator's expiry
+        let layout_2 = ProofRecord { layout_version: 2, ..fixture.clone() };
+        let stored = [&ProofRecord::discriminator()[..], &layout_2.try_to_vec().unwrap()[..layout_2_len]].concat();
+        let upgrade = migrations::upgrade(&stored).unwrap();
+        assert_eq!(upgrade.steps, vec![(2, 3), (3, 4)]);
+        let migrated = ProofRecord::try_deserialize(&mut upgrade.data.as_slice()).unwrap();
+        assert_eq!(proof_deadline(&migrated, &validator_fixture()), migrated.timestamp + DEFAULT_PROOF_EXPIRY_SECS);
+        
+        // Layout 3 records keep their Ethereum confirmation, with no per-chain records
+        let layout_3 = ProofRecord { layout_version: 3, ..fixture };
+        let stored = [&ProofRecord::discriminator()[..], &layout_3.try_to_vec().unwrap()[..layout_3_len]].concat();
+        let upgrade = migrations::upgrade(&stored).unwrap();
+        assert_eq!(upgrade.steps, vec![(3, 4)]);
+        let migrated = ProofRecord::try_deserialize(&mut upgrade.data.as_slice()).unwrap();
+        assert_eq!((migrated.submitted_to_ethereum, migrated.ethereum_tx_hash), (true, [0x07; 32]));
+        assert!(migrated.confirmations.is_empty());
+    }
+    
+    #[test]
+    fn registry_reaches_every_current_layout() {
+        let kinds = [
+            AccountKind::Validator,
+            AccountKind::ProofRecord,
+            AccountKind::OperatorMetrics,
+            AccountKind::ConsensusState,
+            AccountKind::CheckConfig,
+        ];
+        for kind in kinds {
+            let mut version = 0;
+            while version < kind.current_version() {
+                let step = migrations::REGISTRY.iter().find(|step| step.kind == kind && step.from == version);
+                version = step.unwrap_or_else(|| panic!("{:?} has no step from {}", kind, version)).to;
+            }
+            assert_eq!(version, kind.current_version());
+        }
+        
+        let mut foreign = DailyStats::discriminator().to_vec();
+        foreign.resize(64, 0);
+        assert_eq!(migrations::upgrade(&foreign).unwrap_err(), error!(TrinityError::UnknownAccountKind));
+    }
+    
+    #[test]
+    fn migrates_a_full_consensus_state_to_the_pause_layout() {
+        let consensus = ConsensusState {
+            operation_id: [0x09; 32],
+            finalized_at: 1_700_000_000,
+            overridden: false,
+            justification_hash: [0; 32],
+            merkle_root: [0x0a; 32],
+            chains: vec![1, 2, 3],
+            finalized: true,
+            failed_at: 0,
+            bump: 253,
+            layout_version: 1,
+            operation_paused: false,
+            contributors: vec![],
+            stake_weight: 0,
+        };
+        // Layout 1 ended at layout_version: with all three chains, no
+        // padding. Layout 2 added a bool, layout 3 an empty Vec and a u64.
+        const LAYOUT_1_SPACE: usize = 123;
+        let body = consensus.try_to_vec().unwrap();
+        let mut stored = ConsensusState::discriminator().to_vec();
+        stored.extend_from_slice(&body[..LAYOUT_1_SPACE]);
+        
+        let upgrade = migrations::upgrade(&stored).unwrap();
+        assert_eq!(upgrade.steps, vec![(1, 2), (2, 3)]);
+        assert_eq!(upgrade.data.len(), 8 + ConsensusState::INIT_SPACE);
+        let migrated = ConsensusState::try_deserialize(&mut upgrade.data.as_slice()).unwrap();
+        assert!(migrated.is_current() && !migrated.operation_paused);
+        assert_eq!(migrated.chains, consensus.chains);
+        assert_eq!((migrated.contributors.len(), migrated.stake_weight), (0, 0));
+        
+        // A layout 2 account sized to its old INIT_SPACE takes the last step
+        let mut stored = ConsensusState::discriminator().to_vec();
+        let layout_2 = ConsensusState { layout_version: 2, ..consensus.clone() }.try_to_vec().unwrap();
+        stored.extend_from_slice(&layout_2[..LAYOUT_1_SPACE + 1]);
+        assert_eq!(migrations::upgrade(&stored).unwrap().steps, vec![(2, 3)]);
+        
+        // Unversioned accounts take every step
+        let without_version = ConsensusState { layout_version: 0, ..consensus }.try_to_vec().unwrap();
+        let mut stored = ConsensusState::discriminator().to_vec();
+        stored.extend_from_slice(&without_version[..LAYOUT_1_SPACE - 1]);
+        assert_eq!(migrations::upgrade(&stored).unwrap().steps, vec![(0, 1), (1, 2), (2, 3)]);
+    }
+    
+    #[test]
+    fn migrates_a_check_config_to_the_window_layout() {
+        // Layout 1 ended at layout_version, allocated at its INIT_SPACE
+        let checks = CheckConfig { layout_version: 1, ..CheckConfig::unconfigured() };
+        let body = checks.try_to_vec().unwrap();
+        let mut stored = CheckConfig::discriminator().to_vec();
+        stored.extend_from_slice(&body[..CheckConfig::INIT_SPACE - 8 * MAX_OPERATION_TYPES]);
+        
+        let upgrade = migrations::upgrade(&stored).unwrap();
+        assert_eq!(upgrade.steps, vec![(1, 2)]);
+        assert_eq!(upgrade.data.len(), 8 + CheckConfig::INIT_SPACE);
+        let migrated = CheckConfig::try_deserialize(&mut upgrade.data.as_slice()).unwrap();
+        assert!(migrated.is_current());
+        assert_eq!(migrated.consensus_window_secs, [0; MAX_OPERATION_TYPES]);
+        assert_eq!(migrated.enabled_codes(), checks.enabled_codes());
+    }
+    
+    #[test]
+    fn consensus_windows_fix_each_proofs_deadline_at_submission() {
+        let mut rules = CheckConfig::unconfigured();
+        let recovery = rules.type_slot(&OperationType::EmergencyRecovery).unwrap();
+        let transfer = rules.type_slot(&OperationType::CrossChainTransfer).unwrap();
+        rules.consensus_window_secs[recovery] = 3 * 86_400;
+        rules.consensus_window_secs[transfer] = 600;
+        
+        let mut validator = validator_fixture();
+        let submitted_at = 1_700_000_000;
+        let submitted = |operation_type, slot: usize| ProofRecord {
+            operation_type,
+            timestamp: submitted_at,
+            submitted_to_ethereum: false,
+            consensus_deadline: consensus_deadline(rules.consensus_window_secs[slot], submitted_at),
+            ..proof_record_fixture()
+        };
+        let recovery_proof = submitted(OperationType::EmergencyRecovery, recovery);
+        let transfer_proof = submitted(OperationType::CrossChainTransfer, transfer);
+        assert_eq!(proof_deadline(&recovery_proof, &validator), submitted_at + 3 * 86_400);
+        assert_eq!(proof_deadline(&transfer_proof, &validator), submitted_at + 600);
+        
+        // The transfer's window closes while the recovery's stays open
+        let now = submitted_at + 600;
+        assert!(proof_is_expirable(&transfer_proof, &validator, now));
+        assert!(!proof_is_expirable(&recovery_proof, &validator, now));
+        
+        // Fixed deadlines ignore later expiry changes; types without a window follow them
+        let withdrawal = rules.type_slot(&OperationType::VaultWithdrawal).unwrap();
+        let withdrawal_proof = submitted(OperationType::VaultWithdrawal, withdrawal);
+        assert_eq!(withdrawal_proof.consensus_deadline, 0);
+        validator.proof_expiry_secs = 60;
+        assert_eq!(proof_deadline(&transfer_proof, &validator), submitted_at + 600);
+        assert_eq!(proof_deadline(&withdrawal_proof, &validator), submitted_at + 60);
+    }
+    
+    fn build_attestation_fixture() -> BuildAttestation {
+        BuildAttestation {
+            upgrade_guard: Pubkey::new_unique(),
+            attestation_window_slots: 100,
+            build_hash: [0x0b; 32],
+            source_commit: source_commit("3f2a9c"),
+            upgrade_slot: 1_000,
+            attested_slot: 1_010,
+            attested_at: 0,
+            attestations: 1,
+            warned_upgrade_slot: 0,
+            bump: 254,
+        }
+    }
+    
+    #[test]
+    fn attested_deployment_is_never_reported() {
+        let attestation = build_attestation_fixture();
+        assert_eq!(unattested_slots(&attestation, 1_000, 1_000_000), None);
+    }
+    
+    #[test]
+    fn upgrade_is_reported_once_after_its_window() {
+        let mut attestation = build_attestation_fixture();
+        assert_eq!(unattested_slots(&attestation, 2_000, 2_099), None);
+        assert_eq!(unattested_slots(&attestation, 2_000, 2_100), Some(100));
+        
+        attestation.warned_upgrade_slot = 2_000;
+        assert_eq!(unattested_slots(&attestation, 2_000, 5_000), None);
+        // A further upgrade gets its own warning
+        assert_eq!(unattested_slots(&attestation, 3_000, 3_100), Some(100));
+    }
+    
+    #[test]
+    fn source_commit_is_zero_padded_and_truncated() {
+        assert_eq!(&source_commit("3f2a9c")[..7], b"3f2a9c\0");
+        assert_eq!(source_commit(&"a".repeat(64)), [b'a'; SOURCE_COMMIT_LEN]);
+    }
+    
+    /// 32 bytes from hex, for fixed test vectors
+    fn bytes32(hex: &str) -> [u8; 32] {
+        let mut out = [0; 32];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+    
+    #[test]
+    fn equal_siblings_fold_like_the_ethereum_verifier() {
+        // Roots TrinityConsensusVerifier._verifyMerkleProof computes, i.e.
+        // keccak256(abi.encodePacked(node, node)) at each tie
+        let leaf = [0x11; 32];
+        let first = bytes32("f7363c7e1636e1798b5b1fa2ad299c05a7bb4bfeba5584fa760b4abb12c65abf");
+        let second = bytes32("428f4497f9150ea70bf869052d5164c24fd56243850398c2e3679eddbc461f8a");
+        
+        assert_eq!(calculate_merkle_root(&[leaf], &leaf), first);
+        // A tie one level up, against the running node rather than the leaf
+        assert_eq!(calculate_merkle_root(&[leaf, first], &leaf), second);
+        // Either order of the equal pair is the same preimage
+        assert_eq!(hashv(&[&leaf, &leaf]).0, first);
+    }
+    
+    /// Four-leaf position-aware tree built as the Ethereum side builds it,
+    /// keccak256(abi.encodePacked(left, right)) at each node, with leaves out
+    /// of sorted order so that sorted folding can't reproduce it
+    fn positional_tree() -> ([[u8; 32]; 4], [u8; 32], [u8; 32], [u8; 32]) {
+        let leaves = [[0x99; 32], [0x11; 32], [0x77; 32], [0x22; 32]];
+        let left = bytes32("b2053a253e40bbccb5445f2caf0ea765f6cbd78635e17b4adfd8a562b259cd8d");
+        let right = bytes32("f175c8f463cb2b205c5909ecd07185e633ae15c96f5740f9ffa409370b14b23b");
+        let root = bytes32("6622108974e9bd18f17d98970a20e135bcfde406fb98d91027f98f76f06c8c8b");
+        (leaves, left, right, root)
+    }
+    
+    #[test]
+    fn positional_proofs_reach_the_ethereum_root() {
+        let (leaves, left, right, root) = positional_tree();
+        let paths = [
+            (vec![leaves[1], right], vec![false, false]),
+            (vec![leaves[0], right], vec![true, false]),
+            (vec![leaves[3], left], vec![false, true]),
+            (vec![leaves[2], left], vec![true, true]),
+        ];
+        for (leaf, (proof, sibling_is_left)) in leaves.iter().zip(&paths) {
+            assert_eq!(MerkleMode::Positional.root(proof, sibling_is_left, leaf), root);
+            assert!(check_merkle_path(MerkleMode::Positional, proof, sibling_is_left).is_ok());
+        }
+        
+        // The same path folded as sorted pairs misses the root
+        let (proof, _) = &paths[1];
+        assert_ne!(MerkleMode::Sorted.root(proof, &[], &leaves[1]), root);
+        // And a flipped flag lands elsewhere
+        assert_ne!(MerkleMode::Positional.root(proof, &[false, false], &leaves[1]), root);
+    }
+    
+    #[test]
+    fn merkle_flags_must_match_the_mode() {
+        let (leaves, _, right, _) = positional_tree();
+        let proof = [leaves[1], right];
+        
+        for flags in [&[true][..], &[true, false, false]] {
+            let violation = check_merkle_path(MerkleMode::Positional, &proof, flags).unwrap_err();
+            assert_eq!(u32::from(violation.error), u32::from(TrinityError::InvalidMerklePath));
+        }
+        let violation = check_merkle_path(MerkleMode::Sorted, &proof, &[true, false]).unwrap_err();
+        assert_eq!(u32::from(violation.error), u32::from(TrinityError::InvalidMerklePath));
+        assert!(check_merkle_path(MerkleMode::Sorted, &proof, &[]).is_ok());
+    }
+    
+    fn risk_thresholds() -> RiskThresholds {
+        RiskThresholds { max_operation_amount: 10_000, max_operator_share_bps: 5_000, max_consensus_secs: 60 }
+    }
+    
+    #[test]
+    fn large_operation_flags_amounts_above_the_threshold() {
+        let at = RiskObservation { amount: Some(10_000), ..RiskObservation::default() };
+        assert!(crossed_risk_flags(&risk_thresholds(), &at).is_empty());
+        let above = RiskObservation { amount: Some(10_001), ..RiskObservation::default() };
+        assert_eq!(crossed_risk_flags(&risk_thresholds(), &above), vec![(RiskFlag::LargeOperation, 10_001, 10_000)]);
+        assert!(crossed_risk_flags(&RiskThresholds::NONE, &RiskObservation { amount: Some(u64::MAX), ..above }).is_empty());
+    }
+    
+    #[test]
+    fn operator_concentration_flags_a_dominant_submitter() {
+        let mut metrics = RiskMetrics {
+            thresholds: risk_thresholds(),
+            queued: QueuedRiskThresholds::NONE,
+            latched: 0,
+            raised_at: [0; RISK_FLAG_COUNT],
+            recent_operators: [Pubkey::default(); RISK_SHARE_WINDOW],
+            recent_proofs: 0,
+            bump: 0,
+        };
+        let (dominant, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // No reading until the window holds RISK_SHARE_MIN_PROOFS
+        for _ in 1..RISK_SHARE_MIN_PROOFS {
+            assert_eq!(metrics.record_submission(dominant), None);
+        }
+        assert_eq!(metrics.record_submission(other), Some(2_500));
+        let share = metrics.record_submission(dominant);
+        assert_eq!(share, Some(8_000));
+        let observation = RiskObservation { operator_share_bps: share, ..RiskObservation::default() };
+        assert_eq!(
+            crossed_risk_flags(&metrics.thresholds, &observation),
+            vec![(RiskFlag::OperatorConcentration, 8_000, 5_000)]
+        );
+        
+        // The window slides: once full, only the latest RISK_SHARE_WINDOW proofs count
+        for _ in 0..RISK_SHARE_WINDOW {
+            metrics.record_submission(other);
+        }
+        assert_eq!(metrics.record_submission(dominant), Some(625));
+        let unanimous = RiskObservation { operator_share_bps: Some(SCORE_BPS), ..observation };
+        assert!(crossed_risk_flags(&RiskThresholds::NONE, &unanimous).is_empty());
+    }
+    
+    #[test]
+    fn slow_consensus_flags_old_proofs() {
+        let prompt = RiskObservation { consensus_secs: Some(60), ..RiskObservation::default() };
+        assert!(crossed_risk_flags(&risk_thresholds(), &prompt).is_empty());
+        let slow = RiskObservation { consensus_secs: Some(61), ..RiskObservation::default() };
+        assert_eq!(crossed_risk_flags(&risk_thresholds(), &slow), vec![(RiskFlag::SlowConsensus, 61, 60)]);
+    }
+    
+    #[test]
+    fn risk_flags_have_distinct_bits() {
+        let flags = [RiskFlag::LargeOperation, RiskFlag::OperatorConcentration, RiskFlag::SlowConsensus];
+        let bits = flags.iter().fold(0u8, |bits, flag| bits | flag.bit());
+        assert_eq!(bits.count_ones() as usize, RISK_FLAG_COUNT);
+        let all = RiskObservation {
+            amount: Some(u64::MAX),
+            operator_share_bps: Some(SCORE_BPS),
+            consensus_secs: Some(u64::MAX),
+        };
+        let crossed: Vec<_> = crossed_risk_flags(&risk_thresholds(), &all).into_iter().map(|(flag, ..)| flag).collect();
+        assert_eq!(crossed, flags);
+    }
+    
+    fn halt_monitor(max_slot_gap: u64) -> HaltMonitor {
+        HaltMonitor {
+            policy: HaltPolicy { max_slot_gap, cooldown_secs: 60, extra_eth_confirmations: 3 },
+            last_heartbeat_slot: 1_000,
+            halted_since: None,
+            resumed_at: 0,
+            cooldown_until: 0,
+            extra_eth_confirmations: 0,
+            halts: 0,
+            bump: 0,
+        }
+    }
+    
+    #[test]
+    fn halt_is_latched_until_resumed() {
+        let mut monitor = halt_monitor(100);
+        assert!(!monitor.observe_slot(1_100));
+        assert_eq!(monitor.last_heartbeat_slot, 1_100);
+        
+        assert!(monitor.observe_slot(1_201));
+        assert_eq!(monitor.halted_since, Some(1_100));
+        // Heartbeats after the gap neither clear the halt nor hide its start
+        assert!(monitor.observe_slot(1_202));
+        assert_eq!((monitor.halted_since, monitor.last_heartbeat_slot), (Some(1_100), 1_100));
+        
+        let mut disabled = halt_monitor(0);
+        assert!(!disabled.observe_slot(u64::MAX));
+    }
+    
+    #[test]
+    fn only_cooldown_proofs_need_extra_confirmations() {
+        let mut monitor = halt_monitor(100);
+        assert_eq!(monitor.required_confirmations(5_000), 0);
+        
+        monitor.resumed_at = 5_000;
+        monitor.cooldown_until = 5_060;
+        monitor.extra_eth_confirmations = 3;
+        assert_eq!(monitor.required_confirmations(4_999), 0);
+        assert_eq!(monitor.required_confirmations(5_000), 3);
+        assert_eq!(monitor.required_confirmations(5_059), 3);
+        assert_eq!(monitor.required_confirmations(5_060), 0);
+    }
+    
+    #[test]
+    fn rpc_urls_must_be_https_without_control_characters() {
+        assert!(is_well_formed_rpc_url("https://sepolia-rollup.arbitrum.io/rpc"));
+        for malformed in [
+            "",
+            "https://",
+            "http://arb1.example.org",
+            "sepolia-rollup.arbitrum.io/rpc",
+            "HTTPS://arb1.example.org",
+            " https://arb1.example.org",
+            "https://arb1.example.org\n",
+            "https://arb1.exa\u{0}mple.org",
+            "https://arb1.example.org/\u{7f}",
+        ] {
+            assert!(!is_well_formed_rpc_url(malformed), "{malformed:?}");
+        }
+    }
+    
+    #[test]
+    fn invariant_checks_flag_each_violation() {
+        let address = Pubkey::new_from_array([0x44; 32]);
+        
+        let mut record = proof_record_fixture();
+        assert!(invariants::check_proof_record(&address, &record).is_ok());
+        record.ethereum_tx_hash = [0; 32];
+        assert!(invariants::check_proof_record(&address, &record).is_err());
+        record.submitted_to_ethereum = false;
+        assert!(invariants::check_proof_record(&address, &record).is_ok());
+        
+        // Destination confirmations imply a confirmed proof, one per chain
+        let confirmation = |chain_id| ChainConfirmation {
+            chain_id,
+            tx_hash: [0x07; 32],
+            block_number: 19_000_000,
+            confirmed_at: 1_700_000_600,
+        };
+        record.confirmations = vec![confirmation(1)];
+        assert!(invariants::check_proof_record(&address, &record).is_err());
+        record.submitted_to_ethereum = true;
+        record.ethereum_tx_hash = [0x07; 32];
+        record.confirmations.push(confirmation(42_161));
+        assert!(invariants::check_proof_record(&address, &record).is_ok());
+        record.confirmations.push(confirmation(1));
+        assert!(invariants::check_proof_record(&address, &record).is_err());
+        
+        let mut consensus = ConsensusState {
+            operation_id: [0x09; 32],
+            finalized_at: 1_700_000_000,
+            overridden: false,
+            justification_hash: [0; 32],
+            merkle_root: [0x0a; 32],
+            chains: vec![1, 2],
+            finalized: true,
+            failed_at: 0,
+            bump: 253,
+            layout_version: ConsensusState::LAYOUT_VERSION,
+            operation_paused: false,
+            contributors: vec![],
+            stake_weight: 0,
+        };
+        assert!(invariants::check_consensus(&address, &consensus).is_ok());
+        consensus.chains = vec![2, 2];
+        assert!(invariants::check_consensus(&address, &consensus).is_err());
+        // Overrides record no chains, and nothing is owed before finalization
+        consensus.chains.clear();
+        assert!(invariants::check_consensus(&address, &consensus).is_err());
+        consensus.overridden = true;
+        assert!(invariants::check_consensus(&address, &consensus).is_ok());
+        consensus.overridden = false;
+        consensus.finalized = false;
+        assert!(invariants::check_consensus(&address, &consensus).is_ok());
+        
+        // The stake weight is exactly what distinct contributors brought
+        let contribution = Contribution { operator: Pubkey::new_from_array([0x45; 32]), stake: 500 };
+        consensus.contributors = vec![contribution];
+        assert!(invariants::check_consensus(&address, &consensus).is_err());
+        consensus.stake_weight = 500;
+        assert!(invariants::check_consensus(&address, &consensus).is_ok());
+        consensus.contributors.push(contribution);
+        consensus.stake_weight = 1_000;
+        assert!(invariants::check_consensus(&address, &consensus).is_err());
+        
+        // 42 rolled up plus 3 awaiting rollup: 45 records created
+        let validator = validator_fixture();
+        let mut metrics = OperatorMetrics {
+            operator: Pubkey::default(),
+            proofs_submitted: 10,
+            rolled_up: 7,
+            last_submission: 0,
+            bump: 0,
+            last_heartbeat: 0,
+            score_inputs: ScoreComponents::default(),
+            scored_proofs: 0,
+            scored_inputs: ScoreComponents::default(),
+            score: 0,
+            scored_at: 0,
+            layout_version: OperatorMetrics::LAYOUT_VERSION,
+        };
+        assert!(invariants::check_proof_count_bound(&validator, 42).is_ok());
+        assert!(invariants::check_proof_count_bound(&validator, 41).is_err());
+        assert!(invariants::check_proof_count(&validator, 45, std::slice::from_ref(&metrics)).is_ok());
+        assert!(invariants::check_proof_count(&validator, 46, std::slice::from_ref(&metrics)).is_err());
+        assert!(invariants::check_operator_metrics(&address, &metrics).is_ok());
+        metrics.rolled_up = 11;
+        assert!(invariants::check_operator_metrics(&address, &metrics).is_err());
+    }
+    
+    /// `account` serialized into an allocation of `space`
+    fn account_data<T: AccountSerialize>(account: &T, space: usize) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data.resize(space.max(data.len()), 0);
+        data
+    }
+    
+    /// One account of every ExportKind as the exporting deployment (this
+    /// program id) holds it, PDA bumps and validator addresses included
+    fn export_fixtures() -> Vec<(ExportKind, Vec<u8>)> {
+        let mut validator = validator_fixture();
+        validator.layout_version = TrinityValidator::LAYOUT_VERSION;
+        validator.is_active = false;
+        let mut check_config = CheckConfig::unconfigured();
+        check_config.max_amount[0] = 5_000;
+        check_config.layout_version = CheckConfig::LAYOUT_VERSION;
+        let council = DisputeCouncil {
+            validator: Pubkey::default(),
+            members: vec![Pubkey::new_from_array([0x44; 32]), Pubkey::new_from_array([0x45; 32])],
+            threshold: 2,
+            bump: 0,
+        };
+        let fee_vault = FeeVault {
+            operator: Pubkey::new_from_array([0x46; 32]),
+            daily_cap: 100,
+            day: 19_700,
+            sponsored_today: 3,
+            total_sponsored: 250,
+            total_rent_sponsored: 1_500_000,
+            bump: 0,
+        };
+        let metrics = OperatorMetrics {
+            operator: Pubkey::new_from_array([0x46; 32]),
+            proofs_submitted: 10,
+            rolled_up: 7,
+            last_submission: 1_700_000_000,
+            bump: 0,
+            last_heartbeat: 1_700_000_100,
+            score_inputs: ScoreComponents { latency_slots: 40, divergences: 1, disputes_lost: 0, missed_heartbeats: 2 },
+            scored_proofs: 8,
+            scored_inputs: ScoreComponents::default(),
+            score: 9_000,
+            scored_at: 1_699_990_000,
+            layout_version: OperatorMetrics::LAYOUT_VERSION,
+        };
+        let mut sequence = ProofSequence {
+            global_proof_seq: 0,
+            rolling_hash: [0; 32],
+            recent: [SequenceCheckpoint::default(); RECONCILE_WINDOW],
+            bump: 0,
+        };
+        for i in 0..70u8 {
+            sequence.advance(&[i; 32], 1_700_000_000 + i as i64).unwrap();
+        }
+        let consensus = ConsensusState {
+            operation_id: [0x09; 32],
+            finalized_at: 1_700_000_000,
+            overridden: false,
+            justification_hash: [0; 32],
+            merkle_root: [0x0a; 32],
+            chains: vec![1, 2],
+            finalized: true,
+            failed_at: 0,
+            bump: 0,
+            layout_version: ConsensusState::LAYOUT_VERSION,
+            operation_paused: false,
+            contributors: vec![],
+            stake_weight: 0,
+        };
+        let mut record = proof_record_fixture();
+        record.submitted_to_ethereum = false;
+        record.ethereum_tx_hash = [0; 32];
+        record.layout_version = ProofRecord::LAYOUT_VERSION;
+        
+        let fixtures = vec![
+            (ExportKind::Validator, account_data(&validator, 8 + TrinityValidator::INIT_SPACE)),
+            (ExportKind::CheckConfig, account_data(&check_config, 8 + CheckConfig::INIT_SPACE)),
+            (ExportKind::DisputeCouncil, account_data(&council, 8 + DisputeCouncil::INIT_SPACE)),
+            (ExportKind::FeeVault, account_data(&fee_vault, 8 + FeeVault::INIT_SPACE)),
+            (ExportKind::OperatorMetrics, account_data(&metrics, 8 + OperatorMetrics::INIT_SPACE)),
+            (ExportKind::ProofSequence, account_data(&sequence, 8 + ProofSequence::INIT_SPACE)),
+            (ExportKind::ConsensusState, account_data(&consensus, 8 + ConsensusState::INIT_SPACE)),
+            (ExportKind::ProofRecord, account_data(&record, 0)),
+        ];
+        // Bind each to this deployment, as its instructions would have
+        fixtures
+            .into_iter()
+            .map(|(kind, data)| (kind, state_export::place(kind, &data, &crate::ID).unwrap().data))
+            .collect()
+    }
+    
+    #[test]
+    fn export_import_round_trip_is_byte_identical() {
+        let fresh = Pubkey::new_from_array([0x5b; 32]);
+        let (fresh_validator, _) = Pubkey::find_program_address(&[b"trinity_validator"], &fresh);
+        let fixtures = export_fixtures();
+        assert_eq!(fixtures.len(), EXPORT_KIND_COUNT);
+        
+        for (kind, exported) in &fixtures {
+            let source = state_export::place(*kind, exported, &crate::ID).unwrap();
+            state_export::check_exportable(*kind, &source.address, exported).unwrap();
+            
+            // Imported: at the fresh program's PDA, rebound to its validator
+            let imported = state_export::place(*kind, exported, &fresh).unwrap();
+            let seeds: Vec<&[u8]> = imported.seeds.iter().map(Vec::as_slice).collect();
+            assert_eq!((imported.address, imported.bump), Pubkey::find_program_address(&seeds, &fresh));
+            assert_eq!(imported.data.len(), exported.len());
+            assert_eq!(imported.data[..8], exported[..8]);
+            
+            // Importing twice changes nothing, and exporting the import back
+            // to the original program id restores every byte
+            assert_eq!(state_export::place(*kind, &imported.data, &fresh).unwrap().data, imported.data);
+            let restored = state_export::place(*kind, &imported.data, &crate::ID).unwrap();
+            assert_eq!(restored.address, source.address);
+            assert_eq!(restored.data, *exported);
+        }
+        
+        let (_, exported) = &fixtures[ExportKind::ProofRecord as usize];
+        let imported = state_export::place(ExportKind::ProofRecord, exported, &fresh).unwrap();
+        let record = ProofRecord::try_deserialize(&mut &imported.data[..]).unwrap();
+        assert_eq!(record.validator, fresh_validator);
+        assert_eq!(record.proof_id, proof_record_fixture().proof_id);
+        let (_, exported) = &fixtures[ExportKind::DisputeCouncil as usize];
+        let imported = state_export::place(ExportKind::DisputeCouncil, exported, &fresh).unwrap();
+        let council = DisputeCouncil::try_deserialize(&mut &imported.data[..]).unwrap();
+        assert_eq!((council.validator, council.bump), (fresh_validator, imported.bump));
+    }
+    
+    #[test]
+    fn only_live_current_accounts_at_their_pda_export() {
+        let fixtures = export_fixtures();
+        let (_, validator) = &fixtures[ExportKind::Validator as usize];
+        let address = state_export::place(ExportKind::Validator, validator, &crate::ID).unwrap().address;
+        let rejected =
+            |kind, address: &Pubkey, data: &[u8]| state_export::check_exportable(kind, address, data).unwrap_err();
+        
+        // Another kind's data, or the right data at another address
+        assert_eq!(rejected(ExportKind::CheckConfig, &address, validator), error!(TrinityError::InvalidExportAccount));
+        let elsewhere = Pubkey::new_from_array([0x47; 32]);
+        assert_eq!(rejected(ExportKind::Validator, &elsewhere, validator), error!(TrinityError::InvalidExportAccount));
+        
+        // Layouts older than this build's must be migrated first
+        let stale = account_data(&validator_fixture(), 8 + TrinityValidator::INIT_SPACE);
+        assert_eq!(rejected(ExportKind::Validator, &address, &stale), error!(TrinityError::AccountNeedsMigration));
+        
+        // Settled and expired proofs stay behind
+        let (_, live) = &fixtures[ExportKind::ProofRecord as usize];
+        let address = state_export::place(ExportKind::ProofRecord, live, &crate::ID).unwrap().address;
+        let mut record = ProofRecord::try_deserialize(&mut &live[..]).unwrap();
+        record.submitted_to_ethereum = true;
+        record.ethereum_tx_hash = [0x07; 32];
+        let confirmed = account_data(&record, 0);
+        assert_eq!(rejected(ExportKind::ProofRecord, &address, &confirmed), error!(TrinityError::InvalidExportAccount));
+        record.submitted_to_ethereum = false;
+        record.expired = true;
+        let expired = account_data(&record, 0);
+        assert_eq!(rejected(ExportKind::ProofRecord, &address, &expired), error!(TrinityError::InvalidExportAccount));
+    }
+    
+    #[test]
+    fn export_pages_continue_from_the_cursor_in_address_order() {
+        let key = |byte| Pubkey::new_from_array([byte; 32]);
+        let start = Pubkey::default();
+        assert_eq!(state_export::check_page(&start, &start, &[key(1), key(2), key(9)]).unwrap(), key(9));
+        assert_eq!(state_export::check_page(&key(9), &key(9), &[key(10)]).unwrap(), key(10));
+        
+        let mismatch = error!(TrinityError::ExportCursorMismatch);
+        let rejected = |ledger: &Pubkey, cursor: &Pubkey, page: &[Pubkey]| {
+            state_export::check_page(ledger, cursor, page).unwrap_err()
+        };
+        // Stale cursor, repeated or out-of-order accounts, empty or oversized pages
+        assert_eq!(rejected(&key(9), &start, &[key(10)]), mismatch);
+        assert_eq!(rejected(&key(9), &key(9), &[key(9)]), mismatch);
+        assert_eq!(rejected(&start, &start, &[key(2), key(1)]), mismatch);
+        assert_eq!(rejected(&start, &start, &[]), mismatch);
+        let oversized: Vec<Pubkey> = (1..=MAX_EXPORT_PAGE as u8 + 1).map(key).collect();
+        assert_eq!(rejected(&start, &start, &oversized), mismatch);
+    }
+    
+    fn unattested_consensus() -> ConsensusState {
+        ConsensusState {
+            operation_id: [0x09; 32],
+            finalized_at: 0,
+            overridden: false,
+            justification_hash: [0; 32],
+            merkle_root: [0; 32],
+            chains: vec![],
+            finalized: false,
+            failed_at: 0,
+            bump: 253,
+            layout_version: ConsensusState::LAYOUT_VERSION,
+            operation_paused: false,
+            contributors: vec![],
+            stake_weight: 0,
+        }
+    }
+    
+    #[test]
+    fn many_tiny_stakes_cannot_finalize_where_a_few_large_ones_can() {
+        const THRESHOLD: u64 = 1_000;
+        let operator = |i: u8| Pubkey::new_from_array([i + 1; 32]);
+        
+        // Sybils: every slot filled with negligible stake
+        let mut sybils = unattested_consensus();
+        for i in 0..MAX_CONSENSUS_CONTRIBUTORS as u8 {
+            assert_eq!(record_contribution(&mut sybils, operator(i), 10).unwrap(), None);
+        }
+        assert_eq!(sybils.stake_weight, 100);
+        assert_eq!(
+            record_contribution(&mut sybils, operator(0xee), 10).unwrap_err(),
+            error!(TrinityError::ContributorLimitReached)
+        );
+        assert_eq!(require_stake_weight(&sybils, THRESHOLD).unwrap_err(), error!(TrinityError::StakeWeightNotReached));
+        
+        // ...but can't keep real stake out: each large stake displaces the first smallest
+        let displaced = record_contribution(&mut sybils, operator(0xa0), 600).unwrap();
+        assert_eq!(displaced, Some(Contribution { operator: operator(0), stake: 10 }));
+        let displaced = record_contribution(&mut sybils, operator(0xa1), 600).unwrap();
+        assert_eq!(displaced, Some(Contribution { operator: operator(1), stake: 10 }));
+        assert_eq!((sybils.contributors.len(), sybils.stake_weight), (MAX_CONSENSUS_CONTRIBUTORS, 1_280));
+        assert_eq!(sybils.contributors[0], Contribution { operator: operator(0xa0), stake: 600 });
+        assert!(require_stake_weight(&sybils, THRESHOLD).is_ok());
+        assert!(invariants::check_consensus(&Pubkey::default(), &sybils).is_ok());
+        
+        // Two well-staked operators; reaching the threshold isn't exceeding it
+        let mut staked = unattested_consensus();
+        record_contribution(&mut staked, operator(0), 600).unwrap();
+        record_contribution(&mut staked, operator(1), 400).unwrap();
+        assert_eq!(require_stake_weight(&staked, THRESHOLD).unwrap_err(), error!(TrinityError::StakeWeightNotReached));
+        record_contribution(&mut staked, operator(2), 1).unwrap();
+        assert!(require_stake_weight(&staked, THRESHOLD).is_ok());
+        assert_eq!(staked.contributors[0], Contribution { operator: operator(0), stake: 600 });
+        assert!(invariants::check_consensus(&Pubkey::default(), &staked).is_ok());
+        
+        // Without a threshold the chain quorum alone decides
+        assert!(require_stake_weight(&unattested_consensus(), 0).is_ok());
+    }
+    
+    #[test]
+    fn operators_attest_once_with_bonded_stake() {
+        let mut consensus = unattested_consensus();
+        let operator = Pubkey::new_from_array([0x46; 32]);
+        assert_eq!(
+            record_contribution(&mut consensus, operator, 0).unwrap_err(),
+            error!(TrinityError::InsufficientStake)
+        );
+        record_contribution(&mut consensus, operator, 700).unwrap();
+        assert_eq!(
+            record_contribution(&mut consensus, operator, 700).unwrap_err(),
+            error!(TrinityError::AlreadyAttested)
+        );
+        assert_eq!((consensus.contributors.len(), consensus.stake_weight), (1, 700));
+    }
 }
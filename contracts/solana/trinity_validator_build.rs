@@ -0,0 +1,23 @@
+//! Build script for the Trinity validator program (`build = ` in its
+//! manifest): embeds the source commit as `GIT_COMMIT`, which
+//! get_program_info reports and attest_build records. A GIT_COMMIT already
+//! in the environment wins, for verified builds made without a .git
+//! directory; without either the program reports "unknown".
+
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT");
+    println!("cargo:rerun-if-changed=../../.git/HEAD");
+
+    let commit = std::env::var("GIT_COMMIT").ok().or_else(|| {
+        let output = Command::new("git").args(["rev-parse", "HEAD"]).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    });
+    if let Some(commit) = commit {
+        println!("cargo:rustc-env=GIT_COMMIT={}", commit);
+    }
+}
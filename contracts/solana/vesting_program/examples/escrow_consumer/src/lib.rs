@@ -0,0 +1,222 @@
+//! Example: composing with the CVT vesting program over CPI.
+//!
+//! A depositor locks tokens in a vesting schedule whose beneficiary is this
+//! program's escrow PDA, then releases them back once unlocked. `lock`
+//! creates and funds the schedule; `release` reads the claimable amount from
+//! `project_claimable`'s return data and claims it through
+//! `withdraw_sponsored`, the path built for PDA beneficiaries: the escrow
+//! signs with its seeds and the depositor pays any rent.
+//!
+//! Build against `cvt-vesting` with its `cpi` feature, which drops the
+//! vesting entrypoint and exposes `cvt_vesting::cpi`.
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use cvt_vesting::cpi::accounts::{CreateVesting, ViewVesting, WithdrawSponsored};
+use cvt_vesting::program::CvtVesting;
+
+declare_id!("EscrowConsumer11111111111111111111111111111");
+
+/// Escrow PDA seeds: `[ESCROW_SEED, depositor]`
+pub const ESCROW_SEED: &[u8] = b"escrow";
+
+#[program]
+pub mod escrow_consumer {
+    use super::*;
+
+    /// Lock `amount` of the depositor's tokens until `unlock_timestamp`. The
+    /// depositor pays for and becomes the authority of the schedule; the
+    /// escrow PDA is its beneficiary.
+    pub fn lock(ctx: Context<Lock>, schedule_id: u64, unlock_timestamp: i64, amount: u64) -> Result<()> {
+        let accounts = &ctx.accounts;
+        cvt_vesting::cpi::create_vesting(
+            CpiContext::new(
+                accounts.vesting_program.to_account_info(),
+                CreateVesting {
+                    vesting: accounts.vesting.to_account_info(),
+                    mint: accounts.mint.to_account_info(),
+                    beneficiary: accounts.escrow.to_account_info(),
+                    payer: accounts.depositor.to_account_info(),
+                    system_program: accounts.system_program.to_account_info(),
+                    config: accounts.vesting_config.to_account_info(),
+                    schedule_count: accounts.schedule_count.to_account_info(),
+                    pending: accounts.pending_schedule.to_account_info(),
+                },
+            ),
+            schedule_id,
+            0,
+            unlock_timestamp,
+            unlock_timestamp,
+            amount,
+            0,
+            [0; 32],
+            [0; 32],
+            0,
+            Pubkey::default(),
+            vec![],
+            None,
+            false,
+        )?;
+
+        token::transfer(
+            CpiContext::new(
+                accounts.token_program.to_account_info(),
+                Transfer {
+                    from: accounts.depositor_tokens.to_account_info(),
+                    to: accounts.vesting_ata.to_account_info(),
+                    authority: accounts.depositor.to_account_info(),
+                },
+            ),
+            amount,
+        )
+    }
+
+    /// Claim everything claimable now into the escrow's token account and
+    /// forward it to the depositor
+    pub fn release(ctx: Context<Release>) -> Result<()> {
+        let accounts = &ctx.accounts;
+        let now = Clock::get()?.unix_timestamp;
+        let claimable = cvt_vesting::cpi::project_claimable(
+            CpiContext::new(
+                accounts.vesting_program.to_account_info(),
+                ViewVesting { vesting: accounts.vesting.to_account_info() },
+            ),
+            vec![now],
+        )?
+        .get();
+        let amount = claimable[0];
+        require!(amount > 0, EscrowError::NothingClaimable);
+
+        let depositor = accounts.depositor.key();
+        let signer_seeds: &[&[u8]] = &[ESCROW_SEED, depositor.as_ref(), &[ctx.bumps.escrow]];
+        cvt_vesting::cpi::withdraw_sponsored(
+            CpiContext::new_with_signer(
+                accounts.vesting_program.to_account_info(),
+                WithdrawSponsored {
+                    vesting: accounts.vesting.to_account_info(),
+                    mint: accounts.mint.to_account_info(),
+                    vesting_ata: accounts.vesting_ata.to_account_info(),
+                    beneficiary_ata: accounts.escrow_tokens.to_account_info(),
+                    beneficiary: accounts.escrow.to_account_info(),
+                    payer: accounts.depositor.to_account_info(),
+                    token_program: accounts.token_program.to_account_info(),
+                    associated_token_program: accounts.associated_token_program.to_account_info(),
+                    system_program: accounts.system_program.to_account_info(),
+                    config: accounts.vesting_config.to_account_info(),
+                    kyc_attestation: None,
+                    price_oracle: None,
+                },
+                &[signer_seeds],
+            ),
+            amount,
+        )?;
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                Transfer {
+                    from: accounts.escrow_tokens.to_account_info(),
+                    to: accounts.depositor_tokens.to_account_info(),
+                    authority: accounts.escrow.to_account_info(),
+                },
+                &[signer_seeds],
+            ),
+            amount,
+        )?;
+
+        emit!(EscrowReleased { depositor, vesting: accounts.vesting.key(), amount });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Lock<'info> {
+    /// CHECK: Schedule PDA, created by `create_vesting`, which checks its seeds
+    #[account(mut)]
+    pub vesting: UncheckedAccount<'info>,
+
+    /// Schedule escrow, owned by the schedule PDA before the schedule exists
+    #[account(
+        init,
+        payer = depositor,
+        associated_token::mint = mint,
+        associated_token::authority = vesting
+    )]
+    pub vesting_ata: Account<'info, TokenAccount>,
+
+    /// CHECK: Escrow PDA, the schedule's beneficiary; holds no data and only signs
+    #[account(seeds = [ESCROW_SEED, depositor.key().as_ref()], bump)]
+    pub escrow: UncheckedAccount<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut, token::mint = mint, token::authority = depositor)]
+    pub depositor_tokens: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    /// CHECK: Vesting config PDA, checked by the vesting program
+    pub vesting_config: UncheckedAccount<'info>,
+
+    /// CHECK: The depositor's schedule counter, created if needed and checked by the vesting program
+    #[account(mut)]
+    pub schedule_count: UncheckedAccount<'info>,
+
+    /// CHECK: Pending schedule PDA at the same seeds, checked by the vesting program
+    pub pending_schedule: UncheckedAccount<'info>,
+
+    pub vesting_program: Program<'info, CvtVesting>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Release<'info> {
+    /// CHECK: Schedule PDA; the vesting program checks its seeds and that the escrow is its beneficiary
+    #[account(mut)]
+    pub vesting: UncheckedAccount<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: Schedule escrow, checked by the vesting program
+    #[account(mut)]
+    pub vesting_ata: UncheckedAccount<'info>,
+
+    /// CHECK: Escrow PDA, signs the claim as the schedule's beneficiary
+    #[account(seeds = [ESCROW_SEED, depositor.key().as_ref()], bump)]
+    pub escrow: UncheckedAccount<'info>,
+
+    /// CHECK: The escrow's ATA, created on the first claim at the depositor's expense
+    #[account(mut)]
+    pub escrow_tokens: UncheckedAccount<'info>,
+
+    #[account(mut, token::mint = mint, token::authority = depositor)]
+    pub depositor_tokens: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    /// CHECK: Vesting config PDA, checked by the vesting program
+    pub vesting_config: UncheckedAccount<'info>,
+
+    pub vesting_program: Program<'info, CvtVesting>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct EscrowReleased {
+    pub depositor: Pubkey,
+    pub vesting: Pubkey,
+    pub amount: u64,
+}
+
+#[error_code]
+pub enum EscrowError {
+    #[msg("Nothing is claimable from the schedule yet")]
+    NothingClaimable,
+}
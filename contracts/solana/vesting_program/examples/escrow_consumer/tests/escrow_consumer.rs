@@ -0,0 +1,129 @@
+//! The escrow consumer locks tokens in a schedule for its own PDA and
+//! releases them over CPI; this keeps the example compiling and working
+//! against the current vesting program.
+
+#[path = "../../../tests/common/mod.rs"]
+mod common;
+
+use anchor_lang::prelude::AccountInfo;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token::spl_token;
+use common::*;
+use cvt_vesting::Vesting;
+use escrow_consumer::{EscrowError, ESCROW_SEED};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+fn process_vesting(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    // Anchor's entrypoint ties the account slice to the AccountInfo lifetime
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    cvt_vesting::entry(program_id, accounts, data)
+}
+
+fn process_consumer(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    escrow_consumer::entry(program_id, accounts, data)
+}
+
+async fn start_both() -> ProgramTestContext {
+    let mut program_test = ProgramTest::new("cvt_vesting", cvt_vesting::ID, processor!(process_vesting));
+    program_test.add_program("escrow_consumer", escrow_consumer::ID, processor!(process_consumer));
+    program_test.start_with_context().await
+}
+
+fn escrow_pda(depositor: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[ESCROW_SEED, depositor.as_ref()], &escrow_consumer::ID).0
+}
+
+fn lock_ix(depositor: Pubkey, mint: Pubkey, schedule_id: u64, unlock_timestamp: i64, amount: u64) -> Instruction {
+    let escrow = escrow_pda(&depositor);
+    let vesting = vesting_pda(&escrow, &mint, schedule_id, 0);
+    Instruction {
+        program_id: escrow_consumer::ID,
+        accounts: escrow_consumer::accounts::Lock {
+            vesting,
+            vesting_ata: get_associated_token_address(&vesting, &mint),
+            escrow,
+            mint,
+            depositor_tokens: get_associated_token_address(&depositor, &mint),
+            depositor,
+            vesting_config: config_pda(),
+            schedule_count: schedule_count_pda(&depositor),
+            pending_schedule: pending_schedule_pda(&escrow, &mint, schedule_id, 0),
+            vesting_program: cvt_vesting::ID,
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        }
+        .to_account_metas(None),
+        data: escrow_consumer::instruction::Lock { schedule_id, unlock_timestamp, amount }.data(),
+    }
+}
+
+fn release_ix(depositor: Pubkey, mint: Pubkey, schedule_id: u64) -> Instruction {
+    let escrow = escrow_pda(&depositor);
+    let vesting = vesting_pda(&escrow, &mint, schedule_id, 0);
+    Instruction {
+        program_id: escrow_consumer::ID,
+        accounts: escrow_consumer::accounts::Release {
+            vesting,
+            mint,
+            vesting_ata: get_associated_token_address(&vesting, &mint),
+            escrow,
+            escrow_tokens: get_associated_token_address(&escrow, &mint),
+            depositor_tokens: get_associated_token_address(&depositor, &mint),
+            depositor,
+            vesting_config: config_pda(),
+            vesting_program: cvt_vesting::ID,
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        }
+        .to_account_metas(None),
+        data: escrow_consumer::instruction::Release {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn locks_for_its_pda_and_releases_over_cpi() {
+    let mut ctx = start_both().await;
+    let depositor = Keypair::new();
+    fund(&mut ctx, &depositor.pubkey(), 1_000_000_000).await;
+    let mint = create_mint(&mut ctx).await;
+    let depositor_tokens = create_ata(&mut ctx, &depositor.pubkey(), &mint).await;
+    mint_to(&mut ctx, &mint, &depositor_tokens, 1_000).await;
+
+    let unlock = now(&mut ctx).await + DAY;
+    send(&mut ctx, &[lock_ix(depositor.pubkey(), mint, 7, unlock, 1_000)], &[&depositor]).await.unwrap();
+    let escrow = escrow_pda(&depositor.pubkey());
+    let vesting = vesting_pda(&escrow, &mint, 7, 0);
+    let schedule: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!((schedule.beneficiary, schedule.authority), (escrow, depositor.pubkey()));
+    assert_eq!(token_balance(&mut ctx, &get_associated_token_address(&vesting, &mint)).await, 1_000);
+    assert_eq!(token_balance(&mut ctx, &depositor_tokens).await, 0);
+
+    // Still locked: the projected claimable amount is zero
+    let result = send(&mut ctx, &[release_ix(depositor.pubkey(), mint, 7)], &[&depositor]).await;
+    let expected = u32::from(EscrowError::NothingClaimable);
+    assert!(
+        matches!(result, Err(TransactionError::InstructionError(0, InstructionError::Custom(code))) if code == expected),
+        "{:?}",
+        result
+    );
+
+    // Unlocked: the escrow PDA claims as beneficiary and forwards to the depositor
+    warp_to(&mut ctx, unlock).await;
+    let claimable: Vec<u64> = view(&mut ctx, project_claimable_ix(vesting, vec![unlock])).await;
+    assert_eq!(claimable, vec![1_000]);
+    send(&mut ctx, &[release_ix(depositor.pubkey(), mint, 7)], &[&depositor]).await.unwrap();
+    assert_eq!(token_balance(&mut ctx, &depositor_tokens).await, 1_000);
+    assert_eq!(token_balance(&mut ctx, &get_associated_token_address(&escrow, &mint)).await, 0);
+    assert_eq!(fetch::<Vesting>(&mut ctx, vesting).await.withdrawn, 1_000);
+}
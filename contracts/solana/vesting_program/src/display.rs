@@ -0,0 +1,120 @@
+//! Decimals-aware amount formatting and parsing for off-chain clients (CLI, SDK).
+//!
+//! Host-only: compiled with the `host` feature and never for the on-chain program.
+//! Amounts are converted exactly; nothing is ever rounded. Input that can't be
+//! represented at the mint's decimals is rejected instead.
+
+use std::fmt;
+
+/// Why a human-readable amount couldn't be parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmountParseError {
+    /// Empty input
+    Empty,
+    /// Anything other than ASCII digits, a single `.` and `_` digit separators
+    InvalidFormat,
+    /// More fractional digits than the mint supports
+    TooManyDecimals,
+    /// Value doesn't fit in a u64 of base units
+    Overflow,
+}
+
+impl fmt::Display for AmountParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AmountParseError::Empty => write!(f, "empty amount"),
+            AmountParseError::InvalidFormat => write!(f, "invalid amount format"),
+            AmountParseError::TooManyDecimals => write!(f, "amount has more decimals than the mint"),
+            AmountParseError::Overflow => write!(f, "amount overflows u64 base units"),
+        }
+    }
+}
+
+impl std::error::Error for AmountParseError {}
+
+/// Format base units as a decimal string, e.g. `format_amount(1_500_000_000, 9) == "1.5"`
+///
+/// Trailing fractional zeros are dropped; whole amounts have no decimal point.
+pub fn format_amount(amount: u64, decimals: u8) -> String {
+    let digits = amount.to_string();
+    let decimals = decimals as usize;
+    if decimals == 0 {
+        return digits;
+    }
+
+    let padded = format!("{:0>width$}", digits, width = decimals + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// Parse a decimal string into base units, e.g. `parse_amount("1_000.5", 9) == Ok(1_000_500_000_000)`
+///
+/// Accepts ASCII digits with an optional single `.`, and `_` between digits as a
+/// separator. Signs, whitespace, exponents and locale separators (`,`) are rejected,
+/// as is any precision beyond `decimals` (even trailing zeros).
+pub fn parse_amount(input: &str, decimals: u8) -> Result<u64, AmountParseError> {
+    if input.is_empty() {
+        return Err(AmountParseError::Empty);
+    }
+
+    let (whole, fraction) = match input.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (input, None),
+    };
+    let whole = strip_separators(whole)?;
+    let fraction = match fraction {
+        Some(fraction) => strip_separators(fraction)?,
+        None => String::new(),
+    };
+    if fraction.len() > decimals as usize {
+        return Err(AmountParseError::TooManyDecimals);
+    }
+
+    let scale = 10u128
+        .checked_pow(decimals as u32)
+        .ok_or(AmountParseError::Overflow)?;
+    let whole_units = parse_digits(&whole)?
+        .checked_mul(scale)
+        .ok_or(AmountParseError::Overflow)?;
+    let fraction_units = parse_digits(&fraction)?
+        * 10u128.pow((decimals as usize - fraction.len()) as u32);
+
+    let total = whole_units
+        .checked_add(fraction_units)
+        .ok_or(AmountParseError::Overflow)?;
+    u64::try_from(total).map_err(|_| AmountParseError::Overflow)
+}
+
+/// Validate a digit group and drop `_` separators; separators must sit between digits
+fn strip_separators(group: &str) -> Result<String, AmountParseError> {
+    let bytes = group.as_bytes();
+    if bytes.is_empty() || !bytes[0].is_ascii_digit() || !bytes[bytes.len() - 1].is_ascii_digit() {
+        return Err(AmountParseError::InvalidFormat);
+    }
+    let mut digits = String::with_capacity(bytes.len());
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            b'0'..=b'9' => digits.push(b as char),
+            b'_' if bytes[i - 1] != b'_' => {}
+            _ => return Err(AmountParseError::InvalidFormat),
+        }
+    }
+    Ok(digits)
+}
+
+fn parse_digits(digits: &str) -> Result<u128, AmountParseError> {
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    digits
+        .bytes()
+        .try_fold(0u128, |acc, b| {
+            acc.checked_mul(10)?.checked_add((b - b'0') as u128)
+        })
+        .ok_or(AmountParseError::Overflow)
+}
@@ -6,35 +6,203 @@
 
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer, Mint};
-use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Burn, CloseAccount, Token, TokenAccount, Transfer, Mint};
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::associated_token::{get_associated_token_address, AssociatedToken};
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::incinerator;
+use anchor_lang::solana_program::keccak::hashv;
+use anchor_lang::solana_program::program::set_return_data;
 
 declare_id!("CVTvest11111111111111111111111111111111111");
 
+/// Structured `error_ctx` log lines emitted before each failure (shared with Trinity)
+#[macro_use]
+#[path = "../../common/error_ctx.rs"]
+pub mod error_ctx;
+
+/// Sorted-pair keccak Merkle root (shared with Trinity), for allowlists
+#[path = "../../common/merkle.rs"]
+pub mod merkle;
+
+/// Transaction-size ceilings the batch caps below are checked against
+pub mod limits;
+
+/// Client-side amount display helpers (never built for the on-chain program)
+#[cfg(all(feature = "host", not(target_os = "solana")))]
+pub mod display;
+
+/// Maximum guardians in the recovery quorum
+pub const MAX_GUARDIANS: usize = 10;
+/// Minimum recovery timelock: 30 days gives a live beneficiary time to object
+pub const MIN_RECOVERY_TIMELOCK: i64 = 30 * 24 * 60 * 60;
+/// Maximum co-signer accounts on a recovery proposal. Each one is scanned
+/// against the guardian set, so the list is bounded up front rather than
+/// letting an oversized batch run out of compute mid-instruction. A full
+/// quorum only fits in one transaction if the proposer pays the fee.
+#[constant]
+pub const MAX_RECOVERY_CO_SIGNERS: usize = 9;
+/// Maximum schedules summed by one `aggregate_beneficiary` call
+#[constant]
+pub const MAX_AGGREGATE_SCHEDULES: usize = 16;
+/// Maximum timestamps projected by one `project_claimable` call
+#[constant]
+pub const MAX_PROJECTION_POINTS: usize = 24;
+/// Maximum schedules read by one `claimable_batch` call
+#[constant]
+pub const MAX_CLAIMABLE_BATCH: usize = 16;
+/// Maximum allowlist proof depth (2^19 wallets)
+#[constant]
+pub const MAX_ALLOWLIST_PROOF_LEN: usize = 19;
+
+// Every batch cap fits in one transaction (see `limits`), and the recovery
+// cap lets a single proposal reach the largest possible guardian threshold
+const _: () = assert!(MAX_RECOVERY_CO_SIGNERS == MAX_GUARDIANS - 1);
+const _: () = assert!(MAX_RECOVERY_CO_SIGNERS <= limits::RECOVERY_CO_SIGNERS_CEILING);
+const _: () = assert!(MAX_AGGREGATE_SCHEDULES <= limits::AGGREGATE_SCHEDULES_CEILING);
+const _: () = assert!(MAX_PROJECTION_POINTS <= limits::PROJECTION_POINTS_CEILING);
+const _: () = assert!(MAX_CLAIMABLE_BATCH <= limits::CLAIMABLE_BATCH_CEILING);
+const _: () = assert!(MAX_ALLOWLIST_PROOF_LEN <= limits::ALLOWLIST_PROOF_CEILING);
+const _: () = assert!(MAX_ALLOWLIST_PROOF_LEN <= limits::ACTIVATION_PROOF_CEILING);
+/// USD amounts are micro-dollars (6 decimals)
+pub const USD_DECIMALS: i32 = 6;
+/// Spacing between the tranches of a USD-denominated schedule (30 days)
+pub const USD_TRANCHE_INTERVAL: i64 = 30 * 24 * 60 * 60;
+/// Escrow balance `close_vesting` sweeps by default (raw units)
+pub const DEFAULT_DUST_THRESHOLD: u64 = 10;
+/// Largest dust threshold the config may set: enough for rounding leftovers,
+/// too little to close out a real balance
+pub const MAX_DUST_THRESHOLD: u64 = 1_000;
+/// Maximum coverage programs on the config allowlist
+pub const MAX_COVERAGE_PROGRAMS: usize = 4;
+/// Maximum claim destination programs on the config allowlist
+pub const MAX_DESTINATION_PROGRAMS: usize = 4;
+/// How long a full pause lasts unless the config sets otherwise (72 hours)
+pub const DEFAULT_MAX_PAUSE_DURATION: i64 = 72 * 60 * 60;
+/// Longest pause duration the config may set (7 days)
+pub const MAX_PAUSE_DURATION: i64 = 7 * 24 * 60 * 60;
+/// Rolling window re-pauses are counted over (30 days)
+pub const REPAUSE_WINDOW: i64 = 30 * 24 * 60 * 60;
+/// Re-pauses allowed across all schedules within any REPAUSE_WINDOW
+pub const MAX_REPAUSES_PER_WINDOW: usize = 3;
+/// How long a tripped circuit breaker blocks claims unless the config sets otherwise (24 hours)
+pub const DEFAULT_CIRCUIT_BREAKER_DURATION: i64 = 24 * 60 * 60;
+/// Longest circuit breaker duration the config may set (7 days)
+pub const MAX_CIRCUIT_BREAKER_DURATION: i64 = 7 * 24 * 60 * 60;
+/// Live schedules one authority may have unless the config sets otherwise
+pub const DEFAULT_MAX_SCHEDULES_PER_AUTHORITY: u32 = 1_000;
+/// Leading byte of `serialize_state` blobs; bump on any change to the Borsh
+/// layout of `Vesting`
+pub const STATE_BLOB_VERSION: u8 = 4;
+/// ConsensusMirror layout `deposit_from_bridge` understands (Trinity's
+/// CONSENSUS_MIRROR_SCHEMA_VERSION); mirrors of any other version are refused
+pub const CONSENSUS_MIRROR_SCHEMA_VERSION: u8 = 1;
+/// Chains that must have attested a bridge operation (Trinity's 2-of-3)
+pub const BRIDGE_CONSENSUS_THRESHOLD: u32 = 2;
+
+// ConsensusMirror byte offsets, after the 8-byte Anchor discriminator (see
+// Trinity's ConsensusMirror ABI comment)
+const MIRROR_SCHEMA_VERSION_OFFSET: usize = 8;
+const MIRROR_OPERATION_ID_OFFSET: usize = 9;
+const MIRROR_CHAIN_BITMAP_OFFSET: usize = 81;
+const MIRROR_LEN: usize = 82;
+
 #[program]
 pub mod cvt_vesting {
     use super::*;
 
     /// Create vesting schedule with unique identifier
+    /// `nonce` is a client-chosen random value mixed into the PDA seeds so the
+    /// schedule (and its ATA) address can't be predicted and squatted ahead of creation
+    /// `allowlist_proof` is only checked when the config commits an allowlist root
+    /// With `usd_terms` the schedule pays USD tranches converted at claim time,
+    /// and `amount` is the most tokens it can ever pay out
+    /// With `hashed_beneficiary` the `beneficiary` passed is
+    /// `beneficiary_hash(key, salt)`, so the key itself never appears on-chain
+    /// `terms_hash` commits the off-chain grant agreement (zero for none);
+    /// nothing can change it afterwards
+    /// With an `expiry_sink` (default pubkey for none), whatever is left
+    /// unclaimed at `claim_deadline` goes there via `expire_and_return`;
+    /// the incinerator as sink burns it
+    #[allow(clippy::too_many_arguments)]
     pub fn create_vesting(
         ctx: Context<CreateVesting>,
         schedule_id: u64,
+        nonce: u64,
         unlock_timestamp: i64,
+        max_unlock_timestamp: i64,
         amount: u64,
+        category: u8,
+        label_hash: [u8; 32],
+        terms_hash: [u8; 32],
+        claim_deadline: i64,
+        expiry_sink: Pubkey,
+        allowlist_proof: Vec<[u8; 32]>,
+        usd_terms: Option<UsdTerms>,
+        hashed_beneficiary: bool,
     ) -> Result<()> {
-        let vesting = &mut ctx.accounts.vesting;
+        let config = load_config(&ctx.accounts.config)?;
+        if let Some(config) = &config {
+            require_allowlisted(config, &ctx.accounts.beneficiary.key(), &allowlist_proof)?;
+        }
+
+        count_new_schedule(
+            config.as_ref(),
+            &mut ctx.accounts.schedule_count,
+            ctx.accounts.payer.key(),
+            ctx.bumps.schedule_count,
+        )?;
+
+        let terms = ScheduleTerms {
+            schedule_id,
+            nonce,
+            unlock_timestamp,
+            max_unlock_timestamp,
+            amount,
+            category,
+            label_hash,
+            terms_hash,
+            claim_deadline,
+            expiry_sink,
+        };
         let clock = Clock::get()?;
+        check_schedule_terms(config.as_ref(), &terms, clock.unix_timestamp)?;
+        if let Some(terms) = &usd_terms {
+            require_ctx!(
+                terms.usd_per_tranche > 0
+                    && terms.tranches > 0
+                    && terms.usd_per_tranche.checked_mul(terms.tranches as u64).is_some(),
+                VestingError::InvalidAmount,
+                usd_per_tranche = terms.usd_per_tranche,
+                tranches = terms.tranches,
+            );
+        }
 
-        require!(unlock_timestamp > clock.unix_timestamp, VestingError::InvalidUnlockTime);
-        require!(amount > 0, VestingError::InvalidAmount);
+        let vesting = &mut ctx.accounts.vesting;
+        init_vesting(
+            vesting,
+            &terms,
+            ctx.accounts.beneficiary.key(),
+            ctx.accounts.payer.key(),
+            &ctx.accounts.mint,
+            clock.unix_timestamp,
+            ctx.bumps.vesting,
+        );
+        vesting.beneficiary_hashed = hashed_beneficiary;
+        vesting.usd_terms = usd_terms;
 
-        vesting.beneficiary = ctx.accounts.beneficiary.key();
-        vesting.mint = ctx.accounts.mint.key();
-        vesting.schedule_id = schedule_id;
-        vesting.unlock_timestamp = unlock_timestamp;
-        vesting.total_amount = amount;
-        vesting.withdrawn = 0;
-        vesting.bump = ctx.bumps.vesting;
+        if verbose_events(config.as_ref()) {
+            emit!(VestingCreated {
+                category,
+                label_hash,
+                vesting: vesting.key(),
+                beneficiary: vesting.beneficiary,
+                mint: vesting.mint,
+                amount,
+                unlock_timestamp,
+                terms_hash,
+            });
+        }
 
         msg!("✅ Vesting schedule {} created", schedule_id);
         msg!("   Amount: {}", amount);
@@ -44,137 +212,4757 @@ pub mod cvt_vesting {
     }
 
     /// Withdraw tokens ONLY after time-lock expires
+    /// With a KYC gate configured, `kyc_attestation` must attest the beneficiary.
+    /// USD schedules pay at most `amount`, converted at the `price_oracle` price.
+    /// Tokens go to the beneficiary's ATA (created if missing) unless an
+    /// explicit `destination` is given: the same ATA, a token account owned
+    /// by a PDA of an allowlisted destination program (e.g. a staking vault),
+    /// or, for a program-owned beneficiary such as a DAO treasury, a token
+    /// account its program binds to it with an `OwnershipAttestation`.
     pub fn withdraw(
         ctx: Context<Withdraw>,
         amount: u64,
     ) -> Result<()> {
+        require_ctx!(
+            !ctx.accounts.vesting.beneficiary_hashed,
+            VestingError::BeneficiarySaltRequired,
+            vesting = ctx.accounts.vesting.key(),
+        );
+        let claimant = ctx.accounts.beneficiary.key();
+        withdraw_tokens(ctx, claimant, amount)
+    }
+
+    /// `withdraw` for a hashed-beneficiary schedule: the signer proves it is
+    /// the beneficiary by presenting the salt the schedule was created with.
+    /// A lost salt cannot be recovered on-chain; only guardian recovery to a
+    /// plain key releases the funds.
+    pub fn withdraw_hashed(
+        ctx: Context<Withdraw>,
+        amount: u64,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        let vesting = &ctx.accounts.vesting;
+        require_ctx!(vesting.beneficiary_hashed, VestingError::NotHashedBeneficiary, vesting = vesting.key());
+        require_ctx!(
+            beneficiary_hash(&ctx.accounts.beneficiary.key(), &salt) == vesting.beneficiary,
+            VestingError::InvalidBeneficiarySalt,
+            vesting = vesting.key(),
+            signer = ctx.accounts.beneficiary.key(),
+        );
+        let claimant = vesting.beneficiary;
+        withdraw_tokens(ctx, claimant, amount)
+    }
+
+    /// `withdraw` with the ATA rent fronted by a separate `payer`, for
+    /// beneficiaries that can sign but can't pay: typically a program PDA
+    /// (e.g. a DAO treasury) signing through `invoke_signed`. Tokens always go
+    /// to the beneficiary's ATA, recreated at the payer's expense if it was
+    /// closed between claims; the schedule's claim history is untouched.
+    pub fn withdraw_sponsored(
+        ctx: Context<WithdrawSponsored>,
+        amount: u64,
+    ) -> Result<()> {
+        let config = load_config(&ctx.accounts.config)?;
+        let vesting = &mut ctx.accounts.vesting;
+        require_ctx!(!vesting.beneficiary_hashed, VestingError::BeneficiarySaltRequired, vesting = vesting.key());
+        let (amount, usd) = usd_payout(
+            vesting,
+            config.as_ref(),
+            ctx.accounts.price_oracle.as_deref(),
+            amount,
+            ctx.accounts.vesting_ata.amount,
+        )?;
+        check_withdrawal(vesting, config.as_ref(), &ctx.accounts.beneficiary.key(), amount)?;
+        require_kyc(config.as_ref(), vesting, ctx.accounts.kyc_attestation.as_deref())?;
+        require_schedule_mint(vesting, &ctx.accounts.beneficiary_ata)?;
+
+        with_vesting_signer(vesting, |signer| {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.vesting_ata.to_account_info(),
+                        to: ctx.accounts.beneficiary_ata.to_account_info(),
+                        authority: vesting.to_account_info(),
+                    },
+                    signer
+                ),
+                amount
+            )
+        })?;
+
+        record_withdrawal(vesting, amount, usd, config.as_ref())?;
+
+        msg!("✅ Withdrawn {} tokens (ATA rent sponsored by {})", amount, ctx.accounts.payer.key());
+
+        Ok(())
+    }
+
+    /// Claim on the beneficiary's behalf as their auto-claim delegate. Tokens
+    /// still go to the beneficiary's ATA, and the delegate's running total
+    /// can't pass the cap the beneficiary set.
+    pub fn auto_claim(
+        ctx: Context<AutoClaim>,
+        amount: u64,
+    ) -> Result<()> {
+        let config = load_config(&ctx.accounts.config)?;
+        let vesting = &mut ctx.accounts.vesting;
+        require_ctx!(
+            vesting.auto_claim_delegate != Pubkey::default()
+                && ctx.accounts.delegate.key() == vesting.auto_claim_delegate,
+            VestingError::AutoClaimNotAuthorized,
+            signer = ctx.accounts.delegate.key(),
+            delegate = vesting.auto_claim_delegate,
+        );
+        let (amount, usd) = usd_payout(
+            vesting,
+            config.as_ref(),
+            ctx.accounts.price_oracle.as_deref(),
+            amount,
+            ctx.accounts.vesting_ata.amount,
+        )?;
+        let auto_claimed_total = vesting.auto_claimed_total.checked_add(amount)
+            .ok_or(VestingError::Overflow)?;
+        require_ctx!(
+            auto_claimed_total <= vesting.auto_claim_max_total,
+            VestingError::AutoClaimCapExceeded,
+            requested = amount,
+            auto_claimed_total = vesting.auto_claimed_total,
+            max_total = vesting.auto_claim_max_total,
+        );
+        let beneficiary = vesting.beneficiary;
+        check_withdrawal(vesting, config.as_ref(), &beneficiary, amount)?;
+        require_kyc(config.as_ref(), vesting, ctx.accounts.kyc_attestation.as_deref())?;
+
+        with_vesting_signer(vesting, |signer| {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.vesting_ata.to_account_info(),
+                        to: ctx.accounts.beneficiary_ata.to_account_info(),
+                        authority: vesting.to_account_info(),
+                    },
+                    signer
+                ),
+                amount
+            )
+        })?;
+
+        vesting.auto_claimed_total = auto_claimed_total;
+        record_withdrawal(vesting, amount, usd, config.as_ref())?;
+
+        msg!("🤖 Auto-claimed {} tokens ({}/{})", amount, auto_claimed_total, vesting.auto_claim_max_total);
+
+        Ok(())
+    }
+
+    /// Withdraw a wSOL schedule as native SOL: the tokens move into a temporary
+    /// wSOL account that is closed straight to the beneficiary's system account
+    /// (which fronts its rent and gets it back in the same instruction)
+    pub fn withdraw_native(
+        ctx: Context<WithdrawNative>,
+        amount: u64,
+    ) -> Result<()> {
+        require_ctx!(
+            ctx.accounts.mint.key() == native_mint::ID,
+            VestingError::NotNativeMint,
+            mint = ctx.accounts.mint.key(),
+        );
+
+        let config = load_config(&ctx.accounts.config)?;
+        let vesting = &mut ctx.accounts.vesting;
+        let (amount, usd) = usd_payout(
+            vesting,
+            config.as_ref(),
+            ctx.accounts.price_oracle.as_deref(),
+            amount,
+            ctx.accounts.vesting_ata.amount,
+        )?;
+        check_withdrawal(vesting, config.as_ref(), &ctx.accounts.beneficiary.key(), amount)?;
+        require_kyc(config.as_ref(), vesting, ctx.accounts.kyc_attestation.as_deref())?;
+
+        with_vesting_signer(vesting, |signer| {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.vesting_ata.to_account_info(),
+                        to: ctx.accounts.unwrap_account.to_account_info(),
+                        authority: vesting.to_account_info(),
+                    },
+                    signer
+                ),
+                amount
+            )?;
+            token::close_account(CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: ctx.accounts.unwrap_account.to_account_info(),
+                    destination: ctx.accounts.beneficiary.to_account_info(),
+                    authority: vesting.to_account_info(),
+                },
+                signer
+            ))
+        })?;
+
+        record_withdrawal(vesting, amount, usd, config.as_ref())?;
+
+        msg!("✅ Withdrawn {} lamports as native SOL", amount);
+
+        Ok(())
+    }
+
+    /// Close an unlocked schedule and its vesting ATA, returning the rent to the
+    /// beneficiary. Rounding dust at or below the config's dust threshold goes
+    /// to the beneficiary's ATA, or is burned if that ATA no longer exists.
+    /// As with cancel_unfunded, a pending recovery, mint migration or bridge
+    /// deposit must be resolved first; the bridge marker's rent goes back to
+    /// the authority that paid it.
+    pub fn close_vesting(ctx: Context<CloseVesting>) -> Result<()> {
+        release_bridge_inflight(&ctx.accounts.bridge_inflight, &ctx.accounts.authority, ctx.accounts.vesting.key())?;
+
+        let config = load_config(&ctx.accounts.config)?;
+        let dust_threshold = config.as_ref().map_or(DEFAULT_DUST_THRESHOLD, |config| config.dust_threshold);
+        let vesting = &mut ctx.accounts.vesting;
+        let dust = ctx.accounts.vesting_ata.amount;
+
+        let now = observed_now(vesting, &Clock::get()?);
+        expire_pause(vesting, now);
+        require_ctx!(
+            !vesting.is_paused,
+            VestingError::Paused,
+            vesting = vesting.key(),
+            paused_until = vesting.paused_until,
+        );
+        require_ctx!(
+            is_unlocked(now, vesting.unlock_timestamp),
+            VestingError::StillLocked,
+            unlock_timestamp = vesting.unlock_timestamp,
+            now = now,
+        );
+        require_ctx!(
+            dust <= dust_threshold,
+            VestingError::DustAboveThreshold,
+            balance = dust,
+            dust_threshold = dust_threshold,
+        );
+
+        let beneficiary_ata = &ctx.accounts.beneficiary_ata;
+        let disposition = if dust == 0 {
+            DustDisposition::None
+        } else if beneficiary_ata.owner == &token::ID && !beneficiary_ata.data_is_empty() {
+            DustDisposition::Transferred
+        } else {
+            DustDisposition::Burned
+        };
+
+        with_vesting_signer(vesting, |signer| {
+            let token_program = ctx.accounts.token_program.to_account_info();
+            match disposition {
+                DustDisposition::None => {}
+                DustDisposition::Transferred => token::transfer(
+                    CpiContext::new_with_signer(
+                        token_program.clone(),
+                        Transfer {
+                            from: ctx.accounts.vesting_ata.to_account_info(),
+                            to: beneficiary_ata.to_account_info(),
+                            authority: vesting.to_account_info(),
+                        },
+                        signer
+                    ),
+                    dust
+                )?,
+                DustDisposition::Burned => token::burn(
+                    CpiContext::new_with_signer(
+                        token_program.clone(),
+                        Burn {
+                            mint: ctx.accounts.mint.to_account_info(),
+                            from: ctx.accounts.vesting_ata.to_account_info(),
+                            authority: vesting.to_account_info(),
+                        },
+                        signer
+                    ),
+                    dust
+                )?,
+            }
+            token::close_account(CpiContext::new_with_signer(
+                token_program,
+                CloseAccount {
+                    account: ctx.accounts.vesting_ata.to_account_info(),
+                    destination: ctx.accounts.beneficiary.to_account_info(),
+                    authority: vesting.to_account_info(),
+                },
+                signer
+            ))
+        })?;
+
+        // Closing ends any coverage, expired or not
+        if vesting.coverage.is_some() {
+            clear_coverage(vesting);
+        }
+        let schedule_count = &mut ctx.accounts.schedule_count;
+        schedule_count.schedule_count = schedule_count.schedule_count.saturating_sub(1);
+        emit!(VestingClosed {
+            category: vesting.category,
+            label_hash: vesting.label_hash,
+            vesting: vesting.key(),
+            beneficiary: vesting.beneficiary,
+            dust,
+            dust_threshold,
+            disposition,
+            withdrawn: vesting.withdrawn,
+            lock_seconds_accumulated: vesting.lock_seconds_accumulated,
+        });
+
+        msg!("🧹 Closed schedule {} (dust {}: {:?})", vesting.schedule_id, dust, disposition);
+
+        Ok(())
+    }
+
+    /// Deposit tokens into a schedule's vesting ATA
+    pub fn deposit_tokens(
+        ctx: Context<DepositTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        let config = load_config(&ctx.accounts.config)?;
+        let vesting = &mut ctx.accounts.vesting;
+        let now = observed_now(vesting, &Clock::get()?);
+
+        require_ctx!(
+            !pause_in_force(vesting, now),
+            VestingError::Paused,
+            vesting = vesting.key(),
+            paused_until = vesting.paused_until,
+        );
+        require_ctx!(!vesting.deposits_paused, VestingError::DepositsPaused, vesting = vesting.key());
+        require_ctx!(amount > 0, VestingError::InvalidAmount, amount = amount);
+        require_schedule_mint(vesting, &ctx.accounts.depositor_ata)?;
+        // It would count as the open bridge deposit's arrival
+        require_ctx!(
+            !bridge_deposit_open(&ctx.accounts.bridge_inflight)?,
+            VestingError::BridgeDepositInFlight,
+            vesting = vesting.key(),
+        );
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.depositor_ata.to_account_info(),
+                    to: ctx.accounts.vesting_ata.to_account_info(),
+                    authority: ctx.accounts.depositor.to_account_info(),
+                },
+            ),
+            amount
+        )?;
+        // vesting_ata still holds its pre-transfer balance (not reloaded)
+        vesting.deposit_weighted_start =
+            weighted_lock_start(vesting.deposit_weighted_start, ctx.accounts.vesting_ata.amount, amount, now);
+
+        if verbose_events(config.as_ref()) {
+            emit!(TokensDeposited {
+                category: vesting.category,
+                label_hash: vesting.label_hash,
+                vesting: vesting.key(),
+                depositor: ctx.accounts.depositor.key(),
+                amount,
+            });
+        }
+
+        msg!("✅ Deposited {} tokens into schedule {}", amount, vesting.schedule_id);
+
+        Ok(())
+    }
+
+    /// Pause a schedule entirely, or only its deposits (e.g. during a wind-down).
+    /// A full pause lapses after the config's `max_pause_duration`; pausing a
+    /// schedule again within REPAUSE_WINDOW of its last pause ending is a
+    /// re-pause, and at most MAX_REPAUSES_PER_WINDOW are allowed per window.
+    pub fn set_pause(
+        ctx: Context<SetPause>,
+        is_paused: bool,
+        deposits_paused: bool,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let vesting = &mut ctx.accounts.vesting;
+        let now = observed_now(vesting, &Clock::get()?);
+        expire_pause(vesting, now);
+
+        let repause = is_paused && vesting.paused_until != 0
+            && now < vesting.paused_until.saturating_add(REPAUSE_WINDOW);
+        if repause {
+            config.recent_repauses.retain(|at| now - at < REPAUSE_WINDOW);
+            require_ctx!(
+                config.recent_repauses.len() < MAX_REPAUSES_PER_WINDOW,
+                VestingError::RepauseLimitReached,
+                vesting = vesting.key(),
+                repauses = config.recent_repauses.len(),
+                max = MAX_REPAUSES_PER_WINDOW,
+            );
+            config.recent_repauses.push(now);
+        }
+        if is_paused {
+            vesting.paused_until = now.checked_add(config.max_pause_duration)
+                .ok_or(VestingError::Overflow)?;
+        } else if vesting.is_paused {
+            // Lifted early: the pause ended now, which starts the re-pause window
+            vesting.paused_until = now;
+        }
+        vesting.is_paused = is_paused;
+        vesting.deposits_paused = deposits_paused;
+        expire_coverage(vesting, now);
+
+        emit!(PauseUpdated {
+            category: vesting.category,
+            label_hash: vesting.label_hash,
+            vesting: vesting.key(),
+            is_paused,
+            deposits_paused,
+            paused_until: vesting.paused_until,
+            repause,
+            policy_id: covering_policy(vesting, now),
+        });
+
+        msg!("⏸️ Schedule {} paused: {}, deposits paused: {}", vesting.schedule_id, is_paused, deposits_paused);
+        if is_paused {
+            msg!("   Until: {}{}", vesting.paused_until, if repause { " (re-pause)" } else { "" });
+        }
+
+        Ok(())
+    }
+
+    /// Recategorize a schedule for indexer routing (config authority only)
+    pub fn update_category(ctx: Context<UpdateCategory>, category: u8) -> Result<()> {
         let vesting = &mut ctx.accounts.vesting;
+        let previous_category = vesting.category;
+        vesting.category = category;
+
+        emit!(CategoryUpdated {
+            category,
+            label_hash: vesting.label_hash,
+            vesting: vesting.key(),
+            previous_category,
+        });
+
+        msg!("🏷️ Schedule {} category: {} -> {}", vesting.schedule_id, previous_category, category);
+
+        Ok(())
+    }
+
+    /// Read-only: whether anything can be claimed right now (return data)
+    pub fn is_claimable(ctx: Context<ViewVesting>) -> Result<bool> {
+        let vesting = &ctx.accounts.vesting;
+        Ok(claimable_now(vesting, observed_now(vesting, &Clock::get()?)) > 0)
+    }
+
+    /// Read-only: whether `document_hash` is the grant agreement committed at
+    /// creation (return data). Always false for a schedule without terms.
+    pub fn verify_terms(ctx: Context<ViewVesting>, document_hash: [u8; 32]) -> Result<bool> {
+        let terms_hash = ctx.accounts.vesting.terms_hash;
+        Ok(terms_hash != [0; 32] && terms_hash == document_hash)
+    }
+
+    /// Read-only: what `claimable_amount` would give at each hypothetical
+    /// timestamp against the schedule's current state (return data), so
+    /// frontends can chart a schedule without reimplementing its math.
+    /// Points are taken as given, never from the clock; ones before the
+    /// schedule's last observed time (its creation, until first used) are
+    /// rejected. Nothing is claimable at points before a pause lapses.
+    pub fn project_claimable(ctx: Context<ViewVesting>, at_timestamps: Vec<i64>) -> Result<Vec<u64>> {
+        let vesting = &ctx.accounts.vesting;
+        require_ctx!(
+            at_timestamps.len() <= MAX_PROJECTION_POINTS,
+            VestingError::BatchTooLarge,
+            points = at_timestamps.len(),
+            max = MAX_PROJECTION_POINTS,
+        );
+        let earliest = at_timestamps.iter().copied().min().unwrap_or(i64::MAX);
+        require_ctx!(
+            earliest >= vesting.last_observed_timestamp,
+            VestingError::ProjectionBeforeStart,
+            at = earliest,
+            start = vesting.last_observed_timestamp,
+        );
+
+        Ok(at_timestamps.iter().map(|at| claimable_now(vesting, *at)).collect())
+    }
+
+    /// Read-only: when the schedule next unlocks and how much (return data),
+    /// for a "next unlock" card in one call. See `next_unlock` for each mode.
+    pub fn next_unlock_info(ctx: Context<ViewVesting>) -> Result<NextUnlock> {
+        let vesting = &ctx.accounts.vesting;
+        Ok(next_unlock(vesting, observed_now(vesting, &Clock::get()?)))
+    }
+
+    /// Read-only: totals across a beneficiary's schedules, passed as
+    /// remaining_accounts (return data). Paused schedules count as locked.
+    pub fn aggregate_beneficiary(
+        ctx: Context<AggregateBeneficiary>,
+        beneficiary: Pubkey,
+    ) -> Result<BeneficiarySummary> {
+        require_ctx!(
+            ctx.remaining_accounts.len() <= MAX_AGGREGATE_SCHEDULES,
+            VestingError::BatchTooLarge,
+            schedules = ctx.remaining_accounts.len(),
+            max = MAX_AGGREGATE_SCHEDULES,
+        );
+
+        let clock = Clock::get()?;
+        let mut summary = BeneficiarySummary::default();
+        for (i, info) in ctx.remaining_accounts.iter().enumerate() {
+            require_ctx!(
+                !ctx.remaining_accounts[..i].iter().any(|seen| seen.key == info.key),
+                VestingError::DuplicateSchedule,
+                schedule = info.key,
+            );
+            require_ctx!(
+                info.owner == &crate::ID,
+                VestingError::Unauthorized,
+                schedule = info.key,
+                owner = info.owner,
+            );
+            let vesting = Vesting::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            require_ctx!(
+                vesting.beneficiary == beneficiary,
+                VestingError::Unauthorized,
+                schedule = info.key,
+                beneficiary = vesting.beneficiary,
+            );
+
+            let claimable = claimable_now(&vesting, observed_now(&vesting, &clock));
+            let locked = vesting.total_amount
+                .saturating_sub(vesting.withdrawn)
+                .saturating_sub(claimable);
+
+            summary.schedules += 1;
+            summary.total_locked = summary.total_locked.checked_add(locked)
+                .ok_or(VestingError::Overflow)?;
+            summary.total_claimed = summary.total_claimed.checked_add(vesting.withdrawn)
+                .ok_or(VestingError::Overflow)?;
+            summary.total_claimable = summary.total_claimable.checked_add(claimable)
+                .ok_or(VestingError::Overflow)?;
+            summary.total_lock_seconds = summary.total_lock_seconds.saturating_add(vesting.lock_seconds_accumulated);
+        }
+
+        Ok(summary)
+    }
+
+    /// Read-only: what each schedule passed as remaining_accounts could claim
+    /// right now, in account order (return data), so a portfolio view needs
+    /// one call. Paused schedules read 0.
+    pub fn claimable_batch(ctx: Context<ClaimableBatch>) -> Result<Vec<u64>> {
+        require_ctx!(
+            ctx.remaining_accounts.len() <= MAX_CLAIMABLE_BATCH,
+            VestingError::BatchTooLarge,
+            schedules = ctx.remaining_accounts.len(),
+            max = MAX_CLAIMABLE_BATCH,
+        );
+
         let clock = Clock::get()?;
+        let mut claimable = Vec::with_capacity(ctx.remaining_accounts.len());
+        for info in ctx.remaining_accounts {
+            require_ctx!(
+                info.owner == &crate::ID,
+                VestingError::Unauthorized,
+                schedule = info.key,
+                owner = info.owner,
+            );
+            let vesting = Vesting::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            claimable.push(claimable_now(&vesting, observed_now(&vesting, &clock)));
+        }
+
+        Ok(claimable)
+    }
+
+    /// Read-only: how long the schedule's claimed tokens actually stayed
+    /// locked, in total and on average per token (return data)
+    pub fn get_lock_duration(ctx: Context<ViewVesting>) -> Result<LockDuration> {
+        let vesting = &ctx.accounts.vesting;
+        Ok(LockDuration {
+            lock_seconds_accumulated: vesting.lock_seconds_accumulated,
+            withdrawn: vesting.withdrawn,
+            average_lock_seconds: average_lock_seconds(vesting.lock_seconds_accumulated, vesting.withdrawn),
+        })
+    }
+
+    /// Read-only: the escrow's live token balance next to the schedule's own
+    /// accounting (return data). The two drift apart when the escrow is
+    /// under- or over-funded, so frontends should show the gap rather than
+    /// trust `total_amount - withdrawn`.
+    pub fn get_actual_balance(ctx: Context<ViewBalance>) -> Result<ScheduleBalance> {
+        let vesting = &ctx.accounts.vesting;
+        Ok(ScheduleBalance {
+            actual_balance: ctx.accounts.vesting_ata.amount,
+            total_amount: vesting.total_amount,
+            withdrawn: vesting.withdrawn,
+            accounted_remaining: vesting.total_amount.saturating_sub(vesting.withdrawn),
+        })
+    }
+
+    /// Read-only: the whole schedule as `STATE_BLOB_VERSION` followed by the
+    /// Borsh encoding of `Vesting`, without the account discriminator (return
+    /// data). Clients decode by version byte rather than by Anchor layout.
+    pub fn serialize_state(ctx: Context<ViewVesting>) -> Result<Vec<u8>> {
+        let mut blob = vec![STATE_BLOB_VERSION];
+        blob.extend(Vesting::try_to_vec(&ctx.accounts.vesting)?);
+        Ok(blob)
+    }
+
+    /// Read-only: whether the schedule's invariants hold, and if not the
+    /// first one violated (return data), for monitoring. Unlike the other
+    /// views the schedule isn't bound to its PDA or mint up front, so a
+    /// schedule that has drifted is reported rather than refused. A missing
+    /// escrow counts as empty.
+    pub fn audit_schedule(ctx: Context<AuditSchedule>) -> Result<ScheduleAudit> {
+        let escrow_info = &ctx.accounts.vesting_ata;
+        let escrow = if escrow_info.owner == &token::ID && !escrow_info.data_is_empty() {
+            Some(TokenAccount::try_deserialize(&mut &escrow_info.try_borrow_data()?[..])?)
+        } else {
+            None
+        };
+        let violation = first_violation(
+            &ctx.accounts.vesting,
+            &ctx.accounts.vesting.key(),
+            escrow.as_ref(),
+            ctx.accounts.mint.decimals,
+        );
+        Ok(ScheduleAudit { passed: violation.is_none(), violation })
+    }
+
+    /// Initialize the guardian quorum used for lost-key recovery
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        guardians: Vec<Pubkey>,
+        guardian_threshold: u8,
+        recovery_timelock: i64,
+    ) -> Result<()> {
+        validate_guardian_set(&guardians, guardian_threshold)?;
+        require_ctx!(
+            recovery_timelock >= MIN_RECOVERY_TIMELOCK,
+            VestingError::InvalidRecoveryTimelock,
+            recovery_timelock = recovery_timelock,
+            min = MIN_RECOVERY_TIMELOCK,
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.guardians = guardians;
+        config.guardian_threshold = guardian_threshold;
+        config.recovery_timelock = recovery_timelock;
+        config.verbose_events = true;
+        config.allowlist_root = [0u8; 32];
+        config.kyc_program = None;
+        config.compliance_authority = ctx.accounts.authority.key();
+        config.min_cliff_secs = 0;
+        config.oracle_program = None;
+        config.oracle_max_staleness_secs = 60;
+        config.oracle_max_conf_bps = 100;
+        config.dust_threshold = DEFAULT_DUST_THRESHOLD;
+        config.coverage_programs = Vec::new();
+        config.destination_programs = Vec::new();
+        config.max_pause_duration = DEFAULT_MAX_PAUSE_DURATION;
+        config.recent_repauses = Vec::new();
+        config.max_schedules_per_authority = DEFAULT_MAX_SCHEDULES_PER_AUTHORITY;
+        config.bridge_consensus_program = None;
+        config.circuit_breaker_duration = DEFAULT_CIRCUIT_BREAKER_DURATION;
+        config.circuit_breaker_until = 0;
+        config.guardian_epoch = 0;
+        config.bump = ctx.bumps.config;
+
+        msg!("✅ Vesting config initialized");
+        msg!("   Guardians: {} (threshold {})", config.guardians.len(), guardian_threshold);
+        msg!("   Recovery timelock: {}s", recovery_timelock);
+
+        Ok(())
+    }
+
+    /// Turn routine per-action events (create, deposit, withdraw) on or off.
+    /// Recovery, pause and category events are always emitted.
+    pub fn set_event_verbosity(ctx: Context<UpdateConfig>, verbose_events: bool) -> Result<()> {
+        ctx.accounts.config.verbose_events = verbose_events;
+
+        msg!("📣 Verbose events: {}", verbose_events);
+
+        Ok(())
+    }
+
+    /// Gate schedule creation on a Merkle allowlist of beneficiaries
+    /// (leaf = keccak(beneficiary)); the zero root disables the allowlist
+    pub fn set_allowlist_root(ctx: Context<UpdateConfig>, allowlist_root: [u8; 32]) -> Result<()> {
+        ctx.accounts.config.allowlist_root = allowlist_root;
+
+        msg!("📋 Allowlist root: {:?}", allowlist_root);
+
+        Ok(())
+    }
+
+    /// Minimum time between creation and unlock for new schedules (0 disables
+    /// the policy); existing schedules are unaffected
+    pub fn set_min_cliff(ctx: Context<UpdateConfig>, min_cliff_secs: i64) -> Result<()> {
+        require_ctx!(min_cliff_secs >= 0, VestingError::InvalidSchedule, min_cliff_secs = min_cliff_secs);
+        ctx.accounts.config.min_cliff_secs = min_cliff_secs;
+
+        msg!("⏳ Minimum cliff: {}s", min_cliff_secs);
+
+        Ok(())
+    }
+
+    /// How long a full pause lasts before claims resume on their own (at most
+    /// MAX_PAUSE_DURATION); applies to pauses set from now on
+    pub fn set_max_pause_duration(ctx: Context<UpdateConfig>, max_pause_duration: i64) -> Result<()> {
+        require_ctx!(
+            max_pause_duration > 0 && max_pause_duration <= MAX_PAUSE_DURATION,
+            VestingError::InvalidPauseDuration,
+            max_pause_duration = max_pause_duration,
+            max = MAX_PAUSE_DURATION,
+        );
+        ctx.accounts.config.max_pause_duration = max_pause_duration;
+
+        msg!("⏸️ Max pause duration: {}s", max_pause_duration);
+
+        Ok(())
+    }
+
+    /// How long a tripped circuit breaker blocks claims (at most
+    /// MAX_CIRCUIT_BREAKER_DURATION); applies to trips from now on
+    pub fn set_circuit_breaker_duration(ctx: Context<UpdateConfig>, circuit_breaker_duration: i64) -> Result<()> {
+        require_ctx!(
+            circuit_breaker_duration > 0 && circuit_breaker_duration <= MAX_CIRCUIT_BREAKER_DURATION,
+            VestingError::InvalidCircuitBreakerDuration,
+            circuit_breaker_duration = circuit_breaker_duration,
+            max = MAX_CIRCUIT_BREAKER_DURATION,
+        );
+        ctx.accounts.config.circuit_breaker_duration = circuit_breaker_duration;
+
+        msg!("🚨 Circuit breaker duration: {}s", circuit_breaker_duration);
+
+        Ok(())
+    }
+
+    /// Program-wide kill switch: block claims from every schedule for the
+    /// configured circuit breaker duration, after which they resume on their
+    /// own. Tripping again restarts the duration. Guardian recovery, pauses
+    /// and other emergency actions are unaffected.
+    pub fn trip_circuit_breaker(ctx: Context<UpdateConfig>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.circuit_breaker_until = Clock::get()?.unix_timestamp
+            .checked_add(config.circuit_breaker_duration)
+            .ok_or(VestingError::Overflow)?;
+
+        msg!("🚨 Circuit breaker tripped until {}", config.circuit_breaker_until);
+
+        Ok(())
+    }
+
+    /// Most live schedules one authority may have; schedules already over a
+    /// lowered cap stay, but no more can be created until enough are closed
+    pub fn set_max_schedules_per_authority(ctx: Context<UpdateConfig>, max_schedules_per_authority: u32) -> Result<()> {
+        require_ctx!(
+            max_schedules_per_authority > 0,
+            VestingError::InvalidScheduleLimit,
+            max_schedules_per_authority = max_schedules_per_authority,
+        );
+        ctx.accounts.config.max_schedules_per_authority = max_schedules_per_authority;
+
+        msg!("🗂️ Max schedules per authority: {}", max_schedules_per_authority);
 
-        // CRITICAL: Enforce time-lock
-        require!(
-            clock.unix_timestamp >= vesting.unlock_timestamp,
-            VestingError::StillLocked
+        Ok(())
+    }
+
+    /// Largest escrow balance `close_vesting` sweeps as dust (at most MAX_DUST_THRESHOLD)
+    pub fn set_dust_threshold(ctx: Context<UpdateConfig>, dust_threshold: u64) -> Result<()> {
+        require_ctx!(
+            dust_threshold <= MAX_DUST_THRESHOLD,
+            VestingError::InvalidDustThreshold,
+            dust_threshold = dust_threshold,
+            max = MAX_DUST_THRESHOLD,
+        );
+        ctx.accounts.config.dust_threshold = dust_threshold;
+
+        msg!("🧹 Dust threshold: {}", dust_threshold);
+
+        Ok(())
+    }
+
+    /// Price oracle policy for USD schedules: the Pyth program owning price
+    /// accounts (None blocks USD claims), maximum price age, and maximum
+    /// confidence interval relative to the price
+    pub fn set_oracle_policy(
+        ctx: Context<UpdateConfig>,
+        oracle_program: Option<Pubkey>,
+        max_staleness_secs: i64,
+        max_conf_bps: u16,
+    ) -> Result<()> {
+        require_ctx!(
+            max_staleness_secs > 0 && max_conf_bps <= 10_000,
+            VestingError::InvalidOraclePolicy,
+            max_staleness_secs = max_staleness_secs,
+            max_conf_bps = max_conf_bps,
+        );
+        let config = &mut ctx.accounts.config;
+        config.oracle_program = oracle_program;
+        config.oracle_max_staleness_secs = max_staleness_secs;
+        config.oracle_max_conf_bps = max_conf_bps;
+
+        msg!("📈 Oracle program: {:?}", oracle_program);
+        msg!("   Max staleness: {}s, max confidence: {} bps", max_staleness_secs, max_conf_bps);
+
+        Ok(())
+    }
+
+    /// Require a KYC attestation from `kyc_program` on every claim (None turns
+    /// the gate off) and name the compliance authority that grants exemptions
+    pub fn set_kyc_gate(
+        ctx: Context<UpdateConfig>,
+        kyc_program: Option<Pubkey>,
+        compliance_authority: Pubkey,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.kyc_program = kyc_program;
+        config.compliance_authority = compliance_authority;
+
+        msg!("🪪 KYC program: {:?}", kyc_program);
+        msg!("   Compliance authority: {}", compliance_authority);
+
+        Ok(())
+    }
+
+    /// Exempt a schedule from the KYC gate, or re-subject it (compliance authority only)
+    pub fn set_kyc_exempt(ctx: Context<SetKycExempt>, kyc_exempt: bool) -> Result<()> {
+        let vesting = &mut ctx.accounts.vesting;
+        vesting.kyc_exempt = kyc_exempt;
+
+        emit!(KycExemptionUpdated {
+            category: vesting.category,
+            label_hash: vesting.label_hash,
+            vesting: vesting.key(),
+            kyc_exempt,
+        });
+
+        msg!("🪪 Schedule {} KYC exempt: {}", vesting.schedule_id, kyc_exempt);
+
+        Ok(())
+    }
+
+    /// Let `delegate` claim up to `max_total` tokens via `auto_claim`; the
+    /// default pubkey revokes. Every call starts a fresh budget. The
+    /// beneficiary's own withdrawals are never limited by it.
+    pub fn set_auto_claim(ctx: Context<SetAutoClaim>, delegate: Pubkey, max_total: u64) -> Result<()> {
+        let vesting = &mut ctx.accounts.vesting;
+        vesting.auto_claim_delegate = delegate;
+        vesting.auto_claim_max_total = max_total;
+        vesting.auto_claimed_total = 0;
+
+        emit!(AutoClaimUpdated {
+            category: vesting.category,
+            label_hash: vesting.label_hash,
+            vesting: vesting.key(),
+            delegate,
+            max_total,
+        });
+
+        msg!("🤖 Schedule {} auto-claim: {} up to {}", vesting.schedule_id, delegate, max_total);
+
+        Ok(())
+    }
+
+    /// Coverage programs beneficiaries may register policies from (config
+    /// authority only); an empty list disables `register_coverage`
+    pub fn set_coverage_programs(ctx: Context<UpdateConfig>, coverage_programs: Vec<Pubkey>) -> Result<()> {
+        require_ctx!(
+            coverage_programs.len() <= MAX_COVERAGE_PROGRAMS,
+            VestingError::BatchTooLarge,
+            coverage_programs = coverage_programs.len(),
+            max = MAX_COVERAGE_PROGRAMS,
+        );
+        ctx.accounts.config.coverage_programs = coverage_programs;
+
+        msg!("☂️ Coverage programs: {:?}", ctx.accounts.config.coverage_programs);
+
+        Ok(())
+    }
+
+    /// Programs whose PDAs `withdraw` may pay into instead of the beneficiary's
+    /// ATA (config authority only); an empty list allows only the ATA
+    pub fn set_destination_programs(ctx: Context<UpdateConfig>, destination_programs: Vec<Pubkey>) -> Result<()> {
+        require_ctx!(
+            destination_programs.len() <= MAX_DESTINATION_PROGRAMS,
+            VestingError::BatchTooLarge,
+            destination_programs = destination_programs.len(),
+            max = MAX_DESTINATION_PROGRAMS,
+        );
+        ctx.accounts.config.destination_programs = destination_programs;
+
+        msg!("🏦 Destination programs: {:?}", ctx.accounts.config.destination_programs);
+
+        Ok(())
+    }
+
+    /// Name the Trinity validator program whose consensus mirrors prove
+    /// bridge deposits (None turns `deposit_from_bridge` off)
+    pub fn set_bridge_consensus_program(
+        ctx: Context<UpdateConfig>,
+        bridge_consensus_program: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.config.bridge_consensus_program = bridge_consensus_program;
+
+        msg!("🌉 Bridge consensus program: {:?}", bridge_consensus_program);
+
+        Ok(())
+    }
+
+    /// Record an insurance policy covering this schedule until `expiry`,
+    /// replacing any previous one. Claims and emergency actions carry the
+    /// policy id in their events until it expires or the schedule closes.
+    pub fn register_coverage(
+        ctx: Context<RegisterCoverage>,
+        coverage_program: Pubkey,
+        policy_id: u64,
+        expiry: i64,
+    ) -> Result<()> {
+        let now = observed_now(&ctx.accounts.vesting, &Clock::get()?);
+        require_ctx!(
+            ctx.accounts.config.coverage_programs.contains(&coverage_program),
+            VestingError::CoverageProgramNotAllowed,
+            coverage_program = coverage_program,
+        );
+        require_ctx!(
+            policy_id != 0 && expiry > now,
+            VestingError::InvalidCoverage,
+            policy_id = policy_id,
+            expiry = expiry,
+            now = now,
         );
 
-        require!(
-            ctx.accounts.beneficiary.key() == vesting.beneficiary,
-            VestingError::Unauthorized
-        );
+        let vesting = &mut ctx.accounts.vesting;
+        vesting.coverage = Some(CoverageInfo { coverage_program, policy_id, expiry });
+
+        emit!(CoverageRegistered {
+            category: vesting.category,
+            label_hash: vesting.label_hash,
+            vesting: vesting.key(),
+            coverage_program,
+            policy_id,
+            expiry,
+        });
+
+        msg!("☂️ Schedule {} covered by policy {} until {}", vesting.schedule_id, policy_id, expiry);
+
+        Ok(())
+    }
+
+    /// Read-only: whether `proof` shows `beneficiary` is allowlisted (return data).
+    /// Always true while no allowlist is set.
+    pub fn is_allowlisted(
+        ctx: Context<ViewConfig>,
+        beneficiary: Pubkey,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<bool> {
+        Ok(require_allowlisted(&ctx.accounts.config, &beneficiary, &proof).is_ok())
+    }
+
+    /// Guardian quorum proposes a new beneficiary for a schedule whose key was lost.
+    /// The proposer plus any co-signing guardians passed as remaining_accounts
+    /// must reach the threshold. Execution waits for `recovery_timelock`.
+    pub fn guardian_recover_beneficiary(
+        ctx: Context<ProposeRecovery>,
+        new_beneficiary: Pubkey,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let vesting = &ctx.accounts.vesting;
+        let clock = Clock::get()?;
+
+        require_ctx!(
+            new_beneficiary != Pubkey::default() && new_beneficiary != vesting.beneficiary,
+            VestingError::InvalidBeneficiary,
+            new_beneficiary = new_beneficiary,
+        );
+
+        require_ctx!(
+            ctx.remaining_accounts.len() <= MAX_RECOVERY_CO_SIGNERS,
+            VestingError::BatchTooLarge,
+            co_signers = ctx.remaining_accounts.len(),
+            max = MAX_RECOVERY_CO_SIGNERS,
+        );
+        let approvals = count_guardian_approvals(
+            config,
+            &ctx.accounts.proposer.key(),
+            ctx.remaining_accounts,
+        );
+        require_ctx!(
+            approvals >= config.guardian_threshold,
+            VestingError::InsufficientGuardianApprovals,
+            approvals = approvals,
+            threshold = config.guardian_threshold,
+        );
+
+        let recovery = &mut ctx.accounts.recovery;
+        recovery.vesting = vesting.key();
+        recovery.new_beneficiary = new_beneficiary;
+        recovery.proposer = ctx.accounts.proposer.key();
+        recovery.proposed_at = clock.unix_timestamp;
+        recovery.executable_at = clock.unix_timestamp
+            .checked_add(config.recovery_timelock)
+            .ok_or(VestingError::Overflow)?;
+        recovery.approvals = approvals;
+        recovery.guardian_epoch = config.guardian_epoch;
+        recovery.bump = ctx.bumps.recovery;
+
+        emit!(BeneficiaryRecoveryProposed {
+            category: vesting.category,
+            label_hash: vesting.label_hash,
+            vesting: vesting.key(),
+            current_beneficiary: vesting.beneficiary,
+            new_beneficiary,
+            approvals,
+            executable_at: recovery.executable_at,
+            policy_id: covering_policy(vesting, clock.unix_timestamp),
+        });
+
+        msg!("🛡️ Beneficiary recovery proposed for schedule {}", vesting.schedule_id);
+        msg!("   Executable at: {}", recovery.executable_at);
+
+        Ok(())
+    }
+
+    /// Execute a recovery once its timelock has elapsed (permissionless). A
+    /// recovery proposed before the last guardian rotation is discarded
+    /// instead: the guardians who approved it are no longer trusted.
+    pub fn execute_beneficiary_recovery(ctx: Context<ExecuteRecovery>) -> Result<()> {
+        let recovery = &ctx.accounts.recovery;
+        let vesting = &mut ctx.accounts.vesting;
+        let clock = Clock::get()?;
+
+        if recovery.guardian_epoch != ctx.accounts.config.guardian_epoch {
+            cancel_recovery(vesting, Pubkey::default());
+            return Ok(());
+        }
+
+        require_ctx!(
+            clock.unix_timestamp >= recovery.executable_at,
+            VestingError::RecoveryTimelockActive,
+            executable_at = recovery.executable_at,
+            now = clock.unix_timestamp,
+        );
+
+        let previous = vesting.beneficiary;
+        vesting.beneficiary = recovery.new_beneficiary;
+        // The new beneficiary is a plain key; the PDA keeps its hashed seed
+        vesting.beneficiary_hashed = false;
+        // The lost key's automation doesn't carry over to the new beneficiary
+        vesting.auto_claim_delegate = Pubkey::default();
+        vesting.auto_claim_max_total = 0;
+        vesting.auto_claimed_total = 0;
+        expire_coverage(vesting, clock.unix_timestamp);
+
+        emit!(BeneficiaryRecovered {
+            category: vesting.category,
+            label_hash: vesting.label_hash,
+            vesting: vesting.key(),
+            previous_beneficiary: previous,
+            new_beneficiary: vesting.beneficiary,
+            policy_id: covering_policy(vesting, clock.unix_timestamp),
+        });
+
+        msg!("✅ Beneficiary recovered for schedule {}", vesting.schedule_id);
+        msg!("   New beneficiary: {}", vesting.beneficiary);
+
+        Ok(())
+    }
+
+    /// Current guardian quorum proposes replacing the whole guardian set and
+    /// threshold at once (the proposer plus co-signing guardians passed as
+    /// remaining_accounts). Execution waits for `recovery_timelock`, so there
+    /// is never a half-rotated set. One rotation can be pending at a time.
+    pub fn rotate_guardians(
+        ctx: Context<RotateGuardians>,
+        guardians: Vec<Pubkey>,
+        guardian_threshold: u8,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let clock = Clock::get()?;
+
+        validate_guardian_set(&guardians, guardian_threshold)?;
+        require_ctx!(
+            ctx.remaining_accounts.len() <= MAX_RECOVERY_CO_SIGNERS,
+            VestingError::BatchTooLarge,
+            co_signers = ctx.remaining_accounts.len(),
+            max = MAX_RECOVERY_CO_SIGNERS,
+        );
+        let approvals = count_guardian_approvals(
+            config,
+            &ctx.accounts.proposer.key(),
+            ctx.remaining_accounts,
+        );
+        require_ctx!(
+            approvals >= config.guardian_threshold,
+            VestingError::InsufficientGuardianApprovals,
+            approvals = approvals,
+            threshold = config.guardian_threshold,
+        );
+
+        let rotation = &mut ctx.accounts.rotation;
+        rotation.guardians = guardians;
+        rotation.guardian_threshold = guardian_threshold;
+        rotation.proposer = ctx.accounts.proposer.key();
+        rotation.proposed_at = clock.unix_timestamp;
+        rotation.executable_at = clock.unix_timestamp
+            .checked_add(config.recovery_timelock)
+            .ok_or(VestingError::Overflow)?;
+        rotation.approvals = approvals;
+        rotation.bump = ctx.bumps.rotation;
+
+        emit!(GuardianRotationProposed {
+            category: 0,
+            label_hash: [0; 32],
+            proposer: rotation.proposer,
+            guardians_hash: guardian_set_hash(&rotation.guardians),
+            guardian_count: rotation.guardians.len() as u8,
+            guardian_threshold,
+            approvals,
+            executable_at: rotation.executable_at,
+        });
+
+        msg!("🛡️ Guardian rotation proposed: {} guardians (threshold {})", rotation.guardians.len(), guardian_threshold);
+        msg!("   Executable at: {}", rotation.executable_at);
+
+        Ok(())
+    }
+
+    /// Swap in a proposed guardian set once its timelock has elapsed (permissionless)
+    pub fn execute_guardian_rotation(ctx: Context<ExecuteGuardianRotation>) -> Result<()> {
+        let rotation = &ctx.accounts.rotation;
+        let clock = Clock::get()?;
+
+        require_ctx!(
+            clock.unix_timestamp >= rotation.executable_at,
+            VestingError::RecoveryTimelockActive,
+            executable_at = rotation.executable_at,
+            now = clock.unix_timestamp,
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.guardians = rotation.guardians.clone();
+        config.guardian_threshold = rotation.guardian_threshold;
+        // Pending recoveries were approved by the outgoing set
+        config.guardian_epoch = config.guardian_epoch.checked_add(1).ok_or(VestingError::Overflow)?;
+
+        emit!(GuardiansRotated {
+            category: 0,
+            label_hash: [0; 32],
+            guardians_hash: guardian_set_hash(&config.guardians),
+            guardian_count: config.guardians.len() as u8,
+            guardian_threshold: config.guardian_threshold,
+            guardian_epoch: config.guardian_epoch,
+        });
+
+        msg!("✅ Guardians rotated: {} guardians (threshold {})", config.guardians.len(), config.guardian_threshold);
+
+        Ok(())
+    }
+
+    /// Current guardian quorum withdraws a pending rotation (the signer plus
+    /// co-signing guardians passed as remaining_accounts)
+    pub fn cancel_guardian_rotation(ctx: Context<CancelGuardianRotation>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require_ctx!(
+            ctx.remaining_accounts.len() <= MAX_RECOVERY_CO_SIGNERS,
+            VestingError::BatchTooLarge,
+            co_signers = ctx.remaining_accounts.len(),
+            max = MAX_RECOVERY_CO_SIGNERS,
+        );
+        let approvals = count_guardian_approvals(config, &ctx.accounts.guardian.key(), ctx.remaining_accounts);
+        require_ctx!(
+            approvals >= config.guardian_threshold,
+            VestingError::InsufficientGuardianApprovals,
+            approvals = approvals,
+            threshold = config.guardian_threshold,
+        );
+
+        emit!(GuardianRotationCancelled {
+            category: 0,
+            label_hash: [0; 32],
+            guardians_hash: guardian_set_hash(&ctx.accounts.rotation.guardians),
+            cancelled_by: ctx.accounts.guardian.key(),
+            approvals,
+        });
+
+        msg!("⛔ Guardian rotation cancelled");
+
+        Ok(())
+    }
+
+    /// Propose moving a schedule to `new_mint`, e.g. a spun-out subsidiary's
+    /// 1:1 migration token, at `conversion_numerator / conversion_denominator`
+    /// new tokens per old token. The authority deposits the converted escrow
+    /// balance into the schedule's new-mint ATA up front, so that
+    /// `accept_mint_migration` can switch the schedule in one instruction.
+    /// The schedule moves to `new_authority` (the current one to keep it),
+    /// and a USD schedule to `new_price_oracle`, the new mint's Pyth account.
+    pub fn migrate_mint(
+        ctx: Context<MigrateMint>,
+        conversion_numerator: u64,
+        conversion_denominator: u64,
+        new_authority: Pubkey,
+        new_price_oracle: Option<Pubkey>,
+    ) -> Result<()> {
+        let vesting = &ctx.accounts.vesting;
+        require_ctx!(
+            conversion_numerator > 0 && conversion_denominator > 0 && ctx.accounts.new_mint.key() != vesting.mint,
+            VestingError::InvalidConversion,
+            numerator = conversion_numerator,
+            denominator = conversion_denominator,
+            new_mint = ctx.accounts.new_mint.key(),
+        );
+        // The old mint's price says nothing about the new one
+        require_ctx!(
+            new_price_oracle.is_some() == vesting.usd_terms.is_some(),
+            VestingError::InvalidConversion,
+            vesting = vesting.key(),
+            usd = vesting.usd_terms.is_some(),
+        );
+        // Fails now rather than at acceptance if the converted total overflows
+        convert_amount(vesting.total_amount, conversion_numerator, conversion_denominator)?;
+
+        let deposited = convert_amount(ctx.accounts.vesting_ata.amount, conversion_numerator, conversion_denominator)?;
+        if deposited > 0 {
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.authority_new_ata.to_account_info(),
+                        to: ctx.accounts.new_vesting_ata.to_account_info(),
+                        authority: ctx.accounts.authority.to_account_info(),
+                    },
+                ),
+                deposited
+            )?;
+        }
+
+        let migration = &mut ctx.accounts.migration;
+        migration.vesting = vesting.key();
+        migration.new_mint = ctx.accounts.new_mint.key();
+        migration.conversion_numerator = conversion_numerator;
+        migration.conversion_denominator = conversion_denominator;
+        migration.deposited = deposited;
+        migration.proposed_at = Clock::get()?.unix_timestamp;
+        migration.new_authority = new_authority;
+        migration.new_price_oracle = new_price_oracle;
+        migration.bump = ctx.bumps.migration;
+
+        // Acceptance moves the schedule onto this count, so it has to exist
+        let new_schedule_count = &mut ctx.accounts.new_schedule_count;
+        if new_schedule_count.authority == Pubkey::default() {
+            new_schedule_count.authority = new_authority;
+            new_schedule_count.bump = ctx.bumps.new_schedule_count;
+        }
+
+        emit!(MintMigrationProposed {
+            category: vesting.category,
+            label_hash: vesting.label_hash,
+            vesting: vesting.key(),
+            new_mint: migration.new_mint,
+            conversion_numerator,
+            conversion_denominator,
+            deposited,
+            new_authority,
+        });
+
+        msg!("🔁 Mint migration proposed for schedule {}", vesting.schedule_id);
+        msg!("   New mint: {} ({}/{})", migration.new_mint, conversion_numerator, conversion_denominator);
+
+        Ok(())
+    }
+
+    /// Beneficiary accepts a proposed mint migration. In one step the old-mint
+    /// escrow goes back to the authority and its ATA is closed to it, any
+    /// new-mint deposit beyond the converted balance is refunded, and the
+    /// schedule's amounts are rewritten at the ratio (rounded down; the
+    /// claimable remainder is converted directly, so rounding never adds to
+    /// it). The schedule then belongs to the proposal's new authority, whose
+    /// `new_schedule_count` must be passed if it differs, and a USD schedule
+    /// is priced by the new oracle.
+    pub fn accept_mint_migration(ctx: Context<AcceptMintMigration>) -> Result<()> {
+        let migration = &ctx.accounts.migration;
+        let (numerator, denominator) = (migration.conversion_numerator, migration.conversion_denominator);
+        let (new_authority, new_price_oracle) = (migration.new_authority, migration.new_price_oracle);
+        let vesting = &mut ctx.accounts.vesting;
+
+        let released = ctx.accounts.vesting_ata.amount;
+        let required = convert_amount(released, numerator, denominator)?;
+        let available = ctx.accounts.new_vesting_ata.amount;
+        require_ctx!(
+            available >= required,
+            VestingError::MigrationUnderfunded,
+            required = required,
+            available = available,
+        );
+        let refunded = available - required;
+
+        let total_amount = convert_amount(vesting.total_amount, numerator, denominator)?;
+        let remaining = convert_amount(vesting.total_amount.saturating_sub(vesting.withdrawn), numerator, denominator)?;
+        let auto_claim_max_total = convert_amount(vesting.auto_claim_max_total, numerator, denominator)?;
+        let auto_claimed_total = convert_amount(vesting.auto_claimed_total, numerator, denominator)?;
+        let lock_seconds_accumulated = convert_token_seconds(vesting.lock_seconds_accumulated, numerator, denominator);
+
+        with_vesting_signer(vesting, |signer| {
+            let token_program = ctx.accounts.token_program.to_account_info();
+            if released > 0 {
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        token_program.clone(),
+                        Transfer {
+                            from: ctx.accounts.vesting_ata.to_account_info(),
+                            to: ctx.accounts.authority_ata.to_account_info(),
+                            authority: vesting.to_account_info(),
+                        },
+                        signer
+                    ),
+                    released
+                )?;
+            }
+            if refunded > 0 {
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        token_program.clone(),
+                        Transfer {
+                            from: ctx.accounts.new_vesting_ata.to_account_info(),
+                            to: ctx.accounts.authority_new_ata.to_account_info(),
+                            authority: vesting.to_account_info(),
+                        },
+                        signer
+                    ),
+                    refunded
+                )?;
+            }
+            token::close_account(CpiContext::new_with_signer(
+                token_program,
+                CloseAccount {
+                    account: ctx.accounts.vesting_ata.to_account_info(),
+                    destination: ctx.accounts.authority.to_account_info(),
+                    authority: vesting.to_account_info(),
+                },
+                signer
+            ))
+        })?;
+
+        if new_authority != vesting.authority {
+            let new_schedule_count =
+                ctx.accounts.new_schedule_count.as_mut().ok_or(VestingError::ScheduleCountRequired)?;
+            new_schedule_count.schedule_count += 1;
+            let schedule_count = &mut ctx.accounts.schedule_count;
+            schedule_count.schedule_count = schedule_count.schedule_count.saturating_sub(1);
+            vesting.authority = new_authority;
+        }
+        if let (Some(terms), Some(price_oracle)) = (vesting.usd_terms.as_mut(), new_price_oracle) {
+            terms.price_oracle = price_oracle;
+        }
+
+        let old_mint = vesting.mint;
+        vesting.mint = ctx.accounts.new_mint.key();
+        vesting.decimals = ctx.accounts.new_mint.decimals;
+        vesting.total_amount = total_amount;
+        vesting.withdrawn = total_amount - remaining;
+        vesting.auto_claim_max_total = auto_claim_max_total;
+        vesting.auto_claimed_total = auto_claimed_total;
+        vesting.lock_seconds_accumulated = lock_seconds_accumulated;
+
+        emit!(MintMigrated {
+            category: vesting.category,
+            label_hash: vesting.label_hash,
+            vesting: vesting.key(),
+            old_mint,
+            new_mint: vesting.mint,
+            total_amount,
+            withdrawn: vesting.withdrawn,
+            released,
+        });
+
+        msg!("✅ Schedule {} migrated to mint {}", vesting.schedule_id, vesting.mint);
+
+        Ok(())
+    }
+
+    /// Authority withdraws a mint migration the beneficiary hasn't accepted,
+    /// taking back its new-mint deposit
+    pub fn cancel_mint_migration(ctx: Context<CancelMintMigration>) -> Result<()> {
+        let vesting = &ctx.accounts.vesting;
+        let refunded = ctx.accounts.new_vesting_ata.amount;
+        if refunded > 0 {
+            with_vesting_signer(vesting, |signer| {
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.new_vesting_ata.to_account_info(),
+                            to: ctx.accounts.authority_new_ata.to_account_info(),
+                            authority: vesting.to_account_info(),
+                        },
+                        signer
+                    ),
+                    refunded
+                )
+            })?;
+        }
+
+        emit!(MintMigrationCancelled {
+            category: vesting.category,
+            label_hash: vesting.label_hash,
+            vesting: vesting.key(),
+            new_mint: ctx.accounts.new_mint.key(),
+            refunded,
+        });
+
+        msg!("⛔ Mint migration cancelled for schedule {}", vesting.schedule_id);
+
+        Ok(())
+    }
+
+    /// The schedule's authority pushes a still-locked unlock later, up to the
+    /// `max_unlock_timestamp` fixed at creation
+    pub fn extend_unlock(ctx: Context<ExtendUnlock>, new_unlock_timestamp: i64) -> Result<()> {
+        let vesting = &mut ctx.accounts.vesting;
+        let now = observed_now(vesting, &Clock::get()?);
+        require_ctx!(
+            !is_unlocked(now, vesting.unlock_timestamp),
+            VestingError::InvalidUnlockTime,
+            unlock_timestamp = vesting.unlock_timestamp,
+            now = now,
+        );
+        require_ctx!(
+            new_unlock_timestamp > vesting.unlock_timestamp
+                && new_unlock_timestamp <= vesting.max_unlock_timestamp,
+            VestingError::InvalidUnlockTime,
+            unlock_timestamp = vesting.unlock_timestamp,
+            requested = new_unlock_timestamp,
+            max_unlock_timestamp = vesting.max_unlock_timestamp,
+        );
+
+        let previous_unlock = vesting.unlock_timestamp;
+        vesting.unlock_timestamp = new_unlock_timestamp;
+
+        emit!(UnlockExtended {
+            category: vesting.category,
+            label_hash: vesting.label_hash,
+            vesting: vesting.key(),
+            previous_unlock,
+            unlock_timestamp: new_unlock_timestamp,
+            max_unlock_timestamp: vesting.max_unlock_timestamp,
+        });
+
+        msg!("⏳ Schedule {} unlock: {} -> {}", vesting.schedule_id, previous_unlock, new_unlock_timestamp);
+
+        Ok(())
+    }
+
+    /// A live beneficiary objects to a pending recovery, cancelling it
+    pub fn object_beneficiary_recovery(ctx: Context<ObjectRecovery>) -> Result<()> {
+        let vesting = &ctx.accounts.vesting;
+        require_ctx!(!vesting.beneficiary_hashed, VestingError::BeneficiarySaltRequired, vesting = vesting.key());
+        cancel_recovery(vesting, ctx.accounts.beneficiary.key());
+        Ok(())
+    }
+
+    /// `object_beneficiary_recovery` for a hashed-beneficiary schedule: the
+    /// signer proves it is the beneficiary with the schedule's salt, as in
+    /// `withdraw_hashed`. A beneficiary who lost the salt can't object.
+    pub fn object_beneficiary_recovery_hashed(ctx: Context<ObjectRecovery>, salt: [u8; 32]) -> Result<()> {
+        let vesting = &ctx.accounts.vesting;
+        require_ctx!(vesting.beneficiary_hashed, VestingError::NotHashedBeneficiary, vesting = vesting.key());
+        require_ctx!(
+            beneficiary_hash(&ctx.accounts.beneficiary.key(), &salt) == vesting.beneficiary,
+            VestingError::InvalidBeneficiarySalt,
+            vesting = vesting.key(),
+            signer = ctx.accounts.beneficiary.key(),
+        );
+        cancel_recovery(vesting, ctx.accounts.beneficiary.key());
+        Ok(())
+    }
+
+    /// Before bridging CVT from Ethereum into a schedule's escrow, its
+    /// authority opens the pending deposit for the bridge operation, recording
+    /// the escrow balance the bridged `amount` has to arrive on top of. The
+    /// operation must be bridge_operation_id(escrow, amount, salt), so its
+    /// consensus proves this recipient and amount. The account is never
+    /// closed: it is also the operation's consumed marker. A schedule has one
+    /// open deposit at a time, so two can't count the same arrival against a
+    /// shared baseline.
+    pub fn open_bridge_deposit(
+        ctx: Context<OpenBridgeDeposit>,
+        operation_id: [u8; 32],
+        amount: u64,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        let vesting = &ctx.accounts.vesting;
+        let now = observed_now(vesting, &Clock::get()?);
+        require_ctx!(
+            !pause_in_force(vesting, now),
+            VestingError::Paused,
+            vesting = vesting.key(),
+            paused_until = vesting.paused_until,
+        );
+        require_ctx!(!vesting.deposits_paused, VestingError::DepositsPaused, vesting = vesting.key());
+        require_ctx!(amount > 0, VestingError::InvalidAmount, amount = amount);
+        require_ctx!(
+            operation_id == bridge_operation_id(&ctx.accounts.vesting_ata.key(), amount, &salt),
+            VestingError::BridgeOperationMismatch,
+            vesting_ata = ctx.accounts.vesting_ata.key(),
+            amount = amount,
+        );
+        let inflight = &mut ctx.accounts.bridge_inflight;
+        require_ctx!(
+            inflight.operation_id.is_none(),
+            VestingError::BridgeDepositInFlight,
+            vesting = vesting.key(),
+        );
+        inflight.vesting = vesting.key();
+        inflight.operation_id = Some(operation_id);
+        inflight.bump = ctx.bumps.bridge_inflight;
+
+        let deposit = &mut ctx.accounts.bridge_deposit;
+        deposit.vesting = vesting.key();
+        deposit.operation_id = operation_id;
+        deposit.amount = amount;
+        deposit.escrow_before = ctx.accounts.vesting_ata.amount;
+        deposit.withdrawn_before = vesting.withdrawn;
+        deposit.opened_at = now;
+        deposit.credited_at = 0;
+        deposit.bump = ctx.bumps.bridge_deposit;
+
+        msg!("🌉 Bridge deposit of {} opened for schedule {}", amount, vesting.schedule_id);
+
+        Ok(())
+    }
+
+    /// Permissionless: credit an open bridge deposit once Trinity consensus on
+    /// its operation is mirrored and the bridged tokens are in the escrow.
+    /// Claims made since it was opened count as arrived, since they left the
+    /// escrow; each operation is credited at most once.
+    pub fn deposit_from_bridge(ctx: Context<DepositFromBridge>, operation_id: [u8; 32]) -> Result<()> {
+        let vesting = &mut ctx.accounts.vesting;
+        let deposit = &mut ctx.accounts.bridge_deposit;
+        require_ctx!(
+            deposit.credited_at == 0,
+            VestingError::BridgeDepositConsumed,
+            deposit = deposit.key(),
+            credited_at = deposit.credited_at,
+        );
+        check_consensus_mirror(&ctx.accounts.config, &ctx.accounts.consensus_mirror, &operation_id)?;
+
+        let claimed_since = vesting.withdrawn.saturating_sub(deposit.withdrawn_before);
+        let arrived = ctx.accounts.vesting_ata.amount
+            .saturating_add(claimed_since)
+            .saturating_sub(deposit.escrow_before);
+        require_ctx!(
+            arrived >= deposit.amount,
+            VestingError::BridgeDepositNotArrived,
+            expected = deposit.amount,
+            arrived = arrived,
+        );
+
+        deposit.credited_at = observed_now(vesting, &Clock::get()?);
+        ctx.accounts.bridge_inflight.operation_id = None;
+        // Locked from the credit, the first time the schedule sees the tokens
+        let escrow_before = ctx.accounts.vesting_ata.amount.saturating_sub(deposit.amount);
+        vesting.deposit_weighted_start =
+            weighted_lock_start(vesting.deposit_weighted_start, escrow_before, deposit.amount, deposit.credited_at);
+
+        emit!(BridgeDepositCredited {
+            category: vesting.category,
+            label_hash: vesting.label_hash,
+            vesting: vesting.key(),
+            operation_id,
+            amount: deposit.amount,
+        });
+
+        msg!("🌉 Bridged deposit of {} credited to schedule {}", deposit.amount, vesting.schedule_id);
+
+        Ok(())
+    }
+
+    /// Permissionless: once a schedule's claim deadline passes, send the
+    /// escrow's remaining balance to the expiry sink fixed at creation and
+    /// forfeit the unclaimed remainder. `sink_tokens` must be a token account
+    /// of the sink for the schedule's mint; the incinerator needs none, since
+    /// sending to it burns.
+    pub fn expire_and_return(ctx: Context<ExpireAndReturn>) -> Result<()> {
+        let vesting = &mut ctx.accounts.vesting;
+        let sink = vesting.expiry_sink;
+        require_ctx!(sink != Pubkey::default(), VestingError::ExpirySinkNotSet, vesting = vesting.key());
+        let now = observed_now(vesting, &Clock::get()?);
+        require_ctx!(
+            now >= vesting.claim_deadline,
+            VestingError::ClaimWindowOpen,
+            claim_deadline = vesting.claim_deadline,
+            now = now,
+        );
+
+        let amount = ctx.accounts.vesting_ata.amount;
+        let burned = sink == incinerator::ID;
+        let sink_tokens = if burned {
+            None
+        } else {
+            let sink_tokens = ctx.accounts.sink_tokens.as_ref().ok_or(VestingError::InvalidExpirySink)?;
+            require_ctx!(
+                sink_tokens.owner == sink,
+                VestingError::InvalidExpirySink,
+                sink = sink,
+                owner = sink_tokens.owner,
+            );
+            require_ctx!(
+                sink_tokens.mint == vesting.mint,
+                VestingError::TokenMintMismatch,
+                mint = vesting.mint,
+                token_mint = sink_tokens.mint,
+            );
+            Some(sink_tokens)
+        };
+
+        if amount > 0 {
+            with_vesting_signer(vesting, |signer| match sink_tokens {
+                Some(sink_tokens) => token::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.vesting_ata.to_account_info(),
+                            to: sink_tokens.to_account_info(),
+                            authority: vesting.to_account_info(),
+                        },
+                        signer
+                    ),
+                    amount
+                ),
+                None => token::burn(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        Burn {
+                            mint: ctx.accounts.mint.to_account_info(),
+                            from: ctx.accounts.vesting_ata.to_account_info(),
+                            authority: vesting.to_account_info(),
+                        },
+                        signer
+                    ),
+                    amount
+                ),
+            })?;
+        }
+
+        // Nothing is claimable from here on, even if the escrow is refilled
+        let forfeited = vesting.total_amount.saturating_sub(vesting.withdrawn);
+        vesting.total_amount = vesting.withdrawn;
+        vesting.last_observed_timestamp = now;
+
+        emit!(VestingExpired {
+            category: vesting.category,
+            label_hash: vesting.label_hash,
+            vesting: vesting.key(),
+            sink,
+            amount,
+            forfeited,
+            burned,
+        });
+
+        msg!("⌛ Schedule {} expired: {} returned to {} (burned: {})", vesting.schedule_id, amount, sink, burned);
+
+        Ok(())
+    }
+
+    /// Register a schedule ahead of its start: the authority stages `amount`
+    /// tokens in the pending PDA's ATA now, and anyone can activate it from
+    /// `activation_timestamp` on. Creation policy is checked as of activation,
+    /// and the schedule counts against the authority's cap from here.
+    pub fn register_pending_schedule(
+        ctx: Context<RegisterPendingSchedule>,
+        terms: ScheduleTerms,
+        activation_timestamp: i64,
+    ) -> Result<()> {
+        let config = load_config(&ctx.accounts.config)?;
+        let now = Clock::get()?.unix_timestamp;
+        require_ctx!(
+            activation_timestamp > now,
+            VestingError::InvalidActivationTime,
+            activation_timestamp = activation_timestamp,
+            now = now,
+        );
+        check_schedule_terms(config.as_ref(), &terms, activation_timestamp)?;
+        count_new_schedule(
+            config.as_ref(),
+            &mut ctx.accounts.schedule_count,
+            ctx.accounts.authority.key(),
+            ctx.bumps.schedule_count,
+        )?;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority_tokens.to_account_info(),
+                    to: ctx.accounts.staging.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            terms.amount,
+        )?;
+
+        let pending = &mut ctx.accounts.pending;
+        pending.authority = ctx.accounts.authority.key();
+        pending.beneficiary = ctx.accounts.beneficiary.key();
+        pending.mint = ctx.accounts.mint.key();
+        pending.terms = terms;
+        pending.activation_timestamp = activation_timestamp;
+        pending.bump = ctx.bumps.pending;
+
+        msg!("🗓️ Schedule {} pending until {}", terms.schedule_id, activation_timestamp);
+
+        Ok(())
+    }
+
+    /// Permissionless: turn a due pending schedule into a live one, moving the
+    /// staged tokens into its escrow. The payer funds the new accounts; the
+    /// pending account and staging ATA close to the authority, so a schedule
+    /// activates at most once. `allowlist_proof` is only checked when the
+    /// config commits an allowlist root.
+    pub fn activate_schedule(ctx: Context<ActivateSchedule>, allowlist_proof: Vec<[u8; 32]>) -> Result<()> {
+        let config = load_config(&ctx.accounts.config)?;
+        let pending = &ctx.accounts.pending;
+        if let Some(config) = &config {
+            require_allowlisted(config, &pending.beneficiary, &allowlist_proof)?;
+        }
+        let now = Clock::get()?.unix_timestamp;
+        require_ctx!(
+            now >= pending.activation_timestamp,
+            VestingError::ActivationPending,
+            activation_timestamp = pending.activation_timestamp,
+            now = now,
+        );
+
+        let staged = ctx.accounts.staging.amount;
+        with_pending_signer(pending, |signer| {
+            let token_program = ctx.accounts.token_program.to_account_info();
+            token::transfer(
+                CpiContext::new_with_signer(
+                    token_program.clone(),
+                    Transfer {
+                        from: ctx.accounts.staging.to_account_info(),
+                        to: ctx.accounts.vesting_ata.to_account_info(),
+                        authority: pending.to_account_info(),
+                    },
+                    signer
+                ),
+                staged
+            )?;
+            token::close_account(CpiContext::new_with_signer(
+                token_program,
+                CloseAccount {
+                    account: ctx.accounts.staging.to_account_info(),
+                    destination: ctx.accounts.authority.to_account_info(),
+                    authority: pending.to_account_info(),
+                },
+                signer
+            ))
+        })?;
+
+        let terms = pending.terms;
+        let vesting = &mut ctx.accounts.vesting;
+        init_vesting(
+            vesting,
+            &terms,
+            pending.beneficiary,
+            pending.authority,
+            &ctx.accounts.mint,
+            now,
+            ctx.bumps.vesting,
+        );
+
+        if verbose_events(config.as_ref()) {
+            emit!(VestingCreated {
+                category: terms.category,
+                label_hash: terms.label_hash,
+                vesting: vesting.key(),
+                beneficiary: vesting.beneficiary,
+                mint: vesting.mint,
+                amount: terms.amount,
+                unlock_timestamp: terms.unlock_timestamp,
+                terms_hash: terms.terms_hash,
+            });
+        }
+
+        msg!("✅ Pending schedule {} activated with {} staged", terms.schedule_id, staged);
+
+        Ok(())
+    }
+
+    /// The authority withdraws a schedule that has not been activated yet,
+    /// getting the staged tokens, the rent and its cap slot back
+    pub fn cancel_pending_schedule(ctx: Context<CancelPendingSchedule>) -> Result<()> {
+        let pending = &ctx.accounts.pending;
+        let staged = ctx.accounts.staging.amount;
+        with_pending_signer(pending, |signer| {
+            let token_program = ctx.accounts.token_program.to_account_info();
+            token::transfer(
+                CpiContext::new_with_signer(
+                    token_program.clone(),
+                    Transfer {
+                        from: ctx.accounts.staging.to_account_info(),
+                        to: ctx.accounts.authority_tokens.to_account_info(),
+                        authority: pending.to_account_info(),
+                    },
+                    signer
+                ),
+                staged
+            )?;
+            token::close_account(CpiContext::new_with_signer(
+                token_program,
+                CloseAccount {
+                    account: ctx.accounts.staging.to_account_info(),
+                    destination: ctx.accounts.authority.to_account_info(),
+                    authority: pending.to_account_info(),
+                },
+                signer
+            ))
+        })?;
+
+        let schedule_count = &mut ctx.accounts.schedule_count;
+        schedule_count.schedule_count = schedule_count.schedule_count.saturating_sub(1);
+
+        msg!("🗑️ Pending schedule {} cancelled, {} refunded", pending.terms.schedule_id, staged);
+
+        Ok(())
+    }
+
+    /// The authority withdraws a schedule nobody has funded yet, getting the
+    /// rent (the vesting ATA's and bridge marker's too, if created) and its
+    /// cap slot back. Once any tokens have reached the schedule it can only
+    /// run its course. A pending recovery, mint migration or bridge deposit
+    /// must be resolved first, so none is left pointing at a closed schedule.
+    pub fn cancel_unfunded(ctx: Context<CancelUnfunded>) -> Result<()> {
+        release_bridge_inflight(
+            &ctx.accounts.bridge_inflight,
+            &ctx.accounts.authority.to_account_info(),
+            ctx.accounts.vesting.key(),
+        )?;
+
+        let vesting = &mut ctx.accounts.vesting;
+        let vesting_ata = &ctx.accounts.vesting_ata;
+        let created = vesting_ata.owner == &token::ID && !vesting_ata.data_is_empty();
+        let balance = if created {
+            TokenAccount::try_deserialize(&mut &vesting_ata.try_borrow_data()?[..])?.amount
+        } else {
+            0
+        };
+        // A claim already paid out means it was funded, even if drained since
+        require_ctx!(
+            balance == 0 && vesting.withdrawn == 0,
+            VestingError::InsufficientBalance,
+            vesting = vesting.key(),
+            balance = balance,
+            withdrawn = vesting.withdrawn,
+        );
+
+        if created {
+            with_vesting_signer(vesting, |signer| {
+                token::close_account(CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    CloseAccount {
+                        account: vesting_ata.to_account_info(),
+                        destination: ctx.accounts.authority.to_account_info(),
+                        authority: vesting.to_account_info(),
+                    },
+                    signer
+                ))
+            })?;
+        }
+
+        if vesting.coverage.is_some() {
+            clear_coverage(vesting);
+        }
+        let schedule_count = &mut ctx.accounts.schedule_count;
+        schedule_count.schedule_count = schedule_count.schedule_count.saturating_sub(1);
+
+        msg!("🗑️ Unfunded schedule {} cancelled", vesting.schedule_id);
+
+        Ok(())
+    }
+}
+
+/// Require `mirror` to be the bridge consensus program's ConsensusMirror for
+/// `operation_id`, attested by at least BRIDGE_CONSENSUS_THRESHOLD chains.
+/// Decoded by byte offset from Trinity's stable mirror ABI.
+fn check_consensus_mirror(config: &VestingConfig, mirror: &AccountInfo, operation_id: &[u8; 32]) -> Result<()> {
+    let program = config.bridge_consensus_program.ok_or(VestingError::BridgeNotConfigured)?;
+    let (expected, _) = Pubkey::find_program_address(&[b"consensus_mirror", operation_id], &program);
+    require_ctx!(
+        mirror.key() == expected && *mirror.owner == program,
+        VestingError::InvalidConsensusMirror,
+        mirror = mirror.key(),
+        expected = expected,
+    );
+
+    let data = mirror.try_borrow_data()?;
+    let discriminator = hash(b"account:ConsensusMirror").to_bytes();
+    let valid = data.len() >= MIRROR_LEN
+        && data[..8] == discriminator[..8]
+        && data[MIRROR_SCHEMA_VERSION_OFFSET] == CONSENSUS_MIRROR_SCHEMA_VERSION
+        && data[MIRROR_OPERATION_ID_OFFSET..MIRROR_OPERATION_ID_OFFSET + 32] == operation_id[..]
+        && data[MIRROR_CHAIN_BITMAP_OFFSET].count_ones() >= BRIDGE_CONSENSUS_THRESHOLD;
+    require_ctx!(valid, VestingError::InvalidConsensusMirror, mirror = mirror.key());
+    Ok(())
+}
+
+/// Count a new schedule against its authority's cap. Without a config there
+/// is no cap, but schedules are still counted.
+fn count_new_schedule(
+    config: Option<&VestingConfig>,
+    schedule_count: &mut ScheduleCount,
+    authority: Pubkey,
+    bump: u8,
+) -> Result<()> {
+    if let Some(config) = config {
+        require_ctx!(
+            schedule_count.schedule_count < config.max_schedules_per_authority,
+            VestingError::ScheduleLimitReached,
+            authority = authority,
+            schedule_count = schedule_count.schedule_count,
+            max = config.max_schedules_per_authority,
+        );
+    }
+    schedule_count.authority = authority;
+    schedule_count.schedule_count += 1;
+    schedule_count.bump = bump;
+    Ok(())
+}
+
+/// Creation policy for a schedule starting at `start`: its creation, or a
+/// pending schedule's activation
+fn check_schedule_terms(config: Option<&VestingConfig>, terms: &ScheduleTerms, start: i64) -> Result<()> {
+    // A new schedule must start locked: unlocking in the creation second is rejected
+    require_ctx!(
+        !is_unlocked(start, terms.unlock_timestamp),
+        VestingError::InvalidUnlockTime,
+        unlock_timestamp = terms.unlock_timestamp,
+        now = start,
+    );
+    // Fixed for the schedule's life: extend_unlock can never pass it
+    require_ctx!(
+        terms.max_unlock_timestamp >= terms.unlock_timestamp,
+        VestingError::InvalidUnlockTime,
+        unlock_timestamp = terms.unlock_timestamp,
+        max_unlock_timestamp = terms.max_unlock_timestamp,
+    );
+    // Claims must stay open for a while after the latest possible unlock
+    require_ctx!(
+        terms.expiry_sink == Pubkey::default() || terms.claim_deadline > terms.max_unlock_timestamp,
+        VestingError::InvalidSchedule,
+        claim_deadline = terms.claim_deadline,
+        max_unlock_timestamp = terms.max_unlock_timestamp,
+    );
+    require_ctx!(terms.amount > 0, VestingError::InvalidAmount, amount = terms.amount);
+    // Governance policy: the cliff must be real, not an unlock moments after creation
+    let min_cliff_secs = config.map_or(0, |config| config.min_cliff_secs);
+    require_ctx!(
+        terms.unlock_timestamp - start >= min_cliff_secs,
+        VestingError::InvalidSchedule,
+        cliff = terms.unlock_timestamp - start,
+        min_cliff_secs = min_cliff_secs,
+    );
+    Ok(())
+}
+
+/// Fill in a schedule created at `now` as a plain token schedule with a
+/// key beneficiary; `create_vesting` then applies its USD and hashed options
+fn init_vesting(
+    vesting: &mut Vesting,
+    terms: &ScheduleTerms,
+    beneficiary: Pubkey,
+    authority: Pubkey,
+    mint: &Account<Mint>,
+    now: i64,
+    bump: u8,
+) {
+    vesting.beneficiary = beneficiary;
+    vesting.original_beneficiary = beneficiary;
+    vesting.beneficiary_hashed = false;
+    vesting.authority = authority;
+    vesting.mint = mint.key();
+    vesting.original_mint = mint.key();
+    vesting.decimals = mint.decimals;
+    vesting.schedule_id = terms.schedule_id;
+    vesting.nonce = terms.nonce;
+    vesting.category = terms.category;
+    vesting.label_hash = terms.label_hash;
+    vesting.terms_hash = terms.terms_hash;
+    vesting.claim_deadline = terms.claim_deadline;
+    vesting.expiry_sink = terms.expiry_sink;
+    vesting.unlock_timestamp = terms.unlock_timestamp;
+    vesting.max_unlock_timestamp = terms.max_unlock_timestamp;
+    vesting.total_amount = terms.amount;
+    vesting.withdrawn = 0;
+    vesting.last_observed_timestamp = now;
+    vesting.is_paused = false;
+    vesting.paused_until = 0;
+    vesting.deposits_paused = false;
+    vesting.kyc_exempt = false;
+    vesting.usd_terms = None;
+    vesting.usd_claimed = 0;
+    vesting.auto_claim_delegate = Pubkey::default();
+    vesting.auto_claim_max_total = 0;
+    vesting.auto_claimed_total = 0;
+    vesting.coverage = None;
+    vesting.deposit_weighted_start = now;
+    vesting.lock_seconds_accumulated = 0;
+    vesting.bump = bump;
+}
+
+/// Effective time for vesting math: the clock can briefly regress across
+/// leaders, so never go back before a timestamp this schedule already observed
+fn observed_now(vesting: &Vesting, clock: &Clock) -> i64 {
+    clock.unix_timestamp.max(vesting.last_observed_timestamp)
+}
+
+/// The one lock rule every path uses: claims are allowed from exactly
+/// `unlock_timestamp` on (`now == unlock` is unlocked), never before
+pub fn is_unlocked(now: i64, unlock_timestamp: i64) -> bool {
+    now >= unlock_timestamp
+}
+
+/// Amount the beneficiary could withdraw at `now` (the token cap for USD
+/// schedules, whose payout depends on the claim-time price)
+fn claimable_amount(vesting: &Vesting, now: i64) -> u64 {
+    if !is_unlocked(now, vesting.unlock_timestamp) {
+        return 0;
+    }
+    vesting.total_amount.saturating_sub(vesting.withdrawn)
+}
+
+/// The next unlock after `now`: a single-unlock schedule releases all it
+/// has left at `unlock_timestamp`; a USD schedule its next tranche, in
+/// micro-dollars. Zero once nothing is left to unlock. Pauses don't move it.
+fn next_unlock(vesting: &Vesting, now: i64) -> NextUnlock {
+    match vesting.usd_terms {
+        Some(terms) => {
+            let unlocked = if is_unlocked(now, vesting.unlock_timestamp) {
+                (now - vesting.unlock_timestamp) / USD_TRANCHE_INTERVAL + 1
+            } else {
+                0
+            };
+            if unlocked >= terms.tranches as i64 {
+                return NextUnlock::default();
+            }
+            NextUnlock {
+                next_ts: vesting.unlock_timestamp + unlocked * USD_TRANCHE_INTERVAL,
+                next_amount: terms.usd_per_tranche,
+            }
+        }
+        None if !is_unlocked(now, vesting.unlock_timestamp) => NextUnlock {
+            next_ts: vesting.unlock_timestamp,
+            next_amount: vesting.total_amount.saturating_sub(vesting.withdrawn),
+        },
+        None => NextUnlock::default(),
+    }
+}
+
+/// Circuit breaker, pause, time-lock, signer and balance checks shared by
+/// every withdrawal path
+fn check_withdrawal(
+    vesting: &mut Account<Vesting>,
+    config: Option<&VestingConfig>,
+    beneficiary: &Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let now = observed_now(vesting, &Clock::get()?);
+    vesting.last_observed_timestamp = now;
+
+    let circuit_breaker_until = config.map_or(0, |config| config.circuit_breaker_until);
+    require_ctx!(
+        now >= circuit_breaker_until,
+        VestingError::CircuitBreakerTripped,
+        circuit_breaker_until = circuit_breaker_until,
+        now = now,
+    );
+    expire_pause(vesting, now);
+    require_ctx!(
+        !vesting.is_paused,
+        VestingError::Paused,
+        vesting = vesting.key(),
+        paused_until = vesting.paused_until,
+    );
+
+    // CRITICAL: Enforce time-lock
+    require_ctx!(
+        is_unlocked(now, vesting.unlock_timestamp),
+        VestingError::StillLocked,
+        unlock_timestamp = vesting.unlock_timestamp,
+        now = now,
+    );
+
+    require_ctx!(
+        *beneficiary == vesting.beneficiary,
+        VestingError::Unauthorized,
+        signer = beneficiary,
+        beneficiary = vesting.beneficiary,
+    );
+
+    let available = vesting.total_amount.checked_sub(vesting.withdrawn)
+        .ok_or(VestingError::Overflow)?;
+    // Report the claimable amount (Borsh u64, like the views) with the
+    // failure so a client can retry for the right amount without a query
+    if amount > available {
+        set_return_data(&available.to_le_bytes());
+    }
+    require_ctx!(
+        amount <= available,
+        VestingError::InsufficientBalance,
+        requested = amount,
+        available = available,
+    );
+    Ok(())
+}
+
+/// Shared body of `withdraw` and `withdraw_hashed`; `claimant` is the
+/// already-verified key to match against `vesting.beneficiary`
+fn withdraw_tokens(ctx: Context<Withdraw>, claimant: Pubkey, amount: u64) -> Result<()> {
+    let config = load_config(&ctx.accounts.config)?;
+    let vesting = &mut ctx.accounts.vesting;
+    let (amount, usd) = usd_payout(
+        vesting,
+        config.as_ref(),
+        ctx.accounts.price_oracle.as_deref(),
+        amount,
+        ctx.accounts.vesting_ata.amount,
+    )?;
+    check_withdrawal(vesting, config.as_ref(), &claimant, amount)?;
+    require_kyc(config.as_ref(), vesting, ctx.accounts.kyc_attestation.as_deref())?;
+    let (explicit, beneficiary_ata) = (ctx.accounts.destination.as_ref(), ctx.accounts.beneficiary_ata.as_ref());
+    require_ctx!(
+        explicit.is_some() != beneficiary_ata.is_some(),
+        VestingError::ClaimDestinationRequired,
+        destination = explicit.is_some(),
+        beneficiary_ata = beneficiary_ata.is_some(),
+    );
+    let destination = explicit.or(beneficiary_ata).unwrap();
+    require_schedule_mint(vesting, destination)?;
+    check_claim_destination(
+        config.as_ref(),
+        &ctx.accounts.beneficiary,
+        destination,
+        ctx.accounts.destination_owner.as_deref(),
+        ctx.accounts.ownership_attestation.as_deref(),
+    )?;
+
+    // Transfer using PDA signer
+    with_vesting_signer(vesting, |signer| {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vesting_ata.to_account_info(),
+                    to: destination.to_account_info(),
+                    authority: vesting.to_account_info(),
+                },
+                signer
+            ),
+            amount
+        )
+    })?;
+
+    record_withdrawal(vesting, amount, usd, config.as_ref())?;
+
+    msg!("✅ Withdrawn {} tokens", amount);
+
+    Ok(())
+}
+
+/// Announce a recovery the beneficiary objected to; the context closes it
+fn cancel_recovery(vesting: &Account<Vesting>, beneficiary: Pubkey) {
+    emit!(BeneficiaryRecoveryCancelled {
+        category: vesting.category,
+        label_hash: vesting.label_hash,
+        vesting: vesting.key(),
+        beneficiary,
+    });
+
+    if beneficiary == Pubkey::default() {
+        msg!("⛔ Beneficiary recovery discarded: guardians rotated since it was proposed");
+    } else {
+        msg!("⛔ Beneficiary recovery cancelled by beneficiary");
+    }
+}
+
+/// keccak(beneficiary || salt): the key a hashed-beneficiary schedule stores
+/// (and is seeded with) instead of the beneficiary's own
+pub fn beneficiary_hash(beneficiary: &Pubkey, salt: &[u8; 32]) -> Pubkey {
+    Pubkey::new_from_array(hashv(&[beneficiary.as_ref(), salt]).0)
+}
+
+/// keccak(recipient || amount LE || salt): the operation ID of a bridge-in
+/// funding a schedule, so the mirrored ID itself names where the tokens go
+/// and how many
+pub fn bridge_operation_id(recipient: &Pubkey, amount: u64, salt: &[u8; 32]) -> [u8; 32] {
+    hashv(&[recipient.as_ref(), &amount.to_le_bytes(), salt]).0
+}
+
+/// Book a completed withdrawal (`usd` micro-dollars for USD schedules) and announce it
+fn record_withdrawal(
+    vesting: &mut Account<Vesting>,
+    amount: u64,
+    usd: u64,
+    config: Option<&VestingConfig>,
+) -> Result<()> {
+    vesting.withdrawn = vesting.withdrawn.checked_add(amount)
+        .ok_or(VestingError::Overflow)?;
+    vesting.usd_claimed = vesting.usd_claimed.checked_add(usd)
+        .ok_or(VestingError::Overflow)?;
+    let now = observed_now(vesting, &Clock::get()?);
+    // Analytics only: saturates rather than blocking a claim
+    vesting.lock_seconds_accumulated = vesting.lock_seconds_accumulated
+        .saturating_add(lock_token_seconds(amount, vesting.deposit_weighted_start, now));
+    expire_coverage(vesting, now);
+
+    if verbose_events(config) {
+        emit!(TokensWithdrawn {
+            category: vesting.category,
+            label_hash: vesting.label_hash,
+            vesting: vesting.key(),
+            beneficiary: vesting.beneficiary,
+            amount,
+            withdrawn: vesting.withdrawn,
+            policy_id: covering_policy(vesting, now),
+        });
+    }
+    Ok(())
+}
+
+/// Whether a full pause blocks the schedule at `now` (pauses lapse at `paused_until`)
+fn pause_in_force(vesting: &Vesting, now: i64) -> bool {
+    vesting.is_paused && now < vesting.paused_until
+}
+
+/// First invariant `vesting` (stored at `address`) breaks, in
+/// ScheduleInvariant order, given its escrow (None if never created) and its
+/// mint's decimals
+fn first_violation(
+    vesting: &Vesting,
+    address: &Pubkey,
+    escrow: Option<&TokenAccount>,
+    mint_decimals: u8,
+) -> Option<ScheduleInvariant> {
+    let balance = escrow.map_or(0, |escrow| escrow.amount);
+    let (expected_address, canonical_bump) = Pubkey::find_program_address(
+        &[
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes(),
+        ],
+        &crate::ID,
+    );
+    let escrow_matches = escrow.is_none_or(|escrow| escrow.mint == vesting.mint && escrow.owner == *address);
+
+    if vesting.withdrawn > vesting.total_amount {
+        Some(ScheduleInvariant::WithdrawnWithinTotal)
+    } else if balance < vesting.total_amount - vesting.withdrawn {
+        Some(ScheduleInvariant::EscrowCoversRemaining)
+    } else if expected_address != *address || canonical_bump != vesting.bump {
+        Some(ScheduleInvariant::CanonicalBump)
+    } else if !escrow_matches || mint_decimals != vesting.decimals {
+        Some(ScheduleInvariant::MintConsistent)
+    } else {
+        None
+    }
+}
+
+/// What the views report as claimable at `now`: nothing while a pause is in force
+fn claimable_now(vesting: &Vesting, now: i64) -> u64 {
+    if pause_in_force(vesting, now) {
+        0
+    } else {
+        claimable_amount(vesting, now)
+    }
+}
+
+/// Lift a full pause that has lapsed by `now`; nothing unpauses on its own,
+/// so the schedule's next claim (or pause change) does it and tells indexers
+fn expire_pause(vesting: &mut Account<Vesting>, now: i64) {
+    if vesting.is_paused && !pause_in_force(vesting, now) {
+        vesting.is_paused = false;
+        emit!(PauseExpired {
+            category: vesting.category,
+            label_hash: vesting.label_hash,
+            vesting: vesting.key(),
+            paused_until: vesting.paused_until,
+        });
+    }
+}
+
+/// Policy id covering the schedule at `now`, or 0 when uncovered
+fn covering_policy(vesting: &Vesting, now: i64) -> u64 {
+    match vesting.coverage {
+        Some(coverage) if now < coverage.expiry => coverage.policy_id,
+        _ => 0,
+    }
+}
+
+/// Drop coverage that has lapsed by `now`
+fn expire_coverage(vesting: &mut Account<Vesting>, now: i64) {
+    if vesting.coverage.is_some_and(|coverage| now >= coverage.expiry) {
+        clear_coverage(vesting);
+    }
+}
+
+/// Remove the schedule's coverage and tell the insurer's indexer
+fn clear_coverage(vesting: &mut Account<Vesting>) {
+    let Some(coverage) = vesting.coverage.take() else {
+        return;
+    };
+    emit!(CoverageCleared {
+        category: vesting.category,
+        label_hash: vesting.label_hash,
+        vesting: vesting.key(),
+        coverage_program: coverage.coverage_program,
+        policy_id: coverage.policy_id,
+        expiry: coverage.expiry,
+    });
+}
+
+/// Tokens a claim pays and the micro-dollars it uses up. Token-denominated
+/// schedules pay `requested` as-is. USD schedules convert their unclaimed USD
+/// at the oracle price, capped by `requested` and the escrow balance; before
+/// unlock `requested` passes through so `check_withdrawal` reports StillLocked.
+fn usd_payout(
+    vesting: &Vesting,
+    config: Option<&VestingConfig>,
+    oracle: Option<&AccountInfo>,
+    requested: u64,
+    escrow_balance: u64,
+) -> Result<(u64, u64)> {
+    let Some(terms) = vesting.usd_terms else {
+        return Ok((requested, 0));
+    };
+    let now = observed_now(vesting, &Clock::get()?);
+    if !is_unlocked(now, vesting.unlock_timestamp) {
+        return Ok((requested, 0));
+    }
+    let price = oracle_price(config, &terms, oracle, now)?;
+
+    let tranches = ((now - vesting.unlock_timestamp) / USD_TRANCHE_INTERVAL + 1).min(terms.tranches as i64);
+    let vested_usd = terms.usd_per_tranche.checked_mul(tranches as u64)
+        .ok_or(VestingError::Overflow)?;
+    let claimable_usd = vested_usd.saturating_sub(vesting.usd_claimed);
+
+    let amount = usd_to_tokens(claimable_usd, price.price as u64, price.expo, vesting.decimals)
+        .min(requested)
+        .min(escrow_balance);
+    require_ctx!(
+        amount > 0,
+        VestingError::InsufficientBalance,
+        claimable_usd = claimable_usd,
+        requested = requested,
+        escrow_balance = escrow_balance,
+    );
+    let usd = tokens_to_usd(amount, price.price as u64, price.expo, vesting.decimals)
+        .ok_or(VestingError::Overflow)?
+        .min(claimable_usd);
+    Ok((amount, usd))
+}
+
+/// Validated oracle price for a USD schedule claim. Every failure blocks the
+/// claim; there is no fallback price.
+fn oracle_price(
+    config: Option<&VestingConfig>,
+    terms: &UsdTerms,
+    oracle: Option<&AccountInfo>,
+    now: i64,
+) -> Result<PythPrice> {
+    let policy = config.and_then(|config| config.oracle_program.map(|program| (config, program)));
+    require_ctx!(policy.is_some(), VestingError::OracleUnavailable, price_oracle = terms.price_oracle);
+    let (config, oracle_program) = policy.unwrap();
+    require_ctx!(oracle.is_some(), VestingError::OracleUnavailable, price_oracle = terms.price_oracle);
+    let oracle = oracle.unwrap();
+    require_ctx!(
+        oracle.key == &terms.price_oracle && oracle.owner == &oracle_program,
+        VestingError::OracleInvalid,
+        oracle = oracle.key,
+        owner = oracle.owner,
+        expected = terms.price_oracle,
+    );
+
+    let price = PythPrice::read(oracle);
+    require_ctx!(price.is_some(), VestingError::OracleInvalid, oracle = oracle.key);
+    let price = price.unwrap();
+    require_ctx!(
+        price.status == PythPrice::STATUS_TRADING,
+        VestingError::OracleUnavailable,
+        oracle = oracle.key,
+        status = price.status,
+    );
+    require_ctx!(price.price > 0, VestingError::OracleInvalid, oracle = oracle.key, price = price.price);
+    require_ctx!(
+        now - price.publish_time <= config.oracle_max_staleness_secs,
+        VestingError::OracleStale,
+        publish_time = price.publish_time,
+        now = now,
+        max_staleness_secs = config.oracle_max_staleness_secs,
+    );
+    // conf / price <= max_conf_bps / 10_000
+    require_ctx!(
+        price.conf as u128 * 10_000 <= price.price as u128 * config.oracle_max_conf_bps as u128,
+        VestingError::OracleConfidenceTooWide,
+        conf = price.conf,
+        price = price.price,
+        max_conf_bps = config.oracle_max_conf_bps,
+    );
+    Ok(price)
+}
+
+/// Exponent `k` such that one base unit of the token is worth `price * 10^k`
+/// micro-dollars
+fn unit_scale(expo: i32, decimals: u8) -> i32 {
+    expo + USD_DECIMALS - decimals as i32
+}
+
+/// Base units worth `usd` micro-dollars at `price * 10^expo` dollars per whole
+/// token, rounded down (u128 math; saturates at u64::MAX). A zero price buys nothing.
+pub fn usd_to_tokens(usd: u64, price: u64, expo: i32, decimals: u8) -> u64 {
+    if usd == 0 || price == 0 {
+        return 0;
+    }
+    let k = unit_scale(expo, decimals);
+    let tokens = if k >= 0 {
+        // A denominator past u128 means a single base unit outprices any u64 amount
+        match 10u128.checked_pow(k as u32).and_then(|scale| scale.checked_mul(price as u128)) {
+            Some(unit_value) => usd as u128 / unit_value,
+            None => 0,
+        }
+    } else {
+        // A numerator past u128 means the quotient exceeds u64 (price < 2^64)
+        match 10u128.checked_pow(k.unsigned_abs()).and_then(|scale| scale.checked_mul(usd as u128)) {
+            Some(scaled) => scaled / price as u128,
+            None => u128::MAX,
+        }
+    };
+    u64::try_from(tokens).unwrap_or(u64::MAX)
+}
+
+/// `amount` at `numerator / denominator` (a mint migration ratio), rounded
+/// down with u128 math
+pub fn convert_amount(amount: u64, numerator: u64, denominator: u64) -> Result<u64> {
+    let converted = (amount as u128 * numerator as u128) / denominator as u128;
+    u64::try_from(converted).map_err(|_| error!(VestingError::Overflow))
+}
+
+/// `convert_amount` for token-seconds: split so the product can't overflow
+/// u128 unless the result does, and saturating then
+pub fn convert_token_seconds(token_seconds: u128, numerator: u64, denominator: u64) -> u128 {
+    let (numerator, denominator) = (numerator as u128, denominator as u128);
+    (token_seconds / denominator)
+        .saturating_mul(numerator)
+        .saturating_add(token_seconds % denominator * numerator / denominator)
+}
+
+/// Token-seconds `amount` base units spent locked from `since` to `until`
+/// (none if `until` is earlier). u128: u64 amounts times i64 seconds can't
+/// overflow it.
+pub fn lock_token_seconds(amount: u64, since: i64, until: i64) -> u128 {
+    amount as u128 * until.saturating_sub(since).max(0) as u128
+}
+
+/// Realized lock duration per claimed token; 0 before the first claim
+pub fn average_lock_seconds(lock_seconds: u128, withdrawn: u64) -> u64 {
+    if withdrawn == 0 {
+        return 0;
+    }
+    u64::try_from(lock_seconds / withdrawn as u128).unwrap_or(u64::MAX)
+}
+
+/// Lock start of an escrow that held `balance` tokens locked on average since
+/// `start`, once `deposit` more arrive at `now`: the balance-weighted mean,
+/// rounded down. An empty escrow starts over at `now`.
+pub fn weighted_lock_start(start: i64, balance: u64, deposit: u64, now: i64) -> i64 {
+    if balance == 0 {
+        return now;
+    }
+    let total = balance as i128 + deposit as i128;
+    ((start as i128 * balance as i128 + now as i128 * deposit as i128) / total) as i64
+}
+
+/// Micro-dollars `tokens` base units are worth, rounded up so a claim never
+/// uses up less USD than it pays out. None if the value exceeds u64.
+pub fn tokens_to_usd(tokens: u64, price: u64, expo: i32, decimals: u8) -> Option<u64> {
+    let value = tokens as u128 * price as u128;
+    let k = unit_scale(expo, decimals);
+    let usd = if k >= 0 {
+        value.checked_mul(10u128.checked_pow(k as u32)?)?
+    } else {
+        match 10u128.checked_pow(k.unsigned_abs()) {
+            Some(scale) => value.div_ceil(scale),
+            None => u128::from(value > 0),
+        }
+    };
+    u64::try_from(usd).ok()
+}
+
+/// Run `f` with the pending schedule PDA's signer seeds (it owns the staging ATA)
+fn with_pending_signer<R>(pending: &PendingSchedule, f: impl FnOnce(&[&[&[u8]]]) -> R) -> R {
+    let schedule_id = pending.terms.schedule_id.to_le_bytes();
+    let nonce = pending.terms.nonce.to_le_bytes();
+    let bump = [pending.bump];
+    let seeds: &[&[u8]] = &[
+        b"pending_schedule",
+        pending.beneficiary.as_ref(),
+        pending.mint.as_ref(),
+        &schedule_id,
+        &nonce,
+        &bump,
+    ];
+    f(&[seeds])
+}
+
+/// Whether a schedule's BridgeInflight marker (possibly never created) has
+/// an open deposit
+fn bridge_deposit_open(marker: &AccountInfo) -> Result<bool> {
+    if marker.owner != &crate::ID || marker.data_is_empty() {
+        return Ok(false);
+    }
+    let inflight = BridgeInflight::try_deserialize(&mut &marker.try_borrow_data()?[..])?;
+    Ok(inflight.operation_id.is_some())
+}
+
+/// Before a schedule closes: fail if its BridgeInflight marker has an open
+/// deposit, otherwise close the marker (if ever created) to `rent_to`
+fn release_bridge_inflight<'info>(marker: &AccountInfo<'info>, rent_to: &AccountInfo<'info>, vesting: Pubkey) -> Result<()> {
+    require_ctx!(!bridge_deposit_open(marker)?, VestingError::BridgeDepositInFlight, vesting = vesting);
+    if marker.owner != &crate::ID || marker.data_is_empty() {
+        return Ok(());
+    }
+
+    let rent = marker.lamports();
+    **rent_to.try_borrow_mut_lamports()? = rent_to.lamports().checked_add(rent).ok_or(VestingError::Overflow)?;
+    **marker.try_borrow_mut_lamports()? = 0;
+    marker.assign(&System::id());
+    marker.realloc(0, false)?;
+    Ok(())
+}
+
+/// Run `f` with the schedule PDA's signer seeds (it owns the vesting ATA)
+fn with_vesting_signer<R>(vesting: &Vesting, f: impl FnOnce(&[&[&[u8]]]) -> R) -> R {
+    let schedule_id = vesting.schedule_id.to_le_bytes();
+    let nonce = vesting.nonce.to_le_bytes();
+    let bump = [vesting.bump];
+    let seeds: &[&[u8]] = &[
+        b"vesting",
+        vesting.original_beneficiary.as_ref(),
+        vesting.original_mint.as_ref(),
+        &schedule_id,
+        &nonce,
+        &bump,
+    ];
+    f(&[seeds])
+}
+
+/// Routine events are on unless the config turned them off; schedules used
+/// without a config (no account passed) keep the default
+fn verbose_events(config: Option<&VestingConfig>) -> bool {
+    config.map(|config| config.verbose_events).unwrap_or(true)
+}
+
+/// The config, or None if it was never initialized (the address is pinned by
+/// the accounts constraint, so it can't be swapped to skip the allowlist)
+fn load_config(info: &AccountInfo) -> Result<Option<VestingConfig>> {
+    if info.owner != &crate::ID {
+        return Ok(None);
+    }
+    Ok(Some(VestingConfig::try_deserialize(&mut &info.try_borrow_data()?[..])?))
+}
+
+/// With an allowlist root set, `proof` must fold keccak(beneficiary) into it
+fn require_allowlisted(config: &VestingConfig, beneficiary: &Pubkey, proof: &[[u8; 32]]) -> Result<()> {
+    if config.allowlist_root == [0u8; 32] {
+        return Ok(());
+    }
+    require_ctx!(
+        proof.len() <= MAX_ALLOWLIST_PROOF_LEN,
+        VestingError::NotAllowlisted,
+        beneficiary = beneficiary,
+        proof_len = proof.len(),
+        max = MAX_ALLOWLIST_PROOF_LEN,
+    );
+    let leaf = hashv(&[beneficiary.as_ref()]).0;
+    require_ctx!(
+        merkle::calculate_merkle_root(proof, &leaf) == config.allowlist_root,
+        VestingError::NotAllowlisted,
+        beneficiary = beneficiary,
+        proof_len = proof.len(),
+    );
+    Ok(())
+}
+
+/// A claim pays the beneficiary's own ATA, a token account the beneficiary's
+/// own program attests is the beneficiary's (see `check_ownership_attestation`),
+/// or a token account whose owner is `destination_owner`: an account held by
+/// one of the config's destination programs (in practice one of its PDAs,
+/// e.g. a staking vault)
+fn check_claim_destination(
+    config: Option<&VestingConfig>,
+    beneficiary: &AccountInfo,
+    destination: &Account<TokenAccount>,
+    destination_owner: Option<&AccountInfo>,
+    ownership_attestation: Option<&AccountInfo>,
+) -> Result<()> {
+    if destination.owner == *beneficiary.key
+        && destination.key() == get_associated_token_address(beneficiary.key, &destination.mint)
+    {
+        return Ok(());
+    }
+    if let Some(attestation) = ownership_attestation {
+        return check_ownership_attestation(beneficiary, destination, attestation);
+    }
+    let owner_program = destination_owner
+        .filter(|owner| *owner.key == destination.owner)
+        .map(|owner| *owner.owner);
+    require_ctx!(
+        owner_program.is_some_and(|program| {
+            config.is_some_and(|config| config.destination_programs.contains(&program))
+        }),
+        VestingError::ClaimDestinationNotAllowed,
+        destination = destination.key(),
+        owner = destination.owner,
+        owner_program = owner_program.unwrap_or_default(),
+    );
+
+    Ok(())
+}
+
+/// A program-owned beneficiary's own program vouches for `destination`: the
+/// attestation must sit at that program's [b"ownership_attestation",
+/// destination] PDA, be owned by it, and bind the destination to the
+/// beneficiary. Attestations from any other program are spoofs.
+fn check_ownership_attestation(
+    beneficiary: &AccountInfo,
+    destination: &Account<TokenAccount>,
+    attestation: &AccountInfo,
+) -> Result<()> {
+    let program = *beneficiary.owner;
+    let (expected, _) = Pubkey::find_program_address(&[OwnershipAttestation::SEED, destination.key().as_ref()], &program);
+    let valid = program != System::id()
+        && *attestation.owner == program
+        && attestation.key() == expected
+        && OwnershipAttestation::read(attestation).is_some_and(|record| {
+            record.beneficiary == beneficiary.key() && record.token_account == destination.key()
+        });
+    require_ctx!(
+        valid,
+        VestingError::InvalidOwnershipAttestation,
+        attestation = attestation.key(),
+        attestation_owner = attestation.owner,
+        beneficiary_owner = program,
+        destination = destination.key(),
+    );
+
+    Ok(())
+}
+
+/// Tokens only move between the escrow and accounts of the schedule's own
+/// mint. The account constraints already bind the mint; this keeps the rule
+/// in the handler too, so it survives a context refactor
+fn require_schedule_mint(vesting: &Vesting, account: &Account<TokenAccount>) -> Result<()> {
+    require_ctx!(
+        account.mint == vesting.mint,
+        VestingError::TokenMintMismatch,
+        account = account.key(),
+        mint = account.mint,
+        expected = vesting.mint,
+    );
+
+    Ok(())
+}
+
+/// With a KYC program configured (and the schedule not exempt), the claim
+/// must carry an unexpired attestation of the beneficiary owned by that program
+fn require_kyc(config: Option<&VestingConfig>, vesting: &Vesting, attestation: Option<&AccountInfo>) -> Result<()> {
+    let kyc_program = match config.and_then(|config| config.kyc_program) {
+        Some(kyc_program) if !vesting.kyc_exempt => kyc_program,
+        _ => return Ok(()),
+    };
+    require_ctx!(attestation.is_some(), VestingError::KycRequired, beneficiary = vesting.beneficiary);
+    let attestation = attestation.unwrap();
+    require_ctx!(
+        attestation.owner == &kyc_program,
+        VestingError::KycAttestationInvalid,
+        attestation = attestation.key,
+        owner = attestation.owner,
+        expected = kyc_program,
+    );
+
+    let record = KycAttestation::read(attestation).filter(|record| record.subject == vesting.beneficiary);
+    require_ctx!(
+        record.is_some(),
+        VestingError::KycAttestationInvalid,
+        attestation = attestation.key,
+        beneficiary = vesting.beneficiary,
+    );
+    let record = record.unwrap();
+
+    let now = Clock::get()?.unix_timestamp;
+    require_ctx!(
+        record.expiry > now,
+        VestingError::KycExpired,
+        attestation = attestation.key,
+        expiry = record.expiry,
+        now = now,
+    );
+    Ok(())
+}
+
+/// keccak of the guardian keys in order: how guardian events identify a set
+pub fn guardian_set_hash(guardians: &[Pubkey]) -> [u8; 32] {
+    let keys: Vec<&[u8]> = guardians.iter().map(|guardian| guardian.as_ref()).collect();
+    hashv(&keys).0
+}
+
+/// Guardian sets must be non-empty, bounded, duplicate-free, with 1 <= threshold <= len
+fn validate_guardian_set(guardians: &[Pubkey], threshold: u8) -> Result<()> {
+    require_ctx!(
+        !guardians.is_empty() && guardians.len() <= MAX_GUARDIANS,
+        VestingError::InvalidGuardianSet,
+        guardians = guardians.len(),
+        max = MAX_GUARDIANS,
+    );
+    require_ctx!(
+        threshold > 0 && threshold as usize <= guardians.len(),
+        VestingError::InvalidGuardianSet,
+        threshold = threshold,
+        guardians = guardians.len(),
+    );
+    for (i, guardian) in guardians.iter().enumerate() {
+        require_ctx!(
+            !guardians[i + 1..].contains(guardian),
+            VestingError::InvalidGuardianSet,
+            duplicate = guardian,
+        );
+    }
+    Ok(())
+}
+
+/// Count distinct guardian signers among the (signing) proposer and remaining accounts
+fn count_guardian_approvals(
+    config: &VestingConfig,
+    proposer: &Pubkey,
+    remaining: &[AccountInfo],
+) -> u8 {
+    let mut seen: Vec<Pubkey> = Vec::with_capacity(MAX_GUARDIANS);
+    if config.guardians.contains(proposer) {
+        seen.push(*proposer);
+    }
+    for info in remaining.iter().filter(|info| info.is_signer) {
+        if config.guardians.contains(info.key) && !seen.contains(info.key) {
+            seen.push(*info.key);
+        }
+    }
+    seen.len() as u8
+}
+
+#[derive(Accounts)]
+#[instruction(schedule_id: u64, nonce: u64)]
+pub struct CreateVesting<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Vesting::INIT_SPACE,
+        seeds = [
+            b"vesting",
+            beneficiary.key().as_ref(),
+            mint.key().as_ref(),
+            &schedule_id.to_le_bytes(),
+            &nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    pub vesting: Account<'info, Vesting>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    /// CHECK: Beneficiary address
+    pub beneficiary: UncheckedAccount<'info>,
+    
+    /// The schedule's authority
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Config PDA, always passed since it may gate creation; read only
+    /// if initialized (event verbosity, allowlist, minimum cliff, schedule cap)
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + ScheduleCount::INIT_SPACE,
+        seeds = [b"schedule_count", payer.key().as_ref()],
+        bump
+    )]
+    pub schedule_count: Account<'info, ScheduleCount>,
+
+    /// CHECK: Pending schedule at the same seeds, always passed; while one is
+    /// registered its address is reserved for activate_schedule
+    #[account(
+        seeds = [
+            b"pending_schedule",
+            beneficiary.key().as_ref(),
+            mint.key().as_ref(),
+            &schedule_id.to_le_bytes(),
+            &nonce.to_le_bytes()
+        ],
+        bump,
+        constraint = pending.data_is_empty() @ VestingError::ScheduleReserved
+    )]
+    pub pending: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump,
+        // Hashed-beneficiary schedules store a hash here; withdraw_hashed checks the signer
+        constraint = vesting.beneficiary_hashed || vesting.beneficiary == beneficiary.key()
+            @ VestingError::Unauthorized,
+        has_one = mint
+    )]
+    pub vesting: Account<'info, Vesting>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting
+    )]
+    pub vesting_ata: Account<'info, TokenAccount>,
+    
+    /// Default claim destination, created at the beneficiary's expense if
+    /// missing; omitted when claiming to `destination`
+    #[account(
+        init_if_needed,
+        payer = beneficiary,
+        associated_token::mint = mint,
+        associated_token::authority = beneficiary
+    )]
+    pub beneficiary_ata: Option<Account<'info, TokenAccount>>,
+    
+    /// Explicit claim destination: a token account owned by
+    /// `destination_owner` (validated in `check_claim_destination`)
+    #[account(mut, token::mint = mint)]
+    pub destination: Option<Account<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Config PDA, always passed since it may gate claims; read only
+    /// if initialized (event verbosity, KYC gate, destination programs)
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: KYC attestation, validated in `require_kyc` when the config sets a KYC program
+    pub kyc_attestation: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Pyth price account for USD schedules, validated in `oracle_price`
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: PDA owning a non-ATA destination, validated in `check_claim_destination`
+    pub destination_owner: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: The beneficiary program's statement that `destination` is the
+    /// beneficiary's, validated in `check_ownership_attestation`
+    pub ownership_attestation: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSponsored<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump,
+        constraint = vesting.beneficiary_hashed || vesting.beneficiary == beneficiary.key()
+            @ VestingError::Unauthorized,
+        has_one = mint
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting
+    )]
+    pub vesting_ata: Account<'info, TokenAccount>,
+
+    /// Claim destination, recreated at the payer's expense if missing
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = beneficiary
+    )]
+    pub beneficiary_ata: Account<'info, TokenAccount>,
+
+    /// Signs but pays nothing, so a PDA signing via CPI works here
+    pub beneficiary: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Config PDA, always passed since it may gate claims; read only
+    /// if initialized (event verbosity, KYC gate)
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: KYC attestation, validated in `require_kyc` when the config sets a KYC program
+    pub kyc_attestation: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Pyth price account for USD schedules, validated in `oracle_price`
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVesting<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump,
+        has_one = beneficiary,
+        has_one = mint,
+        close = beneficiary
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    /// Mutable so dust can be burned
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting
+    )]
+    pub vesting_ata: Account<'info, TokenAccount>,
+
+    /// CHECK: The beneficiary's ATA address; it may have been closed, in which
+    /// case dust is burned instead
+    #[account(mut, address = get_associated_token_address(&beneficiary.key(), &mint.key()))]
+    pub beneficiary_ata: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+
+    /// CHECK: Config PDA, always passed since it sets the dust threshold; read
+    /// only if initialized
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    /// Frees a slot under the schedule authority's cap
+    #[account(
+        mut,
+        seeds = [b"schedule_count", vesting.authority.as_ref()],
+        bump = schedule_count.bump
+    )]
+    pub schedule_count: Account<'info, ScheduleCount>,
+
+    /// CHECK: The schedule's recovery proposal address; must hold none
+    #[account(
+        seeds = [b"recovery", vesting.key().as_ref()],
+        bump,
+        constraint = recovery.data_is_empty() @ VestingError::ScheduleProposalPending
+    )]
+    pub recovery: UncheckedAccount<'info>,
+
+    /// CHECK: The schedule's mint migration address; must hold none (a
+    /// pending one escrows the new mint's deposit)
+    #[account(
+        seeds = [b"mint_migration", vesting.key().as_ref()],
+        bump,
+        constraint = mint_migration.data_is_empty() @ VestingError::ScheduleProposalPending
+    )]
+    pub mint_migration: UncheckedAccount<'info>,
+
+    /// CHECK: The schedule's BridgeInflight address; if created, it must have
+    /// no open deposit and is closed along with the schedule
+    #[account(mut, seeds = [b"bridge_inflight", vesting.key().as_ref()], bump)]
+    pub bridge_inflight: UncheckedAccount<'info>,
+
+    /// CHECK: The schedule's authority, refunded the bridge marker's rent
+    #[account(mut, address = vesting.authority)]
+    pub authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AutoClaim<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump,
+        has_one = mint
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting
+    )]
+    pub vesting_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting.beneficiary
+    )]
+    pub beneficiary_ata: Account<'info, TokenAccount>,
+
+    /// Checked against `vesting.auto_claim_delegate` in the handler
+    pub delegate: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Config PDA, always passed since it may gate claims; read only
+    /// if initialized (event verbosity, KYC gate)
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: KYC attestation, validated in `require_kyc` when the config sets a KYC program
+    pub kyc_attestation: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Pyth price account for USD schedules, validated in `oracle_price`
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawNative<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump,
+        has_one = beneficiary,
+        has_one = mint
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting
+    )]
+    pub vesting_ata: Account<'info, TokenAccount>,
+
+    /// Temporary wSOL account, closed before the instruction returns
+    #[account(
+        init,
+        payer = beneficiary,
+        seeds = [b"unwrap", vesting.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vesting
+    )]
+    pub unwrap_account: Account<'info, TokenAccount>,
+
+    /// Receives the claim as lamports
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Config PDA, always passed since it may gate claims; read only
+    /// if initialized (event verbosity, KYC gate)
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: KYC attestation, validated in `require_kyc` when the config sets a KYC program
+    pub kyc_attestation: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Pyth price account for USD schedules, validated in `oracle_price`
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct DepositTokens<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    #[account(address = vesting.mint)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vesting
+    )]
+    pub vesting_ata: Account<'info, TokenAccount>,
+
+    #[account(mut, token::mint = mint, token::authority = depositor)]
+    pub depositor_ata: Account<'info, TokenAccount>,
+
+    pub depositor: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+
+    /// CHECK: Config PDA, always passed; read only if initialized (event verbosity)
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: The schedule's BridgeInflight PDA; read only if initialized
+    #[account(seeds = [b"bridge_inflight", vesting.key().as_ref()], bump)]
+    pub bridge_inflight: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    /// Mutable to track re-pauses
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, VestingConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetKycExempt<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = compliance_authority)]
+    pub config: Account<'info, VestingConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    pub compliance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAutoClaim<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump,
+        has_one = beneficiary
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    pub beneficiary: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterCoverage<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, VestingConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump,
+        has_one = beneficiary
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    pub beneficiary: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCategory<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, VestingConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, VestingConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ViewConfig<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, VestingConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ViewVesting<'info> {
+    #[account(
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump
+    )]
+    pub vesting: Account<'info, Vesting>,
+}
+
+#[derive(Accounts)]
+pub struct ViewBalance<'info> {
+    #[account(
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump,
+        has_one = mint
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(associated_token::mint = mint, associated_token::authority = vesting)]
+    pub vesting_ata: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct AuditSchedule<'info> {
+    /// Any schedule of this program; its PDA and bump are audited, not enforced
+    pub vesting: Account<'info, Vesting>,
+
+    #[account(address = vesting.mint)]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: The vesting ATA's address; it may never have been created
+    #[account(address = get_associated_token_address(&vesting.key(), &vesting.mint))]
+    pub vesting_ata: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AggregateBeneficiary {}
+
+#[derive(Accounts)]
+pub struct ClaimableBatch {}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VestingConfig::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, VestingConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeRecovery<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, VestingConfig>,
+
+    #[account(
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + RecoveryProposal::INIT_SPACE,
+        seeds = [b"recovery", vesting.key().as_ref()],
+        bump
+    )]
+    pub recovery: Account<'info, RecoveryProposal>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteRecovery<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, VestingConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    #[account(
+        mut,
+        seeds = [b"recovery", vesting.key().as_ref()],
+        bump = recovery.bump,
+        has_one = vesting,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub recovery: Account<'info, RecoveryProposal>,
+
+    /// CHECK: Original proposer, receives the proposal rent back
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ObjectRecovery<'info> {
+    #[account(
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump,
+        // Hashed-beneficiary schedules store a hash here; the _hashed variant checks the signer
+        constraint = vesting.beneficiary_hashed || vesting.beneficiary == beneficiary.key()
+            @ VestingError::Unauthorized
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    #[account(
+        mut,
+        seeds = [b"recovery", vesting.key().as_ref()],
+        bump = recovery.bump,
+        has_one = vesting,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub recovery: Account<'info, RecoveryProposal>,
+
+    /// CHECK: Original proposer, receives the proposal rent back
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    pub beneficiary: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RotateGuardians<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, VestingConfig>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + GuardianRotation::INIT_SPACE,
+        seeds = [b"guardian_rotation"],
+        bump
+    )]
+    pub rotation: Account<'info, GuardianRotation>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteGuardianRotation<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, VestingConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"guardian_rotation"],
+        bump = rotation.bump,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub rotation: Account<'info, GuardianRotation>,
+
+    /// CHECK: Original proposer, receives the proposal rent back
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelGuardianRotation<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, VestingConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"guardian_rotation"],
+        bump = rotation.bump,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub rotation: Account<'info, GuardianRotation>,
+
+    /// CHECK: Original proposer, receives the proposal rent back
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(conversion_numerator: u64, conversion_denominator: u64, new_authority: Pubkey)]
+pub struct MigrateMint<'info> {
+    #[account(
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    /// One migration in flight per schedule
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MintMigration::INIT_SPACE,
+        seeds = [b"mint_migration", vesting.key().as_ref()],
+        bump
+    )]
+    pub migration: Account<'info, MintMigration>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(associated_token::mint = mint, associated_token::authority = vesting)]
+    pub vesting_ata: Account<'info, TokenAccount>,
+
+    pub new_mint: Account<'info, Mint>,
+
+    /// The schedule's escrow for the new mint, holding the deposit until acceptance
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = new_mint,
+        associated_token::authority = vesting
+    )]
+    pub new_vesting_ata: Account<'info, TokenAccount>,
+
+    #[account(mut, token::mint = new_mint, token::authority = authority)]
+    pub authority_new_ata: Account<'info, TokenAccount>,
+
+    /// The new authority's schedule count, created if needed
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ScheduleCount::INIT_SPACE,
+        seeds = [b"schedule_count", new_authority.as_ref()],
+        bump
+    )]
+    pub new_schedule_count: Account<'info, ScheduleCount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptMintMigration<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump,
+        has_one = beneficiary,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_migration", vesting.key().as_ref()],
+        bump = migration.bump,
+        has_one = new_mint,
+        close = authority
+    )]
+    pub migration: Account<'info, MintMigration>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut, associated_token::mint = mint, associated_token::authority = vesting)]
+    pub vesting_ata: Account<'info, TokenAccount>,
+
+    pub new_mint: Account<'info, Mint>,
+
+    #[account(mut, associated_token::mint = new_mint, associated_token::authority = vesting)]
+    pub new_vesting_ata: Account<'info, TokenAccount>,
+
+    /// Receives the old-mint escrow
+    #[account(mut, token::mint = mint, token::authority = authority)]
+    pub authority_ata: Account<'info, TokenAccount>,
+
+    /// Receives any new-mint deposit beyond the converted balance
+    #[account(mut, token::mint = new_mint, token::authority = authority)]
+    pub authority_new_ata: Account<'info, TokenAccount>,
+
+    /// CHECK: The schedule's authority (has_one), receives the proposal and
+    /// old escrow rent back
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [b"schedule_count", authority.key().as_ref()], bump = schedule_count.bump)]
+    pub schedule_count: Account<'info, ScheduleCount>,
+
+    /// Only when the migration changes the authority
+    #[account(
+        mut,
+        seeds = [b"schedule_count", migration.new_authority.as_ref()],
+        bump = new_schedule_count.bump
+    )]
+    pub new_schedule_count: Option<Account<'info, ScheduleCount>>,
+
+    pub beneficiary: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelMintMigration<'info> {
+    #[account(
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump,
+        has_one = authority
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_migration", vesting.key().as_ref()],
+        bump = migration.bump,
+        has_one = new_mint,
+        close = authority
+    )]
+    pub migration: Account<'info, MintMigration>,
+
+    pub new_mint: Account<'info, Mint>,
+
+    #[account(mut, associated_token::mint = new_mint, associated_token::authority = vesting)]
+    pub new_vesting_ata: Account<'info, TokenAccount>,
+
+    #[account(mut, token::mint = new_mint, token::authority = authority)]
+    pub authority_new_ata: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendUnlock<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump,
+        has_one = authority
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct OpenBridgeDeposit<'info> {
+    #[account(
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump,
+        has_one = authority,
+        has_one = mint
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    /// One per schedule and bridge operation, kept once credited
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BridgeDeposit::INIT_SPACE,
+        seeds = [b"bridge_deposit", vesting.key().as_ref(), operation_id.as_ref()],
+        bump
+    )]
+    pub bridge_deposit: Account<'info, BridgeDeposit>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + BridgeInflight::INIT_SPACE,
+        seeds = [b"bridge_inflight", vesting.key().as_ref()],
+        bump
+    )]
+    pub bridge_inflight: Account<'info, BridgeInflight>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// The bridge's recipient for this operation
+    #[account(associated_token::mint = mint, associated_token::authority = vesting)]
+    pub vesting_ata: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct DepositFromBridge<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump,
+        has_one = mint
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_deposit", vesting.key().as_ref(), operation_id.as_ref()],
+        bump = bridge_deposit.bump,
+        has_one = vesting
+    )]
+    pub bridge_deposit: Account<'info, BridgeDeposit>,
+
+    #[account(mut, seeds = [b"bridge_inflight", vesting.key().as_ref()], bump = bridge_inflight.bump)]
+    pub bridge_inflight: Account<'info, BridgeInflight>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(associated_token::mint = mint, associated_token::authority = vesting)]
+    pub vesting_ata: Account<'info, TokenAccount>,
+
+    /// CHECK: Trinity ConsensusMirror for `operation_id`; owner, address and
+    /// contents are checked against the config by check_consensus_mirror
+    pub consensus_mirror: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, VestingConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireAndReturn<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
+        ],
+        bump = vesting.bump,
+        has_one = mint
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    /// Mutable so an incinerator sink can burn
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut, associated_token::mint = mint, associated_token::authority = vesting)]
+    pub vesting_ata: Account<'info, TokenAccount>,
+
+    /// The expiry sink's token account; owner and mint checked in the
+    /// handler. Omitted when the sink is the incinerator.
+    #[account(mut)]
+    pub sink_tokens: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(terms: ScheduleTerms)]
+pub struct RegisterPendingSchedule<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PendingSchedule::INIT_SPACE,
+        seeds = [
+            b"pending_schedule",
+            beneficiary.key().as_ref(),
+            mint.key().as_ref(),
+            &terms.schedule_id.to_le_bytes(),
+            &terms.nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    pub pending: Account<'info, PendingSchedule>,
+
+    /// Holds the staged tokens until activation or cancellation. Anyone can
+    /// create an ATA, so it may already exist
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = pending
+    )]
+    pub staging: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: Beneficiary address
+    pub beneficiary: UncheckedAccount<'info>,
+
+    #[account(mut, token::mint = mint, token::authority = authority)]
+    pub authority_tokens: Account<'info, TokenAccount>,
 
-        let available = vesting.total_amount.checked_sub(vesting.withdrawn)
-            .ok_or(VestingError::Overflow)?;
-        require!(amount <= available, VestingError::InsufficientBalance);
+    /// The schedule's authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
 
-        // Transfer using PDA signer
-        let seeds = &[
+    /// CHECK: Live schedule at the same seeds, which activation would collide
+    /// with; must not exist
+    #[account(
+        seeds = [
             b"vesting",
-            vesting.beneficiary.as_ref(),
-            vesting.mint.as_ref(),
-            &vesting.schedule_id.to_le_bytes(),
-            &[vesting.bump],
-        ];
-        let signer = &[&seeds[..]];
-
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.vesting_ata.to_account_info(),
-                    to: ctx.accounts.beneficiary_ata.to_account_info(),
-                    authority: vesting.to_account_info(),
-                },
-                signer
-            ),
-            amount
-        )?;
+            beneficiary.key().as_ref(),
+            mint.key().as_ref(),
+            &terms.schedule_id.to_le_bytes(),
+            &terms.nonce.to_le_bytes()
+        ],
+        bump,
+        constraint = vesting.data_is_empty() @ VestingError::ScheduleReserved
+    )]
+    pub vesting: UncheckedAccount<'info>,
 
-        vesting.withdrawn = vesting.withdrawn.checked_add(amount)
-            .ok_or(VestingError::Overflow)?;
+    /// CHECK: Config PDA, always passed since it may gate creation; read only
+    /// if initialized (minimum cliff, schedule cap)
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
 
-        msg!("✅ Withdrawn {} tokens", amount);
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ScheduleCount::INIT_SPACE,
+        seeds = [b"schedule_count", authority.key().as_ref()],
+        bump
+    )]
+    pub schedule_count: Account<'info, ScheduleCount>,
 
-        Ok(())
-    }
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(schedule_id: u64)]
-pub struct CreateVesting<'info> {
+pub struct ActivateSchedule<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pending_schedule",
+            pending.beneficiary.as_ref(),
+            pending.mint.as_ref(),
+            &pending.terms.schedule_id.to_le_bytes(),
+            &pending.terms.nonce.to_le_bytes()
+        ],
+        bump = pending.bump,
+        has_one = authority,
+        has_one = mint,
+        close = authority
+    )]
+    pub pending: Account<'info, PendingSchedule>,
+
+    #[account(mut, associated_token::mint = mint, associated_token::authority = pending)]
+    pub staging: Account<'info, TokenAccount>,
+
     #[account(
         init,
         payer = payer,
         space = 8 + Vesting::INIT_SPACE,
         seeds = [
             b"vesting",
-            beneficiary.key().as_ref(),
-            mint.key().as_ref(),
-            &schedule_id.to_le_bytes()
+            pending.beneficiary.as_ref(),
+            pending.mint.as_ref(),
+            &pending.terms.schedule_id.to_le_bytes(),
+            &pending.terms.nonce.to_le_bytes()
         ],
         bump
     )]
     pub vesting: Account<'info, Vesting>,
-    
+
+    /// Anyone can create an ATA, so it may already exist
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = vesting
+    )]
+    pub vesting_ata: Account<'info, TokenAccount>,
+
     pub mint: Account<'info, Mint>,
-    
-    /// CHECK: Beneficiary address
-    pub beneficiary: UncheckedAccount<'info>,
-    
+
+    /// CHECK: The pending schedule's authority, receiving its rent back
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+
     #[account(mut)]
     pub payer: Signer<'info>,
-    
+
+    /// CHECK: Config PDA, always passed since it may gate creation; read only
+    /// if initialized (event verbosity, allowlist)
+    #[account(seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Withdraw<'info> {
+pub struct CancelPendingSchedule<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"pending_schedule",
+            pending.beneficiary.as_ref(),
+            pending.mint.as_ref(),
+            &pending.terms.schedule_id.to_le_bytes(),
+            &pending.terms.nonce.to_le_bytes()
+        ],
+        bump = pending.bump,
+        has_one = authority,
+        has_one = mint,
+        close = authority
+    )]
+    pub pending: Account<'info, PendingSchedule>,
+
+    #[account(mut, associated_token::mint = mint, associated_token::authority = pending)]
+    pub staging: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut, token::mint = mint, token::authority = authority)]
+    pub authority_tokens: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Frees a slot under the schedule authority's cap
+    #[account(
+        mut,
+        seeds = [b"schedule_count", pending.authority.as_ref()],
+        bump = schedule_count.bump
+    )]
+    pub schedule_count: Account<'info, ScheduleCount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelUnfunded<'info> {
     #[account(
         mut,
         seeds = [
             b"vesting",
-            vesting.beneficiary.as_ref(),
-            vesting.mint.as_ref(),
-            &vesting.schedule_id.to_le_bytes()
+            vesting.original_beneficiary.as_ref(),
+            vesting.original_mint.as_ref(),
+            &vesting.schedule_id.to_le_bytes(),
+            &vesting.nonce.to_le_bytes()
         ],
         bump = vesting.bump,
-        has_one = beneficiary,
-        has_one = mint
+        has_one = authority,
+        close = authority
     )]
     pub vesting: Account<'info, Vesting>,
-    
-    pub mint: Account<'info, Mint>,
-    
+
+    /// CHECK: The vesting ATA's address; it may never have been created, and
+    /// if it was it must be empty
+    #[account(mut, address = get_associated_token_address(&vesting.key(), &vesting.mint))]
+    pub vesting_ata: UncheckedAccount<'info>,
+
+    /// CHECK: The schedule's recovery proposal address; must hold none
     #[account(
-        mut,
-        associated_token::mint = mint,
-        associated_token::authority = vesting
+        seeds = [b"recovery", vesting.key().as_ref()],
+        bump,
+        constraint = recovery.data_is_empty() @ VestingError::ScheduleProposalPending
     )]
-    pub vesting_ata: Account<'info, TokenAccount>,
-    
+    pub recovery: UncheckedAccount<'info>,
+
+    /// CHECK: The schedule's mint migration address; must hold none (a
+    /// pending one escrows the new mint's deposit)
+    #[account(
+        seeds = [b"mint_migration", vesting.key().as_ref()],
+        bump,
+        constraint = mint_migration.data_is_empty() @ VestingError::ScheduleProposalPending
+    )]
+    pub mint_migration: UncheckedAccount<'info>,
+
+    /// CHECK: The schedule's BridgeInflight address; if created, it must have
+    /// no open deposit and is closed along with the schedule
+    #[account(mut, seeds = [b"bridge_inflight", vesting.key().as_ref()], bump)]
+    pub bridge_inflight: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Frees a slot under the schedule authority's cap
     #[account(
         mut,
-        associated_token::mint = mint,
-        associated_token::authority = beneficiary
+        seeds = [b"schedule_count", vesting.authority.as_ref()],
+        bump = schedule_count.bump
     )]
-    pub beneficiary_ata: Account<'info, TokenAccount>,
-    
-    pub beneficiary: Signer<'info>,
+    pub schedule_count: Account<'info, ScheduleCount>,
+
     pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct Vesting {
     pub beneficiary: Pubkey,
+    /// Beneficiary at creation; part of the PDA seeds so it never changes
+    pub original_beneficiary: Pubkey,
+    /// `beneficiary` is `beneficiary_hash(key, salt)`, not a key: only
+    /// `withdraw_hashed` (with the salt) can claim, and instructions that
+    /// bind the beneficiary with `has_one` reject the schedule
+    pub beneficiary_hashed: bool,
+    /// Created (and paid for) the schedule; it counts against this key's cap
+    pub authority: Pubkey,
     pub mint: Pubkey,
+    /// Mint at creation; part of the PDA seeds so a mint migration keeps the address
+    pub original_mint: Pubkey,
+    /// Decimals of `mint`, so clients display amounts without refetching it
+    pub decimals: u8,
     pub schedule_id: u64,
+    /// Random per-schedule salt in the PDA seeds (anti-squatting)
+    pub nonce: u64,
+    /// Indexer routing key (HR, finance, community, ...); meaning is off-chain
+    pub category: u8,
+    /// Hash of the schedule's off-chain label
+    pub label_hash: [u8; 32],
+    /// Hash of the off-chain grant agreement, fixed at creation; zero means none
+    pub terms_hash: [u8; 32],
+    /// From here on `expire_and_return` may sweep what is left unclaimed
+    pub claim_deadline: i64,
+    /// Where expired tokens go, fixed at creation; the default pubkey means
+    /// the schedule never expires, the incinerator means they are burned
+    pub expiry_sink: Pubkey,
     pub unlock_timestamp: i64,
+    /// Ceiling set at creation; `extend_unlock` never moves the unlock past it
+    pub max_unlock_timestamp: i64,
     pub total_amount: u64,
     pub withdrawn: u64,
+    /// Highest clock timestamp used by this schedule (monotonic)
+    pub last_observed_timestamp: i64,
+    /// Blocks withdrawals and deposits until `paused_until`
+    pub is_paused: bool,
+    /// When the current (or last) full pause ends; 0 if never paused
+    pub paused_until: i64,
+    /// Blocks deposits only; beneficiaries can still claim
+    pub deposits_paused: bool,
+    /// Claims skip the config's KYC gate (set by the compliance authority)
+    pub kyc_exempt: bool,
+    /// Set for USD-denominated schedules; `total_amount` is then a token cap
+    pub usd_terms: Option<UsdTerms>,
+    /// Micro-dollars paid out so far (USD schedules)
+    pub usd_claimed: u64,
+    /// May claim via `auto_claim`; the default pubkey means none
+    pub auto_claim_delegate: Pubkey,
+    /// Most the delegate may claim under the current authorization
+    pub auto_claim_max_total: u64,
+    /// Claimed by the delegate under the current authorization
+    pub auto_claimed_total: u64,
+    /// Insurance policy registered by the beneficiary, if any
+    pub coverage: Option<CoverageInfo>,
+    /// Balance-weighted time the escrowed tokens have been locked since: the
+    /// creation time, moved forward by each deposit
+    pub deposit_weighted_start: i64,
+    /// Token-seconds the claimed tokens spent locked: each withdrawal adds
+    /// `amount * (now - deposit_weighted_start)` (see `lock_token_seconds`)
+    pub lock_seconds_accumulated: u128,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct VestingConfig {
+    pub authority: Pubkey,
+    #[max_len(10)]
+    pub guardians: Vec<Pubkey>,
+    /// Changed only by a timelocked guardian-quorum rotation, never by the authority
+    pub guardian_threshold: u8,
+    /// Delay on recoveries and rotations; fixed at initialization, so nothing can shorten it
+    pub recovery_timelock: i64,
+    /// Emit routine per-action events; security-relevant ones are unconditional
+    pub verbose_events: bool,
+    /// Beneficiary allowlist Merkle root; zero means no allowlist
+    pub allowlist_root: [u8; 32],
+    /// Program whose attestations claims must carry; None means no KYC gate
+    pub kyc_program: Option<Pubkey>,
+    /// Grants per-schedule KYC exemptions
+    pub compliance_authority: Pubkey,
+    /// Shortest cliff (creation to unlock) new schedules may have
+    pub min_cliff_secs: i64,
+    /// Owner of the Pyth price accounts USD schedules read; None blocks USD claims
+    pub oracle_program: Option<Pubkey>,
+    /// Oldest oracle price a claim accepts
+    pub oracle_max_staleness_secs: i64,
+    /// Widest confidence interval a claim accepts, in bps of the price
+    pub oracle_max_conf_bps: u16,
+    /// Largest escrow balance `close_vesting` sweeps as dust
+    pub dust_threshold: u64,
+    /// Programs whose policies `register_coverage` accepts
+    #[max_len(4)]
+    pub coverage_programs: Vec<Pubkey>,
+    /// Programs whose PDAs' token accounts `withdraw` may pay into
+    #[max_len(4)]
+    pub destination_programs: Vec<Pubkey>,
+    /// How long a full pause lasts before it lapses
+    pub max_pause_duration: i64,
+    /// Timestamps of re-pauses within the last REPAUSE_WINDOW
+    #[max_len(3)]
+    pub recent_repauses: Vec<i64>,
+    /// Most live schedules one authority may have
+    pub max_schedules_per_authority: u32,
+    /// Trinity validator whose consensus mirrors prove bridge deposits; None
+    /// turns `deposit_from_bridge` off
+    pub bridge_consensus_program: Option<Pubkey>,
+    /// How long `trip_circuit_breaker` blocks claims
+    pub circuit_breaker_duration: i64,
+    /// Claims from every schedule are blocked before this timestamp
+    pub circuit_breaker_until: i64,
+    /// Bumped by each executed guardian rotation
+    pub guardian_epoch: u32,
+    pub bump: u8,
+}
+
+/// A mint migration proposed by the schedule's authority, awaiting the beneficiary
+#[account]
+#[derive(InitSpace)]
+pub struct MintMigration {
+    pub vesting: Pubkey,
+    pub new_mint: Pubkey,
+    /// New tokens per old token, as numerator / denominator
+    pub conversion_numerator: u64,
+    pub conversion_denominator: u64,
+    /// New-mint tokens the authority deposited with the proposal
+    pub deposited: u64,
+    pub proposed_at: i64,
+    /// Authority of the schedule once accepted (the current one to keep it)
+    pub new_authority: Pubkey,
+    /// New mint's Pyth account for USD schedules; None otherwise
+    pub new_price_oracle: Option<Pubkey>,
+    pub bump: u8,
+}
+
+/// A schedule registered ahead of its start, with its tokens staged in the
+/// PDA's own ATA; becomes a live `Vesting` at `activation_timestamp`
+#[account]
+#[derive(InitSpace)]
+pub struct PendingSchedule {
+    /// Registered it and staged the tokens; gets the rent back either way
+    pub authority: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub terms: ScheduleTerms,
+    pub activation_timestamp: i64,
+    pub bump: u8,
+}
+
+/// A bridge-in from Ethereum funding a schedule, opened by its authority
+/// before bridging and credited once consensus and the tokens arrive
+#[account]
+#[derive(InitSpace)]
+pub struct BridgeDeposit {
+    pub vesting: Pubkey,
+    /// Ethereum bridge operation, as finalized by Trinity
+    pub operation_id: [u8; 32],
+    pub amount: u64,
+    /// Escrow balance and the schedule's `withdrawn` when opened
+    pub escrow_before: u64,
+    pub withdrawn_before: u64,
+    pub opened_at: i64,
+    /// 0 until credited; then the operation is consumed for good
+    pub credited_at: i64,
+    pub bump: u8,
+}
+
+/// A schedule's open bridge deposit, if any: set when opened, cleared when
+/// credited
+#[account]
+#[derive(InitSpace)]
+pub struct BridgeInflight {
+    pub vesting: Pubkey,
+    pub operation_id: Option<[u8; 32]>,
+    pub bump: u8,
+}
+
+/// Live (created, not yet closed) schedules of one authority
+#[account]
+#[derive(InitSpace)]
+pub struct ScheduleCount {
+    pub authority: Pubkey,
+    pub schedule_count: u32,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RecoveryProposal {
+    pub vesting: Pubkey,
+    pub new_beneficiary: Pubkey,
+    pub proposer: Pubkey,
+    pub proposed_at: i64,
+    pub executable_at: i64,
+    pub approvals: u8,
+    /// Config's guardian epoch when proposed; stale once the set rotates
+    pub guardian_epoch: u32,
+    pub bump: u8,
+}
+
+/// Pending replacement of the config's guardian set
+#[account]
+#[derive(InitSpace)]
+pub struct GuardianRotation {
+    #[max_len(10)]
+    pub guardians: Vec<Pubkey>,
+    pub guardian_threshold: u8,
+    pub proposer: Pubkey,
+    pub proposed_at: i64,
+    pub executable_at: i64,
+    pub approvals: u8,
     pub bump: u8,
 }
 
+/// KYC attestation layout read from accounts owned by `VestingConfig::kyc_program`
+/// (no discriminator, so any provider can write it): borsh at offset 0,
+/// `owner` (32) | `subject` (32) | `expiry` (i64 LE, 8) | `level` (1) = 73 bytes.
+/// Only `subject` and `expiry` are checked; `owner` (the issuer) and `level`
+/// are the provider's own bookkeeping.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct KycAttestation {
+    pub owner: Pubkey,
+    /// Must equal the schedule's beneficiary
+    pub subject: Pubkey,
+    /// Unix timestamp; the attestation is valid strictly before it
+    pub expiry: i64,
+    pub level: u8,
+}
+
+impl KycAttestation {
+    pub const LEN: usize = 32 + 32 + 8 + 1;
+
+    /// Decode the layout from an account's data, or None if it is too short
+    pub fn read(info: &AccountInfo) -> Option<Self> {
+        let data = info.try_borrow_data().ok()?;
+        Self::deserialize(&mut data.get(..Self::LEN)?).ok()
+    }
+}
+
+/// Ownership attestation layout read from accounts owned by a program-owned
+/// beneficiary's own program (e.g. a DAO), at its PDA
+/// [b"ownership_attestation", token_account]: borsh at offset 0,
+/// `beneficiary` (32) | `token_account` (32) = 64 bytes. Lets the beneficiary
+/// claim into a token account owned by another of the program's PDAs.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OwnershipAttestation {
+    pub beneficiary: Pubkey,
+    pub token_account: Pubkey,
+}
+
+impl OwnershipAttestation {
+    pub const LEN: usize = 32 + 32;
+    pub const SEED: &'static [u8] = b"ownership_attestation";
+
+    /// Decode the layout from an account's data, or None if it is too short
+    pub fn read(info: &AccountInfo) -> Option<Self> {
+        let data = info.try_borrow_data().ok()?;
+        Self::deserialize(&mut data.get(..Self::LEN)?).ok()
+    }
+}
+
+/// External insurance on a schedule; the policy itself lives in `coverage_program`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct CoverageInfo {
+    pub coverage_program: Pubkey,
+    pub policy_id: u64,
+    /// Coverage lapses at this timestamp
+    pub expiry: i64,
+}
+
+/// What a token schedule is created with (see `create_vesting` for each
+/// field), stored by pending schedules until activation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct ScheduleTerms {
+    pub schedule_id: u64,
+    pub nonce: u64,
+    pub unlock_timestamp: i64,
+    pub max_unlock_timestamp: i64,
+    pub amount: u64,
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub terms_hash: [u8; 32],
+    pub claim_deadline: i64,
+    pub expiry_sink: Pubkey,
+}
+
+/// Terms of a USD-denominated schedule: `usd_per_tranche` micro-dollars unlock
+/// at `unlock_timestamp` and every USD_TRANCHE_INTERVAL after, `tranches` times,
+/// priced by the Pyth account `price_oracle` at claim time
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct UsdTerms {
+    pub usd_per_tranche: u64,
+    pub tranches: u32,
+    pub price_oracle: Pubkey,
+}
+
+/// Aggregate price of a Pyth (v2) price account, read by offset rather than
+/// through the Pyth SDK: `magic` u32 @0, `atype` u32 @8, `expo` i32 @20,
+/// `timestamp` i64 @96, then the aggregate `price` i64 @208, `conf` u64 @216
+/// and `status` u32 @224
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PythPrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub status: u32,
+    pub publish_time: i64,
+}
+
+impl PythPrice {
+    pub const MAGIC: u32 = 0xa1b2_c3d4;
+    pub const ACCOUNT_TYPE_PRICE: u32 = 3;
+    pub const STATUS_TRADING: u32 = 1;
+    /// Header plus aggregate price info; publisher components follow
+    pub const LEN: usize = 240;
+
+    /// Decode a price account, or None if it is short or not a Pyth price account
+    pub fn read(info: &AccountInfo) -> Option<Self> {
+        let data = info.try_borrow_data().ok()?;
+        let data = data.get(..Self::LEN)?;
+        let u32_at = |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+        let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        if u32_at(0) != Self::MAGIC || u32_at(8) != Self::ACCOUNT_TYPE_PRICE {
+            return None;
+        }
+        Some(Self {
+            price: u64_at(208) as i64,
+            conf: u64_at(216),
+            expo: u32_at(20) as i32,
+            status: u32_at(224),
+            publish_time: u64_at(96) as i64,
+        })
+    }
+}
+
+/// Return data of `aggregate_beneficiary`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BeneficiarySummary {
+    pub schedules: u32,
+    /// Not yet claimable (before unlock, or paused)
+    pub total_locked: u64,
+    pub total_claimed: u64,
+    pub total_claimable: u64,
+    /// Sum of the schedules' `lock_seconds_accumulated`; divided by
+    /// `total_claimed` it is the realized average lock duration
+    pub total_lock_seconds: u128,
+}
+
+/// Return data of `get_lock_duration`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LockDuration {
+    /// Token-seconds (base units times seconds)
+    pub lock_seconds_accumulated: u128,
+    pub withdrawn: u64,
+    /// `lock_seconds_accumulated / withdrawn`, in seconds
+    pub average_lock_seconds: u64,
+}
+
+/// Return data of `next_unlock_info`; all zero once nothing is left to unlock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct NextUnlock {
+    pub next_ts: i64,
+    /// Tokens, or micro-dollars for USD schedules
+    pub next_amount: u64,
+}
+
+/// Return data of `audit_schedule`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScheduleAudit {
+    pub passed: bool,
+    /// First invariant that failed (None if passed)
+    pub violation: Option<ScheduleInvariant>,
+}
+
+/// What `audit_schedule` checks, in the order it checks them
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ScheduleInvariant {
+    /// `withdrawn <= total_amount`
+    WithdrawnWithinTotal,
+    /// The escrow holds at least `total_amount - withdrawn`
+    EscrowCoversRemaining,
+    /// The account sits at its PDA under the canonical bump it stores
+    CanonicalBump,
+    /// The escrow holds `mint` for this schedule, and `decimals` is the mint's
+    MintConsistent,
+}
+
+/// Return data of `get_actual_balance`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScheduleBalance {
+    /// Tokens the escrow actually holds
+    pub actual_balance: u64,
+    pub total_amount: u64,
+    pub withdrawn: u64,
+    /// `total_amount - withdrawn`
+    pub accounted_remaining: u64,
+}
+
+// Every event starts with `category` and `label_hash`, so an indexer can route
+// on bytes 8..41 of the payload (after the discriminator) without decoding the
+// rest or fetching the account. Sizes are the decoded `Program data:` payload
+// (discriminator included); the log line is base64 (~4/3 larger) and every
+// transaction shares a 10 KB log budget, so keep events small and fixed-size.
+
+/// Bytes every event spends on routing: category (1) + label_hash (32)
+pub const EVENT_ROUTING_LEN: usize = 1 + 32;
+
+/// 185 bytes
+#[event]
+pub struct VestingCreated {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub unlock_timestamp: i64,
+    pub terms_hash: [u8; 32],
+}
+
+/// 129 bytes
+#[event]
+pub struct TokensWithdrawn {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    /// Cumulative withdrawn after this claim
+    pub withdrawn: u64,
+    /// Coverage policy in force; 0 when uncovered
+    pub policy_id: u64,
+}
+
+/// 113 bytes
+#[event]
+pub struct TokensDeposited {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+}
+
+/// 74 bytes
+#[event]
+pub struct CategoryUpdated {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub previous_category: u8,
+}
+
+/// 154 bytes
+#[event]
+pub struct BeneficiaryRecoveryProposed {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub current_beneficiary: Pubkey,
+    pub new_beneficiary: Pubkey,
+    pub approvals: u8,
+    pub executable_at: i64,
+    /// Coverage policy in force; 0 when uncovered
+    pub policy_id: u64,
+}
+
+/// 145 bytes
+#[event]
+pub struct BeneficiaryRecovered {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub previous_beneficiary: Pubkey,
+    pub new_beneficiary: Pubkey,
+    /// Coverage policy in force; 0 when uncovered
+    pub policy_id: u64,
+}
+
+/// 105 bytes
+#[event]
+pub struct BeneficiaryRecoveryCancelled {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    /// Who objected; the default pubkey when discarded after a guardian rotation
+    pub beneficiary: Pubkey,
+}
+
+/// 92 bytes
+#[event]
+pub struct PauseUpdated {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub is_paused: bool,
+    pub deposits_paused: bool,
+    /// When the full pause lapses (when it ended, if lifted)
+    pub paused_until: i64,
+    /// Counted against MAX_REPAUSES_PER_WINDOW
+    pub repause: bool,
+    /// Coverage policy in force; 0 when uncovered
+    pub policy_id: u64,
+}
+
+/// 81 bytes; emitted when a lapsed pause is lifted (on the schedule's next
+/// claim or pause change)
+#[event]
+pub struct PauseExpired {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub paused_until: i64,
+}
+
+/// 74 bytes
+#[event]
+pub struct KycExemptionUpdated {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub kyc_exempt: bool,
+}
+
+/// 113 bytes
+#[event]
+pub struct AutoClaimUpdated {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    /// Default pubkey when revoked
+    pub delegate: Pubkey,
+    pub max_total: u64,
+}
+
+/// 121 bytes
+#[event]
+pub struct CoverageRegistered {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub coverage_program: Pubkey,
+    pub policy_id: u64,
+    pub expiry: i64,
+}
+
+/// 121 bytes; emitted when coverage lapses (seen on the schedule's next
+/// claim or emergency action) or the schedule closes
+#[event]
+pub struct CoverageCleared {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub coverage_program: Pubkey,
+    pub policy_id: u64,
+    pub expiry: i64,
+}
+
+/// 161 bytes
+#[event]
+pub struct MintMigrationProposed {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub new_mint: Pubkey,
+    pub conversion_numerator: u64,
+    pub conversion_denominator: u64,
+    /// New-mint tokens escrowed with the proposal
+    pub deposited: u64,
+    pub new_authority: Pubkey,
+}
+
+/// 161 bytes
+#[event]
+pub struct MintMigrated {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub old_mint: Pubkey,
+    pub new_mint: Pubkey,
+    /// Converted amounts, in new-mint units
+    pub total_amount: u64,
+    pub withdrawn: u64,
+    /// Old-mint escrow returned to the authority
+    pub released: u64,
+}
+
+/// 113 bytes
+#[event]
+pub struct MintMigrationCancelled {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub new_mint: Pubkey,
+    /// New-mint deposit returned to the authority
+    pub refunded: u64,
+}
+
+/// 97 bytes
+#[event]
+pub struct UnlockExtended {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub previous_unlock: i64,
+    pub unlock_timestamp: i64,
+    pub max_unlock_timestamp: i64,
+}
+
+/// 113 bytes
+#[event]
+pub struct BridgeDepositCredited {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    /// Ethereum bridge operation the deposit came in through
+    pub operation_id: [u8; 32],
+    pub amount: u64,
+}
+
+/// 122 bytes
+#[event]
+pub struct VestingExpired {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub sink: Pubkey,
+    /// Escrow balance sent to the sink (or burned)
+    pub amount: u64,
+    /// Unclaimed entitlement written off
+    pub forfeited: u64,
+    pub burned: bool,
+}
+
+// Guardian events are config-wide: their routing prefix is zero
+
+/// 116 bytes
+#[event]
+pub struct GuardianRotationProposed {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub proposer: Pubkey,
+    /// `guardian_set_hash` of the proposed set
+    pub guardians_hash: [u8; 32],
+    pub guardian_count: u8,
+    pub guardian_threshold: u8,
+    pub approvals: u8,
+    pub executable_at: i64,
+}
+
+/// 79 bytes
+#[event]
+pub struct GuardiansRotated {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub guardians_hash: [u8; 32],
+    pub guardian_count: u8,
+    pub guardian_threshold: u8,
+    /// Recoveries proposed under an earlier epoch are discarded
+    pub guardian_epoch: u32,
+}
+
+/// 106 bytes
+#[event]
+pub struct GuardianRotationCancelled {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    /// `guardian_set_hash` of the withdrawn set
+    pub guardians_hash: [u8; 32],
+    pub cancelled_by: Pubkey,
+    pub approvals: u8,
+}
+
+/// What `close_vesting` did with the escrow's leftover balance
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DustDisposition {
+    /// Escrow was already empty
+    None,
+    /// Sent to the beneficiary's ATA
+    Transferred,
+    /// Burned because the beneficiary's ATA was closed
+    Burned,
+}
+
+/// 146 bytes
+#[event]
+pub struct VestingClosed {
+    pub category: u8,
+    pub label_hash: [u8; 32],
+    pub vesting: Pubkey,
+    pub beneficiary: Pubkey,
+    /// Escrow balance swept at close
+    pub dust: u64,
+    /// Threshold in force for this close
+    pub dust_threshold: u64,
+    pub disposition: DustDisposition,
+    /// Final lock analytics (see `get_lock_duration`)
+    pub withdrawn: u64,
+    pub lock_seconds_accumulated: u128,
+}
+
 #[error_code]
 pub enum VestingError {
     #[msg("Unlock time must be in future")]
@@ -189,4 +4977,116 @@ pub enum VestingError {
     InsufficientBalance,
     #[msg("Overflow")]
     Overflow,
+    #[msg("Invalid guardian set or threshold")]
+    InvalidGuardianSet,
+    #[msg("Recovery timelock below minimum (30 days)")]
+    InvalidRecoveryTimelock,
+    #[msg("Not enough guardian approvals")]
+    InsufficientGuardianApprovals,
+    #[msg("Recovery timelock has not elapsed")]
+    RecoveryTimelockActive,
+    #[msg("Invalid beneficiary")]
+    InvalidBeneficiary,
+    #[msg("Too many accounts in batch")]
+    BatchTooLarge,
+    #[msg("Schedule is paused")]
+    Paused,
+    #[msg("Deposits are paused for this schedule")]
+    DepositsPaused,
+    #[msg("Schedule listed more than once")]
+    DuplicateSchedule,
+    #[msg("Beneficiary is not on the allowlist")]
+    NotAllowlisted,
+    #[msg("Native SOL withdrawals require a wSOL schedule")]
+    NotNativeMint,
+    #[msg("Claim requires a KYC attestation")]
+    KycRequired,
+    #[msg("KYC attestation is not from the KYC program or not for this beneficiary")]
+    KycAttestationInvalid,
+    #[msg("KYC attestation has expired")]
+    KycExpired,
+    #[msg("Schedule violates the vesting policy")]
+    InvalidSchedule,
+    #[msg("Price oracle unavailable")]
+    OracleUnavailable,
+    #[msg("Price oracle account is not the schedule's Pyth price account")]
+    OracleInvalid,
+    #[msg("Oracle price is stale")]
+    OracleStale,
+    #[msg("Oracle confidence interval too wide")]
+    OracleConfidenceTooWide,
+    #[msg("Invalid oracle staleness or confidence bound")]
+    InvalidOraclePolicy,
+    #[msg("Escrow balance is above the dust threshold")]
+    DustAboveThreshold,
+    #[msg("Dust threshold above MAX_DUST_THRESHOLD")]
+    InvalidDustThreshold,
+    #[msg("Signer is not the schedule's auto-claim delegate")]
+    AutoClaimNotAuthorized,
+    #[msg("Auto-claim would exceed the delegate's cap")]
+    AutoClaimCapExceeded,
+    #[msg("Projection point is before the schedule's start")]
+    ProjectionBeforeStart,
+    #[msg("Coverage program is not on the config allowlist")]
+    CoverageProgramNotAllowed,
+    #[msg("Coverage needs a policy id and a future expiry")]
+    InvalidCoverage,
+    #[msg("Claim destination is neither the beneficiary's ATA nor owned by an allowlisted program's PDA")]
+    ClaimDestinationNotAllowed,
+    #[msg("Pause duration must be positive and at most MAX_PAUSE_DURATION")]
+    InvalidPauseDuration,
+    #[msg("Too many re-pauses within the rolling window")]
+    RepauseLimitReached,
+    #[msg("Authority already has the maximum number of live schedules")]
+    ScheduleLimitReached,
+    #[msg("Schedule limit must be positive")]
+    InvalidScheduleLimit,
+    #[msg("Mint migration needs a different mint, a positive ratio and a new oracle exactly for USD schedules")]
+    InvalidConversion,
+    #[msg("New-mint deposit doesn't cover the converted escrow balance")]
+    MigrationUnderfunded,
+    #[msg("Hashed-beneficiary schedule: claim with withdraw_hashed and the salt")]
+    BeneficiarySaltRequired,
+    #[msg("Schedule's beneficiary is not hashed; use withdraw")]
+    NotHashedBeneficiary,
+    #[msg("Signer and salt don't hash to the schedule's beneficiary (wrong or lost salt)")]
+    InvalidBeneficiarySalt,
+    #[msg("Token account mint does not match the schedule mint")]
+    TokenMintMismatch,
+    #[msg("No bridge consensus program is configured")]
+    BridgeNotConfigured,
+    #[msg("Not a consensus mirror of the bridge operation with enough attesting chains")]
+    InvalidConsensusMirror,
+    #[msg("Bridged tokens have not arrived in the schedule's escrow")]
+    BridgeDepositNotArrived,
+    #[msg("Bridge operation was already credited")]
+    BridgeDepositConsumed,
+    #[msg("Schedule has no expiry sink; it never expires")]
+    ExpirySinkNotSet,
+    #[msg("Claim deadline has not passed")]
+    ClaimWindowOpen,
+    #[msg("Token account is not the schedule's expiry sink")]
+    InvalidExpirySink,
+    #[msg("Activation time must be in the future")]
+    InvalidActivationTime,
+    #[msg("Pending schedule's activation time has not arrived")]
+    ActivationPending,
+    #[msg("Circuit breaker duration must be positive and at most MAX_CIRCUIT_BREAKER_DURATION")]
+    InvalidCircuitBreakerDuration,
+    #[msg("Circuit breaker is tripped: claims are blocked until it resets")]
+    CircuitBreakerTripped,
+    #[msg("Ownership attestation is not the beneficiary program's binding of this destination")]
+    InvalidOwnershipAttestation,
+    #[msg("Schedule address is reserved by a pending schedule or already live")]
+    ScheduleReserved,
+    #[msg("Schedule already has an open bridge deposit; credit it first")]
+    BridgeDepositInFlight,
+    #[msg("Changing the schedule's authority needs the new authority's schedule count")]
+    ScheduleCountRequired,
+    #[msg("Pass exactly one claim destination: the beneficiary's ATA or an explicit destination")]
+    ClaimDestinationRequired,
+    #[msg("Schedule has a pending recovery or mint migration; resolve it first")]
+    ScheduleProposalPending,
+    #[msg("Bridge operation ID does not commit to the schedule's escrow and this amount")]
+    BridgeOperationMismatch,
 }
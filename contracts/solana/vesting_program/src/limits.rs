@@ -0,0 +1,176 @@
+//! Transaction-size ceilings for the batched instructions, evaluated at
+//! compile time.
+//!
+//! A batch has to fit in one legacy transaction: at most PACKET_DATA_SIZE
+//! serialized bytes and MAX_TX_ACCOUNTS account keys. Each ceiling below is
+//! the largest batch a transaction carrying just that instruction can hold,
+//! given the instruction's account metas and argument layout. The caps in
+//! the crate root are asserted to stay at or under them, so an oversized
+//! batch fails on-chain with `BatchTooLarge` rather than as an unsendable
+//! transaction.
+
+use anchor_lang::Space;
+
+use crate::UsdTerms;
+
+/// Largest serialized transaction (solana_sdk::packet::PACKET_DATA_SIZE)
+pub const PACKET_DATA_SIZE: usize = 1232;
+/// Most account keys one transaction may reference, program ids included
+pub const MAX_TX_ACCOUNTS: usize = 64;
+/// Most return data an instruction may set (views answer through it)
+pub const MAX_RETURN_DATA: usize = 1024;
+
+const SIGNATURE_LEN: usize = 64;
+const PUBKEY_LEN: usize = 32;
+const DISCRIMINATOR_LEN: usize = 8;
+const VEC_PREFIX_LEN: usize = 4;
+
+/// A single-instruction legacy transaction, counted the way it is serialized
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TxShape {
+    /// Signatures, the fee payer's included
+    pub signers: usize,
+    /// Distinct account keys, the fee payer and program id included
+    pub keys: usize,
+    /// Account metas on the instruction
+    pub metas: usize,
+    /// Instruction data bytes
+    pub data: usize,
+}
+
+impl TxShape {
+    /// Starting point for what one batch item adds
+    pub const EMPTY: Self = Self { signers: 0, keys: 0, metas: 0, data: 0 };
+
+    /// `self` with `n` more of `item`
+    pub const fn with(self, item: TxShape, n: usize) -> Self {
+        Self {
+            signers: self.signers + n * item.signers,
+            keys: self.keys + n * item.keys,
+            metas: self.metas + n * item.metas,
+            data: self.data + n * item.data,
+        }
+    }
+
+    /// Serialized size: signatures, message header, keys, recent blockhash,
+    /// then the instruction (program id index, metas, data)
+    pub const fn size(self) -> usize {
+        short_vec_len(self.signers)
+            + self.signers * SIGNATURE_LEN
+            + 3
+            + short_vec_len(self.keys)
+            + self.keys * PUBKEY_LEN
+            + PUBKEY_LEN
+            + short_vec_len(1)
+            + 1
+            + short_vec_len(self.metas)
+            + self.metas
+            + short_vec_len(self.data)
+            + self.data
+    }
+
+    pub const fn fits(self) -> bool {
+        self.size() <= PACKET_DATA_SIZE && self.keys <= MAX_TX_ACCOUNTS
+    }
+
+    /// Most items that still fit alongside `self`
+    pub const fn max_items(self, item: TxShape) -> usize {
+        let mut n = 0;
+        while self.with(item, n + 1).fits() {
+            n += 1;
+        }
+        n
+    }
+}
+
+/// Length prefix of a compact-u16 ("short vec") encoded count
+const fn short_vec_len(n: usize) -> usize {
+    if n < 0x80 {
+        1
+    } else if n < 0x4000 {
+        2
+    } else {
+        3
+    }
+}
+
+const fn min(a: usize, b: usize) -> usize {
+    if a < b { a } else { b }
+}
+
+/// aggregate_beneficiary: fee payer and program, `beneficiary` argument;
+/// each schedule is one read-only remaining account
+pub const AGGREGATE_BENEFICIARY: TxShape = TxShape {
+    signers: 1,
+    keys: 2,
+    metas: 0,
+    data: DISCRIMINATOR_LEN + PUBKEY_LEN,
+};
+pub const AGGREGATE_SCHEDULE: TxShape = TxShape { keys: 1, metas: 1, ..TxShape::EMPTY };
+pub const AGGREGATE_SCHEDULES_CEILING: usize = AGGREGATE_BENEFICIARY.max_items(AGGREGATE_SCHEDULE);
+
+/// project_claimable: fee payer, program and the schedule; each point is an
+/// i64 in, and a u64 back through return data
+pub const PROJECT_CLAIMABLE: TxShape = TxShape {
+    signers: 1,
+    keys: 3,
+    metas: 1,
+    data: DISCRIMINATOR_LEN + VEC_PREFIX_LEN,
+};
+pub const PROJECTION_POINT: TxShape = TxShape { data: 8, ..TxShape::EMPTY };
+pub const PROJECTION_POINTS_CEILING: usize = min(
+    PROJECT_CLAIMABLE.max_items(PROJECTION_POINT),
+    (MAX_RETURN_DATA - VEC_PREFIX_LEN) / 8,
+);
+
+/// claimable_batch: fee payer and program, no arguments; each schedule is
+/// one read-only remaining account and a u64 back through return data
+pub const CLAIMABLE_BATCH: TxShape = TxShape {
+    signers: 1,
+    keys: 2,
+    metas: 0,
+    data: DISCRIMINATOR_LEN,
+};
+pub const CLAIMABLE_BATCH_CEILING: usize = min(
+    CLAIMABLE_BATCH.max_items(AGGREGATE_SCHEDULE),
+    (MAX_RETURN_DATA - VEC_PREFIX_LEN) / 8,
+);
+
+/// guardian_recover_beneficiary with the proposer paying the fee (it already
+/// pays the proposal's rent): proposer, config, vesting, recovery, system
+/// program and program; each co-signer adds a key and a signature. A
+/// separate fee payer costs one co-signer's worth of space.
+pub const PROPOSE_RECOVERY: TxShape = TxShape {
+    signers: 1,
+    keys: 6,
+    metas: 5,
+    data: DISCRIMINATOR_LEN + PUBKEY_LEN,
+};
+pub const RECOVERY_CO_SIGNER: TxShape = TxShape { signers: 1, keys: 1, metas: 1, ..TxShape::EMPTY };
+pub const RECOVERY_CO_SIGNERS_CEILING: usize = PROPOSE_RECOVERY.max_items(RECOVERY_CO_SIGNER);
+
+/// create_vesting with USD terms (its largest form): payer, vesting, mint,
+/// beneficiary, system program, config, schedule count, pending schedule
+/// and program; each allowlist proof node is 32 bytes of data
+pub const CREATE_VESTING: TxShape = TxShape {
+    signers: 1,
+    keys: 9,
+    metas: 8,
+    // schedule_id, nonce, unlock_timestamp, max_unlock_timestamp, amount,
+    // category, label_hash, terms_hash, claim_deadline, expiry_sink,
+    // allowlist_proof prefix, Some(usd_terms), hashed_beneficiary
+    data: DISCRIMINATOR_LEN + 6 * 8 + 1 + 2 * 32 + PUBKEY_LEN + VEC_PREFIX_LEN + 1 + UsdTerms::INIT_SPACE + 1,
+};
+pub const ALLOWLIST_PROOF_NODE: TxShape = TxShape { data: 32, ..TxShape::EMPTY };
+pub const ALLOWLIST_PROOF_CEILING: usize = CREATE_VESTING.max_items(ALLOWLIST_PROOF_NODE);
+
+/// activate_schedule: pending schedule, staging ATA, vesting, vesting ATA,
+/// mint, authority, payer, config, token, associated token and system
+/// programs, and the program; each allowlist proof node is 32 bytes of data
+pub const ACTIVATE_SCHEDULE: TxShape = TxShape {
+    signers: 1,
+    keys: 12,
+    metas: 11,
+    data: DISCRIMINATOR_LEN + VEC_PREFIX_LEN,
+};
+pub const ACTIVATION_PROOF_CEILING: usize = ACTIVATE_SCHEDULE.max_items(ALLOWLIST_PROOF_NODE);
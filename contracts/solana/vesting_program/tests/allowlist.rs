@@ -0,0 +1,81 @@
+//! Merkle allowlist gating schedule creation
+
+mod common;
+
+use anchor_lang::solana_program::keccak::hashv;
+use common::*;
+use cvt_vesting::merkle::calculate_merkle_root;
+use cvt_vesting::{VestingError, MIN_RECOVERY_TIMELOCK};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+fn leaf(beneficiary: &Pubkey) -> [u8; 32] {
+    hashv(&[beneficiary.as_ref()]).0
+}
+
+fn parent(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    calculate_merkle_root(&[b], &a)
+}
+
+/// Four-member tree: (root, proof per member)
+fn allowlist(members: &[Pubkey; 4]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+    let leaves: Vec<[u8; 32]> = members.iter().map(leaf).collect();
+    let left = parent(leaves[0], leaves[1]);
+    let right = parent(leaves[2], leaves[3]);
+    let proofs = vec![
+        vec![leaves[1], right],
+        vec![leaves[0], right],
+        vec![leaves[3], left],
+        vec![leaves[2], left],
+    ];
+    (parent(left, right), proofs)
+}
+
+#[tokio::test]
+async fn allowlist_gates_schedule_creation() {
+    let mut ctx = start().await;
+    let init = initialize_config_ix(ctx.payer.pubkey(), vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(&mut ctx, &[init], &[]).await.unwrap();
+    let authority = ctx.payer.pubkey();
+    let mint = create_mint(&mut ctx).await;
+    let unlock = now(&mut ctx).await + DAY;
+
+    let members = [Keypair::new().pubkey(), Keypair::new().pubkey(), Keypair::new().pubkey(), Keypair::new().pubkey()];
+    let (root, proofs) = allowlist(&members);
+    let outsider = Keypair::new().pubkey();
+    let create = |beneficiary, nonce, allowlist_proof| {
+        let data = cvt_vesting::instruction::CreateVesting { allowlist_proof, ..base_create(1, nonce, unlock, 1_000) };
+        create_vesting_ix_with(authority, beneficiary, mint, data)
+    };
+
+    // No allowlist yet: anyone, no proof needed
+    send(&mut ctx, &[create(outsider, 1, vec![])], &[]).await.unwrap();
+    assert!(view::<bool>(&mut ctx, is_allowlisted_ix(outsider, vec![])).await);
+
+    // Only the config authority commits a root
+    let stranger = Keypair::new();
+    fund(&mut ctx, &stranger.pubkey(), 1_000_000_000).await;
+    let result = send(&mut ctx, &[set_allowlist_root_ix(stranger.pubkey(), root)], &[&stranger]).await;
+    assert!(result.is_err());
+    send(&mut ctx, &[set_allowlist_root_ix(authority, root)], &[]).await.unwrap();
+
+    for (i, (member, proof)) in members.iter().zip(&proofs).enumerate() {
+        assert!(view::<bool>(&mut ctx, is_allowlisted_ix(*member, proof.clone())).await);
+        send(&mut ctx, &[create(*member, 10 + i as u64, proof.clone())], &[]).await.unwrap();
+    }
+
+    // A non-member can't borrow a member's proof, and a member needs theirs
+    assert!(!view::<bool>(&mut ctx, is_allowlisted_ix(outsider, proofs[0].clone())).await);
+    let result = send(&mut ctx, &[create(outsider, 2, proofs[0].clone())], &[]).await;
+    assert_vesting_error(result, VestingError::NotAllowlisted);
+    let result = send(&mut ctx, &[create(members[0], 3, vec![])], &[]).await;
+    assert_vesting_error(result, VestingError::NotAllowlisted);
+    let result = send(&mut ctx, &[create(members[0], 4, proofs[1].clone())], &[]).await;
+    assert_vesting_error(result, VestingError::NotAllowlisted);
+
+    // Clearing the root reopens creation
+    send(&mut ctx, &[set_allowlist_root_ix(authority, [0; 32])], &[]).await.unwrap();
+    send(&mut ctx, &[create(outsider, 5, vec![])], &[]).await.unwrap();
+}
@@ -0,0 +1,79 @@
+//! Host-side amount helpers (`cargo test --features host`)
+
+use cvt_vesting::display::{format_amount, parse_amount, AmountParseError};
+
+#[test]
+fn formats_exactly_without_rounding() {
+    assert_eq!(format_amount(0, 9), "0");
+    assert_eq!(format_amount(1, 9), "0.000000001");
+    assert_eq!(format_amount(999_999_999, 9), "0.999999999");
+    assert_eq!(format_amount(1_000_000_000, 9), "1");
+    assert_eq!(format_amount(1_500_000_000, 9), "1.5");
+    assert_eq!(format_amount(1_000_500_000_000, 9), "1000.5");
+    assert_eq!(format_amount(u64::MAX, 9), "18446744073.709551615");
+}
+
+#[test]
+fn formats_six_and_zero_decimals() {
+    // The classic 9-vs-6 mix-up: same base units, very different display
+    assert_eq!(format_amount(1_500_000, 6), "1.5");
+    assert_eq!(format_amount(1_500_000, 9), "0.0015");
+    assert_eq!(format_amount(42, 0), "42");
+    assert_eq!(format_amount(u64::MAX, 0), "18446744073709551615");
+    assert_eq!(format_amount(u64::MAX, 25), "0.0000018446744073709551615");
+}
+
+#[test]
+fn parses_plain_and_separated_amounts() {
+    assert_eq!(parse_amount("0", 9), Ok(0));
+    assert_eq!(parse_amount("1", 9), Ok(1_000_000_000));
+    assert_eq!(parse_amount("1.5", 9), Ok(1_500_000_000));
+    assert_eq!(parse_amount("1_000.5", 9), Ok(1_000_500_000_000));
+    assert_eq!(parse_amount("1000.500000000", 9), Ok(1_000_500_000_000));
+    assert_eq!(parse_amount("0.000000001", 9), Ok(1));
+    assert_eq!(parse_amount("0.000_001", 6), Ok(1));
+    assert_eq!(parse_amount("007", 0), Ok(7));
+}
+
+#[test]
+fn rejects_excess_precision_instead_of_rounding() {
+    // Nine fractional digits is exactly representable at 9 decimals; one more is not
+    assert_eq!(parse_amount("1000.500000001", 9), Ok(1_000_500_000_001));
+    assert_eq!(parse_amount("1000.5000000001", 9), Err(AmountParseError::TooManyDecimals));
+    assert_eq!(parse_amount("1000.500000001", 6), Err(AmountParseError::TooManyDecimals));
+    assert_eq!(parse_amount("1.0000001", 6), Err(AmountParseError::TooManyDecimals));
+    assert_eq!(parse_amount("1.0", 0), Err(AmountParseError::TooManyDecimals));
+    assert_eq!(parse_amount("0.0000000000", 9), Err(AmountParseError::TooManyDecimals));
+}
+
+#[test]
+fn rejects_overflow() {
+    assert_eq!(parse_amount("18446744073.709551615", 9), Ok(u64::MAX));
+    assert_eq!(parse_amount("18446744073.709551616", 9), Err(AmountParseError::Overflow));
+    assert_eq!(parse_amount("18446744074", 9), Err(AmountParseError::Overflow));
+    assert_eq!(parse_amount("18446744073709551615", 0), Ok(u64::MAX));
+    assert_eq!(parse_amount("18446744073709551616", 0), Err(AmountParseError::Overflow));
+    assert_eq!(parse_amount(&"9".repeat(60), 9), Err(AmountParseError::Overflow));
+    assert_eq!(parse_amount("1", 40), Err(AmountParseError::Overflow));
+}
+
+#[test]
+fn rejects_locale_and_malformed_input() {
+    assert_eq!(parse_amount("", 9), Err(AmountParseError::Empty));
+    for input in [
+        "1,000.5", "1.000,5", "1 000", " 1", "1 ", "+1", "-1", "1e9", "0x10", ".5", "5.", ".",
+        "1..5", "1.5.0", "_1", "1_", "1__000", "1_.5", "1._5", "١٢", "１",
+    ] {
+        assert_eq!(parse_amount(input, 9), Err(AmountParseError::InvalidFormat), "{input:?}");
+    }
+}
+
+#[test]
+fn round_trips() {
+    for decimals in [0u8, 6, 9] {
+        for amount in [0, 1, 10, 999, 1_000_000, 1_234_567_890, u64::MAX / 3, u64::MAX] {
+            let text = format_amount(amount, decimals);
+            assert_eq!(parse_amount(&text, decimals), Ok(amount), "{text} @ {decimals}");
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Schedule audits: a healthy schedule passes every invariant, and one whose
+//! state has drifted reports the first invariant it breaks
+
+mod common;
+
+use anchor_lang::AccountSerialize;
+use common::*;
+use cvt_vesting::{ScheduleAudit, ScheduleInvariant, Vesting};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::AccountSharedData,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+struct Schedule {
+    vesting: Pubkey,
+    mint: Pubkey,
+    beneficiary: Keypair,
+    unlock: i64,
+}
+
+/// A 1,000-unit schedule `id` whose escrow holds `funded`
+async fn schedule(ctx: &mut ProgramTestContext, id: u64, funded: u64) -> Schedule {
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    fund(ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(ctx).await + DAY;
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, id, 88, unlock, 1_000);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, id, 88);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &escrow, funded).await;
+    Schedule { vesting, mint, beneficiary, unlock }
+}
+
+/// Overwrite the stored schedule with `edit` applied, bypassing the program
+async fn desync(ctx: &mut ProgramTestContext, vesting: Pubkey, edit: impl FnOnce(&mut Vesting)) {
+    let account = ctx.banks_client.get_account(vesting).await.unwrap().unwrap();
+    let mut state: Vesting = fetch(ctx, vesting).await;
+    edit(&mut state);
+    let mut data = account.data.clone();
+    state.try_serialize(&mut data.as_mut_slice()).unwrap();
+    let mut account = AccountSharedData::from(account);
+    account.set_data_from_slice(&data);
+    ctx.set_account(&vesting, &account);
+}
+
+async fn audit(ctx: &mut ProgramTestContext, schedule: &Schedule) -> ScheduleAudit {
+    view(ctx, audit_schedule_ix(schedule.vesting, schedule.mint)).await
+}
+
+fn failed(invariant: ScheduleInvariant) -> ScheduleAudit {
+    ScheduleAudit { passed: false, violation: Some(invariant) }
+}
+
+#[tokio::test]
+async fn healthy_schedule_passes() {
+    let mut ctx = start().await;
+    let s = schedule(&mut ctx, 1, 1_000).await;
+    let passed = ScheduleAudit { passed: true, violation: None };
+    assert_eq!(audit(&mut ctx, &s).await, passed);
+
+    // Still healthy once part of it has been claimed
+    warp_to(&mut ctx, s.unlock).await;
+    send(&mut ctx, &[withdraw_ix(s.vesting, s.mint, s.beneficiary.pubkey(), 400)], &[&s.beneficiary]).await.unwrap();
+    assert_eq!(audit(&mut ctx, &s).await, passed);
+}
+
+#[tokio::test]
+async fn underfunded_escrow_fails() {
+    let mut ctx = start().await;
+    let s = schedule(&mut ctx, 1, 600).await;
+    assert_eq!(audit(&mut ctx, &s).await, failed(ScheduleInvariant::EscrowCoversRemaining));
+}
+
+#[tokio::test]
+async fn desynced_state_reports_the_first_broken_invariant() {
+    let mut ctx = start().await;
+    let s = schedule(&mut ctx, 1, 1_000).await;
+
+    desync(&mut ctx, s.vesting, |state| state.decimals += 1).await;
+    assert_eq!(audit(&mut ctx, &s).await, failed(ScheduleInvariant::MintConsistent));
+
+    desync(&mut ctx, s.vesting, |state| state.bump = state.bump.wrapping_sub(1)).await;
+    assert_eq!(audit(&mut ctx, &s).await, failed(ScheduleInvariant::CanonicalBump));
+
+    // Over-withdrawal is checked before everything else
+    desync(&mut ctx, s.vesting, |state| state.withdrawn = state.total_amount + 1).await;
+    assert_eq!(audit(&mut ctx, &s).await, failed(ScheduleInvariant::WithdrawnWithinTotal));
+}
@@ -0,0 +1,747 @@
+//! Authorization matrix: every (instruction, role) pair, executed with the
+//! role as the transaction's only signer and checked against one table of
+//! expected outcomes. The same table renders AUTHORIZATION_MATRIX.md, and an
+//! instruction missing from it fails the suite.
+
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token::native_mint;
+use common::*;
+use cvt_vesting::{beneficiary_hash, VestingError, MIN_RECOVERY_TIMELOCK};
+use solana_program_test::ProgramTestContext;
+use std::process::Command;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+const SALT: [u8; 32] = [0x5a; 32];
+const NEW_BENEFICIARY: Pubkey = Pubkey::new_from_array([0x6e; 32]);
+const ROTATED_GUARDIAN: Pubkey = Pubkey::new_from_array([0x67; 32]);
+const COVERAGE_PROGRAM: Pubkey = Pubkey::new_from_array([0x63; 32]);
+const BRIDGE_CONSENSUS: Pubkey = Pubkey::new_from_array([0x7c; 32]);
+const BRIDGE_SALT: [u8; 32] = [0xb7; 32];
+
+const DOCUMENT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/AUTHORIZATION_MATRIX.md");
+const DOCUMENT_HEADER: &str = "\
+# Vesting authorization matrix
+
+Generated by `tests/authorization_matrix.rs` from the table it executes; after
+changing an instruction's authorization, regenerate with
+`UPDATE_AUTHORIZATION_MATRIX=1 cargo test --test authorization_matrix`.
+
+Each column is a role signing (and paying for) the transaction alone: `allowed`
+means the instruction succeeds, anything else is the error it fails with.
+Instructions without a signer slot need no authorization at all.
+
+";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Role {
+    /// Config authority, and authority of every schedule in the fixture
+    Authority,
+    Beneficiary,
+    /// Auto-claim delegate
+    Delegate,
+    /// Sole guardian, quorum of one
+    Guardian,
+    /// Config compliance authority
+    Compliance,
+    /// Unrelated token holder
+    Funder,
+}
+
+impl Role {
+    const ALL: [Role; 6] = [
+        Role::Authority,
+        Role::Beneficiary,
+        Role::Delegate,
+        Role::Guardian,
+        Role::Compliance,
+        Role::Funder,
+    ];
+}
+
+/// State an instruction needs to succeed for its allowed roles
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Stage {
+    /// No config yet
+    Bare,
+    Locked,
+    Unlocked,
+    /// Unlocked and fully withdrawn
+    Claimed,
+    /// A guardian recovery of the token and hashed schedules, past its timelock
+    RecoveryPending,
+    /// A guardian rotation past its timelock
+    RotationPending,
+    /// A mint migration awaiting the beneficiary
+    MigrationPending,
+    /// An open bridge deposit with its consensus mirror and tokens in place
+    BridgeArrived,
+    /// Past the token schedule's claim deadline
+    Expired,
+    /// A pending schedule past its activation time
+    PendingDue,
+    /// A fourth schedule, never funded
+    Unfunded,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Denied {
+    Vesting(VestingError),
+    Anchor(ErrorCode),
+}
+
+impl Denied {
+    fn code(self) -> u32 {
+        match self {
+            Denied::Vesting(error) => error.into(),
+            Denied::Anchor(error) => error.into(),
+        }
+    }
+
+    fn name(self) -> String {
+        match self {
+            Denied::Vesting(error) => format!("{:?}", error),
+            Denied::Anchor(error) => format!("{:?}", error),
+        }
+    }
+}
+
+const HAS_ONE: Denied = Denied::Anchor(ErrorCode::ConstraintHasOne);
+const ANYONE: &[Role] = &Role::ALL;
+
+struct Row {
+    name: &'static str,
+    /// Account the role signs as; None if the instruction takes no signer
+    signer: Option<&'static str>,
+    stage: Stage,
+    allowed: &'static [Role],
+    /// Error for every other role
+    denied: Option<Denied>,
+}
+
+impl Row {
+    fn restricted(name: &'static str, signer: &'static str, stage: Stage, allowed: &'static [Role], denied: Denied) -> Self {
+        Row { name, signer: Some(signer), stage, allowed, denied: Some(denied) }
+    }
+
+    fn open(name: &'static str, signer: &'static str, stage: Stage) -> Self {
+        Row { name, signer: Some(signer), stage, allowed: ANYONE, denied: None }
+    }
+
+    fn unsigned(name: &'static str, stage: Stage) -> Self {
+        Row { name, signer: None, stage, allowed: ANYONE, denied: None }
+    }
+
+    fn expected(&self, role: Role) -> Option<Denied> {
+        if self.allowed.contains(&role) {
+            None
+        } else {
+            self.denied
+        }
+    }
+}
+
+/// One variant per program instruction, in program order
+#[derive(Clone, Copy, Debug)]
+enum Ix {
+    CreateVesting,
+    Withdraw,
+    WithdrawHashed,
+    WithdrawSponsored,
+    AutoClaim,
+    WithdrawNative,
+    CloseVesting,
+    DepositTokens,
+    SetPause,
+    UpdateCategory,
+    IsClaimable,
+    VerifyTerms,
+    ProjectClaimable,
+    NextUnlockInfo,
+    AggregateBeneficiary,
+    ClaimableBatch,
+    GetLockDuration,
+    GetActualBalance,
+    SerializeState,
+    AuditSchedule,
+    InitializeConfig,
+    SetEventVerbosity,
+    SetAllowlistRoot,
+    SetMinCliff,
+    SetMaxPauseDuration,
+    SetCircuitBreakerDuration,
+    TripCircuitBreaker,
+    SetMaxSchedulesPerAuthority,
+    SetDustThreshold,
+    SetOraclePolicy,
+    SetKycGate,
+    SetKycExempt,
+    SetAutoClaim,
+    SetCoveragePrograms,
+    SetDestinationPrograms,
+    SetBridgeConsensusProgram,
+    RegisterCoverage,
+    IsAllowlisted,
+    GuardianRecoverBeneficiary,
+    ExecuteBeneficiaryRecovery,
+    RotateGuardians,
+    ExecuteGuardianRotation,
+    CancelGuardianRotation,
+    MigrateMint,
+    AcceptMintMigration,
+    CancelMintMigration,
+    ExtendUnlock,
+    ObjectBeneficiaryRecovery,
+    ObjectBeneficiaryRecoveryHashed,
+    OpenBridgeDeposit,
+    DepositFromBridge,
+    ExpireAndReturn,
+    RegisterPendingSchedule,
+    ActivateSchedule,
+    CancelPendingSchedule,
+    CancelUnfunded,
+}
+
+impl Ix {
+    const ALL: [Ix; 56] = [
+        Ix::CreateVesting,
+        Ix::Withdraw,
+        Ix::WithdrawHashed,
+        Ix::WithdrawSponsored,
+        Ix::AutoClaim,
+        Ix::WithdrawNative,
+        Ix::CloseVesting,
+        Ix::DepositTokens,
+        Ix::SetPause,
+        Ix::UpdateCategory,
+        Ix::IsClaimable,
+        Ix::VerifyTerms,
+        Ix::ProjectClaimable,
+        Ix::NextUnlockInfo,
+        Ix::AggregateBeneficiary,
+        Ix::ClaimableBatch,
+        Ix::GetLockDuration,
+        Ix::GetActualBalance,
+        Ix::SerializeState,
+        Ix::AuditSchedule,
+        Ix::InitializeConfig,
+        Ix::SetEventVerbosity,
+        Ix::SetAllowlistRoot,
+        Ix::SetMinCliff,
+        Ix::SetMaxPauseDuration,
+        Ix::SetCircuitBreakerDuration,
+        Ix::TripCircuitBreaker,
+        Ix::SetMaxSchedulesPerAuthority,
+        Ix::SetDustThreshold,
+        Ix::SetOraclePolicy,
+        Ix::SetKycGate,
+        Ix::SetKycExempt,
+        Ix::SetAutoClaim,
+        Ix::SetCoveragePrograms,
+        Ix::SetDestinationPrograms,
+        Ix::SetBridgeConsensusProgram,
+        Ix::RegisterCoverage,
+        Ix::IsAllowlisted,
+        Ix::GuardianRecoverBeneficiary,
+        Ix::ExecuteBeneficiaryRecovery,
+        Ix::RotateGuardians,
+        Ix::ExecuteGuardianRotation,
+        Ix::CancelGuardianRotation,
+        Ix::MigrateMint,
+        Ix::AcceptMintMigration,
+        Ix::CancelMintMigration,
+        Ix::ExtendUnlock,
+        Ix::ObjectBeneficiaryRecovery,
+        Ix::ObjectBeneficiaryRecoveryHashed,
+        Ix::OpenBridgeDeposit,
+        Ix::DepositFromBridge,
+        Ix::ExpireAndReturn,
+        Ix::RegisterPendingSchedule,
+        Ix::ActivateSchedule,
+        Ix::CancelPendingSchedule,
+        Ix::CancelUnfunded,
+    ];
+
+    /// The matrix itself
+    fn row(self) -> Row {
+        use Role::*;
+        use Stage::*;
+        let unauthorized = Denied::Vesting(VestingError::Unauthorized);
+        let config = |name| Row::restricted(name, "authority", Locked, &[Authority], HAS_ONE);
+        match self {
+            Ix::CreateVesting => Row::open("create_vesting", "payer", Locked),
+            Ix::Withdraw => Row::restricted("withdraw", "beneficiary", Unlocked, &[Beneficiary], unauthorized),
+            Ix::WithdrawHashed => Row::restricted(
+                "withdraw_hashed",
+                "beneficiary",
+                Unlocked,
+                &[Beneficiary],
+                Denied::Vesting(VestingError::InvalidBeneficiarySalt),
+            ),
+            Ix::WithdrawSponsored => {
+                Row::restricted("withdraw_sponsored", "beneficiary", Unlocked, &[Beneficiary], unauthorized)
+            }
+            Ix::AutoClaim => Row::restricted(
+                "auto_claim",
+                "delegate",
+                Unlocked,
+                &[Delegate],
+                Denied::Vesting(VestingError::AutoClaimNotAuthorized),
+            ),
+            Ix::WithdrawNative => Row::restricted("withdraw_native", "beneficiary", Unlocked, &[Beneficiary], HAS_ONE),
+            Ix::CloseVesting => Row::restricted("close_vesting", "beneficiary", Claimed, &[Beneficiary], HAS_ONE),
+            Ix::DepositTokens => Row::open("deposit_tokens", "depositor", Locked),
+            Ix::SetPause => config("set_pause"),
+            Ix::UpdateCategory => config("update_category"),
+            Ix::IsClaimable => Row::unsigned("is_claimable", Locked),
+            Ix::VerifyTerms => Row::unsigned("verify_terms", Locked),
+            Ix::ProjectClaimable => Row::unsigned("project_claimable", Locked),
+            Ix::NextUnlockInfo => Row::unsigned("next_unlock_info", Locked),
+            Ix::AggregateBeneficiary => Row::unsigned("aggregate_beneficiary", Locked),
+            Ix::ClaimableBatch => Row::unsigned("claimable_batch", Locked),
+            Ix::GetLockDuration => Row::unsigned("get_lock_duration", Locked),
+            Ix::GetActualBalance => Row::unsigned("get_actual_balance", Locked),
+            Ix::SerializeState => Row::unsigned("serialize_state", Locked),
+            Ix::AuditSchedule => Row::unsigned("audit_schedule", Locked),
+            // First caller becomes the config authority
+            Ix::InitializeConfig => Row::open("initialize_config", "authority", Bare),
+            Ix::SetEventVerbosity => config("set_event_verbosity"),
+            Ix::SetAllowlistRoot => config("set_allowlist_root"),
+            Ix::SetMinCliff => config("set_min_cliff"),
+            Ix::SetMaxPauseDuration => config("set_max_pause_duration"),
+            Ix::SetCircuitBreakerDuration => config("set_circuit_breaker_duration"),
+            Ix::TripCircuitBreaker => config("trip_circuit_breaker"),
+            Ix::SetMaxSchedulesPerAuthority => config("set_max_schedules_per_authority"),
+            Ix::SetDustThreshold => config("set_dust_threshold"),
+            Ix::SetOraclePolicy => config("set_oracle_policy"),
+            Ix::SetKycGate => config("set_kyc_gate"),
+            Ix::SetKycExempt => Row::restricted("set_kyc_exempt", "compliance_authority", Locked, &[Compliance], HAS_ONE),
+            Ix::SetAutoClaim => Row::restricted("set_auto_claim", "beneficiary", Locked, &[Beneficiary], HAS_ONE),
+            Ix::SetCoveragePrograms => config("set_coverage_programs"),
+            Ix::SetDestinationPrograms => config("set_destination_programs"),
+            Ix::SetBridgeConsensusProgram => config("set_bridge_consensus_program"),
+            Ix::RegisterCoverage => Row::restricted("register_coverage", "beneficiary", Locked, &[Beneficiary], HAS_ONE),
+            Ix::IsAllowlisted => Row::unsigned("is_allowlisted", Locked),
+            Ix::GuardianRecoverBeneficiary => Row::restricted(
+                "guardian_recover_beneficiary",
+                "proposer",
+                Locked,
+                &[Guardian],
+                Denied::Vesting(VestingError::InsufficientGuardianApprovals),
+            ),
+            Ix::ExecuteBeneficiaryRecovery => Row::unsigned("execute_beneficiary_recovery", RecoveryPending),
+            Ix::RotateGuardians => Row::restricted(
+                "rotate_guardians",
+                "proposer",
+                Locked,
+                &[Guardian],
+                Denied::Vesting(VestingError::InsufficientGuardianApprovals),
+            ),
+            Ix::ExecuteGuardianRotation => Row::unsigned("execute_guardian_rotation", RotationPending),
+            Ix::CancelGuardianRotation => Row::restricted(
+                "cancel_guardian_rotation",
+                "guardian",
+                RotationPending,
+                &[Guardian],
+                Denied::Vesting(VestingError::InsufficientGuardianApprovals),
+            ),
+            Ix::MigrateMint => Row::restricted("migrate_mint", "authority", Locked, &[Authority], HAS_ONE),
+            Ix::AcceptMintMigration => {
+                Row::restricted("accept_mint_migration", "beneficiary", MigrationPending, &[Beneficiary], HAS_ONE)
+            }
+            Ix::CancelMintMigration => {
+                Row::restricted("cancel_mint_migration", "authority", MigrationPending, &[Authority], HAS_ONE)
+            }
+            Ix::ExtendUnlock => Row::restricted("extend_unlock", "authority", Locked, &[Authority], HAS_ONE),
+            Ix::ObjectBeneficiaryRecovery => Row::restricted(
+                "object_beneficiary_recovery",
+                "beneficiary",
+                RecoveryPending,
+                &[Beneficiary],
+                unauthorized,
+            ),
+            Ix::ObjectBeneficiaryRecoveryHashed => Row::restricted(
+                "object_beneficiary_recovery_hashed",
+                "beneficiary",
+                RecoveryPending,
+                &[Beneficiary],
+                Denied::Vesting(VestingError::InvalidBeneficiarySalt),
+            ),
+            Ix::OpenBridgeDeposit => Row::restricted("open_bridge_deposit", "authority", Locked, &[Authority], HAS_ONE),
+            Ix::DepositFromBridge => Row::unsigned("deposit_from_bridge", BridgeArrived),
+            Ix::ExpireAndReturn => Row::unsigned("expire_and_return", Expired),
+            Ix::RegisterPendingSchedule => Row::open("register_pending_schedule", "authority", Locked),
+            Ix::ActivateSchedule => Row::open("activate_schedule", "payer", PendingDue),
+            Ix::CancelPendingSchedule => {
+                Row::restricted("cancel_pending_schedule", "authority", PendingDue, &[Authority], HAS_ONE)
+            }
+            Ix::CancelUnfunded => Row::restricted("cancel_unfunded", "authority", Unfunded, &[Authority], HAS_ONE),
+        }
+    }
+
+    /// The instruction with `role` in its signer slot (ignored if it has none)
+    fn build(self, world: &World, role: Role) -> Instruction {
+        let signer = world.key(role);
+        let authority = world.key(Role::Authority);
+        let beneficiary = world.key(Role::Beneficiary);
+        let (vesting, mint) = (world.vesting, world.mint);
+        match self {
+            Ix::CreateVesting => create_vesting_ix(signer, beneficiary, mint, 9, 79, world.unlock, 500),
+            Ix::Withdraw => withdraw_ix(vesting, mint, signer, 100),
+            Ix::WithdrawHashed => withdraw_hashed_ix(world.hashed_vesting, mint, signer, 100, SALT),
+            Ix::WithdrawSponsored => withdraw_sponsored_ix(vesting, mint, signer, signer, 100),
+            Ix::AutoClaim => auto_claim_ix(vesting, mint, beneficiary, signer, 100),
+            Ix::WithdrawNative => withdraw_native_ix(world.native_vesting, native_mint::ID, signer, 100),
+            Ix::CloseVesting => close_vesting_ix(vesting, mint, signer, authority),
+            Ix::DepositTokens => deposit_ix(vesting, mint, signer, 50),
+            Ix::SetPause => set_pause_ix(signer, vesting, true, false),
+            Ix::UpdateCategory => update_category_ix(signer, vesting, 3),
+            Ix::IsClaimable => is_claimable_ix(vesting),
+            Ix::VerifyTerms => verify_terms_ix(vesting, [0; 32]),
+            Ix::ProjectClaimable => project_claimable_ix(vesting, vec![world.unlock]),
+            Ix::NextUnlockInfo => next_unlock_info_ix(vesting),
+            Ix::AggregateBeneficiary => aggregate_beneficiary_ix(beneficiary, &[vesting]),
+            Ix::ClaimableBatch => claimable_batch_ix(&[vesting, world.hashed_vesting]),
+            Ix::GetLockDuration => get_lock_duration_ix(vesting),
+            Ix::GetActualBalance => get_actual_balance_ix(vesting, mint),
+            Ix::SerializeState => serialize_state_ix(vesting),
+            Ix::AuditSchedule => audit_schedule_ix(vesting, mint),
+            Ix::InitializeConfig => {
+                initialize_config_ix(signer, vec![world.key(Role::Guardian)], 1, MIN_RECOVERY_TIMELOCK)
+            }
+            Ix::SetEventVerbosity => set_event_verbosity_ix(signer, false),
+            Ix::SetAllowlistRoot => set_allowlist_root_ix(signer, [1; 32]),
+            Ix::SetMinCliff => set_min_cliff_ix(signer, DAY),
+            Ix::SetMaxPauseDuration => set_max_pause_duration_ix(signer, DAY),
+            Ix::SetCircuitBreakerDuration => set_circuit_breaker_duration_ix(signer, DAY),
+            Ix::TripCircuitBreaker => trip_circuit_breaker_ix(signer),
+            Ix::SetMaxSchedulesPerAuthority => set_max_schedules_per_authority_ix(signer, 10),
+            Ix::SetDustThreshold => set_dust_threshold_ix(signer, 5),
+            Ix::SetOraclePolicy => set_oracle_policy_ix(signer, None, 60, 100),
+            Ix::SetKycGate => set_kyc_gate_ix(signer, None, world.key(Role::Compliance)),
+            Ix::SetKycExempt => set_kyc_exempt_ix(signer, vesting, true),
+            Ix::SetAutoClaim => set_auto_claim_ix(vesting, signer, world.key(Role::Delegate), 500),
+            Ix::SetCoveragePrograms => set_coverage_programs_ix(signer, vec![]),
+            Ix::SetDestinationPrograms => set_destination_programs_ix(signer, vec![]),
+            Ix::SetBridgeConsensusProgram => set_bridge_consensus_program_ix(signer, Some(BRIDGE_CONSENSUS)),
+            Ix::RegisterCoverage => register_coverage_ix(vesting, signer, COVERAGE_PROGRAM, 1, world.unlock),
+            Ix::IsAllowlisted => is_allowlisted_ix(beneficiary, vec![]),
+            Ix::GuardianRecoverBeneficiary => propose_recovery_ix(world.keypair(role), &[], vesting, NEW_BENEFICIARY),
+            Ix::ExecuteBeneficiaryRecovery => execute_recovery_ix(vesting, world.key(Role::Guardian)),
+            Ix::RotateGuardians => {
+                rotate_guardians_ix(world.keypair(role), &[], vec![world.key(Role::Guardian), ROTATED_GUARDIAN], 1)
+            }
+            Ix::ExecuteGuardianRotation => execute_guardian_rotation_ix(world.key(Role::Guardian)),
+            Ix::CancelGuardianRotation => {
+                cancel_guardian_rotation_ix(world.keypair(role), &[], world.key(Role::Guardian))
+            }
+            Ix::MigrateMint => migrate_mint_ix(signer, vesting, mint, world.new_mint, 1, 1),
+            Ix::AcceptMintMigration => accept_mint_migration_ix(signer, authority, vesting, mint, world.new_mint),
+            Ix::CancelMintMigration => cancel_mint_migration_ix(signer, vesting, world.new_mint),
+            Ix::ExtendUnlock => extend_unlock_ix(signer, vesting, world.unlock + DAY),
+            Ix::ObjectBeneficiaryRecovery => Instruction {
+                program_id: cvt_vesting::ID,
+                accounts: cvt_vesting::accounts::ObjectRecovery {
+                    vesting,
+                    recovery: recovery_pda(&vesting),
+                    proposer: world.key(Role::Guardian),
+                    beneficiary: signer,
+                }
+                .to_account_metas(None),
+                data: cvt_vesting::instruction::ObjectBeneficiaryRecovery {}.data(),
+            },
+            Ix::ObjectBeneficiaryRecoveryHashed => {
+                object_recovery_hashed_ix(world.hashed_vesting, world.key(Role::Guardian), signer, SALT)
+            }
+            Ix::OpenBridgeDeposit => {
+                let operation = bridge_operation(&vesting, &mint, 100, &BRIDGE_SALT);
+                open_bridge_deposit_ix(signer, vesting, mint, operation, 100, BRIDGE_SALT)
+            }
+            Ix::DepositFromBridge => {
+                deposit_from_bridge_ix(vesting, mint, bridge_operation(&vesting, &mint, 100, &BRIDGE_SALT), BRIDGE_CONSENSUS)
+            }
+            Ix::ExpireAndReturn => {
+                expire_and_return_ix(vesting, mint, Some(get_associated_token_address(&authority, &mint)))
+            }
+            Ix::RegisterPendingSchedule => {
+                let terms = schedule_terms(10, 80, world.unlock + DAY, 50);
+                register_pending_schedule_ix(signer, beneficiary, mint, terms, world.unlock)
+            }
+            Ix::ActivateSchedule => activate_schedule_ix(signer, authority, beneficiary, mint, 4, 74, vec![]),
+            Ix::CancelPendingSchedule => {
+                with_schedule_count(cancel_pending_schedule_ix(signer, beneficiary, mint, 4, 74), signer, authority)
+            }
+            Ix::CancelUnfunded => {
+                let unfunded = vesting_pda(&beneficiary, &mint, 5, 75);
+                with_schedule_count(cancel_unfunded_ix(signer, unfunded, mint), signer, authority)
+            }
+        }
+    }
+}
+
+/// `ix` built for `signer` but carrying `authority`'s schedule count, which
+/// the program finds from the schedule rather than from whoever signs
+fn with_schedule_count(mut ix: Instruction, signer: Pubkey, authority: Pubkey) -> Instruction {
+    let signer_count = schedule_count_pda(&signer);
+    ix.accounts.iter_mut().find(|meta| meta.pubkey == signer_count).unwrap().pubkey = schedule_count_pda(&authority);
+    ix
+}
+
+/// A fresh cluster with one keypair per role and three schedules from the
+/// authority to the beneficiary: an extendable token schedule (with the
+/// delegate set up for auto-claim, expiring to the authority two years after
+/// unlock), a hashed one and a wSOL one, each escrowing 1,000 units. Every
+/// role holds 100 tokens of the mint and 1,000 of the new mint.
+struct World {
+    ctx: ProgramTestContext,
+    roles: Vec<Keypair>,
+    mint: Pubkey,
+    new_mint: Pubkey,
+    vesting: Pubkey,
+    hashed_vesting: Pubkey,
+    native_vesting: Pubkey,
+    unlock: i64,
+}
+
+impl World {
+    async fn new(stage: Stage) -> Self {
+        let mut ctx = start().await;
+        let mut roles = vec![Keypair::from_bytes(&ctx.payer.to_bytes()).unwrap()];
+        for _ in 1..Role::ALL.len() {
+            let role = Keypair::new();
+            fund(&mut ctx, &role.pubkey(), 1_000_000_000).await;
+            roles.push(role);
+        }
+        let key = |role: Role| roles[role as usize].pubkey();
+        let (authority, beneficiary) = (key(Role::Authority), key(Role::Beneficiary));
+
+        if stage != Stage::Bare {
+            let init = initialize_config_ix(authority, vec![key(Role::Guardian)], 1, MIN_RECOVERY_TIMELOCK);
+            let compliance = set_kyc_gate_ix(authority, None, key(Role::Compliance));
+            let coverage = set_coverage_programs_ix(authority, vec![COVERAGE_PROGRAM]);
+            let bridge = set_bridge_consensus_program_ix(authority, Some(BRIDGE_CONSENSUS));
+            send(&mut ctx, &[init, compliance, coverage, bridge], &[]).await.unwrap();
+        }
+
+        let mint = create_mint(&mut ctx).await;
+        let unlock = now(&mut ctx).await + DAY;
+        let data = cvt_vesting::instruction::CreateVesting {
+            max_unlock_timestamp: unlock + YEAR,
+            claim_deadline: unlock + 2 * YEAR,
+            expiry_sink: authority,
+            ..base_create(1, 71, unlock, 1_000)
+        };
+        let create = create_vesting_ix_with(authority, beneficiary, mint, data);
+        send(&mut ctx, &[create], &[]).await.unwrap();
+        let vesting = vesting_pda(&beneficiary, &mint, 1, 71);
+        let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+        mint_to(&mut ctx, &mint, &escrow, 1_000).await;
+
+        let hash = beneficiary_hash(&beneficiary, &SALT);
+        let data = cvt_vesting::instruction::CreateVesting { hashed_beneficiary: true, ..base_create(2, 72, unlock, 1_000) };
+        let create = create_vesting_ix_with(authority, hash, mint, data);
+        send(&mut ctx, &[create], &[]).await.unwrap();
+        let hashed_vesting = vesting_pda(&hash, &mint, 2, 72);
+        let escrow = create_ata(&mut ctx, &hashed_vesting, &mint).await;
+        mint_to(&mut ctx, &mint, &escrow, 1_000).await;
+
+        let create = create_vesting_ix(authority, beneficiary, native_mint::ID, 3, 73, unlock, 1_000);
+        send(&mut ctx, &[create], &[]).await.unwrap();
+        let native_vesting = vesting_pda(&beneficiary, &native_mint::ID, 3, 73);
+        let escrow = create_ata(&mut ctx, &native_vesting, &native_mint::ID).await;
+        wrap_sol(&mut ctx, &escrow, 1_000).await;
+
+        for role in Role::ALL {
+            let holding = create_ata(&mut ctx, &key(role), &mint).await;
+            mint_to(&mut ctx, &mint, &holding, 100).await;
+        }
+        let delegate = set_auto_claim_ix(vesting, beneficiary, key(Role::Delegate), 1_000);
+        send(&mut ctx, &[delegate], &[&roles[Role::Beneficiary as usize]]).await.unwrap();
+
+        let new_mint = create_mint(&mut ctx).await;
+        for role in Role::ALL {
+            let holding = create_ata(&mut ctx, &key(role), &new_mint).await;
+            mint_to(&mut ctx, &new_mint, &holding, 1_000).await;
+        }
+
+        let guardian = &roles[Role::Guardian as usize];
+        match stage {
+            Stage::Bare | Stage::Locked => {}
+            Stage::Unlocked => warp_to(&mut ctx, unlock).await,
+            Stage::Claimed => {
+                warp_to(&mut ctx, unlock).await;
+                let withdraw = withdraw_ix(vesting, mint, beneficiary, 1_000);
+                send(&mut ctx, &[withdraw], &[&roles[Role::Beneficiary as usize]]).await.unwrap();
+            }
+            Stage::RecoveryPending => {
+                let propose = propose_recovery_ix(guardian, &[], vesting, NEW_BENEFICIARY);
+                let propose_hashed = propose_recovery_ix(guardian, &[], hashed_vesting, NEW_BENEFICIARY);
+                send(&mut ctx, &[propose, propose_hashed], &[guardian]).await.unwrap();
+                warp_forward(&mut ctx, MIN_RECOVERY_TIMELOCK).await;
+            }
+            Stage::RotationPending => {
+                let propose = rotate_guardians_ix(guardian, &[], vec![guardian.pubkey(), ROTATED_GUARDIAN], 1);
+                send(&mut ctx, &[propose], &[guardian]).await.unwrap();
+                warp_forward(&mut ctx, MIN_RECOVERY_TIMELOCK).await;
+            }
+            Stage::MigrationPending => {
+                let propose = migrate_mint_ix(authority, vesting, mint, new_mint, 1, 1);
+                send(&mut ctx, &[propose], &[]).await.unwrap();
+            }
+            Stage::BridgeArrived => {
+                let operation = bridge_operation(&vesting, &mint, 100, &BRIDGE_SALT);
+                let open = open_bridge_deposit_ix(authority, vesting, mint, operation, 100, BRIDGE_SALT);
+                send(&mut ctx, &[open], &[]).await.unwrap();
+                set_consensus_mirror(&mut ctx, &BRIDGE_CONSENSUS, &operation, 0b011);
+                mint_to(&mut ctx, &mint, &get_associated_token_address(&vesting, &mint), 100).await;
+            }
+            Stage::Expired => warp_to(&mut ctx, unlock + 2 * YEAR).await,
+            Stage::PendingDue => {
+                let terms = schedule_terms(4, 74, unlock + DAY, 50);
+                let register = register_pending_schedule_ix(authority, beneficiary, mint, terms, unlock);
+                send(&mut ctx, &[register], &[]).await.unwrap();
+                warp_to(&mut ctx, unlock).await;
+            }
+            Stage::Unfunded => {
+                let create = create_vesting_ix(authority, beneficiary, mint, 5, 75, unlock, 1_000);
+                send(&mut ctx, &[create], &[]).await.unwrap();
+            }
+        }
+
+        World { ctx, roles, mint, new_mint, vesting, hashed_vesting, native_vesting, unlock }
+    }
+
+    fn keypair(&self, role: Role) -> &Keypair {
+        &self.roles[role as usize]
+    }
+
+    fn key(&self, role: Role) -> Pubkey {
+        self.keypair(role).pubkey()
+    }
+
+    /// `role` signs and pays for the transaction alone
+    async fn send_as(&mut self, ix: Instruction, role: Role) -> Result<(), TransactionError> {
+        let signer = &self.roles[role as usize];
+        let blockhash = self.ctx.banks_client.get_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(&[ix], Some(&signer.pubkey()), &[signer], blockhash);
+        self.ctx.banks_client.process_transaction_with_metadata(tx).await.unwrap().result
+    }
+}
+
+fn outcome_name(expected: Option<Denied>) -> String {
+    expected.map_or_else(|| "allowed".to_string(), Denied::name)
+}
+
+fn render() -> String {
+    let mut document = DOCUMENT_HEADER.to_string();
+    document.push_str("| Instruction | Signer |");
+    for role in Role::ALL {
+        document.push_str(&format!(" {:?} |", role));
+    }
+    document.push_str("\n|---|---|");
+    document.push_str(&"---|".repeat(Role::ALL.len()));
+    document.push('\n');
+    for ix in Ix::ALL {
+        let row = ix.row();
+        let signer = row.signer.map_or_else(|| "none".to_string(), |signer| format!("`{}`", signer));
+        document.push_str(&format!("| `{}` | {} |", row.name, signer));
+        for role in Role::ALL {
+            document.push_str(&format!(" {} |", outcome_name(row.expected(role))));
+        }
+        document.push('\n');
+    }
+    document
+}
+
+/// Set on the child process that checks one row, to the row's index in `Ix::ALL`
+const ROW_ENV: &str = "AUTHORIZATION_MATRIX_ROW";
+
+async fn assert_row(ix: Ix) {
+    let row = ix.row();
+    let mut mismatches = Vec::new();
+    for role in Role::ALL {
+        // A fresh cluster per pair, so an allowed call can't shape the next
+        let mut world = World::new(row.stage).await;
+        let instruction = ix.build(&world, role);
+        let discriminator = hash(format!("global:{}", row.name).as_bytes()).to_bytes();
+        assert_eq!(instruction.data[..8], discriminator[..8], "{:?} builds another instruction", ix);
+
+        let result = world.send_as(instruction, role).await;
+        let expected = row.expected(role);
+        let matches = match (&result, expected) {
+            (Ok(()), None) => true,
+            (Err(TransactionError::InstructionError(0, InstructionError::Custom(code))), Some(denied)) => {
+                *code == denied.code()
+            }
+            _ => false,
+        };
+        if !matches {
+            mismatches.push(format!("{} as {:?}: expected {}, got {:?}", row.name, role, outcome_name(expected), result));
+        }
+    }
+    assert!(mismatches.is_empty(), "authorization matrix mismatches:\n{}", mismatches.join("\n"));
+}
+
+#[test]
+fn every_role_gets_the_expected_outcome() {
+    if let Ok(index) = std::env::var(ROW_ENV) {
+        let ix = Ix::ALL[index.parse::<usize>().unwrap()];
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        runtime.block_on(assert_row(ix));
+        return;
+    }
+
+    // solana-program-test never frees a cluster (its bank forks and program
+    // cache hold each other) and every live one slows the next to start, so
+    // each row runs in a child process of this test binary
+    let mut failures = Vec::new();
+    for (index, ix) in Ix::ALL.iter().enumerate() {
+        let output = Command::new(std::env::current_exe().unwrap())
+            .args(["every_role_gets_the_expected_outcome", "--exact", "--test-threads=1"])
+            .env(ROW_ENV, index.to_string())
+            .output()
+            .unwrap();
+        if !output.status.success() {
+            failures.push(format!("{:?}:\n{}", ix, String::from_utf8_lossy(&output.stdout)));
+        }
+    }
+    assert!(failures.is_empty(), "authorization matrix rows failed:\n{}", failures.join("\n"));
+}
+
+#[test]
+fn every_program_instruction_is_in_the_matrix() {
+    let source = include_str!("../src/lib.rs");
+    let start = source.find("pub mod cvt_vesting {").expect("program module");
+    let end = start + source[start..].find("\n}\n").expect("end of program module");
+    let mut program: Vec<&str> = source[start..end]
+        .lines()
+        .filter_map(|line| line.strip_prefix("    pub fn "))
+        .map(|signature| signature.split(['(', '<']).next().unwrap())
+        .collect();
+    let mut matrix: Vec<&str> = Ix::ALL.iter().map(|ix| ix.row().name).collect();
+    program.sort_unstable();
+    matrix.sort_unstable();
+    assert_eq!(matrix, program, "add new instructions to Ix and its row");
+}
+
+#[test]
+fn document_matches_the_matrix() {
+    let rendered = render();
+    if std::env::var_os("UPDATE_AUTHORIZATION_MATRIX").is_some() {
+        std::fs::write(DOCUMENT, &rendered).unwrap();
+    }
+    let committed = std::fs::read_to_string(DOCUMENT).unwrap_or_default();
+    assert!(
+        committed == rendered,
+        "AUTHORIZATION_MATRIX.md is stale; regenerate with UPDATE_AUTHORIZATION_MATRIX=1"
+    );
+}
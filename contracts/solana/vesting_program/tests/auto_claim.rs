@@ -0,0 +1,92 @@
+//! Auto-claim delegation: a bot claims into the beneficiary's ATA up to the
+//! cap the beneficiary set, while the beneficiary stays unrestricted
+
+mod common;
+
+use anchor_spl::associated_token::get_associated_token_address;
+use common::*;
+use cvt_vesting::{Vesting, VestingError};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+struct Delegated {
+    vesting: Pubkey,
+    mint: Pubkey,
+    beneficiary: Keypair,
+    bot: Keypair,
+}
+
+/// An unlocked, funded 1,000-unit schedule whose beneficiary let `bot` claim up to 300
+async fn delegated(ctx: &mut ProgramTestContext) -> Delegated {
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    let bot = Keypair::new();
+    fund(ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    fund(ctx, &bot.pubkey(), 1_000_000_000).await;
+    let unlock = now(ctx).await + DAY;
+
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 90, unlock, 1_000);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 90);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &escrow, 1_000).await;
+    create_ata(ctx, &beneficiary.pubkey(), &mint).await;
+    let authorize = set_auto_claim_ix(vesting, beneficiary.pubkey(), bot.pubkey(), 300);
+    send(ctx, &[authorize], &[&beneficiary]).await.unwrap();
+    warp_to(ctx, unlock).await;
+
+    Delegated { vesting, mint, beneficiary, bot }
+}
+
+async fn bot_claim(ctx: &mut ProgramTestContext, schedule: &Delegated, amount: u64) -> Result<(), TransactionError> {
+    let beneficiary = schedule.beneficiary.pubkey();
+    let ix = auto_claim_ix(schedule.vesting, schedule.mint, beneficiary, schedule.bot.pubkey(), amount);
+    send(ctx, &[ix], &[&schedule.bot]).await
+}
+
+#[tokio::test]
+async fn delegate_claims_for_the_beneficiary_within_the_cap() {
+    let mut ctx = start().await;
+    let schedule = delegated(&mut ctx).await;
+    let beneficiary_ata = get_associated_token_address(&schedule.beneficiary.pubkey(), &schedule.mint);
+
+    bot_claim(&mut ctx, &schedule, 200).await.unwrap();
+    bot_claim(&mut ctx, &schedule, 100).await.unwrap();
+
+    let state: Vesting = fetch(&mut ctx, schedule.vesting).await;
+    assert_eq!((state.withdrawn, state.auto_claimed_total), (300, 300));
+    assert_eq!(token_balance(&mut ctx, &beneficiary_ata).await, 300);
+
+    // Anyone else is refused, even within the cap
+    let stranger = Keypair::new();
+    let ix = auto_claim_ix(schedule.vesting, schedule.mint, schedule.beneficiary.pubkey(), stranger.pubkey(), 1);
+    let result = send(&mut ctx, &[ix], &[&stranger]).await;
+    assert_vesting_error(result, VestingError::AutoClaimNotAuthorized);
+}
+
+#[tokio::test]
+async fn cap_stops_the_delegate_but_not_the_beneficiary() {
+    let mut ctx = start().await;
+    let schedule = delegated(&mut ctx).await;
+    let beneficiary = schedule.beneficiary.pubkey();
+
+    bot_claim(&mut ctx, &schedule, 250).await.unwrap();
+    assert_vesting_error(bot_claim(&mut ctx, &schedule, 51).await, VestingError::AutoClaimCapExceeded);
+
+    send(&mut ctx, &[withdraw_ix(schedule.vesting, schedule.mint, beneficiary, 700)], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+    bot_claim(&mut ctx, &schedule, 50).await.unwrap();
+    let state: Vesting = fetch(&mut ctx, schedule.vesting).await;
+    assert_eq!((state.withdrawn, state.auto_claimed_total), (1_000, 300));
+
+    // Revoking leaves the delegate with nothing
+    send(&mut ctx, &[set_auto_claim_ix(schedule.vesting, beneficiary, Pubkey::default(), 0)], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+    assert_vesting_error(bot_claim(&mut ctx, &schedule, 0).await, VestingError::AutoClaimNotAuthorized);
+}
@@ -0,0 +1,197 @@
+//! Bridge deposits: CVT bridged in from Ethereum is credited to a schedule
+//! once Trinity's consensus mirror proves the operation and the tokens have
+//! reached the escrow. The operation ID commits to the escrow and amount, and
+//! each operation is credited at most once.
+
+mod common;
+
+use anchor_spl::associated_token::get_associated_token_address;
+use common::*;
+use cvt_vesting::{BridgeDeposit, VestingError, MIN_RECOVERY_TIMELOCK};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const TRINITY: Pubkey = Pubkey::new_from_array([0x7c; 32]);
+const SALT: [u8; 32] = [0xb7; 32];
+const NEXT_SALT: [u8; 32] = [0xb8; 32];
+/// Arbitrum and Solana attested
+const TWO_CHAINS: u8 = 0b011;
+
+/// A config trusting TRINITY's mirrors and a schedule whose escrow holds
+/// `escrowed`; returns (vesting, mint, beneficiary)
+async fn bridged_schedule(ctx: &mut ProgramTestContext, escrowed: u64) -> (Pubkey, Pubkey, Keypair) {
+    let authority = ctx.payer.pubkey();
+    let init = initialize_config_ix(authority, vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(ctx, &[init, set_bridge_consensus_program_ix(authority, Some(TRINITY))], &[]).await.unwrap();
+
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    fund(ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(ctx).await + DAY;
+    let create = create_vesting_ix(authority, beneficiary.pubkey(), mint, 1, 85, unlock, 2_000);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 85);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &escrow, escrowed).await;
+    (vesting, mint, beneficiary)
+}
+
+#[tokio::test]
+async fn bridged_deposit_is_credited_once() {
+    let mut ctx = start().await;
+    let (vesting, mint, _) = bridged_schedule(&mut ctx, 300).await;
+    let authority = ctx.payer.pubkey();
+    let operation = bridge_operation(&vesting, &mint, 700, &SALT);
+    let open = || open_bridge_deposit_ix(authority, vesting, mint, operation, 700, SALT);
+    send(&mut ctx, &[open()], &[]).await.unwrap();
+    let credit = || deposit_from_bridge_ix(vesting, mint, operation, TRINITY);
+
+    // Neither consensus nor the tokens yet
+    assert_vesting_error(send(&mut ctx, &[credit()], &[]).await, VestingError::InvalidConsensusMirror);
+    set_consensus_mirror(&mut ctx, &TRINITY, &operation, TWO_CHAINS);
+    // Each retry is byte-identical, so it needs a fresh blockhash (warping advances a slot)
+    warp_forward(&mut ctx, 1).await;
+    assert_vesting_error(send(&mut ctx, &[credit()], &[]).await, VestingError::BridgeDepositNotArrived);
+
+    // The bridge mints into the schedule's escrow
+    let escrow = get_associated_token_address(&vesting, &mint);
+    mint_to(&mut ctx, &mint, &escrow, 700).await;
+    warp_forward(&mut ctx, 1).await;
+    send(&mut ctx, &[credit()], &[]).await.unwrap();
+    let deposit: BridgeDeposit = fetch(&mut ctx, bridge_deposit_pda(&vesting, &operation)).await;
+    assert_eq!((deposit.vesting, deposit.amount, deposit.escrow_before), (vesting, 700, 300));
+    assert!(deposit.credited_at > 0);
+
+    // Replays fail, whether crediting again or reopening the operation
+    warp_forward(&mut ctx, 1).await;
+    assert_vesting_error(send(&mut ctx, &[credit()], &[]).await, VestingError::BridgeDepositConsumed);
+    let result = send(&mut ctx, &[open()], &[]).await;
+    assert!(matches!(result, Err(TransactionError::InstructionError(0, _))), "{:?}", result);
+}
+
+#[tokio::test]
+async fn claims_since_opening_count_as_arrived() {
+    let mut ctx = start().await;
+    let (vesting, mint, beneficiary) = bridged_schedule(&mut ctx, 1_000).await;
+    let operation = bridge_operation(&vesting, &mint, 500, &SALT);
+    let open = open_bridge_deposit_ix(ctx.payer.pubkey(), vesting, mint, operation, 500, SALT);
+    send(&mut ctx, &[open], &[]).await.unwrap();
+    set_consensus_mirror(&mut ctx, &TRINITY, &operation, TWO_CHAINS);
+
+    warp_forward(&mut ctx, DAY).await;
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 400)], &[&beneficiary]).await.unwrap();
+    let escrow = get_associated_token_address(&vesting, &mint);
+    mint_to(&mut ctx, &mint, &escrow, 500).await;
+
+    // The escrow is 100 above its opening balance, but 400 left as a claim
+    assert_eq!(token_balance(&mut ctx, &escrow).await, 1_100);
+    send(&mut ctx, &[deposit_from_bridge_ix(vesting, mint, operation, TRINITY)], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn one_open_deposit_per_schedule() {
+    let mut ctx = start().await;
+    let (vesting, mint, _) = bridged_schedule(&mut ctx, 0).await;
+    let authority = ctx.payer.pubkey();
+    let operation = bridge_operation(&vesting, &mint, 700, &SALT);
+    send(&mut ctx, &[open_bridge_deposit_ix(authority, vesting, mint, operation, 700, SALT)], &[]).await.unwrap();
+
+    // A second deposit would count the same arrival against the same baseline
+    let next_operation = bridge_operation(&vesting, &mint, 700, &NEXT_SALT);
+    let second = || open_bridge_deposit_ix(authority, vesting, mint, next_operation, 700, NEXT_SALT);
+    assert_vesting_error(send(&mut ctx, &[second()], &[]).await, VestingError::BridgeDepositInFlight);
+
+    let escrow = get_associated_token_address(&vesting, &mint);
+    mint_to(&mut ctx, &mint, &escrow, 700).await;
+    set_consensus_mirror(&mut ctx, &TRINITY, &operation, TWO_CHAINS);
+    send(&mut ctx, &[deposit_from_bridge_ix(vesting, mint, operation, TRINITY)], &[]).await.unwrap();
+
+    // Once credited, the next one opens on top of it
+    warp_forward(&mut ctx, 1).await;
+    send(&mut ctx, &[second()], &[]).await.unwrap();
+    let deposit: BridgeDeposit = fetch(&mut ctx, bridge_deposit_pda(&vesting, &next_operation)).await;
+    assert_eq!(deposit.escrow_before, 700);
+    set_consensus_mirror(&mut ctx, &TRINITY, &next_operation, TWO_CHAINS);
+    let credit = deposit_from_bridge_ix(vesting, mint, next_operation, TRINITY);
+    assert_vesting_error(send(&mut ctx, &[credit], &[]).await, VestingError::BridgeDepositNotArrived);
+}
+
+#[tokio::test]
+async fn mirror_must_prove_the_operation() {
+    let mut ctx = start().await;
+    let (vesting, mint, _) = bridged_schedule(&mut ctx, 0).await;
+    let operation = bridge_operation(&vesting, &mint, 700, &SALT);
+    let open = open_bridge_deposit_ix(ctx.payer.pubkey(), vesting, mint, operation, 700, SALT);
+    send(&mut ctx, &[open], &[]).await.unwrap();
+    let escrow = get_associated_token_address(&vesting, &mint);
+    mint_to(&mut ctx, &mint, &escrow, 700).await;
+
+    // One attesting chain is short of consensus
+    set_consensus_mirror(&mut ctx, &TRINITY, &operation, 0b010);
+    let credit = deposit_from_bridge_ix(vesting, mint, operation, TRINITY);
+    assert_vesting_error(send(&mut ctx, std::slice::from_ref(&credit), &[]).await, VestingError::InvalidConsensusMirror);
+
+    // A well-formed mirror from a program other than the configured one
+    let impostor = Pubkey::new_unique();
+    set_consensus_mirror(&mut ctx, &impostor, &operation, TWO_CHAINS);
+    let forged = deposit_from_bridge_ix(vesting, mint, operation, impostor);
+    assert_vesting_error(send(&mut ctx, &[forged], &[]).await, VestingError::InvalidConsensusMirror);
+
+    // Without a configured program nothing is credited
+    let unset = set_bridge_consensus_program_ix(ctx.payer.pubkey(), None);
+    send(&mut ctx, &[unset], &[]).await.unwrap();
+    set_consensus_mirror(&mut ctx, &TRINITY, &operation, TWO_CHAINS);
+    warp_forward(&mut ctx, 1).await;
+    assert_vesting_error(send(&mut ctx, &[credit], &[]).await, VestingError::BridgeNotConfigured);
+}
+
+#[tokio::test]
+async fn operation_must_name_the_escrow_and_amount() {
+    let mut ctx = start().await;
+    let (vesting, mint, _) = bridged_schedule(&mut ctx, 0).await;
+    let authority = ctx.payer.pubkey();
+
+    // The operation bridged 700, so it can't be opened as a larger deposit
+    let operation = bridge_operation(&vesting, &mint, 700, &SALT);
+    let inflated = open_bridge_deposit_ix(authority, vesting, mint, operation, 7_000, SALT);
+    assert_vesting_error(send(&mut ctx, &[inflated], &[]).await, VestingError::BridgeOperationMismatch);
+
+    // Nor claimed by another schedule whose escrow it doesn't name
+    let other_beneficiary = Keypair::new().pubkey();
+    let create = create_vesting_ix(authority, other_beneficiary, mint, 2, 86, now(&mut ctx).await + DAY, 2_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let other = vesting_pda(&other_beneficiary, &mint, 2, 86);
+    create_ata(&mut ctx, &other, &mint).await;
+    let squatted = open_bridge_deposit_ix(authority, other, mint, operation, 700, SALT);
+    assert_vesting_error(send(&mut ctx, &[squatted], &[]).await, VestingError::BridgeOperationMismatch);
+
+    // Its own schedule still opens it
+    send(&mut ctx, &[open_bridge_deposit_ix(authority, vesting, mint, operation, 700, SALT)], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn plain_deposits_wait_for_the_open_bridge_deposit() {
+    let mut ctx = start().await;
+    let (vesting, mint, _) = bridged_schedule(&mut ctx, 0).await;
+    let authority = ctx.payer.pubkey();
+    let depositor_ata = create_ata(&mut ctx, &authority, &mint).await;
+    mint_to(&mut ctx, &mint, &depositor_ata, 700).await;
+    let operation = bridge_operation(&vesting, &mint, 700, &SALT);
+    send(&mut ctx, &[open_bridge_deposit_ix(authority, vesting, mint, operation, 700, SALT)], &[]).await.unwrap();
+
+    // A plain deposit would pass for the bridged tokens' arrival
+    let deposit = deposit_ix(vesting, mint, authority, 700);
+    assert_vesting_error(send(&mut ctx, std::slice::from_ref(&deposit), &[]).await, VestingError::BridgeDepositInFlight);
+
+    let escrow = get_associated_token_address(&vesting, &mint);
+    mint_to(&mut ctx, &mint, &escrow, 700).await;
+    set_consensus_mirror(&mut ctx, &TRINITY, &operation, TWO_CHAINS);
+    send(&mut ctx, &[deposit_from_bridge_ix(vesting, mint, operation, TRINITY)], &[]).await.unwrap();
+    warp_forward(&mut ctx, 1).await;
+    send(&mut ctx, &[deposit], &[]).await.unwrap();
+    assert_eq!(token_balance(&mut ctx, &escrow).await, 1_400);
+}
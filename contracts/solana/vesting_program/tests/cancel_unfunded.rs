@@ -0,0 +1,114 @@
+//! Unfunded schedules: the authority can take back a schedule no tokens have
+//! reached, with its rent and cap slot, but never one that was funded, nor
+//! while a recovery, mint migration or bridge deposit is pending on it
+
+mod common;
+
+use anchor_spl::associated_token::get_associated_token_address;
+use common::*;
+use cvt_vesting::{ScheduleCount, VestingError, MIN_RECOVERY_TIMELOCK};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+#[tokio::test]
+async fn authority_cancels_an_unfunded_schedule() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let authority = ctx.payer.pubkey();
+    let beneficiary = Pubkey::new_unique();
+    let unlock = now(&mut ctx).await + DAY;
+    let first = create_vesting_ix(authority, beneficiary, mint, 1, 86, unlock, 1_000);
+    let second = create_vesting_ix(authority, beneficiary, mint, 2, 86, unlock, 1_000);
+    send(&mut ctx, &[first, second], &[]).await.unwrap();
+    let bare = vesting_pda(&beneficiary, &mint, 1, 86);
+    let with_escrow = vesting_pda(&beneficiary, &mint, 2, 86);
+    let escrow = create_ata(&mut ctx, &with_escrow, &mint).await;
+
+    // Without a vesting ATA, and with an empty one (closed along with it)
+    for vesting in [bare, with_escrow] {
+        send(&mut ctx, &[cancel_unfunded_ix(authority, vesting, mint)], &[]).await.unwrap();
+        assert!(ctx.banks_client.get_account(vesting).await.unwrap().is_none());
+    }
+    assert!(ctx.banks_client.get_account(escrow).await.unwrap().is_none());
+    let count: ScheduleCount = fetch(&mut ctx, schedule_count_pda(&authority)).await;
+    assert_eq!(count.schedule_count, 0);
+}
+
+#[tokio::test]
+async fn funded_schedules_cannot_be_cancelled() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let authority = ctx.payer.pubkey();
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(&mut ctx).await + DAY;
+    let create = create_vesting_ix(authority, beneficiary.pubkey(), mint, 1, 87, unlock, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 87);
+    let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+    mint_to(&mut ctx, &mint, &escrow, 1).await;
+
+    let result = send(&mut ctx, &[cancel_unfunded_ix(authority, vesting, mint)], &[]).await;
+    assert_vesting_error(result, VestingError::InsufficientBalance);
+
+    // Nor once the beneficiary has drained it
+    warp_to(&mut ctx, unlock).await;
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 1)], &[&beneficiary]).await.unwrap();
+    assert_eq!(token_balance(&mut ctx, &get_associated_token_address(&vesting, &mint)).await, 0);
+    let result = send(&mut ctx, &[cancel_unfunded_ix(authority, vesting, mint)], &[]).await;
+    assert_vesting_error(result, VestingError::InsufficientBalance);
+    let count: ScheduleCount = fetch(&mut ctx, schedule_count_pda(&authority)).await;
+    assert_eq!(count.schedule_count, 1);
+}
+
+#[tokio::test]
+async fn pending_proposals_must_be_resolved_first() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let guardian = Keypair::new();
+    fund(&mut ctx, &guardian.pubkey(), 1_000_000_000).await;
+    let trinity = Pubkey::new_unique();
+    let init = initialize_config_ix(authority, vec![guardian.pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(&mut ctx, &[init, set_bridge_consensus_program_ix(authority, Some(trinity))], &[]).await.unwrap();
+
+    let mint = create_mint(&mut ctx).await;
+    let new_mint = create_mint(&mut ctx).await;
+    create_ata(&mut ctx, &authority, &new_mint).await;
+    let beneficiary = Pubkey::new_unique();
+    let unlock = now(&mut ctx).await + DAY;
+    let first = create_vesting_ix(authority, beneficiary, mint, 1, 88, unlock, 1_000);
+    let second = create_vesting_ix(authority, beneficiary, mint, 2, 88, unlock, 1_000);
+    send(&mut ctx, &[first, second], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary, &mint, 1, 88);
+    let bridged = vesting_pda(&beneficiary, &mint, 2, 88);
+    create_ata(&mut ctx, &vesting, &mint).await;
+    create_ata(&mut ctx, &bridged, &mint).await;
+
+    // A guardian recovery, until it's executed
+    let propose = propose_recovery_ix(&guardian, &[], vesting, Pubkey::new_unique());
+    send(&mut ctx, &[propose], &[&guardian]).await.unwrap();
+    let result = send(&mut ctx, &[cancel_unfunded_ix(authority, vesting, mint)], &[]).await;
+    assert_vesting_error(result, VestingError::ScheduleProposalPending);
+    warp_forward(&mut ctx, MIN_RECOVERY_TIMELOCK).await;
+    send(&mut ctx, &[execute_recovery_ix(vesting, guardian.pubkey())], &[]).await.unwrap();
+
+    // A mint migration, until it's withdrawn
+    send(&mut ctx, &[migrate_mint_ix(authority, vesting, mint, new_mint, 1, 1)], &[]).await.unwrap();
+    let result = send(&mut ctx, &[cancel_unfunded_ix(authority, vesting, mint)], &[]).await;
+    assert_vesting_error(result, VestingError::ScheduleProposalPending);
+    send(&mut ctx, &[cancel_mint_migration_ix(authority, vesting, new_mint)], &[]).await.unwrap();
+
+    warp_forward(&mut ctx, 1).await;
+    send(&mut ctx, &[cancel_unfunded_ix(authority, vesting, mint)], &[]).await.unwrap();
+    assert!(ctx.banks_client.get_account(vesting).await.unwrap().is_none());
+
+    // An open bridge deposit: its tokens are on their way to this schedule
+    let operation = bridge_operation(&bridged, &mint, 1_000, &[0xb9; 32]);
+    let open = open_bridge_deposit_ix(authority, bridged, mint, operation, 1_000, [0xb9; 32]);
+    send(&mut ctx, &[open], &[]).await.unwrap();
+    let result = send(&mut ctx, &[cancel_unfunded_ix(authority, bridged, mint)], &[]).await;
+    assert_vesting_error(result, VestingError::BridgeDepositInFlight);
+    assert!(ctx.banks_client.get_account(bridged).await.unwrap().is_some());
+}
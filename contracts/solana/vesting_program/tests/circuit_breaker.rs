@@ -0,0 +1,100 @@
+//! Program-wide circuit breaker: the config authority blocks claims from
+//! every schedule at once, and claims resume on their own after the
+//! configured duration
+
+mod common;
+
+use common::*;
+use cvt_vesting::{
+    RecoveryProposal, VestingConfig, VestingError, DEFAULT_CIRCUIT_BREAKER_DURATION, MAX_CIRCUIT_BREAKER_DURATION,
+    MIN_RECOVERY_TIMELOCK,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const HOUR: i64 = 60 * 60;
+
+struct Schedule {
+    vesting: Pubkey,
+    mint: Pubkey,
+    beneficiary: Keypair,
+}
+
+/// Unlocked, funded 1,000-unit schedule `id`
+async fn unlocked_schedule(ctx: &mut ProgramTestContext, id: u64) -> Schedule {
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    fund(ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(ctx).await + DAY;
+    let payer = ctx.payer.pubkey();
+    send(ctx, &[create_vesting_ix(payer, beneficiary.pubkey(), mint, id, 99, unlock, 1_000)], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, id, 99);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &escrow, 1_000).await;
+    warp_to(ctx, unlock).await;
+    Schedule { vesting, mint, beneficiary }
+}
+
+async fn withdraw(ctx: &mut ProgramTestContext, schedule: &Schedule, amount: u64) -> Result<(), TransactionError> {
+    let withdraw = withdraw_ix(schedule.vesting, schedule.mint, schedule.beneficiary.pubkey(), amount);
+    send(ctx, &[withdraw], &[&schedule.beneficiary]).await
+}
+
+#[tokio::test]
+async fn tripped_breaker_blocks_every_schedule_until_it_resets() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let guardian = Keypair::new();
+    fund(&mut ctx, &guardian.pubkey(), 1_000_000_000).await;
+    let init = initialize_config_ix(authority, vec![guardian.pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(&mut ctx, &[init], &[]).await.unwrap();
+    let first = unlocked_schedule(&mut ctx, 1).await;
+    let second = unlocked_schedule(&mut ctx, 2).await;
+    withdraw(&mut ctx, &first, 100).await.unwrap();
+
+    send(&mut ctx, &[trip_circuit_breaker_ix(authority)], &[]).await.unwrap();
+    let config: VestingConfig = fetch(&mut ctx, config_pda()).await;
+    assert_eq!(config.circuit_breaker_until, now(&mut ctx).await + DEFAULT_CIRCUIT_BREAKER_DURATION);
+
+    assert_vesting_error(withdraw(&mut ctx, &first, 100).await, VestingError::CircuitBreakerTripped);
+    assert_vesting_error(withdraw(&mut ctx, &second, 100).await, VestingError::CircuitBreakerTripped);
+    let sponsored = withdraw_sponsored_ix(second.vesting, second.mint, second.beneficiary.pubkey(), authority, 100);
+    assert_vesting_error(send(&mut ctx, &[sponsored], &[&second.beneficiary]).await, VestingError::CircuitBreakerTripped);
+
+    // Emergency actions still go through
+    let propose = propose_recovery_ix(&guardian, &[], second.vesting, Pubkey::new_unique());
+    send(&mut ctx, &[propose], &[&guardian]).await.unwrap();
+    let recovery: RecoveryProposal = fetch(&mut ctx, recovery_pda(&second.vesting)).await;
+    assert_eq!(recovery.vesting, second.vesting);
+
+    warp_forward(&mut ctx, DEFAULT_CIRCUIT_BREAKER_DURATION - 1).await;
+    assert_vesting_error(withdraw(&mut ctx, &first, 100).await, VestingError::CircuitBreakerTripped);
+    warp_forward(&mut ctx, 1).await;
+    withdraw(&mut ctx, &first, 100).await.unwrap();
+    withdraw(&mut ctx, &second, 100).await.unwrap();
+}
+
+#[tokio::test]
+async fn breaker_duration_is_configurable_within_bounds() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let init = initialize_config_ix(authority, vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(&mut ctx, &[init], &[]).await.unwrap();
+    let schedule = unlocked_schedule(&mut ctx, 1).await;
+
+    for invalid in [0, MAX_CIRCUIT_BREAKER_DURATION + 1] {
+        let result = send(&mut ctx, &[set_circuit_breaker_duration_ix(authority, invalid)], &[]).await;
+        assert_vesting_error(result, VestingError::InvalidCircuitBreakerDuration);
+    }
+
+    send(&mut ctx, &[set_circuit_breaker_duration_ix(authority, HOUR), trip_circuit_breaker_ix(authority)], &[])
+        .await
+        .unwrap();
+    assert_vesting_error(withdraw(&mut ctx, &schedule, 100).await, VestingError::CircuitBreakerTripped);
+    warp_forward(&mut ctx, HOUR).await;
+    withdraw(&mut ctx, &schedule, 100).await.unwrap();
+}
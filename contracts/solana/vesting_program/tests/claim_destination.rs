@@ -0,0 +1,143 @@
+//! Claim destinations: withdrawals default to the beneficiary's ATA
+//! (created if missing) or, for allowlisted destination programs, go to a
+//! token account owned by one of their PDAs (e.g. a staking vault); exactly
+//! one of the two is passed
+
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address;
+use common::*;
+use cvt_vesting::{VestingError, MIN_RECOVERY_TIMELOCK};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::AccountSharedData,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+struct Claimable {
+    vesting: Pubkey,
+    mint: Pubkey,
+    beneficiary: Keypair,
+    staking_program: Pubkey,
+}
+
+/// An unlocked, funded 1,000-unit schedule under a config that allowlists
+/// one staking program as a claim destination
+async fn claimable(ctx: &mut ProgramTestContext) -> Claimable {
+    let authority = ctx.payer.pubkey();
+    let staking_program = Pubkey::new_unique();
+    let init = initialize_config_ix(authority, vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(ctx, &[init, set_destination_programs_ix(authority, vec![staking_program])], &[]).await.unwrap();
+
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    fund(ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(ctx).await + DAY;
+    let create = create_vesting_ix(authority, beneficiary.pubkey(), mint, 1, 55, unlock, 1_000);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 55);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &escrow, 1_000).await;
+    warp_to(ctx, unlock).await;
+
+    Claimable { vesting, mint, beneficiary, staking_program }
+}
+
+/// A staking vault PDA of `program` holding a token account for `mint`
+async fn staking_vault(ctx: &mut ProgramTestContext, program: &Pubkey, mint: &Pubkey) -> (Pubkey, Pubkey) {
+    let (vault, _) = Pubkey::find_program_address(&[b"stake_vault", mint.as_ref()], program);
+    ctx.set_account(&vault, &AccountSharedData::new(1_000_000_000, 8, program));
+    let destination = create_ata(ctx, &vault, mint).await;
+    (vault, destination)
+}
+
+#[tokio::test]
+async fn claims_into_an_allowlisted_program_pda() {
+    let mut ctx = start().await;
+    let schedule = claimable(&mut ctx).await;
+    let (vault, destination) = staking_vault(&mut ctx, &schedule.staking_program, &schedule.mint).await;
+
+    let beneficiary = schedule.beneficiary.pubkey();
+    let ix = withdraw_to_ix(schedule.vesting, schedule.mint, beneficiary, destination, vault, 400);
+    send(&mut ctx, &[ix], &[&schedule.beneficiary]).await.unwrap();
+    assert_eq!(token_balance(&mut ctx, &destination).await, 400);
+
+    // Claiming elsewhere doesn't create the beneficiary's ATA
+    let ata = get_associated_token_address(&beneficiary, &schedule.mint);
+    assert!(ctx.banks_client.get_account(ata).await.unwrap().is_none());
+
+    // The beneficiary's own ATA still works without a destination owner
+    send(&mut ctx, &[withdraw_ix(schedule.vesting, schedule.mint, beneficiary, 600)], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn defaults_to_the_beneficiary_ata() {
+    let mut ctx = start().await;
+    let schedule = claimable(&mut ctx).await;
+    let beneficiary = schedule.beneficiary.pubkey();
+    let ata = get_associated_token_address(&beneficiary, &schedule.mint);
+    assert!(ctx.banks_client.get_account(ata).await.unwrap().is_none());
+
+    // No explicit destination: the ATA is created and receives the claim
+    send(&mut ctx, &[withdraw_ix(schedule.vesting, schedule.mint, beneficiary, 400)], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+    assert_eq!(token_balance(&mut ctx, &ata).await, 400);
+
+    // Once it exists, later claims reuse it
+    send(&mut ctx, &[withdraw_ix(schedule.vesting, schedule.mint, beneficiary, 600)], &[&schedule.beneficiary])
+        .await
+        .unwrap();
+    assert_eq!(token_balance(&mut ctx, &ata).await, 1_000);
+}
+
+#[tokio::test]
+async fn other_destinations_are_refused() {
+    let mut ctx = start().await;
+    let schedule = claimable(&mut ctx).await;
+    let beneficiary = schedule.beneficiary.pubkey();
+    let claim =
+        |destination, owner| withdraw_to_ix(schedule.vesting, schedule.mint, beneficiary, destination, owner, 1);
+
+    // A PDA of a program that isn't allowlisted
+    let (vault, destination) = staking_vault(&mut ctx, &Pubkey::new_unique(), &schedule.mint).await;
+    let result = send(&mut ctx, &[claim(destination, vault)], &[&schedule.beneficiary]).await;
+    assert_vesting_error(result, VestingError::ClaimDestinationNotAllowed);
+
+    // Someone else's wallet, vouched for by an unrelated allowlisted PDA
+    let (vault, _) = staking_vault(&mut ctx, &schedule.staking_program, &schedule.mint).await;
+    let wallet = create_ata(&mut ctx, &Keypair::new().pubkey(), &schedule.mint).await;
+    let result = send(&mut ctx, &[claim(wallet, vault)], &[&schedule.beneficiary]).await;
+    assert_vesting_error(result, VestingError::ClaimDestinationNotAllowed);
+}
+
+#[tokio::test]
+async fn exactly_one_destination_is_passed() {
+    let mut ctx = start().await;
+    let schedule = claimable(&mut ctx).await;
+    let (vault, destination) = staking_vault(&mut ctx, &schedule.staking_program, &schedule.mint).await;
+    let beneficiary = schedule.beneficiary.pubkey();
+    let claim = |beneficiary_ata: Option<Pubkey>, destination: Option<Pubkey>| Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::Withdraw {
+            beneficiary_ata,
+            destination,
+            destination_owner: destination.map(|_| vault),
+            ..withdraw_accounts(schedule.vesting, schedule.mint, beneficiary, None, None)
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::Withdraw { amount: 1 }.data(),
+    };
+
+    let ata = get_associated_token_address(&beneficiary, &schedule.mint);
+    for (beneficiary_ata, destination) in [(None, None), (Some(ata), Some(destination))] {
+        let result = send(&mut ctx, &[claim(beneficiary_ata, destination)], &[&schedule.beneficiary]).await;
+        assert_vesting_error(result, VestingError::ClaimDestinationRequired);
+    }
+    assert_eq!(token_balance(&mut ctx, &destination).await, 0);
+}
@@ -0,0 +1,135 @@
+//! Clock regression across leaders must never un-vest or double-vest, nor
+//! rewind the schedule's lock and coverage accounting
+
+mod common;
+
+use common::*;
+use cvt_vesting::{lock_token_seconds, weighted_lock_start, Vesting, VestingError, MIN_RECOVERY_TIMELOCK};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+#[tokio::test]
+async fn regressed_clock_is_clamped_to_last_observed() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+
+    let created_at = now(&mut ctx).await;
+    let unlock = created_at + YEAR;
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 9, unlock, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 9);
+    let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+    let beneficiary_ata = create_ata(&mut ctx, &beneficiary.pubkey(), &mint).await;
+    mint_to(&mut ctx, &mint, &escrow, 1_000).await;
+
+    let state: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!(state.last_observed_timestamp, created_at);
+
+    // Withdraw just after unlock, recording the observed time
+    warp_to(&mut ctx, unlock + DAY).await;
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 400)], &[&beneficiary])
+        .await
+        .unwrap();
+    let state: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!(state.last_observed_timestamp, unlock + DAY);
+
+    // Clock regresses below the unlock: the schedule stays unlocked
+    warp_to(&mut ctx, unlock - DAY).await;
+    assert!(view::<bool>(&mut ctx, is_claimable_ix(vesting)).await);
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 500)], &[&beneficiary])
+        .await
+        .unwrap();
+
+    // The observed time never moves backwards
+    let state: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!(state.last_observed_timestamp, unlock + DAY);
+    assert_eq!(state.withdrawn, 900);
+
+    // No double-vesting: only the remainder is claimable
+    let result = send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 101)], &[&beneficiary]).await;
+    assert_vesting_error(result, VestingError::InsufficientBalance);
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 100)], &[&beneficiary])
+        .await
+        .unwrap();
+
+    assert_eq!(token_balance(&mut ctx, &beneficiary_ata).await, 1_000);
+    assert_eq!(token_balance(&mut ctx, &escrow).await, 0);
+    assert!(!view::<bool>(&mut ctx, is_claimable_ix(vesting)).await);
+
+    // Once the clock catches up it advances the observed time again
+    warp_to(&mut ctx, unlock + 2 * DAY).await;
+    let result = send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 1)], &[&beneficiary]).await;
+    assert_vesting_error(result, VestingError::InsufficientBalance);
+}
+
+#[tokio::test]
+async fn regression_before_unlock_stays_locked() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+
+    let unlock = now(&mut ctx).await + YEAR;
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 9, unlock, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 9);
+    let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+    create_ata(&mut ctx, &beneficiary.pubkey(), &mint).await;
+    mint_to(&mut ctx, &mint, &escrow, 1_000).await;
+
+    // Clamping only holds time at what was observed; it never fast-forwards
+    warp_to(&mut ctx, unlock - DAY).await;
+    let result = send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 1)], &[&beneficiary]).await;
+    assert_vesting_error(result, VestingError::StillLocked);
+    warp_to(&mut ctx, unlock - 2 * DAY).await;
+    assert!(!view::<bool>(&mut ctx, is_claimable_ix(vesting)).await);
+}
+
+#[tokio::test]
+async fn regressed_clock_does_not_rewind_lock_or_coverage_accounting() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let insurer = Pubkey::new_unique();
+    let init = initialize_config_ix(authority, vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(&mut ctx, &[init, set_coverage_programs_ix(authority, vec![insurer])], &[]).await.unwrap();
+
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let created_at = now(&mut ctx).await;
+    let unlock = created_at + DAY;
+    let create = create_vesting_ix(authority, beneficiary.pubkey(), mint, 1, 10, unlock, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 10);
+    let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+    create_ata(&mut ctx, &beneficiary.pubkey(), &mint).await;
+    mint_to(&mut ctx, &mint, &escrow, 900).await;
+    let depositor_ata = create_ata(&mut ctx, &authority, &mint).await;
+    mint_to(&mut ctx, &mint, &depositor_ata, 100).await;
+
+    let observed = unlock + 10 * DAY;
+    warp_to(&mut ctx, observed).await;
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 100)], &[&beneficiary]).await.unwrap();
+
+    // The clock falls back below a policy's expiry the schedule already passed
+    warp_to(&mut ctx, unlock + DAY).await;
+    let lapsed = register_coverage_ix(vesting, beneficiary.pubkey(), insurer, 1, unlock + 5 * DAY);
+    let result = send(&mut ctx, &[lapsed], &[&beneficiary]).await;
+    assert_vesting_error(result, VestingError::InvalidCoverage);
+
+    // A deposit and a claim on the regressed clock are booked at the observed time
+    send(&mut ctx, &[deposit_ix(vesting, mint, authority, 100)], &[]).await.unwrap();
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 200)], &[&beneficiary]).await.unwrap();
+    let state: Vesting = fetch(&mut ctx, vesting).await;
+    let start = weighted_lock_start(created_at, 800, 100, observed);
+    assert_eq!(state.deposit_weighted_start, start);
+    assert_eq!(
+        state.lock_seconds_accumulated,
+        lock_token_seconds(100, created_at, observed) + lock_token_seconds(200, start, observed)
+    );
+    assert_eq!(state.coverage, None);
+}
@@ -0,0 +1,152 @@
+//! Closing a drawn-down schedule: rounding dust below the config's threshold
+//! is swept (or burned without a beneficiary ATA) and the rent returned, but
+//! not while a proposal is pending on it
+
+mod common;
+
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{spl_token, Mint};
+use common::*;
+use cvt_vesting::{DustDisposition, VestingClosed, VestingError, MAX_DUST_THRESHOLD, MIN_RECOVERY_TIMELOCK};
+use solana_program_test::{ProgramTestBanksClientExt, ProgramTestContext};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+struct Schedule {
+    vesting: Pubkey,
+    mint: Pubkey,
+    beneficiary: Keypair,
+}
+
+/// A funded 1,000-unit schedule, unlocked and drawn down to `dust`
+async fn drawn_down(ctx: &mut ProgramTestContext, dust: u64) -> Schedule {
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    fund(ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(ctx).await + DAY;
+
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 77, unlock, 1_000);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 77);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &escrow, 1_000).await;
+    create_ata(ctx, &beneficiary.pubkey(), &mint).await;
+    warp_to(ctx, unlock).await;
+    send(ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 1_000 - dust)], &[&beneficiary])
+        .await
+        .unwrap();
+
+    Schedule { vesting, mint, beneficiary }
+}
+
+async fn close(ctx: &mut ProgramTestContext, schedule: &Schedule) -> VestingClosed {
+    let ix = close_vesting_ix(schedule.vesting, schedule.mint, schedule.beneficiary.pubkey(), ctx.payer.pubkey());
+    let (result, logs) = send_with_logs(ctx, &[ix], &[&schedule.beneficiary]).await;
+    result.unwrap();
+    decode_events::<VestingClosed>(&logs).remove(0)
+}
+
+async fn exists(ctx: &mut ProgramTestContext, address: Pubkey) -> bool {
+    ctx.banks_client.get_account(address).await.unwrap().is_some()
+}
+
+#[tokio::test]
+async fn dust_is_swept_to_the_beneficiary_and_rent_returned() {
+    let mut ctx = start().await;
+    let schedule = drawn_down(&mut ctx, 3).await;
+    let beneficiary = schedule.beneficiary.pubkey();
+    let escrow = get_associated_token_address(&schedule.vesting, &schedule.mint);
+    let before = lamports(&mut ctx, beneficiary).await;
+
+    let event = close(&mut ctx, &schedule).await;
+    assert_eq!((event.dust, event.dust_threshold, event.disposition), (3, 10, DustDisposition::Transferred));
+
+    let beneficiary_ata = get_associated_token_address(&beneficiary, &schedule.mint);
+    assert_eq!(token_balance(&mut ctx, &beneficiary_ata).await, 1_000);
+    assert!(!exists(&mut ctx, schedule.vesting).await);
+    assert!(!exists(&mut ctx, escrow).await);
+    assert!(lamports(&mut ctx, beneficiary).await > before);
+}
+
+#[tokio::test]
+async fn dust_is_burned_when_the_beneficiary_ata_is_closed() {
+    let mut ctx = start().await;
+    let schedule = drawn_down(&mut ctx, 2).await;
+    let beneficiary = schedule.beneficiary.pubkey();
+
+    // Beneficiary moves the claimed tokens elsewhere and closes their ATA
+    let beneficiary_ata = get_associated_token_address(&beneficiary, &schedule.mint);
+    let elsewhere = create_ata(&mut ctx, &Keypair::new().pubkey(), &schedule.mint).await;
+    transfer_tokens(&mut ctx, &beneficiary_ata, &elsewhere, &schedule.beneficiary, 998).await;
+    let close_ata =
+        spl_token::instruction::close_account(&spl_token::ID, &beneficiary_ata, &beneficiary, &beneficiary, &[])
+            .unwrap();
+    send(&mut ctx, &[close_ata], &[&schedule.beneficiary]).await.unwrap();
+
+    let event = close(&mut ctx, &schedule).await;
+    assert_eq!((event.dust, event.disposition), (2, DustDisposition::Burned));
+    let mint: Mint = fetch(&mut ctx, schedule.mint).await;
+    assert_eq!(mint.supply, 998);
+}
+
+#[tokio::test]
+async fn close_requires_a_balance_within_the_threshold() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let init = initialize_config_ix(authority, vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(&mut ctx, &[init], &[]).await.unwrap();
+    let schedule = drawn_down(&mut ctx, 11).await;
+
+    let ix = close_vesting_ix(schedule.vesting, schedule.mint, schedule.beneficiary.pubkey(), authority);
+    let refused_at = ctx.banks_client.get_latest_blockhash().await.unwrap();
+    let result = send(&mut ctx, &[ix], &[&schedule.beneficiary]).await;
+    assert_vesting_error(result, VestingError::DustAboveThreshold);
+
+    let result = send(&mut ctx, &[set_dust_threshold_ix(authority, MAX_DUST_THRESHOLD + 1)], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidDustThreshold);
+    send(&mut ctx, &[set_dust_threshold_ix(authority, 11)], &[]).await.unwrap();
+
+    // The retry is byte-identical to the refused close, so it needs a fresh blockhash
+    ctx.banks_client.get_new_latest_blockhash(&refused_at).await.unwrap();
+    let event = close(&mut ctx, &schedule).await;
+    assert_eq!((event.dust, event.dust_threshold), (11, 11));
+}
+
+#[tokio::test]
+async fn locked_schedule_cannot_close() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    let unlock = now(&mut ctx).await + DAY;
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 78, unlock, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 78);
+    create_ata(&mut ctx, &vesting, &mint).await;
+
+    let ix = close_vesting_ix(vesting, mint, beneficiary.pubkey(), ctx.payer.pubkey());
+    let result = send(&mut ctx, &[ix], &[&beneficiary]).await;
+    assert_vesting_error(result, VestingError::StillLocked);
+}
+
+#[tokio::test]
+async fn pending_mint_migration_blocks_close() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let schedule = drawn_down(&mut ctx, 0).await;
+    let new_mint = create_mint(&mut ctx).await;
+    create_ata(&mut ctx, &authority, &new_mint).await;
+    send(&mut ctx, &[migrate_mint_ix(authority, schedule.vesting, schedule.mint, new_mint, 1, 1)], &[]).await.unwrap();
+
+    // Closing would strand the migration and the new mint's escrow
+    let ix = close_vesting_ix(schedule.vesting, schedule.mint, schedule.beneficiary.pubkey(), authority);
+    let result = send(&mut ctx, &[ix], &[&schedule.beneficiary]).await;
+    assert_vesting_error(result, VestingError::ScheduleProposalPending);
+    assert!(exists(&mut ctx, schedule.vesting).await);
+
+    send(&mut ctx, &[cancel_mint_migration_ix(authority, schedule.vesting, new_mint)], &[]).await.unwrap();
+    warp_forward(&mut ctx, 1).await;
+    close(&mut ctx, &schedule).await;
+    assert!(!exists(&mut ctx, schedule.vesting).await);
+}
@@ -0,0 +1,48 @@
+//! Stand-in DAO program whose treasury is a PDA: its only instruction
+//! forwards an inner instruction to another program, signed by the treasury.
+//!
+//! Accounts: `[target program, ...the inner instruction's accounts]`
+//! Data: the inner instruction's data
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::program_error::ProgramError;
+
+pub const ID: Pubkey = Pubkey::new_from_array([0x44; 32]);
+
+pub fn treasury() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"treasury"], &ID)
+}
+
+/// Wrap `inner` so the DAO executes it with the treasury as signer
+pub fn execute_ix(inner: Instruction) -> Instruction {
+    let (treasury, _) = treasury();
+    let mut accounts = vec![AccountMeta::new_readonly(inner.program_id, false)];
+    accounts.extend(inner.accounts.into_iter().map(|meta| AccountMeta {
+        is_signer: meta.is_signer && meta.pubkey != treasury,
+        ..meta
+    }));
+    Instruction { program_id: ID, accounts, data: inner.data }
+}
+
+pub fn process_instruction(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [target, inner_accounts @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (treasury, bump) = treasury();
+    let inner = Instruction {
+        program_id: *target.key,
+        accounts: inner_accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: *account.key,
+                is_signer: account.is_signer || *account.key == treasury,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: data.to_vec(),
+    };
+    invoke_signed(&inner, accounts, &[&[b"treasury", &[bump]]])
+}
@@ -0,0 +1,30 @@
+//! Stand-in KYC provider for the vesting KYC gate: its only instruction writes
+//! a `KycAttestation` into an account it already owns.
+//!
+//! Accounts: `[attestation (writable), issuer (signer)]`
+//! Data: `subject (32) | expiry (i64 LE) | level (u8)`
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::program_error::ProgramError;
+use cvt_vesting::KycAttestation;
+
+pub const ID: Pubkey = Pubkey::new_from_array([0x4b; 32]);
+
+pub fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [attestation, issuer] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if attestation.owner != program_id || !issuer.is_signer {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let mut args = data;
+    let record = KycAttestation {
+        owner: *issuer.key,
+        subject: Pubkey::deserialize(&mut args)?,
+        expiry: i64::deserialize(&mut args)?,
+        level: u8::deserialize(&mut args)?,
+    };
+    record.serialize(&mut &mut attestation.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
@@ -0,0 +1,1427 @@
+//! Shared solana-program-test harness for the vesting integration tests:
+//! program bootstrapping, clock warping, SPL token setup and instruction builders.
+
+#![allow(dead_code)]
+
+pub mod mock_dao;
+pub mod mock_kyc;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::program_stubs;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token::spl_token;
+use cvt_vesting::VestingError;
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use std::sync::Once;
+
+use solana_sdk::{
+    account::AccountSharedData,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+
+pub const DAY: i64 = 24 * 60 * 60;
+pub const YEAR: i64 = 365 * DAY;
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    // Anchor's entrypoint ties the account slice to the AccountInfo lifetime
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    cvt_vesting::entry(program_id, accounts, data)
+}
+
+pub async fn start() -> ProgramTestContext {
+    let mut program_test = ProgramTest::new("cvt_vesting", cvt_vesting::ID, processor!(process_instruction));
+    program_test.add_program("mock_kyc", mock_kyc::ID, processor!(mock_kyc::process_instruction));
+    program_test.add_program("mock_dao", mock_dao::ID, processor!(mock_dao::process_instruction));
+    let ctx = program_test.start_with_context().await;
+    // After the first start, which installs program-test's own stubs (once per process)
+    static LOG_DATA: Once = Once::new();
+    LOG_DATA.call_once(|| {
+        let inner = program_stubs::set_syscall_stubs(Box::new(NoStubs));
+        program_stubs::set_syscall_stubs(Box::new(LogDataStubs(inner)));
+    });
+    ctx
+}
+
+/// Placeholder held only while swapping program-test's stubs out
+struct NoStubs;
+
+impl program_stubs::SyscallStubs for NoStubs {}
+
+/// program-test's native stubs print `sol_log_data` (i.e. `emit!`) to stdout
+/// instead of the transaction log. Route it into the log as
+/// `Program log: Program data: <base64>` so tests can decode events;
+/// everything else is forwarded unchanged.
+struct LogDataStubs(Box<dyn program_stubs::SyscallStubs>);
+
+impl program_stubs::SyscallStubs for LogDataStubs {
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        for field in fields {
+            self.0.sol_log(&format!("Program data: {}", anchor_lang::__private::base64::encode(field)));
+        }
+    }
+    fn sol_log(&self, message: &str) {
+        self.0.sol_log(message)
+    }
+    fn sol_log_compute_units(&self) {
+        self.0.sol_log_compute_units()
+    }
+    fn sol_remaining_compute_units(&self) -> u64 {
+        self.0.sol_remaining_compute_units()
+    }
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.0.sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_clock_sysvar(var_addr)
+    }
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_fees_sysvar(var_addr)
+    }
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_rent_sysvar(var_addr)
+    }
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_rewards_sysvar(var_addr)
+    }
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_last_restart_slot(var_addr)
+    }
+    unsafe fn sol_memcpy(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.0.sol_memcpy(dst, src, n)
+    }
+    unsafe fn sol_memmove(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.0.sol_memmove(dst, src, n)
+    }
+    unsafe fn sol_memcmp(&self, s1: *const u8, s2: *const u8, n: usize, result: *mut i32) {
+        self.0.sol_memcmp(s1, s2, n, result)
+    }
+    unsafe fn sol_memset(&self, s: *mut u8, c: u8, n: usize) {
+        self.0.sol_memset(s, c, n)
+    }
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.0.sol_get_return_data()
+    }
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.0.sol_set_return_data(data)
+    }
+    fn sol_get_processed_sibling_instruction(&self, index: usize) -> Option<Instruction> {
+        self.0.sol_get_processed_sibling_instruction(index)
+    }
+    fn sol_get_stack_height(&self) -> u64 {
+        self.0.sol_get_stack_height()
+    }
+}
+
+/// Every transaction goes through the banks server's synchronous (metadata)
+/// path. Its queued `process_transaction` path can report a signature before
+/// releasing account locks, so a direct transaction right after it may fail
+/// with `AccountInUse`.
+pub async fn send(
+    ctx: &mut ProgramTestContext,
+    ixs: &[Instruction],
+    signers: &[&Keypair],
+) -> std::result::Result<(), TransactionError> {
+    send_with_logs(ctx, ixs, signers).await.0
+}
+
+/// Like `send`, but also returns the transaction's program logs
+pub async fn send_with_logs(
+    ctx: &mut ProgramTestContext,
+    ixs: &[Instruction],
+    signers: &[&Keypair],
+) -> (std::result::Result<(), TransactionError>, Vec<String>) {
+    let blockhash = ctx.banks_client.get_latest_blockhash().await.unwrap();
+    let mut all: Vec<&Keypair> = vec![&ctx.payer];
+    all.extend_from_slice(signers);
+    let tx = Transaction::new_signed_with_payer(ixs, Some(&ctx.payer.pubkey()), &all, blockhash);
+    let outcome = ctx.banks_client.process_transaction_with_metadata(tx).await.unwrap();
+    let logs = outcome.metadata.map(|m| m.log_messages).unwrap_or_default();
+    (outcome.result, logs)
+}
+
+/// Raw payloads (discriminator + borsh) of every event in a transaction's logs
+pub fn event_payloads(logs: &[String]) -> Vec<Vec<u8>> {
+    logs.iter()
+        .filter_map(|line| line.strip_prefix("Program log: ").unwrap_or(line).strip_prefix("Program data: "))
+        .map(|data| anchor_lang::__private::base64::decode(data).unwrap())
+        .collect()
+}
+
+/// Decode every `T` event from a transaction's logs
+pub fn decode_events<T: anchor_lang::Event + AnchorDeserialize>(logs: &[String]) -> Vec<T> {
+    event_payloads(logs)
+        .into_iter()
+        .filter(|payload| payload.starts_with(&T::DISCRIMINATOR))
+        .map(|payload| T::try_from_slice(&payload[8..]).unwrap())
+        .collect()
+}
+
+/// Simulate a view instruction and decode its Anchor return data
+pub async fn view<T: AnchorDeserialize>(ctx: &mut ProgramTestContext, ix: Instruction) -> T {
+    let blockhash = ctx.banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&ctx.payer.pubkey()), &[&ctx.payer], blockhash);
+    let simulation = ctx.banks_client.simulate_transaction(tx).await.unwrap();
+    simulation.result.unwrap().unwrap();
+    let return_data = simulation
+        .simulation_details
+        .and_then(|details| details.return_data)
+        .expect("view returned no data");
+    T::try_from_slice(&return_data.data).unwrap()
+}
+
+// ----------------------------------------------------------------------------
+// Clock
+// ----------------------------------------------------------------------------
+
+pub async fn now(ctx: &mut ProgramTestContext) -> i64 {
+    let clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp
+}
+
+/// Move the clock forward by `seconds`
+pub async fn warp_forward(ctx: &mut ProgramTestContext, seconds: i64) {
+    let target = now(ctx).await + seconds;
+    warp_to(ctx, target).await;
+}
+
+/// Set the clock to an absolute unix timestamp
+pub async fn warp_to(ctx: &mut ProgramTestContext, unix_timestamp: i64) {
+    // Advance a slot first so retried transactions get a fresh blockhash
+    let mut clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    ctx.warp_to_slot(clock.slot + 1).unwrap();
+    clock.slot += 1;
+    clock.unix_timestamp = unix_timestamp;
+    ctx.set_sysvar(&clock);
+}
+
+// ----------------------------------------------------------------------------
+// Assertions and account access
+// ----------------------------------------------------------------------------
+
+pub fn assert_vesting_error(result: std::result::Result<(), TransactionError>, expected: VestingError) {
+    let code = match result {
+        Err(TransactionError::InstructionError(_, InstructionError::Custom(code))) => code,
+        other => panic!("expected {:?}, got {:?}", expected, other),
+    };
+    assert_eq!(code, u32::from(expected));
+}
+
+pub async fn fetch<T: AccountDeserialize>(ctx: &mut ProgramTestContext, address: Pubkey) -> T {
+    let account = ctx.banks_client.get_account(address).await.unwrap().unwrap();
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+pub async fn lamports(ctx: &mut ProgramTestContext, address: Pubkey) -> u64 {
+    ctx.banks_client.get_balance(address).await.unwrap()
+}
+
+pub async fn fund(ctx: &mut ProgramTestContext, to: &Pubkey, lamports: u64) {
+    let ix = system_instruction::transfer(&ctx.payer.pubkey(), to, lamports);
+    send(ctx, &[ix], &[]).await.unwrap();
+}
+
+// ----------------------------------------------------------------------------
+// SPL token helpers
+// ----------------------------------------------------------------------------
+
+/// Create a 9-decimal mint whose mint authority is the test payer
+pub async fn create_mint(ctx: &mut ProgramTestContext) -> Pubkey {
+    let mint = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let ixs = [
+        system_instruction::create_account(
+            &ctx.payer.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_mint(&spl_token::ID, &mint.pubkey(), &ctx.payer.pubkey(), None, 9)
+            .unwrap(),
+    ];
+    send(ctx, &ixs, &[&mint]).await.unwrap();
+    mint.pubkey()
+}
+
+/// Create the associated token account of `owner` (which may be a PDA)
+pub async fn create_ata(ctx: &mut ProgramTestContext, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    let ix = spl_associated_token_account_ix(&ctx.payer.pubkey(), owner, mint);
+    send(ctx, &[ix], &[]).await.unwrap();
+    get_associated_token_address(owner, mint)
+}
+
+fn spl_associated_token_account_ix(payer: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: associated_token::ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(get_associated_token_address(owner, mint), false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ],
+        data: vec![],
+    }
+}
+
+pub async fn mint_to(ctx: &mut ProgramTestContext, mint: &Pubkey, to: &Pubkey, amount: u64) {
+    let ix = spl_token::instruction::mint_to(&spl_token::ID, mint, to, &ctx.payer.pubkey(), &[], amount).unwrap();
+    send(ctx, &[ix], &[]).await.unwrap();
+}
+
+/// Wrap `lamports` into an existing wSOL token account
+pub async fn wrap_sol(ctx: &mut ProgramTestContext, token_account: &Pubkey, lamports: u64) {
+    let ixs = [
+        system_instruction::transfer(&ctx.payer.pubkey(), token_account, lamports),
+        spl_token::instruction::sync_native(&spl_token::ID, token_account).unwrap(),
+    ];
+    send(ctx, &ixs, &[]).await.unwrap();
+}
+
+pub async fn transfer_tokens(
+    ctx: &mut ProgramTestContext,
+    from: &Pubkey,
+    to: &Pubkey,
+    owner: &Keypair,
+    amount: u64,
+) {
+    let ix = spl_token::instruction::transfer(&spl_token::ID, from, to, &owner.pubkey(), &[], amount).unwrap();
+    send(ctx, &[ix], &[owner]).await.unwrap();
+}
+
+pub async fn token_balance(ctx: &mut ProgramTestContext, token_account: &Pubkey) -> u64 {
+    let account = ctx.banks_client.get_account(*token_account).await.unwrap().unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+// ----------------------------------------------------------------------------
+// PDAs
+// ----------------------------------------------------------------------------
+
+pub fn vesting_pda(beneficiary: &Pubkey, mint: &Pubkey, schedule_id: u64, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"vesting",
+            beneficiary.as_ref(),
+            mint.as_ref(),
+            &schedule_id.to_le_bytes(),
+            &nonce.to_le_bytes(),
+        ],
+        &cvt_vesting::ID,
+    )
+    .0
+}
+
+pub fn pending_schedule_pda(beneficiary: &Pubkey, mint: &Pubkey, schedule_id: u64, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"pending_schedule", beneficiary.as_ref(), mint.as_ref(), &schedule_id.to_le_bytes(), &nonce.to_le_bytes()],
+        &cvt_vesting::ID,
+    )
+    .0
+}
+
+pub fn config_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &cvt_vesting::ID).0
+}
+
+pub fn recovery_pda(vesting: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"recovery", vesting.as_ref()], &cvt_vesting::ID).0
+}
+
+pub fn unwrap_pda(vesting: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"unwrap", vesting.as_ref()], &cvt_vesting::ID).0
+}
+
+pub fn mint_migration_pda(vesting: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"mint_migration", vesting.as_ref()], &cvt_vesting::ID).0
+}
+
+pub fn guardian_rotation_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"guardian_rotation"], &cvt_vesting::ID).0
+}
+
+pub fn schedule_count_pda(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"schedule_count", authority.as_ref()], &cvt_vesting::ID).0
+}
+
+pub fn bridge_deposit_pda(vesting: &Pubkey, operation_id: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[b"bridge_deposit", vesting.as_ref(), operation_id], &cvt_vesting::ID).0
+}
+
+pub fn bridge_inflight_pda(vesting: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"bridge_inflight", vesting.as_ref()], &cvt_vesting::ID).0
+}
+
+/// The operation ID of a bridge-in of `amount` to the schedule's escrow
+pub fn bridge_operation(vesting: &Pubkey, mint: &Pubkey, amount: u64, salt: &[u8; 32]) -> [u8; 32] {
+    cvt_vesting::bridge_operation_id(&get_associated_token_address(vesting, mint), amount, salt)
+}
+
+/// Trinity's ConsensusMirror PDA for `operation_id` under `consensus_program`
+pub fn consensus_mirror_pda(consensus_program: &Pubkey, operation_id: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[b"consensus_mirror", operation_id], consensus_program).0
+}
+
+/// Write the ConsensusMirror Trinity would publish for `operation_id`
+/// (stable ABI: discriminator, schema 1, id, root, consensus_at, chain bitmap)
+pub fn set_consensus_mirror(
+    ctx: &mut ProgramTestContext,
+    consensus_program: &Pubkey,
+    operation_id: &[u8; 32],
+    chain_bitmap: u8,
+) -> Pubkey {
+    let mut data = anchor_lang::solana_program::hash::hash(b"account:ConsensusMirror").to_bytes()[..8].to_vec();
+    data.push(cvt_vesting::CONSENSUS_MIRROR_SCHEMA_VERSION);
+    data.extend_from_slice(operation_id);
+    data.extend_from_slice(&[0x4d; 32]);
+    data.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+    data.push(chain_bitmap);
+
+    let mirror = consensus_mirror_pda(consensus_program, operation_id);
+    let mut account = AccountSharedData::new(1_000_000_000, data.len(), consensus_program);
+    account.set_data_from_slice(&data);
+    ctx.set_account(&mirror, &account);
+    mirror
+}
+
+// ----------------------------------------------------------------------------
+// Instruction builders
+// ----------------------------------------------------------------------------
+
+pub fn initialize_config_ix(
+    authority: Pubkey,
+    guardians: Vec<Pubkey>,
+    guardian_threshold: u8,
+    recovery_timelock: i64,
+) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::InitializeConfig {
+            config: config_pda(),
+            authority,
+            system_program: anchor_lang::system_program::ID,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::InitializeConfig {
+            guardians,
+            guardian_threshold,
+            recovery_timelock,
+        }
+        .data(),
+    }
+}
+
+/// Uncategorized schedule (category 0, zero label hash)
+pub fn create_vesting_ix(
+    payer: Pubkey,
+    beneficiary: Pubkey,
+    mint: Pubkey,
+    schedule_id: u64,
+    nonce: u64,
+    unlock_timestamp: i64,
+    amount: u64,
+) -> Instruction {
+    create_vesting_ix_with(payer, beneficiary, mint, base_create(schedule_id, nonce, unlock_timestamp, amount))
+}
+
+/// Arguments of an uncategorized, non-extendable schedule with no options;
+/// tests set the fields they exercise with `..base_create(..)`
+pub fn base_create(
+    schedule_id: u64,
+    nonce: u64,
+    unlock_timestamp: i64,
+    amount: u64,
+) -> cvt_vesting::instruction::CreateVesting {
+    cvt_vesting::instruction::CreateVesting {
+        schedule_id,
+        nonce,
+        unlock_timestamp,
+        max_unlock_timestamp: unlock_timestamp,
+        amount,
+        category: 0,
+        label_hash: [0; 32],
+        terms_hash: [0; 32],
+        claim_deadline: 0,
+        expiry_sink: Pubkey::default(),
+        allowlist_proof: vec![],
+        usd_terms: None,
+        hashed_beneficiary: false,
+    }
+}
+
+/// `create_vesting` with explicit arguments (`beneficiary` is the hash for
+/// hashed-beneficiary schedules)
+pub fn create_vesting_ix_with(
+    payer: Pubkey,
+    beneficiary: Pubkey,
+    mint: Pubkey,
+    data: cvt_vesting::instruction::CreateVesting,
+) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::CreateVesting {
+            vesting: vesting_pda(&beneficiary, &mint, data.schedule_id, data.nonce),
+            mint,
+            beneficiary,
+            payer,
+            system_program: anchor_lang::system_program::ID,
+            config: config_pda(),
+            schedule_count: schedule_count_pda(&payer),
+            pending: pending_schedule_pda(&beneficiary, &mint, data.schedule_id, data.nonce),
+        }
+        .to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn withdraw_ix(vesting: Pubkey, mint: Pubkey, beneficiary: Pubkey, amount: u64) -> Instruction {
+    withdraw_kyc_ix(vesting, mint, beneficiary, amount, None)
+}
+
+/// Withdrawal carrying a KYC attestation account
+pub fn withdraw_kyc_ix(
+    vesting: Pubkey,
+    mint: Pubkey,
+    beneficiary: Pubkey,
+    amount: u64,
+    kyc_attestation: Option<Pubkey>,
+) -> Instruction {
+    withdraw_ix_with(vesting, mint, beneficiary, amount, kyc_attestation, None)
+}
+
+/// Withdrawal from a USD schedule, priced by `price_oracle`
+pub fn withdraw_usd_ix(
+    vesting: Pubkey,
+    mint: Pubkey,
+    beneficiary: Pubkey,
+    amount: u64,
+    price_oracle: Option<Pubkey>,
+) -> Instruction {
+    withdraw_ix_with(vesting, mint, beneficiary, amount, None, price_oracle)
+}
+
+/// Withdrawal into `destination`, a token account owned by `destination_owner`
+pub fn withdraw_to_ix(
+    vesting: Pubkey,
+    mint: Pubkey,
+    beneficiary: Pubkey,
+    destination: Pubkey,
+    destination_owner: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::Withdraw {
+            beneficiary_ata: None,
+            destination: Some(destination),
+            destination_owner: Some(destination_owner),
+            ..withdraw_accounts(vesting, mint, beneficiary, None, None)
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::Withdraw { amount }.data(),
+    }
+}
+
+/// Withdrawal into `destination`, vouched for by the beneficiary program's `ownership_attestation`
+pub fn withdraw_attested_ix(
+    vesting: Pubkey,
+    mint: Pubkey,
+    beneficiary: Pubkey,
+    destination: Pubkey,
+    ownership_attestation: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::Withdraw {
+            beneficiary_ata: None,
+            destination: Some(destination),
+            ownership_attestation: Some(ownership_attestation),
+            ..withdraw_accounts(vesting, mint, beneficiary, None, None)
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::Withdraw { amount }.data(),
+    }
+}
+
+/// Withdrawal from a hashed-beneficiary schedule, proving the signer with `salt`
+pub fn withdraw_hashed_ix(vesting: Pubkey, mint: Pubkey, beneficiary: Pubkey, amount: u64, salt: [u8; 32]) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: withdraw_accounts(vesting, mint, beneficiary, None, None).to_account_metas(None),
+        data: cvt_vesting::instruction::WithdrawHashed { amount, salt }.data(),
+    }
+}
+
+/// Accounts of a plain withdrawal to the beneficiary's ATA, for tests that vary them
+pub fn withdraw_accounts(
+    vesting: Pubkey,
+    mint: Pubkey,
+    beneficiary: Pubkey,
+    kyc_attestation: Option<Pubkey>,
+    price_oracle: Option<Pubkey>,
+) -> cvt_vesting::accounts::Withdraw {
+    cvt_vesting::accounts::Withdraw {
+        vesting,
+        mint,
+        vesting_ata: get_associated_token_address(&vesting, &mint),
+        beneficiary_ata: Some(get_associated_token_address(&beneficiary, &mint)),
+        destination: None,
+        beneficiary,
+        token_program: spl_token::ID,
+        associated_token_program: associated_token::ID,
+        system_program: anchor_lang::system_program::ID,
+        config: config_pda(),
+        kyc_attestation,
+        price_oracle,
+        destination_owner: None,
+        ownership_attestation: None,
+    }
+}
+
+fn withdraw_ix_with(
+    vesting: Pubkey,
+    mint: Pubkey,
+    beneficiary: Pubkey,
+    amount: u64,
+    kyc_attestation: Option<Pubkey>,
+    price_oracle: Option<Pubkey>,
+) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: withdraw_accounts(vesting, mint, beneficiary, kyc_attestation, price_oracle).to_account_metas(None),
+        data: cvt_vesting::instruction::Withdraw { amount }.data(),
+    }
+}
+
+/// Withdrawal to the beneficiary's ATA with `payer` fronting its rent
+pub fn withdraw_sponsored_ix(vesting: Pubkey, mint: Pubkey, beneficiary: Pubkey, payer: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::WithdrawSponsored {
+            vesting,
+            mint,
+            vesting_ata: get_associated_token_address(&vesting, &mint),
+            beneficiary_ata: get_associated_token_address(&beneficiary, &mint),
+            beneficiary,
+            payer,
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+            config: config_pda(),
+            kyc_attestation: None,
+            price_oracle: None,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::WithdrawSponsored { amount }.data(),
+    }
+}
+
+pub fn auto_claim_ix(vesting: Pubkey, mint: Pubkey, beneficiary: Pubkey, delegate: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::AutoClaim {
+            vesting,
+            mint,
+            vesting_ata: get_associated_token_address(&vesting, &mint),
+            beneficiary_ata: get_associated_token_address(&beneficiary, &mint),
+            delegate,
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            config: config_pda(),
+            kyc_attestation: None,
+            price_oracle: None,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::AutoClaim { amount }.data(),
+    }
+}
+
+pub fn set_auto_claim_ix(vesting: Pubkey, beneficiary: Pubkey, delegate: Pubkey, max_total: u64) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::SetAutoClaim { vesting, beneficiary }.to_account_metas(None),
+        data: cvt_vesting::instruction::SetAutoClaim { delegate, max_total }.data(),
+    }
+}
+
+pub fn register_coverage_ix(
+    vesting: Pubkey,
+    beneficiary: Pubkey,
+    coverage_program: Pubkey,
+    policy_id: u64,
+    expiry: i64,
+) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::RegisterCoverage { config: config_pda(), vesting, beneficiary }
+            .to_account_metas(None),
+        data: cvt_vesting::instruction::RegisterCoverage { coverage_program, policy_id, expiry }.data(),
+    }
+}
+
+pub fn withdraw_native_ix(vesting: Pubkey, mint: Pubkey, beneficiary: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::WithdrawNative {
+            vesting,
+            mint,
+            vesting_ata: get_associated_token_address(&vesting, &mint),
+            unwrap_account: unwrap_pda(&vesting),
+            beneficiary,
+            token_program: spl_token::ID,
+            system_program: anchor_lang::system_program::ID,
+            config: config_pda(),
+            kyc_attestation: None,
+            price_oracle: None,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::WithdrawNative { amount }.data(),
+    }
+}
+
+/// `authority` created the schedule and gets its cap slot back
+pub fn close_vesting_ix(vesting: Pubkey, mint: Pubkey, beneficiary: Pubkey, authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::CloseVesting {
+            vesting,
+            mint,
+            vesting_ata: get_associated_token_address(&vesting, &mint),
+            beneficiary_ata: get_associated_token_address(&beneficiary, &mint),
+            beneficiary,
+            token_program: spl_token::ID,
+            config: config_pda(),
+            schedule_count: schedule_count_pda(&authority),
+            recovery: recovery_pda(&vesting),
+            mint_migration: mint_migration_pda(&vesting),
+            bridge_inflight: bridge_inflight_pda(&vesting),
+            authority,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::CloseVesting {}.data(),
+    }
+}
+
+/// Propose migrating `vesting` to `new_mint`, keeping the authority (token
+/// schedules only); the deposit comes from the authority's new-mint ATA
+pub fn migrate_mint_ix(
+    authority: Pubkey,
+    vesting: Pubkey,
+    mint: Pubkey,
+    new_mint: Pubkey,
+    conversion_numerator: u64,
+    conversion_denominator: u64,
+) -> Instruction {
+    migrate_mint_to_ix(authority, vesting, mint, new_mint, conversion_numerator, conversion_denominator, authority, None)
+}
+
+/// Propose a migration that also hands the schedule to `new_authority` and,
+/// for a USD schedule, prices it with `new_price_oracle`
+#[allow(clippy::too_many_arguments)]
+pub fn migrate_mint_to_ix(
+    authority: Pubkey,
+    vesting: Pubkey,
+    mint: Pubkey,
+    new_mint: Pubkey,
+    conversion_numerator: u64,
+    conversion_denominator: u64,
+    new_authority: Pubkey,
+    new_price_oracle: Option<Pubkey>,
+) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::MigrateMint {
+            vesting,
+            migration: mint_migration_pda(&vesting),
+            mint,
+            vesting_ata: get_associated_token_address(&vesting, &mint),
+            new_mint,
+            new_vesting_ata: get_associated_token_address(&vesting, &new_mint),
+            authority_new_ata: get_associated_token_address(&authority, &new_mint),
+            new_schedule_count: schedule_count_pda(&new_authority),
+            authority,
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::MigrateMint {
+            conversion_numerator,
+            conversion_denominator,
+            new_authority,
+            new_price_oracle,
+        }
+        .data(),
+    }
+}
+
+/// Acceptance of a migration keeping the authority
+pub fn accept_mint_migration_ix(
+    beneficiary: Pubkey,
+    authority: Pubkey,
+    vesting: Pubkey,
+    mint: Pubkey,
+    new_mint: Pubkey,
+) -> Instruction {
+    accept_mint_migration_to_ix(beneficiary, authority, None, vesting, mint, new_mint)
+}
+
+/// Acceptance of a migration handing the schedule to `new_authority`, if any
+pub fn accept_mint_migration_to_ix(
+    beneficiary: Pubkey,
+    authority: Pubkey,
+    new_authority: Option<Pubkey>,
+    vesting: Pubkey,
+    mint: Pubkey,
+    new_mint: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::AcceptMintMigration {
+            vesting,
+            migration: mint_migration_pda(&vesting),
+            mint,
+            vesting_ata: get_associated_token_address(&vesting, &mint),
+            new_mint,
+            new_vesting_ata: get_associated_token_address(&vesting, &new_mint),
+            authority_ata: get_associated_token_address(&authority, &mint),
+            authority_new_ata: get_associated_token_address(&authority, &new_mint),
+            authority,
+            schedule_count: schedule_count_pda(&authority),
+            new_schedule_count: new_authority.map(|new_authority| schedule_count_pda(&new_authority)),
+            beneficiary,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::AcceptMintMigration {}.data(),
+    }
+}
+
+pub fn cancel_mint_migration_ix(authority: Pubkey, vesting: Pubkey, new_mint: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::CancelMintMigration {
+            vesting,
+            migration: mint_migration_pda(&vesting),
+            new_mint,
+            new_vesting_ata: get_associated_token_address(&vesting, &new_mint),
+            authority_new_ata: get_associated_token_address(&authority, &new_mint),
+            authority,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::CancelMintMigration {}.data(),
+    }
+}
+
+pub fn extend_unlock_ix(authority: Pubkey, vesting: Pubkey, new_unlock_timestamp: i64) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::ExtendUnlock { vesting, authority }.to_account_metas(None),
+        data: cvt_vesting::instruction::ExtendUnlock { new_unlock_timestamp }.data(),
+    }
+}
+
+/// `sink_tokens` is None for an incinerator sink
+pub fn expire_and_return_ix(vesting: Pubkey, mint: Pubkey, sink_tokens: Option<Pubkey>) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::ExpireAndReturn {
+            vesting,
+            mint,
+            vesting_ata: get_associated_token_address(&vesting, &mint),
+            sink_tokens,
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::ExpireAndReturn {}.data(),
+    }
+}
+
+/// Uncategorized token schedule terms without an extension, terms hash or expiry
+pub fn schedule_terms(schedule_id: u64, nonce: u64, unlock_timestamp: i64, amount: u64) -> cvt_vesting::ScheduleTerms {
+    cvt_vesting::ScheduleTerms {
+        schedule_id,
+        nonce,
+        unlock_timestamp,
+        max_unlock_timestamp: unlock_timestamp,
+        amount,
+        category: 0,
+        label_hash: [0; 32],
+        terms_hash: [0; 32],
+        claim_deadline: 0,
+        expiry_sink: Pubkey::default(),
+    }
+}
+
+/// The authority stages `terms.amount` from its ATA
+pub fn register_pending_schedule_ix(
+    authority: Pubkey,
+    beneficiary: Pubkey,
+    mint: Pubkey,
+    terms: cvt_vesting::ScheduleTerms,
+    activation_timestamp: i64,
+) -> Instruction {
+    let pending = pending_schedule_pda(&beneficiary, &mint, terms.schedule_id, terms.nonce);
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::RegisterPendingSchedule {
+            pending,
+            staging: get_associated_token_address(&pending, &mint),
+            mint,
+            beneficiary,
+            authority_tokens: get_associated_token_address(&authority, &mint),
+            authority,
+            vesting: vesting_pda(&beneficiary, &mint, terms.schedule_id, terms.nonce),
+            config: config_pda(),
+            schedule_count: schedule_count_pda(&authority),
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::RegisterPendingSchedule { terms, activation_timestamp }.data(),
+    }
+}
+
+pub fn activate_schedule_ix(
+    payer: Pubkey,
+    authority: Pubkey,
+    beneficiary: Pubkey,
+    mint: Pubkey,
+    schedule_id: u64,
+    nonce: u64,
+    allowlist_proof: Vec<[u8; 32]>,
+) -> Instruction {
+    let pending = pending_schedule_pda(&beneficiary, &mint, schedule_id, nonce);
+    let vesting = vesting_pda(&beneficiary, &mint, schedule_id, nonce);
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::ActivateSchedule {
+            pending,
+            staging: get_associated_token_address(&pending, &mint),
+            vesting,
+            vesting_ata: get_associated_token_address(&vesting, &mint),
+            mint,
+            authority,
+            payer,
+            config: config_pda(),
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: anchor_lang::system_program::ID,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::ActivateSchedule { allowlist_proof }.data(),
+    }
+}
+
+/// The staged tokens go back to the authority's ATA
+pub fn cancel_pending_schedule_ix(authority: Pubkey, beneficiary: Pubkey, mint: Pubkey, schedule_id: u64, nonce: u64) -> Instruction {
+    let pending = pending_schedule_pda(&beneficiary, &mint, schedule_id, nonce);
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::CancelPendingSchedule {
+            pending,
+            staging: get_associated_token_address(&pending, &mint),
+            mint,
+            authority_tokens: get_associated_token_address(&authority, &mint),
+            authority,
+            schedule_count: schedule_count_pda(&authority),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::CancelPendingSchedule {}.data(),
+    }
+}
+
+pub fn cancel_unfunded_ix(authority: Pubkey, vesting: Pubkey, mint: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::CancelUnfunded {
+            vesting,
+            vesting_ata: get_associated_token_address(&vesting, &mint),
+            recovery: recovery_pda(&vesting),
+            mint_migration: mint_migration_pda(&vesting),
+            bridge_inflight: bridge_inflight_pda(&vesting),
+            authority,
+            schedule_count: schedule_count_pda(&authority),
+            token_program: spl_token::ID,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::CancelUnfunded {}.data(),
+    }
+}
+
+pub fn deposit_ix(vesting: Pubkey, mint: Pubkey, depositor: Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::DepositTokens {
+            vesting,
+            mint,
+            vesting_ata: get_associated_token_address(&vesting, &mint),
+            depositor_ata: get_associated_token_address(&depositor, &mint),
+            depositor,
+            token_program: spl_token::ID,
+            config: config_pda(),
+            bridge_inflight: bridge_inflight_pda(&vesting),
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::DepositTokens { amount }.data(),
+    }
+}
+
+pub fn open_bridge_deposit_ix(
+    authority: Pubkey,
+    vesting: Pubkey,
+    mint: Pubkey,
+    operation_id: [u8; 32],
+    amount: u64,
+    salt: [u8; 32],
+) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::OpenBridgeDeposit {
+            vesting,
+            bridge_deposit: bridge_deposit_pda(&vesting, &operation_id),
+            bridge_inflight: bridge_inflight_pda(&vesting),
+            mint,
+            vesting_ata: get_associated_token_address(&vesting, &mint),
+            authority,
+            system_program: anchor_lang::system_program::ID,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::OpenBridgeDeposit { operation_id, amount, salt }.data(),
+    }
+}
+
+/// Credit against the mirror `consensus_program` published for `operation_id`
+pub fn deposit_from_bridge_ix(vesting: Pubkey, mint: Pubkey, operation_id: [u8; 32], consensus_program: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::DepositFromBridge {
+            vesting,
+            bridge_deposit: bridge_deposit_pda(&vesting, &operation_id),
+            bridge_inflight: bridge_inflight_pda(&vesting),
+            mint,
+            vesting_ata: get_associated_token_address(&vesting, &mint),
+            consensus_mirror: consensus_mirror_pda(&consensus_program, &operation_id),
+            config: config_pda(),
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::DepositFromBridge { operation_id }.data(),
+    }
+}
+
+pub fn set_pause_ix(authority: Pubkey, vesting: Pubkey, is_paused: bool, deposits_paused: bool) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::SetPause {
+            config: config_pda(),
+            vesting,
+            authority,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::SetPause { is_paused, deposits_paused }.data(),
+    }
+}
+
+pub fn update_category_ix(authority: Pubkey, vesting: Pubkey, category: u8) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::UpdateCategory { config: config_pda(), vesting, authority }
+            .to_account_metas(None),
+        data: cvt_vesting::instruction::UpdateCategory { category }.data(),
+    }
+}
+
+pub fn set_event_verbosity_ix(authority: Pubkey, verbose_events: bool) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::UpdateConfig { config: config_pda(), authority }.to_account_metas(None),
+        data: cvt_vesting::instruction::SetEventVerbosity { verbose_events }.data(),
+    }
+}
+
+pub fn set_allowlist_root_ix(authority: Pubkey, allowlist_root: [u8; 32]) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::UpdateConfig { config: config_pda(), authority }.to_account_metas(None),
+        data: cvt_vesting::instruction::SetAllowlistRoot { allowlist_root }.data(),
+    }
+}
+
+pub fn set_min_cliff_ix(authority: Pubkey, min_cliff_secs: i64) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::UpdateConfig { config: config_pda(), authority }.to_account_metas(None),
+        data: cvt_vesting::instruction::SetMinCliff { min_cliff_secs }.data(),
+    }
+}
+
+pub fn set_max_pause_duration_ix(authority: Pubkey, max_pause_duration: i64) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::UpdateConfig { config: config_pda(), authority }.to_account_metas(None),
+        data: cvt_vesting::instruction::SetMaxPauseDuration { max_pause_duration }.data(),
+    }
+}
+
+pub fn set_circuit_breaker_duration_ix(authority: Pubkey, circuit_breaker_duration: i64) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::UpdateConfig { config: config_pda(), authority }.to_account_metas(None),
+        data: cvt_vesting::instruction::SetCircuitBreakerDuration { circuit_breaker_duration }.data(),
+    }
+}
+
+pub fn trip_circuit_breaker_ix(authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::UpdateConfig { config: config_pda(), authority }.to_account_metas(None),
+        data: cvt_vesting::instruction::TripCircuitBreaker {}.data(),
+    }
+}
+
+pub fn set_max_schedules_per_authority_ix(authority: Pubkey, max_schedules_per_authority: u32) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::UpdateConfig { config: config_pda(), authority }.to_account_metas(None),
+        data: cvt_vesting::instruction::SetMaxSchedulesPerAuthority { max_schedules_per_authority }.data(),
+    }
+}
+
+pub fn set_dust_threshold_ix(authority: Pubkey, dust_threshold: u64) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::UpdateConfig { config: config_pda(), authority }.to_account_metas(None),
+        data: cvt_vesting::instruction::SetDustThreshold { dust_threshold }.data(),
+    }
+}
+
+pub fn set_coverage_programs_ix(authority: Pubkey, coverage_programs: Vec<Pubkey>) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::UpdateConfig { config: config_pda(), authority }.to_account_metas(None),
+        data: cvt_vesting::instruction::SetCoveragePrograms { coverage_programs }.data(),
+    }
+}
+
+pub fn set_destination_programs_ix(authority: Pubkey, destination_programs: Vec<Pubkey>) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::UpdateConfig { config: config_pda(), authority }.to_account_metas(None),
+        data: cvt_vesting::instruction::SetDestinationPrograms { destination_programs }.data(),
+    }
+}
+
+pub fn set_bridge_consensus_program_ix(authority: Pubkey, bridge_consensus_program: Option<Pubkey>) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::UpdateConfig { config: config_pda(), authority }.to_account_metas(None),
+        data: cvt_vesting::instruction::SetBridgeConsensusProgram { bridge_consensus_program }.data(),
+    }
+}
+
+pub fn set_oracle_policy_ix(
+    authority: Pubkey,
+    oracle_program: Option<Pubkey>,
+    max_staleness_secs: i64,
+    max_conf_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::UpdateConfig { config: config_pda(), authority }.to_account_metas(None),
+        data: cvt_vesting::instruction::SetOraclePolicy { oracle_program, max_staleness_secs, max_conf_bps }.data(),
+    }
+}
+
+/// Write a Pyth price account (just the fields `PythPrice` reads) at `address`
+pub fn set_pyth_price(
+    ctx: &mut ProgramTestContext,
+    address: Pubkey,
+    owner: Pubkey,
+    price: i64,
+    conf: u64,
+    expo: i32,
+    publish_time: i64,
+) {
+    let mut data = vec![0u8; cvt_vesting::PythPrice::LEN];
+    data[0..4].copy_from_slice(&cvt_vesting::PythPrice::MAGIC.to_le_bytes());
+    data[8..12].copy_from_slice(&cvt_vesting::PythPrice::ACCOUNT_TYPE_PRICE.to_le_bytes());
+    data[20..24].copy_from_slice(&expo.to_le_bytes());
+    data[96..104].copy_from_slice(&publish_time.to_le_bytes());
+    data[208..216].copy_from_slice(&price.to_le_bytes());
+    data[216..224].copy_from_slice(&conf.to_le_bytes());
+    data[224..228].copy_from_slice(&cvt_vesting::PythPrice::STATUS_TRADING.to_le_bytes());
+    let mut account = AccountSharedData::new(1_000_000_000, data.len(), &owner);
+    account.set_data_from_slice(&data);
+    ctx.set_account(&address, &account);
+}
+
+pub fn set_kyc_gate_ix(authority: Pubkey, kyc_program: Option<Pubkey>, compliance_authority: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::UpdateConfig { config: config_pda(), authority }.to_account_metas(None),
+        data: cvt_vesting::instruction::SetKycGate { kyc_program, compliance_authority }.data(),
+    }
+}
+
+pub fn set_kyc_exempt_ix(compliance_authority: Pubkey, vesting: Pubkey, kyc_exempt: bool) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::SetKycExempt { config: config_pda(), vesting, compliance_authority }
+            .to_account_metas(None),
+        data: cvt_vesting::instruction::SetKycExempt { kyc_exempt }.data(),
+    }
+}
+
+/// Create an attestation account owned by the mock KYC program and have the
+/// harness payer issue it for `subject`
+pub async fn kyc_attest(ctx: &mut ProgramTestContext, subject: Pubkey, expiry: i64, level: u8) -> Pubkey {
+    let attestation = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let mut data = subject.to_bytes().to_vec();
+    data.extend_from_slice(&expiry.to_le_bytes());
+    data.push(level);
+    let ixs = [
+        system_instruction::create_account(
+            &ctx.payer.pubkey(),
+            &attestation.pubkey(),
+            rent.minimum_balance(cvt_vesting::KycAttestation::LEN),
+            cvt_vesting::KycAttestation::LEN as u64,
+            &mock_kyc::ID,
+        ),
+        Instruction {
+            program_id: mock_kyc::ID,
+            accounts: vec![
+                AccountMeta::new(attestation.pubkey(), false),
+                AccountMeta::new_readonly(ctx.payer.pubkey(), true),
+            ],
+            data,
+        },
+    ];
+    send(ctx, &ixs, &[&attestation]).await.unwrap();
+    attestation.pubkey()
+}
+
+pub fn is_allowlisted_ix(beneficiary: Pubkey, proof: Vec<[u8; 32]>) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::ViewConfig { config: config_pda() }.to_account_metas(None),
+        data: cvt_vesting::instruction::IsAllowlisted { beneficiary, proof }.data(),
+    }
+}
+
+pub fn is_claimable_ix(vesting: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::ViewVesting { vesting }.to_account_metas(None),
+        data: cvt_vesting::instruction::IsClaimable {}.data(),
+    }
+}
+
+pub fn verify_terms_ix(vesting: Pubkey, document_hash: [u8; 32]) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::ViewVesting { vesting }.to_account_metas(None),
+        data: cvt_vesting::instruction::VerifyTerms { document_hash }.data(),
+    }
+}
+
+pub fn project_claimable_ix(vesting: Pubkey, at_timestamps: Vec<i64>) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::ViewVesting { vesting }.to_account_metas(None),
+        data: cvt_vesting::instruction::ProjectClaimable { at_timestamps }.data(),
+    }
+}
+
+pub fn next_unlock_info_ix(vesting: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::ViewVesting { vesting }.to_account_metas(None),
+        data: cvt_vesting::instruction::NextUnlockInfo {}.data(),
+    }
+}
+
+pub fn serialize_state_ix(vesting: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::ViewVesting { vesting }.to_account_metas(None),
+        data: cvt_vesting::instruction::SerializeState {}.data(),
+    }
+}
+
+pub fn audit_schedule_ix(vesting: Pubkey, mint: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::AuditSchedule {
+            vesting,
+            mint,
+            vesting_ata: get_associated_token_address(&vesting, &mint),
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::AuditSchedule {}.data(),
+    }
+}
+
+pub fn get_lock_duration_ix(vesting: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::ViewVesting { vesting }.to_account_metas(None),
+        data: cvt_vesting::instruction::GetLockDuration {}.data(),
+    }
+}
+
+pub fn get_actual_balance_ix(vesting: Pubkey, mint: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::ViewBalance {
+            vesting,
+            mint,
+            vesting_ata: get_associated_token_address(&vesting, &mint),
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::GetActualBalance {}.data(),
+    }
+}
+
+pub fn aggregate_beneficiary_ix(beneficiary: Pubkey, schedules: &[Pubkey]) -> Instruction {
+    let mut accounts = cvt_vesting::accounts::AggregateBeneficiary {}.to_account_metas(None);
+    accounts.extend(schedules.iter().map(|schedule| AccountMeta::new_readonly(*schedule, false)));
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts,
+        data: cvt_vesting::instruction::AggregateBeneficiary { beneficiary }.data(),
+    }
+}
+
+pub fn claimable_batch_ix(schedules: &[Pubkey]) -> Instruction {
+    let mut accounts = cvt_vesting::accounts::ClaimableBatch {}.to_account_metas(None);
+    accounts.extend(schedules.iter().map(|schedule| AccountMeta::new_readonly(*schedule, false)));
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts,
+        data: cvt_vesting::instruction::ClaimableBatch {}.data(),
+    }
+}
+
+pub fn propose_recovery_ix(proposer: &Keypair, co_signers: &[&Keypair], vesting: Pubkey, new_beneficiary: Pubkey) -> Instruction {
+    let mut accounts = cvt_vesting::accounts::ProposeRecovery {
+        config: config_pda(),
+        vesting,
+        recovery: recovery_pda(&vesting),
+        proposer: proposer.pubkey(),
+        system_program: anchor_lang::system_program::ID,
+    }
+    .to_account_metas(None);
+    accounts.extend(co_signers.iter().map(|g| AccountMeta::new_readonly(g.pubkey(), true)));
+
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts,
+        data: cvt_vesting::instruction::GuardianRecoverBeneficiary { new_beneficiary }.data(),
+    }
+}
+
+pub fn rotate_guardians_ix(
+    proposer: &Keypair,
+    co_signers: &[&Keypair],
+    guardians: Vec<Pubkey>,
+    guardian_threshold: u8,
+) -> Instruction {
+    let mut accounts = cvt_vesting::accounts::RotateGuardians {
+        config: config_pda(),
+        rotation: guardian_rotation_pda(),
+        proposer: proposer.pubkey(),
+        system_program: anchor_lang::system_program::ID,
+    }
+    .to_account_metas(None);
+    accounts.extend(co_signers.iter().map(|g| AccountMeta::new_readonly(g.pubkey(), true)));
+
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts,
+        data: cvt_vesting::instruction::RotateGuardians { guardians, guardian_threshold }.data(),
+    }
+}
+
+pub fn execute_guardian_rotation_ix(proposer: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::ExecuteGuardianRotation {
+            config: config_pda(),
+            rotation: guardian_rotation_pda(),
+            proposer,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::ExecuteGuardianRotation {}.data(),
+    }
+}
+
+/// A current guardian (plus co-signers) withdraws the pending rotation
+pub fn cancel_guardian_rotation_ix(guardian: &Keypair, co_signers: &[&Keypair], proposer: Pubkey) -> Instruction {
+    let mut accounts = cvt_vesting::accounts::CancelGuardianRotation {
+        config: config_pda(),
+        rotation: guardian_rotation_pda(),
+        proposer,
+        guardian: guardian.pubkey(),
+    }
+    .to_account_metas(None);
+    accounts.extend(co_signers.iter().map(|g| AccountMeta::new_readonly(g.pubkey(), true)));
+
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts,
+        data: cvt_vesting::instruction::CancelGuardianRotation {}.data(),
+    }
+}
+
+pub fn execute_recovery_ix(vesting: Pubkey, proposer: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::ExecuteRecovery {
+            config: config_pda(),
+            vesting,
+            recovery: recovery_pda(&vesting),
+            proposer,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::ExecuteBeneficiaryRecovery {}.data(),
+    }
+}
+
+/// A hashed-beneficiary schedule's beneficiary objects, proving itself with the salt
+pub fn object_recovery_hashed_ix(vesting: Pubkey, proposer: Pubkey, beneficiary: Pubkey, salt: [u8; 32]) -> Instruction {
+    Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::ObjectRecovery {
+            vesting,
+            recovery: recovery_pda(&vesting),
+            proposer,
+            beneficiary,
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::ObjectBeneficiaryRecoveryHashed { salt }.data(),
+    }
+}
@@ -0,0 +1,118 @@
+//! Insurance coverage: beneficiaries register a policy from an allowlisted
+//! coverage program, claims and emergency actions carry its id, and it is
+//! cleared once it lapses or the schedule closes
+
+mod common;
+
+use common::*;
+use cvt_vesting::{
+    CoverageCleared, CoverageInfo, CoverageRegistered, PauseUpdated, TokensWithdrawn, Vesting, VestingError,
+    MIN_RECOVERY_TIMELOCK,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const POLICY_ID: u64 = 7;
+
+struct Covered {
+    vesting: Pubkey,
+    mint: Pubkey,
+    beneficiary: Keypair,
+    insurer: Pubkey,
+    unlock: i64,
+}
+
+/// A funded 1,000-unit schedule under a config that allowlists one insurer
+async fn schedule(ctx: &mut ProgramTestContext) -> Covered {
+    let authority = ctx.payer.pubkey();
+    let insurer = Pubkey::new_unique();
+    let init = initialize_config_ix(authority, vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(ctx, &[init, set_coverage_programs_ix(authority, vec![insurer])], &[]).await.unwrap();
+
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    fund(ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(ctx).await + DAY;
+    let create = create_vesting_ix(authority, beneficiary.pubkey(), mint, 1, 42, unlock, 1_000);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 42);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &escrow, 1_000).await;
+    create_ata(ctx, &beneficiary.pubkey(), &mint).await;
+
+    Covered { vesting, mint, beneficiary, insurer, unlock }
+}
+
+async fn claim(ctx: &mut ProgramTestContext, schedule: &Covered, amount: u64) -> Vec<String> {
+    let ix = withdraw_ix(schedule.vesting, schedule.mint, schedule.beneficiary.pubkey(), amount);
+    let (result, logs) = send_with_logs(ctx, &[ix], &[&schedule.beneficiary]).await;
+    result.unwrap();
+    logs
+}
+
+#[tokio::test]
+async fn claims_carry_the_policy_until_it_lapses() {
+    let mut ctx = start().await;
+    let schedule = schedule(&mut ctx).await;
+    let beneficiary = schedule.beneficiary.pubkey();
+    let expiry = schedule.unlock + DAY;
+
+    let register =
+        |program, policy_id, expiry| register_coverage_ix(schedule.vesting, beneficiary, program, policy_id, expiry);
+    let result = send(&mut ctx, &[register(Pubkey::new_unique(), POLICY_ID, expiry)], &[&schedule.beneficiary]).await;
+    assert_vesting_error(result, VestingError::CoverageProgramNotAllowed);
+    let past = now(&mut ctx).await - 1;
+    let result = send(&mut ctx, &[register(schedule.insurer, POLICY_ID, past)], &[&schedule.beneficiary]).await;
+    assert_vesting_error(result, VestingError::InvalidCoverage);
+
+    let (result, logs) =
+        send_with_logs(&mut ctx, &[register(schedule.insurer, POLICY_ID, expiry)], &[&schedule.beneficiary]).await;
+    result.unwrap();
+    let registered = decode_events::<CoverageRegistered>(&logs).remove(0);
+    assert_eq!(
+        (registered.coverage_program, registered.policy_id, registered.expiry),
+        (schedule.insurer, POLICY_ID, expiry)
+    );
+
+    warp_to(&mut ctx, schedule.unlock).await;
+    let logs = claim(&mut ctx, &schedule, 100).await;
+    assert_eq!(decode_events::<TokensWithdrawn>(&logs)[0].policy_id, POLICY_ID);
+
+    let pause = set_pause_ix(ctx.payer.pubkey(), schedule.vesting, false, true);
+    let (result, logs) = send_with_logs(&mut ctx, &[pause], &[]).await;
+    result.unwrap();
+    assert_eq!(decode_events::<PauseUpdated>(&logs)[0].policy_id, POLICY_ID);
+
+    // The first claim after expiry clears the coverage and is untagged
+    warp_to(&mut ctx, expiry).await;
+    let logs = claim(&mut ctx, &schedule, 100).await;
+    assert_eq!(decode_events::<CoverageCleared>(&logs)[0].policy_id, POLICY_ID);
+    assert_eq!(decode_events::<TokensWithdrawn>(&logs)[0].policy_id, 0);
+    let state: Vesting = fetch(&mut ctx, schedule.vesting).await;
+    assert_eq!(state.coverage, None);
+}
+
+#[tokio::test]
+async fn closing_the_schedule_clears_coverage() {
+    let mut ctx = start().await;
+    let schedule = schedule(&mut ctx).await;
+    let beneficiary = schedule.beneficiary.pubkey();
+    let expiry = schedule.unlock + 30 * DAY;
+
+    let register = register_coverage_ix(schedule.vesting, beneficiary, schedule.insurer, POLICY_ID, expiry);
+    send(&mut ctx, &[register], &[&schedule.beneficiary]).await.unwrap();
+    let state: Vesting = fetch(&mut ctx, schedule.vesting).await;
+    let coverage = CoverageInfo { coverage_program: schedule.insurer, policy_id: POLICY_ID, expiry };
+    assert_eq!(state.coverage, Some(coverage));
+
+    warp_to(&mut ctx, schedule.unlock).await;
+    claim(&mut ctx, &schedule, 1_000).await;
+    let ix = close_vesting_ix(schedule.vesting, schedule.mint, beneficiary, ctx.payer.pubkey());
+    let (result, logs) = send_with_logs(&mut ctx, &[ix], &[&schedule.beneficiary]).await;
+    result.unwrap();
+    let cleared = decode_events::<CoverageCleared>(&logs).remove(0);
+    assert_eq!((cleared.policy_id, cleared.expiry), (POLICY_ID, expiry));
+}
@@ -0,0 +1,229 @@
+//! Structured `error_ctx` log lines: format, parser, and the line each
+//! `require_ctx!` failure path leaves in the transaction logs
+//! (`cargo test --features host`).
+//!
+//! `Unauthorized` in `withdraw` is not exercised: the `has_one = beneficiary`
+//! constraint rejects a wrong signer before the handler runs.
+
+mod common;
+
+use common::*;
+use cvt_vesting::error_ctx::{format_error_ctx, ErrorContext};
+use cvt_vesting::{VestingError, MAX_GUARDIANS, MAX_RECOVERY_CO_SIGNERS, MIN_RECOVERY_TIMELOCK};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+/// Send, assert the program error, and return the parsed context line
+async fn expect_ctx(
+    ctx: &mut ProgramTestContext,
+    ixs: &[Instruction],
+    signers: &[&Keypair],
+    expected: VestingError,
+) -> ErrorContext {
+    let (result, logs) = send_with_logs(ctx, ixs, signers).await;
+    let name = format!("{:?}", expected);
+    let code = u32::from(expected);
+    assert_vesting_error(result, expected);
+
+    let context = ErrorContext::from_logs(&logs).unwrap_or_else(|| panic!("no error_ctx line in {:#?}", logs));
+    assert_eq!(context.code, code);
+    assert_eq!(context.error, name);
+    context
+}
+
+struct Setup {
+    guardians: Vec<Keypair>,
+    mint: Pubkey,
+    vesting: Pubkey,
+    beneficiary: Keypair,
+    depositor: Keypair,
+    unlock: i64,
+}
+
+/// 2-of-3 guardian config and a funded schedule of 1_000 unlocking in a day
+async fn setup(ctx: &mut ProgramTestContext) -> Setup {
+    let guardians: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+    for guardian in &guardians {
+        fund(ctx, &guardian.pubkey(), 1_000_000_000).await;
+    }
+    let init = initialize_config_ix(
+        ctx.payer.pubkey(),
+        guardians.iter().map(|g| g.pubkey()).collect(),
+        2,
+        MIN_RECOVERY_TIMELOCK,
+    );
+    send(ctx, &[init], &[]).await.unwrap();
+
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    let depositor = Keypair::new();
+    fund(ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    fund(ctx, &depositor.pubkey(), 1_000_000_000).await;
+
+    let unlock = now(ctx).await + DAY;
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 9, unlock, 1_000);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 9);
+
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    create_ata(ctx, &beneficiary.pubkey(), &mint).await;
+    let depositor_ata = create_ata(ctx, &depositor.pubkey(), &mint).await;
+    mint_to(ctx, &mint, &escrow, 1_000).await;
+    mint_to(ctx, &mint, &depositor_ata, 1_000).await;
+
+    Setup { guardians, mint, vesting, beneficiary, depositor, unlock }
+}
+
+#[test]
+fn format_and_parse_round_trip() {
+    let line = format_error_ctx(
+        6004,
+        "InsufficientBalance",
+        &[("requested", 10.to_string()), ("available", 5.to_string())],
+    );
+    assert_eq!(line, "error_ctx: code=6004 error=InsufficientBalance requested=10 available=5");
+
+    let parsed = ErrorContext::parse(&format!("Program log: {}", line)).unwrap();
+    assert_eq!(parsed, ErrorContext::parse(&line).unwrap());
+    assert_eq!(parsed.code, 6004);
+    assert_eq!(parsed.error, "InsufficientBalance");
+    assert_eq!(parsed.get_u64("requested"), Some(10));
+    assert_eq!(parsed.get_u64("available"), Some(5));
+    assert_eq!(parsed.get("missing"), None);
+
+    let bare = ErrorContext::parse("error_ctx: code=6012 error=Paused").unwrap();
+    assert!(bare.fields.is_empty());
+}
+
+#[test]
+fn parser_ignores_other_lines() {
+    for line in [
+        "",
+        "Program log: Instruction: Withdraw",
+        "Program log: AnchorError occurred. Error Code: StillLocked.",
+        "error_ctx:",
+        "error_ctx: code=abc error=StillLocked",
+        "error_ctx: error=StillLocked code=6002",
+        "error_ctx: code=6002 error=StillLocked now",
+    ] {
+        assert_eq!(ErrorContext::parse(line), None, "{line:?}");
+    }
+
+    let logs = [
+        "Program log: Instruction: Withdraw",
+        "Program log: error_ctx: code=6002 error=StillLocked unlock_timestamp=20 now=-5",
+        "Program log: AnchorError occurred.",
+    ];
+    let context = ErrorContext::from_logs(&logs).unwrap();
+    assert_eq!(context.get_i64("now"), Some(-5));
+}
+
+#[tokio::test]
+async fn create_and_config_errors_log_context() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new().pubkey();
+    let ts = now(&mut ctx).await;
+
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary, mint, 1, 1, ts, 1_000);
+    let context = expect_ctx(&mut ctx, &[create], &[], VestingError::InvalidUnlockTime).await;
+    assert_eq!(context.get_i64("unlock_timestamp"), Some(ts));
+    assert_eq!(context.get_i64("now"), Some(ts));
+
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary, mint, 1, 1, ts + DAY, 0);
+    let context = expect_ctx(&mut ctx, &[create], &[], VestingError::InvalidAmount).await;
+    assert_eq!(context.get_u64("amount"), Some(0));
+
+    let guardians: Vec<Pubkey> = (0..3).map(|_| Keypair::new().pubkey()).collect();
+
+    let init = initialize_config_ix(ctx.payer.pubkey(), guardians.clone(), 2, MIN_RECOVERY_TIMELOCK - 1);
+    let context = expect_ctx(&mut ctx, &[init], &[], VestingError::InvalidRecoveryTimelock).await;
+    assert_eq!(context.get_i64("recovery_timelock"), Some(MIN_RECOVERY_TIMELOCK - 1));
+    assert_eq!(context.get_i64("min"), Some(MIN_RECOVERY_TIMELOCK));
+
+    let init = initialize_config_ix(ctx.payer.pubkey(), vec![], 1, MIN_RECOVERY_TIMELOCK);
+    let context = expect_ctx(&mut ctx, &[init], &[], VestingError::InvalidGuardianSet).await;
+    assert_eq!(context.get_u64("guardians"), Some(0));
+    assert_eq!(context.get_u64("max"), Some(MAX_GUARDIANS as u64));
+
+    let init = initialize_config_ix(ctx.payer.pubkey(), guardians.clone(), 4, MIN_RECOVERY_TIMELOCK);
+    let context = expect_ctx(&mut ctx, &[init], &[], VestingError::InvalidGuardianSet).await;
+    assert_eq!(context.get_u64("threshold"), Some(4));
+    assert_eq!(context.get_u64("guardians"), Some(3));
+
+    let duplicated = vec![guardians[0], guardians[1], guardians[0]];
+    let init = initialize_config_ix(ctx.payer.pubkey(), duplicated, 2, MIN_RECOVERY_TIMELOCK);
+    let context = expect_ctx(&mut ctx, &[init], &[], VestingError::InvalidGuardianSet).await;
+    assert_eq!(context.get("duplicate"), Some(guardians[0].to_string().as_str()));
+}
+
+#[tokio::test]
+async fn withdraw_and_deposit_errors_log_context() {
+    let mut ctx = start().await;
+    let s = setup(&mut ctx).await;
+    let withdraw = |amount| withdraw_ix(s.vesting, s.mint, s.beneficiary.pubkey(), amount);
+
+    let ts = now(&mut ctx).await;
+    let context = expect_ctx(&mut ctx, &[withdraw(1)], &[&s.beneficiary], VestingError::StillLocked).await;
+    assert_eq!(context.get_i64("unlock_timestamp"), Some(s.unlock));
+    assert_eq!(context.get_i64("now"), Some(ts));
+
+    warp_to(&mut ctx, s.unlock).await;
+    send(&mut ctx, &[withdraw(400)], &[&s.beneficiary]).await.unwrap();
+    let context = expect_ctx(&mut ctx, &[withdraw(601)], &[&s.beneficiary], VestingError::InsufficientBalance).await;
+    assert_eq!(context.get_u64("requested"), Some(601));
+    assert_eq!(context.get_u64("available"), Some(600));
+
+    let deposit = |amount| deposit_ix(s.vesting, s.mint, s.depositor.pubkey(), amount);
+    let context = expect_ctx(&mut ctx, &[deposit(0)], &[&s.depositor], VestingError::InvalidAmount).await;
+    assert_eq!(context.get_u64("amount"), Some(0));
+
+    let authority = ctx.payer.pubkey();
+    send(&mut ctx, &[set_pause_ix(authority, s.vesting, false, true)], &[]).await.unwrap();
+    let context = expect_ctx(&mut ctx, &[deposit(1)], &[&s.depositor], VestingError::DepositsPaused).await;
+    assert_eq!(context.get("vesting"), Some(s.vesting.to_string().as_str()));
+
+    send(&mut ctx, &[set_pause_ix(authority, s.vesting, true, false)], &[]).await.unwrap();
+    // Distinct amounts: an identical transaction within one blockhash is deduplicated
+    let context = expect_ctx(&mut ctx, &[deposit(2)], &[&s.depositor], VestingError::Paused).await;
+    assert_eq!(context.get("vesting"), Some(s.vesting.to_string().as_str()));
+    let context = expect_ctx(&mut ctx, &[withdraw(2)], &[&s.beneficiary], VestingError::Paused).await;
+    assert_eq!(context.get("vesting"), Some(s.vesting.to_string().as_str()));
+}
+
+#[tokio::test]
+async fn recovery_errors_log_context() {
+    let mut ctx = start().await;
+    let s = setup(&mut ctx).await;
+    let proposer = &s.guardians[0];
+    let replacement = Keypair::new().pubkey();
+
+    let propose = propose_recovery_ix(proposer, &[&s.guardians[1]], s.vesting, s.beneficiary.pubkey());
+    let context = expect_ctx(&mut ctx, &[propose], &[proposer, &s.guardians[1]], VestingError::InvalidBeneficiary).await;
+    assert_eq!(context.get("new_beneficiary"), Some(s.beneficiary.pubkey().to_string().as_str()));
+
+    let mut propose = propose_recovery_ix(proposer, &[&s.guardians[1]], s.vesting, replacement);
+    propose
+        .accounts
+        .extend((0..MAX_RECOVERY_CO_SIGNERS).map(|_| AccountMeta::new_readonly(Keypair::new().pubkey(), false)));
+    let context = expect_ctx(&mut ctx, &[propose], &[proposer, &s.guardians[1]], VestingError::BatchTooLarge).await;
+    assert_eq!(context.get_u64("co_signers"), Some(MAX_RECOVERY_CO_SIGNERS as u64 + 1));
+    assert_eq!(context.get_u64("max"), Some(MAX_RECOVERY_CO_SIGNERS as u64));
+
+    let propose = propose_recovery_ix(proposer, &[], s.vesting, replacement);
+    let context = expect_ctx(&mut ctx, &[propose], &[proposer], VestingError::InsufficientGuardianApprovals).await;
+    assert_eq!(context.get_u64("approvals"), Some(1));
+    assert_eq!(context.get_u64("threshold"), Some(2));
+
+    let propose = propose_recovery_ix(proposer, &[&s.guardians[1]], s.vesting, replacement);
+    send(&mut ctx, &[propose], &[proposer, &s.guardians[1]]).await.unwrap();
+    let ts = now(&mut ctx).await;
+    let execute = execute_recovery_ix(s.vesting, proposer.pubkey());
+    let context = expect_ctx(&mut ctx, &[execute], &[], VestingError::RecoveryTimelockActive).await;
+    assert_eq!(context.get_i64("executable_at"), Some(ts + MIN_RECOVERY_TIMELOCK));
+    assert_eq!(context.get_i64("now"), Some(ts));
+}
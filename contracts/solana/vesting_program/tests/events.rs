@@ -0,0 +1,351 @@
+//! Event routing prefix (category + label_hash), documented event sizes and
+//! the config's event verbosity flag
+
+mod common;
+
+use anchor_lang::{Event, InstructionData, ToAccountMetas};
+use common::*;
+use cvt_vesting::{
+    AutoClaimUpdated, BeneficiaryRecovered, BeneficiaryRecoveryCancelled, BeneficiaryRecoveryProposed,
+    BridgeDepositCredited, CategoryUpdated, CoverageCleared, CoverageRegistered, DustDisposition, GuardianRotationCancelled,
+    GuardianRotationProposed, GuardiansRotated, KycExemptionUpdated, MintMigrated, MintMigrationCancelled,
+    MintMigrationProposed, PauseExpired, PauseUpdated, TokensDeposited, TokensWithdrawn, UnlockExtended, Vesting,
+    VestingClosed, VestingConfig, VestingCreated, VestingExpired, EVENT_ROUTING_LEN, MIN_RECOVERY_TIMELOCK,
+};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const CATEGORY_FINANCE: u8 = 2;
+const CATEGORY_COMMUNITY: u8 = 3;
+const LABEL_HASH: [u8; 32] = [0x1b; 32];
+
+fn routing(payload: &[u8]) -> (u8, [u8; 32]) {
+    (payload[8], payload[9..8 + EVENT_ROUTING_LEN].try_into().unwrap())
+}
+
+#[test]
+fn event_sizes_match_docs() {
+    let key = Pubkey::default();
+    let sizes = [
+        VestingCreated {
+            category: 0,
+            label_hash: [0; 32],
+            vesting: key,
+            beneficiary: key,
+            mint: key,
+            amount: 0,
+            unlock_timestamp: 0,
+            terms_hash: [0; 32],
+        }
+        .data()
+        .len(),
+        TokensWithdrawn {
+            category: 0,
+            label_hash: [0; 32],
+            vesting: key,
+            beneficiary: key,
+            amount: 0,
+            withdrawn: 0,
+            policy_id: 0,
+        }
+        .data()
+        .len(),
+        TokensDeposited { category: 0, label_hash: [0; 32], vesting: key, depositor: key, amount: 0 }.data().len(),
+        CategoryUpdated { category: 0, label_hash: [0; 32], vesting: key, previous_category: 0 }.data().len(),
+        BeneficiaryRecoveryProposed {
+            category: 0,
+            label_hash: [0; 32],
+            vesting: key,
+            current_beneficiary: key,
+            new_beneficiary: key,
+            approvals: 0,
+            executable_at: 0,
+            policy_id: 0,
+        }
+        .data()
+        .len(),
+        BeneficiaryRecovered {
+            category: 0,
+            label_hash: [0; 32],
+            vesting: key,
+            previous_beneficiary: key,
+            new_beneficiary: key,
+            policy_id: 0,
+        }
+        .data()
+        .len(),
+        BeneficiaryRecoveryCancelled { category: 0, label_hash: [0; 32], vesting: key, beneficiary: key }.data().len(),
+        PauseUpdated {
+            category: 0,
+            label_hash: [0; 32],
+            vesting: key,
+            is_paused: false,
+            deposits_paused: false,
+            paused_until: 0,
+            repause: false,
+            policy_id: 0,
+        }
+        .data()
+        .len(),
+        PauseExpired { category: 0, label_hash: [0; 32], vesting: key, paused_until: 0 }.data().len(),
+        KycExemptionUpdated { category: 0, label_hash: [0; 32], vesting: key, kyc_exempt: false }.data().len(),
+        VestingClosed {
+            category: 0,
+            label_hash: [0; 32],
+            vesting: key,
+            beneficiary: key,
+            dust: 0,
+            dust_threshold: 0,
+            disposition: DustDisposition::None,
+            withdrawn: 0,
+            lock_seconds_accumulated: 0,
+        }
+        .data()
+        .len(),
+        AutoClaimUpdated { category: 0, label_hash: [0; 32], vesting: key, delegate: key, max_total: 0 }.data().len(),
+        CoverageRegistered { category: 0, label_hash: [0; 32], vesting: key, coverage_program: key, policy_id: 0, expiry: 0 }
+            .data()
+            .len(),
+        CoverageCleared { category: 0, label_hash: [0; 32], vesting: key, coverage_program: key, policy_id: 0, expiry: 0 }
+            .data()
+            .len(),
+        MintMigrationProposed {
+            category: 0,
+            label_hash: [0; 32],
+            vesting: key,
+            new_mint: key,
+            conversion_numerator: 0,
+            conversion_denominator: 0,
+            deposited: 0,
+            new_authority: key,
+        }
+        .data()
+        .len(),
+        MintMigrated {
+            category: 0,
+            label_hash: [0; 32],
+            vesting: key,
+            old_mint: key,
+            new_mint: key,
+            total_amount: 0,
+            withdrawn: 0,
+            released: 0,
+        }
+        .data()
+        .len(),
+        MintMigrationCancelled { category: 0, label_hash: [0; 32], vesting: key, new_mint: key, refunded: 0 }.data().len(),
+        UnlockExtended {
+            category: 0,
+            label_hash: [0; 32],
+            vesting: key,
+            previous_unlock: 0,
+            unlock_timestamp: 0,
+            max_unlock_timestamp: 0,
+        }
+        .data()
+        .len(),
+        BridgeDepositCredited { category: 0, label_hash: [0; 32], vesting: key, operation_id: [0; 32], amount: 0 }.data().len(),
+        VestingExpired {
+            category: 0,
+            label_hash: [0; 32],
+            vesting: key,
+            sink: key,
+            amount: 0,
+            forfeited: 0,
+            burned: false,
+        }
+        .data()
+        .len(),
+        GuardianRotationProposed {
+            category: 0,
+            label_hash: [0; 32],
+            proposer: key,
+            guardians_hash: [0; 32],
+            guardian_count: 0,
+            guardian_threshold: 0,
+            approvals: 0,
+            executable_at: 0,
+        }
+        .data()
+        .len(),
+        GuardiansRotated {
+            category: 0,
+            label_hash: [0; 32],
+            guardians_hash: [0; 32],
+            guardian_count: 0,
+            guardian_threshold: 0,
+            guardian_epoch: 0,
+        }
+        .data()
+        .len(),
+        GuardianRotationCancelled { category: 0, label_hash: [0; 32], guardians_hash: [0; 32], cancelled_by: key, approvals: 0 }
+            .data()
+            .len(),
+    ];
+    assert_eq!(
+        sizes,
+        [185, 129, 113, 74, 154, 145, 105, 92, 81, 74, 146, 113, 121, 121, 161, 161, 113, 97, 113, 122, 116, 79, 106]
+    );
+}
+
+#[tokio::test]
+async fn every_event_carries_routing_prefix() {
+    let mut ctx = start().await;
+    let guardian = Keypair::new();
+    fund(&mut ctx, &guardian.pubkey(), 1_000_000_000).await;
+    let init = initialize_config_ix(ctx.payer.pubkey(), vec![guardian.pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(&mut ctx, &[init], &[]).await.unwrap();
+    let authority = ctx.payer.pubkey();
+
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    let depositor = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    fund(&mut ctx, &depositor.pubkey(), 1_000_000_000).await;
+    let unlock = now(&mut ctx).await + DAY;
+
+    let data = cvt_vesting::instruction::CreateVesting {
+        category: CATEGORY_FINANCE,
+        label_hash: LABEL_HASH,
+        ..base_create(1, 11, unlock, 500)
+    };
+    let create = create_vesting_ix_with(authority, beneficiary.pubkey(), mint, data);
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 11);
+    let (result, logs) = send_with_logs(&mut ctx, &[create], &[]).await;
+    result.unwrap();
+    let created: Vec<VestingCreated> = decode_events(&logs);
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0].vesting, vesting);
+    assert_eq!(created[0].amount, 500);
+
+    let state: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!((state.category, state.label_hash), (CATEGORY_FINANCE, LABEL_HASH));
+
+    create_ata(&mut ctx, &vesting, &mint).await;
+    create_ata(&mut ctx, &beneficiary.pubkey(), &mint).await;
+    let depositor_ata = create_ata(&mut ctx, &depositor.pubkey(), &mint).await;
+    mint_to(&mut ctx, &mint, &depositor_ata, 500).await;
+
+    let mut payloads = event_payloads(&logs);
+    let (result, logs) = send_with_logs(&mut ctx, &[deposit_ix(vesting, mint, depositor.pubkey(), 500)], &[&depositor]).await;
+    result.unwrap();
+    assert_eq!(decode_events::<TokensDeposited>(&logs)[0].depositor, depositor.pubkey());
+    payloads.extend(event_payloads(&logs));
+
+    warp_to(&mut ctx, unlock).await;
+    let (result, logs) = send_with_logs(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 200)], &[&beneficiary]).await;
+    result.unwrap();
+    assert_eq!(decode_events::<TokensWithdrawn>(&logs)[0].withdrawn, 200);
+    payloads.extend(event_payloads(&logs));
+
+    let (result, logs) = send_with_logs(&mut ctx, &[set_pause_ix(authority, vesting, false, true)], &[]).await;
+    result.unwrap();
+    payloads.extend(event_payloads(&logs));
+
+    let (result, logs) = send_with_logs(&mut ctx, &[propose_recovery_ix(&guardian, &[], vesting, Keypair::new().pubkey())], &[&guardian]).await;
+    result.unwrap();
+    payloads.extend(event_payloads(&logs));
+
+    let object = Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::ObjectRecovery {
+            vesting,
+            recovery: recovery_pda(&vesting),
+            proposer: guardian.pubkey(),
+            beneficiary: beneficiary.pubkey(),
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::ObjectBeneficiaryRecovery {}.data(),
+    };
+    let (result, logs) = send_with_logs(&mut ctx, &[object], &[&beneficiary]).await;
+    result.unwrap();
+    payloads.extend(event_payloads(&logs));
+
+    assert_eq!(payloads.len(), 6);
+    for payload in &payloads {
+        assert_eq!(routing(payload), (CATEGORY_FINANCE, LABEL_HASH));
+    }
+
+    // Recategorization is announced and applies to later events
+    let (result, logs) = send_with_logs(&mut ctx, &[update_category_ix(authority, vesting, CATEGORY_COMMUNITY)], &[]).await;
+    result.unwrap();
+    let updated: Vec<CategoryUpdated> = decode_events(&logs);
+    assert_eq!(updated.len(), 1);
+    assert_eq!((updated[0].category, updated[0].previous_category), (CATEGORY_COMMUNITY, CATEGORY_FINANCE));
+
+    let (result, logs) = send_with_logs(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 100)], &[&beneficiary]).await;
+    result.unwrap();
+    assert_eq!(routing(&event_payloads(&logs)[0]), (CATEGORY_COMMUNITY, LABEL_HASH));
+
+    // Only the config authority can recategorize
+    let result = send(&mut ctx, &[update_category_ix(beneficiary.pubkey(), vesting, 0)], &[&beneficiary]).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn verbosity_flag_gates_routine_events_only() {
+    let mut ctx = start().await;
+    let guardian = Keypair::new();
+    let init = initialize_config_ix(ctx.payer.pubkey(), vec![guardian.pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(&mut ctx, &[init], &[]).await.unwrap();
+    let authority = ctx.payer.pubkey();
+    let config: VestingConfig = fetch(&mut ctx, config_pda()).await;
+    assert!(config.verbose_events);
+
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    let depositor = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    fund(&mut ctx, &depositor.pubkey(), 1_000_000_000).await;
+    let unlock = now(&mut ctx).await + DAY;
+
+    let create = |nonce| create_vesting_ix(authority, beneficiary.pubkey(), mint, 1, nonce, unlock, 500);
+    let (result, logs) = send_with_logs(&mut ctx, &[create(1)], &[]).await;
+    result.unwrap();
+    assert_eq!(decode_events::<VestingCreated>(&logs).len(), 1);
+
+    // Only the config authority can change verbosity
+    let result = send(&mut ctx, &[set_event_verbosity_ix(beneficiary.pubkey(), false)], &[&beneficiary]).await;
+    assert!(result.is_err());
+    send(&mut ctx, &[set_event_verbosity_ix(authority, false)], &[]).await.unwrap();
+    let config: VestingConfig = fetch(&mut ctx, config_pda()).await;
+    assert!(!config.verbose_events);
+
+    let (result, logs) = send_with_logs(&mut ctx, &[create(2)], &[]).await;
+    result.unwrap();
+    assert!(event_payloads(&logs).is_empty());
+
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 2);
+    create_ata(&mut ctx, &vesting, &mint).await;
+    create_ata(&mut ctx, &beneficiary.pubkey(), &mint).await;
+    let depositor_ata = create_ata(&mut ctx, &depositor.pubkey(), &mint).await;
+    mint_to(&mut ctx, &mint, &depositor_ata, 500).await;
+
+    let deposit = deposit_ix(vesting, mint, depositor.pubkey(), 500);
+    let (result, logs) = send_with_logs(&mut ctx, &[deposit], &[&depositor]).await;
+    result.unwrap();
+    assert!(event_payloads(&logs).is_empty());
+
+    warp_to(&mut ctx, unlock).await;
+    let withdraw = |amount| withdraw_ix(vesting, mint, beneficiary.pubkey(), amount);
+    let (result, logs) = send_with_logs(&mut ctx, &[withdraw(100)], &[&beneficiary]).await;
+    result.unwrap();
+    assert!(event_payloads(&logs).is_empty());
+
+    // Security-relevant events are unconditional
+    let (result, logs) = send_with_logs(&mut ctx, &[set_pause_ix(authority, vesting, false, true)], &[]).await;
+    result.unwrap();
+    assert_eq!(decode_events::<PauseUpdated>(&logs).len(), 1);
+    let (result, logs) = send_with_logs(&mut ctx, &[update_category_ix(authority, vesting, CATEGORY_FINANCE)], &[]).await;
+    result.unwrap();
+    assert_eq!(decode_events::<CategoryUpdated>(&logs).len(), 1);
+
+    // Back on
+    send(&mut ctx, &[set_event_verbosity_ix(authority, true)], &[]).await.unwrap();
+    let (result, logs) = send_with_logs(&mut ctx, &[withdraw(200)], &[&beneficiary]).await;
+    result.unwrap();
+    assert_eq!(decode_events::<TokensWithdrawn>(&logs)[0].withdrawn, 300);
+}
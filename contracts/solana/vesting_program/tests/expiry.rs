@@ -0,0 +1,127 @@
+//! Expiry: what a schedule leaves unclaimed at its claim deadline goes to the
+//! expiry sink fixed at creation, a treasury or (burned) the incinerator
+
+mod common;
+
+use anchor_lang::solana_program::incinerator;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::Mint;
+use common::*;
+use cvt_vesting::{Vesting, VestingError, VestingExpired};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+struct Schedule {
+    vesting: Pubkey,
+    mint: Pubkey,
+    beneficiary: Keypair,
+    deadline: i64,
+}
+
+/// A funded 1,000-unit schedule expiring to `sink` a year after unlock, of
+/// which the beneficiary has claimed 300
+async fn claimed_in_part(ctx: &mut ProgramTestContext, sink: Pubkey) -> Schedule {
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    fund(ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(ctx).await + DAY;
+    let deadline = unlock + YEAR;
+
+    let data = cvt_vesting::instruction::CreateVesting {
+        claim_deadline: deadline,
+        expiry_sink: sink,
+        ..base_create(1, 95, unlock, 1_000)
+    };
+    let create = create_vesting_ix_with(ctx.payer.pubkey(), beneficiary.pubkey(), mint, data);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 95);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &escrow, 1_000).await;
+    warp_to(ctx, unlock).await;
+    send(ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 300)], &[&beneficiary]).await.unwrap();
+
+    Schedule { vesting, mint, beneficiary, deadline }
+}
+
+async fn expire(ctx: &mut ProgramTestContext, schedule: &Schedule, sink_tokens: Option<Pubkey>) -> VestingExpired {
+    let (result, logs) = send_with_logs(ctx, &[expire_and_return_ix(schedule.vesting, schedule.mint, sink_tokens)], &[]).await;
+    result.unwrap();
+    decode_events::<VestingExpired>(&logs).remove(0)
+}
+
+#[tokio::test]
+async fn expiry_routes_the_unclaimed_balance_to_a_treasury() {
+    let mut ctx = start().await;
+    let treasury = Pubkey::new_unique();
+    let schedule = claimed_in_part(&mut ctx, treasury).await;
+    let treasury_tokens = create_ata(&mut ctx, &treasury, &schedule.mint).await;
+
+    let result = send(&mut ctx, &[expire_and_return_ix(schedule.vesting, schedule.mint, Some(treasury_tokens))], &[]).await;
+    assert_vesting_error(result, VestingError::ClaimWindowOpen);
+
+    warp_to(&mut ctx, schedule.deadline).await;
+    // Only the sink chosen at creation can receive
+    let elsewhere = create_ata(&mut ctx, &Pubkey::new_unique(), &schedule.mint).await;
+    let result = send(&mut ctx, &[expire_and_return_ix(schedule.vesting, schedule.mint, Some(elsewhere))], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidExpirySink);
+    let result = send(&mut ctx, &[expire_and_return_ix(schedule.vesting, schedule.mint, None)], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidExpirySink);
+
+    let event = expire(&mut ctx, &schedule, Some(treasury_tokens)).await;
+    assert_eq!((event.sink, event.amount, event.forfeited, event.burned), (treasury, 700, 700, false));
+    assert_eq!(token_balance(&mut ctx, &treasury_tokens).await, 700);
+
+    // The remainder is forfeited, not just moved
+    let state: Vesting = fetch(&mut ctx, schedule.vesting).await;
+    assert_eq!((state.total_amount, state.withdrawn), (300, 300));
+    let escrow = get_associated_token_address(&schedule.vesting, &schedule.mint);
+    mint_to(&mut ctx, &schedule.mint, &escrow, 100).await;
+    let withdraw = withdraw_ix(schedule.vesting, schedule.mint, schedule.beneficiary.pubkey(), 1);
+    let result = send(&mut ctx, &[withdraw], &[&schedule.beneficiary]).await;
+    assert_vesting_error(result, VestingError::InsufficientBalance);
+}
+
+#[tokio::test]
+async fn expiry_to_the_incinerator_burns() {
+    let mut ctx = start().await;
+    let schedule = claimed_in_part(&mut ctx, incinerator::ID).await;
+    warp_to(&mut ctx, schedule.deadline).await;
+
+    let event = expire(&mut ctx, &schedule, None).await;
+    assert_eq!((event.sink, event.amount, event.burned), (incinerator::ID, 700, true));
+    let mint: Mint = fetch(&mut ctx, schedule.mint).await;
+    assert_eq!(mint.supply, 300);
+    let escrow = get_associated_token_address(&schedule.vesting, &schedule.mint);
+    assert_eq!(token_balance(&mut ctx, &escrow).await, 0);
+}
+
+#[tokio::test]
+async fn schedules_without_a_sink_never_expire() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Pubkey::new_unique();
+    let unlock = now(&mut ctx).await + DAY;
+    let payer = ctx.payer.pubkey();
+    send(&mut ctx, &[create_vesting_ix(payer, beneficiary, mint, 1, 96, unlock, 1_000)], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary, &mint, 1, 96);
+    create_ata(&mut ctx, &vesting, &mint).await;
+
+    warp_to(&mut ctx, unlock + 10 * YEAR).await;
+    let result = send(&mut ctx, &[expire_and_return_ix(vesting, mint, None)], &[]).await;
+    assert_vesting_error(result, VestingError::ExpirySinkNotSet);
+
+    // A sink needs a deadline after the latest possible unlock
+    let unlock = now(&mut ctx).await + DAY;
+    let data = cvt_vesting::instruction::CreateVesting {
+        max_unlock_timestamp: unlock + DAY,
+        claim_deadline: unlock + DAY,
+        expiry_sink: payer,
+        ..base_create(2, 96, unlock, 1_000)
+    };
+    let create = create_vesting_ix_with(payer, beneficiary, mint, data);
+    let result = send(&mut ctx, &[create], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidSchedule);
+}
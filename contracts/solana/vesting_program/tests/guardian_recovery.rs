@@ -0,0 +1,164 @@
+//! Guardian-driven beneficiary recovery for lost keys
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::*;
+use cvt_vesting::{RecoveryProposal, Vesting, VestingError, MAX_RECOVERY_CO_SIGNERS, MIN_RECOVERY_TIMELOCK};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    signature::{Keypair, Signer},
+};
+
+struct Setup {
+    guardians: Vec<Keypair>,
+    vesting: Pubkey,
+    beneficiary: Keypair,
+}
+
+async fn setup(ctx: &mut ProgramTestContext) -> Setup {
+    let guardians: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+    let init = initialize_config_ix(
+        ctx.payer.pubkey(),
+        guardians.iter().map(|g| g.pubkey()).collect(),
+        2,
+        MIN_RECOVERY_TIMELOCK,
+    );
+    send(ctx, &[init], &[]).await.unwrap();
+
+    // Fund guardians so they can pay for proposals
+    for guardian in &guardians {
+        fund(ctx, &guardian.pubkey(), 1_000_000_000).await;
+    }
+
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 7);
+    let unlock = now(ctx).await + YEAR;
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 7, unlock, 1_000_000);
+    send(ctx, &[create], &[]).await.unwrap();
+
+    Setup { guardians, vesting, beneficiary }
+}
+
+#[tokio::test]
+async fn proposal_requires_guardian_quorum() {
+    let mut ctx = start().await;
+    let s = setup(&mut ctx).await;
+    let new_beneficiary = Keypair::new().pubkey();
+
+    // One guardian alone is below the 2-of-3 threshold
+    let result = send(&mut ctx, &[propose_recovery_ix(&s.guardians[0], &[], s.vesting, new_beneficiary)], &[&s.guardians[0]]).await;
+    assert_vesting_error(result, VestingError::InsufficientGuardianApprovals);
+
+    // A non-guardian co-signer doesn't count towards quorum
+    let outsider = Keypair::new();
+    let result = send(
+        &mut ctx,
+        &[propose_recovery_ix(&s.guardians[0], &[&outsider], s.vesting, new_beneficiary)],
+        &[&s.guardians[0], &outsider],
+    )
+    .await;
+    assert_vesting_error(result, VestingError::InsufficientGuardianApprovals);
+
+    send(
+        &mut ctx,
+        &[propose_recovery_ix(&s.guardians[0], &[&s.guardians[1]], s.vesting, new_beneficiary)],
+        &[&s.guardians[0], &s.guardians[1]],
+    )
+    .await
+    .unwrap();
+
+    let recovery: RecoveryProposal = fetch(&mut ctx, recovery_pda(&s.vesting)).await;
+    assert_eq!(recovery.new_beneficiary, new_beneficiary);
+    assert_eq!(recovery.approvals, 2);
+    assert_eq!(recovery.executable_at - recovery.proposed_at, MIN_RECOVERY_TIMELOCK);
+}
+
+#[tokio::test]
+async fn recovery_executes_only_after_timelock() {
+    let mut ctx = start().await;
+    let s = setup(&mut ctx).await;
+    let new_beneficiary = Keypair::new().pubkey();
+    let proposer = &s.guardians[0];
+
+    send(
+        &mut ctx,
+        &[propose_recovery_ix(proposer, &[&s.guardians[2]], s.vesting, new_beneficiary)],
+        &[proposer, &s.guardians[2]],
+    )
+    .await
+    .unwrap();
+
+    warp_forward(&mut ctx, MIN_RECOVERY_TIMELOCK - 1).await;
+    let result = send(&mut ctx, &[execute_recovery_ix(s.vesting, proposer.pubkey())], &[]).await;
+    assert_vesting_error(result, VestingError::RecoveryTimelockActive);
+
+    warp_forward(&mut ctx, 1).await;
+    send(&mut ctx, &[execute_recovery_ix(s.vesting, proposer.pubkey())], &[]).await.unwrap();
+
+    let vesting: Vesting = fetch(&mut ctx, s.vesting).await;
+    assert_eq!(vesting.beneficiary, new_beneficiary);
+    assert_eq!(vesting.original_beneficiary, s.beneficiary.pubkey());
+    assert!(ctx.banks_client.get_account(recovery_pda(&s.vesting)).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn live_beneficiary_can_object() {
+    let mut ctx = start().await;
+    let s = setup(&mut ctx).await;
+    let proposer = &s.guardians[1];
+
+    send(
+        &mut ctx,
+        &[propose_recovery_ix(proposer, &[&s.guardians[2]], s.vesting, Keypair::new().pubkey())],
+        &[proposer, &s.guardians[2]],
+    )
+    .await
+    .unwrap();
+
+    let object = Instruction {
+        program_id: cvt_vesting::ID,
+        accounts: cvt_vesting::accounts::ObjectRecovery {
+            vesting: s.vesting,
+            recovery: recovery_pda(&s.vesting),
+            proposer: proposer.pubkey(),
+            beneficiary: s.beneficiary.pubkey(),
+        }
+        .to_account_metas(None),
+        data: cvt_vesting::instruction::ObjectBeneficiaryRecovery {}.data(),
+    };
+    send(&mut ctx, &[object], &[&s.beneficiary]).await.unwrap();
+
+    warp_forward(&mut ctx, MIN_RECOVERY_TIMELOCK).await;
+    assert!(send(&mut ctx, &[execute_recovery_ix(s.vesting, proposer.pubkey())], &[]).await.is_err());
+
+    let vesting: Vesting = fetch(&mut ctx, s.vesting).await;
+    assert_eq!(vesting.beneficiary, s.beneficiary.pubkey());
+}
+
+#[tokio::test]
+async fn co_signer_batch_is_bounded() {
+    let mut ctx = start().await;
+    let s = setup(&mut ctx).await;
+    let proposer = &s.guardians[0];
+
+    // Pad the quorum with non-signing accounts up to (and past) the batch cap
+    let padded = |extra: usize| {
+        let mut ix = propose_recovery_ix(proposer, &[&s.guardians[1]], s.vesting, Keypair::new().pubkey());
+        ix.accounts
+            .extend((0..extra).map(|_| AccountMeta::new_readonly(Keypair::new().pubkey(), false)));
+        ix
+    };
+
+    let over = padded(MAX_RECOVERY_CO_SIGNERS);
+    let result = send(&mut ctx, &[over], &[proposer, &s.guardians[1]]).await;
+    assert_vesting_error(result, VestingError::BatchTooLarge);
+
+    let at_cap = padded(MAX_RECOVERY_CO_SIGNERS - 1);
+    send(&mut ctx, &[at_cap], &[proposer, &s.guardians[1]]).await.unwrap();
+    let recovery: RecoveryProposal = fetch(&mut ctx, recovery_pda(&s.vesting)).await;
+    assert_eq!(recovery.approvals, 2);
+}
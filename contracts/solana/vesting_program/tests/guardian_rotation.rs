@@ -0,0 +1,198 @@
+//! Guardian set rotation: the current quorum replaces every guardian and the
+//! threshold in one timelocked step
+
+mod common;
+
+use common::*;
+use cvt_vesting::{
+    guardian_set_hash, BeneficiaryRecoveryCancelled, GuardianRotationCancelled, GuardianRotationProposed, GuardiansRotated,
+    Vesting, VestingConfig, VestingError, MAX_GUARDIANS, MIN_RECOVERY_TIMELOCK,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+async fn funded_guardians(ctx: &mut ProgramTestContext, count: usize) -> Vec<Keypair> {
+    let guardians: Vec<Keypair> = (0..count).map(|_| Keypair::new()).collect();
+    for guardian in &guardians {
+        fund(ctx, &guardian.pubkey(), 1_000_000_000).await;
+    }
+    guardians
+}
+
+fn keys(guardians: &[Keypair]) -> Vec<Pubkey> {
+    guardians.iter().map(|g| g.pubkey()).collect()
+}
+
+/// Config with a 3-of-5 guardian quorum
+async fn setup(ctx: &mut ProgramTestContext) -> Vec<Keypair> {
+    let guardians = funded_guardians(ctx, 5).await;
+    let init = initialize_config_ix(ctx.payer.pubkey(), keys(&guardians), 3, MIN_RECOVERY_TIMELOCK);
+    send(ctx, &[init], &[]).await.unwrap();
+    guardians
+}
+
+#[tokio::test]
+async fn quorum_rotates_three_of_five_to_four_of_seven() {
+    let mut ctx = start().await;
+    let old = setup(&mut ctx).await;
+    let new = funded_guardians(&mut ctx, 7).await;
+
+    // Two of the current five are below quorum
+    let rotate = rotate_guardians_ix(&old[0], &[&old[1]], keys(&new), 4);
+    let result = send(&mut ctx, &[rotate], &[&old[0], &old[1]]).await;
+    assert_vesting_error(result, VestingError::InsufficientGuardianApprovals);
+
+    let rotate = rotate_guardians_ix(&old[0], &[&old[1], &old[2]], keys(&new), 4);
+    send(&mut ctx, &[rotate], &[&old[0], &old[1], &old[2]]).await.unwrap();
+
+    // Nothing changes until the timelock elapses
+    warp_forward(&mut ctx, MIN_RECOVERY_TIMELOCK - 1).await;
+    let result = send(&mut ctx, &[execute_guardian_rotation_ix(old[0].pubkey())], &[]).await;
+    assert_vesting_error(result, VestingError::RecoveryTimelockActive);
+    let config: VestingConfig = fetch(&mut ctx, config_pda()).await;
+    assert_eq!((config.guardians, config.guardian_threshold), (keys(&old), 3));
+
+    warp_forward(&mut ctx, 1).await;
+    send(&mut ctx, &[execute_guardian_rotation_ix(old[0].pubkey())], &[]).await.unwrap();
+    let config: VestingConfig = fetch(&mut ctx, config_pda()).await;
+    assert_eq!((config.guardians, config.guardian_threshold), (keys(&new), 4));
+    assert!(ctx.banks_client.get_account(guardian_rotation_pda()).await.unwrap().is_none());
+
+    // The old quorum no longer counts; the new one needs four
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new().pubkey();
+    let unlock = now(&mut ctx).await + YEAR;
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary, mint, 1, 9, unlock, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary, &mint, 1, 9);
+    let target = Keypair::new().pubkey();
+
+    let propose = propose_recovery_ix(&old[0], &[&old[1], &old[2]], vesting, target);
+    let result = send(&mut ctx, &[propose], &[&old[0], &old[1], &old[2]]).await;
+    assert_vesting_error(result, VestingError::InsufficientGuardianApprovals);
+    let propose = propose_recovery_ix(&new[0], &[&new[1], &new[2], &new[3]], vesting, target);
+    send(&mut ctx, &[propose], &[&new[0], &new[1], &new[2], &new[3]]).await.unwrap();
+}
+
+#[tokio::test]
+async fn malformed_sets_are_rejected() {
+    let mut ctx = start().await;
+    let old = setup(&mut ctx).await;
+    let quorum = [&old[1], &old[2]];
+    let signers = [&old[0], &old[1], &old[2]];
+    let fresh = |n: usize| -> Vec<Pubkey> { (0..n).map(|_| Keypair::new().pubkey()).collect() };
+
+    let mut duplicated = fresh(4);
+    duplicated.push(duplicated[0]);
+    for (guardians, threshold) in [
+        (duplicated, 2),
+        (fresh(4), 0),
+        (fresh(4), 5),
+        (vec![], 0),
+        (fresh(MAX_GUARDIANS + 1), 3),
+    ] {
+        let rotate = rotate_guardians_ix(&old[0], &quorum, guardians, threshold);
+        let result = send(&mut ctx, &[rotate], &signers).await;
+        assert_vesting_error(result, VestingError::InvalidGuardianSet);
+    }
+
+    // A valid proposal still goes through, and only one can be pending
+    send(&mut ctx, &[rotate_guardians_ix(&old[0], &quorum, fresh(4), 2)], &signers).await.unwrap();
+    let result = send(&mut ctx, &[rotate_guardians_ix(&old[0], &quorum, fresh(4), 3)], &signers).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn config_authority_cannot_loosen_the_quorum() {
+    let mut ctx = start().await;
+    let old = setup(&mut ctx).await;
+    let authority = ctx.payer.insecure_clone();
+
+    // The authority isn't a guardian: alone, or with guardians short of
+    // quorum, it can't lower the threshold
+    let rotate = rotate_guardians_ix(&authority, &[], keys(&old), 1);
+    let result = send(&mut ctx, &[rotate], &[]).await;
+    assert_vesting_error(result, VestingError::InsufficientGuardianApprovals);
+    let rotate = rotate_guardians_ix(&authority, &[&old[0], &old[1]], keys(&old), 1);
+    let result = send(&mut ctx, &[rotate], &[&old[0], &old[1]]).await;
+    assert_vesting_error(result, VestingError::InsufficientGuardianApprovals);
+
+    let config: VestingConfig = fetch(&mut ctx, config_pda()).await;
+    assert_eq!((config.guardian_threshold, config.recovery_timelock), (3, MIN_RECOVERY_TIMELOCK));
+    assert!(ctx.banks_client.get_account(guardian_rotation_pda()).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn quorum_can_cancel_a_pending_rotation() {
+    let mut ctx = start().await;
+    let old = setup(&mut ctx).await;
+    let new = funded_guardians(&mut ctx, 3).await;
+
+    let rotate = rotate_guardians_ix(&old[0], &[&old[1], &old[2]], keys(&new), 2);
+    let (result, logs) = send_with_logs(&mut ctx, &[rotate], &[&old[0], &old[1], &old[2]]).await;
+    result.unwrap();
+    let proposed: Vec<GuardianRotationProposed> = decode_events(&logs);
+    assert_eq!(proposed.len(), 1);
+    assert_eq!(proposed[0].guardians_hash, guardian_set_hash(&keys(&new)));
+    assert_eq!((proposed[0].guardian_count, proposed[0].guardian_threshold, proposed[0].approvals), (3, 2, 3));
+
+    // A single guardian can't veto the quorum's proposal
+    let cancel = cancel_guardian_rotation_ix(&old[3], &[&old[4]], old[0].pubkey());
+    let result = send(&mut ctx, &[cancel], &[&old[3], &old[4]]).await;
+    assert_vesting_error(result, VestingError::InsufficientGuardianApprovals);
+
+    let cancel = cancel_guardian_rotation_ix(&old[2], &[&old[3], &old[4]], old[0].pubkey());
+    let (result, logs) = send_with_logs(&mut ctx, &[cancel], &[&old[2], &old[3], &old[4]]).await;
+    result.unwrap();
+    let cancelled: Vec<GuardianRotationCancelled> = decode_events(&logs);
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!((cancelled[0].cancelled_by, cancelled[0].approvals), (old[2].pubkey(), 3));
+    assert!(ctx.banks_client.get_account(guardian_rotation_pda()).await.unwrap().is_none());
+
+    // The set is untouched and a fresh proposal can be made
+    warp_forward(&mut ctx, MIN_RECOVERY_TIMELOCK).await;
+    let config: VestingConfig = fetch(&mut ctx, config_pda()).await;
+    assert_eq!((config.guardians, config.guardian_threshold, config.guardian_epoch), (keys(&old), 3, 0));
+    let rotate = rotate_guardians_ix(&old[0], &[&old[1], &old[2]], keys(&new), 2);
+    send(&mut ctx, &[rotate], &[&old[0], &old[1], &old[2]]).await.unwrap();
+}
+
+#[tokio::test]
+async fn rotation_discards_recoveries_approved_by_the_old_set() {
+    let mut ctx = start().await;
+    let old = setup(&mut ctx).await;
+    let new = funded_guardians(&mut ctx, 3).await;
+
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new().pubkey();
+    let unlock = now(&mut ctx).await + YEAR;
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary, mint, 1, 9, unlock, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary, &mint, 1, 9);
+
+    let propose = propose_recovery_ix(&old[0], &[&old[1], &old[2]], vesting, Keypair::new().pubkey());
+    send(&mut ctx, &[propose], &[&old[0], &old[1], &old[2]]).await.unwrap();
+    let rotate = rotate_guardians_ix(&old[0], &[&old[1], &old[2]], keys(&new), 2);
+    send(&mut ctx, &[rotate], &[&old[0], &old[1], &old[2]]).await.unwrap();
+
+    warp_forward(&mut ctx, MIN_RECOVERY_TIMELOCK).await;
+    let (result, logs) = send_with_logs(&mut ctx, &[execute_guardian_rotation_ix(old[0].pubkey())], &[]).await;
+    result.unwrap();
+    let rotated: Vec<GuardiansRotated> = decode_events(&logs);
+    assert_eq!(rotated.len(), 1);
+    assert_eq!(rotated[0].guardians_hash, guardian_set_hash(&keys(&new)));
+    assert_eq!((rotated[0].guardian_count, rotated[0].guardian_threshold, rotated[0].guardian_epoch), (3, 2, 1));
+
+    // The recovery's timelock has elapsed too, but its approvers are gone
+    let (result, logs) = send_with_logs(&mut ctx, &[execute_recovery_ix(vesting, old[0].pubkey())], &[]).await;
+    result.unwrap();
+    let cancelled: Vec<BeneficiaryRecoveryCancelled> = decode_events(&logs);
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!(cancelled[0].beneficiary, Pubkey::default());
+    let state: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!(state.beneficiary, beneficiary);
+    assert!(ctx.banks_client.get_account(recovery_pda(&vesting)).await.unwrap().is_none());
+}
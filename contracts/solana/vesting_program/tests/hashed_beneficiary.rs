@@ -0,0 +1,134 @@
+//! Hashed-beneficiary mode: the schedule stores (and is seeded with)
+//! keccak(beneficiary || salt), and claims need the salt as well as the
+//! beneficiary's signature
+
+mod common;
+
+use anchor_spl::associated_token::get_associated_token_address;
+use common::*;
+use cvt_vesting::{beneficiary_hash, Vesting, VestingError, MIN_RECOVERY_TIMELOCK};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const SALT: [u8; 32] = [0x5a; 32];
+
+struct Schedule {
+    vesting: Pubkey,
+    mint: Pubkey,
+    beneficiary: Keypair,
+}
+
+async fn hashed_schedule(ctx: &mut ProgramTestContext) -> Schedule {
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    fund(ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let hash = beneficiary_hash(&beneficiary.pubkey(), &SALT);
+    let unlock = now(ctx).await + DAY;
+
+    let data = cvt_vesting::instruction::CreateVesting { hashed_beneficiary: true, ..base_create(1, 3, unlock, 1_000) };
+    let create = create_vesting_ix_with(ctx.payer.pubkey(), hash, mint, data);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&hash, &mint, 1, 3);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &escrow, 1_000).await;
+    warp_to(ctx, unlock).await;
+
+    Schedule { vesting, mint, beneficiary }
+}
+
+#[tokio::test]
+async fn beneficiary_claims_with_salt_and_signature() {
+    let mut ctx = start().await;
+    let s = hashed_schedule(&mut ctx).await;
+    let beneficiary = s.beneficiary.pubkey();
+
+    // Neither the account nor its address reveal the beneficiary's key
+    let state: Vesting = fetch(&mut ctx, s.vesting).await;
+    assert!(state.beneficiary_hashed);
+    assert_eq!(state.beneficiary, beneficiary_hash(&beneficiary, &SALT));
+    let data = ctx.banks_client.get_account(s.vesting).await.unwrap().unwrap().data;
+    assert!(!data.windows(32).any(|window| window == beneficiary.as_ref()));
+    assert_ne!(s.vesting, vesting_pda(&beneficiary, &s.mint, 1, 3));
+
+    let result = send(&mut ctx, &[withdraw_ix(s.vesting, s.mint, beneficiary, 400)], &[&s.beneficiary]).await;
+    assert_vesting_error(result, VestingError::BeneficiarySaltRequired);
+
+    send(&mut ctx, &[withdraw_hashed_ix(s.vesting, s.mint, beneficiary, 400, SALT)], &[&s.beneficiary])
+        .await
+        .unwrap();
+    let ata = get_associated_token_address(&beneficiary, &s.mint);
+    assert_eq!(token_balance(&mut ctx, &ata).await, 400);
+
+    // The salt alone is not enough: someone else presenting it is rejected
+    let thief = Keypair::new();
+    fund(&mut ctx, &thief.pubkey(), 1_000_000_000).await;
+    let result = send(&mut ctx, &[withdraw_hashed_ix(s.vesting, s.mint, thief.pubkey(), 1, SALT)], &[&thief]).await;
+    assert_vesting_error(result, VestingError::InvalidBeneficiarySalt);
+}
+
+#[tokio::test]
+async fn lost_salt_needs_guardian_recovery() {
+    let mut ctx = start().await;
+    let guardian = Keypair::new();
+    fund(&mut ctx, &guardian.pubkey(), 1_000_000_000).await;
+    let init = initialize_config_ix(ctx.payer.pubkey(), vec![guardian.pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(&mut ctx, &[init], &[]).await.unwrap();
+    let s = hashed_schedule(&mut ctx).await;
+
+    // The right key with the wrong salt can't claim
+    let lost = [0u8; 32];
+    let withdraw = withdraw_hashed_ix(s.vesting, s.mint, s.beneficiary.pubkey(), 1, lost);
+    let result = send(&mut ctx, &[withdraw], &[&s.beneficiary]).await;
+    assert_vesting_error(result, VestingError::InvalidBeneficiarySalt);
+
+    // Recovery moves the schedule to a plain key (re-linking it publicly);
+    // without the salt the old key can't object to it either
+    let new_beneficiary = Keypair::new();
+    fund(&mut ctx, &new_beneficiary.pubkey(), 1_000_000_000).await;
+    let propose = propose_recovery_ix(&guardian, &[], s.vesting, new_beneficiary.pubkey());
+    send(&mut ctx, &[propose], &[&guardian]).await.unwrap();
+    let object = object_recovery_hashed_ix(s.vesting, guardian.pubkey(), s.beneficiary.pubkey(), lost);
+    let result = send(&mut ctx, &[object], &[&s.beneficiary]).await;
+    assert_vesting_error(result, VestingError::InvalidBeneficiarySalt);
+    warp_forward(&mut ctx, MIN_RECOVERY_TIMELOCK).await;
+    send(&mut ctx, &[execute_recovery_ix(s.vesting, guardian.pubkey())], &[]).await.unwrap();
+
+    let state: Vesting = fetch(&mut ctx, s.vesting).await;
+    assert!(!state.beneficiary_hashed);
+    let withdraw = withdraw_hashed_ix(s.vesting, s.mint, new_beneficiary.pubkey(), 1, SALT);
+    let result = send(&mut ctx, &[withdraw], &[&new_beneficiary]).await;
+    assert_vesting_error(result, VestingError::NotHashedBeneficiary);
+    let withdraw = withdraw_ix(s.vesting, s.mint, new_beneficiary.pubkey(), 1_000);
+    send(&mut ctx, &[withdraw], &[&new_beneficiary]).await.unwrap();
+}
+
+#[tokio::test]
+async fn beneficiary_with_salt_can_object_to_recovery() {
+    let mut ctx = start().await;
+    let guardian = Keypair::new();
+    fund(&mut ctx, &guardian.pubkey(), 1_000_000_000).await;
+    let init = initialize_config_ix(ctx.payer.pubkey(), vec![guardian.pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(&mut ctx, &[init], &[]).await.unwrap();
+    let s = hashed_schedule(&mut ctx).await;
+    let propose = propose_recovery_ix(&guardian, &[], s.vesting, Keypair::new().pubkey());
+    send(&mut ctx, &[propose], &[&guardian]).await.unwrap();
+
+    // The salt alone is not enough here either
+    let thief = Keypair::new();
+    fund(&mut ctx, &thief.pubkey(), 1_000_000_000).await;
+    let object = object_recovery_hashed_ix(s.vesting, guardian.pubkey(), thief.pubkey(), SALT);
+    let result = send(&mut ctx, &[object], &[&thief]).await;
+    assert_vesting_error(result, VestingError::InvalidBeneficiarySalt);
+
+    let object = object_recovery_hashed_ix(s.vesting, guardian.pubkey(), s.beneficiary.pubkey(), SALT);
+    send(&mut ctx, &[object], &[&s.beneficiary]).await.unwrap();
+    assert!(ctx.banks_client.get_account(recovery_pda(&s.vesting)).await.unwrap().is_none());
+
+    warp_forward(&mut ctx, MIN_RECOVERY_TIMELOCK).await;
+    assert!(send(&mut ctx, &[execute_recovery_ix(s.vesting, guardian.pubkey())], &[]).await.is_err());
+    let state: Vesting = fetch(&mut ctx, s.vesting).await;
+    assert_eq!(state.beneficiary, beneficiary_hash(&s.beneficiary.pubkey(), &SALT));
+}
@@ -0,0 +1,97 @@
+//! Optional KYC gate on claims: attestations from the configured KYC program
+//! (a mock provider here) and per-schedule exemptions
+
+mod common;
+
+use common::*;
+use cvt_vesting::{KycExemptionUpdated, Vesting, VestingError, MIN_RECOVERY_TIMELOCK};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+/// Config with a KYC gate, plus one funded, unlocked schedule of 1_000 tokens
+async fn gated_schedule(ctx: &mut ProgramTestContext, compliance: &Keypair, nonce: u64) -> (Pubkey, Pubkey, Keypair) {
+    let authority = ctx.payer.pubkey();
+    let init = initialize_config_ix(authority, vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(ctx, &[init], &[]).await.unwrap();
+    send(ctx, &[set_kyc_gate_ix(authority, Some(mock_kyc::ID), compliance.pubkey())], &[]).await.unwrap();
+
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    fund(ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(ctx).await + DAY;
+    let create = create_vesting_ix(authority, beneficiary.pubkey(), mint, 1, nonce, unlock, 1_000);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, nonce);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &escrow, 1_000).await;
+    create_ata(ctx, &beneficiary.pubkey(), &mint).await;
+    warp_to(ctx, unlock).await;
+
+    (vesting, mint, beneficiary)
+}
+
+#[tokio::test]
+async fn claims_require_a_live_attestation_of_the_beneficiary() {
+    let mut ctx = start().await;
+    let compliance = Keypair::new();
+    let (vesting, mint, beneficiary) = gated_schedule(&mut ctx, &compliance, 31).await;
+    let withdraw = |amount, attestation| withdraw_kyc_ix(vesting, mint, beneficiary.pubkey(), amount, attestation);
+    let in_a_year = now(&mut ctx).await + YEAR;
+
+    let result = send(&mut ctx, &[withdraw(100, None)], &[&beneficiary]).await;
+    assert_vesting_error(result, VestingError::KycRequired);
+
+    let someone_else = kyc_attest(&mut ctx, Keypair::new().pubkey(), in_a_year, 1).await;
+    let result = send(&mut ctx, &[withdraw(100, Some(someone_else))], &[&beneficiary]).await;
+    assert_vesting_error(result, VestingError::KycAttestationInvalid);
+
+    // Right layout and subject, but not owned by the KYC program
+    let forged = Keypair::new().pubkey();
+    fund(&mut ctx, &forged, 1_000_000).await;
+    let result = send(&mut ctx, &[withdraw(100, Some(forged))], &[&beneficiary]).await;
+    assert_vesting_error(result, VestingError::KycAttestationInvalid);
+
+    let current = now(&mut ctx).await;
+    let expired = kyc_attest(&mut ctx, beneficiary.pubkey(), current, 1).await;
+    let result = send(&mut ctx, &[withdraw(100, Some(expired))], &[&beneficiary]).await;
+    assert_vesting_error(result, VestingError::KycExpired);
+
+    let attestation = kyc_attest(&mut ctx, beneficiary.pubkey(), in_a_year, 1).await;
+    send(&mut ctx, &[withdraw(100, Some(attestation))], &[&beneficiary]).await.unwrap();
+    let state: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!(state.withdrawn, 100);
+
+    // Turning the gate off restores plain withdrawals
+    let authority = ctx.payer.pubkey();
+    send(&mut ctx, &[set_kyc_gate_ix(authority, None, compliance.pubkey())], &[]).await.unwrap();
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 100)], &[&beneficiary]).await.unwrap();
+}
+
+#[tokio::test]
+async fn only_the_compliance_authority_grants_exemptions() {
+    let mut ctx = start().await;
+    let compliance = Keypair::new();
+    let (vesting, mint, beneficiary) = gated_schedule(&mut ctx, &compliance, 32).await;
+    let withdraw = || withdraw_ix(vesting, mint, beneficiary.pubkey(), 100);
+
+    // The config authority is not the compliance authority
+    let authority = ctx.payer.pubkey();
+    let result = send(&mut ctx, &[set_kyc_exempt_ix(authority, vesting, true)], &[]).await;
+    assert!(result.is_err());
+
+    let (result, logs) =
+        send_with_logs(&mut ctx, &[set_kyc_exempt_ix(compliance.pubkey(), vesting, true)], &[&compliance]).await;
+    result.unwrap();
+    let updates: Vec<KycExemptionUpdated> = decode_events(&logs);
+    assert_eq!((updates[0].vesting, updates[0].kyc_exempt), (vesting, true));
+
+    send(&mut ctx, &[withdraw()], &[&beneficiary]).await.unwrap();
+
+    send(&mut ctx, &[set_kyc_exempt_ix(compliance.pubkey(), vesting, false)], &[&compliance]).await.unwrap();
+    warp_forward(&mut ctx, 1).await;
+    let result = send(&mut ctx, &[withdraw()], &[&beneficiary]).await;
+    assert_vesting_error(result, VestingError::KycRequired);
+}
@@ -0,0 +1,254 @@
+//! Long-horizon scenario: a cliff-only grant that unlocks in full after one
+//! year, drawn down in 16 quarterly claims and then closed, with every
+//! balance checked against the test's own bookkeeping.
+//!
+//! The program has no vesting curve: nothing moves before the unlock
+//! timestamp and the whole grant is claimable from it on. The quarterly
+//! amounts are the beneficiary's own drawdown plan, not something the
+//! program enforces. Funding comes in three tranches from two depositors;
+//! along the way the authority amends the unlock once, compliance freezes
+//! and lifts a claim, one quarter is drawn by a session key, and the drained
+//! schedule is closed for its rent.
+
+mod common;
+
+use common::*;
+use cvt_vesting::{Vesting, VestingError, MIN_RECOVERY_TIMELOCK};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const CVT: u64 = 1_000_000_000; // 9 decimals
+const GRANT: u64 = 48_000 * CVT;
+const QUARTER: i64 = (4 * YEAR) / 16;
+const SCHEDULE_ID: u64 = 2024;
+const NONCE: u64 = 0x5eed_cafe;
+
+/// The beneficiary's drawdown plan and the balances it implies, kept
+/// independently of the program
+struct DrawdownPlan {
+    total: u64,
+    start: i64,
+    cliff: i64,
+    duration: i64,
+    deposited: u64,
+    withdrawn: u64,
+}
+
+impl DrawdownPlan {
+    /// Amount the beneficiary plans to have drawn by `now`: nothing before
+    /// the cliff (the program's unlock), then pro rata over `duration`
+    fn planned(&self, now: i64) -> u64 {
+        if now < self.start + self.cliff {
+            0
+        } else if now >= self.start + self.duration {
+            self.total
+        } else {
+            ((self.total as u128 * (now - self.start) as u128) / self.duration as u128) as u64
+        }
+    }
+
+    /// Next planned claim at `now`
+    fn due(&self, now: i64) -> u64 {
+        self.planned(now) - self.withdrawn
+    }
+
+    fn escrow(&self) -> u64 {
+        self.deposited - self.withdrawn
+    }
+}
+
+async fn assert_balances(
+    ctx: &mut ProgramTestContext,
+    plan: &DrawdownPlan,
+    vesting: &Pubkey,
+    escrow: &Pubkey,
+    beneficiary_ata: &Pubkey,
+) {
+    assert_eq!(token_balance(ctx, escrow).await, plan.escrow());
+    assert_eq!(token_balance(ctx, beneficiary_ata).await, plan.withdrawn);
+
+    let state: Vesting = fetch(ctx, *vesting).await;
+    assert_eq!(state.withdrawn, plan.withdrawn);
+    assert_eq!(state.total_amount, plan.total);
+}
+
+#[tokio::test]
+async fn four_year_grant_lifecycle() {
+    let mut ctx = start().await;
+
+    // ---------------------------------------------------------------- setup
+    // The payer is both the config authority (compliance freezes) and the
+    // schedule authority (amendments)
+    let authority = ctx.payer.pubkey();
+    let init = initialize_config_ix(authority, vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(&mut ctx, &[init], &[]).await.unwrap();
+
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    let start_ts = now(&mut ctx).await;
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, SCHEDULE_ID, NONCE);
+
+    let mut plan = DrawdownPlan {
+        total: GRANT,
+        start: start_ts,
+        cliff: YEAR,
+        duration: 4 * YEAR,
+        deposited: 0,
+        withdrawn: 0,
+    };
+
+    // Extendable by up to a year so the amendment below has room
+    let create = create_vesting_ix_with(
+        authority,
+        beneficiary.pubkey(),
+        mint,
+        cvt_vesting::instruction::CreateVesting {
+            max_unlock_timestamp: start_ts + 2 * YEAR,
+            ..base_create(SCHEDULE_ID, NONCE, start_ts + plan.cliff, GRANT)
+        },
+    );
+    send(&mut ctx, &[create], &[]).await.unwrap();
+
+    let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+    let beneficiary_ata = create_ata(&mut ctx, &beneficiary.pubkey(), &mint).await;
+
+    // ------------------------------------------- funding: 3 tranches, 2 depositors
+    let treasury = Keypair::new();
+    let partner = Keypair::new();
+    let treasury_ata = create_ata(&mut ctx, &treasury.pubkey(), &mint).await;
+    let partner_ata = create_ata(&mut ctx, &partner.pubkey(), &mint).await;
+    mint_to(&mut ctx, &mint, &treasury_ata, 36_000 * CVT).await;
+    mint_to(&mut ctx, &mint, &partner_ata, 12_000 * CVT).await;
+
+    for (from, owner, amount) in [
+        (treasury_ata, &treasury, 24_000 * CVT),
+        (partner_ata, &partner, 12_000 * CVT),
+        (treasury_ata, &treasury, 12_000 * CVT),
+    ] {
+        transfer_tokens(&mut ctx, &from, &escrow, owner, amount).await;
+        plan.deposited += amount;
+        assert_eq!(token_balance(&mut ctx, &escrow).await, plan.deposited);
+    }
+    assert_eq!(plan.deposited, GRANT);
+    assert_eq!(token_balance(&mut ctx, &treasury_ata).await, 0);
+    assert_eq!(token_balance(&mut ctx, &partner_ata).await, 0);
+
+    // ------------------------------------------------------- 16 quarterly claims
+    let session_key = Keypair::new();
+
+    for quarter in 1..=16 {
+        let ts = start_ts + quarter * QUARTER;
+        warp_to(&mut ctx, ts).await;
+
+        let due = plan.due(ts);
+        if due == 0 {
+            // Before the cliff nothing can move
+            let result = send(
+                &mut ctx,
+                &[withdraw_ix(vesting, mint, beneficiary.pubkey(), CVT)],
+                &[&beneficiary],
+            )
+            .await;
+            assert_vesting_error(result, VestingError::StillLocked);
+        } else if quarter == 8 {
+            // Compliance freezes the schedule: the claim is refused and
+            // nothing moves until the freeze is lifted
+            send(&mut ctx, &[set_pause_ix(authority, vesting, true, false)], &[]).await.unwrap();
+            let result = send(
+                &mut ctx,
+                &[withdraw_ix(vesting, mint, beneficiary.pubkey(), due)],
+                &[&beneficiary],
+            )
+            .await;
+            assert_vesting_error(result, VestingError::Paused);
+            assert_balances(&mut ctx, &plan, &vesting, &escrow, &beneficiary_ata).await;
+
+            send(&mut ctx, &[set_pause_ix(authority, vesting, false, false)], &[]).await.unwrap();
+            send(
+                &mut ctx,
+                &[withdraw_ix(vesting, mint, beneficiary.pubkey(), due)],
+                &[&beneficiary],
+            )
+            .await
+            .unwrap();
+            plan.withdrawn += due;
+        } else if quarter == 12 {
+            // The beneficiary delegates exactly this quarter's claim to a
+            // session key, which draws it into the beneficiary's account
+            send(
+                &mut ctx,
+                &[set_auto_claim_ix(vesting, beneficiary.pubkey(), session_key.pubkey(), due)],
+                &[&beneficiary],
+            )
+            .await
+            .unwrap();
+            send(
+                &mut ctx,
+                &[auto_claim_ix(vesting, mint, beneficiary.pubkey(), session_key.pubkey(), due)],
+                &[&session_key],
+            )
+            .await
+            .unwrap();
+            plan.withdrawn += due;
+
+            // The session key's allowance is spent
+            let result = send(
+                &mut ctx,
+                &[auto_claim_ix(vesting, mint, beneficiary.pubkey(), session_key.pubkey(), 1)],
+                &[&session_key],
+            )
+            .await;
+            assert_vesting_error(result, VestingError::AutoClaimCapExceeded);
+        } else {
+            send(
+                &mut ctx,
+                &[withdraw_ix(vesting, mint, beneficiary.pubkey(), due)],
+                &[&beneficiary],
+            )
+            .await
+            .unwrap();
+            plan.withdrawn += due;
+        }
+
+        assert_balances(&mut ctx, &plan, &vesting, &escrow, &beneficiary_ata).await;
+
+        // Before the cliff the authority amends the grant, pushing the
+        // unlock back one quarter; the plan's cliff follows it
+        if quarter == 2 {
+            let amended = start_ts + YEAR + QUARTER;
+            send(&mut ctx, &[extend_unlock_ix(authority, vesting, amended)], &[])
+                .await
+                .unwrap();
+            plan.cliff = amended - plan.start;
+
+            let state: Vesting = fetch(&mut ctx, vesting).await;
+            assert_eq!(state.unlock_timestamp, amended);
+        }
+    }
+
+    // ------------------------------------------------------------------ end state
+    assert_eq!(plan.withdrawn, GRANT);
+    assert_eq!(token_balance(&mut ctx, &escrow).await, 0);
+    assert_eq!(token_balance(&mut ctx, &beneficiary_ata).await, GRANT);
+
+    // Nothing left to claim
+    let result = send(
+        &mut ctx,
+        &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 1)],
+        &[&beneficiary],
+    )
+    .await;
+    assert_vesting_error(result, VestingError::InsufficientBalance);
+
+    // ---------------------------------------------------------------- close
+    // The beneficiary closes the drained schedule and takes its rent
+    let before = lamports(&mut ctx, beneficiary.pubkey()).await;
+    let close = close_vesting_ix(vesting, mint, beneficiary.pubkey(), authority);
+    send(&mut ctx, &[close], &[&beneficiary]).await.unwrap();
+    assert!(ctx.banks_client.get_account(vesting).await.unwrap().is_none());
+    assert!(ctx.banks_client.get_account(escrow).await.unwrap().is_none());
+    assert!(lamports(&mut ctx, beneficiary.pubkey()).await > before);
+}
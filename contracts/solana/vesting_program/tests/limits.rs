@@ -0,0 +1,142 @@
+//! Batch limits: the transaction-size ceilings in `limits` match real
+//! serialized transactions exactly at and one past each ceiling, and the
+//! caps enforced on-chain stay under them
+
+mod common;
+
+use common::*;
+use cvt_vesting::limits::{
+    self, TxShape, ACTIVATE_SCHEDULE, AGGREGATE_BENEFICIARY, AGGREGATE_SCHEDULE, ALLOWLIST_PROOF_NODE, CLAIMABLE_BATCH,
+    CREATE_VESTING, PROPOSE_RECOVERY, RECOVERY_CO_SIGNER,
+};
+use cvt_vesting::{
+    BeneficiarySummary, UsdTerms, VestingError, MAX_AGGREGATE_SCHEDULES, MAX_ALLOWLIST_PROOF_LEN,
+    MAX_CLAIMABLE_BATCH, MAX_PROJECTION_POINTS, MAX_RECOVERY_CO_SIGNERS,
+};
+use solana_sdk::{
+    instruction::Instruction,
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+/// Serialized size of an unsigned single-instruction transaction
+fn wire_size(ix: Instruction, payer: &Pubkey) -> usize {
+    let tx = Transaction::new_with_payer(&[ix], Some(payer));
+    assert!(tx.signatures.len() < 0x80);
+    1 + 64 * tx.signatures.len() + tx.message_data().len()
+}
+
+/// The model agrees with the real transaction at the ceiling, which fits,
+/// and one item past it, which doesn't
+fn assert_ceiling(ceiling: usize, base: TxShape, item: TxShape, size_with: impl Fn(usize) -> usize) {
+    for n in [ceiling, ceiling + 1] {
+        assert_eq!(size_with(n), base.with(item, n).size(), "{} items", n);
+    }
+    assert!(size_with(ceiling) <= PACKET_DATA_SIZE);
+    assert!(size_with(ceiling + 1) > PACKET_DATA_SIZE);
+}
+
+#[test]
+fn ceilings_match_serialized_transactions() {
+    assert_eq!(limits::PACKET_DATA_SIZE, PACKET_DATA_SIZE);
+    let payer = Pubkey::new_unique();
+
+    assert_ceiling(limits::AGGREGATE_SCHEDULES_CEILING, AGGREGATE_BENEFICIARY, AGGREGATE_SCHEDULE, |n| {
+        let schedules: Vec<Pubkey> = (0..n).map(|_| Pubkey::new_unique()).collect();
+        wire_size(aggregate_beneficiary_ix(Pubkey::new_unique(), &schedules), &payer)
+    });
+
+    assert_ceiling(limits::CLAIMABLE_BATCH_CEILING, CLAIMABLE_BATCH, AGGREGATE_SCHEDULE, |n| {
+        let schedules: Vec<Pubkey> = (0..n).map(|_| Pubkey::new_unique()).collect();
+        wire_size(claimable_batch_ix(&schedules), &payer)
+    });
+
+    // Return data, not the packet, bounds projections
+    let points = |n| wire_size(project_claimable_ix(Pubkey::new_unique(), vec![0; n]), &payer);
+    assert_eq!(limits::PROJECTION_POINTS_CEILING, (limits::MAX_RETURN_DATA - 4) / 8);
+    assert!(points(limits::PROJECTION_POINTS_CEILING) <= PACKET_DATA_SIZE);
+
+    assert_ceiling(limits::RECOVERY_CO_SIGNERS_CEILING, PROPOSE_RECOVERY, RECOVERY_CO_SIGNER, |n| {
+        let proposer = Keypair::new();
+        let co_signers: Vec<Keypair> = (0..n).map(|_| Keypair::new()).collect();
+        let co_signers: Vec<&Keypair> = co_signers.iter().collect();
+        let ix = propose_recovery_ix(&proposer, &co_signers, Pubkey::new_unique(), Pubkey::new_unique());
+        wire_size(ix, &proposer.pubkey())
+    });
+
+    assert_ceiling(limits::ALLOWLIST_PROOF_CEILING, CREATE_VESTING, ALLOWLIST_PROOF_NODE, |n| {
+        let terms = UsdTerms { usd_per_tranche: 1, tranches: 1, price_oracle: Pubkey::new_unique() };
+        let data = cvt_vesting::instruction::CreateVesting {
+            expiry_sink: Pubkey::new_unique(),
+            allowlist_proof: vec![[0; 32]; n],
+            usd_terms: Some(terms),
+            ..base_create(1, 1, 0, 1)
+        };
+        let ix = create_vesting_ix_with(payer, Pubkey::new_unique(), Pubkey::new_unique(), data);
+        wire_size(ix, &payer)
+    });
+
+    assert_ceiling(limits::ACTIVATION_PROOF_CEILING, ACTIVATE_SCHEDULE, ALLOWLIST_PROOF_NODE, |n| {
+        let (beneficiary, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = activate_schedule_ix(payer, Pubkey::new_unique(), beneficiary, mint, 1, 1, vec![[0; 32]; n]);
+        wire_size(ix, &payer)
+    });
+}
+
+#[test]
+fn full_recovery_quorum_needs_the_proposer_to_pay() {
+    let proposer = Keypair::new();
+    let co_signers: Vec<Keypair> = (0..MAX_RECOVERY_CO_SIGNERS).map(|_| Keypair::new()).collect();
+    let co_signers: Vec<&Keypair> = co_signers.iter().collect();
+    let ix = propose_recovery_ix(&proposer, &co_signers, Pubkey::new_unique(), Pubkey::new_unique());
+
+    assert!(wire_size(ix.clone(), &proposer.pubkey()) <= PACKET_DATA_SIZE);
+    assert!(wire_size(ix, &Pubkey::new_unique()) > PACKET_DATA_SIZE);
+}
+
+#[tokio::test]
+async fn aggregate_cap_is_enforced_with_its_max_in_context() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Pubkey::new_unique();
+    let unlock = now(&mut ctx).await + DAY;
+
+    let mut schedules = vec![];
+    for schedule_id in 0..MAX_AGGREGATE_SCHEDULES as u64 {
+        let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary, mint, schedule_id, 8, unlock, 100);
+        send(&mut ctx, &[create], &[]).await.unwrap();
+        schedules.push(vesting_pda(&beneficiary, &mint, schedule_id, 8));
+    }
+
+    let summary: BeneficiarySummary = view(&mut ctx, aggregate_beneficiary_ix(beneficiary, &schedules)).await;
+    assert_eq!(summary.schedules as usize, MAX_AGGREGATE_SCHEDULES);
+
+    schedules.push(Pubkey::new_unique());
+    let (result, logs) = send_with_logs(&mut ctx, &[aggregate_beneficiary_ix(beneficiary, &schedules)], &[]).await;
+    assert_vesting_error(result, VestingError::BatchTooLarge);
+    let context = logs.iter().find(|log| log.contains("error_ctx:")).unwrap();
+    assert!(context.ends_with(&format!("schedules={} max={}", schedules.len(), MAX_AGGREGATE_SCHEDULES)));
+}
+
+#[tokio::test]
+async fn claimable_batch_cap_is_enforced_with_its_max_in_context() {
+    let mut ctx = start().await;
+    let schedules: Vec<Pubkey> = (0..=MAX_CLAIMABLE_BATCH).map(|_| Pubkey::new_unique()).collect();
+    let (result, logs) = send_with_logs(&mut ctx, &[claimable_batch_ix(&schedules)], &[]).await;
+    assert_vesting_error(result, VestingError::BatchTooLarge);
+    let context = logs.iter().find(|log| log.contains("error_ctx:")).unwrap();
+    assert!(context.ends_with(&format!("schedules={} max={}", schedules.len(), MAX_CLAIMABLE_BATCH)));
+}
+
+#[test]
+fn caps_stay_within_their_ceilings() {
+    // Also asserted at compile time; pinned here so a change shows up in review
+    assert_eq!((MAX_AGGREGATE_SCHEDULES, limits::AGGREGATE_SCHEDULES_CEILING), (16, 31));
+    assert_eq!((MAX_PROJECTION_POINTS, limits::PROJECTION_POINTS_CEILING), (24, 127));
+    assert_eq!((MAX_CLAIMABLE_BATCH, limits::CLAIMABLE_BATCH_CEILING), (16, 31));
+    assert_eq!((MAX_RECOVERY_CO_SIGNERS, limits::RECOVERY_CO_SIGNERS_CEILING), (9, 9));
+    assert_eq!((MAX_ALLOWLIST_PROOF_LEN, limits::ALLOWLIST_PROOF_CEILING), (19, 19));
+    assert_eq!((MAX_ALLOWLIST_PROOF_LEN, limits::ACTIVATION_PROOF_CEILING), (19, 22));
+}
@@ -0,0 +1,166 @@
+//! Lock analytics: how long claimed tokens actually stayed locked, in
+//! token-seconds from the balance-weighted deposit start, and the u128 math
+//! behind it
+
+mod common;
+
+use common::*;
+use cvt_vesting::{
+    convert_token_seconds, lock_token_seconds, weighted_lock_start, LockDuration, Vesting, VestingClosed,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+struct Schedule {
+    vesting: Pubkey,
+    mint: Pubkey,
+    beneficiary: Keypair,
+    created: i64,
+    unlock: i64,
+}
+
+/// A 1,000-unit schedule unlocking `lock` seconds after creation, its escrow
+/// funded with `funded` tokens at creation
+async fn schedule(ctx: &mut ProgramTestContext, nonce: u64, lock: i64, funded: u64) -> Schedule {
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    fund(ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let created = now(ctx).await;
+    let unlock = created + lock;
+    let payer = ctx.payer.pubkey();
+    send(ctx, &[create_vesting_ix(payer, beneficiary.pubkey(), mint, 1, nonce, unlock, 1_000)], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, nonce);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &escrow, funded).await;
+    Schedule { vesting, mint, beneficiary, created, unlock }
+}
+
+/// Deterministic splits for the property checks
+struct Lcg(u64);
+
+impl Lcg {
+    fn below(&mut self, bound: u64) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 33) % bound
+    }
+
+    /// `total` cut into `parts` nonzero pieces
+    fn split(&mut self, total: u64, parts: usize) -> Vec<u64> {
+        let mut pieces = vec![1; parts];
+        for _ in 0..total - parts as u64 {
+            pieces[self.below(parts as u64) as usize] += 1;
+        }
+        pieces
+    }
+}
+
+#[tokio::test]
+async fn lock_accrues_from_the_deposit_weighted_start() {
+    let mut ctx = start().await;
+    let s = schedule(&mut ctx, 120, 4 * DAY, 600).await;
+
+    // 600 locked since creation, 400 more two days in: on average since 0.8 days in
+    warp_to(&mut ctx, s.created + 2 * DAY).await;
+    let depositor = Keypair::new();
+    fund(&mut ctx, &depositor.pubkey(), 1_000_000_000).await;
+    let depositor_ata = create_ata(&mut ctx, &depositor.pubkey(), &s.mint).await;
+    mint_to(&mut ctx, &s.mint, &depositor_ata, 400).await;
+    send(&mut ctx, &[deposit_ix(s.vesting, s.mint, depositor.pubkey(), 400)], &[&depositor]).await.unwrap();
+    let state: Vesting = fetch(&mut ctx, s.vesting).await;
+    assert_eq!(state.deposit_weighted_start, s.created + 4 * DAY / 5);
+
+    warp_to(&mut ctx, s.unlock).await;
+    let withdraw = withdraw_ix(s.vesting, s.mint, s.beneficiary.pubkey(), 1_000);
+    send(&mut ctx, &[withdraw], &[&s.beneficiary]).await.unwrap();
+    let lock: LockDuration = view(&mut ctx, get_lock_duration_ix(s.vesting)).await;
+    let average = 4 * DAY - 4 * DAY / 5;
+    assert_eq!(
+        lock,
+        LockDuration {
+            lock_seconds_accumulated: 1_000 * average as u128,
+            withdrawn: 1_000,
+            average_lock_seconds: average as u64,
+        }
+    );
+
+    // Reported one last time at close
+    let close = close_vesting_ix(s.vesting, s.mint, s.beneficiary.pubkey(), ctx.payer.pubkey());
+    let (result, logs) = send_with_logs(&mut ctx, &[close], &[&s.beneficiary]).await;
+    result.unwrap();
+    let closed = decode_events::<VestingClosed>(&logs).remove(0);
+    assert_eq!((closed.withdrawn, closed.lock_seconds_accumulated), (1_000, 1_000 * average as u128));
+}
+
+#[tokio::test]
+async fn many_small_claims_realize_the_same_lock_as_one() {
+    let mut ctx = start().await;
+    let whole = schedule(&mut ctx, 121, YEAR, 1_000).await;
+    let pieces = schedule(&mut ctx, 122, YEAR, 1_000).await;
+    let lock: LockDuration = view(&mut ctx, get_lock_duration_ix(whole.vesting)).await;
+    assert_eq!(lock, LockDuration::default());
+
+    // Both held to the end, one claimed in a single withdrawal and the other
+    // in a random split within the same second
+    warp_to(&mut ctx, pieces.unlock).await;
+    let at = now(&mut ctx).await;
+    let single = withdraw_ix(whole.vesting, whole.mint, whole.beneficiary.pubkey(), 1_000);
+    send(&mut ctx, &[single], &[&whole.beneficiary]).await.unwrap();
+    let claims: Vec<_> = Lcg(488)
+        .split(1_000, 8)
+        .into_iter()
+        .map(|amount| withdraw_ix(pieces.vesting, pieces.mint, pieces.beneficiary.pubkey(), amount))
+        .collect();
+    send(&mut ctx, &claims, &[&pieces.beneficiary]).await.unwrap();
+
+    for s in [&whole, &pieces] {
+        let state: Vesting = fetch(&mut ctx, s.vesting).await;
+        assert_eq!(state.deposit_weighted_start, s.created);
+        assert_eq!(state.lock_seconds_accumulated, lock_token_seconds(1_000, s.created, at));
+    }
+}
+
+#[test]
+fn token_seconds_add_up_over_any_split() {
+    let mut rng = Lcg(7);
+    for _ in 0..1_000 {
+        let total = 1 + rng.below(1_000_000);
+        let parts = 1 + rng.below(total.min(16)) as usize;
+        let since = 1_700_000_000 + rng.below(YEAR as u64) as i64;
+        let until = since + rng.below(4 * YEAR as u64) as i64;
+        let summed: u128 = rng.split(total, parts).iter().map(|&part| lock_token_seconds(part, since, until)).sum();
+        assert_eq!(summed, lock_token_seconds(total, since, until));
+    }
+
+    // Largest amount over a century still fits, and time never runs backwards
+    assert_eq!(lock_token_seconds(u64::MAX, 0, 100 * YEAR), u64::MAX as u128 * (100 * YEAR) as u128);
+    assert_eq!(lock_token_seconds(1_000, 10, 5), 0);
+}
+
+#[test]
+fn weighted_start_stays_between_its_inputs() {
+    let mut rng = Lcg(11);
+    for _ in 0..1_000 {
+        let start = 1_700_000_000 + rng.below(YEAR as u64) as i64;
+        let now = start + rng.below(YEAR as u64) as i64;
+        let (balance, deposit) = (1 + rng.below(u64::MAX - 1), rng.below(u64::MAX));
+        let weighted = weighted_lock_start(start, balance, deposit, now);
+        assert!(start <= weighted && weighted <= now);
+    }
+    assert_eq!(weighted_lock_start(0, 600, 400, 2 * DAY), 4 * DAY / 5);
+    assert_eq!(weighted_lock_start(0, 1_000, 0, DAY), 0);
+    // An empty escrow starts over
+    assert_eq!(weighted_lock_start(0, 0, 400, DAY), DAY);
+}
+
+#[test]
+fn token_seconds_convert_like_amounts_without_overflow() {
+    assert_eq!(convert_token_seconds(1_000 * DAY as u128, 3, 2), 1_500 * DAY as u128);
+    assert_eq!(convert_token_seconds(7, 1, 2), 3);
+    // Exact where the plain product would overflow u128
+    let huge = u64::MAX as u128 * (100 * YEAR) as u128;
+    assert_eq!(convert_token_seconds(huge, u64::MAX, u64::MAX), huge);
+    assert_eq!(convert_token_seconds(u128::MAX, 2, 1), u128::MAX);
+}
@@ -0,0 +1,41 @@
+//! Grants at the top of the u64 range. Schedules unlock all at once at the
+//! cliff, so claimable is `total - withdrawn` with no `total * elapsed`
+//! product; this pins that a maximal grant still claims exactly.
+
+mod common;
+
+use common::*;
+use cvt_vesting::Vesting;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn maximal_grant_claims_in_halves() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(&mut ctx).await + DAY;
+
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 64, unlock, u64::MAX);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 64);
+    let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+    mint_to(&mut ctx, &mint, &escrow, u64::MAX).await;
+    create_ata(&mut ctx, &beneficiary.pubkey(), &mint).await;
+    warp_to(&mut ctx, unlock).await;
+
+    let half = u64::MAX / 2;
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), half)], &[&beneficiary])
+        .await
+        .unwrap();
+    let state: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!(state.withdrawn, half);
+    assert!(view::<bool>(&mut ctx, is_claimable_ix(vesting)).await);
+
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), u64::MAX - half)], &[&beneficiary])
+        .await
+        .unwrap();
+    let state: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!(state.withdrawn, u64::MAX);
+    assert!(!view::<bool>(&mut ctx, is_claimable_ix(vesting)).await);
+}
@@ -0,0 +1,45 @@
+//! Governance minimum cliff: schedules must stay locked for at least
+//! `min_cliff_secs` after creation
+
+mod common;
+
+use common::*;
+use cvt_vesting::{Vesting, VestingError, MIN_RECOVERY_TIMELOCK};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn rejects_cliffs_shorter_than_the_minimum() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let init = initialize_config_ix(authority, vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(&mut ctx, &[init], &[]).await.unwrap();
+    send(&mut ctx, &[set_min_cliff_ix(authority, DAY)], &[]).await.unwrap();
+
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new().pubkey();
+    let current = now(&mut ctx).await;
+
+    let create = create_vesting_ix(authority, beneficiary, mint, 1, 41, current + DAY - 60, 1_000);
+    let result = send(&mut ctx, &[create], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidSchedule);
+
+    let create = create_vesting_ix(authority, beneficiary, mint, 1, 42, current + DAY + 60, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let state: Vesting = fetch(&mut ctx, vesting_pda(&beneficiary, &mint, 1, 42)).await;
+    assert_eq!(state.unlock_timestamp, current + DAY + 60);
+}
+
+#[tokio::test]
+async fn minimum_cliff_is_authority_only_and_non_negative() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let init = initialize_config_ix(authority, vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(&mut ctx, &[init], &[]).await.unwrap();
+
+    let result = send(&mut ctx, &[set_min_cliff_ix(authority, -1)], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidSchedule);
+
+    let stranger = Keypair::new();
+    let result = send(&mut ctx, &[set_min_cliff_ix(stranger.pubkey(), DAY)], &[&stranger]).await;
+    assert!(result.is_err());
+}
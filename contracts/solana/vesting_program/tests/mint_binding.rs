@@ -0,0 +1,70 @@
+//! Tokens only move between a schedule's escrow and accounts of the
+//! schedule's own mint: a deposit source or claim destination of another
+//! mint is refused before any transfer
+
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::*;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+fn mint_mismatch() -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(0, InstructionError::Custom(u32::from(ErrorCode::ConstraintTokenMint))))
+}
+
+/// An unlocked, funded 1,000-unit schedule; returns (vesting, mint, beneficiary)
+async fn funded_schedule(ctx: &mut ProgramTestContext) -> (Pubkey, Pubkey, Keypair) {
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    fund(ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(ctx).await + DAY;
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 61, unlock, 1_000);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 61);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &escrow, 1_000).await;
+    warp_to(ctx, unlock).await;
+    (vesting, mint, beneficiary)
+}
+
+#[tokio::test]
+async fn deposit_from_another_mint_is_refused() {
+    let mut ctx = start().await;
+    let (vesting, mint, _) = funded_schedule(&mut ctx).await;
+    let depositor = ctx.payer.pubkey();
+    let other_mint = create_mint(&mut ctx).await;
+    let source = create_ata(&mut ctx, &depositor, &other_mint).await;
+    mint_to(&mut ctx, &other_mint, &source, 500).await;
+
+    // Same schedule and mint, but the source account holds a different token
+    let mut ix = deposit_ix(vesting, mint, depositor, 500);
+    let expected_source = get_associated_token_address(&depositor, &mint);
+    ix.accounts.iter_mut().find(|meta| meta.pubkey == expected_source).unwrap().pubkey = source;
+    assert_eq!(send(&mut ctx, &[ix], &[]).await, mint_mismatch());
+
+    assert_eq!(token_balance(&mut ctx, &source).await, 500);
+    let escrow = get_associated_token_address(&vesting, &mint);
+    assert_eq!(token_balance(&mut ctx, &escrow).await, 1_000);
+}
+
+#[tokio::test]
+async fn claim_into_another_mint_is_refused() {
+    let mut ctx = start().await;
+    let (vesting, mint, beneficiary) = funded_schedule(&mut ctx).await;
+    let other_mint = create_mint(&mut ctx).await;
+    let destination = create_ata(&mut ctx, &beneficiary.pubkey(), &other_mint).await;
+
+    let ix = withdraw_to_ix(vesting, mint, beneficiary.pubkey(), destination, beneficiary.pubkey(), 400);
+    assert_eq!(send(&mut ctx, &[ix], &[&beneficiary]).await, mint_mismatch());
+    assert_eq!(token_balance(&mut ctx, &destination).await, 0);
+
+    // The schedule is untouched: the full amount is still claimable to its own mint
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 1_000)], &[&beneficiary]).await.unwrap();
+}
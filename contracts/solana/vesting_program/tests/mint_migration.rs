@@ -0,0 +1,197 @@
+//! Mint migration: the authority proposes (escrowing the converted balance
+//! in the new mint), the beneficiary accepts, and the schedule switches
+//! mints in one instruction
+
+mod common;
+
+use anchor_spl::associated_token::get_associated_token_address;
+use common::*;
+use cvt_vesting::{MintMigrated, ScheduleCount, UsdTerms, Vesting, VestingError};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+struct Schedule {
+    vesting: Pubkey,
+    mint: Pubkey,
+    new_mint: Pubkey,
+    beneficiary: Keypair,
+    unlock: i64,
+}
+
+/// A 1,001-unit schedule, fully funded and unlocked; the authority (test
+/// payer) holds 10,000 units of a fresh migration mint
+async fn schedule(ctx: &mut ProgramTestContext) -> Schedule {
+    let authority = ctx.payer.pubkey();
+    let mint = create_mint(ctx).await;
+    let new_mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    fund(ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(ctx).await + DAY;
+
+    let create = create_vesting_ix(authority, beneficiary.pubkey(), mint, 1, 91, unlock, 1_001);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 91);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &escrow, 1_001).await;
+    create_ata(ctx, &authority, &mint).await;
+    let authority_new_ata = create_ata(ctx, &authority, &new_mint).await;
+    mint_to(ctx, &new_mint, &authority_new_ata, 10_000).await;
+    warp_to(ctx, unlock).await;
+
+    Schedule { vesting, mint, new_mint, beneficiary, unlock }
+}
+
+#[tokio::test]
+async fn two_to_one_conversion_rounds_down_atomically() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let s = schedule(&mut ctx).await;
+    let beneficiary = s.beneficiary.pubkey();
+    send(&mut ctx, &[withdraw_ix(s.vesting, s.mint, beneficiary, 3)], &[&s.beneficiary]).await.unwrap();
+
+    // Two old tokens per new one: 998 escrowed converts to 499
+    let propose = migrate_mint_ix(authority, s.vesting, s.mint, s.new_mint, 1, 2);
+    send(&mut ctx, &[propose], &[]).await.unwrap();
+    let new_escrow = get_associated_token_address(&s.vesting, &s.new_mint);
+    assert_eq!(token_balance(&mut ctx, &new_escrow).await, 499);
+
+    // A claim before acceptance leaves 997 (498 converted): the extra unit is refunded
+    send(&mut ctx, &[withdraw_ix(s.vesting, s.mint, beneficiary, 1)], &[&s.beneficiary]).await.unwrap();
+    let accept = accept_mint_migration_ix(beneficiary, authority, s.vesting, s.mint, s.new_mint);
+    let (result, logs) = send_with_logs(&mut ctx, &[accept], &[&s.beneficiary]).await;
+    result.unwrap();
+
+    let state: Vesting = fetch(&mut ctx, s.vesting).await;
+    assert_eq!((state.mint, state.original_mint), (s.new_mint, s.mint));
+    // total 1,001 -> 500; remaining 997 -> 498, so withdrawn becomes 2
+    assert_eq!((state.total_amount, state.withdrawn), (500, 2));
+    let event = decode_events::<MintMigrated>(&logs).remove(0);
+    assert_eq!((event.total_amount, event.withdrawn, event.released), (500, 2, 997));
+
+    // The emptied old-mint escrow is closed
+    let escrow = get_associated_token_address(&s.vesting, &s.mint);
+    assert!(ctx.banks_client.get_account(escrow).await.unwrap().is_none());
+    assert_eq!(token_balance(&mut ctx, &get_associated_token_address(&authority, &s.mint)).await, 997);
+    assert_eq!(token_balance(&mut ctx, &new_escrow).await, 498);
+    assert_eq!(token_balance(&mut ctx, &get_associated_token_address(&authority, &s.new_mint)).await, 10_000 - 498);
+    assert!(ctx.banks_client.get_account(mint_migration_pda(&s.vesting)).await.unwrap().is_none());
+
+    // The schedule now pays out in the new mint, and only what was converted
+    let result = send(&mut ctx, &[withdraw_ix(s.vesting, s.new_mint, beneficiary, 499)], &[&s.beneficiary]).await;
+    assert_vesting_error(result, VestingError::InsufficientBalance);
+    send(&mut ctx, &[withdraw_ix(s.vesting, s.new_mint, beneficiary, 498)], &[&s.beneficiary]).await.unwrap();
+    let result = send(&mut ctx, &[withdraw_ix(s.vesting, s.mint, beneficiary, 1)], &[&s.beneficiary]).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn underfunded_migration_changes_nothing() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let s = schedule(&mut ctx).await;
+    let beneficiary = s.beneficiary.pubkey();
+
+    let propose = migrate_mint_ix(authority, s.vesting, s.mint, s.new_mint, 1, 2);
+    send(&mut ctx, &[propose], &[]).await.unwrap();
+
+    // Extra old-mint funding after the proposal outgrows the deposit
+    let escrow = get_associated_token_address(&s.vesting, &s.mint);
+    mint_to(&mut ctx, &s.mint, &escrow, 10).await;
+    let accept = accept_mint_migration_ix(beneficiary, authority, s.vesting, s.mint, s.new_mint);
+    let result = send(&mut ctx, &[accept], &[&s.beneficiary]).await;
+    assert_vesting_error(result, VestingError::MigrationUnderfunded);
+
+    let state: Vesting = fetch(&mut ctx, s.vesting).await;
+    assert_eq!((state.mint, state.total_amount, state.withdrawn), (s.mint, 1_001, 0));
+    assert_eq!(token_balance(&mut ctx, &escrow).await, 1_011);
+}
+
+#[tokio::test]
+async fn only_the_beneficiary_accepts_and_the_authority_can_cancel() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let s = schedule(&mut ctx).await;
+
+    let result = send(&mut ctx, &[migrate_mint_ix(authority, s.vesting, s.mint, s.new_mint, 0, 1)], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidConversion);
+    send(&mut ctx, &[migrate_mint_ix(authority, s.vesting, s.mint, s.new_mint, 1, 1)], &[]).await.unwrap();
+
+    let stranger = Keypair::new();
+    let accept = accept_mint_migration_ix(stranger.pubkey(), authority, s.vesting, s.mint, s.new_mint);
+    assert!(send(&mut ctx, &[accept], &[&stranger]).await.is_err());
+
+    send(&mut ctx, &[cancel_mint_migration_ix(authority, s.vesting, s.new_mint)], &[]).await.unwrap();
+    let authority_new_ata = get_associated_token_address(&authority, &s.new_mint);
+    assert_eq!(token_balance(&mut ctx, &authority_new_ata).await, 10_000);
+    assert!(ctx.banks_client.get_account(mint_migration_pda(&s.vesting)).await.unwrap().is_none());
+
+    // Still the old mint, claimable as before
+    let beneficiary = s.beneficiary.pubkey();
+    warp_to(&mut ctx, s.unlock + 1).await;
+    send(&mut ctx, &[withdraw_ix(s.vesting, s.mint, beneficiary, 1_001)], &[&s.beneficiary]).await.unwrap();
+}
+
+#[tokio::test]
+async fn migration_can_hand_over_the_schedule() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let s = schedule(&mut ctx).await;
+    let beneficiary = s.beneficiary.pubkey();
+    let new_authority = Pubkey::new_unique();
+    let escrow = get_associated_token_address(&s.vesting, &s.mint);
+    let escrow_rent = lamports(&mut ctx, escrow).await;
+
+    let propose = migrate_mint_to_ix(authority, s.vesting, s.mint, s.new_mint, 1, 1, new_authority, None);
+    send(&mut ctx, &[propose], &[]).await.unwrap();
+    let before = lamports(&mut ctx, authority).await;
+    let accept = accept_mint_migration_to_ix(beneficiary, authority, Some(new_authority), s.vesting, s.mint, s.new_mint);
+    send(&mut ctx, &[accept], &[&s.beneficiary]).await.unwrap();
+
+    let state: Vesting = fetch(&mut ctx, s.vesting).await;
+    assert_eq!(state.authority, new_authority);
+    assert!(ctx.banks_client.get_account(escrow).await.unwrap().is_none());
+    assert!(lamports(&mut ctx, authority).await >= before + escrow_rent);
+    let old_count: ScheduleCount = fetch(&mut ctx, schedule_count_pda(&authority)).await;
+    let new_count: ScheduleCount = fetch(&mut ctx, schedule_count_pda(&new_authority)).await;
+    assert_eq!((old_count.schedule_count, new_count.schedule_count), (0, 1));
+
+    // The new authority's count is the one closing the schedule releases
+    send(&mut ctx, &[withdraw_ix(s.vesting, s.new_mint, beneficiary, 1_001)], &[&s.beneficiary]).await.unwrap();
+    let close = close_vesting_ix(s.vesting, s.new_mint, beneficiary, new_authority);
+    send(&mut ctx, &[close], &[&s.beneficiary]).await.unwrap();
+    let new_count: ScheduleCount = fetch(&mut ctx, schedule_count_pda(&new_authority)).await;
+    assert_eq!(new_count.schedule_count, 0);
+}
+
+#[tokio::test]
+async fn usd_schedules_move_to_the_new_mints_oracle() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let (mint, new_mint) = (create_mint(&mut ctx).await, create_mint(&mut ctx).await);
+    let beneficiary = Keypair::new();
+    let unlock = now(&mut ctx).await + DAY;
+    let terms = UsdTerms { usd_per_tranche: 100, tranches: 3, price_oracle: Pubkey::new_unique() };
+    let data = cvt_vesting::instruction::CreateVesting { usd_terms: Some(terms), ..base_create(1, 92, unlock, 1_000) };
+    let create = create_vesting_ix_with(authority, beneficiary.pubkey(), mint, data);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 92);
+    create_ata(&mut ctx, &vesting, &mint).await;
+    create_ata(&mut ctx, &authority, &mint).await;
+    create_ata(&mut ctx, &authority, &new_mint).await;
+
+    // The old mint's oracle can't price the new one
+    let result = send(&mut ctx, &[migrate_mint_ix(authority, vesting, mint, new_mint, 1, 1)], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidConversion);
+
+    let oracle = Pubkey::new_unique();
+    let propose = migrate_mint_to_ix(authority, vesting, mint, new_mint, 1, 1, authority, Some(oracle));
+    send(&mut ctx, &[propose], &[]).await.unwrap();
+    let accept = accept_mint_migration_ix(beneficiary.pubkey(), authority, vesting, mint, new_mint);
+    send(&mut ctx, &[accept], &[&beneficiary]).await.unwrap();
+    let state: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!(state.usd_terms.unwrap().price_oracle, oracle);
+    assert_eq!(state.authority, authority);
+}
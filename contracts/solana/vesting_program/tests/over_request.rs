@@ -0,0 +1,61 @@
+//! An over-requested withdrawal fails with `InsufficientBalance` and reports
+//! the amount that was claimable as return data, so the client can retry for
+//! exactly that in one round trip
+
+mod common;
+
+use common::*;
+use cvt_vesting::VestingError;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+/// Simulate `ix` signed by `signer`; returns the error and the claimable
+/// amount it reported
+async fn over_request(ctx: &mut ProgramTestContext, ix: Instruction, signer: &Keypair) -> (TransactionError, u64) {
+    let blockhash = ctx.banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&ctx.payer.pubkey()), &[&ctx.payer, signer], blockhash);
+    let simulation = ctx.banks_client.simulate_transaction(tx).await.unwrap();
+    let error = simulation.result.unwrap().unwrap_err();
+    // The runtime trims trailing zero bytes, down to no return data for zero
+    let mut bytes = match simulation.simulation_details.and_then(|details| details.return_data) {
+        Some(return_data) => {
+            assert_eq!(return_data.program_id, cvt_vesting::ID);
+            return_data.data
+        }
+        None => Vec::new(),
+    };
+    bytes.resize(8, 0);
+    (error, u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+#[tokio::test]
+async fn over_request_reports_the_claimable_amount() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(&mut ctx).await + DAY;
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 81, unlock, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 81);
+    let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+    mint_to(&mut ctx, &mint, &escrow, 1_000).await;
+    warp_to(&mut ctx, unlock).await;
+
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 250)], &[&beneficiary]).await.unwrap();
+
+    let withdraw = |amount| withdraw_ix(vesting, mint, beneficiary.pubkey(), amount);
+    let (error, claimable) = over_request(&mut ctx, withdraw(5_000), &beneficiary).await;
+    let insufficient = u32::from(VestingError::InsufficientBalance);
+    assert_eq!(error, TransactionError::InstructionError(0, InstructionError::Custom(insufficient)));
+    assert_eq!(claimable, 750);
+
+    // Retrying with the reported amount drains the schedule
+    send(&mut ctx, &[withdraw(claimable)], &[&beneficiary]).await.unwrap();
+    let (_, claimable) = over_request(&mut ctx, withdraw(1), &beneficiary).await;
+    assert_eq!(claimable, 0);
+}
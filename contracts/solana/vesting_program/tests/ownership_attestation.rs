@@ -0,0 +1,100 @@
+//! Ownership attestations: a program-owned beneficiary (here a DAO treasury)
+//! claims into a token account held by another of its program's PDAs, which
+//! that program binds to the treasury at its own attestation PDA
+
+mod common;
+
+use anchor_lang::AnchorSerialize;
+use common::*;
+use cvt_vesting::{OwnershipAttestation, VestingError};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{account::AccountSharedData, instruction::Instruction, pubkey::Pubkey, signature::Signer};
+
+struct Treasury {
+    vesting: Pubkey,
+    mint: Pubkey,
+    treasury: Pubkey,
+    /// Token account owned by the DAO's grants PDA, not the treasury
+    grants_tokens: Pubkey,
+}
+
+/// An unlocked, funded 1,000-unit schedule for the DAO treasury, an account
+/// the DAO program owns
+async fn treasury_schedule(ctx: &mut ProgramTestContext) -> Treasury {
+    let (treasury, _) = mock_dao::treasury();
+    ctx.set_account(&treasury, &AccountSharedData::new(1_000_000_000, 8, &mock_dao::ID));
+    let mint = create_mint(ctx).await;
+    let unlock = now(ctx).await + DAY;
+    let create = create_vesting_ix(ctx.payer.pubkey(), treasury, mint, 1, 68, unlock, 1_000);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&treasury, &mint, 1, 68);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &escrow, 1_000).await;
+    // The treasury can't pay rent, so its ATA (always passed) is created up front
+    create_ata(ctx, &treasury, &mint).await;
+    let (grants, _) = Pubkey::find_program_address(&[b"grants"], &mock_dao::ID);
+    let grants_tokens = create_ata(ctx, &grants, &mint).await;
+    warp_to(ctx, unlock).await;
+
+    Treasury { vesting, mint, treasury, grants_tokens }
+}
+
+/// Write an attestation binding `token_account` to `beneficiary` at `program`'s PDA for it
+fn attest(ctx: &mut ProgramTestContext, program: &Pubkey, beneficiary: Pubkey, token_account: Pubkey) -> Pubkey {
+    let (address, _) = Pubkey::find_program_address(&[OwnershipAttestation::SEED, token_account.as_ref()], program);
+    let record = OwnershipAttestation { beneficiary, token_account };
+    let mut account = AccountSharedData::new(1_000_000_000, OwnershipAttestation::LEN, program);
+    account.set_data_from_slice(&record.try_to_vec().unwrap());
+    ctx.set_account(&address, &account);
+    address
+}
+
+/// The DAO claims for its treasury into the grants account, presenting `attestation`
+fn claim(t: &Treasury, attestation: Pubkey, amount: u64) -> Instruction {
+    mock_dao::execute_ix(withdraw_attested_ix(t.vesting, t.mint, t.treasury, t.grants_tokens, attestation, amount))
+}
+
+#[tokio::test]
+async fn dao_claims_into_an_attested_token_account() {
+    let mut ctx = start().await;
+    let t = treasury_schedule(&mut ctx).await;
+
+    // Without an attestation or an allowlisted owner, the grants account is refused
+    let (grants, _) = Pubkey::find_program_address(&[b"grants"], &mock_dao::ID);
+    let unattested = withdraw_to_ix(t.vesting, t.mint, t.treasury, t.grants_tokens, grants, 400);
+    let result = send(&mut ctx, &[mock_dao::execute_ix(unattested)], &[]).await;
+    assert_vesting_error(result, VestingError::ClaimDestinationNotAllowed);
+
+    let attestation = attest(&mut ctx, &mock_dao::ID, t.treasury, t.grants_tokens);
+    send(&mut ctx, &[claim(&t, attestation, 400)], &[]).await.unwrap();
+    assert_eq!(token_balance(&mut ctx, &t.grants_tokens).await, 400);
+}
+
+#[tokio::test]
+async fn attestations_from_other_programs_are_rejected() {
+    let mut ctx = start().await;
+    let t = treasury_schedule(&mut ctx).await;
+
+    // Same statement, but written by a program that doesn't own the treasury
+    let spoofed = attest(&mut ctx, &mock_kyc::ID, t.treasury, t.grants_tokens);
+    let result = send(&mut ctx, &[claim(&t, spoofed, 400)], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidOwnershipAttestation);
+}
+
+#[tokio::test]
+async fn attestation_must_bind_this_destination_to_this_beneficiary() {
+    let mut ctx = start().await;
+    let t = treasury_schedule(&mut ctx).await;
+
+    // The DAO's attestation for some other token account doesn't transfer
+    let other_tokens = create_ata(&mut ctx, &Pubkey::new_unique(), &t.mint).await;
+    let elsewhere = attest(&mut ctx, &mock_dao::ID, t.treasury, other_tokens);
+    let result = send(&mut ctx, &[claim(&t, elsewhere, 400)], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidOwnershipAttestation);
+
+    // Nor does one at the right address naming another beneficiary
+    let wrong_beneficiary = attest(&mut ctx, &mock_dao::ID, Pubkey::new_unique(), t.grants_tokens);
+    let result = send(&mut ctx, &[claim(&t, wrong_beneficiary, 400)], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidOwnershipAttestation);
+    assert_eq!(token_balance(&mut ctx, &t.grants_tokens).await, 0);
+}
@@ -0,0 +1,190 @@
+//! Full pause vs deposits-only pause, and full pauses lapsing on their own
+
+mod common;
+
+use common::*;
+use cvt_vesting::{
+    PauseExpired, PauseUpdated, Vesting, VestingError, DEFAULT_MAX_PAUSE_DURATION, MAX_PAUSE_DURATION,
+    MAX_REPAUSES_PER_WINDOW, MIN_RECOVERY_TIMELOCK, REPAUSE_WINDOW,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const HOUR: i64 = 60 * 60;
+
+struct Setup {
+    mint: Pubkey,
+    vesting: Pubkey,
+    beneficiary: Keypair,
+    depositor: Keypair,
+}
+
+/// Unlocked schedule holding 500 tokens, with a depositor holding 1_000 more
+async fn setup(ctx: &mut ProgramTestContext) -> Setup {
+    let init = initialize_config_ix(ctx.payer.pubkey(), vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(ctx, &[init], &[]).await.unwrap();
+
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    let depositor = Keypair::new();
+    fund(ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    fund(ctx, &depositor.pubkey(), 1_000_000_000).await;
+
+    let unlock = now(ctx).await + DAY;
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 3, unlock, 1_500);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 3);
+
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    create_ata(ctx, &beneficiary.pubkey(), &mint).await;
+    let depositor_ata = create_ata(ctx, &depositor.pubkey(), &mint).await;
+    mint_to(ctx, &mint, &escrow, 500).await;
+    mint_to(ctx, &mint, &depositor_ata, 1_000).await;
+    warp_to(ctx, unlock).await;
+
+    Setup { mint, vesting, beneficiary, depositor }
+}
+
+#[tokio::test]
+async fn deposits_pause_blocks_deposits_but_not_withdrawals() {
+    let mut ctx = start().await;
+    let s = setup(&mut ctx).await;
+
+    send(&mut ctx, &[deposit_ix(s.vesting, s.mint, s.depositor.pubkey(), 400)], &[&s.depositor])
+        .await
+        .unwrap();
+
+    let pause = set_pause_ix(ctx.payer.pubkey(), s.vesting, false, true);
+    send(&mut ctx, &[pause], &[]).await.unwrap();
+
+    let result = send(&mut ctx, &[deposit_ix(s.vesting, s.mint, s.depositor.pubkey(), 100)], &[&s.depositor]).await;
+    assert_vesting_error(result, VestingError::DepositsPaused);
+
+    // Beneficiary can still claim everything deposited so far
+    send(&mut ctx, &[withdraw_ix(s.vesting, s.mint, s.beneficiary.pubkey(), 900)], &[&s.beneficiary])
+        .await
+        .unwrap();
+    assert!(view::<bool>(&mut ctx, is_claimable_ix(s.vesting)).await);
+
+    // Resuming deposits
+    let resume = set_pause_ix(ctx.payer.pubkey(), s.vesting, false, false);
+    send(&mut ctx, &[resume], &[]).await.unwrap();
+    send(&mut ctx, &[deposit_ix(s.vesting, s.mint, s.depositor.pubkey(), 100)], &[&s.depositor])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn full_pause_blocks_deposits_and_withdrawals() {
+    let mut ctx = start().await;
+    let s = setup(&mut ctx).await;
+
+    let pause = set_pause_ix(ctx.payer.pubkey(), s.vesting, true, false);
+    send(&mut ctx, &[pause], &[]).await.unwrap();
+
+    let result = send(&mut ctx, &[deposit_ix(s.vesting, s.mint, s.depositor.pubkey(), 100)], &[&s.depositor]).await;
+    assert_vesting_error(result, VestingError::Paused);
+    let result = send(&mut ctx, &[withdraw_ix(s.vesting, s.mint, s.beneficiary.pubkey(), 100)], &[&s.beneficiary]).await;
+    assert_vesting_error(result, VestingError::Paused);
+    assert!(!view::<bool>(&mut ctx, is_claimable_ix(s.vesting)).await);
+}
+
+#[tokio::test]
+async fn only_config_authority_can_pause() {
+    let mut ctx = start().await;
+    let s = setup(&mut ctx).await;
+
+    let intruder = Keypair::new();
+    let pause = set_pause_ix(intruder.pubkey(), s.vesting, false, true);
+    assert!(send(&mut ctx, &[pause], &[&intruder]).await.is_err());
+}
+
+#[tokio::test]
+async fn full_pause_lapses_without_an_unpause() {
+    let mut ctx = start().await;
+    let s = setup(&mut ctx).await;
+
+    let pause = set_pause_ix(ctx.payer.pubkey(), s.vesting, true, false);
+    let (result, logs) = send_with_logs(&mut ctx, &[pause], &[]).await;
+    result.unwrap();
+    let paused_until = now(&mut ctx).await + DEFAULT_MAX_PAUSE_DURATION;
+    assert_eq!(decode_events::<PauseUpdated>(&logs)[0].paused_until, paused_until);
+
+    warp_to(&mut ctx, paused_until - 1).await;
+    let result = send(&mut ctx, &[withdraw_ix(s.vesting, s.mint, s.beneficiary.pubkey(), 100)], &[&s.beneficiary]).await;
+    assert_vesting_error(result, VestingError::Paused);
+
+    // Views see the lapse before anything lifts the flag
+    warp_to(&mut ctx, paused_until).await;
+    assert!(view::<bool>(&mut ctx, is_claimable_ix(s.vesting)).await);
+
+    // The first claim lifts it and announces the expiry
+    let withdraw = withdraw_ix(s.vesting, s.mint, s.beneficiary.pubkey(), 100);
+    let (result, logs) = send_with_logs(&mut ctx, &[withdraw], &[&s.beneficiary]).await;
+    result.unwrap();
+    let expired: Vec<PauseExpired> = decode_events(&logs);
+    assert_eq!(expired.len(), 1);
+    assert_eq!((expired[0].vesting, expired[0].paused_until), (s.vesting, paused_until));
+    assert!(!fetch::<Vesting>(&mut ctx, s.vesting).await.is_paused);
+
+    // Deposits resume too
+    send(&mut ctx, &[deposit_ix(s.vesting, s.mint, s.depositor.pubkey(), 100)], &[&s.depositor])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn repauses_are_limited_per_window() {
+    let mut ctx = start().await;
+    let s = setup(&mut ctx).await;
+    let authority = ctx.payer.pubkey();
+
+    let (result, logs) = send_with_logs(&mut ctx, &[set_pause_ix(authority, s.vesting, true, false)], &[]).await;
+    result.unwrap();
+    assert!(!decode_events::<PauseUpdated>(&logs)[0].repause);
+
+    // Extending a pause, or pausing again soon after it lapses, is a re-pause
+    for _ in 0..MAX_REPAUSES_PER_WINDOW {
+        warp_forward(&mut ctx, DEFAULT_MAX_PAUSE_DURATION).await;
+        let (result, logs) = send_with_logs(&mut ctx, &[set_pause_ix(authority, s.vesting, true, false)], &[]).await;
+        result.unwrap();
+        assert!(decode_events::<PauseUpdated>(&logs)[0].repause);
+    }
+    warp_forward(&mut ctx, DEFAULT_MAX_PAUSE_DURATION).await;
+    let result = send(&mut ctx, &[set_pause_ix(authority, s.vesting, true, false)], &[]).await;
+    assert_vesting_error(result, VestingError::RepauseLimitReached);
+
+    // Meanwhile the last pause lapsed and claims resumed
+    send(&mut ctx, &[withdraw_ix(s.vesting, s.mint, s.beneficiary.pubkey(), 100)], &[&s.beneficiary])
+        .await
+        .unwrap();
+
+    // Once the earliest re-pause leaves the rolling window, one more is allowed
+    warp_forward(&mut ctx, REPAUSE_WINDOW - MAX_REPAUSES_PER_WINDOW as i64 * DEFAULT_MAX_PAUSE_DURATION).await;
+    send(&mut ctx, &[set_pause_ix(authority, s.vesting, true, false)], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn pause_duration_is_configurable_within_bounds() {
+    let mut ctx = start().await;
+    let s = setup(&mut ctx).await;
+    let authority = ctx.payer.pubkey();
+
+    for invalid in [0, MAX_PAUSE_DURATION + 1] {
+        let result = send(&mut ctx, &[set_max_pause_duration_ix(authority, invalid)], &[]).await;
+        assert_vesting_error(result, VestingError::InvalidPauseDuration);
+    }
+
+    send(&mut ctx, &[set_max_pause_duration_ix(authority, HOUR)], &[]).await.unwrap();
+    send(&mut ctx, &[set_pause_ix(authority, s.vesting, true, false)], &[]).await.unwrap();
+    let state: Vesting = fetch(&mut ctx, s.vesting).await;
+    assert_eq!(state.paused_until, now(&mut ctx).await + HOUR);
+
+    warp_forward(&mut ctx, HOUR).await;
+    send(&mut ctx, &[withdraw_ix(s.vesting, s.mint, s.beneficiary.pubkey(), 100)], &[&s.beneficiary])
+        .await
+        .unwrap();
+}
@@ -0,0 +1,41 @@
+//! Every context re-derives the vesting PDA from the account's own fields and
+//! stored bump, so a program-owned copy parked at another address is refused
+
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use common::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+#[tokio::test]
+async fn substituted_vesting_account_is_rejected() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    let unlock = now(&mut ctx).await + DAY;
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 39, unlock, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 39);
+    create_ata(&mut ctx, &vesting, &mint).await;
+
+    // Byte-for-byte copy of the real schedule, still owned by the program
+    let forged = Pubkey::new_unique();
+    let account = ctx.banks_client.get_account(vesting).await.unwrap().unwrap();
+    ctx.set_account(&forged, &account.into());
+    create_ata(&mut ctx, &forged, &mint).await;
+
+    let depositor = ctx.payer.pubkey();
+    let source = create_ata(&mut ctx, &depositor, &mint).await;
+    mint_to(&mut ctx, &mint, &source, 2_000).await;
+
+    let result = send(&mut ctx, &[deposit_ix(forged, mint, depositor, 1_000)], &[]).await;
+    let seeds = u32::from(ErrorCode::ConstraintSeeds);
+    assert_eq!(result, Err(TransactionError::InstructionError(0, InstructionError::Custom(seeds))));
+
+    send(&mut ctx, &[deposit_ix(vesting, mint, depositor, 1_000)], &[]).await.unwrap();
+}
@@ -0,0 +1,186 @@
+//! Pending schedules: registered with staged tokens ahead of a start date,
+//! activated into a live schedule by anyone once it arrives, or cancelled by
+//! the authority with a full refund before that
+
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::*;
+use cvt_vesting::{ScheduleCount, Vesting, VestingCreated, VestingError};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+struct Pending {
+    authority: Pubkey,
+    mint: Pubkey,
+    beneficiary: Pubkey,
+    authority_tokens: Pubkey,
+    activation: i64,
+    unlock: i64,
+}
+
+/// The authority (payer) registers a 1,000-unit schedule starting in a day
+/// and unlocking a day after that, staging all 1,000 of its tokens
+async fn registered(ctx: &mut ProgramTestContext) -> Pending {
+    let mint = create_mint(ctx).await;
+    let authority = ctx.payer.pubkey();
+    let authority_tokens = create_ata(ctx, &authority, &mint).await;
+    mint_to(ctx, &mint, &authority_tokens, 1_000).await;
+    let beneficiary = Pubkey::new_unique();
+    let activation = now(ctx).await + DAY;
+    let unlock = activation + DAY;
+
+    let register = register_pending_schedule_ix(authority, beneficiary, mint, schedule_terms(1, 97, unlock, 1_000), activation);
+    send(ctx, &[register], &[]).await.unwrap();
+    let staging = get_associated_token_address(&pending_schedule_pda(&beneficiary, &mint, 1, 97), &mint);
+    assert_eq!(token_balance(ctx, &staging).await, 1_000);
+    assert_eq!(token_balance(ctx, &authority_tokens).await, 0);
+
+    Pending { authority, mint, beneficiary, authority_tokens, activation, unlock }
+}
+
+fn activate_ix(pending: &Pending, payer: Pubkey) -> Instruction {
+    activate_schedule_ix(payer, pending.authority, pending.beneficiary, pending.mint, 1, 97, vec![])
+}
+
+#[tokio::test]
+async fn cancel_before_activation_refunds_the_stake() {
+    let mut ctx = start().await;
+    let pending = registered(&mut ctx).await;
+    let count: ScheduleCount = fetch(&mut ctx, schedule_count_pda(&pending.authority)).await;
+    assert_eq!(count.schedule_count, 1);
+
+    let cancel = cancel_pending_schedule_ix(pending.authority, pending.beneficiary, pending.mint, 1, 97);
+    send(&mut ctx, &[cancel], &[]).await.unwrap();
+    assert_eq!(token_balance(&mut ctx, &pending.authority_tokens).await, 1_000);
+    let count: ScheduleCount = fetch(&mut ctx, schedule_count_pda(&pending.authority)).await;
+    assert_eq!(count.schedule_count, 0);
+    let pda = pending_schedule_pda(&pending.beneficiary, &pending.mint, 1, 97);
+    assert!(ctx.banks_client.get_account(pda).await.unwrap().is_none());
+
+    // Nothing left to activate
+    warp_to(&mut ctx, pending.activation).await;
+    let cranker = Keypair::new();
+    fund(&mut ctx, &cranker.pubkey(), 1_000_000_000).await;
+    let result = send(&mut ctx, &[activate_ix(&pending, cranker.pubkey())], &[&cranker]).await;
+    assert_eq!(
+        result.unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::Custom(ErrorCode::AccountNotInitialized.into()))
+    );
+}
+
+#[tokio::test]
+async fn activates_on_time_into_a_live_schedule() {
+    let mut ctx = start().await;
+    let pending = registered(&mut ctx).await;
+    let cranker = Keypair::new();
+    fund(&mut ctx, &cranker.pubkey(), 1_000_000_000).await;
+
+    warp_to(&mut ctx, pending.activation - 1).await;
+    let result = send(&mut ctx, &[activate_ix(&pending, cranker.pubkey())], &[&cranker]).await;
+    assert_vesting_error(result, VestingError::ActivationPending);
+
+    warp_to(&mut ctx, pending.activation).await;
+    let (result, logs) = send_with_logs(&mut ctx, &[activate_ix(&pending, cranker.pubkey())], &[&cranker]).await;
+    result.unwrap();
+    let vesting = vesting_pda(&pending.beneficiary, &pending.mint, 1, 97);
+    let created: Vec<VestingCreated> = decode_events(&logs);
+    assert_eq!((created[0].vesting, created[0].amount), (vesting, 1_000));
+
+    let schedule: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!((schedule.beneficiary, schedule.authority), (pending.beneficiary, pending.authority));
+    assert_eq!((schedule.total_amount, schedule.unlock_timestamp), (1_000, pending.unlock));
+    assert_eq!(token_balance(&mut ctx, &get_associated_token_address(&vesting, &pending.mint)).await, 1_000);
+    let pda = pending_schedule_pda(&pending.beneficiary, &pending.mint, 1, 97);
+    assert!(ctx.banks_client.get_account(pda).await.unwrap().is_none());
+    let staging = get_associated_token_address(&pda, &pending.mint);
+    assert!(ctx.banks_client.get_account(staging).await.unwrap().is_none());
+
+    // Still counted once: registration took the cap slot
+    let count: ScheduleCount = fetch(&mut ctx, schedule_count_pda(&pending.authority)).await;
+    assert_eq!(count.schedule_count, 1);
+}
+
+#[tokio::test]
+async fn activation_happens_once() {
+    let mut ctx = start().await;
+    let pending = registered(&mut ctx).await;
+    let cranker = Keypair::new();
+    fund(&mut ctx, &cranker.pubkey(), 1_000_000_000).await;
+    warp_to(&mut ctx, pending.activation).await;
+    send(&mut ctx, &[activate_ix(&pending, cranker.pubkey())], &[&cranker]).await.unwrap();
+    let vesting = vesting_pda(&pending.beneficiary, &pending.mint, 1, 97);
+    let before: Vesting = fetch(&mut ctx, vesting).await;
+
+    let result = send(&mut ctx, &[activate_ix(&pending, pending.authority)], &[]).await;
+    assert_eq!(
+        result.unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::Custom(ErrorCode::AccountNotInitialized.into()))
+    );
+    // And the authority can no longer pull the tokens back
+    let cancel = cancel_pending_schedule_ix(pending.authority, pending.beneficiary, pending.mint, 1, 97);
+    assert!(send(&mut ctx, &[cancel], &[]).await.is_err());
+
+    let after: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!((after.total_amount, after.withdrawn), (before.total_amount, before.withdrawn));
+    assert_eq!(token_balance(&mut ctx, &get_associated_token_address(&vesting, &pending.mint)).await, 1_000);
+    assert_eq!(token_balance(&mut ctx, &pending.authority_tokens).await, 0);
+}
+
+#[tokio::test]
+async fn squatters_cannot_block_activation() {
+    let mut ctx = start().await;
+    let pending = registered(&mut ctx).await;
+    let squatter = Keypair::new();
+    fund(&mut ctx, &squatter.pubkey(), 1_000_000_000).await;
+
+    // The escrow ATA is anyone's to create, and the schedule address is
+    // reserved for the pending schedule
+    let vesting = vesting_pda(&pending.beneficiary, &pending.mint, 1, 97);
+    create_ata(&mut ctx, &vesting, &pending.mint).await;
+    let create = create_vesting_ix(squatter.pubkey(), pending.beneficiary, pending.mint, 1, 97, pending.unlock, 1);
+    let result = send(&mut ctx, &[create], &[&squatter]).await;
+    assert_vesting_error(result, VestingError::ScheduleReserved);
+
+    warp_to(&mut ctx, pending.activation).await;
+    send(&mut ctx, &[activate_ix(&pending, squatter.pubkey())], &[&squatter]).await.unwrap();
+    let schedule: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!((schedule.authority, schedule.total_amount), (pending.authority, 1_000));
+    assert_eq!(token_balance(&mut ctx, &get_associated_token_address(&vesting, &pending.mint)).await, 1_000);
+
+    // Nor can a pending schedule be registered over a live one
+    let live = create_vesting_ix(squatter.pubkey(), pending.beneficiary, pending.mint, 1, 96, pending.unlock, 1);
+    send(&mut ctx, &[live], &[&squatter]).await.unwrap();
+    let authority_tokens = create_ata(&mut ctx, &squatter.pubkey(), &pending.mint).await;
+    mint_to(&mut ctx, &pending.mint, &authority_tokens, 1).await;
+    let terms = schedule_terms(1, 96, pending.unlock + DAY, 1);
+    let register = register_pending_schedule_ix(squatter.pubkey(), pending.beneficiary, pending.mint, terms, pending.unlock);
+    let result = send(&mut ctx, &[register], &[&squatter]).await;
+    assert_vesting_error(result, VestingError::ScheduleReserved);
+}
+
+#[tokio::test]
+async fn registration_applies_the_creation_policy_at_activation() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let authority = ctx.payer.pubkey();
+    let authority_tokens = create_ata(&mut ctx, &authority, &mint).await;
+    mint_to(&mut ctx, &mint, &authority_tokens, 1_000).await;
+    let activation = now(&mut ctx).await + DAY;
+
+    // Unlocking at (or before) the activation time would start unlocked
+    let register =
+        register_pending_schedule_ix(authority, Pubkey::new_unique(), mint, schedule_terms(1, 98, activation, 1_000), activation);
+    assert_vesting_error(send(&mut ctx, &[register], &[]).await, VestingError::InvalidUnlockTime);
+
+    let past = now(&mut ctx).await;
+    let register =
+        register_pending_schedule_ix(authority, Pubkey::new_unique(), mint, schedule_terms(1, 98, past + DAY, 1_000), past);
+    assert_vesting_error(send(&mut ctx, &[register], &[]).await, VestingError::InvalidActivationTime);
+}
@@ -0,0 +1,69 @@
+//! Per-authority schedule cap: each creator may hold at most
+//! `max_schedules_per_authority` live schedules, and closing one frees a slot
+
+mod common;
+
+use common::*;
+use cvt_vesting::{ScheduleCount, VestingError, MIN_RECOVERY_TIMELOCK};
+use solana_sdk::signature::{Keypair, Signer};
+
+const CAP: u32 = 3;
+
+#[tokio::test]
+async fn creations_stop_at_the_cap_and_closing_frees_a_slot() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let init = initialize_config_ix(authority, vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(&mut ctx, &[init], &[]).await.unwrap();
+    send(&mut ctx, &[set_max_schedules_per_authority_ix(authority, CAP)], &[]).await.unwrap();
+
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(&mut ctx).await + DAY;
+    let create = |nonce| create_vesting_ix(authority, beneficiary.pubkey(), mint, 1, nonce, unlock, 1_000);
+
+    for nonce in 0..CAP as u64 {
+        send(&mut ctx, &[create(nonce)], &[]).await.unwrap();
+    }
+    let count: ScheduleCount = fetch(&mut ctx, schedule_count_pda(&authority)).await;
+    assert_eq!(count.schedule_count, CAP);
+
+    let result = send(&mut ctx, &[create(CAP as u64)], &[]).await;
+    assert_vesting_error(result, VestingError::ScheduleLimitReached);
+
+    // Other authorities have their own count
+    let other = Keypair::new();
+    fund(&mut ctx, &other.pubkey(), 1_000_000_000).await;
+    let ix = create_vesting_ix(other.pubkey(), beneficiary.pubkey(), mint, 2, 0, unlock, 1_000);
+    send(&mut ctx, &[ix], &[&other]).await.unwrap();
+
+    // Drain and close the first schedule
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 0);
+    let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+    mint_to(&mut ctx, &mint, &escrow, 1_000).await;
+    warp_to(&mut ctx, unlock).await;
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 1_000)], &[&beneficiary]).await.unwrap();
+    let close = close_vesting_ix(vesting, mint, beneficiary.pubkey(), authority);
+    send(&mut ctx, &[close], &[&beneficiary]).await.unwrap();
+
+    let count: ScheduleCount = fetch(&mut ctx, schedule_count_pda(&authority)).await;
+    assert_eq!(count.schedule_count, CAP - 1);
+    let create = create_vesting_ix(authority, beneficiary.pubkey(), mint, 1, CAP as u64, unlock + DAY, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn schedule_cap_is_authority_only_and_positive() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let init = initialize_config_ix(authority, vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(&mut ctx, &[init], &[]).await.unwrap();
+
+    let result = send(&mut ctx, &[set_max_schedules_per_authority_ix(authority, 0)], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidScheduleLimit);
+
+    let stranger = Keypair::new();
+    let result = send(&mut ctx, &[set_max_schedules_per_authority_ix(stranger.pubkey(), CAP)], &[&stranger]).await;
+    assert!(result.is_err());
+}
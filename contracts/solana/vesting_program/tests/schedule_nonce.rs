@@ -0,0 +1,61 @@
+//! Per-schedule nonce in the vesting PDA seeds (ATA squatting protection)
+
+mod common;
+
+use anchor_spl::associated_token::get_associated_token_address;
+use common::*;
+use cvt_vesting::Vesting;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn same_schedule_id_with_distinct_nonces_gets_distinct_accounts() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    let unlock = now(&mut ctx).await + YEAR;
+
+    // Same beneficiary + mint + schedule_id used to map to a single PDA
+    let (nonce_a, nonce_b) = (0x1111_2222_3333_4444, 0x5555_6666_7777_8888);
+    for nonce in [nonce_a, nonce_b] {
+        let ix = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, nonce, unlock, 1_000);
+        send(&mut ctx, &[ix], &[]).await.unwrap();
+    }
+
+    let vesting_a = vesting_pda(&beneficiary.pubkey(), &mint, 1, nonce_a);
+    let vesting_b = vesting_pda(&beneficiary.pubkey(), &mint, 1, nonce_b);
+    assert_ne!(vesting_a, vesting_b);
+    assert_ne!(
+        get_associated_token_address(&vesting_a, &mint),
+        get_associated_token_address(&vesting_b, &mint)
+    );
+
+    let state: Vesting = fetch(&mut ctx, vesting_b).await;
+    assert_eq!(state.nonce, nonce_b);
+
+    // Reusing a nonce still collides
+    let ix = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, nonce_a, unlock, 1_000);
+    assert!(send(&mut ctx, &[ix], &[]).await.is_err());
+}
+
+#[tokio::test]
+async fn squatted_ata_does_not_affect_unpredictable_schedule() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    let unlock = now(&mut ctx).await + YEAR;
+
+    // An attacker pre-creates the ATA for the best address they can guess
+    let guessed = vesting_pda(&beneficiary.pubkey(), &mint, 1, 0);
+    let squatted = create_ata(&mut ctx, &guessed, &mint).await;
+
+    // The real schedule uses a random nonce, so its ATA is still free
+    let nonce = 0x9e37_79b9_7f4a_7c15;
+    let ix = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, nonce, unlock, 1_000);
+    send(&mut ctx, &[ix], &[]).await.unwrap();
+
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, nonce);
+    let vesting_ata = get_associated_token_address(&vesting, &mint);
+    assert_ne!(vesting_ata, squatted);
+    assert!(ctx.banks_client.get_account(vesting_ata).await.unwrap().is_none());
+    assert_eq!(create_ata(&mut ctx, &vesting, &mint).await, vesting_ata);
+}
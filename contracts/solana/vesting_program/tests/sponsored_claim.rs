@@ -0,0 +1,72 @@
+//! Sponsored claims: a PDA beneficiary (here a DAO treasury) signs through
+//! CPI while a separate payer fronts the rent of its ATA, including when the
+//! ATA was closed between claims
+
+mod common;
+
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token;
+use common::*;
+use cvt_vesting::{Vesting, VestingError};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+/// An unlocked, funded 1,000-unit schedule for the DAO treasury; returns (vesting, mint)
+async fn treasury_schedule(ctx: &mut ProgramTestContext) -> (Pubkey, Pubkey) {
+    let (treasury, _) = mock_dao::treasury();
+    let mint = create_mint(ctx).await;
+    let unlock = now(ctx).await + DAY;
+    let create = create_vesting_ix(ctx.payer.pubkey(), treasury, mint, 1, 67, unlock, 1_000);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&treasury, &mint, 1, 67);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &escrow, 1_000).await;
+    warp_to(ctx, unlock).await;
+    (vesting, mint)
+}
+
+#[tokio::test]
+async fn dao_claims_for_its_treasury_across_a_closed_ata() {
+    let mut ctx = start().await;
+    let (vesting, mint) = treasury_schedule(&mut ctx).await;
+    let (treasury, _) = mock_dao::treasury();
+    let treasury_ata = get_associated_token_address(&treasury, &mint);
+    let sponsor = ctx.payer.pubkey();
+
+    // First claim creates the treasury's ATA, paid for by the sponsor
+    let claim = |amount| mock_dao::execute_ix(withdraw_sponsored_ix(vesting, mint, treasury, sponsor, amount));
+    send(&mut ctx, &[claim(400)], &[]).await.unwrap();
+    assert_eq!(token_balance(&mut ctx, &treasury_ata).await, 400);
+
+    // The DAO spends the tokens and closes the ATA between claims
+    let spend = create_ata(&mut ctx, &Keypair::new().pubkey(), &mint).await;
+    let transfer = spl_token::instruction::transfer(&spl_token::ID, &treasury_ata, &spend, &treasury, &[], 400).unwrap();
+    let close = spl_token::instruction::close_account(&spl_token::ID, &treasury_ata, &sponsor, &treasury, &[]).unwrap();
+    send(&mut ctx, &[mock_dao::execute_ix(transfer), mock_dao::execute_ix(close)], &[]).await.unwrap();
+    assert!(ctx.banks_client.get_account(treasury_ata).await.unwrap().is_none());
+
+    // The next claim recreates it and picks up where the history left off
+    send(&mut ctx, &[claim(600)], &[]).await.unwrap();
+    assert_eq!(token_balance(&mut ctx, &treasury_ata).await, 600);
+    assert_eq!(fetch::<Vesting>(&mut ctx, vesting).await.withdrawn, 1_000);
+
+    let result = send(&mut ctx, &[claim(1)], &[]).await;
+    assert_vesting_error(result, VestingError::InsufficientBalance);
+}
+
+#[tokio::test]
+async fn sponsor_cannot_claim_without_the_beneficiary() {
+    let mut ctx = start().await;
+    let (vesting, mint) = treasury_schedule(&mut ctx).await;
+    let sponsor = ctx.payer.pubkey();
+
+    // Any other signer in the beneficiary slot is refused
+    let impostor = Keypair::new();
+    let ix = withdraw_sponsored_ix(vesting, mint, impostor.pubkey(), sponsor, 400);
+    let result = send(&mut ctx, &[ix], &[&impostor]).await;
+    assert_vesting_error(result, VestingError::Unauthorized);
+    assert_eq!(fetch::<Vesting>(&mut ctx, vesting).await.withdrawn, 0);
+}
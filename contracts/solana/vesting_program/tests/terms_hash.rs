@@ -0,0 +1,79 @@
+//! Grant agreement commitments: a schedule's terms hash is set at creation,
+//! announced in VestingCreated, verifiable by anyone and never changed
+
+mod common;
+
+use common::*;
+use cvt_vesting::{Vesting, VestingCreated, MIN_RECOVERY_TIMELOCK};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const TERMS_HASH: [u8; 32] = [0x7e; 32];
+
+/// A funded 1,000-unit schedule committing TERMS_HASH; returns (vesting, mint, beneficiary)
+async fn schedule_with_terms(ctx: &mut ProgramTestContext) -> (Pubkey, Pubkey, Keypair) {
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    fund(ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(ctx).await + DAY;
+    let payer = ctx.payer.pubkey();
+    let data = cvt_vesting::instruction::CreateVesting { terms_hash: TERMS_HASH, ..base_create(1, 93, unlock, 1_000) };
+    let create = create_vesting_ix_with(payer, beneficiary.pubkey(), mint, data);
+    let (result, logs) = send_with_logs(ctx, &[create], &[]).await;
+    result.unwrap();
+    let created: Vec<VestingCreated> = decode_events(&logs);
+    assert_eq!(created[0].terms_hash, TERMS_HASH);
+
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 93);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &escrow, 1_000).await;
+    (vesting, mint, beneficiary)
+}
+
+#[tokio::test]
+async fn terms_hash_is_stored_and_verifiable() {
+    let mut ctx = start().await;
+    let (vesting, _, _) = schedule_with_terms(&mut ctx).await;
+    assert_eq!(fetch::<Vesting>(&mut ctx, vesting).await.terms_hash, TERMS_HASH);
+
+    let matches: bool = view(&mut ctx, verify_terms_ix(vesting, TERMS_HASH)).await;
+    assert!(matches);
+    let matches: bool = view(&mut ctx, verify_terms_ix(vesting, [0x7f; 32])).await;
+    assert!(!matches);
+}
+
+#[tokio::test]
+async fn schedule_without_terms_verifies_nothing() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Pubkey::new_unique();
+    let unlock = now(&mut ctx).await + DAY;
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary, mint, 1, 94, unlock, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary, &mint, 1, 94);
+
+    // A zero document hash must not "match" the absent terms
+    let matches: bool = view(&mut ctx, verify_terms_ix(vesting, [0; 32])).await;
+    assert!(!matches);
+}
+
+#[tokio::test]
+async fn terms_hash_survives_the_schedule_lifecycle() {
+    let mut ctx = start().await;
+    let (vesting, mint, beneficiary) = schedule_with_terms(&mut ctx).await;
+    let authority = ctx.payer.pubkey();
+    let init = initialize_config_ix(authority, vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+
+    send(&mut ctx, &[init, update_category_ix(authority, vesting, 3)], &[]).await.unwrap();
+    warp_forward(&mut ctx, DAY).await;
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 400)], &[&beneficiary]).await.unwrap();
+
+    let schedule: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!((schedule.category, schedule.withdrawn), (3, 400));
+    assert_eq!(schedule.terms_hash, TERMS_HASH);
+    let matches: bool = view(&mut ctx, verify_terms_ix(vesting, TERMS_HASH)).await;
+    assert!(matches);
+}
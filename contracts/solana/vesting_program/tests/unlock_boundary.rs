@@ -0,0 +1,79 @@
+//! Unlock boundary: claims are allowed at exactly `unlock_timestamp`, never
+//! before. Every path (creation, withdraw, is_claimable, aggregate_beneficiary)
+//! is checked at unlock-1, unlock and unlock+1 so they can never disagree.
+
+mod common;
+
+use common::*;
+use cvt_vesting::{is_unlocked, BeneficiarySummary, VestingError};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+#[test]
+fn unlock_rule_is_inclusive() {
+    let unlock = 1_700_000_000;
+    assert!(!is_unlocked(unlock - 1, unlock));
+    assert!(is_unlocked(unlock, unlock));
+    assert!(is_unlocked(unlock + 1, unlock));
+}
+
+/// Funded schedule of 300 unlocking at `unlock`: (vesting, beneficiary ATA)
+async fn schedule(ctx: &mut ProgramTestContext, beneficiary: &Keypair, mint: Pubkey, unlock: i64) -> (Pubkey, Pubkey) {
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 21, unlock, 300);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 21);
+    let escrow = create_ata(ctx, &vesting, &mint).await;
+    let beneficiary_ata = create_ata(ctx, &beneficiary.pubkey(), &mint).await;
+    mint_to(ctx, &mint, &escrow, 300).await;
+    (vesting, beneficiary_ata)
+}
+
+#[tokio::test]
+async fn creation_requires_a_locked_schedule() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new().pubkey();
+    let ts = now(&mut ctx).await;
+
+    for (nonce, unlock) in [(1, ts - 1), (2, ts)] {
+        let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary, mint, 1, nonce, unlock, 100);
+        let result = send(&mut ctx, &[create], &[]).await;
+        assert_vesting_error(result, VestingError::InvalidUnlockTime);
+    }
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary, mint, 1, 3, ts + 1, 100);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn every_path_agrees_at_the_boundary() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(&mut ctx).await + DAY;
+    let (vesting, beneficiary_ata) = schedule(&mut ctx, &beneficiary, mint, unlock).await;
+
+    // Ascending, since a successful withdraw records the observed time
+    for (at, unlocked) in [(unlock - 1, false), (unlock, true), (unlock + 1, true)] {
+        warp_to(&mut ctx, at).await;
+        assert_eq!(view::<bool>(&mut ctx, is_claimable_ix(vesting)).await, unlocked, "is_claimable at {at}");
+
+        let summary: BeneficiarySummary = view(&mut ctx, aggregate_beneficiary_ix(beneficiary.pubkey(), &[vesting])).await;
+        let withdrawn = summary.total_claimed;
+        let remaining = 300 - withdrawn;
+        let (claimable, locked) = if unlocked { (remaining, 0) } else { (0, remaining) };
+        assert_eq!((summary.total_claimable, summary.total_locked), (claimable, locked), "aggregate at {at}");
+
+        let result = send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 100)], &[&beneficiary]).await;
+        if unlocked {
+            result.unwrap();
+        } else {
+            assert_vesting_error(result, VestingError::StillLocked);
+        }
+    }
+
+    assert_eq!(token_balance(&mut ctx, &beneficiary_ata).await, 200);
+}
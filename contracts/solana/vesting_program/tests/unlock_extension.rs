@@ -0,0 +1,96 @@
+//! Unlock extension: the schedule's authority may push a locked unlock later,
+//! but never past the `max_unlock_timestamp` fixed at creation
+
+mod common;
+
+use common::*;
+use cvt_vesting::{UnlockExtended, Vesting, VestingError};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn extensions_stop_at_the_ceiling() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(&mut ctx).await + DAY;
+    let ceiling = unlock + 30 * DAY;
+
+    let data = cvt_vesting::instruction::CreateVesting {
+        max_unlock_timestamp: ceiling,
+        ..base_create(1, 7, unlock, 1_000)
+    };
+    let create = create_vesting_ix_with(authority, beneficiary.pubkey(), mint, data);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 7);
+    let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+    mint_to(&mut ctx, &mint, &escrow, 1_000).await;
+
+    let (result, logs) = send_with_logs(&mut ctx, &[extend_unlock_ix(authority, vesting, unlock + DAY)], &[]).await;
+    result.unwrap();
+    let event = decode_events::<UnlockExtended>(&logs).remove(0);
+    assert_eq!(
+        (event.previous_unlock, event.unlock_timestamp, event.max_unlock_timestamp),
+        (unlock, unlock + DAY, ceiling)
+    );
+
+    // Exactly the ceiling is allowed; one second more, or moving backwards, is not
+    send(&mut ctx, &[extend_unlock_ix(authority, vesting, ceiling)], &[]).await.unwrap();
+    let result = send(&mut ctx, &[extend_unlock_ix(authority, vesting, ceiling + 1)], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidUnlockTime);
+    let result = send(&mut ctx, &[extend_unlock_ix(authority, vesting, unlock + 2 * DAY)], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidUnlockTime);
+
+    let state: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!((state.unlock_timestamp, state.max_unlock_timestamp), (ceiling, ceiling));
+
+    // The ceiling is where funds release, whatever the authority wants
+    warp_to(&mut ctx, ceiling - 1).await;
+    let result = send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 1_000)], &[&beneficiary]).await;
+    assert_vesting_error(result, VestingError::StillLocked);
+    warp_to(&mut ctx, ceiling).await;
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 1_000)], &[&beneficiary]).await.unwrap();
+}
+
+#[tokio::test]
+async fn only_the_authority_extends_a_locked_schedule() {
+    let mut ctx = start().await;
+    let authority = ctx.payer.pubkey();
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    let unlock = now(&mut ctx).await + DAY;
+
+    // The ceiling cannot sit before the unlock
+    let data = cvt_vesting::instruction::CreateVesting {
+        max_unlock_timestamp: unlock - 1,
+        ..base_create(1, 8, unlock, 1_000)
+    };
+    let create = create_vesting_ix_with(authority, beneficiary.pubkey(), mint, data);
+    let result = send(&mut ctx, &[create], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidUnlockTime);
+
+    let data = cvt_vesting::instruction::CreateVesting {
+        max_unlock_timestamp: unlock + YEAR,
+        ..base_create(1, 8, unlock, 1_000)
+    };
+    let create = create_vesting_ix_with(authority, beneficiary.pubkey(), mint, data);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 8);
+
+    let stranger = Keypair::new();
+    let result = send(&mut ctx, &[extend_unlock_ix(stranger.pubkey(), vesting, unlock + DAY)], &[&stranger]).await;
+    assert!(result.is_err());
+
+    // Once unlocked, the schedule cannot be re-locked
+    warp_to(&mut ctx, unlock).await;
+    let result = send(&mut ctx, &[extend_unlock_ix(authority, vesting, unlock + DAY)], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidUnlockTime);
+
+    // Schedules created without a ceiling above their unlock cannot be extended at all
+    let fixed = create_vesting_ix(authority, beneficiary.pubkey(), mint, 2, 8, unlock + DAY, 1_000);
+    send(&mut ctx, &[fixed], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 2, 8);
+    let result = send(&mut ctx, &[extend_unlock_ix(authority, vesting, unlock + 2 * DAY)], &[]).await;
+    assert_vesting_error(result, VestingError::InvalidUnlockTime);
+}
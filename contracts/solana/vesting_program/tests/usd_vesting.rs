@@ -0,0 +1,164 @@
+//! USD-denominated schedules: tranches priced by a Pyth account at claim time,
+//! the oracle failure modes that block a claim, and the u128 conversion math
+
+mod common;
+
+use common::*;
+use cvt_vesting::{
+    tokens_to_usd, usd_to_tokens, UsdTerms, Vesting, VestingError, MIN_RECOVERY_TIMELOCK, USD_TRANCHE_INTERVAL,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+const TOKEN: u64 = 1_000_000_000;
+const USD: u64 = 1_000_000;
+/// $2.00 per token at Pyth's usual expo of -8
+const TWO_DOLLARS: i64 = 200_000_000;
+
+struct UsdSchedule {
+    vesting: Pubkey,
+    mint: Pubkey,
+    beneficiary: Keypair,
+    oracle: Pubkey,
+    pyth: Pubkey,
+}
+
+/// Config with an oracle policy (60s, 1%), plus an unlocked schedule of three
+/// $100 tranches with `escrow` tokens deposited
+async fn usd_schedule(ctx: &mut ProgramTestContext, escrow: u64) -> UsdSchedule {
+    let authority = ctx.payer.pubkey();
+    let init = initialize_config_ix(authority, vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(ctx, &[init], &[]).await.unwrap();
+    let pyth = Pubkey::new_unique();
+    send(ctx, &[set_oracle_policy_ix(authority, Some(pyth), 60, 100)], &[]).await.unwrap();
+
+    let mint = create_mint(ctx).await;
+    let beneficiary = Keypair::new();
+    let oracle = Pubkey::new_unique();
+    let unlock = now(ctx).await + DAY;
+    let terms = UsdTerms { usd_per_tranche: 100 * USD, tranches: 3, price_oracle: oracle };
+    let data = cvt_vesting::instruction::CreateVesting { usd_terms: Some(terms), ..base_create(1, 51, unlock, 1_000 * TOKEN) };
+    let create = create_vesting_ix_with(authority, beneficiary.pubkey(), mint, data);
+    send(ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 51);
+    let vesting_ata = create_ata(ctx, &vesting, &mint).await;
+    mint_to(ctx, &mint, &vesting_ata, escrow).await;
+    create_ata(ctx, &beneficiary.pubkey(), &mint).await;
+    warp_to(ctx, unlock).await;
+
+    UsdSchedule { vesting, mint, beneficiary, oracle, pyth }
+}
+
+async fn claim(ctx: &mut ProgramTestContext, schedule: &UsdSchedule, amount: u64) -> Result<(), TransactionError> {
+    let beneficiary = schedule.beneficiary.pubkey();
+    let ix = withdraw_usd_ix(schedule.vesting, schedule.mint, beneficiary, amount, Some(schedule.oracle));
+    send(ctx, &[ix], &[&schedule.beneficiary]).await
+}
+
+#[tokio::test]
+async fn tranches_convert_at_the_claim_time_price() {
+    let mut ctx = start().await;
+    let schedule = usd_schedule(&mut ctx, 1_000 * TOKEN).await;
+
+    let current = now(&mut ctx).await;
+    set_pyth_price(&mut ctx, schedule.oracle, schedule.pyth, TWO_DOLLARS, 0, -8, current);
+    claim(&mut ctx, &schedule, u64::MAX).await.unwrap();
+    let state: Vesting = fetch(&mut ctx, schedule.vesting).await;
+    assert_eq!((state.withdrawn, state.usd_claimed), (50 * TOKEN, 100 * USD));
+
+    // The first tranche is used up until the next one unlocks
+    let result = claim(&mut ctx, &schedule, u64::MAX - 1).await;
+    assert_vesting_error(result, VestingError::InsufficientBalance);
+
+    // Next month at $4.00: the $100 tranche is worth 25 tokens
+    warp_forward(&mut ctx, USD_TRANCHE_INTERVAL).await;
+    let current = now(&mut ctx).await;
+    set_pyth_price(&mut ctx, schedule.oracle, schedule.pyth, 2 * TWO_DOLLARS, 0, -8, current);
+    claim(&mut ctx, &schedule, 10 * TOKEN).await.unwrap();
+    claim(&mut ctx, &schedule, u64::MAX).await.unwrap();
+    let state: Vesting = fetch(&mut ctx, schedule.vesting).await;
+    assert_eq!((state.withdrawn, state.usd_claimed), (75 * TOKEN, 200 * USD));
+
+    // Long after the last tranche, only three are ever paid
+    warp_forward(&mut ctx, 10 * USD_TRANCHE_INTERVAL).await;
+    let current = now(&mut ctx).await;
+    set_pyth_price(&mut ctx, schedule.oracle, schedule.pyth, TWO_DOLLARS, 0, -8, current);
+    claim(&mut ctx, &schedule, u64::MAX).await.unwrap();
+    let state: Vesting = fetch(&mut ctx, schedule.vesting).await;
+    assert_eq!((state.withdrawn, state.usd_claimed), (125 * TOKEN, 300 * USD));
+}
+
+#[tokio::test]
+async fn payout_is_capped_by_the_deposited_balance() {
+    let mut ctx = start().await;
+    let schedule = usd_schedule(&mut ctx, 20 * TOKEN).await;
+
+    let current = now(&mut ctx).await;
+    set_pyth_price(&mut ctx, schedule.oracle, schedule.pyth, TWO_DOLLARS, 0, -8, current);
+    claim(&mut ctx, &schedule, u64::MAX).await.unwrap();
+    let state: Vesting = fetch(&mut ctx, schedule.vesting).await;
+    assert_eq!((state.withdrawn, state.usd_claimed), (20 * TOKEN, 40 * USD));
+}
+
+#[tokio::test]
+async fn oracle_failures_block_the_claim() {
+    let mut ctx = start().await;
+    let schedule = usd_schedule(&mut ctx, 1_000 * TOKEN).await;
+    let current = now(&mut ctx).await;
+
+    let no_oracle = withdraw_usd_ix(schedule.vesting, schedule.mint, schedule.beneficiary.pubkey(), u64::MAX, None);
+    let result = send(&mut ctx, &[no_oracle], &[&schedule.beneficiary]).await;
+    assert_vesting_error(result, VestingError::OracleUnavailable);
+
+    // Right layout, wrong owner
+    set_pyth_price(&mut ctx, schedule.oracle, Pubkey::new_unique(), TWO_DOLLARS, 0, -8, current);
+    assert_vesting_error(claim(&mut ctx, &schedule, u64::MAX).await, VestingError::OracleInvalid);
+
+    set_pyth_price(&mut ctx, schedule.oracle, schedule.pyth, 0, 0, -8, current);
+    assert_vesting_error(claim(&mut ctx, &schedule, u64::MAX - 1).await, VestingError::OracleInvalid);
+
+    set_pyth_price(&mut ctx, schedule.oracle, schedule.pyth, TWO_DOLLARS, 0, -8, current - 61);
+    assert_vesting_error(claim(&mut ctx, &schedule, u64::MAX - 2).await, VestingError::OracleStale);
+
+    // 1% of $2.00 is the widest accepted interval
+    set_pyth_price(&mut ctx, schedule.oracle, schedule.pyth, TWO_DOLLARS, 2_000_001, -8, current);
+    assert_vesting_error(claim(&mut ctx, &schedule, u64::MAX - 3).await, VestingError::OracleConfidenceTooWide);
+
+    // Without an oracle program configured there is no price at all
+    let authority = ctx.payer.pubkey();
+    send(&mut ctx, &[set_oracle_policy_ix(authority, None, 60, 100)], &[]).await.unwrap();
+    set_pyth_price(&mut ctx, schedule.oracle, schedule.pyth, TWO_DOLLARS, 2_000_000, -8, current);
+    assert_vesting_error(claim(&mut ctx, &schedule, u64::MAX - 4).await, VestingError::OracleUnavailable);
+
+    send(&mut ctx, &[set_oracle_policy_ix(authority, Some(schedule.pyth), 60, 100)], &[]).await.unwrap();
+    claim(&mut ctx, &schedule, u64::MAX).await.unwrap();
+}
+
+#[test]
+fn conversion_handles_extreme_prices() {
+    // $50,000 at $2.00 for a 9-decimal token, and back
+    assert_eq!(usd_to_tokens(50_000 * USD, 200_000_000, -8, 9), 25_000 * TOKEN);
+    assert_eq!(tokens_to_usd(25_000 * TOKEN, 200_000_000, -8, 9), Some(50_000 * USD));
+
+    // Rounding: tokens round down, the USD they use up rounds up
+    assert_eq!(usd_to_tokens(1, 300_000_000, -8, 9), 333);
+    assert_eq!(tokens_to_usd(333, 300_000_000, -8, 9), Some(1));
+
+    // A price at i64::MAX buys nothing, and one base unit is worth more than u64
+    assert_eq!(usd_to_tokens(u64::MAX, i64::MAX as u64, 0, 0), 0);
+    assert_eq!(tokens_to_usd(1, i64::MAX as u64, 0, 0), None);
+
+    // Dust prices saturate instead of overflowing
+    assert_eq!(usd_to_tokens(u64::MAX, 1, -18, 9), u64::MAX);
+    assert_eq!(tokens_to_usd(u64::MAX, 1, -18, 9), Some(1));
+    assert_eq!(usd_to_tokens(1, 1, -40, 9), u64::MAX);
+    assert_eq!(tokens_to_usd(1, 1, -40, 9), Some(1));
+
+    // A zero price never converts
+    assert_eq!(usd_to_tokens(50_000 * USD, 0, -8, 9), 0);
+    assert_eq!(usd_to_tokens(0, 200_000_000, -8, 9), 0);
+}
@@ -0,0 +1,318 @@
+//! Read-only views returned via Anchor return data
+
+mod common;
+
+use common::*;
+use anchor_lang::AnchorDeserialize;
+use cvt_vesting::{
+    BeneficiarySummary, NextUnlock, ScheduleBalance, UsdTerms, Vesting, VestingError, MAX_PROJECTION_POINTS,
+    MIN_RECOVERY_TIMELOCK, STATE_BLOB_VERSION, USD_TRANCHE_INTERVAL,
+};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+#[tokio::test]
+async fn is_claimable_tracks_unlock_and_balance() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(&mut ctx).await + YEAR;
+
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 42, unlock, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 42);
+    let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+    mint_to(&mut ctx, &mint, &escrow, 1_000).await;
+    create_ata(&mut ctx, &beneficiary.pubkey(), &mint).await;
+
+    // Before the cliff
+    assert!(!view::<bool>(&mut ctx, is_claimable_ix(vesting)).await);
+    warp_to(&mut ctx, unlock - 1).await;
+    assert!(!view::<bool>(&mut ctx, is_claimable_ix(vesting)).await);
+
+    // At and after the cliff
+    warp_to(&mut ctx, unlock).await;
+    assert!(view::<bool>(&mut ctx, is_claimable_ix(vesting)).await);
+
+    // Fully withdrawn: nothing left to claim
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 1_000)], &[&beneficiary])
+        .await
+        .unwrap();
+    assert!(!view::<bool>(&mut ctx, is_claimable_ix(vesting)).await);
+}
+
+#[tokio::test]
+async fn aggregate_beneficiary_sums_all_schedules() {
+    let mut ctx = start().await;
+    let init = initialize_config_ix(ctx.payer.pubkey(), vec![Keypair::new().pubkey()], 1, MIN_RECOVERY_TIMELOCK);
+    send(&mut ctx, &[init], &[]).await.unwrap();
+
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    create_ata(&mut ctx, &beneficiary.pubkey(), &mint).await;
+    let start_ts = now(&mut ctx).await;
+
+    // (schedule_id, unlock, amount): unlocks soon, unlocks in a year, unlocks soon but paused
+    let mut schedules = vec![];
+    for (schedule_id, unlock, amount) in [(1, start_ts + DAY, 1_000), (2, start_ts + YEAR, 500), (3, start_ts + DAY, 200)] {
+        let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, schedule_id, 5, unlock, amount);
+        send(&mut ctx, &[create], &[]).await.unwrap();
+        let vesting = vesting_pda(&beneficiary.pubkey(), &mint, schedule_id, 5);
+        let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+        mint_to(&mut ctx, &mint, &escrow, amount).await;
+        schedules.push(vesting);
+    }
+
+    let summary: BeneficiarySummary = view(&mut ctx, aggregate_beneficiary_ix(beneficiary.pubkey(), &schedules)).await;
+    assert_eq!(
+        summary,
+        BeneficiarySummary { schedules: 3, total_locked: 1_700, total_claimed: 0, total_claimable: 0, total_lock_seconds: 0 }
+    );
+
+    warp_to(&mut ctx, start_ts + DAY).await;
+    send(&mut ctx, &[withdraw_ix(schedules[0], mint, beneficiary.pubkey(), 300)], &[&beneficiary])
+        .await
+        .unwrap();
+    let authority = ctx.payer.pubkey();
+    send(&mut ctx, &[set_pause_ix(authority, schedules[2], true, false)], &[]).await.unwrap();
+
+    let summary: BeneficiarySummary = view(&mut ctx, aggregate_beneficiary_ix(beneficiary.pubkey(), &schedules)).await;
+    assert_eq!(
+        summary,
+        BeneficiarySummary {
+            schedules: 3,
+            total_locked: 700,
+            total_claimed: 300,
+            total_claimable: 700,
+            // 300 tokens claimed a day after creation
+            total_lock_seconds: 300 * DAY as u128,
+        }
+    );
+
+    // Someone else's schedule, or the same schedule twice, is rejected
+    let other = Keypair::new().pubkey();
+    let create = create_vesting_ix(ctx.payer.pubkey(), other, mint, 1, 5, start_ts + YEAR, 50);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let foreign = [schedules[0], vesting_pda(&other, &mint, 1, 5)];
+    let result = send(&mut ctx, &[aggregate_beneficiary_ix(beneficiary.pubkey(), &foreign)], &[]).await;
+    assert_vesting_error(result, VestingError::Unauthorized);
+
+    let duplicated = [schedules[0], schedules[1], schedules[0]];
+    let result = send(&mut ctx, &[aggregate_beneficiary_ix(beneficiary.pubkey(), &duplicated)], &[]).await;
+    assert_vesting_error(result, VestingError::DuplicateSchedule);
+}
+
+#[tokio::test]
+async fn projection_matches_claims_after_warping() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let start_ts = now(&mut ctx).await;
+    let unlock = start_ts + YEAR;
+
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 43, unlock, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 43);
+    let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+    mint_to(&mut ctx, &mint, &escrow, 1_000).await;
+    create_ata(&mut ctx, &beneficiary.pubkey(), &mint).await;
+
+    let points = vec![start_ts, unlock - 1, unlock, unlock + YEAR];
+    let projected: Vec<u64> = view(&mut ctx, project_claimable_ix(vesting, points.clone())).await;
+    assert_eq!(projected, [0, 0, 1_000, 1_000]);
+
+    // The real clock agrees at each point
+    warp_to(&mut ctx, points[1]).await;
+    let result = send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 1)], &[&beneficiary]).await;
+    assert_vesting_error(result, VestingError::StillLocked);
+    warp_to(&mut ctx, points[2]).await;
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), projected[2] - 400)], &[&beneficiary])
+        .await
+        .unwrap();
+
+    // After a claim, projections start from the current state and time
+    let later = now(&mut ctx).await + DAY;
+    let projected: Vec<u64> = view(&mut ctx, project_claimable_ix(vesting, vec![later])).await;
+    assert_eq!(projected, [400]);
+
+    let result = send(&mut ctx, &[project_claimable_ix(vesting, vec![start_ts])], &[]).await;
+    assert_vesting_error(result, VestingError::ProjectionBeforeStart);
+    let too_many = vec![later; MAX_PROJECTION_POINTS + 1];
+    let result = send(&mut ctx, &[project_claimable_ix(vesting, too_many)], &[]).await;
+    assert_vesting_error(result, VestingError::BatchTooLarge);
+}
+
+#[tokio::test]
+async fn claimable_batch_reads_each_schedule_in_account_order() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    create_ata(&mut ctx, &beneficiary.pubkey(), &mint).await;
+    let start_ts = now(&mut ctx).await;
+
+    // (schedule_id, unlock, amount): partly claimed, untouched, still locked
+    let mut schedules = vec![];
+    for (schedule_id, unlock, amount) in [(1, start_ts + DAY, 1_000), (2, start_ts + DAY, 500), (3, start_ts + YEAR, 200)] {
+        let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, schedule_id, 48, unlock, amount);
+        send(&mut ctx, &[create], &[]).await.unwrap();
+        let vesting = vesting_pda(&beneficiary.pubkey(), &mint, schedule_id, 48);
+        let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+        mint_to(&mut ctx, &mint, &escrow, amount).await;
+        schedules.push(vesting);
+    }
+    warp_to(&mut ctx, start_ts + DAY).await;
+    send(&mut ctx, &[withdraw_ix(schedules[0], mint, beneficiary.pubkey(), 300)], &[&beneficiary])
+        .await
+        .unwrap();
+
+    let order = [schedules[2], schedules[0], schedules[1]];
+    let claimable: Vec<u64> = view(&mut ctx, claimable_batch_ix(&order)).await;
+    assert_eq!(claimable, [0, 700, 500]);
+
+    // Only this program's schedules can be read
+    let result = send(&mut ctx, &[claimable_batch_ix(&[schedules[0], beneficiary.pubkey()])], &[]).await;
+    assert_vesting_error(result, VestingError::Unauthorized);
+}
+
+#[tokio::test]
+async fn actual_balance_surfaces_escrow_drift() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(&mut ctx).await + DAY;
+
+    // A 1,000-unit grant whose escrow only ever received 600
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 44, unlock, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 44);
+    let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+    mint_to(&mut ctx, &mint, &escrow, 600).await;
+    warp_to(&mut ctx, unlock).await;
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 250)], &[&beneficiary])
+        .await
+        .unwrap();
+
+    // Accounting still promises 750; the escrow can pay 350
+    let balance: ScheduleBalance = view(&mut ctx, get_actual_balance_ix(vesting, mint)).await;
+    assert_eq!(
+        balance,
+        ScheduleBalance { actual_balance: 350, total_amount: 1_000, withdrawn: 250, accounted_remaining: 750 }
+    );
+
+    // Direct transfers into the escrow show up only in the live balance
+    mint_to(&mut ctx, &mint, &escrow, 500).await;
+    let balance: ScheduleBalance = view(&mut ctx, get_actual_balance_ix(vesting, mint)).await;
+    assert_eq!((balance.actual_balance, balance.accounted_remaining), (850, 750));
+
+    let other_mint = create_mint(&mut ctx).await;
+    create_ata(&mut ctx, &vesting, &other_mint).await;
+    let result = send(&mut ctx, &[get_actual_balance_ix(vesting, other_mint)], &[]).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn state_blob_is_versioned_and_matches_the_account() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(&mut ctx).await + DAY;
+
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 45, unlock, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 45);
+    let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+    mint_to(&mut ctx, &mint, &escrow, 1_000).await;
+    warp_to(&mut ctx, unlock).await;
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 300)], &[&beneficiary])
+        .await
+        .unwrap();
+
+    let blob: Vec<u8> = view(&mut ctx, serialize_state_ix(vesting)).await;
+    assert_eq!(blob[0], STATE_BLOB_VERSION);
+
+    // Byte for byte the account data past its discriminator
+    let data = ctx.banks_client.get_account(vesting).await.unwrap().unwrap().data;
+    assert_eq!(&blob[1..], &data[8..blob.len() + 7]);
+
+    let decoded = Vesting::try_from_slice(&blob[1..]).unwrap();
+    let stored: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!(
+        (decoded.beneficiary, decoded.mint, decoded.schedule_id, decoded.nonce),
+        (stored.beneficiary, stored.mint, 1, 45)
+    );
+    assert_eq!(
+        (decoded.unlock_timestamp, decoded.total_amount, decoded.withdrawn),
+        (unlock, 1_000, 300)
+    );
+    assert_eq!(decoded.last_observed_timestamp, stored.last_observed_timestamp);
+}
+
+#[tokio::test]
+async fn next_unlock_of_a_single_unlock_schedule_is_its_remainder_at_unlock() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), 1_000_000_000).await;
+    let unlock = now(&mut ctx).await + YEAR;
+
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 46, unlock, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 46);
+    let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+    mint_to(&mut ctx, &mint, &escrow, 1_000).await;
+
+    let next: NextUnlock = view(&mut ctx, next_unlock_info_ix(vesting)).await;
+    assert_eq!(next, NextUnlock { next_ts: unlock, next_amount: 1_000 });
+    warp_to(&mut ctx, unlock - 1).await;
+    let next: NextUnlock = view(&mut ctx, next_unlock_info_ix(vesting)).await;
+    assert_eq!(next, NextUnlock { next_ts: unlock, next_amount: 1_000 });
+
+    // Everything unlocked at once: nothing comes next, claimed or not
+    warp_to(&mut ctx, unlock).await;
+    assert_eq!(view::<NextUnlock>(&mut ctx, next_unlock_info_ix(vesting)).await, NextUnlock::default());
+    send(&mut ctx, &[withdraw_ix(vesting, mint, beneficiary.pubkey(), 400)], &[&beneficiary])
+        .await
+        .unwrap();
+    assert_eq!(view::<NextUnlock>(&mut ctx, next_unlock_info_ix(vesting)).await, NextUnlock::default());
+}
+
+#[tokio::test]
+async fn next_unlock_of_a_tranched_schedule_is_its_next_tranche() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Pubkey::new_unique();
+    let unlock = now(&mut ctx).await + DAY;
+    let terms = UsdTerms { usd_per_tranche: 100_000_000, tranches: 3, price_oracle: Pubkey::new_unique() };
+
+    let data = cvt_vesting::instruction::CreateVesting { usd_terms: Some(terms), ..base_create(1, 47, unlock, 1_000) };
+    let create = create_vesting_ix_with(ctx.payer.pubkey(), beneficiary, mint, data);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary, &mint, 1, 47);
+
+    let next: NextUnlock = view(&mut ctx, next_unlock_info_ix(vesting)).await;
+    assert_eq!(next, NextUnlock { next_ts: unlock, next_amount: 100_000_000 });
+
+    // The first tranche unlocks at unlock, each later one an interval apart
+    for tranche in 1..3 {
+        warp_to(&mut ctx, unlock + (tranche - 1) * USD_TRANCHE_INTERVAL).await;
+        let next: NextUnlock = view(&mut ctx, next_unlock_info_ix(vesting)).await;
+        assert_eq!(
+            next,
+            NextUnlock { next_ts: unlock + tranche * USD_TRANCHE_INTERVAL, next_amount: 100_000_000 }
+        );
+        warp_forward(&mut ctx, USD_TRANCHE_INTERVAL - 1).await;
+        let next: NextUnlock = view(&mut ctx, next_unlock_info_ix(vesting)).await;
+        assert_eq!(next.next_ts, unlock + tranche * USD_TRANCHE_INTERVAL);
+    }
+
+    warp_to(&mut ctx, unlock + 2 * USD_TRANCHE_INTERVAL).await;
+    assert_eq!(view::<NextUnlock>(&mut ctx, next_unlock_info_ix(vesting)).await, NextUnlock::default());
+}
@@ -0,0 +1,67 @@
+//! Claiming a wSOL schedule as native SOL
+
+mod common;
+
+use anchor_spl::token::spl_token::native_mint;
+use common::*;
+use cvt_vesting::{Vesting, VestingError};
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+
+#[tokio::test]
+async fn wsol_schedule_claims_as_native_lamports() {
+    let mut ctx = start().await;
+    let mint = native_mint::ID;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), LAMPORTS_PER_SOL).await;
+
+    let unlock = now(&mut ctx).await + DAY;
+    let amount = 3 * LAMPORTS_PER_SOL;
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 17, unlock, amount);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 17);
+    let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+    wrap_sol(&mut ctx, &escrow, amount).await;
+
+    let withdraw = |amount| withdraw_native_ix(vesting, mint, beneficiary.pubkey(), amount);
+    let result = send(&mut ctx, &[withdraw(LAMPORTS_PER_SOL)], &[&beneficiary]).await;
+    assert_vesting_error(result, VestingError::StillLocked);
+
+    warp_to(&mut ctx, unlock).await;
+    let before = lamports(&mut ctx, beneficiary.pubkey()).await;
+    send(&mut ctx, &[withdraw(2 * LAMPORTS_PER_SOL)], &[&beneficiary]).await.unwrap();
+
+    // Exactly the claim: the harness payer covers the fee and the temporary
+    // account's rent came straight back on close
+    assert_eq!(lamports(&mut ctx, beneficiary.pubkey()).await, before + 2 * LAMPORTS_PER_SOL);
+    assert!(ctx.banks_client.get_account(unwrap_pda(&vesting)).await.unwrap().is_none());
+    assert_eq!(token_balance(&mut ctx, &escrow).await, LAMPORTS_PER_SOL);
+    let state: Vesting = fetch(&mut ctx, vesting).await;
+    assert_eq!(state.withdrawn, 2 * LAMPORTS_PER_SOL);
+
+    // Same accounting as token withdrawals
+    let result = send(&mut ctx, &[withdraw(LAMPORTS_PER_SOL + 1)], &[&beneficiary]).await;
+    assert_vesting_error(result, VestingError::InsufficientBalance);
+}
+
+#[tokio::test]
+async fn native_withdrawal_rejects_other_mints() {
+    let mut ctx = start().await;
+    let mint = create_mint(&mut ctx).await;
+    let beneficiary = Keypair::new();
+    fund(&mut ctx, &beneficiary.pubkey(), LAMPORTS_PER_SOL).await;
+
+    let unlock = now(&mut ctx).await + DAY;
+    let create = create_vesting_ix(ctx.payer.pubkey(), beneficiary.pubkey(), mint, 1, 18, unlock, 1_000);
+    send(&mut ctx, &[create], &[]).await.unwrap();
+    let vesting = vesting_pda(&beneficiary.pubkey(), &mint, 1, 18);
+    let escrow = create_ata(&mut ctx, &vesting, &mint).await;
+    mint_to(&mut ctx, &mint, &escrow, 1_000).await;
+
+    warp_to(&mut ctx, unlock).await;
+    let result = send(&mut ctx, &[withdraw_native_ix(vesting, mint, beneficiary.pubkey(), 100)], &[&beneficiary]).await;
+    assert_vesting_error(result, VestingError::NotNativeMint);
+    assert_eq!(token_balance(&mut ctx, &escrow).await, 1_000);
+}
@@ -0,0 +1,1326 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  SYSVAR_SLOT_HASHES_PUBKEY,
+  Transaction,
+} from '@solana/web3.js';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { keccak256 } from 'ethers';
+import { randomBytes } from 'crypto';
+import { readFileSync } from 'fs';
+import * as path from 'path';
+
+// Shared setup for Trinity Validator tests
+// New features should extend this harness rather than re-deriving PDAs and
+// re-building instructions in each test file.
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+const BPF_LOADER_UPGRADEABLE_ID = new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111');
+
+export type HtlcVector = { name: string; preimage: Buffer; hashlock: Buffer };
+
+/// Overrides for submitProof's defaults
+export type SubmitOptions = {
+  operationType?: object;
+  amount?: number;
+  htlc?: HtlcVector;
+  seenFilter?: boolean;
+  submitter?: Keypair;
+  commit?: boolean;
+  ethEventTimestamp?: number;
+  /// Submit a positional path with these per-level flags (default: sorted pairs)
+  siblingIsLeft?: boolean[];
+};
+
+/// Shared HTLC preimage/hashlock vectors (tests/fixtures/htlc_preimages.json)
+export function htlcVectors(): HtlcVector[] {
+  const fixture = JSON.parse(
+    readFileSync(path.join(__dirname, '..', 'fixtures', 'htlc_preimages.json'), 'utf8')
+  );
+  return fixture.vectors.map((vector: { name: string; preimage: string; hashlock: string }) => ({
+    name: vector.name,
+    preimage: Buffer.from(vector.preimage.slice(2), 'hex'),
+    hashlock: Buffer.from(vector.hashlock.slice(2), 'hex'),
+  }));
+}
+
+export class TrinityTestHarness {
+  readonly provider: anchor.AnchorProvider;
+  readonly program: Program<any>;
+  readonly authority: PublicKey;
+  readonly validatorPda: PublicKey;
+
+  constructor() {
+    this.provider = anchor.AnchorProvider.env();
+    anchor.setProvider(this.provider);
+    this.program = anchor.workspace.TrinityValidator as Program<any>;
+    this.authority = this.provider.wallet.publicKey;
+    [this.validatorPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('trinity_validator')],
+      this.program.programId
+    );
+  }
+
+  proofPda(operationId: Buffer): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('proof'), operationId],
+      this.program.programId
+    )[0];
+  }
+
+  councilPda(): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('dispute_council'), this.validatorPda.toBuffer()],
+      this.program.programId
+    )[0];
+  }
+
+  overridePda(operationId: Buffer): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('override'), operationId],
+      this.program.programId
+    )[0];
+  }
+
+  consensusPda(operationId: Buffer): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('consensus'), operationId],
+      this.program.programId
+    )[0];
+  }
+
+  consensusMirrorPda(operationId: Buffer): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('consensus_mirror'), operationId],
+      this.program.programId
+    )[0];
+  }
+
+  /// Days since the Unix epoch, the DailyStats shard key
+  currentDay(): number {
+    return Math.floor(Date.now() / 1000 / 86_400);
+  }
+
+  dailyStatsPda(day = this.currentDay()): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('stats'), new anchor.BN(day).toArrayLike(Buffer, 'le', 8)],
+      this.program.programId
+    )[0];
+  }
+
+  randomOperationId(): Buffer {
+    return Buffer.from(Keypair.generate().publicKey.toBytes());
+  }
+
+  /// Initialize the validator singleton once per local validator run
+  async ensureInitialized() {
+    const existing = await this.provider.connection.getAccountInfo(this.validatorPda);
+    if (!existing) {
+      await this.program.methods
+        .initialize(
+          Array.from(Buffer.alloc(20, 0xaa)),
+          Array.from(Buffer.alloc(20, 0xbb)),
+          'https://sepolia-rollup.arbitrum.io/rpc'
+        )
+        .accounts({ validator: this.validatorPda, authority: this.authority })
+        .rpc();
+    }
+  }
+
+  monitorConfigPda(): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('monitor_config'), this.validatorPda.toBuffer()],
+      this.program.programId
+    )[0];
+  }
+
+  /// One-call localnet setup (devtools builds only)
+  async bootstrapLocalnet() {
+    await this.program.methods
+      .bootstrapLocalnet()
+      .accounts({
+        validator: this.validatorPda,
+        monitorConfig: this.monitorConfigPda(),
+        council: this.councilPda(),
+        authority: this.authority,
+      })
+      .rpc();
+  }
+
+  /// Deterministic Merkle path of `len` sibling hashes
+  proofElements(len: number): Buffer[] {
+    return Array.from({ length: len }, (_, i) => Buffer.alloc(32, i + 7));
+  }
+
+  /// Off-chain mirror of calculate_merkle_root (sorted-pair keccak)
+  expectedMerkleRoot(proof: Buffer[], leaf: Buffer): Buffer {
+    return proof.reduce((current, sibling) => {
+      const [a, b] = Buffer.compare(current, sibling) < 0 ? [current, sibling] : [sibling, current];
+      return Buffer.from(keccak256(Buffer.concat([a, b])).slice(2), 'hex');
+    }, leaf);
+  }
+
+  /// Off-chain mirror of calculate_positional_merkle_root: the sibling goes
+  /// first where its flag is set, as keccak256(abi.encodePacked(left, right))
+  expectedPositionalRoot(proof: Buffer[], siblingIsLeft: boolean[], leaf: Buffer): Buffer {
+    return proof.reduce((current, sibling, i) => {
+      const pair = siblingIsLeft[i] ? [sibling, current] : [current, sibling];
+      return Buffer.from(keccak256(Buffer.concat(pair)).slice(2), 'hex');
+    }, leaf);
+  }
+
+  /// Off-chain mirror of derive_proof_id: keccak(operation_id, merkle_root, validator)
+  expectedProofId(operationId: Buffer, merkleRoot: Buffer): Buffer {
+    const preimage = Buffer.concat([operationId, merkleRoot, this.validatorPda.toBuffer()]);
+    return Buffer.from(keccak256(preimage).slice(2), 'hex');
+  }
+
+  /// Newest (slot, hash) entry of the SlotHashes sysvar: u64 count, then
+  /// (u64 slot, 32-byte hash) entries by descending slot
+  async recentSlotHash(): Promise<{ slot: number; hash: Buffer }> {
+    const sysvar = await this.provider.connection.getAccountInfo(SYSVAR_SLOT_HASHES_PUBKEY);
+    if (!sysvar || sysvar.data.readBigUInt64LE(0) === 0n) {
+      throw new Error('SlotHashes sysvar is empty');
+    }
+    return {
+      slot: Number(sysvar.data.readBigUInt64LE(8)),
+      hash: Buffer.from(sysvar.data.subarray(16, 48)),
+    };
+  }
+
+  /// Newest SlotHashes entry at least `minAge` slots old, waiting for the
+  /// cluster to advance far enough if needed
+  async staleSlotHash(minAge: number): Promise<{ slot: number; hash: Buffer }> {
+    for (;;) {
+      const current = await this.provider.connection.getSlot();
+      const sysvar = await this.provider.connection.getAccountInfo(SYSVAR_SLOT_HASHES_PUBKEY);
+      const count = sysvar ? Number(sysvar.data.readBigUInt64LE(0)) : 0;
+      for (let i = 0; i < count; i++) {
+        const offset = 8 + i * 40;
+        const slot = Number(sysvar!.data.readBigUInt64LE(offset));
+        if (current - slot >= minAge) {
+          return { slot, hash: Buffer.from(sysvar!.data.subarray(offset + 8, offset + 40)) };
+        }
+      }
+      await sleep(1_000);
+    }
+  }
+
+  expectedOperationPda(operationId: Buffer): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('expected_operation'), operationId],
+      this.program.programId
+    )[0];
+  }
+
+  /// Commit an operation as emitted by the bridge; `committer` defaults to
+  /// the validator authority (the initial operation committer)
+  async commitOperation(operationId: Buffer, committer?: Keypair) {
+    await this.program.methods
+      .commitOperation(Array.from(operationId))
+      .accounts({
+        validator: this.validatorPda,
+        expectedOperation: this.expectedOperationPda(operationId),
+        operationCommitter: committer?.publicKey ?? this.authority,
+        payer: this.authority,
+      })
+      .signers(committer ? [committer] : [])
+      .rpc();
+  }
+
+  /// Commit an operation unless it already has been (resubmission tests)
+  async ensureCommitted(operationId: Buffer) {
+    const existing = await this.provider.connection.getAccountInfo(this.expectedOperationPda(operationId));
+    if (!existing) {
+      await this.commitOperation(operationId);
+    }
+  }
+
+  async setOperationCommitter(committer: PublicKey) {
+    await this.program.methods
+      .setOperationCommitter(committer)
+      .accounts({ validator: this.validatorPda, authority: this.authority })
+      .rpc();
+  }
+
+  ethCheckpointPda(): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('eth_checkpoint'), this.validatorPda.toBuffer()],
+      this.program.programId
+    )[0];
+  }
+
+  /// Post an Ethereum checkpoint as the operation committer (the authority by
+  /// default); `ethEventTimestamp` 0 leaves the latency cross-check out
+  async submitEthCheckpoint(
+    count: number | anchor.BN,
+    rollingHash: Buffer,
+    ethBlock: number,
+    ethEventTimestamp = 0
+  ) {
+    await this.program.methods
+      .submitEthCheckpoint(
+        new anchor.BN(count),
+        Array.from(rollingHash),
+        new anchor.BN(ethBlock),
+        new anchor.BN(ethEventTimestamp)
+      )
+      .accounts({
+        validator: this.validatorPda,
+        ethCheckpoint: this.ethCheckpointPda(),
+        operationCommitter: this.authority,
+        payer: this.authority,
+      })
+      .rpc();
+  }
+
+  async reconcile() {
+    await this.program.methods
+      .reconcile()
+      .accounts({
+        validator: this.validatorPda,
+        ethCheckpoint: this.ethCheckpointPda(),
+        proofSequence: this.proofSequencePda(),
+      })
+      .rpc();
+  }
+
+  async acknowledgeReconciliation() {
+    await this.program.methods
+      .acknowledgeReconciliation()
+      .accounts({ validator: this.validatorPda, authority: this.authority })
+      .rpc();
+  }
+
+  /// Submit a proof referencing a recent block unless `block` overrides it.
+  /// Defaults to a 1,000-unit vault withdrawal without an HTLC preimage, signed
+  /// by the validator authority and bypassing the seen-operations filter, with
+  /// an Ethereum event two seconds old. The operation is committed first
+  /// unless `commit` is false.
+  async submitProof(
+    operationId: Buffer,
+    proofLen = 1,
+    block?: { slot: number; hash: Buffer },
+    options: SubmitOptions = {}
+  ) {
+    const { args, method } = await this.submitProofMethod(operationId, proofLen, block, options);
+    await method.rpc();
+    return args;
+  }
+
+  /// Submit one proof per entry of `proofLens` in a single transaction, e.g.
+  /// to exercise the per-transaction proof budget
+  async submitProofBatch(proofLens: number[]) {
+    const block = await this.recentSlotHash();
+    const tx = new Transaction();
+    for (const proofLen of proofLens) {
+      const { method } = await this.submitProofMethod(this.randomOperationId(), proofLen, block);
+      tx.add(await method.instruction());
+    }
+    try {
+      await this.provider.sendAndConfirm(tx);
+    } catch (err: any) {
+      // Surface program errors the way .rpc() does, for expectError
+      throw anchor.AnchorError.parse(err?.logs) ?? err;
+    }
+  }
+
+  private async submitProofMethod(
+    operationId: Buffer,
+    proofLen: number,
+    block: { slot: number; hash: Buffer } | undefined,
+    options: SubmitOptions = {}
+  ) {
+    const {
+      operationType = { vaultWithdrawal: {} },
+      amount = 1_000,
+      htlc,
+      seenFilter = false,
+      submitter,
+      commit = true,
+      ethEventTimestamp = this.ethEventTimestamp(),
+      siblingIsLeft,
+    } = options;
+    if (commit) {
+      await this.ensureCommitted(operationId);
+    }
+    const { slot, hash } = block ?? (await this.recentSlotHash());
+    const args = {
+      operationId: Array.from(operationId),
+      merkleProof: this.proofElements(proofLen).map((element) => Array.from(element)),
+      solanaBlockHash: Array.from(hash),
+      solanaTxSignature: Array.from(Buffer.alloc(64, 2)),
+      solanaBlockNumber: new anchor.BN(slot),
+      operationType,
+      amount: new anchor.BN(amount),
+      htlc: htlc ? { hashlock: Array.from(htlc.hashlock), preimage: Array.from(htlc.preimage) } : null,
+      ethEventTimestamp: new anchor.BN(ethEventTimestamp),
+      merkleMode: siblingIsLeft ? { positional: {} } : { sorted: {} },
+      siblingIsLeft: siblingIsLeft ?? [],
+    };
+    const method = this.program.methods
+      .submitConsensusProof(
+        args.operationId,
+        args.merkleProof,
+        args.solanaBlockHash,
+        args.solanaTxSignature,
+        args.solanaBlockNumber,
+        args.operationType,
+        args.amount,
+        args.htlc,
+        args.ethEventTimestamp,
+        args.merkleMode,
+        args.siblingIsLeft
+      )
+      .accounts({
+        validator: this.validatorPda,
+        seenOperations: seenFilter ? this.seenOperationsPda() : null,
+        expectedOperation: this.expectedOperationPda(operationId),
+        proofRecord: this.proofPda(operationId),
+        consensusState: this.consensusPda(operationId),
+        dailyStats: this.dailyStatsPda(),
+        operatorMetrics: this.operatorMetricsPda(submitter?.publicKey ?? this.authority),
+        proofSequence: this.proofSequencePda(),
+        slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+        instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        checkConfig: this.checkConfigPda(),
+        riskMetrics: this.riskMetricsPda(),
+        haltMonitor: this.haltMonitorPda(),
+        authority: submitter?.publicKey ?? this.authority,
+      })
+      .signers(submitter ? [submitter] : []);
+    return { args, method };
+  }
+
+  async setMaxProofBudget(maxProofBudget: number) {
+    await this.program.methods
+      .setMaxProofBudget(new anchor.BN(maxProofBudget))
+      .accounts({ validator: this.validatorPda, authority: this.authority })
+      .rpc();
+  }
+
+  /// Unix timestamp `ageSecs` ago, as an operator would report an Ethereum event
+  ethEventTimestamp(ageSecs = 2): number {
+    return Math.floor(Date.now() / 1000) - ageSecs;
+  }
+
+  /// Today's latency summary from get_latency_stats
+  async latencyStats(): Promise<any> {
+    return this.program.methods
+      .getLatencyStats()
+      .accounts({ dailyStats: this.dailyStatsPda() })
+      .view();
+  }
+
+  /// keccak256 of the Borsh-encoded submit args (the instruction data after
+  /// its discriminator), as the program stores it in `submission_hash`
+  submissionHash(args: object): Buffer {
+    const data = this.program.coder.instruction.encode('submitConsensusProof', args);
+    return Buffer.from(keccak256(data.subarray(8)).slice(2), 'hex');
+  }
+
+  operatorMetricsPda(operator: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('operator_metrics'), operator.toBuffer()],
+      this.program.programId
+    )[0];
+  }
+
+  proofSequencePda(): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('proof_sequence'), this.validatorPda.toBuffer()],
+      this.program.programId
+    )[0];
+  }
+
+  /// Fold the given operators' submission counters into the validator total
+  async rollupMetrics(operators: PublicKey[]) {
+    await this.program.methods
+      .rollupMetrics()
+      .accounts({ validator: this.validatorPda })
+      .remainingAccounts(
+        operators.map((operator) => ({ pubkey: this.operatorMetricsPda(operator), isSigner: false, isWritable: true }))
+      )
+      .rpc();
+  }
+
+  async heartbeat(operator?: Keypair) {
+    await this.program.methods
+      .heartbeat()
+      .accounts({
+        operatorMetrics: this.operatorMetricsPda(operator?.publicKey ?? this.authority),
+        haltMonitor: this.haltMonitorPda(),
+        operator: operator?.publicKey ?? this.authority,
+      })
+      .signers(operator ? [operator] : [])
+      .rpc();
+  }
+
+  /// Crank the given operators' scores
+  async updateScores(operators: PublicKey[]) {
+    await this.program.methods
+      .updateScores()
+      .accounts({ validator: this.validatorPda })
+      .remainingAccounts(
+        operators.map((operator) => ({ pubkey: this.operatorMetricsPda(operator), isSigner: false, isWritable: true }))
+      )
+      .rpc();
+  }
+
+  async operatorRanking(operators: PublicKey[]): Promise<any[]> {
+    return this.program.methods
+      .getOperatorRanking()
+      .accounts({ validator: this.validatorPda })
+      .remainingAccounts(
+        operators.map((operator) => ({ pubkey: this.operatorMetricsPda(operator), isSigner: false, isWritable: false }))
+      )
+      .view();
+  }
+
+  seenOperationsPda(): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('seen_operations'), this.validatorPda.toBuffer()],
+      this.program.programId
+    )[0];
+  }
+
+  /// Create the seen-operations replay filter once per local validator run
+  async ensureSeenOperations() {
+    const existing = await this.provider.connection.getAccountInfo(this.seenOperationsPda());
+    if (!existing) {
+      await this.program.methods
+        .initializeSeenOperations()
+        .accounts({
+          validator: this.validatorPda,
+          seenOperations: this.seenOperationsPda(),
+          authority: this.authority,
+        })
+        .rpc();
+    }
+  }
+
+  checkConfigPda(): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('check_config'), this.validatorPda.toBuffer()],
+      this.program.programId
+    )[0];
+  }
+
+  /// Create the shadow-mode check settings (all Monitor) once per local validator run
+  async ensureCheckConfig() {
+    const existing = await this.provider.connection.getAccountInfo(this.checkConfigPda());
+    if (!existing) {
+      await this.program.methods
+        .initializeCheckConfig()
+        .accounts({
+          validator: this.validatorPda,
+          checkConfig: this.checkConfigPda(),
+          authority: this.authority,
+        })
+        .rpc();
+    }
+  }
+
+  /// Queue an enforcement change, e.g. `({ blockFreshness: {} }, { enforce: {} })`
+  async queueEnforcement(check: object, enforcement: object) {
+    await this.program.methods
+      .queueEnforcement(check, enforcement)
+      .accounts({
+        validator: this.validatorPda,
+        checkConfig: this.checkConfigPda(),
+        authority: this.authority,
+      })
+      .rpc();
+  }
+
+  async applyEnforcement(check: object) {
+    await this.program.methods
+      .applyEnforcement(check)
+      .accounts({ checkConfig: this.checkConfigPda() })
+      .rpc();
+  }
+
+  /// Queue a per-type amount limit, e.g. `({ htlcSwap: {} }, 5_000)`
+  async queueAmountLimit(operationType: object, maxAmount: anchor.BN | number) {
+    await this.program.methods
+      .queueAmountLimit(operationType, new anchor.BN(maxAmount))
+      .accounts({
+        validator: this.validatorPda,
+        checkConfig: this.checkConfigPda(),
+        authority: this.authority,
+      })
+      .rpc();
+  }
+
+  async applyAmountLimit(operationType: object) {
+    await this.program.methods
+      .applyAmountLimit(operationType)
+      .accounts({ checkConfig: this.checkConfigPda() })
+      .rpc();
+  }
+
+  /// Set a per-type consensus window, e.g. `({ emergencyRecovery: {} }, 3 * 86_400)`
+  async setConsensusWindow(operationType: object, consensusWindowSecs: anchor.BN | number) {
+    await this.program.methods
+      .setConsensusWindow(operationType, new anchor.BN(consensusWindowSecs))
+      .accounts({
+        validator: this.validatorPda,
+        checkConfig: this.checkConfigPda(),
+        authority: this.authority,
+      })
+      .rpc();
+  }
+
+  async queueBridgeCap(maxBridgedPerUser: anchor.BN | number) {
+    await this.program.methods
+      .queueBridgeCap(new anchor.BN(maxBridgedPerUser))
+      .accounts({
+        validator: this.validatorPda,
+        checkConfig: this.checkConfigPda(),
+        authority: this.authority,
+      })
+      .rpc();
+  }
+
+  async applyBridgeCap() {
+    await this.program.methods
+      .applyBridgeCap()
+      .accounts({ checkConfig: this.checkConfigPda() })
+      .rpc();
+  }
+
+  /// Queue enabling an OperationType code, e.g. `4` for VaultRebalance
+  async queueOperationType(code: number) {
+    await this.program.methods
+      .queueOperationType(code)
+      .accounts({
+        validator: this.validatorPda,
+        checkConfig: this.checkConfigPda(),
+        authority: this.authority,
+      })
+      .rpc();
+  }
+
+  async applyOperationType() {
+    await this.program.methods
+      .applyOperationType()
+      .accounts({ checkConfig: this.checkConfigPda() })
+      .rpc();
+  }
+
+  bridgedTotalPda(user: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync([Buffer.from('bridged'), user.toBuffer()], this.program.programId)[0];
+  }
+
+  async setStoreTxSignatures(store: boolean) {
+    await this.program.methods
+      .setStoreTxSignatures(store)
+      .accounts({ validator: this.validatorPda, authority: this.authority })
+      .rpc();
+  }
+
+  /// Verify a vault operation, using the (program-owned) validator PDA as
+  /// both the vault and its owner. Cross-chain transfers pass `user`'s
+  /// bridged-total account.
+  /// Verify a vault operation; `vault` defaults to the validator PDA, which
+  /// stands in for a vault owned by some other program. Withdrawals from a
+  /// vesting schedule also pass its `emergencyProposal` PDA.
+  async verifyVaultOperation(
+    operationType: object,
+    amount: number,
+    user: PublicKey = this.authority,
+    vault: PublicKey = this.validatorPda,
+    emergencyProposal: PublicKey | null = null
+  ) {
+    const vaultId = new anchor.BN(randomBytes(8));
+    const [verification] = PublicKey.findProgramAddressSync(
+      [Buffer.from('verification'), vaultId.toArrayLike(Buffer, 'le', 8), vault.toBuffer()],
+      this.program.programId
+    );
+    await this.program.methods
+      .verifyVaultOperation(vaultId, vault, operationType, new anchor.BN(amount), user)
+      .accounts({
+        validator: this.validatorPda,
+        verification,
+        dailyStats: this.dailyStatsPda(),
+        vault,
+        checkConfig: this.checkConfigPda(),
+        bridgedTotal: 'crossChainTransfer' in operationType ? this.bridgedTotalPda(user) : null,
+        vestingLink: this.vestingLinkPda(),
+        emergencyProposal,
+        authority: this.authority,
+      })
+      .rpc();
+  }
+
+  vestingLinkPda(): PublicKey {
+    return PublicKey.findProgramAddressSync([Buffer.from('vesting_link')], this.program.programId)[0];
+  }
+
+  /// Cross-check withdrawals from `vestingProgram`'s schedules for pending
+  /// emergency recoveries (PublicKey.default: no cross-check)
+  async setVestingProgram(vestingProgram: PublicKey) {
+    await this.program.methods
+      .setVestingProgram(vestingProgram)
+      .accounts({ validator: this.validatorPda, vestingLink: this.vestingLinkPda(), authority: this.authority })
+      .rpc();
+  }
+
+  feeVaultPda(operator: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('fee_vault'), operator.toBuffer()],
+      this.program.programId
+    )[0];
+  }
+
+  async configureFeeVault(operator: PublicKey, dailyCap: number) {
+    await this.program.methods
+      .configureFeeVault(operator, dailyCap)
+      .accounts({
+        validator: this.validatorPda,
+        feeVault: this.feeVaultPda(operator),
+        authority: this.authority,
+      })
+      .rpc();
+  }
+
+  /// Fund an operator's fee vault from the provider wallet
+  async prefundFeeVault(operator: PublicKey, lamports: number) {
+    await this.program.methods
+      .operatorPrefund(new anchor.BN(lamports))
+      .accounts({ feeVault: this.feeVaultPda(operator), funder: this.authority })
+      .rpc();
+  }
+
+  /// submitProof with the ProofRecord rent paid by `operator`'s fee vault.
+  /// The provider wallet pays the transaction fee; the operator only signs.
+  async submitSponsoredProof(operationId: Buffer, operator: Keypair, proofLen = 1) {
+    await this.ensureCommitted(operationId);
+    const { slot, hash } = await this.recentSlotHash();
+    await this.program.methods
+      .submitWithSponsoredRent(
+        Array.from(operationId),
+        this.proofElements(proofLen).map((element) => Array.from(element)),
+        Array.from(hash),
+        Array.from(Buffer.alloc(64, 2)),
+        new anchor.BN(slot),
+        { vaultWithdrawal: {} },
+        new anchor.BN(1_000),
+        null,
+        new anchor.BN(this.ethEventTimestamp()),
+        { sorted: {} },
+        []
+      )
+      .accounts({
+        validator: this.validatorPda,
+        seenOperations: null,
+        expectedOperation: this.expectedOperationPda(operationId),
+        feeVault: this.feeVaultPda(operator.publicKey),
+        proofRecord: this.proofPda(operationId),
+        consensusState: this.consensusPda(operationId),
+        dailyStats: this.dailyStatsPda(),
+        operatorMetrics: this.operatorMetricsPda(operator.publicKey),
+        proofSequence: this.proofSequencePda(),
+        slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+        instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        checkConfig: this.checkConfigPda(),
+        riskMetrics: this.riskMetricsPda(),
+        haltMonitor: this.haltMonitorPda(),
+        operator: operator.publicKey,
+        feePayer: this.authority,
+      })
+      .signers([operator])
+      .rpc();
+  }
+
+  async operatorLiveness(operator: PublicKey): Promise<any> {
+    return this.program.methods
+      .getOperatorLiveness()
+      .accounts({ feeVault: this.feeVaultPda(operator) })
+      .view();
+  }
+
+  async confirmSubmission(operationId: Buffer, ethereumTxHash: Buffer) {
+    await this.program.methods
+      .confirmEthereumSubmission(Array.from(operationId), Array.from(ethereumTxHash))
+      .accounts({
+        validator: this.validatorPda,
+        proofRecord: this.proofPda(operationId),
+        dailyStats: this.dailyStatsPda(),
+        riskMetrics: this.riskMetricsPda(),
+        haltMonitor: this.haltMonitorPda(),
+        authority: this.authority,
+      })
+      .rpc();
+  }
+
+  /// Record the proof's confirmation on one destination chain (EVM chain
+  /// ID); `confirmer` defaults to the validator authority
+  async confirmOnChain(operationId: Buffer, chainId: number, txHash: Buffer, blockNumber: number, confirmer?: Keypair) {
+    await this.program.methods
+      .confirmSubmission(
+        Array.from(operationId),
+        new anchor.BN(chainId),
+        Array.from(txHash),
+        new anchor.BN(blockNumber)
+      )
+      .accounts({
+        validator: this.validatorPda,
+        proofRecord: this.proofPda(operationId),
+        dailyStats: this.dailyStatsPda(),
+        riskMetrics: this.riskMetricsPda(),
+        haltMonitor: this.haltMonitorPda(),
+        authority: confirmer?.publicKey ?? this.authority,
+      })
+      .signers(confirmer ? [confirmer] : [])
+      .rpc();
+  }
+
+  /// Finalize organic consensus; `chains` defaults to Arbitrum + Solana.
+  /// With `rewardAccounts` (one token account per contributor), the reward
+  /// pool pays each contributor its stake-weighted share.
+  async finalizeConsensus(operationId: Buffer, chains: number[] = [1, 2], rewardAccounts?: PublicKey[]) {
+    await this.program.methods
+      .finalizeConsensus(Array.from(operationId), Buffer.from(chains))
+      .accounts({
+        validator: this.validatorPda,
+        proofRecord: this.proofPda(operationId),
+        consensusState: this.consensusPda(operationId),
+        dailyStats: this.dailyStatsPda(),
+        riskMetrics: this.riskMetricsPda(),
+        stakeConfig: this.stakeConfigPda(),
+        authority: this.authority,
+        rewardPool: rewardAccounts ? this.rewardPoolPda() : null,
+        rewardVault: rewardAccounts ? this.rewardVaultPda() : null,
+        tokenProgram: rewardAccounts ? TOKEN_PROGRAM_ID : null,
+      })
+      .remainingAccounts(
+        (rewardAccounts ?? []).map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+      )
+      .rpc();
+  }
+
+  /// Freeze or release a single operation (validator authority)
+  async setOperationPause(operationId: Buffer, paused: boolean) {
+    await this.program.methods
+      .setOperationPause(Array.from(operationId), paused)
+      .accounts({
+        validator: this.validatorPda,
+        consensusState: this.consensusPda(operationId),
+        authority: this.authority,
+      })
+      .rpc();
+  }
+
+  rewardPoolPda(): PublicKey {
+    return PublicKey.findProgramAddressSync([Buffer.from('reward_pool')], this.program.programId)[0];
+  }
+
+  rewardVaultPda(): PublicKey {
+    return PublicKey.findProgramAddressSync([Buffer.from('reward_vault')], this.program.programId)[0];
+  }
+
+  async configureRewards(mint: PublicKey, rewardPerOperation: number) {
+    await this.program.methods
+      .configureRewards(new anchor.BN(rewardPerOperation))
+      .accounts({
+        validator: this.validatorPda,
+        rewardPool: this.rewardPoolPda(),
+        mint,
+        rewardVault: this.rewardVaultPda(),
+        authority: this.authority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  }
+
+  async configureRewardTreasury(treasury: PublicKey, reserve: number) {
+    await this.program.methods
+      .configureRewardTreasury(treasury, new anchor.BN(reserve))
+      .accounts({ validator: this.validatorPda, rewardPool: this.rewardPoolPda(), authority: this.authority })
+      .rpc();
+  }
+
+  /// Sweep the reward vault's surplus into `treasuryTokenAccount`
+  async sweepRewardSurplus(treasuryTokenAccount: PublicKey) {
+    await this.program.methods
+      .sweepRewardSurplus()
+      .accounts({
+        validator: this.validatorPda,
+        rewardPool: this.rewardPoolPda(),
+        rewardVault: this.rewardVaultPda(),
+        treasuryTokenAccount,
+        authority: this.authority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  }
+
+  stakeConfigPda(): PublicKey {
+    return PublicKey.findProgramAddressSync([Buffer.from('stake_config')], this.program.programId)[0];
+  }
+
+  stakeVaultPda(): PublicKey {
+    return PublicKey.findProgramAddressSync([Buffer.from('stake_vault')], this.program.programId)[0];
+  }
+
+  operatorStakePda(operator: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('operator_stake'), operator.toBuffer()],
+      this.program.programId
+    )[0];
+  }
+
+  /// Stake in `mint` (fixed once configured); 0 turns the stake requirement off
+  async configureStake(mint: PublicKey, consensusWeightThreshold: number) {
+    await this.program.methods
+      .configureStake(new anchor.BN(consensusWeightThreshold))
+      .accounts({
+        validator: this.validatorPda,
+        stakeConfig: this.stakeConfigPda(),
+        mint,
+        stakeVault: this.stakeVaultPda(),
+        authority: this.authority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+  }
+
+  async bondStake(operator: Keypair, tokenAccount: PublicKey, amount: number) {
+    await this.program.methods
+      .bondStake(new anchor.BN(amount))
+      .accounts({
+        stakeConfig: this.stakeConfigPda(),
+        stakeVault: this.stakeVaultPda(),
+        operatorStake: this.operatorStakePda(operator.publicKey),
+        operatorTokenAccount: tokenAccount,
+        operator: operator.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([operator])
+      .rpc();
+  }
+
+  async unbondStake(operator: Keypair, tokenAccount: PublicKey, amount: number) {
+    await this.program.methods
+      .unbondStake(new anchor.BN(amount))
+      .accounts({
+        stakeConfig: this.stakeConfigPda(),
+        stakeVault: this.stakeVaultPda(),
+        operatorStake: this.operatorStakePda(operator.publicKey),
+        operatorTokenAccount: tokenAccount,
+        operator: operator.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([operator])
+      .rpc();
+  }
+
+  /// Attest to an operation with `operator`'s bonded stake
+  async attestOperation(operationId: Buffer, operator: Keypair) {
+    await this.program.methods
+      .attestOperation(Array.from(operationId))
+      .accounts({
+        validator: this.validatorPda,
+        proofRecord: this.proofPda(operationId),
+        consensusState: this.consensusPda(operationId),
+        operatorStake: this.operatorStakePda(operator.publicKey),
+        operator: operator.publicKey,
+      })
+      .signers([operator])
+      .rpc();
+  }
+
+  riskMetricsPda(): PublicKey {
+    return PublicKey.findProgramAddressSync([Buffer.from('risk_metrics')], this.program.programId)[0];
+  }
+
+  /// Create the risk metrics (no thresholds) once per local validator run
+  async ensureRiskMetrics() {
+    const existing = await this.provider.connection.getAccountInfo(this.riskMetricsPda());
+    if (!existing) {
+      await this.program.methods
+        .initializeRiskMetrics()
+        .accounts({ validator: this.validatorPda, riskMetrics: this.riskMetricsPda(), authority: this.authority })
+        .rpc();
+    }
+  }
+
+  /// Queue risk thresholds, e.g. `{ maxOperationAmount, maxOperatorShareBps, maxConsensusSecs }` as BNs
+  async queueRiskThresholds(thresholds: object) {
+    await this.program.methods
+      .queueRiskThresholds(thresholds)
+      .accounts({ validator: this.validatorPda, riskMetrics: this.riskMetricsPda(), authority: this.authority })
+      .rpc();
+  }
+
+  async applyRiskThresholds() {
+    await this.program.methods
+      .applyRiskThresholds()
+      .accounts({ riskMetrics: this.riskMetricsPda() })
+      .rpc();
+  }
+
+  /// Clear a latched flag, e.g. `{ largeOperation: {} }`
+  async acknowledgeRiskFlag(flag: object) {
+    await this.program.methods
+      .acknowledgeRiskFlag(flag)
+      .accounts({ validator: this.validatorPda, riskMetrics: this.riskMetricsPda(), authority: this.authority })
+      .rpc();
+  }
+
+  async fetchRiskMetrics(): Promise<any> {
+    return this.program.account.riskMetrics.fetch(this.riskMetricsPda());
+  }
+
+  haltMonitorPda(): PublicKey {
+    return PublicKey.findProgramAddressSync([Buffer.from('halt_monitor')], this.program.programId)[0];
+  }
+
+  haltRecordPda(index: number): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('halt_record'), new anchor.BN(index).toArrayLike(Buffer, 'le', 8)],
+      this.program.programId
+    )[0];
+  }
+
+  /// Set the halt policy, e.g. `{ maxSlotGap, cooldownSecs, extraEthConfirmations }` as BNs
+  async configureHaltPolicy(policy: object) {
+    await this.program.methods
+      .configureHaltPolicy(policy)
+      .accounts({ validator: this.validatorPda, haltMonitor: this.haltMonitorPda(), authority: this.authority })
+      .rpc();
+  }
+
+  async resumeAfterHalt() {
+    const monitor = await this.fetchHaltMonitor();
+    await this.program.methods
+      .resumeAfterHalt()
+      .accounts({
+        validator: this.validatorPda,
+        haltMonitor: this.haltMonitorPda(),
+        haltRecord: this.haltRecordPda(monitor.halts.toNumber()),
+        authority: this.authority,
+      })
+      .rpc();
+  }
+
+  async fetchHaltMonitor(): Promise<any> {
+    return this.program.account.haltMonitor.fetch(this.haltMonitorPda());
+  }
+
+  proofReissuesPda(operationId: Buffer): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('proof_reissues'), operationId],
+      this.program.programId
+    )[0];
+  }
+
+  /// Rewrite an unconfirmed proof with a `proofLen`-level Merkle proof
+  /// against the newest recorded block (positional proofs pass their flags)
+  async reissueProof(operationId: Buffer, proofLen = 1, siblingIsLeft: boolean[] = []) {
+    const { slot, hash } = await this.recentSlotHash();
+    await this.program.methods
+      .reissueProof(
+        Array.from(operationId),
+        this.proofElements(proofLen).map((element) => Array.from(element)),
+        Array.from(hash),
+        new anchor.BN(slot),
+        siblingIsLeft
+      )
+      .accounts({
+        validator: this.validatorPda,
+        proofRecord: this.proofPda(operationId),
+        proofReissues: this.proofReissuesPda(operationId),
+        slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+        authority: this.authority,
+      })
+      .rpc();
+  }
+
+  /// Run assert_invariants over ProofRecord, ConsensusState and
+  /// OperatorMetrics accounts, in batches that each fit one transaction
+  async assertInvariants(accounts: PublicKey[], batch = 20) {
+    for (let i = 0; i === 0 || i < accounts.length; i += batch) {
+      await this.program.methods
+        .assertInvariants()
+        .accounts({ validator: this.validatorPda, proofSequence: this.proofSequencePda() })
+        .remainingAccounts(
+          accounts.slice(i, i + batch).map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+        )
+        .rpc();
+    }
+  }
+
+  /// Bring a program account written under an older layout to the current one
+  async migrateAccount(account: PublicKey) {
+    await this.program.methods
+      .migrateAccount()
+      .accounts({ account, payer: this.authority })
+      .rpc();
+  }
+
+  buildAttestationPda(): PublicKey {
+    return PublicKey.findProgramAddressSync([Buffer.from('build_attestation')], this.program.programId)[0];
+  }
+
+  /// The program's ProgramData account, whose slot is its last deployment
+  programDataPda(): PublicKey {
+    return PublicKey.findProgramAddressSync([this.program.programId.toBuffer()], BPF_LOADER_UPGRADEABLE_ID)[0];
+  }
+
+  async configureBuildAttestation(upgradeGuard: PublicKey, attestationWindowSlots: number) {
+    await this.program.methods
+      .configureBuildAttestation(upgradeGuard, new anchor.BN(attestationWindowSlots))
+      .accounts({
+        validator: this.validatorPda,
+        buildAttestation: this.buildAttestationPda(),
+        authority: this.authority,
+      })
+      .rpc();
+  }
+
+  /// Attest directly, with `attester` in the upgrade guard's PDA slot
+  async attestBuild(buildHash: Buffer, attester: Keypair) {
+    await this.program.methods
+      .attestBuild(Array.from(buildHash))
+      .accounts({
+        buildAttestation: this.buildAttestationPda(),
+        buildAttester: attester.publicKey,
+        programData: this.programDataPda(),
+      })
+      .signers([attester])
+      .rpc();
+  }
+
+  async checkBuildAttestation() {
+    await this.program.methods
+      .checkBuildAttestation()
+      .accounts({ buildAttestation: this.buildAttestationPda(), programData: this.programDataPda() })
+      .rpc();
+  }
+
+  async buildAttestation(): Promise<any> {
+    return this.program.methods
+      .getBuildAttestation()
+      .accounts({ buildAttestation: this.buildAttestationPda() })
+      .view();
+  }
+
+  async programInfo(): Promise<any> {
+    return this.program.methods.getProgramInfo().accounts({}).view();
+  }
+
+  /// Publish a finalized consensus as a public mirror; `payer` cranks it (the
+  /// provider wallet by default)
+  async createConsensusMirror(operationId: Buffer, payer?: Keypair) {
+    await this.program.methods
+      .createConsensusMirror(Array.from(operationId))
+      .accounts({
+        consensusState: this.consensusPda(operationId),
+        consensusMirror: this.consensusMirrorPda(operationId),
+        payer: payer?.publicKey ?? this.authority,
+      })
+      .signers(payer ? [payer] : [])
+      .rpc();
+  }
+
+  async closeConsensusMirror(operationId: Buffer, closer?: Keypair) {
+    await this.program.methods
+      .closeConsensusMirror(Array.from(operationId))
+      .accounts({
+        consensusMirror: this.consensusMirrorPda(operationId),
+        closer: closer?.publicKey ?? this.authority,
+      })
+      .signers(closer ? [closer] : [])
+      .rpc();
+  }
+
+  /// consensus_status return data: `{ pending: {...} }`, `{ reached: {...} }` or `{ conflict: {...} }`
+  async consensusStatus(operationId: Buffer): Promise<any> {
+    return this.program.methods
+      .consensusStatus(Array.from(operationId))
+      .accounts({
+        consensusState: this.consensusPda(operationId),
+        proofRecord: this.proofPda(operationId),
+        overrideProposal: this.overridePda(operationId),
+      })
+      .view();
+  }
+
+  async configureProofLifecycle(expirySecs: number, retentionSecs: number, bountyBps: number) {
+    await this.program.methods
+      .configureProofLifecycle(new anchor.BN(expirySecs), new anchor.BN(retentionSecs), bountyBps)
+      .accounts({ validator: this.validatorPda, authority: this.authority })
+      .rpc();
+  }
+
+  /// Funded keypairs for council members, operators, etc.
+  async fundedKeypairs(count: number, lamports = LAMPORTS_PER_SOL): Promise<Keypair[]> {
+    const keypairs = Array.from({ length: count }, () => Keypair.generate());
+    for (const keypair of keypairs) {
+      const sig = await this.provider.connection.requestAirdrop(keypair.publicKey, lamports);
+      await this.provider.connection.confirmTransaction(sig);
+    }
+    return keypairs;
+  }
+
+  async initializeDisputeCouncil(members: PublicKey[], threshold: number) {
+    await this.program.methods
+      .initializeDisputeCouncil(members, threshold)
+      .accounts({
+        validator: this.validatorPda,
+        council: this.councilPda(),
+        authority: this.authority,
+      })
+      .rpc();
+  }
+
+  /// `atFault` names the operator charged a lost dispute on execution
+  async proposeOverride(
+    operationId: Buffer,
+    justificationHash: Buffer,
+    proposer: Keypair,
+    coSigners: Keypair[],
+    atFault?: PublicKey
+  ) {
+    await this.program.methods
+      .manualOverride(Array.from(operationId), Array.from(justificationHash))
+      .accounts({
+        validator: this.validatorPda,
+        council: this.councilPda(),
+        proposal: this.overridePda(operationId),
+        atFault: atFault ? this.operatorMetricsPda(atFault) : null,
+        proposer: proposer.publicKey,
+      })
+      .remainingAccounts(
+        coSigners.map((signer) => ({ pubkey: signer.publicKey, isSigner: true, isWritable: false }))
+      )
+      .signers([proposer, ...coSigners])
+      .rpc();
+  }
+
+  /// Execute a proposed override once its delay has passed (permissionless)
+  async executeOverride(operationId: Buffer, proposer: PublicKey) {
+    await this.program.methods
+      .executeManualOverride(Array.from(operationId))
+      .accounts({
+        proposal: this.overridePda(operationId),
+        consensusState: this.consensusPda(operationId),
+        dailyStats: this.dailyStatsPda(),
+        atFault: null,
+        proposer,
+        payer: this.authority,
+      })
+      .rpc();
+  }
+
+  /// Force an operation to Failed with the council quorum (`forcer` + co-signers),
+  /// charging a divergence to `atFault` if given
+  async forceExpireOperation(operationId: Buffer, forcer: Keypair, coSigners: Keypair[], atFault?: PublicKey) {
+    await this.program.methods
+      .forceExpireOperation(Array.from(operationId))
+      .accounts({
+        validator: this.validatorPda,
+        council: this.councilPda(),
+        consensusState: this.consensusPda(operationId),
+        atFault: atFault ? this.operatorMetricsPda(atFault) : null,
+        forcedBy: forcer.publicKey,
+      })
+      .remainingAccounts(
+        coSigners.map((signer) => ({ pubkey: signer.publicKey, isSigner: true, isWritable: false }))
+      )
+      .signers([forcer, ...coSigners])
+      .rpc();
+  }
+
+  async proofEvidence(operationId: Buffer): Promise<any> {
+    return this.program.methods
+      .getProofEvidence(Array.from(operationId))
+      .accounts({ proofRecord: this.proofPda(operationId) })
+      .view();
+  }
+
+  async fetchDailyStats(day = this.currentDay()): Promise<any> {
+    return this.program.account.dailyStats.fetch(this.dailyStatsPda(day));
+  }
+
+  async fetchOperatorMetrics(operator: PublicKey = this.authority): Promise<any> {
+    return this.program.account.operatorMetrics.fetch(this.operatorMetricsPda(operator));
+  }
+
+  async fetchProofSequence(): Promise<any> {
+    return this.program.account.proofSequence.fetch(this.proofSequencePda());
+  }
+
+  async fetchValidator(): Promise<any> {
+    return this.program.account.trinityValidator.fetch(this.validatorPda);
+  }
+
+  async fetchConsensus(operationId: Buffer): Promise<any> {
+    return this.program.account.consensusState.fetch(this.consensusPda(operationId));
+  }
+
+  async fetchProof(operationId: Buffer): Promise<any> {
+    return this.program.account.proofRecord.fetch(this.proofPda(operationId));
+  }
+
+  /// Run `action` and collect every `eventName` event it emits
+  async captureEvents(eventName: string, action: () => Promise<void>): Promise<any[]> {
+    const events: any[] = [];
+    const listener = this.program.addEventListener(eventName, (event: any) => events.push(event));
+    try {
+      await action();
+      await sleep(1_000);
+    } finally {
+      await this.program.removeEventListener(listener);
+    }
+    return events;
+  }
+
+  /// Expect `action` to fail with the given Anchor error code
+  async expectError(action: () => Promise<void>, code: string) {
+    try {
+      await action();
+    } catch (err: any) {
+      if (err?.error?.errorCode?.code !== code) {
+        throw err;
+      }
+      return;
+    }
+    throw new Error(`expected ${code}`);
+  }
+
+  /// Expect `action` to fail with `code` and return the fields of its
+  /// `error_ctx: code=<n> error=<Name> key=value ...` log line
+  async expectErrorContext(action: () => Promise<void>, code: string): Promise<Record<string, string>> {
+    try {
+      await action();
+    } catch (err: any) {
+      if (err?.error?.errorCode?.code !== code) {
+        throw err;
+      }
+      const line = [...(err.logs ?? [])].reverse().find((log: string) => log.startsWith('Program log: error_ctx:'));
+      if (!line) {
+        throw new Error(`no error_ctx line for ${code}`);
+      }
+      const fields = Object.fromEntries(
+        line
+          .slice('Program log: error_ctx:'.length)
+          .trim()
+          .split(' ')
+          .map((pair: string) => pair.split('=', 2))
+      );
+      if (fields.error !== code || Number(fields.code) !== err.error.errorCode.number) {
+        throw new Error(`error_ctx line does not match ${code}: ${line}`);
+      }
+      return fields;
+    }
+    throw new Error(`expected ${code}`);
+  }
+}
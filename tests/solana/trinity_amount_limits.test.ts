@@ -0,0 +1,125 @@
+import { expect } from 'chai';
+import { describe, it, before, after } from 'mocha';
+import * as anchor from '@coral-xyz/anchor';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Per-type amount limits: every proof submission and vault verification is
+// checked against its OperationType's limit, and finalization re-checks the
+// limit snapshotted into the ProofRecord. Limit changes go through the same
+// timelocked queue as shadow-mode enforcement (2 seconds in `devtools` builds),
+// as does the cumulative per-user cap on cross-chain transfers.
+
+const DEVTOOLS_TIMELOCK_MS = 2_000;
+const NO_LIMIT = new anchor.BN('18446744073709551615');
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe('Trinity amount limits (Solana)', () => {
+  const harness = new TrinityTestHarness();
+  // Other suites submit vault withdrawals; limits here only touch transfers
+  const transfer = { crossChainTransfer: {} };
+
+  before(async () => {
+    await harness.ensureInitialized();
+    await harness.ensureCheckConfig();
+  });
+
+  it('should hold a limit change behind the timelock', async () => {
+    await harness.queueAmountLimit(transfer, 5_000);
+    await harness.expectError(() => harness.applyAmountLimit(transfer), 'EnforcementTimelockActive');
+
+    // Re-queueing replaces the pending change
+    await harness.queueAmountLimit(transfer, NO_LIMIT);
+  });
+
+  it('should snapshot the limit into the proof record', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId, 1, undefined, { operationType: transfer, amount: 7_500 });
+
+    const record = await harness.fetchProof(operationId);
+    expect(record.amount.toNumber()).to.equal(7_500);
+    expect(record.amountLimit.eq(NO_LIMIT)).to.equal(true);
+  });
+
+  (process.env.TRINITY_DEVTOOLS ? describe : describe.skip)('with a limit applied', () => {
+    const setLimit = async (maxAmount: anchor.BN | number) => {
+      await harness.queueAmountLimit(transfer, maxAmount);
+      await sleep(DEVTOOLS_TIMELOCK_MS + 1_000);
+      await harness.applyAmountLimit(transfer);
+    };
+
+    before(async () => {
+      await setLimit(5_000);
+    });
+
+    after(async () => {
+      await setLimit(NO_LIMIT);
+    });
+
+    it('should reject an oversized proof with the amounts in context', async () => {
+      const oversized = { operationType: transfer, amount: 5_001 };
+      const fields = await harness.expectErrorContext(
+        () => harness.submitProof(harness.randomOperationId(), 1, undefined, oversized),
+        'AmountExceedsTypeLimit'
+      );
+      expect(Number(fields.amount)).to.equal(5_001);
+      expect(Number(fields.max)).to.equal(5_000);
+
+      // Other types are unaffected
+      await harness.submitProof(harness.randomOperationId(), 1, undefined, { amount: 5_001 });
+    });
+
+    it('should reject an oversized vault verification', async () => {
+      await harness.expectError(() => harness.verifyVaultOperation(transfer, 5_001), 'AmountExceedsTypeLimit');
+      await harness.verifyVaultOperation(transfer, 5_000);
+    });
+
+    it('should finalize against the limit in force at first attestation', async () => {
+      const operationId = harness.randomOperationId();
+      await harness.submitProof(operationId, 1, undefined, { operationType: transfer, amount: 5_000 });
+
+      // Tightening the limit afterwards doesn't strand the pending operation
+      await setLimit(1_000);
+      await harness.confirmSubmission(operationId, Buffer.alloc(32, 0xab));
+      await harness.finalizeConsensus(operationId);
+      expect((await harness.fetchProof(operationId)).amountLimit.toNumber()).to.equal(5_000);
+      await setLimit(5_000);
+    });
+  });
+
+  (process.env.TRINITY_DEVTOOLS ? describe : describe.skip)('with a bridge cap applied', () => {
+    const setBridgeCap = async (maxBridgedPerUser: anchor.BN | number) => {
+      await harness.queueBridgeCap(maxBridgedPerUser);
+      await sleep(DEVTOOLS_TIMELOCK_MS + 1_000);
+      await harness.applyBridgeCap();
+    };
+
+    before(async () => {
+      await setBridgeCap(10_000);
+    });
+
+    after(async () => {
+      await setBridgeCap(NO_LIMIT);
+    });
+
+    it('should accumulate a user\'s transfers up to the cap and reject the next', async () => {
+      const user = anchor.web3.Keypair.generate().publicKey;
+      await harness.verifyVaultOperation(transfer, 4_000, user);
+      await harness.verifyVaultOperation(transfer, 6_000, user);
+
+      const ledger = await harness.program.account.bridgedTotal.fetch(harness.bridgedTotalPda(user));
+      expect(ledger.bridgedTotal.toNumber()).to.equal(10_000);
+
+      const fields = await harness.expectErrorContext(
+        () => harness.verifyVaultOperation(transfer, 1, user),
+        'BridgeCapExceeded'
+      );
+      expect(Number(fields.bridged_total)).to.equal(10_000);
+      expect(Number(fields.max)).to.equal(10_000);
+
+      // The cap is per user, and only transfers count against it
+      await harness.verifyVaultOperation(transfer, 1, anchor.web3.Keypair.generate().publicKey);
+      await harness.verifyVaultOperation({ vaultWithdrawal: {} }, 1, user);
+    });
+  });
+});
@@ -0,0 +1,53 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import { Keypair } from '@solana/web3.js';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Build attestation: the upgrade guard records the verified build hash of each
+// deployment, and a permissionless crank warns once about a deployment left
+// unattested past its window. No guard is deployed on localnet, so only its
+// refusals and the crank are exercised here.
+
+describe('Trinity build attestation (Solana)', () => {
+  const harness = new TrinityTestHarness();
+  const upgradeGuard = Keypair.generate().publicKey;
+
+  /// Slot of the program's current deployment (UpgradeableLoaderState::ProgramData)
+  const deploymentSlot = async () => {
+    const programData = await harness.provider.connection.getAccountInfo(harness.programDataPda());
+    return Number(programData!.data.readBigUInt64LE(4));
+  };
+
+  before(async () => {
+    await harness.ensureInitialized();
+    await harness.configureBuildAttestation(upgradeGuard, 0);
+  });
+
+  it('should report the embedded source commit', async () => {
+    const info = await harness.programInfo();
+    expect(info.version).to.not.equal('');
+    expect(info.gitCommit).to.not.equal('');
+  });
+
+  it('should refuse attestations not signed by the upgrade guard', async () => {
+    await harness.expectError(() => harness.attestBuild(Buffer.alloc(32, 0xb1), Keypair.generate()), 'ConstraintSeeds');
+    expect((await harness.buildAttestation()).attestations.toNumber()).to.equal(0);
+  });
+
+  it('should warn about an unattested deployment once', async () => {
+    const upgradeSlot = await deploymentSlot();
+    // An earlier run against the same deployment already reported it
+    const reported = (await harness.buildAttestation()).warnedUpgradeSlot.toNumber() === upgradeSlot;
+
+    const events = await harness.captureEvents('UnattestedBuild', () => harness.checkBuildAttestation());
+    expect(events).to.have.length(reported ? 0 : 1);
+    for (const event of events) {
+      expect(event.upgradeSlot.toNumber()).to.equal(upgradeSlot);
+      expect(event.attestedUpgradeSlot.toNumber()).to.equal(0);
+    }
+    expect((await harness.buildAttestation()).warnedUpgradeSlot.toNumber()).to.equal(upgradeSlot);
+
+    const repeated = await harness.captureEvents('UnattestedBuild', () => harness.checkBuildAttestation());
+    expect(repeated).to.have.length(0);
+  });
+});
@@ -0,0 +1,68 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Per-chain confirmations: a proof relayed to several destination chains is
+// confirmed on each independently, and every confirmation is kept on the
+// ProofRecord. The first one also confirms the proof itself.
+
+const ETHEREUM = 1;
+const ARBITRUM = 42_161;
+
+describe('Trinity per-chain confirmations (Solana)', () => {
+  const harness = new TrinityTestHarness();
+
+  before(async () => {
+    await harness.ensureInitialized();
+  });
+
+  it('should keep a confirmation for each destination chain', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+
+    await harness.confirmOnChain(operationId, ETHEREUM, Buffer.alloc(32, 0xe1), 19_000_000);
+    await harness.confirmOnChain(operationId, ARBITRUM, Buffer.alloc(32, 0xa1), 210_000_000);
+
+    const record = await harness.fetchProof(operationId);
+    const chains = record.confirmations.map((confirmation: any) => confirmation.chainId.toNumber());
+    expect(chains).to.deep.equal([ETHEREUM, ARBITRUM]);
+    expect(Buffer.from(record.confirmations[0].txHash)).to.deep.equal(Buffer.alloc(32, 0xe1));
+    expect(record.confirmations[0].blockNumber.toNumber()).to.equal(19_000_000);
+    expect(Buffer.from(record.confirmations[1].txHash)).to.deep.equal(Buffer.alloc(32, 0xa1));
+    expect(record.confirmations[1].blockNumber.toNumber()).to.equal(210_000_000);
+
+    // The first confirmation confirmed the proof
+    expect(record.submittedToEthereum).to.equal(true);
+    expect(Buffer.from(record.ethereumTxHash)).to.deep.equal(Buffer.alloc(32, 0xe1));
+  });
+
+  it('should confirm each chain only once', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+    await harness.confirmOnChain(operationId, ARBITRUM, Buffer.alloc(32, 0xa2), 210_000_001);
+
+    await harness.expectError(
+      () => harness.confirmOnChain(operationId, ARBITRUM, Buffer.alloc(32, 0xa3), 210_000_002),
+      'AlreadySubmitted'
+    );
+    await harness.expectError(
+      () => harness.confirmOnChain(operationId, 0, Buffer.alloc(32, 0xa3), 1),
+      'InvalidDestinationChain'
+    );
+    expect((await harness.fetchProof(operationId)).confirmations).to.have.length(1);
+  });
+
+  it('should refuse confirmations from anyone but the authority or committer', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+    const [stranger] = await harness.fundedKeypairs(1);
+
+    await harness.expectError(
+      () => harness.confirmOnChain(operationId, 31_337, Buffer.alloc(32, 0xf1), 1, stranger),
+      'UnauthorizedUser'
+    );
+    const record = await harness.fetchProof(operationId);
+    expect(record.confirmations).to.have.length(0);
+    expect(record.submittedToEthereum).to.equal(false);
+  });
+});
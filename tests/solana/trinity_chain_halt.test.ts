@@ -0,0 +1,93 @@
+import { expect } from 'chai';
+import { describe, it, before, after } from 'mocha';
+import * as anchor from '@coral-xyz/anchor';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Chain halts: heartbeats and submissions record their slot on the
+// HaltMonitor. A gap over max_slot_gap means Solana stalled, so submissions
+// fail with ResumeRequired until the authority resumes, which writes a
+// HaltRecord. Proofs generated in the cooldown after a resume need extra
+// Ethereum confirmations (one per 12s since the proof) before they can be
+// confirmed. Localnet slots are ~400ms, so a few seconds' wait is a halt.
+
+const HALT_SLOT_GAP = 5;
+const HALT_WAIT_MS = 3_500;
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe('Trinity chain halt (Solana)', () => {
+  const harness = new TrinityTestHarness();
+
+  const configure = (maxSlotGap: number, cooldownSecs = 0, extraEthConfirmations = 0) =>
+    harness.configureHaltPolicy({
+      maxSlotGap: new anchor.BN(maxSlotGap),
+      cooldownSecs: new anchor.BN(cooldownSecs),
+      extraEthConfirmations: new anchor.BN(extraEthConfirmations),
+    });
+
+  /// Let more than HALT_SLOT_GAP slots pass without a heartbeat
+  const stall = async () => {
+    await harness.heartbeat();
+    await sleep(HALT_WAIT_MS);
+  };
+
+  before(async () => {
+    await harness.ensureInitialized();
+    await configure(0);
+    // An earlier run may have stopped with a halt latched
+    if ((await harness.fetchHaltMonitor()).haltedSince !== null) {
+      await harness.resumeAfterHalt();
+    }
+  });
+
+  after(async () => {
+    await configure(0);
+  });
+
+  it('should refuse to resume without a halt', async () => {
+    await configure(1_000_000);
+    await harness.expectError(() => harness.resumeAfterHalt(), 'NoHaltDetected');
+  });
+
+  it('should refuse submissions after a slot gap until resumed', async () => {
+    await configure(HALT_SLOT_GAP);
+    await stall();
+
+    await harness.expectError(() => harness.submitProof(harness.randomOperationId()), 'ResumeRequired');
+    const events = await harness.captureEvents('ChainHaltDetected', () => harness.heartbeat());
+    expect(events).to.have.length(1);
+    const haltStartSlot = events[0].haltStartSlot.toNumber();
+    expect(events[0].detectedSlot.toNumber() - haltStartSlot).to.be.greaterThan(HALT_SLOT_GAP);
+    // Heartbeats don't clear it
+    await harness.expectError(() => harness.submitProof(harness.randomOperationId()), 'ResumeRequired');
+
+    const index = (await harness.fetchHaltMonitor()).halts.toNumber();
+    await harness.resumeAfterHalt();
+    const record = await harness.program.account.haltRecord.fetch(harness.haltRecordPda(index));
+    expect(record.startSlot.toNumber()).to.equal(haltStartSlot);
+    expect(record.endSlot.toNumber()).to.be.greaterThan(events[0].detectedSlot.toNumber() - 1);
+    expect(record.resumedBy.toBase58()).to.equal(harness.authority.toBase58());
+
+    await harness.submitProof(harness.randomOperationId());
+  });
+
+  it('should hold back confirmations of proofs generated in the cooldown', async () => {
+    await configure(HALT_SLOT_GAP, 3, 1);
+    await stall();
+    await harness.resumeAfterHalt();
+    // The cooldown was fixed at resume; stop detecting halts while waiting it out
+    await configure(0);
+
+    const postHalt = harness.randomOperationId();
+    await harness.submitProof(postHalt);
+    await harness.expectError(
+      () => harness.confirmSubmission(postHalt, Buffer.alloc(32, 7)),
+      'HaltConfirmationsPending'
+    );
+
+    await sleep(3_500);
+    const afterCooldown = harness.randomOperationId();
+    await harness.submitProof(afterCooldown);
+    await harness.confirmSubmission(afterCooldown, Buffer.alloc(32, 7));
+  });
+});
@@ -0,0 +1,152 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// End-to-end Solana leg of a Trinity consensus round:
+// proof submission -> evidence snapshot -> Ethereum confirmation -> finalization.
+//
+// Operator registration, bonds, divergent attestations and dispute slashing
+// are not part of the Solana program yet; when they land, their stages
+// belong in this flow and their setup in TrinityTestHarness.
+
+describe('Trinity consensus flow (Solana)', () => {
+  const harness = new TrinityTestHarness();
+  const operationId = harness.randomOperationId();
+  const proofLen = 4;
+
+  before(async () => {
+    await harness.ensureInitialized();
+  });
+
+  it('should record a proof whose root matches the off-chain computation', async () => {
+    const before = await harness.fetchValidator();
+
+    const events = await harness.captureEvents('ProofGenerated', () =>
+      harness.submitProof(operationId, proofLen)
+    );
+
+    // Submissions count against the operator; the validator total catches up on rollup
+    const metrics = await harness.fetchOperatorMetrics();
+    const pending = metrics.proofsSubmitted.sub(metrics.rolledUp).toNumber();
+    expect(pending).to.be.at.least(1);
+    await harness.rollupMetrics([harness.authority]);
+    const after = await harness.fetchValidator();
+    expect(after.totalProofsSubmitted.toNumber()).to.equal(before.totalProofsSubmitted.toNumber() + pending);
+    expect((await harness.fetchOperatorMetrics()).rolledUp.eq(metrics.proofsSubmitted)).to.equal(true);
+
+    const expectedRoot = harness.expectedMerkleRoot(harness.proofElements(proofLen), operationId);
+    const record = await harness.fetchProof(operationId);
+    expect(Buffer.from(record.merkleRoot)).to.deep.equal(expectedRoot);
+    expect(record.merkleProof).to.have.length(proofLen);
+    expect(record.validator.toBase58()).to.equal(harness.validatorPda.toBase58());
+    expect(record.submittedToEthereum).to.equal(false);
+    expect(record.expired).to.equal(false);
+
+    expect(events).to.have.length(1);
+    expect(Buffer.from(events[0].operationId)).to.deep.equal(operationId);
+    expect(Buffer.from(events[0].merkleRoot)).to.deep.equal(expectedRoot);
+
+    const expectedProofId = harness.expectedProofId(operationId, expectedRoot);
+    expect(Buffer.from(record.proofId)).to.deep.equal(expectedProofId);
+    expect(Buffer.from(events[0].proofId)).to.deep.equal(expectedProofId);
+  });
+
+  it('should derive proof IDs deterministically from their inputs', async () => {
+    // Same logical proof -> same ID, computed independently of any on-chain state
+    const root = harness.expectedMerkleRoot(harness.proofElements(proofLen), operationId);
+    expect(harness.expectedProofId(operationId, root)).to.deep.equal(harness.expectedProofId(operationId, root));
+
+    // A different operation (same path) yields a different root and ID
+    const otherOp = harness.randomOperationId();
+    await harness.submitProof(otherOp, proofLen);
+    const other = await harness.fetchProof(otherOp);
+    const original = await harness.fetchProof(operationId);
+    expect(Buffer.from(other.proofId)).to.not.deep.equal(Buffer.from(original.proofId));
+    expect(Buffer.from(other.proofId)).to.deep.equal(
+      harness.expectedProofId(otherOp, Buffer.from(other.merkleRoot))
+    );
+
+    // Same operation with a different root yields a different ID
+    const otherRoot = harness.expectedMerkleRoot(harness.proofElements(proofLen + 1), operationId);
+    expect(harness.expectedProofId(operationId, otherRoot)).to.not.deep.equal(Buffer.from(original.proofId));
+  });
+
+  it('should not let a second attestation overwrite recorded evidence', async () => {
+    const snapshot = await harness.fetchProof(operationId);
+
+    let rejected = false;
+    try {
+      await harness.submitProof(operationId, proofLen + 1);
+    } catch {
+      rejected = true;
+    }
+    expect(rejected).to.equal(true);
+
+    const record = await harness.fetchProof(operationId);
+    expect(Buffer.from(record.merkleRoot)).to.deep.equal(Buffer.from(snapshot.merkleRoot));
+    expect(record.timestamp.toNumber()).to.equal(snapshot.timestamp.toNumber());
+  });
+
+  it('should confirm the Ethereum submission exactly once', async () => {
+    const ethereumTxHash = Buffer.alloc(32, 0xee);
+    await harness.confirmSubmission(operationId, ethereumTxHash);
+
+    const record = await harness.fetchProof(operationId);
+    expect(record.submittedToEthereum).to.equal(true);
+    expect(Buffer.from(record.ethereumTxHash)).to.deep.equal(ethereumTxHash);
+
+    await harness.expectError(
+      () => harness.confirmSubmission(operationId, Buffer.alloc(32, 0xff)),
+      'AlreadySubmitted'
+    );
+  });
+
+  it('should only finalize with a 2-of-3 quorum that includes Solana', async () => {
+    await harness.expectError(() => harness.finalizeConsensus(operationId, [2]), 'InvalidConsensusChains');
+    await harness.expectError(() => harness.finalizeConsensus(operationId, [1, 3]), 'InvalidConsensusChains');
+    await harness.expectError(() => harness.finalizeConsensus(operationId, [2, 2]), 'InvalidConsensusChains');
+
+    const unconfirmed = harness.randomOperationId();
+    await harness.submitProof(unconfirmed, proofLen);
+    await harness.expectError(() => harness.finalizeConsensus(unconfirmed), 'ProofNotConfirmed');
+  });
+
+  it('should emit exactly one ConsensusFinalized per operation', async () => {
+    const events = await harness.captureEvents('ConsensusFinalized', () =>
+      harness.finalizeConsensus(operationId, [1, 2, 3])
+    );
+    const record = await harness.fetchProof(operationId);
+
+    expect(events).to.have.length(1);
+    expect(Buffer.from(events[0].operationId)).to.deep.equal(operationId);
+    expect(Buffer.from(events[0].merkleRoot)).to.deep.equal(Buffer.from(record.merkleRoot));
+    expect(Array.from(events[0].chains)).to.deep.equal([1, 2, 3]);
+
+    const consensus = await harness.fetchConsensus(operationId);
+    expect(consensus.finalized).to.equal(true);
+    expect(consensus.overridden).to.equal(false);
+    expect(consensus.finalizedAt.toNumber()).to.equal(events[0].finalizedAt.toNumber());
+
+    const replays = await harness.captureEvents('ConsensusFinalized', () =>
+      harness.expectError(() => harness.finalizeConsensus(operationId), 'ConsensusAlreadyFinalized')
+    );
+    expect(replays).to.have.length(0);
+  });
+
+  it('should report pending confirmations, then reached, to pollers', async () => {
+    const polled = harness.randomOperationId();
+    expect(await harness.consensusStatus(polled)).to.deep.equal({ pending: { confirmations: 0, required: 2 } });
+
+    await harness.submitProof(polled, proofLen);
+    expect(await harness.consensusStatus(polled)).to.deep.equal({ pending: { confirmations: 1, required: 2 } });
+
+    await harness.confirmSubmission(polled, Buffer.alloc(32, 0xee));
+    expect(await harness.consensusStatus(polled)).to.deep.equal({ pending: { confirmations: 2, required: 2 } });
+
+    await harness.finalizeConsensus(polled);
+    const status = await harness.consensusStatus(polled);
+    const consensus = await harness.fetchConsensus(polled);
+    expect(status.reached.chains).to.equal(2);
+    expect(status.reached.finalizedAt.toNumber()).to.equal(consensus.finalizedAt.toNumber());
+  });
+});
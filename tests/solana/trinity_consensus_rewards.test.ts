@@ -0,0 +1,162 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import * as anchor from '@coral-xyz/anchor';
+import { Keypair, PublicKey } from '@solana/web3.js';
+import { createAccount, createMint, getAccount, mintTo } from '@solana/spl-token';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Consensus rewards: finalize_consensus splits reward_per_operation among the
+// operators that attested to the operation, pro rata to their attested stake,
+// paying one token account of each contributor. The rounding remainder stays
+// in the reward vault for later operations, and the authority may sweep
+// whatever exceeds the configured reserve to the treasury.
+
+const REWARD_PER_OPERATION = 100;
+const STAKES = [200, 300, 500];
+
+describe('Trinity consensus rewards (Solana)', () => {
+  const harness = new TrinityTestHarness();
+  const payer = (harness.provider.wallet as anchor.Wallet).payer;
+  let mint: PublicKey;
+  let operators: Keypair[];
+  let rewardAccounts: PublicKey[];
+
+  const balance = async (tokenAccount: PublicKey) =>
+    Number((await getAccount(harness.provider.connection, tokenAccount)).amount);
+
+  /// A confirmed operation that `attesters` (default: every operator) attested to
+  const attestedOperation = async (attesters: Keypair[] = operators) => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId, 1);
+    await harness.confirmSubmission(operationId, Buffer.alloc(32, 0x52));
+    for (const operator of attesters) {
+      await harness.attestOperation(operationId, operator);
+    }
+    return operationId;
+  };
+
+  before(async () => {
+    await harness.ensureInitialized();
+    const connection = harness.provider.connection;
+    // The pool's mint is fixed once configured, e.g. by an earlier run
+    const pool = await harness.program.account.rewardPool.fetchNullable(harness.rewardPoolPda());
+    mint = pool?.mint ?? (await createMint(connection, payer, payer.publicKey, null, 0));
+    await harness.configureRewards(mint, REWARD_PER_OPERATION);
+    await mintTo(connection, payer, mint, harness.rewardVaultPda(), payer, 1_000);
+
+    // Stake only to attest: no consensus weight threshold
+    const config = await harness.program.account.stakeConfig.fetchNullable(harness.stakeConfigPda());
+    const stakeMint = config?.mint ?? (await createMint(connection, payer, payer.publicKey, null, 0));
+    await harness.configureStake(stakeMint, 0);
+
+    operators = await harness.fundedKeypairs(STAKES.length);
+    rewardAccounts = [];
+    for (const [i, operator] of operators.entries()) {
+      const stakeAccount = await createAccount(connection, payer, stakeMint, operator.publicKey, Keypair.generate());
+      await mintTo(connection, payer, stakeMint, stakeAccount, payer, STAKES[i]);
+      await harness.bondStake(operator, stakeAccount, STAKES[i]);
+      rewardAccounts.push(await createAccount(connection, payer, mint, operator.publicKey, Keypair.generate()));
+    }
+  });
+
+  it('should split the reward among contributors by attested stake', async () => {
+    const operationId = await attestedOperation();
+    const vaultBefore = await balance(harness.rewardVaultPda());
+    const before = await Promise.all(rewardAccounts.map(balance));
+
+    // Any order: each account pays the contributor that owns it
+    const [event] = await harness.captureEvents('RewardsDistributed', () =>
+      harness.finalizeConsensus(operationId, [1, 2, 3], [...rewardAccounts].reverse())
+    );
+
+    const after = await Promise.all(rewardAccounts.map(balance));
+    expect(after.map((amount, i) => amount - before[i])).to.deep.equal([20, 30, 50]);
+    expect(await balance(harness.rewardVaultPda())).to.equal(vaultBefore - 100);
+    expect(event.recipients).to.equal(3);
+    expect(event.paid.toNumber()).to.equal(100);
+    expect(event.dust.toNumber()).to.equal(0);
+  });
+
+  it('should keep the rounding dust in the vault', async () => {
+    // Stakes of 300 and 500: 100 * 300/800 and 100 * 500/800 both round down
+    const operationId = await attestedOperation([operators[1], operators[2]]);
+    const vaultBefore = await balance(harness.rewardVaultPda());
+    const [event] = await harness.captureEvents('RewardsDistributed', () =>
+      harness.finalizeConsensus(operationId, [1, 2], [rewardAccounts[1], rewardAccounts[2]])
+    );
+    // 37 + 62 of 100
+    expect(event.paid.toNumber()).to.equal(99);
+    expect(event.dust.toNumber()).to.equal(1);
+    expect(await balance(harness.rewardVaultPda())).to.equal(vaultBefore - 99);
+  });
+
+  it('should finalize without rewards when the pool is not passed', async () => {
+    const operationId = await attestedOperation();
+    const vaultBefore = await balance(harness.rewardVaultPda());
+    await harness.finalizeConsensus(operationId);
+    expect((await harness.fetchConsensus(operationId)).finalized).to.equal(true);
+    expect(await balance(harness.rewardVaultPda())).to.equal(vaultBefore);
+  });
+
+  it('should only pay each contributor, exactly once', async () => {
+    const operationId = await attestedOperation([operators[0], operators[1]]);
+    const [outsider] = await harness.fundedKeypairs(1);
+    const outsiderAccount = await createAccount(
+      harness.provider.connection,
+      payer,
+      mint,
+      outsider.publicKey,
+      Keypair.generate()
+    );
+
+    const refused = [
+      [],
+      // A contributor left out
+      [rewardAccounts[0]],
+      // A contributor paid twice instead of the other
+      [rewardAccounts[0], rewardAccounts[0]],
+      // An operator that didn't attest to this operation
+      [rewardAccounts[0], rewardAccounts[2]],
+      [rewardAccounts[0], outsiderAccount],
+      // Extra payees on top of the contributors
+      [rewardAccounts[0], rewardAccounts[1], outsiderAccount],
+    ];
+    for (const accounts of refused) {
+      await harness.expectError(
+        () => harness.finalizeConsensus(operationId, [1, 2], accounts),
+        'InvalidRewardRecipients'
+      );
+    }
+    await harness.finalizeConsensus(operationId, [1, 2], [rewardAccounts[0], rewardAccounts[1]]);
+  });
+
+  it('should refuse to reward an operation nobody attested to', async () => {
+    const operationId = await attestedOperation([]);
+    await harness.expectError(() => harness.finalizeConsensus(operationId, [1, 2], []), 'InvalidRewardRecipients');
+    await harness.finalizeConsensus(operationId);
+  });
+
+  it('should sweep the surplus above the reserve to the treasury', async () => {
+    const connection = harness.provider.connection;
+    const treasury = Keypair.generate().publicKey;
+    const treasuryAccount = await createAccount(connection, payer, mint, treasury, Keypair.generate());
+    await mintTo(connection, payer, mint, harness.rewardVaultPda(), payer, 500);
+    await harness.finalizeConsensus(await attestedOperation(), [1, 2], rewardAccounts);
+    const vaultBefore = await balance(harness.rewardVaultPda());
+
+    await harness.configureRewardTreasury(treasury, 300);
+    // Only a token account of the treasury can receive the surplus
+    await harness.expectError(() => harness.sweepRewardSurplus(rewardAccounts[0]), 'InvalidRewardTreasury');
+
+    const [event] = await harness.captureEvents('RewardSurplusSwept', () =>
+      harness.sweepRewardSurplus(treasuryAccount)
+    );
+    expect(await balance(treasuryAccount)).to.equal(vaultBefore - 300);
+    expect(await balance(harness.rewardVaultPda())).to.equal(300);
+    expect(event.amount.toNumber()).to.equal(vaultBefore - 300);
+    expect(event.remaining.toNumber()).to.equal(300);
+
+    // The reserve itself is never swept
+    await harness.expectError(() => harness.sweepRewardSurplus(treasuryAccount), 'NoRewardSurplus');
+  });
+});
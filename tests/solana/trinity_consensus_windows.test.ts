@@ -0,0 +1,48 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Consensus windows: each OperationType can get its own time to reach
+// consensus, fixed into the ProofRecord as its deadline when the proof is
+// submitted. Types without a window keep the validator's proof expiry.
+
+const DAY = 86_400;
+
+describe('Trinity consensus windows (Solana)', () => {
+  const harness = new TrinityTestHarness();
+  // Other suites submit vault withdrawals; windows here only touch recoveries and transfers
+  const recovery = { emergencyRecovery: {} };
+  const transfer = { crossChainTransfer: {} };
+
+  before(async () => {
+    await harness.ensureInitialized();
+    await harness.ensureCheckConfig();
+  });
+
+  it('should reject a zero window', async () => {
+    await harness.expectError(() => harness.setConsensusWindow(transfer, 0), 'InvalidConsensusWindow');
+  });
+
+  it('should give an emergency recovery a longer deadline than a transfer', async () => {
+    await harness.setConsensusWindow(recovery, 3 * DAY);
+    await harness.setConsensusWindow(transfer, 600);
+
+    const recoveryOp = harness.randomOperationId();
+    const transferOp = harness.randomOperationId();
+    await harness.submitProof(recoveryOp, 1, undefined, { operationType: recovery });
+    await harness.submitProof(transferOp, 1, undefined, { operationType: transfer });
+
+    const window = async (operationId: Buffer) => {
+      const record = await harness.fetchProof(operationId);
+      return record.consensusDeadline.toNumber() - record.timestamp.toNumber();
+    };
+    expect(await window(recoveryOp)).to.equal(3 * DAY);
+    expect(await window(transferOp)).to.equal(600);
+  });
+
+  it('should leave types without a window on the validator expiry', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+    expect((await harness.fetchProof(operationId)).consensusDeadline.toNumber()).to.equal(0);
+  });
+});
@@ -0,0 +1,62 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import * as anchor from '@coral-xyz/anchor';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// DailyStats shards: lazily created per day, updated by the instructions
+// they count, readable via get_daily_stats and closable after retention.
+
+describe('Trinity daily stats (Solana)', () => {
+  const harness = new TrinityTestHarness();
+
+  before(async () => {
+    await harness.ensureInitialized();
+  });
+
+  it('should count submissions and confirmations in today\'s shard', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+
+    const afterSubmit = await harness.fetchDailyStats();
+    expect(afterSubmit.day.toNumber()).to.equal(harness.currentDay());
+
+    await harness.confirmSubmission(operationId, Buffer.alloc(32, 0xcc));
+    const afterConfirm = await harness.fetchDailyStats();
+
+    expect(afterConfirm.proofsSubmitted.toNumber()).to.equal(afterSubmit.proofsSubmitted.toNumber());
+    expect(afterConfirm.proofsConfirmed.toNumber()).to.equal(afterSubmit.proofsConfirmed.toNumber() + 1);
+
+    await harness.submitProof(harness.randomOperationId());
+    const afterSecond = await harness.fetchDailyStats();
+    expect(afterSecond.proofsSubmitted.toNumber()).to.equal(afterSubmit.proofsSubmitted.toNumber() + 1);
+  });
+
+  it('should return today\'s counters from get_daily_stats', async () => {
+    const viewed = await harness.program.methods
+      .getDailyStats()
+      .accounts({ dailyStats: harness.dailyStatsPda() })
+      .view();
+    const stored = await harness.fetchDailyStats();
+
+    expect(viewed.day.toNumber()).to.equal(stored.day.toNumber());
+    expect(viewed.proofsSubmitted.toNumber()).to.equal(stored.proofsSubmitted.toNumber());
+    expect(viewed.proofsConfirmed.toNumber()).to.equal(stored.proofsConfirmed.toNumber());
+  });
+
+  it('should refuse to close a shard within retention', async () => {
+    const day = harness.currentDay();
+    await harness.expectError(
+      () =>
+        harness.program.methods
+          .closeDailyStats(new anchor.BN(day))
+          .accounts({
+            validator: harness.validatorPda,
+            dailyStats: harness.dailyStatsPda(day),
+            authority: harness.authority,
+          })
+          .rpc(),
+      'StatsRetentionActive'
+    );
+    expect(await harness.provider.connection.getAccountInfo(harness.dailyStatsPda(day))).to.not.equal(null);
+  });
+});
@@ -0,0 +1,57 @@
+import { expect } from 'chai';
+import { describe, it } from 'mocha';
+import { Keypair } from '@solana/web3.js';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Localnet bootstrap. Needs a fresh validator running a `devtools` build
+// (`anchor build -- --features devtools`), so it only runs with
+// TRINITY_DEVTOOLS=1; default builds reject the instruction outright.
+
+const DEVTOOLS_COUNCIL_SEEDS = [0xd1, 0xd2, 0xd3].map((byte) => new Uint8Array(32).fill(byte));
+const DEVTOOLS_ARBITRUM_RPC_URL = 'http://127.0.0.1:8547';
+
+(process.env.TRINITY_DEVTOOLS ? describe : describe.skip)('Trinity devtools localnet bootstrap (Solana)', () => {
+  const harness = new TrinityTestHarness();
+  const council = DEVTOOLS_COUNCIL_SEEDS.map((seed) => Keypair.fromSeed(seed));
+
+  it('should set up validator, monitoring and council in one call', async () => {
+    await harness.bootstrapLocalnet();
+
+    const validator = await harness.fetchValidator();
+    expect(validator.isActive).to.equal(true);
+    expect(validator.authority.toBase58()).to.equal(harness.authority.toBase58());
+    expect(validator.arbitrumRpcUrl).to.equal(DEVTOOLS_ARBITRUM_RPC_URL);
+
+    const monitor = await harness.program.account.monitorConfig.fetch(harness.monitorConfigPda());
+    expect(monitor.isActive).to.equal(true);
+
+    const stored = await harness.program.account.disputeCouncil.fetch(harness.councilPda());
+    expect(stored.threshold).to.equal(2);
+    expect(stored.members.map((member: any) => member.toBase58())).to.deep.equal(
+      council.map((member) => member.publicKey.toBase58())
+    );
+  });
+
+  it('should let the deterministic council keys sign immediately', async () => {
+    const [proposer, coSigner] = council;
+    for (const member of [proposer, coSigner]) {
+      const sig = await harness.provider.connection.requestAirdrop(member.publicKey, 1_000_000_000);
+      await harness.provider.connection.confirmTransaction(sig);
+    }
+
+    const operationId = harness.randomOperationId();
+    await harness.proposeOverride(operationId, Buffer.alloc(32, 0x4a), proposer, [coSigner]);
+    const proposal = await harness.program.account.overrideProposal.fetch(harness.overridePda(operationId));
+    expect(proposal.approvals).to.equal(2);
+  });
+
+  it('should refuse to bootstrap twice', async () => {
+    let rejected = false;
+    try {
+      await harness.bootstrapLocalnet();
+    } catch {
+      rejected = true;
+    }
+    expect(rejected).to.equal(true);
+  });
+});
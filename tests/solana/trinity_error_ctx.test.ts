@@ -0,0 +1,108 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import * as anchor from '@coral-xyz/anchor';
+import { Keypair } from '@solana/web3.js';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Structured `error_ctx` log lines emitted by require_ctx! before a failure.
+// Council paths share the singleton council with the manual override suite and
+// are covered there by error code; the line format is the same everywhere.
+
+const MAX_RPC_URL_LEN = 200;
+const MAX_MERKLE_PROOF_LEN = 10;
+const MAX_SWEEP_BATCH = 20;
+const MAX_SWEEP_BOUNTY_BPS = 2_000;
+const STATS_RETENTION_DAYS = 90;
+
+describe('Trinity structured error context (Solana)', () => {
+  const harness = new TrinityTestHarness();
+  const { program, validatorPda, authority } = harness;
+
+  before(async () => {
+    await harness.ensureInitialized();
+  });
+
+  it('should log the URL length against the cap', async () => {
+    const url = 'https://' + 'a'.repeat(MAX_RPC_URL_LEN);
+    const fields = await harness.expectErrorContext(
+      () => program.methods.setRpcUrl(url).accounts({ validator: validatorPda, authority }).rpc(),
+      'RpcUrlTooLong'
+    );
+    expect(Number(fields.len)).to.equal(url.length);
+    expect(Number(fields.max)).to.equal(MAX_RPC_URL_LEN);
+  });
+
+  it('should log the Merkle proof length against the cap', async () => {
+    const fields = await harness.expectErrorContext(
+      () => harness.submitProof(harness.randomOperationId(), MAX_MERKLE_PROOF_LEN + 1),
+      'MerkleProofTooLong'
+    );
+    expect(Number(fields.len)).to.equal(MAX_MERKLE_PROOF_LEN + 1);
+    expect(Number(fields.max)).to.equal(MAX_MERKLE_PROOF_LEN);
+  });
+
+  it('should log the proof account on a repeated confirmation', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+    await harness.confirmSubmission(operationId, Buffer.alloc(32, 3));
+
+    const fields = await harness.expectErrorContext(
+      () => harness.confirmSubmission(operationId, Buffer.alloc(32, 4)),
+      'AlreadySubmitted'
+    );
+    expect(fields.proof).to.equal(harness.proofPda(operationId).toBase58());
+  });
+
+  it('should log rejected lifecycle parameters', async () => {
+    let fields = await harness.expectErrorContext(
+      () => harness.configureProofLifecycle(1, 1, MAX_SWEEP_BOUNTY_BPS + 1),
+      'SweepBountyTooHigh'
+    );
+    expect(Number(fields.sweep_bounty_bps)).to.equal(MAX_SWEEP_BOUNTY_BPS + 1);
+    expect(Number(fields.max)).to.equal(MAX_SWEEP_BOUNTY_BPS);
+
+    fields = await harness.expectErrorContext(
+      () => harness.configureProofLifecycle(0, 1, 0),
+      'InvalidLifecycleConfig'
+    );
+    expect(Number(fields.proof_expiry_secs)).to.equal(0);
+  });
+
+  it('should log sweep batch sizes', async () => {
+    const sweep = (maxCount: number, accounts: number) =>
+      program.methods
+        .sweepExpired(maxCount)
+        .accounts({ validator: validatorPda, authority, cranker: authority, dailyStats: harness.dailyStatsPda() })
+        .remainingAccounts(
+          Array.from({ length: accounts }, () => ({
+            pubkey: Keypair.generate().publicKey,
+            isSigner: false,
+            isWritable: false,
+          }))
+        )
+        .rpc();
+
+    let fields = await harness.expectErrorContext(() => sweep(0, 1), 'InvalidSweepBatch');
+    expect(Number(fields.max_count)).to.equal(0);
+    expect(Number(fields.max)).to.equal(MAX_SWEEP_BATCH);
+
+    fields = await harness.expectErrorContext(() => sweep(MAX_SWEEP_BATCH, MAX_SWEEP_BATCH + 1), 'InvalidSweepBatch');
+    expect(Number(fields.accounts)).to.equal(MAX_SWEEP_BATCH + 1);
+  });
+
+  it('should log when a stats shard becomes closable', async () => {
+    const day = harness.currentDay();
+    await harness.submitProof(harness.randomOperationId());
+
+    const fields = await harness.expectErrorContext(
+      () =>
+        program.methods
+          .closeDailyStats(new anchor.BN(day))
+          .accounts({ validator: validatorPda, dailyStats: harness.dailyStatsPda(day), authority })
+          .rpc(),
+      'StatsRetentionActive'
+    );
+    expect(Number(fields.day)).to.equal(day);
+    expect(Number(fields.closable_from)).to.equal(day + STATS_RETENTION_DAYS);
+  });
+});
@@ -0,0 +1,59 @@
+import { expect } from 'chai';
+import { describe, it, before, after } from 'mocha';
+import { Keypair } from '@solana/web3.js';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Expected-operations registry: the bridge (or the relayer holding the
+// operation committer key) commits every operation it emits, and proofs are
+// only accepted for committed operations that have not been proven yet.
+
+describe('Trinity expected operations (Solana)', () => {
+  const harness = new TrinityTestHarness();
+
+  before(async () => {
+    await harness.ensureInitialized();
+  });
+
+  it('should accept a proof for a committed operation', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.commitOperation(operationId);
+
+    await harness.submitProof(operationId);
+
+    const expected = await harness.program.account.expectedOperation.fetch(harness.expectedOperationPda(operationId));
+    expect(expected.pending).to.equal(false);
+    expect(Buffer.from(expected.operationId)).to.deep.equal(operationId);
+    expect((await harness.fetchProof(operationId)).operationId).to.deep.equal(Array.from(operationId));
+  });
+
+  it('should reject a proof for an operation the bridge never committed', async () => {
+    const operationId = harness.randomOperationId();
+
+    const fields = await harness.expectErrorContext(
+      () => harness.submitProof(operationId, 1, undefined, { commit: false }),
+      'OperationNotFound'
+    );
+    expect(fields.expected_operation).to.equal(harness.expectedOperationPda(operationId).toBase58());
+  });
+
+  describe('with a delegated committer', () => {
+    let committer: Keypair;
+
+    before(async () => {
+      [committer] = await harness.fundedKeypairs(1);
+      await harness.setOperationCommitter(committer.publicKey);
+    });
+
+    after(async () => {
+      await harness.setOperationCommitter(harness.authority);
+    });
+
+    it('should only let the operation committer commit', async () => {
+      const operationId = harness.randomOperationId();
+      await harness.expectError(() => harness.commitOperation(operationId), 'UnauthorizedUser');
+
+      await harness.commitOperation(operationId, committer);
+      await harness.submitProof(operationId, 1, undefined, { commit: false });
+    });
+  });
+});
@@ -0,0 +1,105 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import * as anchor from '@coral-xyz/anchor';
+import { Keypair, LAMPORTS_PER_SOL } from '@solana/web3.js';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Operator fee vaults: anyone can prefund, the operator's hot key submits
+// without holding SOL, and ProofRecord rent comes out of the vault up to a
+// daily cap.
+
+describe('Trinity operator fee vaults (Solana)', () => {
+  const harness = new TrinityTestHarness();
+  const connection = harness.provider.connection;
+
+  const balanceOf = async (operator: Keypair) =>
+    connection.getBalance(harness.feeVaultPda(operator.publicKey));
+
+  before(async () => {
+    await harness.ensureInitialized();
+  });
+
+  it('should pay proof rent from the vault while the operator holds no SOL', async () => {
+    const operator = Keypair.generate();
+    await harness.configureFeeVault(operator.publicKey, 10);
+    await harness.prefundFeeVault(operator.publicKey, LAMPORTS_PER_SOL / 10);
+
+    const before = await balanceOf(operator);
+    const operationId = harness.randomOperationId();
+    await harness.submitSponsoredProof(operationId, operator);
+
+    const proofRent = await connection.getBalance(harness.proofPda(operationId));
+    expect(before - (await balanceOf(operator))).to.equal(proofRent);
+    expect(await connection.getBalance(operator.publicKey)).to.equal(0);
+
+    const proof = await harness.fetchProof(operationId);
+    expect(Buffer.from(proof.operationId)).to.deep.equal(operationId);
+
+    const vault = await harness.program.account.feeVault.fetch(harness.feeVaultPda(operator.publicKey));
+    expect(vault.sponsoredToday).to.equal(1);
+    expect(vault.totalRentSponsored.toNumber()).to.equal(proofRent);
+  });
+
+  it('should enforce the daily sponsorship cap', async () => {
+    const operator = Keypair.generate();
+    await harness.configureFeeVault(operator.publicKey, 1);
+    await harness.prefundFeeVault(operator.publicKey, LAMPORTS_PER_SOL / 10);
+
+    await harness.submitSponsoredProof(harness.randomOperationId(), operator);
+    await harness.expectError(
+      () => harness.submitSponsoredProof(harness.randomOperationId(), operator),
+      'SponsorCapReached'
+    );
+  });
+
+  it('should reject a submission the vault cannot cover', async () => {
+    const operator = Keypair.generate();
+    await harness.configureFeeVault(operator.publicKey, 10);
+
+    await harness.expectError(
+      () => harness.submitSponsoredProof(harness.randomOperationId(), operator),
+      'FeeVaultInsufficient'
+    );
+  });
+
+  it('should let only the operator reclaim, above the vault rent reserve', async () => {
+    const operator = Keypair.generate();
+    const [stranger] = await harness.fundedKeypairs(1);
+    await harness.configureFeeVault(operator.publicKey, 10);
+    await harness.prefundFeeVault(operator.publicKey, 1_000_000);
+
+    const reclaim = (signer: Keypair, lamports: number) =>
+      harness.program.methods
+        .reclaimPrefund(new anchor.BN(lamports))
+        .accounts({
+          feeVault: harness.feeVaultPda(operator.publicKey),
+          operator: signer.publicKey,
+          recipient: harness.authority,
+        })
+        .signers([signer])
+        .rpc();
+
+    await harness.expectError(() => reclaim(stranger, 1_000_000), 'ConstraintHasOne');
+    await harness.expectError(() => reclaim(operator, 1_000_001), 'FeeVaultInsufficient');
+
+    const before = await balanceOf(operator);
+    await reclaim(operator, 1_000_000);
+    expect(before - (await balanceOf(operator))).to.equal(1_000_000);
+    expect((await harness.operatorLiveness(operator.publicKey)).vaultBalance.toNumber()).to.equal(0);
+  });
+
+  it('should report how many proofs the vault can still sponsor', async () => {
+    const operator = Keypair.generate();
+    await harness.configureFeeVault(operator.publicKey, 5);
+    await harness.prefundFeeVault(operator.publicKey, LAMPORTS_PER_SOL / 10);
+    await harness.submitSponsoredProof(harness.randomOperationId(), operator);
+
+    const liveness = await harness.operatorLiveness(operator.publicKey);
+    expect(liveness.operator.toBase58()).to.equal(operator.publicKey.toBase58());
+    expect(liveness.remainingToday).to.equal(4);
+    expect(liveness.totalSponsored.toNumber()).to.equal(1);
+    expect(liveness.sponsorableProofs.toNumber()).to.equal(
+      Math.floor(liveness.vaultBalance.toNumber() / liveness.proofRent.toNumber())
+    );
+  });
+});
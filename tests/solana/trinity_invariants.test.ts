@@ -0,0 +1,135 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import { Keypair, PublicKey } from '@solana/web3.js';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Protocol invariants (see `invariants` in the program): total_proofs_submitted
+// accounts for every live and archived proof record, organic consensus names
+// at least two distinct chains, and no confirmed proof lacks its Ethereum
+// transaction hash. A seeded random walk over the proof lifecycle asserts them
+// on-chain after every step, and checks the proof count exactly off-chain for
+// its own operator. TRINITY_INVARIANT_SEED replays a particular walk.
+
+const STEPS = 40;
+const MAX_PROOF_REISSUES = 3;
+const CHAIN_SETS = [[1, 2], [2, 3], [1, 2, 3]];
+
+/// The 64-bit LCG the program's unit tests use; a seed always replays the same walk
+class Lcg {
+  private state: bigint;
+
+  constructor(seed: number) {
+    this.state = BigInt(seed);
+  }
+
+  below(bound: number): number {
+    this.state = (this.state * 6364136223846793005n + 1442695040888963407n) & 0xffffffffffffffffn;
+    return Number((this.state >> 33n) % BigInt(bound));
+  }
+
+  pick<T>(items: T[]): T {
+    return items[this.below(items.length)];
+  }
+}
+
+type Operation = { id: Buffer; confirmed: boolean; finalized: boolean; reissues: number };
+
+describe('Trinity protocol invariants (Solana)', () => {
+  const harness = new TrinityTestHarness();
+
+  before(async () => {
+    await harness.ensureInitialized();
+  });
+
+  /// Proofs rolled up plus the walk operator's pending ones, against the
+  /// numbered total; other operators sit still while the walk runs
+  async function proofCount(): Promise<{ counted: number; numbered: number }> {
+    const validator = await harness.fetchValidator();
+    const metrics = await harness.program.account.operatorMetrics.fetchNullable(
+      harness.operatorMetricsPda(harness.authority)
+    );
+    const sequence = await harness.program.account.proofSequence.fetchNullable(harness.proofSequencePda());
+    const pending = metrics ? metrics.proofsSubmitted.toNumber() - metrics.rolledUp.toNumber() : 0;
+    return {
+      counted: validator.totalProofsSubmitted.toNumber() + pending,
+      numbered: sequence ? sequence.globalProofSeq.toNumber() : 0,
+    };
+  }
+
+  it('should hold after every step of a random proof lifecycle', async () => {
+    const seed = Number(process.env.TRINITY_INVARIANT_SEED ?? 487);
+    const rng = new Lcg(seed);
+    const operations: Operation[] = [];
+    const baseline = await proofCount();
+
+    for (let step = 0; step < STEPS; step++) {
+      const unconfirmed = operations.filter((operation) => !operation.confirmed);
+      const reissuable = unconfirmed.filter((operation) => operation.reissues < MAX_PROOF_REISSUES);
+      const finalizable = operations.filter((operation) => operation.confirmed && !operation.finalized);
+      const action = rng.below(6);
+
+      if (action === 1 && unconfirmed.length > 0) {
+        const operation = rng.pick(unconfirmed);
+        await harness.confirmSubmission(operation.id, Buffer.alloc(32, 1 + rng.below(255)));
+        operation.confirmed = true;
+      } else if (action === 2 && finalizable.length > 0) {
+        const operation = rng.pick(finalizable);
+        await harness.finalizeConsensus(operation.id, rng.pick(CHAIN_SETS));
+        operation.finalized = true;
+      } else if (action === 3 && reissuable.length > 0) {
+        const operation = rng.pick(reissuable);
+        await harness.reissueProof(operation.id, 1 + rng.below(3));
+        operation.reissues += 1;
+      } else if (action === 4) {
+        await harness.rollupMetrics([harness.authority]);
+      } else if (action === 5 && unconfirmed.length > 0) {
+        // Found by this walk: a zero hash used to confirm a proof
+        const operation = rng.pick(unconfirmed);
+        await harness.expectError(
+          () => harness.confirmSubmission(operation.id, Buffer.alloc(32)),
+          'MissingEthereumTxHash'
+        );
+      } else {
+        const id = harness.randomOperationId();
+        await harness.submitProof(id, 1 + rng.below(3));
+        operations.push({ id, confirmed: false, finalized: false, reissues: 0 });
+      }
+
+      await harness.assertInvariants([
+        ...operations.map((operation) => harness.proofPda(operation.id)),
+        ...operations.filter((operation) => operation.finalized).map((operation) => harness.consensusPda(operation.id)),
+        harness.operatorMetricsPda(harness.authority),
+      ]);
+      const { counted, numbered } = await proofCount();
+      expect(counted - baseline.counted, `seed ${seed}, step ${step}`).to.equal(numbered - baseline.numbered);
+    }
+  });
+
+  it('should refuse to confirm a proof without an Ethereum transaction hash', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+
+    await harness.expectError(
+      () => harness.confirmSubmission(operationId, Buffer.alloc(32)),
+      'MissingEthereumTxHash'
+    );
+    const record = await harness.program.account.proofRecord.fetch(harness.proofPda(operationId));
+    expect(record.submittedToEthereum).to.equal(false);
+  });
+
+  it('should reject accounts it cannot check', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+    const proof = harness.proofPda(operationId);
+    const unchecked: PublicKey[][] = [
+      [harness.validatorPda],
+      [proof, proof],
+      [Keypair.generate().publicKey],
+    ];
+
+    for (const accounts of unchecked) {
+      await harness.expectError(() => harness.assertInvariants(accounts), 'InvalidInvariantAccount');
+    }
+    await harness.assertInvariants([proof]);
+  });
+});
@@ -0,0 +1,97 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// End-to-end latency: operators report the Ethereum event timestamp with each
+// proof, the program records now - timestamp on the proof, in ProofGenerated
+// and in today's DailyStats shard. Self-reported, so it is advisory and
+// cross-checked against the relayer's timestamp during reconciliation.
+
+const MAX_PLAUSIBLE_LATENCY_SECS = 3_600;
+
+describe('Trinity latency (Solana)', () => {
+  const harness = new TrinityTestHarness();
+  let ethBlock = 0;
+
+  const submitAged = async (ethEventTimestamp: number) => {
+    const operationId = harness.randomOperationId();
+    const [event] = await harness.captureEvents('ProofGenerated', async () => {
+      await harness.submitProof(operationId, 1, undefined, { ethEventTimestamp });
+    });
+    return { proof: await harness.fetchProof(operationId), event };
+  };
+
+  before(async () => {
+    await harness.ensureInitialized();
+    // Checkpoints must move forward from whatever another suite posted
+    const checkpoint = await harness.program.account.ethCheckpoint.fetchNullable(harness.ethCheckpointPda());
+    ethBlock = checkpoint?.ethBlock.toNumber() ?? 0;
+  });
+
+  it('should record the observed latency on the proof and in ProofGenerated', async () => {
+    const ethEventTimestamp = harness.ethEventTimestamp(4);
+    const { proof, event } = await submitAged(ethEventTimestamp);
+
+    const expected = Math.max(0, proof.timestamp.toNumber() - ethEventTimestamp);
+    expect(proof.ethEventTimestamp.toNumber()).to.equal(ethEventTimestamp);
+    expect(proof.observedLatencySeconds.toNumber()).to.equal(expected);
+    expect(proof.latencyImplausible).to.equal(false);
+    expect(event.observedLatencySeconds.toNumber()).to.equal(expected);
+  });
+
+  it('should clamp a future timestamp at zero', async () => {
+    const { proof } = await submitAged(harness.ethEventTimestamp(-600));
+    expect(proof.observedLatencySeconds.toNumber()).to.equal(0);
+    expect(proof.latencyImplausible).to.equal(false);
+  });
+
+  it('should cap and flag an implausible latency without skewing the average', async () => {
+    const before = await harness.fetchDailyStats();
+    const { proof, event } = await submitAged(harness.ethEventTimestamp(2 * MAX_PLAUSIBLE_LATENCY_SECS));
+
+    expect(proof.observedLatencySeconds.toNumber()).to.equal(MAX_PLAUSIBLE_LATENCY_SECS);
+    expect(proof.latencyImplausible).to.equal(true);
+    expect(event.latencyImplausible).to.equal(true);
+
+    const after = await harness.fetchDailyStats();
+    expect(after.latencyImplausible.toNumber()).to.equal(before.latencyImplausible.toNumber() + 1);
+    expect(after.latencySamples.toNumber()).to.equal(before.latencySamples.toNumber());
+    expect(after.latencySumSecs.toNumber()).to.equal(before.latencySumSecs.toNumber());
+  });
+
+  it('should summarize today\'s latency as advisory in get_latency_stats', async () => {
+    await submitAged(harness.ethEventTimestamp(3));
+    const stored = await harness.fetchDailyStats();
+    const stats = await harness.latencyStats();
+
+    expect(stored.latencyAdvisory).to.equal(true);
+    expect(stats.advisory).to.equal(true);
+    expect(stats.samples.toNumber()).to.equal(stored.latencySamples.toNumber());
+    expect(stats.averageSecs.toNumber()).to.equal(
+      Math.floor(stored.latencySumSecs.toNumber() / stored.latencySamples.toNumber())
+    );
+    const histogram = stats.buckets.reduce((sum: number, count: any) => sum + count.toNumber(), 0);
+    expect(histogram).to.equal(stats.samples.toNumber());
+    expect(stats.p95BoundSecs.toNumber()).to.be.greaterThan(0);
+  });
+
+  it('should flag an operator timestamp the relayer disagrees with, without halting', async () => {
+    const ethEventTimestamp = harness.ethEventTimestamp(5);
+    await submitAged(ethEventTimestamp);
+    const sequence = await harness.fetchProofSequence();
+    const count = sequence.globalProofSeq.toNumber();
+    const rollingHash = Buffer.from(sequence.rollingHash);
+
+    // Within tolerance of the relayer's own observation: no flag
+    await harness.submitEthCheckpoint(count, rollingHash, ++ethBlock, ethEventTimestamp + 3);
+    expect(await harness.captureEvents('LatencyReportMismatch', () => harness.reconcile())).to.have.length(0);
+
+    await harness.submitEthCheckpoint(count, rollingHash, ++ethBlock, ethEventTimestamp + 60);
+    const events = await harness.captureEvents('LatencyReportMismatch', () => harness.reconcile());
+    expect(events).to.have.length(1);
+    expect(events[0].count.toNumber()).to.equal(count);
+    expect(events[0].operatorReported.toNumber()).to.equal(ethEventTimestamp);
+    expect(events[0].relayerReported.toNumber()).to.equal(ethEventTimestamp + 60);
+    expect((await harness.fetchValidator()).monitorOnly).to.equal(false);
+  });
+});
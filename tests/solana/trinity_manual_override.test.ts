@@ -0,0 +1,160 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import { Keypair } from '@solana/web3.js';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Manual consensus override: full council quorum, loud proposal, 7-day delay
+// (2 seconds in `devtools` builds), single-member veto. The same quorum can
+// force-expire a jammed operation to Failed, immediately.
+
+const OVERRIDE_DELAY_SECS = process.env.TRINITY_DEVTOOLS ? 2 : 7 * 86_400;
+const DEVTOOLS_DELAY_MS = 2_000;
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe('Trinity manual consensus override (Solana)', () => {
+  const harness = new TrinityTestHarness();
+  const justification = Buffer.alloc(32, 0x4a);
+  let council: Keypair[];
+
+  before(async () => {
+    await harness.ensureInitialized();
+    council = await harness.fundedKeypairs(5);
+    await harness.initializeDisputeCouncil(
+      council.map((member) => member.publicKey),
+      3
+    );
+  });
+
+  it('should reject a proposal below the council threshold', async () => {
+    await harness.expectError(
+      () => harness.proposeOverride(harness.randomOperationId(), justification, council[0], [council[1]]),
+      'InsufficientCouncilApprovals'
+    );
+
+    const outsiders = await harness.fundedKeypairs(2);
+    await harness.expectError(
+      () => harness.proposeOverride(harness.randomOperationId(), justification, council[0], outsiders),
+      'InsufficientCouncilApprovals'
+    );
+  });
+
+  it('should require a justification hash', async () => {
+    await harness.expectError(
+      () => harness.proposeOverride(harness.randomOperationId(), Buffer.alloc(32), council[0], council.slice(1, 3)),
+      'MissingJustification'
+    );
+  });
+
+  it('should announce a quorate proposal and hold it for the delay', async () => {
+    const operationId = harness.randomOperationId();
+
+    const events = await harness.captureEvents('OverrideProposed', () =>
+      harness.proposeOverride(operationId, justification, council[0], council.slice(1, 3))
+    );
+    expect(events).to.have.length(1);
+    expect(events[0].approvals).to.equal(3);
+    expect(Buffer.from(events[0].justificationHash)).to.deep.equal(justification);
+
+    const proposal = await harness.program.account.overrideProposal.fetch(harness.overridePda(operationId));
+    expect(proposal.executableAt.toNumber() - proposal.proposedAt.toNumber()).to.equal(OVERRIDE_DELAY_SECS);
+
+    const execute = () => harness.executeOverride(operationId, council[0].publicKey);
+    await harness.expectError(execute, 'OverrideDelayActive');
+    expect(await harness.provider.connection.getAccountInfo(harness.consensusPda(operationId))).to.equal(null);
+  });
+
+  it('should report an open proposal as a consensus conflict', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+    await harness.proposeOverride(operationId, justification, council[0], council.slice(1, 4));
+
+    expect(await harness.consensusStatus(operationId)).to.deep.equal({
+      conflict: { overrideApprovals: 4, overridden: false },
+    });
+  });
+
+  it('should let any single council member cancel during the delay', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.proposeOverride(operationId, justification, council[0], council.slice(1, 3));
+
+    const cancel = (member: Keypair) =>
+      harness.program.methods
+        .cancelManualOverride(Array.from(operationId))
+        .accounts({
+          validator: harness.validatorPda,
+          council: harness.councilPda(),
+          proposal: harness.overridePda(operationId),
+          proposer: council[0].publicKey,
+          member: member.publicKey,
+        })
+        .signers([member])
+        .rpc();
+
+    const [outsider] = await harness.fundedKeypairs(1);
+    await harness.expectError(() => cancel(outsider), 'NotCouncilMember');
+
+    // A member who did not sign the proposal can still veto it
+    await cancel(council[4]);
+    expect(await harness.provider.connection.getAccountInfo(harness.overridePda(operationId))).to.equal(null);
+  });
+
+  it('should force-expire a conflicting operation so it can never finalize', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+    await harness.confirmSubmission(operationId, Buffer.alloc(32, 0xce));
+    await harness.proposeOverride(operationId, justification, council[0], council.slice(1, 3));
+
+    await harness.expectError(
+      () => harness.forceExpireOperation(operationId, council[3], [council[4]]),
+      'InsufficientCouncilApprovals'
+    );
+    const divergences = (await harness.fetchOperatorMetrics()).scoreInputs.divergences.toNumber();
+    const events = await harness.captureEvents('ConsensusFailed', () =>
+      harness.forceExpireOperation(operationId, council[3], council.slice(0, 2), harness.authority)
+    );
+
+    expect(events).to.have.length(1);
+    expect(events[0].forcedBy.toBase58()).to.equal(council[3].publicKey.toBase58());
+    expect(events[0].approvals).to.equal(3);
+    expect((await harness.fetchOperatorMetrics()).scoreInputs.divergences.toNumber()).to.equal(divergences + 1);
+    const status = await harness.consensusStatus(operationId);
+    expect(status.failed.failedAt.toNumber()).to.equal(events[0].failedAt.toNumber());
+    await harness.expectError(() => harness.finalizeConsensus(operationId), 'OperationFailed');
+    await harness.expectError(
+      () => harness.forceExpireOperation(operationId, council[3], council.slice(0, 2)),
+      'OperationFailed'
+    );
+  });
+
+  (process.env.TRINITY_DEVTOOLS ? it : it.skip)('should execute over a ConsensusState a pause created', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+    await harness.setOperationPause(operationId, true);
+    await harness.proposeOverride(operationId, justification, council[0], council.slice(1, 3));
+    await sleep(DEVTOOLS_DELAY_MS + 1_000);
+
+    const execute = () => harness.executeOverride(operationId, council[0].publicKey);
+    await harness.expectError(execute, 'OperationPaused');
+    await harness.setOperationPause(operationId, false);
+    await execute();
+
+    const consensus = await harness.fetchConsensus(operationId);
+    expect(consensus.finalized).to.equal(true);
+    expect(consensus.overridden).to.equal(true);
+    expect(Buffer.from(consensus.justificationHash)).to.deep.equal(justification);
+    expect(await harness.provider.connection.getAccountInfo(harness.overridePda(operationId))).to.equal(null);
+  });
+
+  it('should refuse to force-expire a reached operation', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+    await harness.confirmSubmission(operationId, Buffer.alloc(32, 0xcf));
+    await harness.finalizeConsensus(operationId);
+
+    await harness.expectError(
+      () => harness.forceExpireOperation(operationId, council[0], council.slice(1, 3)),
+      'ConsensusAlreadyFinalized'
+    );
+  });
+});
@@ -0,0 +1,80 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Merkle modes: sorted-pair paths (the default) carry no flags, positional
+// paths carry one left/right flag per level so proofs from position-aware
+// trees, like the Ethereum side's, fold to the same root. The mode is stored
+// on the ProofRecord and reissues keep it.
+
+/// Four-leaf tree built with keccak256(abi.encodePacked(left, right)) at each
+/// node, leaves out of sorted order (same vector as the program's unit tests)
+const LEAVES = [0x99, 0x11, 0x77, 0x22].map((byte) => Buffer.alloc(32, byte));
+const LEFT = Buffer.from('b2053a253e40bbccb5445f2caf0ea765f6cbd78635e17b4adfd8a562b259cd8d', 'hex');
+const RIGHT = Buffer.from('f175c8f463cb2b205c5909ecd07185e633ae15c96f5740f9ffa409370b14b23b', 'hex');
+const ROOT = Buffer.from('6622108974e9bd18f17d98970a20e135bcfde406fb98d91027f98f76f06c8c8b', 'hex');
+
+describe('Trinity Merkle modes (Solana)', () => {
+  const harness = new TrinityTestHarness();
+
+  before(async () => {
+    await harness.ensureInitialized();
+  });
+
+  it('should fold positional paths to the Ethereum root', () => {
+    const paths: [Buffer[], boolean[]][] = [
+      [[LEAVES[1], RIGHT], [false, false]],
+      [[LEAVES[0], RIGHT], [true, false]],
+      [[LEAVES[3], LEFT], [false, true]],
+      [[LEAVES[2], LEFT], [true, true]],
+    ];
+    paths.forEach(([proof, siblingIsLeft], i) => {
+      expect(harness.expectedPositionalRoot(proof, siblingIsLeft, LEAVES[i])).to.deep.equal(ROOT);
+    });
+    // Sorted folding can't reproduce a tree with unsorted siblings
+    expect(harness.expectedMerkleRoot(paths[1][0], LEAVES[1])).to.not.deep.equal(ROOT);
+  });
+
+  it('should record the mode and the positional root', async () => {
+    const siblingIsLeft = [true, false, true];
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId, 3, undefined, { siblingIsLeft });
+
+    const record = await harness.fetchProof(operationId);
+    expect(record.merkleMode).to.deep.equal({ positional: {} });
+    expect(record.siblingIsLeft).to.deep.equal(siblingIsLeft);
+    const expectedRoot = harness.expectedPositionalRoot(harness.proofElements(3), siblingIsLeft, operationId);
+    expect(Buffer.from(record.merkleRoot)).to.deep.equal(expectedRoot);
+    expect(Buffer.from(record.proofId)).to.deep.equal(harness.expectedProofId(operationId, expectedRoot));
+
+    const sorted = harness.randomOperationId();
+    await harness.submitProof(sorted, 3);
+    const sortedRecord = await harness.fetchProof(sorted);
+    expect(sortedRecord.merkleMode).to.deep.equal({ sorted: {} });
+    expect(sortedRecord.siblingIsLeft).to.have.length(0);
+  });
+
+  it('should require exactly one flag per positional level', async () => {
+    for (const siblingIsLeft of [[true], [true, false, false]]) {
+      await harness.expectError(
+        () => harness.submitProof(harness.randomOperationId(), 2, undefined, { siblingIsLeft }),
+        'InvalidMerklePath'
+      );
+    }
+  });
+
+  it('should reissue a positional proof in its original mode', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId, 2, undefined, { siblingIsLeft: [false, true] });
+
+    // Flags for every new level, and none missing
+    await harness.expectError(() => harness.reissueProof(operationId, 3), 'InvalidMerklePath');
+    await harness.reissueProof(operationId, 3, [true, true, false]);
+
+    const record = await harness.fetchProof(operationId);
+    expect(record.merkleMode).to.deep.equal({ positional: {} });
+    expect(record.siblingIsLeft).to.deep.equal([true, true, false]);
+    const expectedRoot = harness.expectedPositionalRoot(harness.proofElements(3), [true, true, false], operationId);
+    expect(Buffer.from(record.merkleRoot)).to.deep.equal(expectedRoot);
+  });
+});
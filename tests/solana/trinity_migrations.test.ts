@@ -0,0 +1,30 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Account migrations: every versioned account records its layout_version, and
+// instructions refuse stale layouts until migrate_account brings them forward
+// in place. Freshly written accounts are already current.
+
+describe('Trinity account migrations (Solana)', () => {
+  const harness = new TrinityTestHarness();
+
+  before(async () => {
+    await harness.ensureInitialized();
+  });
+
+  it('should stamp new accounts with the current layout', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId, 1);
+    expect((await harness.fetchValidator()).layoutVersion).to.equal(1);
+    expect((await harness.fetchProof(operationId)).layoutVersion).to.equal(4);
+  });
+
+  it('should refuse to migrate an account that is already current', async () => {
+    await harness.expectError(() => harness.migrateAccount(harness.validatorPda), 'AccountAlreadyCurrent');
+  });
+
+  it('should refuse accounts without a versioned layout', async () => {
+    await harness.expectError(() => harness.migrateAccount(harness.dailyStatsPda()), 'UnknownAccountKind');
+  });
+});
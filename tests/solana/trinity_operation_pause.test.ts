@@ -0,0 +1,53 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Per-operation pause: the validator authority can freeze one disputed
+// operation through its ConsensusState. While paused, proofs for it are
+// refused and it can't be finalized; every other operation proceeds.
+
+describe('Trinity operation pause (Solana)', () => {
+  const harness = new TrinityTestHarness();
+
+  const confirmedOperation = async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId, 1);
+    await harness.confirmSubmission(operationId, Buffer.alloc(32, 0x50));
+    return operationId;
+  };
+
+  before(async () => {
+    await harness.ensureInitialized();
+  });
+
+  it('should refuse proofs for a paused operation while others proceed', async () => {
+    const disputed = harness.randomOperationId();
+    const [event] = await harness.captureEvents('OperationPauseSet', () => harness.setOperationPause(disputed, true));
+    expect(event.paused).to.equal(true);
+    expect((await harness.fetchConsensus(disputed)).operationPaused).to.equal(true);
+
+    await harness.expectError(() => harness.submitProof(disputed, 1), 'OperationPaused');
+    expect(await harness.provider.connection.getAccountInfo(harness.proofPda(disputed))).to.equal(null);
+
+    const other = await confirmedOperation();
+    await harness.finalizeConsensus(other);
+    expect((await harness.fetchConsensus(other)).finalized).to.equal(true);
+
+    await harness.setOperationPause(disputed, false);
+    await harness.submitProof(disputed, 1);
+    expect((await harness.fetchProof(disputed)).merkleProof).to.have.length(1);
+  });
+
+  it('should hold finalization of a paused operation until it is released', async () => {
+    const operationId = await confirmedOperation();
+    await harness.setOperationPause(operationId, true);
+    await harness.expectError(() => harness.finalizeConsensus(operationId), 'OperationPaused');
+    expect((await harness.fetchConsensus(operationId)).finalized).to.equal(false);
+
+    await harness.setOperationPause(operationId, false);
+    await harness.finalizeConsensus(operationId);
+    const consensus = await harness.fetchConsensus(operationId);
+    expect(consensus.finalized).to.equal(true);
+    expect(consensus.operationPaused).to.equal(false);
+  });
+});
@@ -0,0 +1,78 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// OperationType codes: the four original types are enabled from the start,
+// further codes (named or Unknown) only once enabled through the timelocked
+// registry queue (2 seconds in `devtools` builds). Named types keep their
+// one-byte Borsh tags, so records written before codes existed still decode.
+
+const DEVTOOLS_TIMELOCK_MS = 2_000;
+const VAULT_REBALANCE = 4;
+
+// A ProofRecord account (discriminator included) serialized before operation
+// codes existed: one Merkle level, no stored tx signature, CrossChainTransfer
+// (tag 3) of 5,000 with no limit, confirmed on Ethereum. Captured once from
+// that layout; do not regenerate it from the current one.
+const LEGACY_PROOF_RECORD =
+  'ed3b9baccc75572c11111111111111111111111111111111111111111111111111111111111111112222222222222222' +
+  '222222222222222222222222222222222222222222222222333333333333333333333333333333333333333333333333' +
+  '333333333333333301000000444444444444444444444444444444444444444444444444444444444444444455555555' +
+  '5555555555555555555555555555555555555555555555555555555500e8030000000000000200000000000000030000' +
+  '00000000006666666666666666666666666666666666666666666666666666666666666666038813000000000000ffff' +
+  'ffffffffffff000000000000000000000000000000000000000000000000000000000000000000000000000000000000' +
+  '0000000000000000000000000000000000000000000000f1536500000000017777777777777777777777777777777777' +
+  '777777777777777777777777777777888888888888888888888888888888888888888888888888888888888888888800' +
+  '0000000000000000';
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe('Trinity operation types (Solana)', () => {
+  const harness = new TrinityTestHarness();
+
+  before(async () => {
+    await harness.ensureInitialized();
+    await harness.ensureCheckConfig();
+  });
+
+  it('should decode a proof record written before operation codes', () => {
+    const record = harness.program.coder.accounts.decode('ProofRecord', Buffer.from(LEGACY_PROOF_RECORD, 'hex'));
+
+    expect(record.operationType).to.deep.equal({ crossChainTransfer: {} });
+    expect(record.merkleProof).to.have.length(1);
+    expect(record.solanaTxSignature).to.equal(null);
+    expect(record.amount.toNumber()).to.equal(5_000);
+    expect(record.timestamp.toNumber()).to.equal(1_700_000_000);
+    expect(record.submittedToEthereum).to.equal(true);
+    expect(Buffer.from(record.ethereumTxHash).equals(Buffer.alloc(32, 0x77))).to.equal(true);
+  });
+
+  it('should reject codes that are not enabled', async () => {
+    const submit = (operationType: object) =>
+      harness.submitProof(harness.randomOperationId(), 1, undefined, { operationType });
+
+    await harness.expectError(() => submit({ feeSettlement: {} }), 'OperationTypeNotEnabled');
+    await harness.expectError(() => submit({ unknown: { 0: 77 } }), 'OperationTypeNotEnabled');
+    // Unknown can't stand in for a named type
+    await harness.expectError(() => submit({ unknown: { 0: 3 } }), 'NonCanonicalOperationType');
+  });
+
+  (process.env.TRINITY_DEVTOOLS ? describe : describe.skip)('enabling a code', () => {
+    it('should enable a queued code in the next slot once the timelock elapses', async () => {
+      await harness.expectError(() => harness.queueOperationType(0), 'OperationTypeAlreadyEnabled');
+      await harness.queueOperationType(VAULT_REBALANCE);
+      await harness.expectError(() => harness.applyOperationType(), 'EnforcementTimelockActive');
+
+      await sleep(DEVTOOLS_TIMELOCK_MS + 1_000);
+      const events = await harness.captureEvents('OperationTypeEnabled', () => harness.applyOperationType());
+      expect(events[0].code).to.equal(VAULT_REBALANCE);
+      expect(events[0].slot).to.equal(4);
+
+      const rebalance = { vaultRebalance: {} };
+      const operationId = harness.randomOperationId();
+      await harness.submitProof(operationId, 1, undefined, { operationType: rebalance });
+      expect((await harness.fetchProof(operationId)).operationType).to.deep.equal(rebalance);
+      await harness.verifyVaultOperation(rebalance, 1);
+    });
+  });
+});
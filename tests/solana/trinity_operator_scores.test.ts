@@ -0,0 +1,69 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import { Keypair, PublicKey } from '@solana/web3.js';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Operator scores: submissions and heartbeats feed score inputs on
+// OperatorMetrics, update_scores folds them in at most once a day, and
+// get_operator_ranking orders operators by the result. The score math itself
+// is property-tested in the program's unit tests.
+//
+// Operators other than the validator authority submit here, which relies on
+// the AuthoritySignature shadow rule still being in Monitor.
+
+const SCORE_BPS = 10_000;
+const SCORE_WEIGHT_TIMELINESS_BPS = 3_000;
+const MAX_SCORE_BATCH = 16;
+
+describe('Trinity operator scores (Solana)', () => {
+  const harness = new TrinityTestHarness();
+  let submitter: Keypair;
+  let idle: Keypair;
+  let unscored: Keypair;
+
+  before(async () => {
+    await harness.ensureInitialized();
+    [submitter, idle, unscored] = await harness.fundedKeypairs(3);
+    await harness.submitProof(harness.randomOperationId(), 1, await harness.recentSlotHash(), { submitter });
+    await harness.heartbeat(idle);
+    await harness.heartbeat(unscored);
+  });
+
+  it('should score each operator once per epoch', async () => {
+    const operators = [submitter.publicKey, idle.publicKey];
+    const events = await harness.captureEvents('OperatorScored', () => harness.updateScores(operators));
+    expect(events).to.have.length(2);
+    const scored = (operator: PublicKey) => events.find((event: any) => event.operator.equals(operator));
+
+    // Only the proof's block age can cost the submitter anything, and at most its weight
+    const fresh = scored(submitter.publicKey);
+    expect(fresh.epochs.toNumber()).to.equal(1);
+    expect(fresh.score.toNumber()).to.equal(fresh.periodScore.toNumber());
+    // (a slot hash is always for an earlier slot, so the age is at least one)
+    expect(fresh.score.toNumber()).to.be.at.least(SCORE_BPS - SCORE_WEIGHT_TIMELINESS_BPS);
+    expect(fresh.score.toNumber()).to.be.below(SCORE_BPS);
+    expect(scored(idle.publicKey).score.toNumber()).to.equal(SCORE_BPS);
+
+    const metrics = await harness.fetchOperatorMetrics(submitter.publicKey);
+    expect(metrics.score.toNumber()).to.equal(fresh.score.toNumber());
+    expect(metrics.scoredProofs.toNumber()).to.equal(1);
+
+    // Not due again until an epoch has passed
+    const again = await harness.captureEvents('OperatorScored', () => harness.updateScores(operators));
+    expect(again).to.have.length(0);
+  });
+
+  it('should rank operators best first', async () => {
+    const ranking = await harness.operatorRanking([unscored.publicKey, submitter.publicKey, idle.publicKey]);
+    expect(ranking.map((entry: any) => entry.operator.toBase58())).to.deep.equal(
+      [idle, submitter, unscored].map((operator) => operator.publicKey.toBase58())
+    );
+    expect(ranking[2].score.toNumber()).to.equal(0);
+    expect(ranking[2].scoredAt.toNumber()).to.equal(0);
+  });
+
+  it('should cap the batch size', async () => {
+    const operators = Array.from({ length: MAX_SCORE_BATCH + 1 }, () => Keypair.generate().publicKey);
+    await harness.expectError(() => harness.updateScores(operators), 'ScoreBatchTooLarge');
+  });
+});
@@ -0,0 +1,58 @@
+import { expect } from 'chai';
+import { describe, it, before, after } from 'mocha';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Proof budget: every submit instruction in a transaction costs a base plus
+// one unit per Merkle level, and the transaction's total must stay within the
+// validator's max_proof_budget. Checked before any hashing or account work.
+
+const BASE_UNITS = 8;
+const LEVEL_UNITS = 1;
+const DEFAULT_MAX_PROOF_BUDGET = 4 * (BASE_UNITS + 10);
+
+const budget = (proofLens: number[]) =>
+  proofLens.reduce((total, len) => total + BASE_UNITS + len * LEVEL_UNITS, 0);
+
+describe('Trinity proof budget (Solana)', () => {
+  const harness = new TrinityTestHarness();
+
+  before(async () => {
+    await harness.ensureInitialized();
+  });
+
+  after(async () => {
+    await harness.setMaxProofBudget(DEFAULT_MAX_PROOF_BUDGET);
+  });
+
+  it('should accept a single proof at the budget and reject one level over', async () => {
+    await harness.setMaxProofBudget(budget([5]));
+
+    const atBudget = harness.randomOperationId();
+    await harness.submitProof(atBudget, 5);
+    expect((await harness.fetchProof(atBudget)).merkleProof).to.have.length(5);
+
+    const overBudget = harness.randomOperationId();
+    const ctx = await harness.expectErrorContext(() => harness.submitProof(overBudget, 6), 'ProofBudgetExceeded');
+    expect(ctx).to.include({ budget: String(budget([6])), max: String(budget([5])), submissions: '1' });
+    expect(await harness.provider.connection.getAccountInfo(harness.proofPda(overBudget))).to.equal(null);
+  });
+
+  it('should charge every submission in the transaction against the budget', async () => {
+    await harness.setMaxProofBudget(budget([3, 3]));
+
+    const before = (await harness.fetchProofSequence()).globalProofSeq.toNumber();
+    await harness.submitProofBatch([3, 3]);
+    expect((await harness.fetchProofSequence()).globalProofSeq.toNumber()).to.equal(before + 2);
+
+    // Each proof fits on its own; a third submission or an extra level does not
+    await harness.expectError(() => harness.submitProofBatch([3, 3, 0]), 'ProofBudgetExceeded');
+    await harness.expectError(() => harness.submitProofBatch([3, 4]), 'ProofBudgetExceeded');
+    expect((await harness.fetchProofSequence()).globalProofSeq.toNumber()).to.equal(before + 2);
+  });
+
+  it('should refuse a budget too small for any proof', async () => {
+    await harness.expectError(() => harness.setMaxProofBudget(budget([1]) - 1), 'InvalidProofBudget');
+    await harness.setMaxProofBudget(budget([1]));
+    await harness.submitProof(harness.randomOperationId(), 1);
+  });
+});
@@ -0,0 +1,46 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Proof reissue: after a reorg the validator authority may rewrite an
+// unconfirmed proof against a newer block, but only MAX_PROOF_REISSUES times
+// per operation, so a proof can't be rewritten forever to dodge consensus.
+// The generation timestamp is kept, so reissues never postpone expiry.
+
+const MAX_PROOF_REISSUES = 3;
+
+describe('Trinity proof reissue (Solana)', () => {
+  const harness = new TrinityTestHarness();
+
+  before(async () => {
+    await harness.ensureInitialized();
+  });
+
+  it('should reissue up to the cap and then refuse', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+    const original = await harness.program.account.proofRecord.fetch(harness.proofPda(operationId));
+
+    for (let i = 1; i <= MAX_PROOF_REISSUES; i++) {
+      const events = await harness.captureEvents('ProofReissued', () => harness.reissueProof(operationId, i + 1));
+      expect(events).to.have.length(1);
+      expect(events[0].reissues).to.equal(i);
+    }
+    const reissued = await harness.program.account.proofRecord.fetch(harness.proofPda(operationId));
+    expect(reissued.merkleProof).to.have.length(MAX_PROOF_REISSUES + 1);
+    expect(Buffer.from(reissued.proofId)).to.not.deep.equal(Buffer.from(original.proofId));
+    expect(reissued.timestamp.toNumber()).to.equal(original.timestamp.toNumber());
+
+    await harness.expectError(() => harness.reissueProof(operationId), 'ReissueLimitReached');
+    const reissues = await harness.program.account.proofReissues.fetch(harness.proofReissuesPda(operationId));
+    expect(reissues.reissues).to.equal(MAX_PROOF_REISSUES);
+  });
+
+  it('should not reissue a confirmed proof', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+    await harness.confirmSubmission(operationId, Buffer.alloc(32, 7));
+
+    await harness.expectError(() => harness.reissueProof(operationId), 'AlreadySubmitted');
+  });
+});
@@ -0,0 +1,85 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import { keccak256 } from 'ethers';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Reconciliation: the bridge posts back how many Solana proofs it processed
+// and a rolling keccak over their operation IDs. A mismatch with the local
+// ProofSequence halts confirmations until the authority acknowledges it.
+
+describe('Trinity reconciliation (Solana)', () => {
+  const harness = new TrinityTestHarness();
+  let ethBlock = Date.now();
+
+  const localSequence = async () => {
+    const sequence = await harness.fetchProofSequence();
+    return { count: sequence.globalProofSeq.toNumber(), rollingHash: Buffer.from(sequence.rollingHash) };
+  };
+
+  before(async () => {
+    await harness.ensureInitialized();
+    await harness.submitProof(harness.randomOperationId());
+  });
+
+  it('should fold each proof into the rolling hash', async () => {
+    const before = await localSequence();
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+
+    const after = await localSequence();
+    expect(after.count).to.equal(before.count + 1);
+    const expected = Buffer.from(keccak256(Buffer.concat([before.rollingHash, operationId])).slice(2), 'hex');
+    expect(after.rollingHash).to.deep.equal(expected);
+  });
+
+  it('should reconcile a matching checkpoint, including an older count', async () => {
+    const older = await localSequence();
+    await harness.submitProof(harness.randomOperationId());
+
+    await harness.submitEthCheckpoint(older.count, older.rollingHash, ++ethBlock);
+    const events = await harness.captureEvents('ReconciliationOk', () => harness.reconcile());
+
+    expect(events).to.have.length(1);
+    expect(events[0].count.toNumber()).to.equal(older.count);
+    expect((await harness.fetchValidator()).monitorOnly).to.equal(false);
+  });
+
+  it('should reject a checkpoint that is not newer', async () => {
+    const { count, rollingHash } = await localSequence();
+    await harness.expectError(() => harness.submitEthCheckpoint(count, rollingHash, ethBlock), 'StaleCheckpoint');
+  });
+
+  it('should go monitor-only on a mismatch until acknowledged', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+    const { count } = await localSequence();
+
+    await harness.submitEthCheckpoint(count, Buffer.alloc(32, 0x5a), ++ethBlock);
+    const events = await harness.captureEvents('ReconciliationMismatch', () => harness.reconcile());
+
+    expect(events).to.have.length(1);
+    expect(Buffer.from(events[0].expected)).to.deep.equal(Buffer.alloc(32, 0x5a));
+    expect(Buffer.from(events[0].actual)).to.deep.equal((await localSequence()).rollingHash);
+    expect((await harness.fetchValidator()).monitorOnly).to.equal(true);
+
+    // Proofs are still generated, but nothing is confirmed
+    await harness.submitProof(harness.randomOperationId());
+    await harness.expectError(() => harness.confirmSubmission(operationId, Buffer.alloc(32, 0xd0)), 'MonitorOnly');
+
+    await harness.acknowledgeReconciliation();
+    expect((await harness.fetchValidator()).monitorOnly).to.equal(false);
+    await harness.confirmSubmission(operationId, Buffer.alloc(32, 0xd0));
+  });
+
+  it('should flag a checkpoint ahead of the local sequence', async () => {
+    const { count, rollingHash } = await localSequence();
+
+    await harness.submitEthCheckpoint(count + 5, rollingHash, ++ethBlock);
+    const events = await harness.captureEvents('ReconciliationMismatch', () => harness.reconcile());
+
+    expect(events).to.have.length(1);
+    expect(events[0].expectedCount.toNumber()).to.equal(count + 5);
+    expect(events[0].actualCount.toNumber()).to.equal(count);
+    await harness.acknowledgeReconciliation();
+  });
+});
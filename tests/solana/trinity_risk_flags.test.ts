@@ -0,0 +1,136 @@
+import { expect } from 'chai';
+import { describe, it, before, after, beforeEach } from 'mocha';
+import * as anchor from '@coral-xyz/anchor';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Risk flags: submissions, Ethereum confirmations and finalizations check the
+// risk thresholds and emit RiskFlagRaised for each newly crossed one. A raised
+// flag stays latched on the RiskMetrics PDA, and is not raised again, until
+// the authority acknowledges it. Thresholds change through the timelocked
+// queue (2 seconds in `devtools` builds).
+
+const DEVTOOLS_TIMELOCK_MS = 2_000;
+const NO_THRESHOLD = new anchor.BN('18446744073709551615');
+const FLAGS = ['largeOperation', 'operatorConcentration', 'slowConsensus'];
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe('Trinity risk flags (Solana)', () => {
+  const harness = new TrinityTestHarness();
+
+  const setThresholds = async ({
+    maxOperationAmount = NO_THRESHOLD,
+    maxOperatorShareBps = new anchor.BN(10_000),
+    maxConsensusSecs = NO_THRESHOLD,
+  }: { maxOperationAmount?: anchor.BN; maxOperatorShareBps?: anchor.BN; maxConsensusSecs?: anchor.BN }) => {
+    await harness.queueRiskThresholds({ maxOperationAmount, maxOperatorShareBps, maxConsensusSecs });
+    await sleep(DEVTOOLS_TIMELOCK_MS + 1_000);
+    await harness.applyRiskThresholds();
+  };
+
+  const raisedFlags = (events: any[]) => events.map((event) => Object.keys(event.flag)[0]);
+
+  before(async () => {
+    await harness.ensureInitialized();
+    await harness.ensureRiskMetrics();
+  });
+
+  it('should hold a threshold change behind the timelock', async () => {
+    await harness.queueRiskThresholds({
+      maxOperationAmount: new anchor.BN(1),
+      maxOperatorShareBps: new anchor.BN(10_000),
+      maxConsensusSecs: NO_THRESHOLD,
+    });
+    await harness.expectError(() => harness.applyRiskThresholds(), 'EnforcementTimelockActive');
+
+    // Re-queueing replaces the pending change
+    await harness.queueRiskThresholds({
+      maxOperationAmount: NO_THRESHOLD,
+      maxOperatorShareBps: new anchor.BN(10_000),
+      maxConsensusSecs: NO_THRESHOLD,
+    });
+    const metrics = await harness.fetchRiskMetrics();
+    expect(metrics.queued.thresholds.maxOperationAmount.eq(NO_THRESHOLD)).to.equal(true);
+  });
+
+  it('should refuse to acknowledge a flag that is not raised', async () => {
+    const metrics = await harness.fetchRiskMetrics();
+    if (metrics.latched & (1 << FLAGS.indexOf('slowConsensus'))) {
+      await harness.acknowledgeRiskFlag({ slowConsensus: {} });
+    }
+    await harness.expectError(() => harness.acknowledgeRiskFlag({ slowConsensus: {} }), 'RiskFlagNotRaised');
+  });
+
+  (process.env.TRINITY_DEVTOOLS ? describe : describe.skip)('with thresholds applied', () => {
+    // Earlier runs against the same validator may have left flags latched
+    beforeEach(async () => {
+      const metrics = await harness.fetchRiskMetrics();
+      for (const [bit, flag] of FLAGS.entries()) {
+        if (metrics.latched & (1 << bit)) {
+          await harness.acknowledgeRiskFlag({ [flag]: {} });
+        }
+      }
+    });
+
+    after(async () => {
+      await setThresholds({});
+    });
+
+    it('should raise LargeOperation once per acknowledgement', async () => {
+      await setThresholds({ maxOperationAmount: new anchor.BN(5_000) });
+      const operationId = harness.randomOperationId();
+
+      const events = await harness.captureEvents('RiskFlagRaised', async () => {
+        await harness.submitProof(harness.randomOperationId(), 1, undefined, { amount: 5_000 });
+        await harness.submitProof(operationId, 1, undefined, { amount: 5_001 });
+        // Latched: a second oversized operation is not reported again
+        await harness.submitProof(harness.randomOperationId(), 1, undefined, { amount: 9_000 });
+      });
+      expect(raisedFlags(events)).to.deep.equal(['largeOperation']);
+      expect(Buffer.from(events[0].context.operationId)).to.deep.equal(operationId);
+      expect(events[0].context.observed.toNumber()).to.equal(5_001);
+      expect(events[0].context.threshold.toNumber()).to.equal(5_000);
+
+      await harness.acknowledgeRiskFlag({ largeOperation: {} });
+      expect((await harness.fetchRiskMetrics()).latched).to.equal(0);
+      const again = await harness.captureEvents('RiskFlagRaised', () =>
+        harness.submitProof(harness.randomOperationId(), 1, undefined, { amount: 9_000 })
+      );
+      expect(raisedFlags(again)).to.deep.equal(['largeOperation']);
+    });
+
+    it('should raise OperatorConcentration for a dominant submitter', async () => {
+      // The authority submits every proof in these suites: its share is 100%
+      await setThresholds({ maxOperatorShareBps: new anchor.BN(5_000) });
+
+      const events = await harness.captureEvents('RiskFlagRaised', async () => {
+        for (let i = 0; i < 4; i++) {
+          await harness.submitProof(harness.randomOperationId());
+        }
+      });
+      expect(raisedFlags(events)).to.deep.equal(['operatorConcentration']);
+      expect(events[0].context.operator.toBase58()).to.equal(harness.authority.toBase58());
+      expect(events[0].context.observed.toNumber()).to.be.greaterThan(5_000);
+    });
+
+    it('should raise SlowConsensus when confirmation lags the proof', async () => {
+      await setThresholds({ maxConsensusSecs: new anchor.BN(1) });
+      const operationId = harness.randomOperationId();
+      await harness.submitProof(operationId);
+      await sleep(2_500);
+
+      const events = await harness.captureEvents('RiskFlagRaised', async () => {
+        await harness.confirmSubmission(operationId, Buffer.alloc(32, 7));
+        // Finalization is as late, but the flag is already latched
+        await harness.finalizeConsensus(operationId);
+      });
+      expect(raisedFlags(events)).to.deep.equal(['slowConsensus']);
+      expect(events[0].context.observed.toNumber()).to.be.greaterThan(1);
+
+      const metrics = await harness.fetchRiskMetrics();
+      expect(metrics.raisedAt[FLAGS.indexOf('slowConsensus')].toNumber()).to.equal(
+        events[0].context.raisedAt.toNumber()
+      );
+    });
+  });
+});
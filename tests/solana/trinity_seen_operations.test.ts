@@ -0,0 +1,45 @@
+import { expect } from 'chai';
+import { describe, it, before } from 'mocha';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Seen-operations filter: a ring of recent operation fingerprints that
+// rejects replays before any ProofRecord is created. Misses fall through to
+// the ProofRecord PDA, which remains the authoritative duplicate check.
+
+describe('Trinity seen-operations filter (Solana)', () => {
+  const harness = new TrinityTestHarness();
+
+  before(async () => {
+    await harness.ensureInitialized();
+    await harness.ensureSeenOperations();
+  });
+
+  it('should reject a recently seen operation via the cheap path', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId, 1, undefined, { seenFilter: true });
+
+    await harness.expectError(
+      () => harness.submitProof(operationId, 1, undefined, { seenFilter: true }),
+      'OperationAlreadySeen'
+    );
+  });
+
+  it('should fall back to the proof record check without the filter', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId, 1, undefined, { seenFilter: true });
+
+    await harness.expectError(() => harness.submitProof(operationId), 'ProofAlreadyExists');
+  });
+
+  it('should only remember submissions made through the filter', async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId);
+
+    const seen = await harness.program.account.seenOperations.fetch(harness.seenOperationsPda());
+    const fingerprint = operationId.readBigUInt64LE(0);
+    const recorded = seen.fingerprints
+      .slice(0, seen.len)
+      .some((entry: any) => BigInt(entry.toString()) === fingerprint);
+    expect(recorded).to.equal(false);
+  });
+});
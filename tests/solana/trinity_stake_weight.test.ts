@@ -0,0 +1,137 @@
+import { expect } from 'chai';
+import { describe, it, before, after } from 'mocha';
+import * as anchor from '@coral-xyz/anchor';
+import { Keypair, PublicKey } from '@solana/web3.js';
+import { createAccount, createMint, getAccount, mintTo } from '@solana/spl-token';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Stake-weighted consensus: operators bond stake and attest to operations
+// with it, and once a consensus weight threshold is configured,
+// finalize_consensus needs the attested stake to exceed it on top of the
+// chain quorum. The threshold is reset afterwards so other suites finalize
+// on chain quorum alone. Attested stake stays bonded for
+// STAKE_UNBONDING_SECS (2 seconds in `devtools` builds).
+
+const THRESHOLD = 1_000;
+const DEVTOOLS_UNBONDING_MS = 2_000;
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+type Staker = { keypair: Keypair; tokenAccount: PublicKey };
+
+describe('Trinity stake-weighted consensus (Solana)', () => {
+  const harness = new TrinityTestHarness();
+  const payer = (harness.provider.wallet as anchor.Wallet).payer;
+  let mint: PublicKey;
+  let tiny: Staker[];
+  let large: Staker[];
+  let dust: Staker[];
+
+  /// Operators holding `stake` each, all of it bonded
+  const stakers = async (count: number, stake: number): Promise<Staker[]> => {
+    const connection = harness.provider.connection;
+    const result: Staker[] = [];
+    for (const keypair of await harness.fundedKeypairs(count)) {
+      const tokenAccount = await createAccount(connection, payer, mint, keypair.publicKey, Keypair.generate());
+      await mintTo(connection, payer, mint, tokenAccount, payer, stake);
+      await harness.bondStake(keypair, tokenAccount, stake);
+      result.push({ keypair, tokenAccount });
+    }
+    return result;
+  };
+
+  const confirmedOperation = async () => {
+    const operationId = harness.randomOperationId();
+    await harness.submitProof(operationId, 1);
+    await harness.confirmSubmission(operationId, Buffer.alloc(32, 0x57));
+    return operationId;
+  };
+
+  before(async () => {
+    await harness.ensureInitialized();
+    // The stake mint is fixed once configured, e.g. by an earlier run
+    const config = await harness.program.account.stakeConfig.fetchNullable(harness.stakeConfigPda());
+    mint = config?.mint ?? (await createMint(harness.provider.connection, payer, payer.publicKey, null, 0));
+    await harness.configureStake(mint, THRESHOLD);
+    tiny = await stakers(8, 10);
+    large = await stakers(2, 600);
+    dust = await stakers(11, 1);
+  });
+
+  after(async () => {
+    await harness.configureStake(mint, 0);
+  });
+
+  it('should not finalize on many tiny stakes, but on a few large ones', async () => {
+    const operationId = await confirmedOperation();
+    for (const { keypair } of tiny) {
+      await harness.attestOperation(operationId, keypair);
+    }
+    const finalize = () => harness.finalizeConsensus(operationId);
+    const fields = await harness.expectErrorContext(finalize, 'StakeWeightNotReached');
+    expect(fields.stake_weight).to.equal('80');
+    expect(fields.contributors).to.equal('8');
+
+    const events = await harness.captureEvents('OperationAttested', async () => {
+      for (const { keypair } of large) {
+        await harness.attestOperation(operationId, keypair);
+      }
+    });
+    expect(events.map((event) => event.stakeWeight.toNumber())).to.deep.equal([680, 1_280]);
+    await harness.finalizeConsensus(operationId);
+
+    const consensus = await harness.fetchConsensus(operationId);
+    expect(consensus.finalized).to.equal(true);
+    expect(consensus.stakeWeight.toNumber()).to.equal(1_280);
+    expect(consensus.contributors.map((c: any) => c.operator.toBase58())).to.deep.equal(
+      [...tiny, ...large].map(({ keypair }) => keypair.publicKey.toBase58())
+    );
+    expect(consensus.contributors[9].stake.toNumber()).to.equal(600);
+  });
+
+  it('should let large stakes displace dust attestations that filled the list first', async () => {
+    const operationId = await confirmedOperation();
+    for (const { keypair } of dust.slice(0, 10)) {
+      await harness.attestOperation(operationId, keypair);
+    }
+    // Another dust stake can't get in: it is no larger than the smallest
+    const latecomer = dust[10].keypair;
+    await harness.expectError(() => harness.attestOperation(operationId, latecomer), 'ContributorLimitReached');
+
+    const events = await harness.captureEvents('OperationAttested', async () => {
+      for (const { keypair } of large) {
+        await harness.attestOperation(operationId, keypair);
+      }
+    });
+    expect(events.map((event) => event.displaced.toBase58())).to.deep.equal(
+      dust.slice(0, 2).map(({ keypair }) => keypair.publicKey.toBase58())
+    );
+    await harness.finalizeConsensus(operationId);
+
+    const consensus = await harness.fetchConsensus(operationId);
+    expect(consensus.finalized).to.equal(true);
+    expect(consensus.stakeWeight.toNumber()).to.equal(1_208);
+  });
+
+  it('should count each operator once and only with bonded stake', async () => {
+    const operationId = await confirmedOperation();
+    await harness.attestOperation(operationId, large[0].keypair);
+    await harness.expectError(() => harness.attestOperation(operationId, large[0].keypair), 'AlreadyAttested');
+    // 600 alone doesn't exceed the threshold
+    await harness.expectError(() => harness.finalizeConsensus(operationId), 'StakeWeightNotReached');
+  });
+
+  (process.env.TRINITY_DEVTOOLS ? it : it.skip)('should keep attested stake bonded while unbonding', async () => {
+    const { keypair, tokenAccount } = tiny[0];
+    await harness.expectError(() => harness.unbondStake(keypair, tokenAccount, 10), 'StakeLocked');
+
+    await sleep(DEVTOOLS_UNBONDING_MS + 1_000);
+    await harness.expectError(() => harness.unbondStake(keypair, tokenAccount, 11), 'InsufficientStake');
+    await harness.unbondStake(keypair, tokenAccount, 10);
+    expect(Number((await getAccount(harness.provider.connection, tokenAccount)).amount)).to.equal(10);
+
+    // With nothing bonded, there is nothing to attest with
+    const operationId = await confirmedOperation();
+    await harness.expectError(() => harness.attestOperation(operationId, keypair), 'InsufficientStake');
+  });
+});
@@ -0,0 +1,387 @@
+import { expect } from 'chai';
+import { describe, it, before, after } from 'mocha';
+import * as anchor from '@coral-xyz/anchor';
+import { Keypair, LAMPORTS_PER_SOL } from '@solana/web3.js';
+import { TrinityTestHarness, htlcVectors } from './trinityTestHarness';
+
+// Trinity Validator program tests
+// Run against a local validator with the program deployed (`anchor test`)
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe('Trinity Validator Program (Solana)', () => {
+  const harness = new TrinityTestHarness();
+  const { provider, program, authority, validatorPda } = harness;
+
+  const proofPda = (operationId: Buffer) => harness.proofPda(operationId);
+  const randomOperationId = () => harness.randomOperationId();
+  const submitProof = (operationId: Buffer, proofLen = 1) => harness.submitProof(operationId, proofLen);
+
+  before(async () => {
+    await harness.ensureInitialized();
+  });
+
+  describe('Proof expiry sweep', () => {
+    const cranker = Keypair.generate();
+
+    before(async () => {
+      const sig = await provider.connection.requestAirdrop(cranker.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+    });
+
+    it('should reject bounty above the cap', async () => {
+      try {
+        await program.methods
+          .configureProofLifecycle(new anchor.BN(1), new anchor.BN(1), 2_001)
+          .accounts({ validator: validatorPda, authority })
+          .rpc();
+        expect.fail('bounty above MAX_SWEEP_BOUNTY_BPS must be rejected');
+      } catch (err: any) {
+        expect(err.error.errorCode.code).to.equal('SweepBountyTooHigh');
+      }
+    });
+
+    it('should expire, close and skip a mixed batch', async () => {
+      // 1s expiry / 1s retention so the batch ages within the test
+      await program.methods
+        .configureProofLifecycle(new anchor.BN(1), new anchor.BN(1), 1_000)
+        .accounts({ validator: validatorPda, authority })
+        .rpc();
+
+      const staleOp = randomOperationId();
+      const expiringOp = randomOperationId();
+      const confirmedOp = randomOperationId();
+      await submitProof(staleOp);
+      await submitProof(expiringOp);
+      await submitProof(confirmedOp);
+
+      await program.methods
+        .confirmEthereumSubmission(Array.from(confirmedOp), Array.from(Buffer.alloc(32, 9)))
+        .accounts({ proofRecord: proofPda(confirmedOp), dailyStats: harness.dailyStatsPda(), authority })
+        .rpc();
+
+      await sleep(2_000);
+
+      // First pass only sees staleOp: it expires but is retained
+      await program.methods
+        .sweepExpired(20)
+        .accounts({ validator: validatorPda, authority, cranker: cranker.publicKey, dailyStats: harness.dailyStatsPda() })
+        .remainingAccounts([{ pubkey: proofPda(staleOp), isSigner: false, isWritable: true }])
+        .signers([cranker])
+        .rpc();
+
+      const staleRecord = await program.account.proofRecord.fetch(proofPda(staleOp));
+      expect(staleRecord.expired).to.equal(true);
+
+      await sleep(2_000);
+
+      const freshOp = randomOperationId();
+      await submitProof(freshOp);
+      const staleRent = await provider.connection.getBalance(proofPda(staleOp));
+      const crankerBefore = await provider.connection.getBalance(cranker.publicKey);
+
+      const staleEvidence = await harness.proofEvidence(staleOp);
+      const events: any[] = [];
+      const archived: any[] = [];
+      const listener = program.addEventListener('ProofsSwept', (event: any) => events.push(event));
+      const archiveListener = program.addEventListener('ProofArchived', (event: any) => archived.push(event));
+
+      await program.methods
+        .sweepExpired(20)
+        .accounts({ validator: validatorPda, authority, cranker: cranker.publicKey, dailyStats: harness.dailyStatsPda() })
+        .remainingAccounts([
+          { pubkey: proofPda(staleOp), isSigner: false, isWritable: true },     // closed
+          { pubkey: proofPda(expiringOp), isSigner: false, isWritable: true },  // expired + closed (retention elapsed)
+          { pubkey: proofPda(confirmedOp), isSigner: false, isWritable: true }, // skipped: confirmed
+          { pubkey: proofPda(freshOp), isSigner: false, isWritable: true },     // skipped: fresh
+          { pubkey: validatorPda, isSigner: false, isWritable: false },         // skipped: not a proof
+        ])
+        .signers([cranker])
+        .rpc();
+
+      await sleep(1_000);
+      await program.removeEventListener(listener);
+      await program.removeEventListener(archiveListener);
+
+      expect(await provider.connection.getAccountInfo(proofPda(staleOp))).to.equal(null);
+      expect(await provider.connection.getAccountInfo(proofPda(confirmedOp))).to.not.equal(null);
+      expect(await provider.connection.getAccountInfo(proofPda(freshOp))).to.not.equal(null);
+
+      const crankerAfter = await provider.connection.getBalance(cranker.publicKey);
+      const expectedBounty = Math.min(Math.floor((staleRent * 1_000) / 10_000), 1_000_000);
+      // Cranker paid the tx fee, so compare against the bounty for two closed accounts minus fees
+      expect(crankerAfter).to.be.greaterThan(crankerBefore + expectedBounty - 10_000);
+
+      expect(events).to.have.length(1);
+      expect(events[0].examined).to.equal(5);
+      expect(events[0].expired).to.equal(1);
+      expect(events[0].closed).to.equal(2);
+      expect(events[0].skipped).to.equal(3);
+
+      // Closed records leave their forensic evidence behind
+      expect(archived.map((event) => Buffer.from(event.evidence.operationId))).to.deep.equal([staleOp, expiringOp]);
+      expect(archived[0].evidence.solanaEpoch.toNumber()).to.equal(staleEvidence.solanaEpoch.toNumber());
+      expect(archived[0].evidence.environmentHash).to.deep.equal(staleEvidence.environmentHash);
+    });
+  });
+
+  describe('Batch limits', () => {
+    const MAX_MERKLE_PROOF_LEN = 10;
+    const MAX_SWEEP_BATCH = 20;
+
+    it('should accept a Merkle proof at the cap and reject one over it', async () => {
+      const atCap = randomOperationId();
+      await submitProof(atCap, MAX_MERKLE_PROOF_LEN);
+      const record = await program.account.proofRecord.fetch(proofPda(atCap));
+      expect(record.merkleProof).to.have.length(MAX_MERKLE_PROOF_LEN);
+
+      try {
+        await submitProof(randomOperationId(), MAX_MERKLE_PROOF_LEN + 1);
+        expect.fail('proof longer than MAX_MERKLE_PROOF_LEN must be rejected');
+      } catch (err: any) {
+        expect(err.error.errorCode.code).to.equal('MerkleProofTooLong');
+      }
+    });
+
+    it('should sweep a batch at the cap and reject one over it', async () => {
+      const batch = (n: number) =>
+        Array.from({ length: n }, () => ({
+          pubkey: proofPda(randomOperationId()),
+          isSigner: false,
+          isWritable: true,
+        }));
+
+      // Unknown accounts are skipped, so a full batch of them still succeeds
+      await program.methods
+        .sweepExpired(MAX_SWEEP_BATCH)
+        .accounts({ validator: validatorPda, authority, cranker: authority, dailyStats: harness.dailyStatsPda() })
+        .remainingAccounts(batch(MAX_SWEEP_BATCH))
+        .rpc();
+
+      try {
+        await program.methods
+          .sweepExpired(MAX_SWEEP_BATCH)
+          .accounts({ validator: validatorPda, authority, cranker: authority, dailyStats: harness.dailyStatsPda() })
+          .remainingAccounts(batch(MAX_SWEEP_BATCH + 1))
+          .rpc();
+        expect.fail('sweep over MAX_SWEEP_BATCH accounts must be rejected');
+      } catch (err: any) {
+        expect(err.error.errorCode.code).to.equal('InvalidSweepBatch');
+      }
+    });
+  });
+
+  describe('RPC endpoint rotation', () => {
+    const setRpcUrl = (url: string) =>
+      program.methods.setRpcUrl(url).accounts({ validator: validatorPda, authority }).rpc();
+
+    it('should rotate the URL and emit RpcUrlUpdated', async () => {
+      const before = await harness.fetchValidator();
+      const newUrl = 'https://arb-sepolia.example.org/v2/relayer-b';
+
+      const events = await harness.captureEvents('RpcUrlUpdated', () => setRpcUrl(newUrl));
+
+      const after = await harness.fetchValidator();
+      expect(after.arbitrumRpcUrl).to.equal(newUrl);
+      expect(events).to.have.length(1);
+      expect(events[0].previousUrl).to.equal(before.arbitrumRpcUrl);
+      expect(events[0].newUrl).to.equal(newUrl);
+    });
+
+    it('should reject an over-length URL', async () => {
+      await harness.expectError(() => setRpcUrl('https://' + 'a'.repeat(200)), 'RpcUrlTooLong');
+    });
+
+    it('should reject malformed URLs', async () => {
+      for (const url of ['', 'http://arb1.example.org', 'arb1.example.org', 'https://', 'https://arb1.example.org\r\n']) {
+        await harness.expectError(() => setRpcUrl(url), 'InvalidRpcUrl');
+      }
+      // update_validator applies the same check
+      await harness.expectError(
+        () =>
+          program.methods
+            .updateValidator('ftp://arb1.example.org', null, null)
+            .accounts({ validator: validatorPda, authority })
+            .rpc(),
+        'InvalidRpcUrl'
+      );
+    });
+
+    it('should reject a non-authority signer', async () => {
+      const [intruder] = await harness.fundedKeypairs(1);
+      let rejected = false;
+      try {
+        await program.methods
+          .setRpcUrl('https://attacker.example')
+          .accounts({ validator: validatorPda, authority: intruder.publicKey })
+          .signers([intruder])
+          .rpc();
+      } catch {
+        rejected = true;
+      }
+      expect(rejected).to.equal(true);
+    });
+  });
+
+  describe('Block hash recency', () => {
+    it('should accept a recent slot hash and record it', async () => {
+      const block = await harness.recentSlotHash();
+      const operationId = randomOperationId();
+      await harness.submitProof(operationId, 1, block);
+
+      const record = await harness.fetchProof(operationId);
+      expect(record.solanaBlockNumber.toNumber()).to.equal(block.slot);
+      expect(Buffer.from(record.solanaBlockHash)).to.deep.equal(block.hash);
+    });
+
+    it('should capture the cluster environment as forensic evidence', async () => {
+      const operationId = randomOperationId();
+      await harness.submitProof(operationId);
+      const { epoch } = await provider.connection.getEpochInfo();
+
+      const evidence = await harness.proofEvidence(operationId);
+      const record = await harness.fetchProof(operationId);
+      expect(evidence.solanaEpoch.toNumber()).to.be.within(epoch - 1, epoch);
+      expect(evidence.leaderScheduleEpoch.toNumber()).to.be.at.least(evidence.solanaEpoch.toNumber());
+      expect(Buffer.from(evidence.environmentHash).equals(Buffer.alloc(32))).to.equal(false);
+      expect(evidence.environmentHash).to.deep.equal(record.environmentHash);
+      expect(Buffer.from(evidence.solanaBlockHash)).to.deep.equal(Buffer.from(record.solanaBlockHash));
+    });
+
+    it('should record a hash of the exact submitted arguments', async () => {
+      const operationId = randomOperationId();
+      let args: object = {};
+      const events = await harness.captureEvents('ProofGenerated', async () => {
+        args = await harness.submitProof(operationId, 3, undefined, { amount: 4_242 });
+      });
+      const expected = harness.submissionHash(args);
+
+      const record = await harness.fetchProof(operationId);
+      expect(Buffer.from(record.submissionHash)).to.deep.equal(expected);
+      expect(Buffer.from((await harness.proofEvidence(operationId)).submissionHash)).to.deep.equal(expected);
+      expect(events).to.have.length(1);
+      expect(Buffer.from(events[0].submissionHash)).to.deep.equal(expected);
+    });
+
+    it('should reject a fabricated hash for a recent slot', async () => {
+      const { slot } = await harness.recentSlotHash();
+      await harness.expectError(
+        () => harness.submitProof(randomOperationId(), 1, { slot, hash: Buffer.alloc(32, 0xfa) }),
+        'InvalidMerkleProof'
+      );
+    });
+
+    it('should reject a slot outside the recent window', async () => {
+      // Far from the sysvar's ~512 recent slots (in the future, so this holds
+      // even on a freshly started validator)
+      const { slot, hash } = await harness.recentSlotHash();
+      await harness.expectError(
+        () => harness.submitProof(randomOperationId(), 1, { slot: slot + 100_000, hash }),
+        'InvalidMerkleProof'
+      );
+    });
+  });
+
+  describe('HTLC preimage attestation', () => {
+    const htlcSwap = { htlcSwap: {} };
+
+    it('should store and emit a preimage matching its hashlock', async () => {
+      for (const vector of htlcVectors()) {
+        const operationId = randomOperationId();
+        const events = await harness.captureEvents('ProofGenerated', () =>
+          harness.submitProof(operationId, 1, undefined, { operationType: htlcSwap, htlc: vector })
+        );
+
+        const record = await harness.fetchProof(operationId);
+        expect(record.operationType).to.deep.equal(htlcSwap);
+        expect(Buffer.from(record.hashlock)).to.deep.equal(vector.hashlock, vector.name);
+        expect(Buffer.from(record.preimage)).to.deep.equal(vector.preimage, vector.name);
+
+        const event = events.find((e) => Buffer.from(e.operationId).equals(operationId));
+        expect(Buffer.from(event.preimage)).to.deep.equal(vector.preimage, vector.name);
+        expect(Buffer.from(event.hashlock)).to.deep.equal(vector.hashlock, vector.name);
+      }
+    });
+
+    it('should allow an HTLC proof before the preimage is revealed', async () => {
+      const operationId = randomOperationId();
+      await harness.submitProof(operationId, 1, undefined, { operationType: htlcSwap });
+
+      const record = await harness.fetchProof(operationId);
+      expect(Buffer.from(record.preimage)).to.deep.equal(Buffer.alloc(32));
+      expect(Buffer.from(record.hashlock)).to.deep.equal(Buffer.alloc(32));
+    });
+
+    it('should reject a preimage that does not match the hashlock', async () => {
+      const [first, second] = htlcVectors();
+      await harness.expectError(
+        () =>
+          harness.submitProof(randomOperationId(), 1, undefined, {
+            operationType: htlcSwap,
+            htlc: { ...first, hashlock: second.hashlock },
+          }),
+        'HashlockMismatch'
+      );
+    });
+
+    it('should reject a preimage on non-HTLC operations', async () => {
+      const [vector] = htlcVectors();
+      for (const operationType of [{ vaultWithdrawal: {} }, { emergencyRecovery: {} }, { crossChainTransfer: {} }]) {
+        await harness.expectError(
+          () => harness.submitProof(randomOperationId(), 1, undefined, { operationType, htlc: vector }),
+          'PreimageNotAllowed'
+        );
+      }
+    });
+  });
+
+  describe('Proof record size', () => {
+    // Serialized length of a one-element VaultWithdrawal record without a
+    // stored signature (the worst case, ProofRecord::INIT_SPACE, is 736)
+    const COMPACT_RECORD_LEN = 8 + 384;
+    const SIGNATURE_LEN = 64;
+
+    const recordLen = async (operationId: Buffer) =>
+      (await provider.connection.getAccountInfo(proofPda(operationId)))!.data.length;
+
+    after(async () => {
+      await harness.setStoreTxSignatures(true);
+    });
+
+    it('should size records to their contents and round-trip every field', async () => {
+      await harness.setStoreTxSignatures(false);
+      const operationId = randomOperationId();
+      const options = { operationType: { crossChainTransfer: {} }, amount: 4_242 };
+      await harness.submitProof(operationId, 1, undefined, options);
+      expect(await recordLen(operationId)).to.equal(COMPACT_RECORD_LEN);
+
+      const record = await harness.fetchProof(operationId);
+      expect(Buffer.from(record.operationId)).to.deep.equal(operationId);
+      expect(record.merkleProof.map((element: number[]) => Buffer.from(element))).to.deep.equal(
+        harness.proofElements(1)
+      );
+      expect(record.solanaTxSignature).to.equal(null);
+      expect(record.operationType).to.deep.equal({ crossChainTransfer: {} });
+      expect(record.amount.toNumber()).to.equal(4_242);
+      expect(record.validator.toBase58()).to.equal(validatorPda.toBase58());
+      expect(record.submittedToEthereum).to.equal(false);
+      expect(record.expired).to.equal(false);
+
+      // Fixed-size updates after creation fit the compact allocation
+      await harness.confirmSubmission(operationId, Buffer.alloc(32, 0xcd));
+      const confirmed = await harness.fetchProof(operationId);
+      expect(confirmed.submittedToEthereum).to.equal(true);
+      expect(Buffer.from(confirmed.ethereumTxHash)).to.deep.equal(Buffer.alloc(32, 0xcd));
+    });
+
+    it('should store the signature again once re-enabled', async () => {
+      await harness.setStoreTxSignatures(true);
+      const operationId = randomOperationId();
+      await submitProof(operationId);
+
+      expect(await recordLen(operationId)).to.equal(COMPACT_RECORD_LEN + SIGNATURE_LEN);
+      const record = await harness.fetchProof(operationId);
+      expect(Buffer.from(record.solanaTxSignature)).to.deep.equal(Buffer.alloc(64, 2));
+    });
+  });
+});
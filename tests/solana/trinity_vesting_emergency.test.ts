@@ -0,0 +1,154 @@
+import { describe, it, before, after } from 'mocha';
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { Keypair, PublicKey, SystemProgram } from '@solana/web3.js';
+import { createMint } from '@solana/spl-token';
+import { TrinityTestHarness } from './trinityTestHarness';
+
+// Vesting cross-check: with the vesting program linked, Trinity refuses to
+// attest a VaultWithdrawal from a schedule while the vesting program has a
+// beneficiary recovery (its emergency path) pending on it, so both can't
+// drain the schedule at once. Needs the vesting program loaded alongside
+// Trinity, with the test wallet as a vesting guardian.
+
+const MIN_RECOVERY_TIMELOCK = 30 * 24 * 60 * 60;
+
+describe('Trinity vesting emergency cross-check (Solana)', () => {
+  const harness = new TrinityTestHarness();
+  const payer = (harness.provider.wallet as anchor.Wallet).payer;
+  const vesting = anchor.workspace.CvtVesting as Program<any>;
+  const withdrawal = { vaultWithdrawal: {} };
+  const configPda = PublicKey.findProgramAddressSync([Buffer.from('config')], vesting.programId)[0];
+  let mint: PublicKey;
+  let nextScheduleId = Date.now();
+
+  const recoveryPda = (schedule: PublicKey) =>
+    PublicKey.findProgramAddressSync([Buffer.from('recovery'), schedule.toBuffer()], vesting.programId)[0];
+
+  /// A fresh schedule for a funded beneficiary; returns (schedule, beneficiary)
+  const createSchedule = async (): Promise<[PublicKey, Keypair]> => {
+    const [beneficiary] = await harness.fundedKeypairs(1);
+    const scheduleId = new anchor.BN(nextScheduleId++);
+    const nonce = new anchor.BN(0);
+    const [schedule] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('vesting'),
+        beneficiary.publicKey.toBuffer(),
+        mint.toBuffer(),
+        scheduleId.toArrayLike(Buffer, 'le', 8),
+        nonce.toArrayLike(Buffer, 'le', 8),
+      ],
+      vesting.programId
+    );
+    const unlock = new anchor.BN(Math.floor(Date.now() / 1000) + 86_400);
+    await vesting.methods
+      .createVesting(
+        scheduleId,
+        nonce,
+        unlock,
+        unlock,
+        new anchor.BN(1_000),
+        0,
+        Array(32).fill(0),
+        Array(32).fill(0),
+        new anchor.BN(0),
+        PublicKey.default,
+        [],
+        null,
+        false
+      )
+      .accounts({
+        vesting: schedule,
+        mint,
+        beneficiary: beneficiary.publicKey,
+        payer: harness.authority,
+        systemProgram: SystemProgram.programId,
+        config: configPda,
+        scheduleCount: PublicKey.findProgramAddressSync(
+          [Buffer.from('schedule_count'), harness.authority.toBuffer()],
+          vesting.programId
+        )[0],
+      })
+      .rpc();
+    return [schedule, beneficiary];
+  };
+
+  const proposeRecovery = (schedule: PublicKey) =>
+    vesting.methods
+      .guardianRecoverBeneficiary(Keypair.generate().publicKey)
+      .accounts({
+        config: configPda,
+        vesting: schedule,
+        recovery: recoveryPda(schedule),
+        proposer: harness.authority,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  const objectToRecovery = (schedule: PublicKey, beneficiary: Keypair) =>
+    vesting.methods
+      .objectBeneficiaryRecovery()
+      .accounts({
+        vesting: schedule,
+        recovery: recoveryPda(schedule),
+        proposer: harness.authority,
+        beneficiary: beneficiary.publicKey,
+      })
+      .signers([beneficiary])
+      .rpc();
+
+  const verifyWithdrawal = (schedule: PublicKey, emergencyProposal: PublicKey | null = recoveryPda(schedule)) =>
+    harness.verifyVaultOperation(withdrawal, 1, harness.authority, schedule, emergencyProposal);
+
+  before(async function () {
+    await harness.ensureInitialized();
+    const config = await vesting.account.vestingConfig.fetchNullable(configPda);
+    if (!config) {
+      await vesting.methods
+        .initializeConfig([harness.authority], 1, new anchor.BN(MIN_RECOVERY_TIMELOCK))
+        .accounts({ config: configPda, authority: harness.authority, systemProgram: SystemProgram.programId })
+        .rpc();
+    } else if (!config.guardians.some((guardian: PublicKey) => guardian.equals(harness.authority))) {
+      // An earlier deployment configured other guardians; nobody here can propose
+      this.skip();
+    }
+    mint = await createMint(harness.provider.connection, payer, payer.publicKey, null, 0);
+    await harness.setVestingProgram(vesting.programId);
+  });
+
+  after(async () => {
+    await harness.setVestingProgram(PublicKey.default);
+  });
+
+  it('should refuse withdrawals while a recovery is pending, until it resolves', async () => {
+    const [schedule, beneficiary] = await createSchedule();
+    await proposeRecovery(schedule);
+
+    await harness.expectError(() => verifyWithdrawal(schedule), 'ConflictingEmergencyPending');
+    // Other operations on the schedule aren't withdrawals
+    await harness.verifyVaultOperation({ htlcSwap: {} }, 1, harness.authority, schedule);
+
+    await objectToRecovery(schedule, beneficiary);
+    await verifyWithdrawal(schedule);
+  });
+
+  it('should refuse withdrawals after a recovery is proposed on an attested schedule', async () => {
+    const [schedule, beneficiary] = await createSchedule();
+    await verifyWithdrawal(schedule);
+
+    await proposeRecovery(schedule);
+    await harness.expectError(() => verifyWithdrawal(schedule), 'ConflictingEmergencyPending');
+
+    await objectToRecovery(schedule, beneficiary);
+    await verifyWithdrawal(schedule);
+  });
+
+  it('should require the proposal account for vesting withdrawals', async () => {
+    const [schedule] = await createSchedule();
+    await harness.expectError(() => verifyWithdrawal(schedule, null), 'EmergencyProposalRequired');
+    await harness.expectError(
+      () => verifyWithdrawal(schedule, recoveryPda(Keypair.generate().publicKey)),
+      'EmergencyProposalRequired'
+    );
+  });
+});